@@ -1,6 +1,8 @@
 // crates/cli/benches/end_to_end.rs
 use clap::Parser;
 use count_lines_cli::args::Args;
+use count_lines_cli::bench::{Fixture, generate};
+use count_lines_cli::config::Config;
 use criterion::{Criterion, criterion_group, criterion_main};
 use std::hint::black_box;
 
@@ -13,5 +15,29 @@ fn benchmark_cli_parsing(c: &mut Criterion) {
     });
 }
 
-criterion_group!(benches, benchmark_cli_parsing);
+fn benchmark_synthetic_tree(c: &mut Criterion) {
+    let dir = tempfile::tempdir().unwrap();
+    generate(dir.path(), Fixture { files: 2_000 }).unwrap();
+    let root = dir.path().to_string_lossy().into_owned();
+
+    let mut group = c.benchmark_group("synthetic-2k");
+    group.sample_size(20);
+    for (name, flags) in [
+        ("lines", &[][..]),
+        ("sloc", &["--sloc"][..]),
+        ("words_sloc", &["--words", "--sloc"][..]),
+    ] {
+        let args = ["count_lines"]
+            .into_iter()
+            .chain(flags.iter().copied())
+            .chain([root.as_str()]);
+        let config = Config::from(Args::try_parse_from(args).unwrap());
+        group.bench_function(name, |b| {
+            b.iter(|| black_box(count_lines_engine::run(&config).unwrap()));
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, benchmark_cli_parsing, benchmark_synthetic_tree);
 criterion_main!(benches);
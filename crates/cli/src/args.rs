@@ -1,8 +1,15 @@
 // crates/cli/src/args.rs
-use crate::options::{OutputFormat, SortSpec, WatchOutput};
-use crate::parsers::{self, DateTimeArg, SizeArg};
-use clap::{Args as ClapArgs, Parser, ValueHint};
+use crate::options::{
+    BadgeColors, BadgeSpec, CacheKey, ColorChoice, ColumnSpec, CompareFormat, CompareSort, GroupBy,
+    LimitAction, LinguistMode, LogFormat, LogLevel, OutputFormat, RatioBase, RatioMetric, Shell,
+    SortSpec, Theme, WatchOutput,
+};
+use crate::parsers::{self, DateTimeArg, LanguageDefArg, PatternFileArg, SizeArg};
+use clap::{Args as ClapArgs, Parser, Subcommand, ValueHint};
+use count_lines_engine::expr::{FilterExpr, GroupFilter, Threshold};
+use count_lines_engine::plugin::Plugin;
 use std::path::PathBuf;
+use std::time::Duration;
 
 #[derive(Parser, Debug)]
 #[command(
@@ -29,6 +36,371 @@ pub struct Args {
     /// 対象パス
     #[arg(value_hint = ValueHint::AnyPath, help_heading = "走査/入力")]
     pub paths: Vec<PathBuf>,
+
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// 計測結果をスナップショットとして保存し、保存済みのベースラインと比較する
+    Snapshot(SnapshotArgs),
+    /// 2 つのディレクトリを計測し、相対パスで対応づけて比較 (片方にしかないファイルと行数の異なるファイルを表示)
+    DiffDirs(DiffDirsArgs),
+    /// ファイルごとの行数/文字数の上限を超えたファイルを一覧し、違反があれば非ゼロで終了
+    Lint(LintArgs),
+    /// ステージされたファイルを検査する Git の pre-commit フックを導入・実行
+    Hook(HookArgs),
+    /// --incremental のキャッシュの状態を表示・削除・整理
+    Cache(CacheArgs),
+    /// 計測結果を保持し、HTTP で JSON を返す API サーバーを起動 (GET /summary, GET /files, POST /rescan)
+    Serve(ServeArgs),
+    /// ワークスペースを監視し、標準入出力の JSON-RPC で統計を返す常駐モード (エディタのステータスバー向け)
+    Daemon(DaemonArgs),
+    /// シェル補完スクリプトを出力 (例: count_lines completions bash > /etc/bash_completion.d/count_lines)
+    Completions(CompletionsArgs),
+    /// オプションの詳しい説明・例・関連オプションを表示 (例: count_lines explain --filter)
+    Explain(ExplainArgs),
+    /// man ページ (roff 形式) を出力 (例: count_lines man > count_lines.1)
+    Man,
+    /// 生成したツリーを計測し、走査・計測・集計の所要時間を表示 (性能の回帰確認用)
+    #[command(hide = true)]
+    Bench(BenchArgs),
+}
+
+#[derive(ClapArgs, Debug)]
+pub struct CompletionsArgs {
+    /// 対象のシェル
+    #[arg(value_enum)]
+    pub shell: Shell,
+}
+
+#[derive(ClapArgs, Debug)]
+pub struct DiffDirsArgs {
+    /// 比較元のディレクトリ (例: 期待値のコピー)
+    #[arg(value_hint = ValueHint::DirPath)]
+    pub dir_a: PathBuf,
+
+    /// 比較先のディレクトリ (例: 生成された出力)
+    #[arg(value_hint = ValueHint::DirPath)]
+    pub dir_b: PathBuf,
+}
+
+#[derive(ClapArgs, Debug)]
+pub struct ExplainArgs {
+    /// 説明するオプション (例: --filter, -o)。省略すると詳しい説明のあるオプションを一覧
+    #[arg(value_name = "FLAG", allow_hyphen_values = true)]
+    pub flag: Option<String>,
+}
+
+/// Extended documentation of a flag, shown by `explain` and the man page
+/// in addition to its `--help` line.
+#[derive(Debug, Clone, Copy)]
+pub struct FlagDoc {
+    /// Long name without the dashes.
+    pub flag: &'static str,
+    pub details: &'static str,
+    pub examples: &'static [&'static str],
+    /// Long names of the flags it interacts with.
+    pub see_also: &'static [&'static str],
+}
+
+/// Flags with more to say than fits in `--help`.
+pub const FLAG_DOCS: &[FlagDoc] = &[
+    FlagDoc {
+        flag: "filter",
+        details: "\
+式を満たすファイルだけを集計します。フィールドの比較を && / || / ! (and / or / not も可) と括弧で組み合わせます。
+数値フィールド: lines, chars, words, sloc, doc (ドキュメントコメントの行数), size, maxline (最長行の文字数), avgline (平均行長), maxdepth (インデントの最大深さ), avgdepth (インデントの平均深さ)
+文字列フィールド: path (区切りは /), name (ファイル名), ext (拡張子、. なし)
+比較演算子: <, <=, >, >=, ==, != (文字列は ==, != のみ)
+文字列演算子: contains (部分一致), matches (正規表現)
+数値は 500_000 のように _ で区切れます。文字列は \"...\" か '...' で囲みます。words / sloc / doc を参照すると自動的に計測が有効になります。
+複数指定するとすべての式を満たすファイルだけが残ります。",
+        examples: &[
+            "count_lines --filter \"maxline > 120 || (avgline > 80 && lines > 100)\"",
+            "count_lines --filter \"sloc >= 50 and not size > 1_000_000\" src",
+            "count_lines --filter \"ext == 'rs' && !(path contains 'tests/')\"",
+            "count_lines --filter \"name matches '^lib_' || lines > 500\"",
+        ],
+        see_also: &["fail-if", "min-lines", "max-lines"],
+    },
+    FlagDoc {
+        flag: "fail-if",
+        details: "\
+集計結果が式を満たしたら終了コード 1 で終了します (結果は通常どおり出力)。CI のゲート向けです。
+式の文法は --filter と同じで、フィールドは集計値です:
+  total_files / total_lines / total_chars / total_words / total_sloc / total_size
+  files_added / files_removed / files_modified (比較時のみ)
+  lines_delta / chars_delta / words_delta / sloc_delta (比較時のみ)
+複数指定するといずれか 1 つでも満たせば失敗します。",
+        examples: &[
+            "count_lines --fail-if \"total_lines > 500000\" src",
+            "count_lines --compare-ref main..HEAD --fail-if \"sloc_delta > 1000\"",
+        ],
+        see_also: &["filter", "quiet", "compare-ref"],
+    },
+    FlagDoc {
+        flag: "sort",
+        details: "\
+ファイルの並び順をカンマ区切りのキーで指定します。先のキーが同じファイルは次のキーで比べます。
+キー: lines, chars, words, size, name, ext, sloc, maxline, avgline, path, ratio (全体の行数に対する割合。lines と同じ順), maxdepth, avgdepth (インデントの最大・平均深さ)
+キーの後に :desc を付けると降順、:asc (省略可) で昇順になります。sloc / words を指定すると計測も有効になります。
+すべてのキーが同じファイルはパスの昇順に並ぶため、並列実行でも結果の順序は毎回同じです。",
+        examples: &[
+            "count_lines --sort lines:desc,name",
+            "count_lines --sort maxline:desc --top 10",
+            "count_lines --sort sloc:desc,path:asc",
+        ],
+        see_also: &["top", "columns"],
+    },
+    FlagDoc {
+        flag: "by",
+        details: "\
+ファイルごとではなくグループごとに集計します。複数指定すると前のグループの中に次のグループの内訳を入れ子で出力します。
+language / ext / linguist / age (--age-buckets で区切り) / root-dir / crate (最も近い Cargo.toml) / owner (CODEOWNERS) / submodule / root (複数の走査ルートを比較) / author (git blame の作者。遅いが結果はキャッシュ)
+table / csv / tsv / json / markdown で使えます。",
+        examples: &[
+            "count_lines --by language",
+            "count_lines --by root-dir --by language --ratio-of lines",
+            "count_lines --by root frontend/ backend/ infra/",
+            "count_lines --by author --by language --sloc",
+        ],
+        see_also: &["group-filter", "ratio-of", "age-buckets", "codeowners", "format"],
+    },
+    FlagDoc {
+        flag: "group-filter",
+        details: "\
+--by で集計したあと、式を満たすグループだけを出力します。式の文法は --filter と同じで、フィールドはグループの合計です:
+  files / lines / chars / words / sloc / size (数値)、key (グループ名: 拡張子・言語・ディレクトリなど)
+入れ子の --by では各段のグループに適用し、満たさないグループは内訳ごと除きます。
+TOTAL 行と --ratio-of の割合は除いたグループも含めた全ファイルの合計です。",
+        examples: &[
+            "count_lines --by ext --group-filter \"lines > 10000\"",
+            "count_lines --by language --group-filter \"files >= 10 && key != 'Markdown'\"",
+        ],
+        see_also: &["by", "filter"],
+    },
+    FlagDoc {
+        flag: "columns",
+        details: "\
+table / csv / tsv に出す列をカンマ区切りで順に指定します。
+列: path, name, ext, lines, sloc, chars, words, size, mtime, maxline, avgline, ratio (全ファイルの行数合計に対する割合 %), root (複数ルート走査時のファイルの走査ルート), doc (ドキュメントコメントの行数), doc% (ドキュメント密度), maxdepth, avgdepth (インデントの最大・平均深さ)
+未知の列名はエラーになり、有効な列名を表示します。",
+        examples: &["count_lines --columns path,lines,sloc,ratio --sort lines:desc"],
+        see_also: &["format", "sort", "human"],
+    },
+    FlagDoc {
+        flag: "format",
+        details: "\
+table と tree は人が読むための出力で、--human / --thousands / --color が効きます。
+csv / tsv / json / jsonl / yaml は機械可読で、数値は常にそのまま出力されます。
+md はファイルごとの Markdown 表、markdown は言語別 (または --by のグループ別) のサマリ表です。
+github は GitHub Actions のワークフローコマンドで、--highlight-over を超えたファイルに ::warning、
+合計に ::notice、--fail-if の超過に ::error の注釈を付けます。
+sarif は同じ違反 (と読み取りエラー) を SARIF 2.1.0 のログとしてコードスキャン向けに出力します。",
+        examples: &[
+            "count_lines --format json -o stats.json",
+            "count_lines --format tree --tree-depth 2",
+            "count_lines --format github --highlight-over 800 --fail-if 'total_lines > 500000'",
+            "count_lines --format sarif lint --max-file-lines 800 > count_lines.sarif",
+        ],
+        see_also: &["output", "columns", "by"],
+    },
+    FlagDoc {
+        flag: "human",
+        details: "\
+table / tree / Markdown の数値を 12.3k / 1.2M のように短縮し、サイズを KiB / MiB / GiB で表示します。
+--thousands と併用すると短縮しない数値を 3 桁区切りにし、--bytes でサイズだけバイト数に戻せます。",
+        examples: &["count_lines --human --top-dirs 5"],
+        see_also: &["thousands", "bytes"],
+    },
+    FlagDoc {
+        flag: "incremental",
+        details: "\
+各ファイルの計測結果をキャッシュディレクトリに保存し、次回は変更のないファイルを読み直さずに再利用します。
+未変更の判定は --cache-key (mtime: サイズと更新日時、hash: 内容のハッシュ) で選べます。
+計測結果が変わる設定 (--sloc, --encoding など) を変えるとキャッシュは作り直されます。
+古いエントリは cache gc で整理できます。",
+        examples: &[
+            "count_lines --incremental --sloc .",
+            "count_lines cache gc --max-age 30d",
+        ],
+        see_also: &["cache-dir", "cache-key", "shared-cache"],
+    },
+    FlagDoc {
+        flag: "watch",
+        details: "\
+ファイルの変更を監視し、変更のたびに変わったファイルだけを計測し直して出力します。
+--watch-output jsonl で変更ファイルとサマリを 1 行ずつ、--tui でダッシュボードを表示します。
+ネイティブの変更通知が使えない環境では --watch-interval の間隔でポーリングします。",
+        examples: &["count_lines --watch --watch-output jsonl src"],
+        see_also: &["watch-output", "watch-debounce", "tui"],
+    },
+    FlagDoc {
+        flag: "color",
+        details: "\
+table / tree の見出し・TOTAL 行・--highlight-over を超えた行に色を付けます。
+auto は標準出力が端末で NO_COLOR が未設定のときだけ色付けします。always は NO_COLOR より優先されます。",
+        examples: &["count_lines --color always --theme light --highlight-over 1000 | less -R"],
+        see_also: &["theme", "highlight-over"],
+    },
+];
+
+#[derive(ClapArgs, Debug)]
+pub struct BenchArgs {
+    /// 計測対象のツリー (synthetic-<N>: 複数言語の N ファイル。例: synthetic-100k)
+    #[arg(long, value_name = "NAME", default_value = "synthetic-10k")]
+    pub fixture: crate::bench::Fixture,
+
+    /// 計測の回数
+    #[arg(long, value_name = "N", value_parser = parsers::parse_positive_usize, default_value_t = 3)]
+    pub runs: usize,
+}
+
+#[derive(ClapArgs, Debug)]
+pub struct ServeArgs {
+    /// 待ち受けるアドレス
+    #[arg(long, value_name = "ADDR", default_value = "127.0.0.1:8080")]
+    pub listen: std::net::SocketAddr,
+
+    /// 対象パス
+    #[arg(value_hint = ValueHint::AnyPath)]
+    pub paths: Vec<PathBuf>,
+}
+
+#[derive(ClapArgs, Debug)]
+pub struct DaemonArgs {
+    /// 監視するパス
+    #[arg(value_hint = ValueHint::AnyPath)]
+    pub paths: Vec<PathBuf>,
+}
+
+#[derive(ClapArgs, Debug)]
+pub struct CacheArgs {
+    #[command(subcommand)]
+    pub action: CacheAction,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum CacheAction {
+    /// キャッシュのサイズ・エントリ数・前回実行時のヒット率を表示 (--format json 可)
+    Stats,
+    /// キャッシュを削除
+    Clear,
+    /// 指定期間使われていないエントリを削除
+    Gc {
+        /// この期間より前に最後に使われたエントリを削除 (例: 30d, 12h, 2w)
+        #[arg(long, value_name = "AGE", value_parser = parsers::parse_age)]
+        max_age: chrono::Duration,
+    },
+}
+
+#[derive(ClapArgs, Debug)]
+#[command(group = clap::ArgGroup::new("limits").required(true).multiple(true))]
+pub struct LintArgs {
+    /// 1 ファイルあたりの最大行数
+    #[arg(long, value_name = "N", value_parser = parsers::parse_positive_usize, group = "limits")]
+    pub max_file_lines: Option<usize>,
+
+    /// 1 ファイルあたりの最大文字数
+    #[arg(long, value_name = "N", value_parser = parsers::parse_positive_usize, group = "limits")]
+    pub max_file_chars: Option<usize>,
+
+    /// 対象パス
+    #[arg(value_hint = ValueHint::AnyPath)]
+    pub paths: Vec<PathBuf>,
+}
+
+#[derive(ClapArgs, Debug)]
+pub struct HookArgs {
+    #[command(subcommand)]
+    pub action: HookAction,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum HookAction {
+    /// .git/hooks/pre-commit に `hook run` を呼ぶフックを書き込む (hook より前のオプションも引き継ぐ)
+    Install {
+        #[command(flatten)]
+        limits: HookLimits,
+
+        /// count_lines 以外が書いた既存の pre-commit フックを上書き
+        #[arg(long)]
+        force: bool,
+    },
+    /// ステージされたファイルを検査し、上限を超えていれば報告して非ゼロで終了 (フックから呼ばれる)
+    Run {
+        #[command(flatten)]
+        limits: HookLimits,
+    },
+}
+
+/// `hook` で検査する上限
+#[derive(ClapArgs, Debug, Clone, Default)]
+#[command(group = clap::ArgGroup::new("hook_limits").required(true).multiple(true))]
+pub struct HookLimits {
+    /// ステージされたファイル 1 つあたりの最大行数
+    #[arg(long, value_name = "N", value_parser = parsers::parse_positive_usize, group = "hook_limits")]
+    pub max_file_lines: Option<usize>,
+
+    /// ステージされたファイル 1 つあたりの最大文字数
+    #[arg(long, value_name = "N", value_parser = parsers::parse_positive_usize, group = "hook_limits")]
+    pub max_file_chars: Option<usize>,
+
+    /// 1 回のコミットで追加できる行数の上限 (git diff --cached --numstat の合計)
+    #[arg(long, value_name = "N", value_parser = parsers::parse_positive_usize, group = "hook_limits")]
+    pub max_added_lines: Option<usize>,
+}
+
+impl HookLimits {
+    /// The limits as command-line arguments.
+    #[must_use]
+    pub fn to_args(&self) -> Vec<String> {
+        [
+            ("--max-file-lines", self.max_file_lines),
+            ("--max-file-chars", self.max_file_chars),
+            ("--max-added-lines", self.max_added_lines),
+        ]
+        .into_iter()
+        .filter_map(|(flag, value)| value.map(|v| format!("{flag} {v}")))
+        .collect()
+    }
+}
+
+#[derive(ClapArgs, Debug)]
+pub struct SnapshotArgs {
+    #[command(subcommand)]
+    pub action: SnapshotAction,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum SnapshotAction {
+    /// 現在の計測結果をキャッシュディレクトリに保存
+    Save {
+        /// スナップショットに付けるタグ (例: v1.2)
+        #[arg(long)]
+        tag: Option<String>,
+
+        /// 対象パス
+        #[arg(value_hint = ValueHint::AnyPath)]
+        paths: Vec<PathBuf>,
+    },
+    /// 保存済みスナップショットと現在の計測結果 (または別のスナップショット) を比較
+    Diff {
+        /// 比較元 (タグ名、スナップショット名、または latest)
+        baseline: String,
+
+        /// 比較先のスナップショット。省略時は現在の計測結果
+        #[arg(long)]
+        to: Option<String>,
+
+        /// 対象パス
+        #[arg(value_hint = ValueHint::AnyPath)]
+        paths: Vec<PathBuf>,
+    },
+    /// 保存済みスナップショットの一覧を表示
+    List,
 }
 
 #[derive(ClapArgs, Debug)]
@@ -38,8 +410,14 @@ pub struct OutputOptions {
     #[arg(long, value_enum, default_value = "table", help_heading = "出力")]
     pub format: OutputFormat,
 
-    /// ソートキー（複数可, 例: lines:desc,chars:desc,name）
-    #[arg(long, default_value = "lines", help_heading = "出力")]
+    /// ソートキー（複数可, 例: lines:desc,chars:desc,name, sloc:desc,path:asc）
+    #[arg(
+        long,
+        default_value = "lines",
+        value_parser = parsers::ListParser::<SortSpec>::new(crate::options::SORT_KEYS),
+        hide_possible_values = true,
+        help_heading = "出力"
+    )]
     pub sort: SortSpec,
 
     /// CSV/TSV 末尾に TOTAL 行を出力
@@ -50,9 +428,182 @@ pub struct OutputOptions {
     #[arg(long, help_heading = "出力")]
     pub count_newlines_in_chars: bool,
 
-    /// 進捗表示
+    /// 進捗 (処理ファイル数・スループット・経過時間・残り時間) を標準エラーに表示
     #[arg(long, help_heading = "出力")]
     pub progress: bool,
+
+    /// 標準出力に何も書かない (エラーのみ標準エラーに表示。終了コードで結果を判定するスクリプト向け)
+    #[arg(short = 'q', long, conflicts_with = "oneline", help_heading = "出力")]
+    pub quiet: bool,
+
+    /// 合計を 1 行で出力 (例: files=123 lines=45678 sloc=34567 chars=901234)
+    #[arg(short = '1', long, help_heading = "出力")]
+    pub oneline: bool,
+
+    /// 結果を標準出力ではなくファイルへ書き出す
+    #[arg(short = 'o', long, value_hint = ValueHint::FilePath, help_heading = "出力")]
+    pub output: Option<PathBuf>,
+
+    /// 実行ごとのファイル別・合計の集計と実行情報 (日時・git コミット) を SQLite データベースに追記
+    #[arg(long, value_name = "PATH", value_hint = ValueHint::FilePath, help_heading = "出力")]
+    pub output_db: Option<PathBuf>,
+
+    /// 表示するパスをこのディレクトリからの相対パスに書き換える (別ドライブのパスは絶対パスのまま)
+    #[arg(long, value_name = "DIR", value_hint = ValueHint::DirPath, help_heading = "出力")]
+    pub relative_to: Option<PathBuf>,
+
+    /// ファイルごとではなくグループごとに集計して出力 (複数指定で入れ子に内訳を出力)
+    #[arg(long, value_enum, value_name = "GROUP", help_heading = "出力")]
+    pub by: Vec<GroupBy>,
+
+    /// 言語ごとにプロダクションコードとテストコードの行数を分けて集計し、テスト/プロダクション比を出力
+    #[arg(long, help_heading = "出力")]
+    pub test_split: bool,
+
+    /// --test-split でテストコードとみなすパス (.gitignore 風。既定のパターンを置き換える。例: tests/,*_test.go)
+    #[arg(
+        long,
+        value_name = "GLOB",
+        value_delimiter = ',',
+        requires = "test_split",
+        help_heading = "出力"
+    )]
+    pub test_pattern: Vec<String>,
+
+    /// ファイルから --test-pattern のパターンを読み込む (1 行 1 パターン。空行と # で始まる行は無視)
+    #[arg(long, value_name = "FILE", value_hint = ValueHint::FilePath, requires = "test_split", help_heading = "出力")]
+    pub test_patterns_from: Vec<PatternFileArg>,
+
+    /// 行末の空白、タブとスペースの混在、末尾の改行なし、CRLF / LF の改行コードをファイルごとに数え、ディレクトリごとの集計と合わせて出力
+    #[arg(long, help_heading = "出力")]
+    pub audit_whitespace: bool,
+
+    /// --by の集計後に条件式を満たすグループだけを出力 (複数指定は AND。例: --group-filter "lines > 10000")
+    #[arg(long, value_name = "EXPR", requires = "by", help_heading = "出力")]
+    pub group_filter: Vec<GroupFilter>,
+
+    /// --by age の区切り (昇順、例: 1w,1mo,6mo,1y)。最後の区切りより古いファイルは最後のグループへ
+    #[arg(long, value_name = "AGES", value_delimiter = ',', value_parser = parsers::parse_age_bucket, help_heading = "出力")]
+    pub age_buckets: Vec<chrono::Duration>,
+
+    /// --by の各グループにこの値の割合 (%) の列を追加。行数・ファイル数などの降順に並べ、累積割合も出力
+    #[arg(
+        long,
+        value_enum,
+        value_name = "METRIC",
+        requires = "by",
+        help_heading = "出力"
+    )]
+    pub ratio_of: Option<RatioMetric>,
+
+    /// --ratio-of の割合の分母 (total: 全体の合計、group: 1 つ外側のグループの小計)
+    #[arg(long, value_enum, value_name = "BASE", default_value_t = RatioBase::Total, requires = "ratio_of", help_heading = "出力")]
+    pub ratio_base: RatioBase,
+
+    /// --by owner で使う CODEOWNERS (省略時はリポジトリの .github/, ルート, docs/ から探す)
+    #[arg(long, value_name = "FILE", value_hint = ValueHint::FilePath, help_heading = "出力")]
+    pub codeowners: Option<PathBuf>,
+
+    /// ソート後の先頭 N ファイルのみ出力
+    #[arg(long, value_name = "N", help_heading = "出力")]
+    pub top: Option<usize>,
+
+    /// table / csv / tsv に出す列とその順序 (例: path,lines,sloc,ratio)
+    #[arg(
+        long,
+        value_name = "COLUMNS",
+        value_parser = parsers::ListParser::<ColumnSpec>::new(
+            count_lines_engine::options::Column::ALL.map(count_lines_engine::options::Column::name)
+        ),
+        hide_possible_values = true,
+        help_heading = "出力"
+    )]
+    pub columns: Option<ColumnSpec>,
+
+    /// table / tree / Markdown の数値を短縮表記にする (1.2M 行、3.4 GiB)
+    #[arg(long, help_heading = "出力")]
+    pub human: bool,
+
+    /// table / tree / Markdown の数値を 3 桁ごとに区切る (SEP 省略時はロケールの区切り文字)
+    #[arg(long, value_name = "SEP", num_args = 0..=1, help_heading = "出力")]
+    pub thousands: Option<Option<String>>,
+
+    /// --human でもサイズをバイト数のまま表示
+    #[arg(long, help_heading = "出力")]
+    pub bytes: bool,
+
+    /// table / tree の色付け (JSON / CSV などには影響しない)
+    #[arg(long, value_enum, value_name = "WHEN", default_value_t = ColorChoice::Auto, help_heading = "出力")]
+    pub color: ColorChoice,
+
+    /// 色付けのテーマ
+    #[arg(long, value_enum, default_value_t = Theme::Default, help_heading = "出力")]
+    pub theme: Theme,
+
+    /// 行数が N を超えるファイルの行を table で強調表示
+    #[arg(long, value_name = "N", help_heading = "出力")]
+    pub highlight_over: Option<usize>,
+
+    /// 先頭 20 行に TEXT を含まないファイルを --format github で警告 (例: SPDX-License-Identifier)
+    #[arg(long, value_name = "TEXT", help_heading = "出力")]
+    pub license_header: Option<String>,
+
+    /// 合計行数の多いディレクトリ上位 N 件もあわせて出力 (table/md/json)
+    #[arg(long, value_name = "N", help_heading = "出力")]
+    pub top_dirs: Option<usize>,
+
+    /// 行数・サイズの中央値 / p90 / p99 / 最大値とサイズのヒストグラムもあわせて出力 (table/json)
+    #[arg(long, help_heading = "出力")]
+    pub distribution: bool,
+
+    /// コメント・空行を除いたコード行の PERCENT% 以上が共通するファイルの組を出力 (table/md/json)
+    #[arg(long, value_name = "PERCENT", value_parser = clap::value_parser!(u8).range(1..=100), help_heading = "出力")]
+    pub detect_similar: Option<u8>,
+
+    /// UTF-8 / UTF-16 / UTF-32 の BOM 付きファイルと BOM なしファイルの数、UTF-8 のはずの拡張子で不正な UTF-8 を含むファイルもあわせて出力 (table/md/json)
+    #[arg(long, help_heading = "出力")]
+    pub audit_encoding: bool,
+
+    /// --format tree で表示するディレクトリの深さ (ルートが 0)
+    #[arg(long, value_name = "N", help_heading = "出力")]
+    pub tree_depth: Option<usize>,
+
+    /// json / jsonl / yaml / csv / tsv に実行情報 (バージョン・開始日時・ホスト・走査パス・git コミット・所要時間) を付ける
+    #[arg(long, help_heading = "出力")]
+    pub with_metadata: bool,
+
+    /// 走査・テキスト判定・計測・集計・出力の各段階の所要時間とキャッシュのヒット数を表示 (--format json では出力に含め、それ以外は標準エラー出力へ)
+    #[arg(long, help_heading = "出力")]
+    pub timings: bool,
+
+    /// 実行ごとの合計をキャッシュディレクトリに記録し、前回と N 回前 (既定 5) からの増減を標準エラーに表示 (例: --trend=10)
+    #[arg(
+        long,
+        value_name = "N",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "5",
+        value_parser = parsers::parse_positive_usize,
+        help_heading = "出力"
+    )]
+    pub trend: Option<usize>,
+
+    /// --format markdown の表の前に合計値の shields.io バッジを出力
+    #[arg(long, help_heading = "出力")]
+    pub markdown_badges: bool,
+
+    /// 結果の代わりに shields.io endpoint バッジの JSON を出力 (例: lines, sloc:rust)
+    #[arg(long, value_name = "METRIC[:LANGUAGE]", help_heading = "出力")]
+    pub badge: Option<BadgeSpec>,
+
+    /// --badge の色のしきい値 (MIN=COLOR をカンマ区切り, 例: 0=red,10k=yellow,100k=brightgreen)
+    #[arg(
+        long,
+        value_name = "MIN=COLOR,...",
+        requires = "badge",
+        help_heading = "出力"
+    )]
+    pub badge_colors: Option<BadgeColors>,
 }
 
 #[derive(ClapArgs, Debug)]
@@ -64,6 +615,18 @@ pub struct FilterOptions {
     #[arg(long, value_delimiter = ',', help_heading = "フィルタ")]
     pub exclude: Vec<String>,
 
+    /// ファイルから --include のパターンを読み込む (1 行 1 パターン。空行と # で始まる行は無視)
+    #[arg(long, value_name = "FILE", value_hint = ValueHint::FilePath, help_heading = "フィルタ")]
+    pub include_from: Vec<PatternFileArg>,
+
+    /// ファイルから --exclude のパターンを読み込む (1 行 1 パターン。空行と # で始まる行は無視)
+    #[arg(long, value_name = "FILE", value_hint = ValueHint::FilePath, help_heading = "フィルタ")]
+    pub exclude_from: Vec<PatternFileArg>,
+
+    /// --include / --exclude / --override-* のパターンを大文字小文字を区別せずに照合
+    #[arg(long, help_heading = "フィルタ")]
+    pub glob_icase: bool,
+
     #[arg(long, value_delimiter = ',', help_heading = "フィルタ")]
     pub ext: Vec<String>,
 
@@ -91,6 +654,18 @@ pub struct FilterOptions {
     #[arg(long, help_heading = "フィルタ")]
     pub sloc: bool,
 
+    /// ドキュメントコメント (Rust の ///・//!、Javadoc/TSDoc の /** */、Python の docstring) の行数と、SLOC に対する割合 (doc%) を出力 (--sloc も有効になる)
+    #[arg(long, help_heading = "フィルタ")]
+    pub docs: bool,
+
+    /// ファイルごとのインデントの最大深さ (maxdepth) と平均深さ (avgdepth) を出力。複雑さの簡易な目安
+    #[arg(long, help_heading = "フィルタ")]
+    pub indent: bool,
+
+    /// --indent でインデント 1 段とみなす桁数 (タブもこの幅の倍数に揃える)
+    #[arg(long, value_name = "N", default_value_t = 4, value_parser = parsers::parse_positive_usize, help_heading = "フィルタ")]
+    pub tab_width: usize,
+
     #[arg(long, help_heading = "フィルタ")]
     pub min_words: Option<usize>,
 
@@ -103,9 +678,41 @@ pub struct FilterOptions {
     #[arg(long, help_heading = "フィルタ")]
     pub mtime_until: Option<DateTimeArg>,
 
-    /// 拡張子と言語の紐づけ (例: h=cpp, mylang=sh)
-    #[arg(long, value_parser = parsers::parse_key_val, help_heading = "フィルタ")]
+    /// 拡張子と言語の紐づけ (例: --map-ext inc=php --map-ext txt=sql)。大文字小文字は区別しない
+    #[arg(long, value_name = "EXT=LANG", value_parser = parsers::parse_ext_mapping, help_heading = "フィルタ")]
     pub map_ext: Vec<(String, String)>,
+
+    /// 条件式でファイルを絞り込む (複数指定は AND。例: --filter "maxline > 200")
+    #[arg(long, value_name = "EXPR", help_heading = "フィルタ")]
+    pub filter: Vec<FilterExpr>,
+
+    /// 言語定義ファイル (TOML) を読み込み、独自言語のコメント構文を登録
+    #[arg(long, value_name = "FILE", value_hint = ValueHint::FilePath, help_heading = "フィルタ")]
+    pub language_def: Vec<LanguageDefArg>,
+
+    /// 指定した拡張子のファイルを外部コマンドで計測 (例: --plugin dat,rec=./my_counter)。ファイルのパスを引数、内容を標準入力に渡し、標準出力の JSON ({"lines": N, "sloc": N, ...}) を結果とする
+    #[arg(long, value_name = "EXT[,EXT...]=COMMAND", value_parser = parsers::parse_plugin, help_heading = "プラグイン")]
+    pub plugin: Vec<Plugin>,
+
+    /// --plugin の 1 回の実行の制限時間 (超えたら強制終了し、そのファイルはエラーとして報告)
+    #[arg(long, value_name = "DURATION", value_parser = parsers::parse_timeout, default_value = "10s", help_heading = "プラグイン")]
+    pub plugin_timeout: Duration,
+
+    /// --plugin の 1 回の実行で使えるメモリ (仮想アドレス空間) の上限 (例: 512MiB。Unix のみ、ulimit -v で制限)
+    #[arg(long, value_name = "SIZE", help_heading = "プラグイン")]
+    pub plugin_memory: Option<SizeArg>,
+
+    /// 先頭/末尾の Vim モードライン・Emacs の -*- mode -*- から言語を判定
+    #[arg(long, help_heading = "フィルタ")]
+    pub detect_modeline: bool,
+
+    /// 圧縮 (minified) された JS/CSS も通常のファイルとして集計 (既定では別枠で報告し SLOC は 0)
+    #[arg(long, help_heading = "フィルタ")]
+    pub include_minified: bool,
+
+    /// BOM のない非 UTF-8 ファイルをこの文字コードで読む (例: sjis, euc-jp, latin1)。省略時は内容から推定
+    #[arg(long, value_name = "ENCODING", value_parser = parsers::parse_encoding, help_heading = "フィルタ")]
+    pub encoding: Option<&'static count_lines_engine::encoding::Encoding>,
 }
 
 #[derive(ClapArgs, Debug)]
@@ -117,6 +724,30 @@ pub struct ScanOptions {
     #[arg(long, help_heading = "走査/入力")]
     pub follow: bool,
 
+    /// Windows のジャンクション (ディレクトリの再解析ポイント) の先も走査 (既定では --follow でも辿らず、二重計上を防ぐ。循環は検出してスキップ)
+    #[arg(long, help_heading = "走査/入力")]
+    pub follow_junctions: bool,
+
+    /// .gitmodules に書かれた git サブモジュールも走査 (既定ではスキップ。--by submodule でサブモジュールごとに集計)
+    #[arg(
+        long,
+        overrides_with = "exclude_submodules",
+        help_heading = "走査/入力"
+    )]
+    pub include_submodules: bool,
+
+    /// git サブモジュールを走査しない (既定)
+    #[arg(
+        long,
+        overrides_with = "include_submodules",
+        help_heading = "走査/入力"
+    )]
+    pub exclude_submodules: bool,
+
+    /// 辿ったシンボリックリンク・循環して飛ばしたリンク・リンク切れを報告
+    #[arg(long, help_heading = "走査/入力")]
+    pub report_links: bool,
+
     #[arg(long, help_heading = "走査/入力")]
     pub no_gitignore: bool,
 
@@ -129,6 +760,42 @@ pub struct ScanOptions {
     #[arg(long = "walk-threads", value_parser = parsers::parse_usize_1_to_512, help_heading = "走査/入力")]
     pub walk_threads: Option<usize>,
 
+    /// 走査・計測・集計の間で待機できるファイル数の上限 (大きいほど速く、小さいほどメモリが少ない)
+    #[arg(long, value_name = "N", value_parser = parsers::parse_positive_usize, default_value_t = count_lines_engine::config::Config::DEFAULT_MAX_IN_FLIGHT, help_heading = "走査/入力")]
+    pub max_in_flight: usize,
+
+    /// 同時に読み込むファイル数の上限 (--jobs とは別。NFS や SMB のマウントでサーバーに負荷をかけすぎないように)
+    #[arg(long, value_name = "N", value_parser = parsers::parse_positive_usize, help_heading = "走査/入力")]
+    pub io_concurrency: Option<usize>,
+
+    /// このサイズを超えるファイルを行の区切りで分割し、並列に計測 (例: 64MiB。巨大な SQL ダンプやログ向け)
+    #[arg(long, value_name = "SIZE", help_heading = "走査/入力")]
+    pub chunk_size: Option<SizeArg>,
+
+    /// このサイズを超える行はインデントと空白のチェックを省いてその場で数え、該当ファイルを報告 (既定: 1MiB。0 で無効。バンドルやデータを 1 行に詰めたファイル向け)
+    #[arg(long, value_name = "SIZE", help_heading = "走査/入力")]
+    pub long_line_threshold: Option<SizeArg>,
+
+    /// 走査するファイル数の上限 (超えたら --on-limit に従う。/ や巨大な成果物ディレクトリを誤って走査したときの保護)
+    #[arg(long, value_name = "N", value_parser = parsers::parse_positive_usize, help_heading = "走査/入力")]
+    pub max_files: Option<usize>,
+
+    /// 走査するファイルの合計サイズの上限 (例: 2GiB。超えたら --on-limit に従う)
+    #[arg(long, value_name = "SIZE", help_heading = "走査/入力")]
+    pub max_total_bytes: Option<SizeArg>,
+
+    /// --max-files / --max-total-bytes を超えたときの動作
+    #[arg(long, value_enum, value_name = "ACTION", default_value_t = LimitAction::Abort, help_heading = "走査/入力")]
+    pub on_limit: LimitAction,
+
+    /// 1 ファイルの読み込みと計測の制限時間 (例: 10s。超えたファイルはエラーとして報告し、待たずに次へ進む)
+    #[arg(long, value_name = "DURATION", value_parser = parsers::parse_timeout, help_heading = "走査/入力")]
+    pub file_timeout: Option<Duration>,
+
+    /// 実行全体の制限時間 (例: 10m。超えたら走査を止め、残りのファイルはエラーとして報告)
+    #[arg(long, value_name = "DURATION", value_parser = parsers::parse_timeout, help_heading = "走査/入力")]
+    pub timeout: Option<Duration>,
+
     #[arg(
         long = "override-include",
         value_delimiter = ',',
@@ -142,6 +809,39 @@ pub struct ScanOptions {
         help_heading = "走査/入力"
     )]
     pub override_exclude: Vec<String>,
+
+    /// .zip / .tar / .tar.gz の中身を展開せずに計測 (archive.zip!inner/path.rs として出力)
+    #[arg(long, help_heading = "走査/入力")]
+    pub scan_archives: bool,
+
+    /// FIFO・ソケット・デバイスファイルも計測 (既定では読み込みが止まる恐れがあるためスキップし、パスで直接指定したものはエラーとして報告。/proc 配下などを数えたいとき向け)
+    #[arg(long, help_heading = "走査/入力")]
+    pub include_special: bool,
+
+    /// .gitattributes の linguist-vendored / linguist-documentation を反映 (--linguist で除外、--linguist=tag で分類のみ記録)
+    #[arg(
+        long,
+        value_enum,
+        value_name = "MODE",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "exclude",
+        help_heading = "走査/入力"
+    )]
+    pub linguist: Option<LinguistMode>,
+
+    /// パスを走査せず標準入力の内容を 1 ファイルとして計測
+    #[arg(long, conflicts_with_all = ["watch", "paths"], help_heading = "走査/入力")]
+    pub stdin: bool,
+
+    /// 標準入力に付ける擬似ファイル名。拡張子から言語を判定 (例: foo.rs)
+    #[arg(
+        long,
+        value_name = "NAME",
+        requires = "stdin",
+        help_heading = "走査/入力"
+    )]
+    pub stdin_name: Option<PathBuf>,
 }
 
 #[derive(ClapArgs, Debug)]
@@ -150,18 +850,136 @@ pub struct BehaviorOptions {
     #[arg(long, help_heading = "動作")]
     pub strict: bool,
 
+    /// 指定したパスが集計対象か、除外されるならどのルールかを表示 (git check-ignore -v 相当)
+    #[arg(long, value_name = "PATH", value_hint = ValueHint::AnyPath, conflicts_with_all = ["stdin", "watch"], help_heading = "動作")]
+    pub why: Option<PathBuf>,
+
+    /// 計測せず、フィルタを通過したファイルの一覧だけを出力 (--format json で JSON 配列)
+    #[arg(long, conflicts_with_all = ["stdin", "watch", "why"], help_heading = "動作")]
+    pub list_only: bool,
+
+    /// 読み込みに失敗したファイルだけを path・kind・message で出力 (--format json/jsonl/csv/tsv で機械可読)
+    #[arg(long, conflicts_with_all = ["list_only", "why", "print0"], help_heading = "動作")]
+    pub errors_only: bool,
+
+    /// 表の代わりにパスだけを NUL 文字区切りで出力 (xargs -0 向け。--list-only と併用可)
+    #[arg(short = '0', long, help_heading = "動作")]
+    pub print0: bool,
+
+    /// 集計結果が条件式を満たしたら非ゼロで終了 (CI 用。複数指定可。例: --fail-if "total_lines > 500000")
+    #[arg(long, value_name = "EXPR", help_heading = "動作")]
+    pub fail_if: Vec<Threshold>,
+
     #[arg(short = 'w', long, help_heading = "動作")]
     pub watch: bool,
 
     #[arg(long = "watch-interval", value_parser = parsers::parse_positive_u64, help_heading = "ウォッチング")]
     pub watch_interval: Option<u64>,
 
+    /// 最後の変更からこの時間 (ミリ秒) 待ってから再計測 (既定: 200)
+    #[arg(long = "watch-debounce", value_name = "MS", value_parser = parsers::parse_positive_u64, help_heading = "ウォッチング")]
+    pub watch_debounce: Option<u64>,
+
     #[arg(long, value_enum, default_value = "full", help_heading = "動作")]
     pub watch_output: WatchOutput,
+
+    /// ウォッチ中に言語別の集計・最近の変更・SLOC の推移をダッシュボード表示 (s: ソート切替, /: 絞り込み, q: 終了)
+    #[arg(
+        long,
+        requires = "watch",
+        conflicts_with = "output",
+        help_heading = "ウォッチング"
+    )]
+    pub tui: bool,
+
+    /// 集計を定期的に (--watch なら変更時に) 実行し、/metrics で Prometheus 形式の値を公開 (例: 0.0.0.0:9900)
+    #[arg(long, value_name = "ADDR", conflicts_with_all = ["stdin", "tui"], help_heading = "動作")]
+    pub serve_metrics: Option<std::net::SocketAddr>,
+
+    /// --serve-metrics で再集計する間隔 (秒, 既定: 60。--watch 時は変更時に再集計)
+    #[arg(long, value_name = "SECS", value_parser = parsers::parse_positive_u64, requires = "serve_metrics", help_heading = "動作")]
+    pub metrics_interval: Option<u64>,
+
+    /// 標準エラーに出すログの詳細度 (既定: warn。debug で各フェーズの所要時間を表示)
+    #[arg(
+        long,
+        value_enum,
+        value_name = "LEVEL",
+        default_value = "warn",
+        help_heading = "動作"
+    )]
+    pub log_level: LogLevel,
+
+    /// ログの形式 (json は 1 行 1 オブジェクト)
+    #[arg(long, value_enum, default_value = "text", help_heading = "動作")]
+    pub log_format: LogFormat,
+
+    /// スナップショット等を保存するディレクトリ (既定: ./.count_lines)
+    #[arg(long, value_hint = ValueHint::DirPath, help_heading = "動作")]
+    pub cache_dir: Option<PathBuf>,
+
+    /// 前回の実行結果をキャッシュディレクトリに保存し、変更のないファイルは再計測しない
+    #[arg(long, help_heading = "動作")]
+    pub incremental: bool,
+
+    /// --incremental で未変更と判定する方法
+    #[arg(
+        long,
+        value_enum,
+        default_value = "mtime",
+        requires = "incremental",
+        help_heading = "動作"
+    )]
+    pub cache_key: CacheKey,
+
+    /// キャッシュをリポジトリ内の相対パスと内容のハッシュで管理し、複数のワークツリーや CI のチェックアウトで共有 (--cache-dir と併用)
+    #[arg(long, requires = "incremental", help_heading = "動作")]
+    pub shared_cache: bool,
 }
 
 #[derive(ClapArgs, Debug)]
 pub struct ComparisonOptions {
     #[arg(long, num_args = 2, value_names = ["OLD", "NEW"], value_hint = ValueHint::FilePath, help_heading = "比較")]
     pub compare: Option<Vec<PathBuf>>,
+
+    /// git のリビジョン間で比較 (例: main..HEAD)。`REV` 単体ならワーキングツリーと比較
+    #[arg(
+        long,
+        value_name = "REV[..REV]",
+        conflicts_with = "compare",
+        help_heading = "比較"
+    )]
+    pub compare_ref: Option<String>,
+
+    /// 比較結果の出力フォーマット
+    #[arg(long, value_enum, default_value = "table", help_heading = "比較")]
+    pub compare_format: CompareFormat,
+
+    /// 比較結果のファイル一覧の並び順
+    #[arg(long, value_enum, default_value = "path", help_heading = "比較")]
+    pub compare_sort: CompareSort,
+
+    /// 変更のないファイルも一覧に含める
+    #[arg(long, help_heading = "比較")]
+    pub compare_unchanged: bool,
+
+    /// git 履歴の直近 N コミットを順に計測し、時系列で出力
+    #[arg(long, value_name = "N", value_parser = parsers::parse_positive_usize, help_heading = "履歴")]
+    pub history: Option<usize>,
+
+    /// 指定日時以降のコミットを順に計測し、時系列で出力
+    #[arg(long, value_name = "DATE", help_heading = "履歴")]
+    pub history_since: Option<DateTimeArg>,
+
+    /// 履歴モードで K コミットごとに計測 (最新コミットは常に含む)
+    #[arg(long, value_name = "K", default_value = "1", value_parser = parsers::parse_positive_usize, help_heading = "履歴")]
+    pub history_step: usize,
+
+    /// git log --numstat からファイルごとの追加・削除行数を集計し、現在の行数と掛け合わせて変更の多い大きなファイル (ホットスポット) を出力
+    #[arg(long, help_heading = "履歴")]
+    pub churn: bool,
+
+    /// --churn で集計するコミットの期間 (例: 90d, 2w, 2024-01-01)
+    #[arg(long, value_name = "WHEN", requires = "churn", value_parser = parsers::parse_since, help_heading = "履歴")]
+    pub since: Option<DateTimeArg>,
 }
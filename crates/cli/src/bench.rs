@@ -0,0 +1,230 @@
+// crates/cli/src/bench.rs
+//! `count_lines bench` (hidden): counts a generated tree a few times and
+//! reports how long the walk, measure and aggregate stages took, so
+//! performance can be compared across releases.
+
+use crate::args::BenchArgs;
+use crate::config::Config;
+use crate::error::Result;
+use count_lines_engine::options::OutputFormat;
+use count_lines_engine::stats::StageTimings;
+use std::fmt::Write as _;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+/// Files generated per directory.
+const FILES_PER_DIR: usize = 100;
+
+/// A tree to benchmark on: `synthetic-<N>` is N generated files in mixed
+/// languages, `k` and `m` suffixes allowed (`synthetic-100k`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Fixture {
+    pub files: usize,
+}
+
+impl FromStr for Fixture {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let count = s
+            .strip_prefix("synthetic-")
+            .ok_or_else(|| format!("unknown fixture: {s} (expected synthetic-<N>)"))?;
+        let lower = count.to_ascii_lowercase();
+        let (digits, multiplier) = if let Some(n) = lower.strip_suffix('k') {
+            (n, 1_000)
+        } else if let Some(n) = lower.strip_suffix('m') {
+            (n, 1_000_000)
+        } else {
+            (lower.as_str(), 1)
+        };
+        let files = digits
+            .parse::<usize>()
+            .ok()
+            .and_then(|n| n.checked_mul(multiplier))
+            .filter(|&n| n > 0)
+            .ok_or_else(|| format!("invalid file count: {count}"))?;
+        Ok(Self { files })
+    }
+}
+
+impl std::fmt::Display for Fixture {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "synthetic-{}", self.files)
+    }
+}
+
+/// One generated file of language `ext` with function `n`.
+fn file_content(ext: &str, n: usize) -> String {
+    // 5 to 54 blocks, varying from file to file but fixed for each `n`.
+    let blocks = 5 + n * 7919 % 50;
+    let mut out = String::new();
+    for j in 0..blocks {
+        let _ = match ext {
+            "rs" => write!(out, "// f{j}\nfn f{j}() -> usize {{\n    {n}\n}}\n\n"),
+            "py" => write!(out, "# f{j}\ndef f{j}():\n    return {n}\n\n"),
+            "js" => write!(out, "/* f{j} */\nfunction f{j}() {{\n  return {n};\n}}\n"),
+            "c" => write!(out, "/*\n * f{j}\n */\nint f{j}(void) {{ return {n}; }}\n"),
+            "sql" => write!(out, "-- q{j}\nSELECT {j}, '{n}' FROM t;\n"),
+            "md" => write!(out, "## Section {j}\n\nText for item {n}.\n\n"),
+            _ => write!(out, "[s{j}]\nvalue = {n}\n"),
+        };
+    }
+    out
+}
+
+/// Writes the files of `fixture` under `dir`.
+///
+/// # Errors
+/// Returns an error if a directory or file can't be written.
+pub fn generate(dir: &Path, fixture: Fixture) -> io::Result<()> {
+    const EXTENSIONS: [&str; 7] = ["rs", "py", "js", "c", "sql", "md", "toml"];
+    for n in 0..fixture.files {
+        let group = n / FILES_PER_DIR;
+        let subdir = dir
+            .join(format!("d{}", group / FILES_PER_DIR))
+            .join(format!("d{}", group % FILES_PER_DIR));
+        if n % FILES_PER_DIR == 0 {
+            std::fs::create_dir_all(&subdir)?;
+        }
+        let ext = EXTENSIONS[n % EXTENSIONS.len()];
+        std::fs::write(subdir.join(format!("f{n}.{ext}")), file_content(ext, n))?;
+    }
+    Ok(())
+}
+
+/// Removes the generated tree when the benchmark ends.
+struct TempTree(PathBuf);
+
+impl Drop for TempTree {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
+
+/// Timings of one run.
+#[derive(Debug, Clone, Copy, Default)]
+struct Sample {
+    stages: StageTimings,
+    total: Duration,
+}
+
+/// Generates the fixture, counts it `args.runs` times and prints the
+/// stage timings.
+///
+/// # Errors
+/// Returns an error if the fixture can't be generated or a count fails.
+pub fn run(args: &BenchArgs, mut config: Config) -> Result<()> {
+    let tree = TempTree(std::env::temp_dir().join(format!(
+        "count_lines-bench-{}-{}",
+        std::process::id(),
+        args.fixture
+    )));
+    let started = Instant::now();
+    generate(&tree.0, args.fixture)?;
+    let generated = started.elapsed();
+    config.walk.roots = vec![tree.0.clone()];
+
+    let mut samples = Vec::with_capacity(args.runs);
+    let mut counted = (0, 0);
+    for _ in 0..args.runs {
+        let started = Instant::now();
+        let result = count_lines_engine::run(&config)?;
+        samples.push(Sample {
+            stages: result.timings,
+            total: started.elapsed(),
+        });
+        counted = (result.stats.len(), result.summary().lines);
+    }
+
+    let stdout = io::stdout();
+    let mut out = stdout.lock();
+    if matches!(config.format, OutputFormat::Json) {
+        let ms = |d: Duration| d.as_secs_f64() * 1000.0;
+        let runs: Vec<_> = samples
+            .iter()
+            .map(|s| {
+                serde_json::json!({
+                    "walk_ms": ms(s.stages.walk),
+                    "measure_ms": ms(s.stages.measure),
+                    "aggregate_ms": ms(s.stages.aggregate),
+                    "total_ms": ms(s.total),
+                })
+            })
+            .collect();
+        let report = serde_json::json!({
+            "version": crate::VERSION,
+            "fixture": args.fixture.to_string(),
+            "files": counted.0,
+            "lines": counted.1,
+            "threads": config.walk.threads,
+            "generate_ms": ms(generated),
+            "runs": runs,
+        });
+        writeln!(out, "{report:#}")?;
+        return Ok(());
+    }
+
+    writeln!(
+        out,
+        "count_lines v{} · {} · {} files, {} lines · parallel={} (generated in {:.2?})",
+        crate::VERSION,
+        args.fixture,
+        counted.0,
+        counted.1,
+        config.walk.threads,
+        generated
+    )?;
+    writeln!(
+        out,
+        "{:>5} {:>12} {:>12} {:>12} {:>12}",
+        "RUN", "WALK", "MEASURE", "AGGREGATE", "TOTAL"
+    )?;
+    let row = |label: &str, s: &Sample| {
+        format!(
+            "{label:>5} {:>12.2?} {:>12.2?} {:>12.2?} {:>12.2?}",
+            s.stages.walk, s.stages.measure, s.stages.aggregate, s.total
+        )
+    };
+    for (i, sample) in samples.iter().enumerate() {
+        writeln!(out, "{}", row(&(i + 1).to_string(), sample))?;
+    }
+    if let Some(best) = samples.iter().min_by_key(|s| s.total) {
+        writeln!(out, "{}", row("best", best))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use count_lines_engine::config::WalkOptions;
+
+    #[test]
+    fn test_parse_fixture() {
+        assert_eq!("synthetic-100k".parse(), Ok(Fixture { files: 100_000 }));
+        assert_eq!("synthetic-2M".parse(), Ok(Fixture { files: 2_000_000 }));
+        assert_eq!("synthetic-250".parse(), Ok(Fixture { files: 250 }));
+        assert!("synthetic-0".parse::<Fixture>().is_err());
+        assert!("linux".parse::<Fixture>().is_err());
+    }
+
+    #[test]
+    fn test_generated_tree_is_counted() {
+        let dir = tempfile::tempdir().unwrap();
+        generate(dir.path(), Fixture { files: 250 }).unwrap();
+        let config = Config {
+            walk: WalkOptions {
+                roots: vec![dir.path().to_path_buf()],
+                ..WalkOptions::default()
+            },
+            ..Config::default()
+        };
+
+        let result = count_lines_engine::run(&config).unwrap();
+        assert_eq!(result.stats.len(), 250);
+        assert!(result.summary().lines > 250 * 20);
+        assert!(dir.path().join("d0/d2/f249.sql").exists());
+    }
+}
@@ -0,0 +1,94 @@
+// crates/cli/src/cache.rs
+use crate::args::CacheAction;
+use crate::config::Config;
+use crate::error::Result;
+use count_lines_engine::cache::{self, CacheInfo};
+use count_lines_engine::options::OutputFormat;
+use std::io::{self, Write};
+
+/// Runs a `cache` subcommand on the cache of `config.cache_dir`.
+///
+/// # Errors
+/// Returns an error if the cache cannot be read or written.
+pub fn run(action: CacheAction, config: &Config) -> Result<()> {
+    let dir = config.resolved_cache_dir();
+    match action {
+        CacheAction::Stats => {
+            let info = cache::info(&dir)?;
+            let stdout = io::stdout();
+            write_info(&mut stdout.lock(), &info, config.format)?;
+        }
+        CacheAction::Clear => {
+            if cache::clear(&dir)? {
+                println!("Removed {}", dir.join(cache::CACHE_FILE).display());
+            } else {
+                println!("No cache in {}", dir.display());
+            }
+        }
+        CacheAction::Gc { max_age } => {
+            let removed = cache::gc(&dir, max_age, chrono::Local::now())?;
+            println!("Removed {removed} stale cache entries.");
+        }
+    }
+    Ok(())
+}
+
+/// Writes the `cache stats` report; `json` writes [`CacheInfo`] as is.
+///
+/// # Errors
+/// Returns an error if writing to `out` fails.
+pub fn write_info(out: &mut dyn Write, info: &CacheInfo, format: OutputFormat) -> io::Result<()> {
+    if format == OutputFormat::Json {
+        let json = serde_json::to_string_pretty(info).map_err(io::Error::other)?;
+        return writeln!(out, "{json}");
+    }
+    writeln!(out, "Cache:    {}", info.path.display())?;
+    writeln!(out, "Size:     {} bytes", info.size)?;
+    writeln!(out, "Entries:  {}", info.entries)?;
+    match &info.last_run {
+        Some(run) => {
+            let total = run.hits + run.misses;
+            #[allow(clippy::cast_precision_loss)]
+            let rate = if total == 0 {
+                0.0
+            } else {
+                run.hits as f64 * 100.0 / total as f64
+            };
+            writeln!(
+                out,
+                "Last run: {}  {} hits / {} misses ({rate:.1}% hit rate)",
+                run.at.to_rfc3339(),
+                run.hits,
+                run.misses
+            )
+        }
+        None => writeln!(out, "Last run: -"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Local, TimeZone};
+    use count_lines_engine::cache::CacheRun;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_stats_text() {
+        let info = CacheInfo {
+            path: PathBuf::from(".count_lines/file-cache.json"),
+            size: 2048,
+            entries: 4,
+            last_run: Some(CacheRun {
+                at: Local.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap(),
+                hits: 3,
+                misses: 1,
+            }),
+        };
+        let mut buf = Vec::new();
+        write_info(&mut buf, &info, OutputFormat::Table).unwrap();
+        let text = String::from_utf8(buf).unwrap();
+        assert!(text.contains("Entries:  4\n"));
+        assert!(text.contains("3 hits / 1 misses (75.0% hit rate)"));
+    }
+}
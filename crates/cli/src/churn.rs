@@ -0,0 +1,170 @@
+// crates/cli/src/churn.rs
+use crate::config::Config;
+use crate::error::Result;
+use crate::presentation::Delimiter;
+use count_lines_engine::git::{self, FileChurn};
+use count_lines_engine::options::OutputFormat;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+
+/// Reads the churn configured by `--churn` / `--since` and prints the
+/// hotspots to stdout, or to `config.output` when set. `--top` keeps the
+/// first N.
+///
+/// # Errors
+/// Returns an error if the history cannot be read or the output fails.
+pub fn print_churn(config: &Config) -> Result<()> {
+    let options = config.churn.clone().unwrap_or_default();
+    let mut churn = git::count_churn(config, &options)?;
+    if let Some(top) = config.top {
+        churn.truncate(top);
+    }
+
+    if let Some(path) = &config.output {
+        let mut out = BufWriter::new(File::create(path)?);
+        write_churn(&mut out, &churn, config.format)?;
+        out.flush()?;
+    } else {
+        let stdout = io::stdout();
+        let mut out = stdout.lock();
+        write_churn(&mut out, &churn, config.format)?;
+    }
+    Ok(())
+}
+
+/// Writes the files in `format`.
+///
+/// `json`/`jsonl`/`csv`/`tsv` are machine readable; other formats fall back
+/// to a plain table.
+///
+/// # Errors
+/// Returns an error if writing to `out` fails.
+pub fn write_churn(
+    out: &mut dyn Write,
+    churn: &[FileChurn],
+    format: OutputFormat,
+) -> io::Result<()> {
+    match format {
+        OutputFormat::Json => {
+            let json = serde_json::to_string_pretty(churn).map_err(io::Error::other)?;
+            writeln!(out, "{json}")
+        }
+        OutputFormat::Jsonl => {
+            for file in churn {
+                let json = serde_json::to_string(file).map_err(io::Error::other)?;
+                writeln!(out, "{json}")?;
+            }
+            Ok(())
+        }
+        OutputFormat::Csv => write_sv(out, churn, Delimiter::Comma),
+        OutputFormat::Tsv => write_sv(out, churn, Delimiter::Tab),
+        _ => write_table(out, churn),
+    }
+}
+
+fn write_sv(out: &mut dyn Write, churn: &[FileChurn], delimiter: Delimiter) -> io::Result<()> {
+    let header = [
+        "path", "lines", "sloc", "added", "removed", "commits", "churn", "score",
+    ];
+    writeln!(out, "{}", header.join(delimiter.as_str()))?;
+    for f in churn {
+        let fields = [
+            delimiter.escape(&f.path.display().to_string()),
+            f.lines.to_string(),
+            f.sloc.map(|v| v.to_string()).unwrap_or_default(),
+            f.added.to_string(),
+            f.removed.to_string(),
+            f.commits.to_string(),
+            f.churn.to_string(),
+            f.score.to_string(),
+        ];
+        writeln!(out, "{}", fields.join(delimiter.as_str()))?;
+    }
+    Ok(())
+}
+
+fn write_table(out: &mut dyn Write, churn: &[FileChurn]) -> io::Result<()> {
+    writeln!(
+        out,
+        "{:>12}{:>10}{:>10}{:>9}{:>10}{:>14}  FILE",
+        "CHURN", "ADDED", "REMOVED", "COMMITS", "LINES", "SCORE"
+    )?;
+    writeln!(out, "{}", "-".repeat(71))?;
+    for f in churn {
+        writeln!(
+            out,
+            "{:>12}{:>10}{:>10}{:>9}{:>10}{:>14}  {}",
+            f.churn,
+            format!("+{}", f.added),
+            format!("-{}", f.removed),
+            f.commits,
+            f.lines,
+            f.score,
+            f.path.display()
+        )?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn churn() -> Vec<FileChurn> {
+        vec![
+            FileChurn {
+                path: PathBuf::from("src/big, old.rs"),
+                lines: 500,
+                sloc: Some(400),
+                added: 120,
+                removed: 30,
+                commits: 12,
+                churn: 150,
+                score: 75_000,
+            },
+            FileChurn {
+                path: PathBuf::from("src/small.rs"),
+                lines: 10,
+                sloc: None,
+                added: 4,
+                removed: 0,
+                commits: 1,
+                churn: 4,
+                score: 40,
+            },
+        ]
+    }
+
+    #[test]
+    fn test_csv() {
+        let mut buf = Vec::new();
+        write_churn(&mut buf, &churn(), OutputFormat::Csv).unwrap();
+        let csv = String::from_utf8(buf).unwrap();
+        let lines: Vec<_> = csv.lines().collect();
+        assert_eq!(
+            lines,
+            [
+                "path,lines,sloc,added,removed,commits,churn,score",
+                "\"src/big, old.rs\",500,400,120,30,12,150,75000",
+                "src/small.rs,10,,4,0,1,4,40",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_json_and_table() {
+        let mut buf = Vec::new();
+        write_churn(&mut buf, &churn(), OutputFormat::Json).unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&buf).unwrap();
+        assert_eq!(json[0]["score"], 75_000);
+        assert!(json[1].get("sloc").is_none());
+
+        let mut buf = Vec::new();
+        write_churn(&mut buf, &churn(), OutputFormat::Table).unwrap();
+        let table = String::from_utf8(buf).unwrap();
+        let row = table.lines().nth(2).unwrap();
+        assert!(row.contains("+120"));
+        assert!(row.ends_with("  src/big, old.rs"));
+    }
+}
@@ -1,10 +1,16 @@
 // crates/cli/src/compare.rs
+use crate::config::Config;
 use crate::error::{AppError, Result};
-use count_lines_engine::stats::FileStats;
+use crate::github::{self, Annotation, Level};
+use crate::report::Snapshot;
+use count_lines_engine::git;
+use count_lines_engine::options::{CompareFormat, CompareSort};
+use count_lines_engine::stats::{FileStats, RunResult, Summary};
+use serde::Serialize;
 use std::collections::HashMap;
 use std::fs::File;
-use std::io::BufReader;
-use std::path::PathBuf;
+use std::io::{self, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
 
 /// Safely convert usize to isize, capping at `isize::MAX` to avoid wrap-around
 fn to_isize(value: usize) -> isize {
@@ -20,6 +26,7 @@ fn safe_diff(new_val: usize, old_val: usize) -> isize {
     }
 }
 
+#[derive(Debug, Default, Serialize)]
 pub struct ComparisonSummary {
     pub added_files: usize,
     pub removed_files: usize,
@@ -28,84 +35,325 @@ pub struct ComparisonSummary {
     pub diff_lines: isize,
     pub diff_chars: isize,
     pub diff_words: isize,
+    pub diff_sloc: isize,
 }
 
-pub enum FileDiff<'a> {
-    Added(&'a FileStats),
-    Removed(&'a FileStats),
-    Modified {
-        path: &'a PathBuf,
-        old_lines: usize,
-        new_lines: usize,
-        old_chars: usize,
-        new_chars: usize,
-    },
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChangeKind {
+    Added,
+    Removed,
+    Modified,
+    Unchanged,
+}
+
+impl ChangeKind {
+    const fn as_str(self) -> &'static str {
+        match self {
+            Self::Added => "added",
+            Self::Removed => "removed",
+            Self::Modified => "modified",
+            Self::Unchanged => "unchanged",
+        }
+    }
+}
+
+/// Per-file change between two snapshots.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FileDiff {
+    pub path: PathBuf,
+    pub status: ChangeKind,
+    pub old_lines: Option<usize>,
+    pub new_lines: Option<usize>,
+    /// Line delta (`new - old`).
+    pub lines: isize,
+    pub old_sloc: Option<usize>,
+    pub new_sloc: Option<usize>,
+    /// SLOC delta, if either side was counted with `--sloc`.
+    pub sloc: Option<isize>,
+    /// Character delta.
+    pub chars: isize,
+}
+
+impl FileDiff {
+    fn new(status: ChangeKind, old: Option<&FileStats>, new: Option<&FileStats>) -> Self {
+        let path = new.or(old).map(|s| s.path.clone()).unwrap_or_default();
+        let old_lines = old.map(|s| s.lines);
+        let new_lines = new.map(|s| s.lines);
+        let old_sloc = old.and_then(|s| s.sloc);
+        let new_sloc = new.and_then(|s| s.sloc);
+        let sloc = (old_sloc.is_some() || new_sloc.is_some())
+            .then(|| safe_diff(new_sloc.unwrap_or(0), old_sloc.unwrap_or(0)));
+
+        Self {
+            path,
+            status,
+            old_lines,
+            new_lines,
+            lines: safe_diff(new_lines.unwrap_or(0), old_lines.unwrap_or(0)),
+            old_sloc,
+            new_sloc,
+            sloc,
+            chars: safe_diff(new.map_or(0, |s| s.chars), old.map_or(0, |s| s.chars)),
+        }
+    }
+}
+
+/// Net change of one group (language or directory) between two snapshots.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct GroupDelta {
+    pub key: String,
+    pub files: isize,
+    pub lines: isize,
+    pub sloc: isize,
+}
+
+/// Full comparison between two snapshots, as rendered by `--compare-format`.
+#[derive(Debug, Serialize)]
+pub struct Comparison {
+    pub summary: ComparisonSummary,
+    pub files: Vec<FileDiff>,
+    pub by_language: Vec<GroupDelta>,
+    pub by_directory: Vec<GroupDelta>,
+    /// Totals of the new side, for `--fail-if`.
+    #[serde(skip)]
+    totals: Summary,
+    #[serde(skip)]
+    show_words: bool,
+    #[serde(skip)]
+    show_sloc: bool,
+}
+
+impl Comparison {
+    /// Compares two snapshots. Files are sorted by path.
+    #[must_use]
+    pub fn new(old_stats: &[FileStats], new_stats: &[FileStats]) -> Self {
+        let (files, summary) = compare_stats(old_stats, new_stats);
+        Self {
+            summary,
+            files,
+            by_language: group_deltas(old_stats, new_stats, language_key),
+            by_directory: group_deltas(old_stats, new_stats, directory_key),
+            totals: Summary::from_stats(new_stats),
+            show_words: old_stats.iter().any(|s| s.words.is_some())
+                && new_stats.iter().any(|s| s.words.is_some()),
+            show_sloc: old_stats.iter().chain(new_stats).any(|s| s.sloc.is_some()),
+        }
+    }
+
+    /// Returns the value of a `--fail-if` field: the changes, or the totals
+    /// of the new side.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn field(&self, name: &str) -> Option<f64> {
+        let summary = &self.summary;
+        let value = match name {
+            "files_added" => summary.added_files as f64,
+            "files_removed" => summary.removed_files as f64,
+            "files_modified" => summary.modified_files as f64,
+            "lines_delta" => summary.diff_lines as f64,
+            "chars_delta" => summary.diff_chars as f64,
+            "words_delta" => summary.diff_words as f64,
+            "sloc_delta" => summary.diff_sloc as f64,
+            _ => return self.totals.field(name),
+        };
+        Some(value)
+    }
+
+    /// Applies `--compare-sort` and `--compare-unchanged` to the file list.
+    fn arrange(&mut self, sort: CompareSort, include_unchanged: bool) {
+        if !include_unchanged {
+            self.files.retain(|d| d.status != ChangeKind::Unchanged);
+        }
+        if sort == CompareSort::Delta {
+            self.files.sort_by(|a, b| {
+                b.lines
+                    .unsigned_abs()
+                    .cmp(&a.lines.unsigned_abs())
+                    .then_with(|| {
+                        b.sloc
+                            .unwrap_or(0)
+                            .unsigned_abs()
+                            .cmp(&a.sloc.unwrap_or(0).unsigned_abs())
+                    })
+                    .then_with(|| a.path.cmp(&b.path))
+            });
+        }
+    }
 }
 
 /// Compares two snapshots.
 ///
 /// # Errors
 /// Returns an error if the files cannot be read or parsed.
-pub fn compare_snapshots(old_path: &PathBuf, new_path: &PathBuf) -> Result<()> {
+pub fn compare_snapshots(old_path: &PathBuf, new_path: &PathBuf, config: &Config) -> Result<()> {
     let old_stats = load_stats(old_path)?;
     let new_stats = load_stats(new_path)?;
 
-    let (diffs, summary) = compare_stats(&old_stats, &new_stats);
+    print_comparison(&old_stats, &new_stats, config)
+}
+
+/// Compares two git revisions (`A..B`, `B` defaulting to `HEAD`), or a
+/// revision against the working tree when no range is given.
+///
+/// # Errors
+/// Returns an error if a revision cannot be read or the output fails.
+pub fn compare_refs(spec: &str, config: &Config) -> Result<()> {
+    let (old_rev, new_rev) = match spec.split_once("..") {
+        Some((old, "")) => (old, Some("HEAD")),
+        Some((old, new)) => (old, Some(new)),
+        None => (spec, None),
+    };
 
-    print_comparison_results(&diffs, &summary, &old_stats, &new_stats);
+    let (old, new) = match new_rev {
+        Some(rev) => (
+            git::count_revision(config, old_rev)?,
+            git::count_revision(config, rev)?,
+        ),
+        None => (
+            git::count_checked_out_revision(config, old_rev)?,
+            git::count_working_tree(config)?,
+        ),
+    };
+    if old.sparse_skipped > 0 {
+        log::warn!(
+            "{} file(s) outside the sparse checkout skipped.",
+            old.sparse_skipped
+        );
+    }
+    for (path, err) in &new.errors {
+        log::warn!("Error processing {}: {err}", path.display());
+    }
 
-    Ok(())
+    let text_files = |result: RunResult| -> Vec<FileStats> {
+        result.stats.into_iter().filter(|s| !s.is_binary).collect()
+    };
+    print_comparison(&text_files(old), &text_files(new), config)
+}
+
+/// Compares two directory trees (`diff-dirs`), pairing files by their path
+/// relative to each directory: files only in `old_dir` show as removed,
+/// files only in `new_dir` as added.
+///
+/// # Errors
+/// Returns an error if a directory cannot be counted or the output fails.
+pub fn compare_dirs(old_dir: &Path, new_dir: &Path, config: &Config) -> Result<()> {
+    let old = count_dir(old_dir, config)?;
+    let new = count_dir(new_dir, config)?;
+    print_comparison(&old, &new, config)
 }
 
-fn compare_stats<'a>(
-    old_stats: &'a [FileStats],
-    new_stats: &'a [FileStats],
-) -> (Vec<FileDiff<'a>>, ComparisonSummary) {
-    let old_map: HashMap<PathBuf, &FileStats> =
-        old_stats.iter().map(|s| (s.path.clone(), s)).collect();
-    let new_map: HashMap<PathBuf, &FileStats> =
-        new_stats.iter().map(|s| (s.path.clone(), s)).collect();
+/// Counts the text files under `dir`, with paths relative to it.
+fn count_dir(dir: &Path, config: &Config) -> Result<Vec<FileStats>> {
+    let mut config = config.clone();
+    config.walk.roots = vec![dir.to_path_buf()];
+    let result = count_lines_engine::run(&config)?;
+    for (path, err) in &result.errors {
+        log::warn!("Error processing {}: {err}", path.display());
+    }
+    Ok(result
+        .stats
+        .into_iter()
+        .filter(|s| !s.is_binary)
+        .map(|mut s| {
+            if let Ok(relative) = s.path.strip_prefix(dir) {
+                s.path = relative.to_path_buf();
+            }
+            s
+        })
+        .collect())
+}
 
-    let mut diffs = Vec::new();
-    let mut summary = ComparisonSummary {
-        added_files: 0,
-        removed_files: 0,
-        modified_files: 0,
-        unchanged_files: 0,
-        diff_lines: 0,
-        diff_chars: 0,
-        diff_words: 0,
+/// Prints the comparison to stdout, or to `config.output` when set, then
+/// checks the `--fail-if` thresholds against it.
+///
+/// # Errors
+/// Returns an error if writing the output fails or a threshold is exceeded.
+pub fn print_comparison(
+    old_stats: &[FileStats],
+    new_stats: &[FileStats],
+    config: &Config,
+) -> Result<()> {
+    let mut comparison = Comparison::new(old_stats, new_stats);
+    comparison.arrange(config.compare_sort, config.compare_unchanged);
+    let checked = crate::threshold::check(config, &|name| comparison.field(name));
+
+    let write = |out: &mut dyn Write| -> io::Result<()> {
+        write_comparison(out, &comparison, config.compare_format)?;
+        if config.compare_format == CompareFormat::Github {
+            github::write_threshold_error(out, &checked)?;
+        }
+        Ok(())
     };
+    if let Some(path) = &config.output {
+        let mut out = BufWriter::new(File::create(path)?);
+        write(&mut out)?;
+        out.flush()?;
+    } else {
+        let stdout = io::stdout();
+        write(&mut stdout.lock())?;
+    }
+    checked
+}
 
-    // Check old entries (Modified and Removed)
+/// Writes the comparison in the given format.
+///
+/// # Errors
+/// Returns an error if writing to `out` fails.
+pub fn write_comparison(
+    out: &mut dyn Write,
+    comparison: &Comparison,
+    format: CompareFormat,
+) -> io::Result<()> {
+    match format {
+        CompareFormat::Table => write_table(out, comparison),
+        CompareFormat::Markdown => write_markdown(out, comparison),
+        CompareFormat::Json => {
+            let json = serde_json::to_string_pretty(comparison).map_err(io::Error::other)?;
+            writeln!(out, "{json}")
+        }
+        CompareFormat::Github => write_github(out, comparison),
+    }
+}
+
+fn compare_stats(
+    old_stats: &[FileStats],
+    new_stats: &[FileStats],
+) -> (Vec<FileDiff>, ComparisonSummary) {
+    let old_map: HashMap<&PathBuf, &FileStats> = old_stats.iter().map(|s| (&s.path, s)).collect();
+    let new_map: HashMap<&PathBuf, &FileStats> = new_stats.iter().map(|s| (&s.path, s)).collect();
+
+    let mut diffs = Vec::new();
+    let mut summary = ComparisonSummary::default();
+
+    // Check old entries (Modified, Unchanged and Removed)
     for (path, old_s) in &old_map {
         if let Some(new_s) = new_map.get(path) {
-            // Compare
-            if old_s.lines != new_s.lines
+            let changed = old_s.lines != new_s.lines
                 || old_s.chars != new_s.chars
                 || old_s.words != new_s.words
-            {
-                diffs.push(FileDiff::Modified {
-                    path: &old_s.path,
-                    old_lines: old_s.lines,
-                    new_lines: new_s.lines,
-                    old_chars: old_s.chars,
-                    new_chars: new_s.chars,
-                });
+                || old_s.sloc != new_s.sloc;
+            if changed {
+                diffs.push(FileDiff::new(
+                    ChangeKind::Modified,
+                    Some(old_s),
+                    Some(new_s),
+                ));
                 summary.modified_files += 1;
-                summary.diff_lines += safe_diff(new_s.lines, old_s.lines);
-                summary.diff_chars += safe_diff(new_s.chars, old_s.chars);
                 if let (Some(w1), Some(w2)) = (old_s.words, new_s.words) {
                     summary.diff_words += safe_diff(w2, w1);
                 }
             } else {
+                diffs.push(FileDiff::new(
+                    ChangeKind::Unchanged,
+                    Some(old_s),
+                    Some(new_s),
+                ));
                 summary.unchanged_files += 1;
             }
         } else {
-            diffs.push(FileDiff::Removed(old_s));
+            diffs.push(FileDiff::new(ChangeKind::Removed, Some(old_s), None));
             summary.removed_files += 1;
-            summary.diff_lines -= to_isize(old_s.lines);
-            summary.diff_chars -= to_isize(old_s.chars);
             if let Some(w) = old_s.words {
                 summary.diff_words -= to_isize(w);
             }
@@ -115,113 +363,277 @@ fn compare_stats<'a>(
     // Check new entries (Added)
     for (path, new_s) in &new_map {
         if !old_map.contains_key(path) {
-            diffs.push(FileDiff::Added(new_s));
+            diffs.push(FileDiff::new(ChangeKind::Added, None, Some(new_s)));
             summary.added_files += 1;
-            summary.diff_lines += to_isize(new_s.lines);
-            summary.diff_chars += to_isize(new_s.chars);
             if let Some(w) = new_s.words {
                 summary.diff_words += to_isize(w);
             }
         }
     }
 
+    for diff in &diffs {
+        summary.diff_lines += diff.lines;
+        summary.diff_chars += diff.chars;
+        summary.diff_sloc += diff.sloc.unwrap_or(0);
+    }
+
     // Sort by path for consistent output
-    diffs.sort_by(|a, b| {
-        let p1 = match a {
-            FileDiff::Added(s) | FileDiff::Removed(s) => &s.path,
-            FileDiff::Modified { path, .. } => path,
-        };
-        let p2 = match b {
-            FileDiff::Added(s) | FileDiff::Removed(s) => &s.path,
-            FileDiff::Modified { path, .. } => path,
-        };
-        p1.cmp(p2)
-    });
+    diffs.sort_by(|a, b| a.path.cmp(&b.path));
 
     (diffs, summary)
 }
 
-fn print_comparison_results(
-    diffs: &[FileDiff],
-    summary: &ComparisonSummary,
-    old_stats: &[FileStats],
-    new_stats: &[FileStats],
-) {
+fn fmt_opt(value: Option<usize>) -> String {
+    value.map_or_else(|| "-".to_string(), |v| v.to_string())
+}
+
+fn fmt_delta(value: Option<isize>) -> String {
+    value.map_or_else(|| "-".to_string(), |v| format!("{v:+}"))
+}
+
+/// A `::notice` per added, removed or modified file, then one with the
+/// totals.
+fn write_github(out: &mut dyn Write, comparison: &Comparison) -> io::Result<()> {
+    for d in &comparison.files {
+        let title = match d.status {
+            ChangeKind::Added => "Added file",
+            ChangeKind::Removed => "Removed file",
+            ChangeKind::Modified => "Modified file",
+            ChangeKind::Unchanged => continue,
+        };
+        let message = format!(
+            "{:+} lines ({} -> {})",
+            d.lines,
+            fmt_opt(d.old_lines),
+            fmt_opt(d.new_lines)
+        );
+        let annotation = Annotation::new(Level::Notice, title, &message).file(&d.path);
+        writeln!(out, "{annotation}")?;
+    }
+
+    let summary = &comparison.summary;
+    let mut message = format!(
+        "files +{} -{} ~{}, lines {:+}",
+        summary.added_files, summary.removed_files, summary.modified_files, summary.diff_lines
+    );
+    if comparison.show_sloc {
+        message.push_str(&format!(", SLOC {:+}", summary.diff_sloc));
+    }
+    writeln!(
+        out,
+        "{}",
+        Annotation::new(Level::Notice, "count_lines", &message)
+    )
+}
+
+fn write_table(out: &mut dyn Write, comparison: &Comparison) -> io::Result<()> {
+    let summary = &comparison.summary;
+
     // Print Summary
-    println!("Comparison Summary");
-    println!("-------------------");
-    println!(
+    writeln!(out, "Comparison Summary")?;
+    writeln!(out, "-------------------")?;
+    writeln!(
+        out,
         "Files: +{} -{} ~{} ({} unchanged)",
         summary.added_files, summary.removed_files, summary.modified_files, summary.unchanged_files
-    );
-    println!("Lines: {:+}", summary.diff_lines);
-    println!("Chars: {:+}", summary.diff_chars);
-
-    let show_words =
-        old_stats.iter().any(|s| s.words.is_some()) && new_stats.iter().any(|s| s.words.is_some());
-    if show_words {
-        println!("Words: {:+}", summary.diff_words);
+    )?;
+    writeln!(out, "Lines: {:+}", summary.diff_lines)?;
+    if comparison.show_sloc {
+        writeln!(out, "SLOC: {:+}", summary.diff_sloc)?;
+    }
+    writeln!(out, "Chars: {:+}", summary.diff_chars)?;
+    if comparison.show_words {
+        writeln!(out, "Words: {:+}", summary.diff_words)?;
     }
-    println!();
 
-    let mut added_sections = Vec::new();
-    let mut removed_sections = Vec::new();
-    let mut modified_sections = Vec::new();
+    if !comparison.files.is_empty() {
+        writeln!(out)?;
+        writeln!(
+            out,
+            "{:<10}{:>10}{:>10}{:>10}{:>10}  FILE",
+            "STATUS", "OLD", "NEW", "ΔLINES", "ΔSLOC"
+        )?;
+        writeln!(out, "{}", "-".repeat(58))?;
+        for d in &comparison.files {
+            writeln!(
+                out,
+                "{:<10}{:>10}{:>10}{:>10}{:>10}  {}",
+                d.status.as_str(),
+                fmt_opt(d.old_lines),
+                fmt_opt(d.new_lines),
+                format!("{:+}", d.lines),
+                fmt_delta(d.sloc),
+                d.path.display()
+            )?;
+        }
+    }
 
-    for diff in diffs {
-        match diff {
-            FileDiff::Added(s) => added_sections.push(s),
-            FileDiff::Removed(s) => removed_sections.push(s),
-            FileDiff::Modified { .. } => modified_sections.push(diff),
+    for (title, deltas) in [
+        ("By Language", &comparison.by_language),
+        ("By Directory", &comparison.by_directory),
+    ] {
+        if deltas.is_empty() {
+            continue;
+        }
+        writeln!(out)?;
+        writeln!(out, "### {title}")?;
+        for d in deltas {
+            writeln!(
+                out,
+                "~ {} (Files: {:+}, Lines: {:+}, SLOC: {:+})",
+                d.key, d.files, d.lines, d.sloc
+            )?;
         }
     }
+    Ok(())
+}
+
+fn write_markdown(out: &mut dyn Write, comparison: &Comparison) -> io::Result<()> {
+    let summary = &comparison.summary;
 
-    if !added_sections.is_empty() {
-        println!("### Added Files");
-        for s in added_sections {
-            println!("+ {} (L:{}, C:{})", s.path.display(), s.lines, s.chars);
+    writeln!(out, "### Comparison Summary")?;
+    writeln!(out)?;
+    writeln!(
+        out,
+        "| Added | Removed | Modified | Unchanged | Δ Lines | Δ SLOC | Δ Chars |"
+    )?;
+    writeln!(out, "|---:|---:|---:|---:|---:|---:|---:|")?;
+    writeln!(
+        out,
+        "| {} | {} | {} | {} | {:+} | {:+} | {:+} |",
+        summary.added_files,
+        summary.removed_files,
+        summary.modified_files,
+        summary.unchanged_files,
+        summary.diff_lines,
+        summary.diff_sloc,
+        summary.diff_chars
+    )?;
+
+    if !comparison.files.is_empty() {
+        writeln!(out)?;
+        writeln!(out, "### Files")?;
+        writeln!(out)?;
+        writeln!(
+            out,
+            "| Status | File | Old Lines | New Lines | Δ Lines | Δ SLOC |"
+        )?;
+        writeln!(out, "|:---|:---|---:|---:|---:|---:|")?;
+        for d in &comparison.files {
+            let path = d.path.display().to_string().replace('|', "\\|");
+            writeln!(
+                out,
+                "| {} | {path} | {} | {} | {:+} | {} |",
+                d.status.as_str(),
+                fmt_opt(d.old_lines),
+                fmt_opt(d.new_lines),
+                d.lines,
+                fmt_delta(d.sloc)
+            )?;
         }
-        println!();
     }
 
-    if !removed_sections.is_empty() {
-        println!("### Removed Files");
-        for s in removed_sections {
-            println!("- {} (L:{}, C:{})", s.path.display(), s.lines, s.chars);
+    for (title, column, deltas) in [
+        ("By Language", "Language", &comparison.by_language),
+        ("By Directory", "Directory", &comparison.by_directory),
+    ] {
+        if deltas.is_empty() {
+            continue;
         }
-        println!();
-    }
-
-    if !modified_sections.is_empty() {
-        println!("### Modified Files");
-        for diff in modified_sections {
-            if let FileDiff::Modified {
-                path,
-                old_lines,
-                new_lines,
-                old_chars,
-                new_chars,
-            } = diff
-            {
-                let dl = safe_diff(*new_lines, *old_lines);
-                let dc = safe_diff(*new_chars, *old_chars);
-                println!("~ {} (Lines: {:+}, Chars: {:+})", path.display(), dl, dc);
-            }
+        writeln!(out)?;
+        writeln!(out, "### {title}")?;
+        writeln!(out)?;
+        writeln!(out, "| {column} | Δ Files | Δ Lines | Δ SLOC |")?;
+        writeln!(out, "|:---|---:|---:|---:|")?;
+        for d in deltas {
+            writeln!(
+                out,
+                "| {} | {:+} | {:+} | {:+} |",
+                d.key.replace('|', "\\|"),
+                d.files,
+                d.lines,
+                d.sloc
+            )?;
         }
     }
+    Ok(())
+}
+
+/// Aggregates the per-group change between two snapshots.
+///
+/// Groups without any change are omitted; the result is sorted by key.
+pub fn group_deltas(
+    old_stats: &[FileStats],
+    new_stats: &[FileStats],
+    key: impl Fn(&FileStats) -> String,
+) -> Vec<GroupDelta> {
+    let mut groups: HashMap<String, GroupDelta> = HashMap::new();
+    let mut apply = |s: &FileStats, sign: isize| {
+        let k = key(s);
+        let delta = groups.entry(k.clone()).or_insert_with(|| GroupDelta {
+            key: k,
+            files: 0,
+            lines: 0,
+            sloc: 0,
+        });
+        delta.files += sign;
+        delta.lines += sign * to_isize(s.lines);
+        delta.sloc += sign * to_isize(s.sloc.unwrap_or(0));
+    };
+    old_stats.iter().for_each(|s| apply(s, -1));
+    new_stats.iter().for_each(|s| apply(s, 1));
+
+    let mut deltas: Vec<GroupDelta> = groups
+        .into_values()
+        .filter(|d| d.files != 0 || d.lines != 0 || d.sloc != 0)
+        .collect();
+    deltas.sort_by(|a, b| a.key.cmp(&b.key));
+    deltas
+}
+
+/// Groups files by lowercase extension.
+#[must_use]
+pub fn language_key(stats: &FileStats) -> String {
+    if stats.ext.is_empty() {
+        "(none)".to_string()
+    } else {
+        stats.ext.to_lowercase()
+    }
+}
+
+/// Groups files by their parent directory.
+#[must_use]
+pub fn directory_key(stats: &FileStats) -> String {
+    stats
+        .path
+        .parent()
+        .map(|p| p.display().to_string())
+        .filter(|p| !p.is_empty())
+        .unwrap_or_else(|| ".".to_string())
 }
 
-fn load_stats(path: &PathBuf) -> Result<Vec<FileStats>> {
+/// Loads the file statistics from a JSON snapshot.
+///
+/// # Errors
+/// Returns an error if the file cannot be read or parsed.
+pub fn load_stats(path: &PathBuf) -> Result<Vec<FileStats>> {
     let file = File::open(path).map_err(AppError::Io)?;
     let reader = BufReader::new(file);
-    let stats: Vec<FileStats> = serde_json::from_reader(reader)?;
-    Ok(stats)
+    let snapshot: Snapshot = serde_json::from_reader(reader)?;
+    Ok(snapshot.into_files())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn changed(diffs: &[FileDiff]) -> Vec<&FileDiff> {
+        diffs
+            .iter()
+            .filter(|d| d.status != ChangeKind::Unchanged)
+            .collect()
+    }
+
     #[test]
     fn test_compare_identical() {
         let stats = vec![FileStats {
@@ -230,7 +642,7 @@ mod tests {
             ..Default::default()
         }];
         let (diffs, summary) = compare_stats(&stats, &stats);
-        assert!(diffs.is_empty());
+        assert!(changed(&diffs).is_empty());
         assert_eq!(summary.added_files, 0);
         assert_eq!(summary.removed_files, 0);
         assert_eq!(summary.modified_files, 0);
@@ -248,10 +660,8 @@ mod tests {
         }];
         let (diffs, summary) = compare_stats(&old, &new);
         assert_eq!(diffs.len(), 1);
-        match &diffs[0] {
-            FileDiff::Added(s) => assert_eq!(s.lines, 10),
-            _ => panic!("Expected Added"),
-        }
+        assert_eq!(diffs[0].status, ChangeKind::Added);
+        assert_eq!(diffs[0].new_lines, Some(10));
         assert_eq!(summary.added_files, 1);
         assert_eq!(summary.diff_lines, 10);
     }
@@ -266,14 +676,77 @@ mod tests {
         let new = vec![];
         let (diffs, summary) = compare_stats(&old, &new);
         assert_eq!(diffs.len(), 1);
-        match &diffs[0] {
-            FileDiff::Removed(s) => assert_eq!(s.lines, 10),
-            _ => panic!("Expected Removed"),
-        }
+        assert_eq!(diffs[0].status, ChangeKind::Removed);
+        assert_eq!(diffs[0].old_lines, Some(10));
         assert_eq!(summary.removed_files, 1);
         assert_eq!(summary.diff_lines, -10);
     }
 
+    #[test]
+    fn test_count_dir_pairs_by_relative_path() {
+        let (a, b) = (tempfile::tempdir().unwrap(), tempfile::tempdir().unwrap());
+        for dir in [a.path(), b.path()] {
+            std::fs::create_dir(dir.join("src")).unwrap();
+            std::fs::write(dir.join("src/same.rs"), "fn same() {}\n").unwrap();
+        }
+        std::fs::write(a.path().join("src/gen.rs"), "a\nb\n").unwrap();
+        std::fs::write(b.path().join("src/gen.rs"), "a\nb\nc\n").unwrap();
+        std::fs::write(a.path().join("only_a.txt"), "a\n").unwrap();
+        std::fs::write(b.path().join("only_b.txt"), "b\n").unwrap();
+
+        let config = Config::default();
+        let old = count_dir(a.path(), &config).unwrap();
+        let new = count_dir(b.path(), &config).unwrap();
+        let (diffs, summary) = compare_stats(&old, &new);
+        let mut changes: Vec<_> = changed(&diffs)
+            .iter()
+            .map(|d| (d.path.clone(), d.status))
+            .collect();
+        changes.sort_by(|x, y| x.0.cmp(&y.0));
+        assert_eq!(
+            changes,
+            [
+                (PathBuf::from("only_a.txt"), ChangeKind::Removed),
+                (PathBuf::from("only_b.txt"), ChangeKind::Added),
+                (PathBuf::from("src/gen.rs"), ChangeKind::Modified),
+            ]
+        );
+        assert_eq!(summary.unchanged_files, 1);
+        assert_eq!(summary.diff_lines, 1);
+    }
+
+    #[test]
+    fn test_group_deltas() {
+        let file = |path: &str, lines: usize| FileStats {
+            lines,
+            sloc: Some(lines),
+            ..FileStats::new(PathBuf::from(path))
+        };
+        let old = vec![file("src/a.rs", 10), file("src/b.py", 5)];
+        let new = vec![
+            file("src/a.rs", 12),
+            file("src/b.py", 5),
+            file("tests/c.rs", 3),
+        ];
+
+        let by_lang = group_deltas(&old, &new, language_key);
+        assert_eq!(
+            by_lang,
+            vec![GroupDelta {
+                key: "rs".to_string(),
+                files: 1,
+                lines: 5,
+                sloc: 5,
+            }]
+        );
+
+        let by_dir = group_deltas(&old, &new, directory_key);
+        let keys: Vec<_> = by_dir.iter().map(|d| d.key.as_str()).collect();
+        assert_eq!(keys, ["src", "tests"]);
+        assert_eq!(by_dir[0].lines, 2);
+        assert_eq!(by_dir[1].files, 1);
+    }
+
     #[test]
     fn test_compare_modified() {
         let old = vec![FileStats {
@@ -288,19 +761,92 @@ mod tests {
         }];
         let (diffs, summary) = compare_stats(&old, &new);
         assert_eq!(diffs.len(), 1);
-        match &diffs[0] {
-            FileDiff::Modified {
-                path: _,
-                old_lines,
-                new_lines,
-                ..
-            } => {
-                assert_eq!(*old_lines, 10);
-                assert_eq!(*new_lines, 15);
-            }
-            _ => panic!("Expected Modified"),
-        }
+        assert_eq!(diffs[0].status, ChangeKind::Modified);
+        assert_eq!(diffs[0].old_lines, Some(10));
+        assert_eq!(diffs[0].new_lines, Some(15));
         assert_eq!(summary.modified_files, 1);
         assert_eq!(summary.diff_lines, 5);
     }
+
+    fn sample() -> (Vec<FileStats>, Vec<FileStats>) {
+        let file = |path: &str, lines: usize, sloc: usize| FileStats {
+            lines,
+            sloc: Some(sloc),
+            ..FileStats::new(PathBuf::from(path))
+        };
+        (
+            vec![file("a.rs", 10, 8), file("b.rs", 5, 5), file("c.rs", 1, 1)],
+            vec![
+                file("a.rs", 12, 9),
+                file("b.rs", 5, 5),
+                file("d|e.rs", 30, 20),
+            ],
+        )
+    }
+
+    #[test]
+    fn test_sort_by_delta_and_hide_unchanged() {
+        let (old, new) = sample();
+        let mut comparison = Comparison::new(&old, &new);
+        comparison.arrange(CompareSort::Delta, false);
+
+        let paths: Vec<_> = comparison.files.iter().map(|d| d.path.clone()).collect();
+        assert_eq!(
+            paths,
+            [
+                PathBuf::from("d|e.rs"),
+                PathBuf::from("a.rs"),
+                PathBuf::from("c.rs")
+            ]
+        );
+        assert_eq!(comparison.summary.diff_sloc, 1 + 20 - 1);
+    }
+
+    #[test]
+    fn test_threshold_fields() {
+        let (old, new) = sample();
+        let comparison = Comparison::new(&old, &new);
+        assert_eq!(comparison.field("sloc_delta"), Some(20.0));
+        assert_eq!(comparison.field("files_removed"), Some(1.0));
+        assert_eq!(comparison.field("total_lines"), Some(47.0));
+        assert_eq!(comparison.field("lines"), None);
+    }
+
+    #[test]
+    fn test_markdown_and_json_output() {
+        let (old, new) = sample();
+        let mut comparison = Comparison::new(&old, &new);
+        comparison.arrange(CompareSort::Path, true);
+
+        let mut buf = Vec::new();
+        write_comparison(&mut buf, &comparison, CompareFormat::Markdown).unwrap();
+        let md = String::from_utf8(buf).unwrap();
+        assert!(md.contains("| modified | a.rs | 10 | 12 | +2 | +1 |"));
+        assert!(md.contains("| unchanged | b.rs | 5 | 5 | +0 | +0 |"));
+        assert!(md.contains("| added | d\\|e.rs | - | 30 | +30 | +20 |"));
+
+        let mut buf = Vec::new();
+        write_comparison(&mut buf, &comparison, CompareFormat::Json).unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&buf).unwrap();
+        assert_eq!(json["summary"]["added_files"], 1);
+        assert_eq!(json["files"][2]["status"], "removed");
+        assert_eq!(json["files"][2]["lines"], -1);
+        assert_eq!(json["by_language"][0]["key"], "rs");
+    }
+
+    #[test]
+    fn test_github_output() {
+        let (old, new) = sample();
+        let mut comparison = Comparison::new(&old, &new);
+        comparison.arrange(CompareSort::Path, true);
+
+        let mut buf = Vec::new();
+        write_comparison(&mut buf, &comparison, CompareFormat::Github).unwrap();
+        let out = String::from_utf8(buf).unwrap();
+        let lines: Vec<_> = out.lines().collect();
+        assert_eq!(lines.len(), 4, "{out}");
+        assert!(lines.contains(&"::notice file=a.rs,title=Modified file::+2 lines (10 -> 12)"));
+        assert!(lines.contains(&"::notice file=d|e.rs,title=Added file::+30 lines (- -> 30)"));
+        assert!(lines[3].starts_with("::notice title=count_lines::files +1 -1 ~1, lines +31"));
+    }
 }
@@ -0,0 +1,84 @@
+// crates/cli/src/completions.rs
+//! `count_lines completions <SHELL>`: writes the completion script
+//! `clap_complete` generates from the clap definition of the arguments, so
+//! new flags are picked up without touching this module.
+
+use crate::args::Args;
+use crate::options::Shell;
+use clap::CommandFactory;
+use std::io::{self, Write};
+
+/// Name the scripts complete.
+const BIN: &str = "count_lines";
+
+impl From<Shell> for clap_complete::Shell {
+    fn from(shell: Shell) -> Self {
+        match shell {
+            Shell::Bash => Self::Bash,
+            Shell::Zsh => Self::Zsh,
+            Shell::Fish => Self::Fish,
+            Shell::Powershell => Self::PowerShell,
+        }
+    }
+}
+
+/// Writes the completion script for `shell` to stdout.
+///
+/// # Errors
+/// Returns an error if writing to stdout fails.
+pub fn print(shell: Shell) -> io::Result<()> {
+    let stdout = io::stdout();
+    let mut out = stdout.lock();
+    out.write_all(generate(shell).as_bytes())
+}
+
+/// The completion script for `shell`.
+#[must_use]
+pub fn generate(shell: Shell) -> String {
+    let mut script = Vec::new();
+    clap_complete::generate(
+        clap_complete::Shell::from(shell),
+        &mut Args::command(),
+        BIN,
+        &mut script,
+    );
+    String::from_utf8_lossy(&script).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scripts_complete_values() {
+        let bash = generate(Shell::Bash);
+        assert!(bash.contains("--format)"));
+        assert!(bash.contains("table csv tsv json"));
+        assert!(bash.contains("count_lines__subcmd__snapshot"));
+        assert!(bash.contains("complete -F _count_lines"));
+        // `--sort` and `--columns` complete their names, not file names.
+        let sort = bash
+            .lines()
+            .skip_while(|l| l.trim() != "--sort)")
+            .nth(1)
+            .unwrap();
+        assert!(sort.contains("lines chars words"), "{sort}");
+        assert!(bash.contains("path name ext lines"));
+
+        let zsh = generate(Shell::Zsh);
+        assert!(zsh.starts_with("#compdef count_lines\n"));
+        assert!(zsh.contains("--by="));
+        assert!(zsh.contains("maxdepth"));
+
+        let fish = generate(Shell::Fish);
+        assert!(fish.contains("-l format"));
+        assert!(fish.contains("bash"));
+        assert!(
+            fish.lines()
+                .any(|l| l.contains("-l sort ") && l.contains("-r -f -a \"lines"))
+        );
+
+        let powershell = generate(Shell::Powershell);
+        assert!(powershell.contains("'count_lines;completions'"));
+    }
+}
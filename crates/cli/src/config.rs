@@ -1,10 +1,15 @@
 // crates/cli/src/config.rs
 use crate::args::Args;
 use crate::options::{self, SortKey};
+use crate::parsers::PatternFileArg;
 pub use count_lines_engine::config::{
     Config, ConfigBuilder, FilterConfig, FilterConfigBuilder, WalkOptions, WalkOptionsBuilder,
 };
-use count_lines_engine::options as engine_options;
+use count_lines_engine::git::{ChurnOptions, HistoryOptions};
+use count_lines_engine::io_limit::IoLimit;
+use count_lines_engine::options::{self as engine_options, Column};
+use count_lines_engine::plugin::PluginLimits;
+use count_lines_engine::test_code::TestSplitOptions;
 use std::time::Duration;
 
 impl From<Args> for Config {
@@ -13,6 +18,12 @@ impl From<Args> for Config {
         let count_words = args.filter.words
             || args.filter.min_words.is_some()
             || args.filter.max_words.is_some()
+            || args.filter.filter.iter().any(|e| e.uses("words"))
+            || args.behavior.fail_if.iter().any(|t| t.uses("words"))
+            || args.output.group_filter.iter().any(|e| e.uses("words"))
+            || args.output.ratio_of == Some(options::RatioMetric::Words)
+            || badge_uses(&args, options::RatioMetric::Words)
+            || has_column(&args, Column::Words)
             || args
                 .output
                 .sort
@@ -20,7 +31,35 @@ impl From<Args> for Config {
                 .iter()
                 .any(|(k, _)| matches!(k, SortKey::Words));
 
+        let count_docs = args.filter.docs
+            || args.filter.filter.iter().any(|e| e.uses("doc"))
+            || has_column(&args, Column::Doc)
+            || has_column(&args, Column::DocDensity);
+
+        let count_indent = args.filter.indent
+            || args
+                .filter
+                .filter
+                .iter()
+                .any(|e| e.uses("maxdepth") || e.uses("avgdepth"))
+            || has_column(&args, Column::MaxDepth)
+            || has_column(&args, Column::AvgDepth)
+            || args
+                .output
+                .sort
+                .0
+                .iter()
+                .any(|(k, _)| matches!(k, SortKey::MaxDepth | SortKey::AvgDepth));
+
         let count_sloc = args.filter.sloc
+            || count_docs
+            || args.output.oneline
+            || args.filter.filter.iter().any(|e| e.uses("sloc"))
+            || args.behavior.fail_if.iter().any(|t| t.uses("sloc"))
+            || args.output.group_filter.iter().any(|e| e.uses("sloc"))
+            || args.output.ratio_of == Some(options::RatioMetric::Sloc)
+            || badge_uses(&args, options::RatioMetric::Sloc)
+            || has_column(&args, Column::Sloc)
             || args
                 .output
                 .sort
@@ -31,6 +70,13 @@ impl From<Args> for Config {
         let walk = walk_options_from_args(&args);
         let filter = filter_config_from_args(&args);
 
+        let mut age_buckets = args.output.age_buckets.clone();
+        age_buckets.sort();
+        age_buckets.dedup();
+        if age_buckets.is_empty() {
+            age_buckets = Config::default_age_buckets();
+        }
+
         // Handle compare tuple
         let compare = args
             .comparison
@@ -39,6 +85,20 @@ impl From<Args> for Config {
             .filter(|files| files.len() == 2)
             .map(|files| (files[0].clone(), files[1].clone()));
 
+        let history = (args.comparison.history.is_some()
+            || args.comparison.history_since.is_some())
+        .then(|| HistoryOptions {
+            max_count: args.comparison.history,
+            since: args.comparison.history_since.map(|d| d.0),
+            step: args.comparison.history_step,
+        });
+        let churn = args.comparison.churn.then(|| ChurnOptions {
+            since: args.comparison.since.map(|d| d.0),
+        });
+        let test_split = args.output.test_split.then(|| TestSplitOptions {
+            patterns: merge_patterns(&args.output.test_pattern, &args.output.test_patterns_from),
+        });
+
         // Convert enums via From impls
         let format: engine_options::OutputFormat = args.output.format.into();
         let watch_output: engine_options::WatchOutput = args.behavior.watch_output.into();
@@ -57,16 +117,148 @@ impl From<Args> for Config {
             .sort(sort)
             .total_row(args.output.total_row)
             .count_newlines_in_chars(args.output.count_newlines_in_chars)
-            .progress(args.output.progress)
+            .progress(args.output.progress && !args.output.quiet)
+            .output(args.output.output.clone())
+            .output_db(args.output.output_db.clone())
+            .relative_to(
+                args.output
+                    .relative_to
+                    .as_deref()
+                    .map(|dir| std::path::absolute(dir).unwrap_or_else(|_| dir.to_path_buf())),
+            )
+            .group_by(
+                args.output
+                    .by
+                    .iter()
+                    .copied()
+                    .map(engine_options::GroupBy::from)
+                    .collect::<Vec<_>>(),
+            )
+            .group_filter(args.output.group_filter.clone())
+            .age_buckets(age_buckets)
+            .codeowners(args.output.codeowners.clone())
+            .top(args.output.top)
+            .top_dirs(args.output.top_dirs)
+            .columns(match &args.output.columns {
+                Some(columns) => columns.0.clone(),
+                None if count_docs || count_indent => {
+                    Column::metric_defaults(count_docs, count_indent)
+                }
+                None => Vec::new(),
+            })
+            .human_numbers(args.output.human)
+            .thousands_separator(
+                args.output.thousands.clone().map(|sep| {
+                    sep.unwrap_or_else(|| crate::numbers::locale_separator().to_string())
+                }),
+            )
+            .raw_bytes(args.output.bytes)
+            .color(crate::style::use_color(
+                args.output.color,
+                args.output.output.is_some(),
+            ))
+            .theme(engine_options::Theme::from(args.output.theme))
+            .highlight_over(args.output.highlight_over)
+            .distribution(args.output.distribution)
+            .ratio_of(args.output.ratio_of.map(engine_options::RatioMetric::from))
+            .ratio_base(engine_options::RatioBase::from(args.output.ratio_base))
+            .detect_similar(args.output.detect_similar)
+            .audit_encoding(args.output.audit_encoding)
+            .license_header(args.output.license_header.clone())
+            .tree_depth(args.output.tree_depth)
+            .markdown_badges(args.output.markdown_badges)
+            .with_metadata(args.output.with_metadata)
+            .timings(args.output.timings)
+            .trend(args.output.trend)
+            .badge(args.output.badge.clone().map(|spec| {
+                engine_options::Badge {
+                    metric: spec.metric.into(),
+                    language: spec.language,
+                    colors: args
+                        .output
+                        .badge_colors
+                        .clone()
+                        .map(|c| c.0)
+                        .unwrap_or_default(),
+                }
+            }))
             .count_words(count_words)
             .count_sloc(count_sloc)
+            .count_docs(count_docs)
+            .count_indent(count_indent)
+            .tab_width(args.filter.tab_width)
+            .languages(
+                args.filter
+                    .language_def
+                    .iter()
+                    .flat_map(|arg| arg.0.clone())
+                    .collect::<Vec<_>>(),
+            )
+            .detect_modeline(args.filter.detect_modeline)
+            .plugins(args.filter.plugin.clone())
+            .plugin_limits(PluginLimits {
+                timeout: args.filter.plugin_timeout,
+                memory: args.filter.plugin_memory.map(|s| s.0),
+            })
+            .encoding(args.filter.encoding)
+            .include_minified(args.filter.include_minified)
+            .stdin(args.scan.stdin.then(|| {
+                args.scan
+                    .stdin_name
+                    .clone()
+                    .unwrap_or_else(|| std::path::PathBuf::from("-"))
+            }))
+            .why(args.behavior.why.clone())
+            .list_only(args.behavior.list_only)
+            .null_separated(args.behavior.print0)
+            .errors_only(args.behavior.errors_only)
+            .quiet(args.output.quiet)
+            .oneline(args.output.oneline)
+            .max_in_flight(args.scan.max_in_flight)
+            .io_limit(IoLimit::new(args.scan.io_concurrency))
+            .chunk_size(args.scan.chunk_size.map(|s| s.0))
+            .long_line_threshold(
+                args.scan
+                    .long_line_threshold
+                    .map_or(Config::DEFAULT_LONG_LINE_THRESHOLD, |s| s.0),
+            )
+            .max_files(args.scan.max_files)
+            .max_total_bytes(args.scan.max_total_bytes.map(|s| s.0))
+            .on_limit(engine_options::LimitAction::from(args.scan.on_limit))
+            .file_timeout(args.scan.file_timeout)
+            .timeout(args.scan.timeout)
+            .fail_if(args.behavior.fail_if.clone())
             .strict(args.behavior.strict)
             .watch(args.behavior.watch)
             .watch_interval(Duration::from_secs(
                 args.behavior.watch_interval.unwrap_or(1),
             ))
+            .watch_debounce(Duration::from_millis(
+                args.behavior.watch_debounce.unwrap_or(200),
+            ))
             .watch_output(watch_output)
+            .watch_tui(args.behavior.tui)
+            .serve_metrics(args.behavior.serve_metrics)
+            .metrics_interval(Duration::from_secs(
+                args.behavior.metrics_interval.unwrap_or(60),
+            ))
             .compare(compare)
+            .history(history)
+            .churn(churn)
+            .test_split(test_split)
+            .audit_whitespace(args.output.audit_whitespace)
+            .compare_ref(args.comparison.compare_ref.clone())
+            .compare_format(engine_options::CompareFormat::from(
+                args.comparison.compare_format,
+            ))
+            .compare_sort(engine_options::CompareSort::from(
+                args.comparison.compare_sort,
+            ))
+            .compare_unchanged(args.comparison.compare_unchanged)
+            .cache_dir(args.behavior.cache_dir.clone())
+            .incremental(args.behavior.incremental)
+            .cache_key(engine_options::CacheKey::from(args.behavior.cache_key))
+            .shared_cache(args.behavior.shared_cache)
             .build()
             .expect("Failed to build config")
     }
@@ -94,12 +286,39 @@ fn walk_options_from_args(args: &Args) -> WalkOptions {
         .git_ignore(!scan.no_gitignore)
         .max_depth(scan.max_depth)
         .follow_links(scan.follow)
+        .follow_junctions(scan.follow_junctions)
+        .include_submodules(scan.include_submodules)
         .override_include(scan.override_include.clone())
         .override_exclude(scan.override_exclude.clone())
+        .scan_archives(scan.scan_archives)
+        .include_special(scan.include_special)
+        .report_links(scan.report_links)
+        // `--by linguist` needs the classes even without `--linguist`.
+        .linguist(match scan.linguist {
+            Some(mode) => Some(engine_options::LinguistMode::from(mode)),
+            None if args.output.by.contains(&options::GroupBy::Linguist) => {
+                Some(engine_options::LinguistMode::Tag)
+            }
+            None => None,
+        })
         .build()
         .expect("Failed to build walk options")
 }
 
+fn badge_uses(args: &Args, metric: options::RatioMetric) -> bool {
+    args.output
+        .badge
+        .as_ref()
+        .is_some_and(|b| b.metric == metric)
+}
+
+fn has_column(args: &Args, column: Column) -> bool {
+    args.output
+        .columns
+        .as_ref()
+        .is_some_and(|c| c.0.contains(&column))
+}
+
 fn filter_config_from_args(args: &Args) -> FilterConfig {
     let opts = &args.filter;
     let map_ext: hashbrown::HashMap<String, String> = opts.map_ext.clone().into_iter().collect();
@@ -116,13 +335,24 @@ fn filter_config_from_args(args: &Args) -> FilterConfig {
         .max_size(opts.max_size.map(|s| s.0))
         .mtime_since(opts.mtime_since.map(|d| d.0))
         .mtime_until(opts.mtime_until.map(|d| d.0))
-        .include_patterns(opts.include.clone())
-        .exclude_patterns(opts.exclude.clone())
+        .include_patterns(merge_patterns(&opts.include, &opts.include_from))
+        .exclude_patterns(merge_patterns(&opts.exclude, &opts.exclude_from))
+        .glob_case_insensitive(opts.glob_icase)
         .map_ext(map_ext)
+        .expressions(opts.filter.clone())
         .build()
         .expect("Failed to build filter config")
 }
 
+/// Command-line patterns followed by those read from pattern files.
+fn merge_patterns(patterns: &[String], files: &[PatternFileArg]) -> Vec<String> {
+    patterns
+        .iter()
+        .chain(files.iter().flat_map(|f| &f.0))
+        .cloned()
+        .collect()
+}
+
 // From trait implementations for CLI -> Engine enum conversion
 
 macro_rules! map_enum {
@@ -146,7 +376,50 @@ map_enum!(
     Json,
     Yaml,
     Md,
-    Jsonl
+    Markdown,
+    Jsonl,
+    Tree,
+    Github,
+    Sarif
+);
+map_enum!(
+    options::GroupBy,
+    engine_options::GroupBy,
+    Language,
+    Ext,
+    Linguist,
+    Age,
+    RootDir,
+    Crate,
+    Owner,
+    Submodule,
+    Root,
+    Author
+);
+map_enum!(
+    options::RatioMetric,
+    engine_options::RatioMetric,
+    Files,
+    Lines,
+    Sloc,
+    Chars,
+    Words,
+    Size
+);
+map_enum!(options::RatioBase, engine_options::RatioBase, Total, Group);
+map_enum!(
+    options::LimitAction,
+    engine_options::LimitAction,
+    Abort,
+    Truncate
+);
+map_enum!(options::Theme, engine_options::Theme, Default, Light, Mono);
+map_enum!(options::CacheKey, engine_options::CacheKey, Mtime, Hash);
+map_enum!(
+    options::LinguistMode,
+    engine_options::LinguistMode,
+    Exclude,
+    Tag
 );
 map_enum!(
     options::WatchOutput,
@@ -154,6 +427,20 @@ map_enum!(
     Full,
     Jsonl
 );
+map_enum!(
+    options::CompareFormat,
+    engine_options::CompareFormat,
+    Table,
+    Json,
+    Markdown,
+    Github
+);
+map_enum!(
+    options::CompareSort,
+    engine_options::CompareSort,
+    Path,
+    Delta
+);
 map_enum!(
     options::SortKey,
     engine_options::SortKey,
@@ -163,5 +450,11 @@ map_enum!(
     Size,
     Name,
     Ext,
-    Sloc
+    Sloc,
+    MaxLine,
+    AvgLine,
+    Path,
+    Ratio,
+    MaxDepth,
+    AvgDepth
 );
@@ -0,0 +1,317 @@
+// crates/cli/src/daemon.rs
+//! `count_lines daemon`: watches the workspace and answers JSON-RPC 2.0
+//! requests on stdin, one JSON object per line, for editor status bars.
+//!
+//! Methods:
+//! - `summary`: totals and per-language totals of the workspace
+//! - `file` (`{"path": ..., "text"?: ...}`): counts of one file, or of the
+//!   unsaved `text` of its buffer
+//! - `shutdown`: replies and exits
+//!
+//! After every watch cycle a `summaryChanged` notification carries the new
+//! summary. Recounts go through the incremental cache, as in `--watch`.
+
+use crate::args::DaemonArgs;
+use crate::config::Config;
+use crate::error::Result;
+use count_lines_engine::grouping::group_stats;
+use count_lines_engine::options::GroupBy;
+use count_lines_engine::processor::count_content;
+use count_lines_engine::stats::{FileStats, RunResult, Summary};
+use count_lines_engine::watch::{self, Changes};
+use serde_json::{Value, json};
+use std::io::{self, BufRead, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, PoisonError, RwLock};
+
+/// JSON-RPC error codes.
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+const INTERNAL_ERROR: i64 = -32603;
+
+/// The latest count of the workspace, as answered to requests.
+#[derive(Default)]
+struct State {
+    stats: Vec<FileStats>,
+    errors: usize,
+    /// Number of finished watch cycles; 0 until the first count is done.
+    cycle: u64,
+}
+
+/// The workspace count shared by the watcher and the request loop.
+pub struct Daemon {
+    config: Config,
+    /// The result the watch cycles update; requests read [`State`] instead,
+    /// so they aren't held up by a recount.
+    result: Mutex<RunResult>,
+    state: RwLock<State>,
+}
+
+impl Daemon {
+    /// Creates a daemon for the walk roots of `config`; nothing is counted
+    /// until the first [`apply`](Self::apply).
+    #[must_use]
+    pub fn new(config: Config) -> Self {
+        Self {
+            config,
+            result: Mutex::new(RunResult::default()),
+            state: RwLock::new(State::default()),
+        }
+    }
+
+    /// Applies the changes of one watch cycle.
+    ///
+    /// # Errors
+    /// Returns an error if a full recount fails; the previous count is kept.
+    pub fn apply(&self, changes: &Changes) -> Result<()> {
+        let mut result = self.result.lock().unwrap_or_else(PoisonError::into_inner);
+        watch::apply_changes(&mut result, changes, &self.config)?;
+        let stats = result
+            .stats
+            .iter()
+            .filter(|s| !s.is_binary)
+            .cloned()
+            .collect();
+        let errors = result.error_count();
+        drop(result);
+
+        let mut state = self.state.write().unwrap_or_else(PoisonError::into_inner);
+        state.stats = stats;
+        state.errors = errors;
+        state.cycle += 1;
+        Ok(())
+    }
+
+    /// Answers one request line. Notifications (requests without an `id`)
+    /// get no reply.
+    #[must_use]
+    pub fn handle(&self, line: &str) -> Option<Value> {
+        let request: Value = match serde_json::from_str(line) {
+            Ok(request) => request,
+            Err(e) => return Some(failure(&Value::Null, PARSE_ERROR, &e.to_string())),
+        };
+        let id = request.get("id").cloned();
+        let Some(method) = request.get("method").and_then(Value::as_str) else {
+            let id = id.unwrap_or(Value::Null);
+            return Some(failure(&id, INVALID_REQUEST, "missing method"));
+        };
+        let params = request.get("params").unwrap_or(&Value::Null);
+
+        let outcome = match method {
+            "summary" => Ok(self.summary()),
+            "file" => self.file(params),
+            "shutdown" => Ok(Value::Null),
+            _ => Err((METHOD_NOT_FOUND, format!("unknown method: {method}"))),
+        };
+        let id = id?;
+        Some(match outcome {
+            Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+            Err((code, message)) => failure(&id, code, &message),
+        })
+    }
+
+    /// Totals and per-language totals of the current count.
+    #[must_use]
+    pub fn summary(&self) -> Value {
+        let state = self.state.read().unwrap_or_else(PoisonError::into_inner);
+        json!({
+            "ready": state.cycle > 0,
+            "cycle": state.cycle,
+            "summary": Summary::from_stats(&state.stats),
+            "by_language": group_stats(&state.stats, GroupBy::Language, &self.config),
+            "errors": state.errors,
+        })
+    }
+
+    /// Counts of the file at `params.path`: of `params.text` when given,
+    /// otherwise from the current count (`null` if it isn't counted).
+    fn file(&self, params: &Value) -> std::result::Result<Value, (i64, String)> {
+        let Some(path) = params.get("path").and_then(Value::as_str) else {
+            return Err((INVALID_PARAMS, "missing path".to_string()));
+        };
+        if let Some(text) = params.get("text").and_then(Value::as_str) {
+            let stats = count_content(PathBuf::from(path), text.as_bytes(), &self.config)
+                .map_err(|e| (INTERNAL_ERROR, e.to_string()))?;
+            return serde_json::to_value(stats).map_err(|e| (INVALID_PARAMS, e.to_string()));
+        }
+
+        let path = absolute(Path::new(path));
+        let state = self.state.read().unwrap_or_else(PoisonError::into_inner);
+        let stats = state.stats.iter().find(|s| s.path == path);
+        serde_json::to_value(stats).map_err(|e| (INVALID_PARAMS, e.to_string()))
+    }
+}
+
+fn failure(id: &Value, code: i64, message: &str) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": { "code": code, "message": message },
+    })
+}
+
+/// `path` made absolute and resolved, as the walk spells canonical roots.
+fn absolute(path: &Path) -> PathBuf {
+    std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+}
+
+fn write_line(out: &Mutex<io::Stdout>, value: &Value) -> io::Result<()> {
+    let mut out = out.lock().unwrap_or_else(PoisonError::into_inner);
+    writeln!(out, "{value}")?;
+    out.flush()
+}
+
+/// Watches `args.paths` and serves requests from stdin until `shutdown` or
+/// the end of the input.
+///
+/// # Errors
+/// Returns an error if reading stdin or writing stdout fails.
+pub fn run(args: DaemonArgs, mut config: Config) -> Result<()> {
+    if !args.paths.is_empty() {
+        config.walk.roots = args.paths;
+    }
+    // Absolute roots give absolute result paths, which editors send back.
+    config.walk.roots = config.walk.roots.iter().map(|p| absolute(p)).collect();
+    config.incremental = true;
+    config.count_sloc = true;
+
+    let daemon = Arc::new(Daemon::new(config.clone()));
+    let stdout = Arc::new(Mutex::new(io::stdout()));
+    {
+        let daemon = daemon.clone();
+        let stdout = stdout.clone();
+        std::thread::spawn(move || {
+            let watched = watch::watch_loop(&config, |changes| {
+                if let Err(e) = daemon.apply(changes) {
+                    log::error!("Error in watch cycle: {e}");
+                    return;
+                }
+                let notification = json!({
+                    "jsonrpc": "2.0",
+                    "method": "summaryChanged",
+                    "params": daemon.summary(),
+                });
+                if let Err(e) = write_line(&stdout, &notification) {
+                    log::error!("Output Error: {e}");
+                }
+            });
+            if let Err(e) = watched {
+                log::error!("Watch Error: {e}");
+            }
+        });
+    }
+
+    for line in io::stdin().lock().lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        if let Some(reply) = daemon.handle(&line) {
+            write_line(&stdout, &reply)?;
+        }
+        let shutdown = serde_json::from_str::<Value>(&line)
+            .is_ok_and(|r| r.get("method").and_then(Value::as_str) == Some("shutdown"));
+        if shutdown {
+            break;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use count_lines_engine::config::WalkOptions;
+
+    fn daemon(dir: &Path) -> Daemon {
+        std::fs::write(dir.join("a.rs"), "// a\nfn a() {}\n").unwrap();
+        std::fs::write(dir.join("b.py"), "b = 1\nb = 2\n").unwrap();
+        let daemon = Daemon::new(Config {
+            walk: WalkOptions {
+                roots: vec![absolute(dir)],
+                ..WalkOptions::default()
+            },
+            count_sloc: true,
+            cache_dir: Some(dir.join(".cache")),
+            incremental: true,
+            ..Config::default()
+        });
+        daemon.apply(&Changes::rescan()).unwrap();
+        daemon
+    }
+
+    #[test]
+    fn test_summary_request() {
+        let dir = tempfile::tempdir().unwrap();
+        let daemon = Daemon::new(Config::default());
+        let reply = daemon
+            .handle(r#"{"jsonrpc":"2.0","id":1,"method":"summary"}"#)
+            .unwrap();
+        assert_eq!(reply["result"]["ready"], false);
+
+        let daemon = self::daemon(dir.path());
+        let reply = daemon
+            .handle(r#"{"jsonrpc":"2.0","id":2,"method":"summary"}"#)
+            .unwrap();
+        assert_eq!(reply["id"], 2);
+        assert_eq!(reply["result"]["ready"], true);
+        assert_eq!(reply["result"]["summary"]["files"], 2);
+        assert_eq!(reply["result"]["summary"]["sloc"], 3);
+    }
+
+    #[test]
+    fn test_file_request() {
+        let dir = tempfile::tempdir().unwrap();
+        let daemon = daemon(dir.path());
+        let path = dir.path().join("a.rs");
+        let request =
+            json!({ "jsonrpc": "2.0", "id": 1, "method": "file", "params": { "path": path } });
+        let reply = daemon.handle(&request.to_string()).unwrap();
+        assert_eq!(reply["result"]["lines"], 2);
+        assert_eq!(reply["result"]["sloc"], 1);
+
+        let unsaved = json!({
+            "jsonrpc": "2.0",
+            "id": 2,
+            "method": "file",
+            "params": { "path": path, "text": "fn a() {}\nfn b() {}\n// c\n" },
+        });
+        let reply = daemon.handle(&unsaved.to_string()).unwrap();
+        assert_eq!(reply["result"]["sloc"], 2);
+
+        let missing = json!({ "jsonrpc": "2.0", "id": 3, "method": "file", "params": { "path": dir.path().join("nope.rs") } });
+        assert_eq!(
+            daemon.handle(&missing.to_string()).unwrap()["result"],
+            Value::Null
+        );
+    }
+
+    #[test]
+    fn test_apply_picks_up_changes() {
+        let dir = tempfile::tempdir().unwrap();
+        let daemon = daemon(dir.path());
+        let path = absolute(dir.path()).join("c.rs");
+        std::fs::write(&path, "fn c() {}\n").unwrap();
+        daemon
+            .apply(&Changes {
+                paths: [path].into(),
+                ..Changes::default()
+            })
+            .unwrap();
+        assert_eq!(daemon.summary()["summary"]["files"], 3);
+        assert_eq!(daemon.summary()["cycle"], 2);
+    }
+
+    #[test]
+    fn test_errors() {
+        let daemon = Daemon::new(Config::default());
+        assert_eq!(daemon.handle("{").unwrap()["error"]["code"], PARSE_ERROR);
+        let reply = daemon.handle(r#"{"id":1,"method":"nope"}"#).unwrap();
+        assert_eq!(reply["error"]["code"], METHOD_NOT_FOUND);
+        let reply = daemon.handle(r#"{"id":1,"method":"file"}"#).unwrap();
+        assert_eq!(reply["error"]["code"], INVALID_PARAMS);
+        assert!(daemon.handle(r#"{"method":"summary"}"#).is_none());
+    }
+}
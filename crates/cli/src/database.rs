@@ -0,0 +1,254 @@
+// crates/cli/src/database.rs
+//! `--output-db`: appends each run to a SQLite database, for queries over
+//! time with plain SQL.
+//!
+//! Tables:
+//! - `runs`: one row per run (`started_at` in RFC 3339, count_lines
+//!   `version`, `git_commit` of the first root, `roots`)
+//! - `run_summaries`: totals of each run (`run_id` → `runs.id`)
+//! - `files`: per-file stats of each run (`run_id` → `runs.id`)
+//!
+//! The schema version is kept in `PRAGMA user_version`; opening an older
+//! database applies the missing [`MIGRATIONS`] in order.
+
+use crate::config::Config;
+use crate::error::{AppError, Result};
+use crate::presentation;
+use chrono::{DateTime, Local};
+use count_lines_core::language::language_name;
+use count_lines_engine::git;
+use count_lines_engine::stats::{FileStats, RunResult, Summary};
+use rusqlite::{Connection, Transaction, params};
+use std::path::Path;
+
+/// `MIGRATIONS[n]` upgrades a database from schema version `n` to `n + 1`.
+const MIGRATIONS: &[&str] = &["
+    CREATE TABLE runs (
+        id INTEGER PRIMARY KEY,
+        started_at TEXT NOT NULL,
+        version TEXT NOT NULL,
+        git_commit TEXT,
+        roots TEXT NOT NULL
+    );
+    CREATE TABLE run_summaries (
+        run_id INTEGER PRIMARY KEY REFERENCES runs (id) ON DELETE CASCADE,
+        files INTEGER NOT NULL,
+        lines INTEGER NOT NULL,
+        sloc INTEGER,
+        chars INTEGER NOT NULL,
+        words INTEGER,
+        size INTEGER NOT NULL,
+        errors INTEGER NOT NULL
+    );
+    CREATE TABLE files (
+        run_id INTEGER NOT NULL REFERENCES runs (id) ON DELETE CASCADE,
+        path TEXT NOT NULL,
+        language TEXT,
+        ext TEXT NOT NULL,
+        lines INTEGER NOT NULL,
+        sloc INTEGER,
+        chars INTEGER NOT NULL,
+        words INTEGER,
+        size INTEGER NOT NULL,
+        mtime TEXT,
+        PRIMARY KEY (run_id, path)
+    );
+    CREATE INDEX files_path ON files (path);
+"];
+
+/// The schema version this build writes.
+#[allow(clippy::cast_possible_wrap)]
+pub const SCHEMA_VERSION: i64 = MIGRATIONS.len() as i64;
+
+/// Opens (or creates) the database at `path` and migrates it to
+/// [`SCHEMA_VERSION`].
+///
+/// # Errors
+/// Returns an error if the database cannot be opened, was written by a newer
+/// count_lines, or a migration fails.
+pub fn open(path: &Path) -> Result<Connection> {
+    let mut conn = Connection::open(path)?;
+    conn.pragma_update(None, "foreign_keys", true)?;
+    migrate(&mut conn)?;
+    Ok(conn)
+}
+
+fn migrate(conn: &mut Connection) -> Result<()> {
+    let version: i64 = conn.pragma_query_value(None, "user_version", |row| row.get(0))?;
+    if version > SCHEMA_VERSION {
+        return Err(AppError::Database(format!(
+            "schema version {version} is newer than this count_lines supports ({SCHEMA_VERSION})"
+        )));
+    }
+    let tx = conn.transaction()?;
+    for (from, migration) in (version..).zip(&MIGRATIONS[usize::try_from(version).unwrap_or(0)..]) {
+        tx.execute_batch(migration)?;
+        tx.pragma_update(None, "user_version", from + 1)?;
+    }
+    tx.commit()?;
+    Ok(())
+}
+
+/// Appends `result` as a new run started at `started_at` and returns its id.
+///
+/// Binary files are left out, as in the other outputs.
+///
+/// # Errors
+/// Returns an error if the database cannot be opened or written.
+pub fn write_run(
+    path: &Path,
+    result: &RunResult,
+    config: &Config,
+    started_at: DateTime<Local>,
+) -> Result<i64> {
+    let mut conn = open(path)?;
+    let tx = conn.transaction()?;
+    let run_id = insert_run(&tx, result, config, started_at)?;
+    tx.commit()?;
+    Ok(run_id)
+}
+
+fn insert_run(
+    tx: &Transaction<'_>,
+    result: &RunResult,
+    config: &Config,
+    started_at: DateTime<Local>,
+) -> Result<i64> {
+    let stats: Vec<&FileStats> = result.stats.iter().filter(|s| !s.is_binary).collect();
+    // Not every run is inside a repository.
+    let commit = git::head_commit(config).ok();
+    let roots: Vec<String> = config
+        .walk
+        .roots
+        .iter()
+        .map(|p| p.display().to_string())
+        .collect();
+    tx.execute(
+        "INSERT INTO runs (started_at, version, git_commit, roots) VALUES (?1, ?2, ?3, ?4)",
+        params![
+            started_at.to_rfc3339(),
+            crate::VERSION,
+            commit,
+            serde_json::to_string(&roots)?
+        ],
+    )?;
+    let run_id = tx.last_insert_rowid();
+
+    let mut summary = Summary::default();
+    for s in &stats {
+        summary.add(s);
+    }
+    tx.execute(
+        "INSERT INTO run_summaries (run_id, files, lines, sloc, chars, words, size, errors)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+        params![
+            run_id,
+            summary.files,
+            summary.lines,
+            summary.sloc,
+            summary.chars,
+            summary.words,
+            summary.size,
+            result.error_count()
+        ],
+    )?;
+
+    let mut insert = tx.prepare(
+        "INSERT OR REPLACE INTO files
+             (run_id, path, language, ext, lines, sloc, chars, words, size, mtime)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+    )?;
+    for s in stats {
+        let path = presentation::display_path(&s.path, config);
+        insert.execute(params![
+            run_id,
+            path.display().to_string(),
+            language_name(&s.ext),
+            s.ext,
+            s.lines,
+            s.sloc,
+            s.chars,
+            s.words,
+            s.size,
+            s.mtime.map(|t| t.to_rfc3339())
+        ])?;
+    }
+    Ok(run_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn result(lines: &[(&str, usize)]) -> RunResult {
+        RunResult {
+            stats: lines
+                .iter()
+                .map(|&(path, lines)| FileStats {
+                    lines,
+                    sloc: Some(lines / 2),
+                    ext: "rs".to_string(),
+                    ..FileStats::new(PathBuf::from(path))
+                })
+                .collect(),
+            ..RunResult::default()
+        }
+    }
+
+    #[test]
+    fn test_runs_are_appended() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = dir.path().join("stats.sqlite");
+        let config = Config::default();
+        let first = write_run(&db, &result(&[("a.rs", 10)]), &config, Local::now()).unwrap();
+        let second = write_run(
+            &db,
+            &result(&[("a.rs", 12), ("b.rs", 4)]),
+            &config,
+            Local::now(),
+        )
+        .unwrap();
+        assert_ne!(first, second);
+
+        let conn = open(&db).unwrap();
+        let totals: Vec<(i64, i64)> = conn
+            .prepare("SELECT files, lines FROM run_summaries ORDER BY run_id")
+            .unwrap()
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+            .unwrap()
+            .collect::<rusqlite::Result<_>>()
+            .unwrap();
+        assert_eq!(totals, [(1, 10), (2, 16)]);
+
+        let (language, sloc): (String, i64) = conn
+            .query_row(
+                "SELECT language, sloc FROM files WHERE run_id = ?1 AND path = 'b.rs'",
+                [second],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap();
+        assert_eq!((language.as_str(), sloc), ("Rust", 2));
+    }
+
+    #[test]
+    fn test_schema_version() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = dir.path().join("stats.sqlite");
+        let conn = open(&db).unwrap();
+        let version: i64 = conn
+            .pragma_query_value(None, "user_version", |row| row.get(0))
+            .unwrap();
+        assert_eq!(version, SCHEMA_VERSION);
+        // Reopening an up-to-date database applies nothing.
+        drop(conn);
+        open(&db).unwrap();
+
+        let conn = Connection::open(&db).unwrap();
+        conn.pragma_update(None, "user_version", SCHEMA_VERSION + 1)
+            .unwrap();
+        drop(conn);
+        let err = open(&db).unwrap_err();
+        assert!(err.to_string().contains("is newer"), "{err}");
+    }
+}
@@ -14,6 +14,25 @@ pub enum AppError {
 
     #[error("Comparison error: {0}")]
     Comparison(String),
+
+    #[error("Snapshot error: {0}")]
+    Snapshot(String),
+
+    #[error("Database error: {0}")]
+    Database(String),
+
+    #[error("Database error: {0}")]
+    Sqlite(#[from] rusqlite::Error),
+
+    /// One or more `--fail-if` thresholds were exceeded.
+    #[error("Threshold exceeded: {0}")]
+    Threshold(String),
+
+    #[error("Invalid --fail-if expression: {0}")]
+    InvalidThreshold(String),
+
+    #[error("Unknown flag: {0}")]
+    UnknownFlag(String),
 }
 
 pub type Result<T> = std::result::Result<T, AppError>;
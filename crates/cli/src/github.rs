@@ -0,0 +1,143 @@
+// crates/cli/src/github.rs
+//! GitHub Actions workflow commands for `--format github`: each
+//! `::warning file=...::message` line printed by a workflow step becomes an
+//! annotation on the pull request.
+
+use crate::error::{AppError, Result};
+use std::fmt;
+use std::io::{self, Write};
+use std::path::Path;
+
+/// Severity of an annotation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Level {
+    Notice,
+    Warning,
+    Error,
+}
+
+impl Level {
+    const fn as_str(self) -> &'static str {
+        match self {
+            Self::Notice => "notice",
+            Self::Warning => "warning",
+            Self::Error => "error",
+        }
+    }
+}
+
+/// One workflow command line, without the trailing newline.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Annotation<'a> {
+    pub level: Level,
+    /// The file the annotation is attached to, relative to the repository.
+    pub file: Option<&'a Path>,
+    pub title: &'a str,
+    pub message: &'a str,
+}
+
+impl<'a> Annotation<'a> {
+    #[must_use]
+    pub const fn new(level: Level, title: &'a str, message: &'a str) -> Self {
+        Self {
+            level,
+            file: None,
+            title,
+            message,
+        }
+    }
+
+    #[must_use]
+    pub const fn file(mut self, file: &'a Path) -> Self {
+        self.file = Some(file);
+        self
+    }
+}
+
+impl fmt::Display for Annotation<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "::{}", self.level.as_str())?;
+        let mut separator = ' ';
+        if let Some(file) = self.file {
+            // The runner expects `/` separators on every platform.
+            let file = file.to_string_lossy().replace('\\', "/");
+            write!(f, "{separator}file={}", escape_property(&file))?;
+            separator = ',';
+        }
+        write!(f, "{separator}title={}", escape_property(self.title))?;
+        write!(f, "::{}", escape_data(self.message))
+    }
+}
+
+/// Writes an `::error` annotation when `checked` failed on `--fail-if`
+/// thresholds, so the failure shows on the pull request and not only in the
+/// log.
+///
+/// # Errors
+/// Returns an error if writing to `out` fails.
+pub fn write_threshold_error(out: &mut dyn Write, checked: &Result<()>) -> io::Result<()> {
+    if let Err(AppError::Threshold(message)) = checked {
+        let annotation = Annotation::new(Level::Error, "Threshold exceeded", message);
+        writeln!(out, "{annotation}")?;
+    }
+    Ok(())
+}
+
+/// Escapes the message of a command, which may not span lines.
+fn escape_data(s: &str) -> String {
+    s.replace('%', "%25")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+/// Escapes a `key=value` property, where `:` and `,` are delimiters.
+fn escape_property(s: &str) -> String {
+    escape_data(s).replace(':', "%3A").replace(',', "%2C")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_annotation_format() {
+        let annotation =
+            Annotation::new(Level::Warning, "Oversized file", "1200 lines (limit 800)")
+                .file(Path::new("src/main.rs"));
+        assert_eq!(
+            annotation.to_string(),
+            "::warning file=src/main.rs,title=Oversized file::1200 lines (limit 800)"
+        );
+        assert_eq!(
+            Annotation::new(Level::Notice, "count_lines", "3 files").to_string(),
+            "::notice title=count_lines::3 files"
+        );
+    }
+
+    #[test]
+    fn test_annotation_escaping() {
+        let annotation =
+            Annotation::new(Level::Error, "a: b, c", "100%\nnext").file(Path::new("dir,x/a:b.rs"));
+        assert_eq!(
+            annotation.to_string(),
+            "::error file=dir%2Cx/a%3Ab.rs,title=a%3A b%2C c::100%25%0Anext"
+        );
+    }
+
+    #[test]
+    fn test_write_threshold_error() {
+        let mut buf = Vec::new();
+        write_threshold_error(&mut buf, &Ok(())).unwrap();
+        write_threshold_error(&mut buf, &Err(AppError::InvalidThreshold("x".into()))).unwrap();
+        assert!(buf.is_empty());
+
+        let checked = Err(AppError::Threshold(
+            "total_lines > 10 (total_lines = 12)".into(),
+        ));
+        write_threshold_error(&mut buf, &checked).unwrap();
+        assert_eq!(
+            String::from_utf8(buf).unwrap(),
+            "::error title=Threshold exceeded::total_lines > 10 (total_lines = 12)\n"
+        );
+    }
+}
@@ -0,0 +1,189 @@
+// crates/cli/src/history.rs
+use crate::config::Config;
+use crate::error::Result;
+use crate::presentation::Delimiter;
+use count_lines_engine::git::{self, HistoryPoint};
+use count_lines_engine::options::OutputFormat;
+use std::collections::BTreeSet;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+
+/// Counts the git history configured by `--history` / `--history-since` and
+/// prints the time series to stdout, or to `config.output` when set.
+///
+/// # Errors
+/// Returns an error if the history cannot be read or the output fails.
+pub fn print_history(config: &Config) -> Result<()> {
+    let options = config.history.clone().unwrap_or_default();
+    let points = git::count_history(config, &options)?;
+
+    if let Some(path) = &config.output {
+        let mut out = BufWriter::new(File::create(path)?);
+        write_history(&mut out, &points, config.format)?;
+        out.flush()?;
+    } else {
+        let stdout = io::stdout();
+        let mut out = stdout.lock();
+        write_history(&mut out, &points, config.format)?;
+    }
+    Ok(())
+}
+
+/// Writes the time series in `format`.
+///
+/// `json`/`jsonl`/`csv`/`tsv` are machine readable; other formats fall back
+/// to a plain table.
+///
+/// # Errors
+/// Returns an error if writing to `out` fails.
+pub fn write_history(
+    out: &mut dyn Write,
+    points: &[HistoryPoint],
+    format: OutputFormat,
+) -> io::Result<()> {
+    match format {
+        OutputFormat::Json => {
+            let json = serde_json::to_string_pretty(points).map_err(io::Error::other)?;
+            writeln!(out, "{json}")
+        }
+        OutputFormat::Jsonl => {
+            for point in points {
+                let json = serde_json::to_string(point).map_err(io::Error::other)?;
+                writeln!(out, "{json}")?;
+            }
+            Ok(())
+        }
+        OutputFormat::Csv => write_sv(out, points, Delimiter::Comma),
+        OutputFormat::Tsv => write_sv(out, points, Delimiter::Tab),
+        _ => write_table(out, points),
+    }
+}
+
+/// One row per commit; per-language line counts follow the fixed columns as
+/// `lines:<ext>`.
+fn write_sv(out: &mut dyn Write, points: &[HistoryPoint], delimiter: Delimiter) -> io::Result<()> {
+    let languages: BTreeSet<&String> = points.iter().flat_map(|p| p.languages.keys()).collect();
+
+    let mut header: Vec<String> = ["commit", "date", "files", "lines", "sloc", "chars"]
+        .iter()
+        .map(ToString::to_string)
+        .collect();
+    header.extend(
+        languages
+            .iter()
+            .map(|l| delimiter.escape(&format!("lines:{l}"))),
+    );
+    writeln!(out, "{}", header.join(delimiter.as_str()))?;
+
+    for p in points {
+        let mut fields = vec![
+            p.commit.clone(),
+            p.date.to_rfc3339(),
+            p.summary.files.to_string(),
+            p.summary.lines.to_string(),
+            p.summary.sloc.map(|v| v.to_string()).unwrap_or_default(),
+            p.summary.chars.to_string(),
+        ];
+        fields.extend(languages.iter().map(|l| {
+            p.languages
+                .get(*l)
+                .map_or_else(|| "0".to_string(), |t| t.lines.to_string())
+        }));
+        writeln!(out, "{}", fields.join(delimiter.as_str()))?;
+    }
+    Ok(())
+}
+
+fn write_table(out: &mut dyn Write, points: &[HistoryPoint]) -> io::Result<()> {
+    writeln!(
+        out,
+        "{:<10}  {:<25}{:>8}{:>12}{:>12}",
+        "COMMIT", "DATE", "FILES", "LINES", "SLOC"
+    )?;
+    writeln!(out, "{}", "-".repeat(69))?;
+    for p in points {
+        writeln!(
+            out,
+            "{:<10}  {:<25}{:>8}{:>12}{:>12}",
+            &p.commit[..p.commit.len().min(10)],
+            p.date.to_rfc3339(),
+            p.summary.files,
+            p.summary.lines,
+            p.summary.sloc.map(|v| v.to_string()).unwrap_or_default()
+        )?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Local, TimeZone};
+    use count_lines_engine::git::LanguageTotals;
+    use count_lines_engine::stats::Summary;
+    use std::collections::BTreeMap;
+
+    fn point(commit: &str, rs_lines: usize, py_lines: Option<usize>) -> HistoryPoint {
+        let mut languages = BTreeMap::new();
+        languages.insert(
+            "rs".to_string(),
+            LanguageTotals {
+                files: 1,
+                lines: rs_lines,
+                sloc: None,
+            },
+        );
+        if let Some(lines) = py_lines {
+            languages.insert(
+                "py".to_string(),
+                LanguageTotals {
+                    files: 1,
+                    lines,
+                    sloc: None,
+                },
+            );
+        }
+        HistoryPoint {
+            commit: commit.to_string(),
+            date: Local.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap(),
+            summary: Summary {
+                files: languages.len(),
+                lines: rs_lines + py_lines.unwrap_or(0),
+                ..Summary::default()
+            },
+            languages,
+        }
+    }
+
+    #[test]
+    fn test_csv_has_language_columns() {
+        let points = [point("aaa", 10, None), point("bbb", 12, Some(3))];
+        let mut buf = Vec::new();
+        write_history(&mut buf, &points, OutputFormat::Csv).unwrap();
+        let csv = String::from_utf8(buf).unwrap();
+        let lines: Vec<_> = csv.lines().collect();
+
+        assert_eq!(
+            lines[0],
+            "commit,date,files,lines,sloc,chars,lines:py,lines:rs"
+        );
+        assert!(lines[1].starts_with("aaa,"));
+        assert!(lines[1].ends_with(",1,10,,0,0,10"));
+        assert!(lines[2].ends_with(",2,15,,0,3,12"));
+    }
+
+    #[test]
+    fn test_jsonl_one_point_per_line() {
+        let points = [point("aaa", 10, None), point("bbb", 12, Some(3))];
+        let mut buf = Vec::new();
+        write_history(&mut buf, &points, OutputFormat::Jsonl).unwrap();
+        let values: Vec<serde_json::Value> = String::from_utf8(buf)
+            .unwrap()
+            .lines()
+            .map(|l| serde_json::from_str(l).unwrap())
+            .collect();
+        assert_eq!(values.len(), 2);
+        assert_eq!(values[1]["commit"], "bbb");
+        assert_eq!(values[1]["languages"]["py"]["lines"], 3);
+    }
+}
@@ -0,0 +1,246 @@
+// crates/cli/src/hook.rs
+//! `count_lines hook`: a git pre-commit hook that checks the staged files
+//! against per-file and per-commit limits before the commit is made.
+
+use crate::args::{HookAction, HookLimits};
+use crate::config::Config;
+use crate::error::{AppError, Result};
+use crate::lint::{self, LintLimits};
+use count_lines_engine::git;
+use std::ffi::OsString;
+use std::io::{self, Write};
+use std::path::Path;
+
+/// First line after the shebang of hooks written by `hook install`, so they
+/// can be replaced without `--force`.
+const MARKER: &str = "# count_lines pre-commit hook";
+
+/// Runs a `hook` subcommand.
+///
+/// Returns the number of violations found by `hook run`.
+///
+/// # Errors
+/// Returns an error if the repository can't be read or the hook can't be
+/// written.
+pub fn run(action: HookAction, config: &Config) -> Result<usize> {
+    match action {
+        HookAction::Install { limits, force } => {
+            let path = git::git_path(config, "hooks/pre-commit")?;
+            let global: Vec<OsString> = global_args(std::env::args_os().collect());
+            install(&path, &global, &limits, force)?;
+            eprintln!("[count_lines] Installed {}", path.display());
+            Ok(0)
+        }
+        HookAction::Run { limits } => {
+            let violations = check(&limits, config)?;
+            if !violations.is_empty() {
+                report(&mut io::stderr().lock(), &violations)?;
+            }
+            Ok(violations.len())
+        }
+    }
+}
+
+/// The options given before `hook`, replayed by the installed hook.
+fn global_args(args: Vec<OsString>) -> Vec<OsString> {
+    let end = args
+        .windows(2)
+        .position(|w| w[0] == "hook" && w[1] == "install")
+        .unwrap_or(args.len());
+    args.into_iter().take(end).skip(1).collect()
+}
+
+/// Writes the hook script to `path`.
+fn install(path: &Path, global: &[OsString], limits: &HookLimits, force: bool) -> Result<()> {
+    if let Ok(existing) = std::fs::read_to_string(path)
+        && !existing.contains(MARKER)
+        && !force
+    {
+        return Err(AppError::Io(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            format!(
+                "{} already exists; use --force to replace it",
+                path.display()
+            ),
+        )));
+    }
+
+    let exe = std::env::current_exe()?;
+    let mut command = vec![quote(&exe.to_string_lossy())];
+    command.extend(global.iter().map(|a| quote(&a.to_string_lossy())));
+    command.extend(["hook".to_string(), "run".to_string()]);
+    command.extend(limits.to_args());
+    let script = format!(
+        "#!/bin/sh\n{MARKER} (written by `count_lines hook install`)\nexec {}\n",
+        command.join(" ")
+    );
+
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    std::fs::write(path, script)?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o755))?;
+    }
+    Ok(())
+}
+
+/// Quotes `arg` for `sh`.
+fn quote(arg: &str) -> String {
+    format!("'{}'", arg.replace('\'', r"'\''"))
+}
+
+/// A limit exceeded by the staged changes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HookViolation {
+    /// The file, or `None` for a limit on the whole commit.
+    pub path: Option<String>,
+    pub metric: &'static str,
+    pub value: usize,
+    pub limit: usize,
+}
+
+/// Checks the staged files of the repository against `limits`.
+///
+/// # Errors
+/// Returns an error if the index can't be read.
+pub fn check(limits: &HookLimits, config: &Config) -> Result<Vec<HookViolation>> {
+    let staged = git::count_staged(config)?;
+    let file_limits = LintLimits {
+        max_lines: limits.max_file_lines,
+        max_chars: limits.max_file_chars,
+    };
+    let mut violations: Vec<HookViolation> = lint::find_violations(&staged.stats, file_limits)
+        .into_iter()
+        .map(|v| HookViolation {
+            path: Some(v.path.display().to_string()),
+            metric: v.metric,
+            value: v.value,
+            limit: v.limit,
+        })
+        .collect();
+
+    if let Some(limit) = limits.max_added_lines {
+        let added: usize = git::staged_line_changes(config)?
+            .iter()
+            .filter_map(|c| c.added)
+            .sum();
+        if added > limit {
+            violations.push(HookViolation {
+                path: None,
+                metric: "added lines",
+                value: added,
+                limit,
+            });
+        }
+    }
+    Ok(violations)
+}
+
+/// Writes the report shown when the commit is blocked.
+///
+/// # Errors
+/// Returns an error if writing to `out` fails.
+pub fn report(out: &mut dyn Write, violations: &[HookViolation]) -> io::Result<()> {
+    writeln!(
+        out,
+        "[count_lines] Commit blocked: {} limit violation(s) in the staged changes.",
+        violations.len()
+    )?;
+    for v in violations {
+        let path = v.path.as_deref().unwrap_or("(staged changes)");
+        writeln!(out, "  {path}: {} {} > {}", v.metric, v.value, v.limit)?;
+    }
+    writeln!(
+        out,
+        "[count_lines] Fix them, or skip the check with `git commit --no-verify`."
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use count_lines_engine::config::WalkOptions;
+    use count_lines_engine::testing::git;
+
+    fn config(root: &Path) -> Config {
+        Config {
+            walk: WalkOptions {
+                roots: vec![root.to_path_buf()],
+                ..WalkOptions::default()
+            },
+            ..Config::default()
+        }
+    }
+
+    #[test]
+    fn test_check_staged_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        git(root, &["init", "-q"]);
+        std::fs::write(root.join("small.rs"), "a\n").unwrap();
+        std::fs::write(root.join("big.rs"), "a\nb\nc\nd\n").unwrap();
+        std::fs::write(root.join("unstaged.rs"), "a\nb\nc\nd\ne\n").unwrap();
+        git(root, &["add", "small.rs", "big.rs"]);
+
+        let limits = HookLimits {
+            max_file_lines: Some(3),
+            max_added_lines: Some(4),
+            ..HookLimits::default()
+        };
+        let violations = check(&limits, &config(root)).unwrap();
+        assert_eq!(
+            violations,
+            [
+                HookViolation {
+                    path: Some("big.rs".to_string()),
+                    metric: "lines",
+                    value: 4,
+                    limit: 3,
+                },
+                HookViolation {
+                    path: None,
+                    metric: "added lines",
+                    value: 5,
+                    limit: 4,
+                },
+            ]
+        );
+
+        let mut buf = Vec::new();
+        report(&mut buf, &violations).unwrap();
+        let text = String::from_utf8(buf).unwrap();
+        assert!(text.contains("  big.rs: lines 4 > 3\n"));
+        assert!(text.contains("  (staged changes): added lines 5 > 4\n"));
+    }
+
+    #[test]
+    fn test_install_writes_hook() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("hooks/pre-commit");
+        let limits = HookLimits {
+            max_file_lines: Some(800),
+            ..HookLimits::default()
+        };
+        let global = [OsString::from("--ext"), OsString::from("rs")];
+        install(&path, &global, &limits, false).unwrap();
+        let script = std::fs::read_to_string(&path).unwrap();
+        assert!(script.starts_with("#!/bin/sh\n# count_lines pre-commit hook"));
+        assert!(script.ends_with(" '--ext' 'rs' hook run --max-file-lines 800\n"));
+        // Our own hook is replaced without --force.
+        install(&path, &global, &limits, false).unwrap();
+
+        std::fs::write(&path, "#!/bin/sh\nmake lint\n").unwrap();
+        assert!(install(&path, &global, &limits, false).is_err());
+        install(&path, &global, &limits, true).unwrap();
+    }
+
+    #[test]
+    fn test_global_args() {
+        let args = ["count_lines", "--ext", "rs", "hook", "install", "--force"].map(OsString::from);
+        assert_eq!(global_args(args.to_vec()), ["--ext", "rs"]);
+        assert_eq!(quote("it's"), r"'it'\''s'");
+    }
+}
@@ -0,0 +1,234 @@
+// crates/cli/src/http.rs
+//! Just enough HTTP/1.1 for `--serve-metrics` and `serve`: one request per
+//! connection, no request bodies, `Connection: close` responses.
+
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::TcpStream;
+use std::time::Duration;
+
+/// How long a client may take to send its request.
+const READ_TIMEOUT: Duration = Duration::from_secs(5);
+/// Longest request line or header line accepted, in bytes.
+const MAX_LINE: u64 = 8 * 1024;
+/// Most header lines accepted.
+const MAX_HEADERS: usize = 100;
+
+/// The request line of an HTTP request, with the query decoded.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Request {
+    pub method: String,
+    pub path: String,
+    pub query: Vec<(String, String)>,
+}
+
+impl Request {
+    /// The first value of query parameter `name`.
+    #[must_use]
+    pub fn param(&self, name: &str) -> Option<&str> {
+        self.query
+            .iter()
+            .find(|(k, _)| k == name)
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+/// Reads the request line and skips the headers.
+///
+/// A request line over [`MAX_LINE`] bytes is answered with `400 Bad
+/// Request`, and a header line over it or more than [`MAX_HEADERS`] header
+/// lines with `431 Request Header Fields Too Large`, so a client can't make
+/// the server buffer without bound.
+///
+/// # Errors
+/// Returns an error if reading fails or times out, or the request line is
+/// malformed or too long, or the headers are too large.
+pub fn read_request(stream: &TcpStream) -> io::Result<Request> {
+    stream.set_read_timeout(Some(READ_TIMEOUT))?;
+    let mut reader = BufReader::new(stream);
+    let Some(line) = read_line(&mut reader)? else {
+        return reject(stream, "400 Bad Request", "request line too long");
+    };
+    let mut headers = 0;
+    loop {
+        match read_line(&mut reader)? {
+            Some(header) if header.len() <= 2 => break,
+            Some(_) if headers < MAX_HEADERS => headers += 1,
+            _ => {
+                return reject(
+                    stream,
+                    "431 Request Header Fields Too Large",
+                    "request headers too large",
+                );
+            }
+        }
+    }
+
+    let mut parts = line.split_whitespace();
+    let (Some(method), Some(target)) = (parts.next(), parts.next()) else {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("malformed request line: {}", line.trim_end()),
+        ));
+    };
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    Ok(Request {
+        method: method.to_string(),
+        path: decode(path),
+        query: query
+            .split('&')
+            .filter(|pair| !pair.is_empty())
+            .map(|pair| {
+                let (k, v) = pair.split_once('=').unwrap_or((pair, ""));
+                (decode(k), decode(v))
+            })
+            .collect(),
+    })
+}
+
+/// Reads one line of at most [`MAX_LINE`] bytes; `None` if it is longer.
+fn read_line(reader: &mut impl BufRead) -> io::Result<Option<String>> {
+    let mut line = String::new();
+    let n = reader.take(MAX_LINE + 1).read_line(&mut line)?;
+    Ok((n as u64 <= MAX_LINE).then_some(line))
+}
+
+/// Answers a request that is too large with `status` and fails with
+/// `message`.
+fn reject(stream: &TcpStream, status: &str, message: &str) -> io::Result<Request> {
+    write_response(
+        stream,
+        status,
+        "text/plain; charset=utf-8",
+        &format!("{message}\n"),
+    )?;
+    Err(io::Error::new(io::ErrorKind::InvalidData, message))
+}
+
+/// Writes a complete response and flushes it.
+///
+/// # Errors
+/// Returns an error if writing to `stream` fails.
+pub fn write_response(
+    mut stream: &TcpStream,
+    status: &str,
+    content_type: &str,
+    body: &str,
+) -> io::Result<()> {
+    write!(
+        stream,
+        "HTTP/1.1 {status}\r\nContent-Type: {content_type}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    )?;
+    stream.flush()
+}
+
+/// Decodes `%XX` escapes and `+` of a URL component.
+fn decode(component: &str) -> String {
+    let bytes = component.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'+' => out.push(b' '),
+            b'%' => {
+                let hex = component.get(i + 1..i + 3);
+                if let Some(byte) = hex.and_then(|h| u8::from_str_radix(h, 16).ok()) {
+                    out.push(byte);
+                    i += 2;
+                } else {
+                    out.push(b'%');
+                }
+            }
+            b => out.push(b),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+    use std::net::TcpListener;
+
+    #[test]
+    fn test_decode() {
+        assert_eq!(decode("src%2Fmain.rs"), "src/main.rs");
+        assert_eq!(decode("a+b%zz"), "a b%zz");
+    }
+
+    /// Sends `request` to `read_request`; returns its result and the
+    /// response the client got.
+    fn exchange(request: Vec<u8>) -> (io::Result<Request>, String) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let client = std::thread::spawn(move || {
+            let mut stream = TcpStream::connect(addr).unwrap();
+            stream.write_all(&request).unwrap();
+            let mut response = String::new();
+            stream.read_to_string(&mut response).unwrap();
+            response
+        });
+        let (stream, _) = listener.accept().unwrap();
+        let result = read_request(&stream);
+        drop(stream);
+        (result, client.join().unwrap())
+    }
+
+    #[test]
+    fn test_rejects_oversized_requests() {
+        // Exactly one byte over the limit, so nothing is left unread.
+        let line = vec![b'G'; usize::try_from(MAX_LINE).unwrap() + 1];
+        let (result, response) = exchange(line.clone());
+        assert_eq!(result.unwrap_err().kind(), io::ErrorKind::InvalidData);
+        assert!(response.starts_with("HTTP/1.1 400 Bad Request\r\n"));
+
+        let mut request = b"GET / HTTP/1.1\r\n".to_vec();
+        for i in 0..MAX_HEADERS + 1 {
+            request.extend(format!("X-{i}: y\r\n").bytes());
+        }
+        let (result, response) = exchange(request);
+        assert!(result.is_err());
+        assert!(response.starts_with("HTTP/1.1 431 Request Header Fields Too Large\r\n"));
+
+        let mut request = b"GET / HTTP/1.1\r\n".to_vec();
+        let header = format!("X-Big: {}", "y".repeat(line.len() - 7));
+        request.extend(header.bytes());
+        let (result, response) = exchange(request);
+        assert!(result.is_err());
+        assert!(response.starts_with("HTTP/1.1 431 "));
+    }
+
+    #[test]
+    fn test_request_round_trip() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let client = std::thread::spawn(move || {
+            let mut stream = TcpStream::connect(addr).unwrap();
+            write!(
+                stream,
+                "GET /files?sort=lines%3Adesc&top=5 HTTP/1.1\r\nHost: x\r\n\r\n"
+            )
+            .unwrap();
+            let mut response = String::new();
+            stream.read_to_string(&mut response).unwrap();
+            response
+        });
+
+        let (stream, _) = listener.accept().unwrap();
+        let request = read_request(&stream).unwrap();
+        assert_eq!(request.method, "GET");
+        assert_eq!(request.path, "/files");
+        assert_eq!(request.param("sort"), Some("lines:desc"));
+        assert_eq!(request.param("top"), Some("5"));
+        assert_eq!(request.param("ext"), None);
+        write_response(&stream, "200 OK", "text/plain", "ok").unwrap();
+        drop(stream);
+
+        let response = client.join().unwrap();
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(response.contains("Content-Length: 2\r\n"));
+        assert!(response.ends_with("\r\n\r\nok"));
+    }
+}
@@ -5,11 +5,39 @@
 #![allow(clippy::multiple_crate_versions)]
 
 pub mod args;
+pub mod bench;
+pub mod cache;
+pub mod churn;
 pub mod compare;
+pub mod completions;
 pub mod config;
+pub mod daemon;
+pub mod database;
 pub mod error;
+pub mod github;
+pub mod history;
+pub mod hook;
+pub mod http;
+pub mod lint;
+pub mod list;
+pub mod logging;
+pub mod manual;
+pub mod metrics;
+pub mod numbers;
 pub mod options;
 pub mod parsers;
 pub mod presentation;
+pub mod progress;
+pub mod report;
+pub mod serve;
+pub mod snapshot;
+pub mod style;
+pub mod test_split;
+pub mod threshold;
+pub mod trend;
+pub mod tui;
+pub mod watch;
+pub mod whitespace;
+pub mod why;
 
 pub const VERSION: &str = env!("CARGO_PKG_VERSION");
@@ -0,0 +1,228 @@
+// crates/cli/src/lint.rs
+use crate::args::LintArgs;
+use crate::config::Config;
+use crate::error::Result;
+use crate::github::{Annotation, Level};
+use crate::presentation;
+use crate::presentation::sarif::{self, Finding, Rule};
+use count_lines_engine::options::OutputFormat;
+use count_lines_engine::stats::FileStats;
+use serde::Serialize;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::PathBuf;
+
+/// Per-file limits checked by `lint`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LintLimits {
+    pub max_lines: Option<usize>,
+    pub max_chars: Option<usize>,
+}
+
+/// A file exceeding one of the limits.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Violation {
+    pub path: PathBuf,
+    /// `lines` or `chars`.
+    pub metric: &'static str,
+    pub value: usize,
+    pub limit: usize,
+}
+
+/// Returns every limit exceeded by `stats`, sorted by path. Binary files
+/// are ignored.
+#[must_use]
+pub fn find_violations(stats: &[FileStats], limits: LintLimits) -> Vec<Violation> {
+    let mut violations: Vec<Violation> = stats
+        .iter()
+        .filter(|s| !s.is_binary)
+        .flat_map(|s| {
+            [
+                ("lines", s.lines, limits.max_lines),
+                ("chars", s.chars, limits.max_chars),
+            ]
+            .into_iter()
+            .filter_map(|(metric, value, limit)| {
+                limit.filter(|&limit| value > limit).map(|limit| Violation {
+                    path: s.path.clone(),
+                    metric,
+                    value,
+                    limit,
+                })
+            })
+        })
+        .collect();
+    violations.sort_by(|a, b| a.path.cmp(&b.path));
+    violations
+}
+
+/// Runs the `lint` subcommand and prints the violations to stdout, or to
+/// `config.output` when set. The normal filter options decide which files
+/// are checked.
+///
+/// Returns the number of violations.
+///
+/// # Errors
+/// Returns an error if the scan or the output fails.
+pub fn run(args: LintArgs, mut config: Config) -> Result<usize> {
+    if !args.paths.is_empty() {
+        config.walk.roots = args.paths;
+    }
+    let limits = LintLimits {
+        max_lines: args.max_file_lines,
+        max_chars: args.max_file_chars,
+    };
+
+    let result = count_lines_engine::run(&config)?;
+    for (path, err) in &result.errors {
+        log::warn!("Error processing {}: {err}", path.display());
+    }
+    let mut violations = find_violations(&result.stats, limits);
+    for v in &mut violations {
+        v.path = presentation::display_path(&v.path, &config);
+    }
+
+    if let Some(path) = &config.output {
+        let mut out = BufWriter::new(File::create(path)?);
+        write_violations(&mut out, &violations, config.format)?;
+        out.flush()?;
+    } else {
+        let stdout = io::stdout();
+        let mut out = stdout.lock();
+        write_violations(&mut out, &violations, config.format)?;
+    }
+    Ok(violations.len())
+}
+
+/// Writes the violations: a JSON array for `json`, one object per line for
+/// `jsonl`, a `::warning` annotation each for `github`, a SARIF log for
+/// `sarif`, otherwise one
+/// `path: lines 1200 > 800` line per violation.
+///
+/// # Errors
+/// Returns an error if writing to `out` fails.
+pub fn write_violations(
+    out: &mut dyn Write,
+    violations: &[Violation],
+    format: OutputFormat,
+) -> io::Result<()> {
+    match format {
+        OutputFormat::Json => {
+            let json = serde_json::to_string_pretty(violations).map_err(io::Error::other)?;
+            writeln!(out, "{json}")
+        }
+        OutputFormat::Jsonl => {
+            for violation in violations {
+                let json = serde_json::to_string(violation).map_err(io::Error::other)?;
+                writeln!(out, "{json}")?;
+            }
+            Ok(())
+        }
+        OutputFormat::Sarif => {
+            let findings: Vec<Finding> = violations
+                .iter()
+                .map(|v| {
+                    let rule = if v.metric == "chars" {
+                        Rule::MaxFileChars
+                    } else {
+                        Rule::MaxFileLines
+                    };
+                    let message = format!("{} {} > {}", v.metric, v.value, v.limit);
+                    Finding::new(rule, message).file(&v.path)
+                })
+                .collect();
+            sarif::write_sarif(out, &findings)
+        }
+        OutputFormat::Github => {
+            for v in violations {
+                let message = format!("{} {} > {}", v.metric, v.value, v.limit);
+                let annotation =
+                    Annotation::new(Level::Warning, "Oversized file", &message).file(&v.path);
+                writeln!(out, "{annotation}")?;
+            }
+            Ok(())
+        }
+        _ => {
+            for v in violations {
+                writeln!(
+                    out,
+                    "{}: {} {} > {}",
+                    v.path.display(),
+                    v.metric,
+                    v.value,
+                    v.limit
+                )?;
+            }
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stats(path: &str, lines: usize, chars: usize) -> FileStats {
+        FileStats {
+            lines,
+            chars,
+            ..FileStats::new(PathBuf::from(path))
+        }
+    }
+
+    #[test]
+    fn test_find_violations() {
+        let files = [
+            stats("b.rs", 900, 10),
+            stats("a.rs", 800, 200_000),
+            FileStats {
+                is_binary: true,
+                ..stats("c.bin", 5000, 0)
+            },
+        ];
+        let limits = LintLimits {
+            max_lines: Some(800),
+            max_chars: Some(100_000),
+        };
+        let violations = find_violations(&files, limits);
+        let found: Vec<_> = violations
+            .iter()
+            .map(|v| (v.path.to_str().unwrap(), v.metric))
+            .collect();
+        assert_eq!(found, [("a.rs", "chars"), ("b.rs", "lines")]);
+    }
+
+    #[test]
+    fn test_write_violations() {
+        let violations = find_violations(
+            &[stats("a.rs", 1200, 0)],
+            LintLimits {
+                max_lines: Some(800),
+                ..LintLimits::default()
+            },
+        );
+        let mut buf = Vec::new();
+        write_violations(&mut buf, &violations, OutputFormat::Table).unwrap();
+        assert_eq!(String::from_utf8(buf).unwrap(), "a.rs: lines 1200 > 800\n");
+
+        let mut buf = Vec::new();
+        write_violations(&mut buf, &violations, OutputFormat::Jsonl).unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&buf).unwrap();
+        assert_eq!(json["metric"], "lines");
+        assert_eq!(json["limit"], 800);
+
+        let mut buf = Vec::new();
+        write_violations(&mut buf, &violations, OutputFormat::Github).unwrap();
+        assert_eq!(
+            String::from_utf8(buf).unwrap(),
+            "::warning file=a.rs,title=Oversized file::lines 1200 > 800\n"
+        );
+
+        let mut buf = Vec::new();
+        write_violations(&mut buf, &violations, OutputFormat::Sarif).unwrap();
+        let log: serde_json::Value = serde_json::from_slice(&buf).unwrap();
+        let result = &log["runs"][0]["results"][0];
+        assert_eq!(result["ruleId"], "count-lines/max-file-lines");
+        assert_eq!(result["message"]["text"], "lines 1200 > 800");
+    }
+}
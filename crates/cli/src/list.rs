@@ -0,0 +1,78 @@
+// crates/cli/src/list.rs
+use crate::config::Config;
+use crate::error::Result;
+use crate::presentation::{display_path, write_raw_paths};
+use count_lines_engine::options::OutputFormat;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::PathBuf;
+
+/// Lists the files that would be counted (`--list-only`) to stdout, or to
+/// `config.output` when set.
+///
+/// # Errors
+/// Returns an error if the walk or the output fails.
+pub fn print_file_list(config: &Config) -> Result<()> {
+    let files: Vec<PathBuf> = count_lines_engine::list_files(config)?
+        .iter()
+        .map(|path| display_path(path, config))
+        .collect();
+
+    if let Some(path) = &config.output {
+        let mut out = BufWriter::new(File::create(path)?);
+        write_file_list(&mut out, &files, config)?;
+        out.flush()?;
+    } else {
+        let stdout = io::stdout();
+        let mut out = BufWriter::new(stdout.lock());
+        write_file_list(&mut out, &files, config)?;
+        out.flush()?;
+    }
+    Ok(())
+}
+
+/// Writes one path per line, NUL-terminated paths with `--null`, or a JSON
+/// array with `--format json`.
+///
+/// # Errors
+/// Returns an error if writing to `out` fails.
+pub fn write_file_list(out: &mut dyn Write, files: &[PathBuf], config: &Config) -> io::Result<()> {
+    if config.format == OutputFormat::Json {
+        let json = serde_json::to_string_pretty(files).map_err(io::Error::other)?;
+        return writeln!(out, "{json}");
+    }
+    let terminator = if config.null_separated { b'\0' } else { b'\n' };
+    write_raw_paths(out, files.iter().map(PathBuf::as_path), terminator)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn files() -> Vec<PathBuf> {
+        vec![PathBuf::from("a b.rs"), PathBuf::from("src/lib.rs")]
+    }
+
+    #[test]
+    fn test_null_separated() {
+        let config = Config {
+            null_separated: true,
+            ..Config::default()
+        };
+        let mut buf = Vec::new();
+        write_file_list(&mut buf, &files(), &config).unwrap();
+        assert_eq!(buf, b"a b.rs\0src/lib.rs\0");
+    }
+
+    #[test]
+    fn test_json_array() {
+        let config = Config {
+            format: OutputFormat::Json,
+            ..Config::default()
+        };
+        let mut buf = Vec::new();
+        write_file_list(&mut buf, &files(), &config).unwrap();
+        let value: serde_json::Value = serde_json::from_slice(&buf).unwrap();
+        assert_eq!(value, serde_json::json!(["a b.rs", "src/lib.rs"]));
+    }
+}
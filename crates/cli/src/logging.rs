@@ -0,0 +1,172 @@
+// crates/cli/src/logging.rs
+//! stderr `tracing` subscriber (`--log-level`, `--log-format`).
+//!
+//! Records of the `log` facade, which the engine and the walker use, are
+//! forwarded to it. The engine's `walk`, `measure` and `aggregate` spans are
+//! reported when they close, with their fields and timings, from `info` on.
+//!
+//! Below `trace` only records of this tool's own crates are shown; the
+//! walker's dependencies log every ignored path at `debug`.
+
+use crate::options::{LogFormat, LogLevel};
+use std::fmt;
+use tracing::{Event, Subscriber};
+use tracing_subscriber::filter::{LevelFilter, Targets};
+use tracing_subscriber::fmt::format::{FmtSpan, Writer};
+use tracing_subscriber::fmt::{FmtContext, FormatEvent, FormatFields, FormattedFields, MakeWriter};
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::util::SubscriberInitExt;
+
+/// Installs the subscriber. Calling it again has no effect.
+pub fn init(level: LogLevel, format: LogFormat) {
+    let _ = subscriber(level, format, std::io::stderr).try_init();
+}
+
+/// The subscriber for `level` and `format`, writing to `writer`.
+fn subscriber<W>(level: LogLevel, format: LogFormat, writer: W) -> Box<dyn Subscriber + Send + Sync>
+where
+    W: for<'a> MakeWriter<'a> + Send + Sync + 'static,
+{
+    let filter = match level {
+        LogLevel::Off => LevelFilter::OFF,
+        LogLevel::Error => LevelFilter::ERROR,
+        LogLevel::Warn => LevelFilter::WARN,
+        LogLevel::Info => LevelFilter::INFO,
+        LogLevel::Debug => LevelFilter::DEBUG,
+        LogLevel::Trace => LevelFilter::TRACE,
+    };
+    let targets = if level == LogLevel::Trace {
+        Targets::new().with_default(filter)
+    } else {
+        Targets::new().with_target("count_lines", filter)
+    };
+    let layer = tracing_subscriber::fmt::layer()
+        .with_writer(writer)
+        .with_ansi(false)
+        .with_span_events(FmtSpan::CLOSE);
+    let registry = tracing_subscriber::registry().with(targets);
+    match format {
+        LogFormat::Text => Box::new(registry.with(layer.event_format(TextFormat))),
+        LogFormat::Json => Box::new(registry.with(layer.json().flatten_event(true))),
+    }
+}
+
+/// `[count_lines] WARN message`, after the enclosing spans and their
+/// fields: `[count_lines] INFO run: walk{files=3}: close time.busy=...`.
+struct TextFormat;
+
+impl<S, N> FormatEvent<S, N> for TextFormat
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+    N: for<'a> FormatFields<'a> + 'static,
+{
+    fn format_event(
+        &self,
+        ctx: &FmtContext<'_, S, N>,
+        mut writer: Writer<'_>,
+        event: &Event<'_>,
+    ) -> fmt::Result {
+        write!(writer, "[count_lines] {} ", event.metadata().level())?;
+        for span in ctx
+            .event_scope()
+            .into_iter()
+            .flat_map(|scope| scope.from_root())
+        {
+            write!(writer, "{}", span.name())?;
+            if let Some(fields) = span.extensions().get::<FormattedFields<N>>()
+                && !fields.is_empty()
+            {
+                write!(writer, "{{{fields}}}")?;
+            }
+            write!(writer, ": ")?;
+        }
+        ctx.format_fields(writer.by_ref(), event)?;
+        writeln!(writer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io;
+    use std::sync::{Arc, Mutex, PoisonError};
+
+    #[derive(Clone, Default)]
+    struct Buffer(Arc<Mutex<Vec<u8>>>);
+
+    impl io::Write for Buffer {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    /// The lines logged by `f` under a subscriber for `level` and `format`.
+    fn capture(level: LogLevel, format: LogFormat, f: impl FnOnce()) -> Vec<String> {
+        let buffer = Buffer::default();
+        let writer = buffer.clone();
+        tracing::subscriber::with_default(subscriber(level, format, move || writer.clone()), f);
+        let bytes = buffer.0.lock().unwrap().clone();
+        String::from_utf8(bytes)
+            .unwrap()
+            .lines()
+            .map(str::to_string)
+            .collect()
+    }
+
+    fn walk() {
+        let span = tracing::info_span!(target: "count_lines_engine", "walk", files = 3);
+        let _entered = span.enter();
+        tracing::warn!(target: "count_lines_engine::filesystem", "walk: denied");
+        tracing::debug!(target: "ignore::walk", "ignoring target/");
+    }
+
+    #[test]
+    fn test_text_format() {
+        // The `info` span is below `warn`, so only the message is shown.
+        let lines = capture(LogLevel::Warn, LogFormat::Text, walk);
+        assert_eq!(lines, ["[count_lines] WARN walk: denied"]);
+
+        let lines = capture(LogLevel::Info, LogFormat::Text, walk);
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0], "[count_lines] WARN walk{files=3}: walk: denied");
+        assert!(
+            lines[1].starts_with("[count_lines] INFO walk{files=3}: close time.busy="),
+            "{}",
+            lines[1]
+        );
+    }
+
+    #[test]
+    fn test_json_format_has_span_fields() {
+        let lines = capture(LogLevel::Info, LogFormat::Json, walk);
+        let values: Vec<serde_json::Value> = lines
+            .iter()
+            .map(|l| serde_json::from_str(l).unwrap())
+            .collect();
+        assert_eq!(values[0]["level"], "WARN");
+        assert_eq!(values[0]["target"], "count_lines_engine::filesystem");
+        assert_eq!(values[0]["message"], "walk: denied");
+        assert_eq!(values[0]["span"]["name"], "walk");
+        assert_eq!(values[0]["span"]["files"], 3);
+        assert_eq!(values[1]["message"], "close");
+        assert_eq!(values[1]["span"]["files"], 3);
+        assert!(values[1]["time.busy"].is_string());
+    }
+
+    #[test]
+    fn test_dependency_targets_need_trace() {
+        let lines = capture(LogLevel::Debug, LogFormat::Text, walk);
+        assert!(lines.iter().all(|l| !l.contains("ignoring")));
+        let lines = capture(LogLevel::Trace, LogFormat::Text, walk);
+        assert!(lines.iter().any(|l| l.contains("ignoring target/")));
+    }
+}
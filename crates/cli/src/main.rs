@@ -1,54 +1,262 @@
 // crates/cli/src/main.rs
 use clap::Parser;
-use count_lines_cli::args::Args;
+use count_lines_cli::args::{Args, Command};
 use count_lines_cli::config::Config;
+use count_lines_cli::database;
+use count_lines_cli::error::AppError;
+use count_lines_cli::github;
 use count_lines_cli::presentation;
+use count_lines_cli::progress::ProgressBar;
+use count_lines_cli::threshold;
+use count_lines_cli::trend;
+use count_lines_cli::watch::WatchReporter;
+use count_lines_engine::options::OutputFormat;
+use count_lines_engine::stats::{RunResult, Summary};
+use count_lines_engine::watch::{self, Changes};
+use std::io;
 use std::process::ExitCode;
+use std::time::Instant;
+
+/// Exit code of a run interrupted by Ctrl-C (128 + SIGINT, as shells report it).
+const EXIT_INTERRUPTED: u8 = 130;
+
+/// Makes SIGINT/SIGTERM cancel the run so the partial results still get
+/// written; a second signal exits immediately.
+fn install_interrupt_handler(config: &Config) {
+    use signal_hook::consts::{SIGINT, SIGTERM};
+    for signal in [SIGINT, SIGTERM] {
+        let flag = config.cancel.flag();
+        let _ = signal_hook::flag::register_conditional_shutdown(
+            signal,
+            i32::from(EXIT_INTERRUPTED),
+            flag.clone(),
+        );
+        let _ = signal_hook::flag::register(signal, flag);
+    }
+}
+
+/// Reports a failed command; exceeded `--fail-if` thresholds are reported
+/// as such rather than as an error of the command.
+fn failure(context: &str, err: &AppError) -> ExitCode {
+    if matches!(err, AppError::Threshold(_)) {
+        eprintln!("[count_lines] {err}");
+    } else {
+        eprintln!("{context}: {err}");
+    }
+    ExitCode::FAILURE
+}
 
 fn main() -> ExitCode {
-    let args = Args::parse();
+    let mut args = Args::parse();
+    let command = args.command.take();
+    count_lines_cli::logging::init(args.behavior.log_level, args.behavior.log_format);
     // Convert args to engine::Config
     let config = Config::from(args);
 
-    if let Some((old, new)) = &config.compare {
-        match count_lines_cli::compare::compare_snapshots(old, new) {
+    if let Some(Command::Snapshot(snapshot)) = command {
+        match count_lines_cli::snapshot::run(snapshot.action, config) {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(e) => failure("Snapshot Error", &e),
+        }
+    } else if let Some(Command::Cache(cache)) = command {
+        match count_lines_cli::cache::run(cache.action, &config) {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(e) => failure("Cache Error", &e),
+        }
+    } else if let Some(Command::Serve(serve)) = command {
+        match count_lines_cli::serve::run(serve, config) {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(e) => failure("Serve Error", &e),
+        }
+    } else if let Some(Command::Daemon(daemon)) = command {
+        match count_lines_cli::daemon::run(daemon, config) {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(e) => failure("Daemon Error", &e),
+        }
+    } else if let Some(Command::Completions(completions)) = command {
+        match count_lines_cli::completions::print(completions.shell) {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(e) => {
+                eprintln!("Output Error: {e}");
+                ExitCode::FAILURE
+            }
+        }
+    } else if let Some(Command::Explain(explain)) = command {
+        match count_lines_cli::manual::print_explain(explain.flag.as_deref()) {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(e) => failure("Explain Error", &e),
+        }
+    } else if let Some(Command::Man) = command {
+        match count_lines_cli::manual::print_man() {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(e) => {
+                eprintln!("Output Error: {e}");
+                ExitCode::FAILURE
+            }
+        }
+    } else if let Some(Command::Bench(bench)) = command {
+        match count_lines_cli::bench::run(&bench, config) {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(e) => failure("Bench Error", &e),
+        }
+    } else if let Some(Command::DiffDirs(dirs)) = command {
+        match count_lines_cli::compare::compare_dirs(&dirs.dir_a, &dirs.dir_b, &config) {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(e) => failure("Comparison Error", &e),
+        }
+    } else if let Some(Command::Lint(lint)) = command {
+        match count_lines_cli::lint::run(lint, config) {
+            Ok(0) => ExitCode::SUCCESS,
+            Ok(count) => {
+                eprintln!("[count_lines] {count} limit violation(s) found.");
+                ExitCode::FAILURE
+            }
+            Err(e) => failure("Lint Error", &e),
+        }
+    } else if let Some(Command::Hook(hook)) = command {
+        match count_lines_cli::hook::run(hook.action, &config) {
+            Ok(0) => ExitCode::SUCCESS,
+            Ok(_) => ExitCode::FAILURE,
+            Err(e) => failure("Hook Error", &e),
+        }
+    } else if let Some(path) = &config.why {
+        match count_lines_cli::why::print_why(path, &config) {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(e) => failure("Application Error", &e),
+        }
+    } else if config.list_only {
+        match count_lines_cli::list::print_file_list(&config) {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(e) => failure("Application Error", &e),
+        }
+    } else if let Some((old, new)) = &config.compare {
+        match count_lines_cli::compare::compare_snapshots(old, new, &config) {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(e) => failure("Comparison Error", &e),
+        }
+    } else if let Some(spec) = &config.compare_ref {
+        match count_lines_cli::compare::compare_refs(spec, &config) {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(e) => failure("Comparison Error", &e),
+        }
+    } else if config.history.is_some() {
+        match count_lines_cli::history::print_history(&config) {
             Ok(()) => ExitCode::SUCCESS,
             Err(e) => {
-                eprintln!("Comparison Error: {e}");
+                eprintln!("History Error: {e}");
                 ExitCode::FAILURE
             }
         }
+    } else if config.churn.is_some() {
+        match count_lines_cli::churn::print_churn(&config) {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(e) => failure("Churn Error", &e),
+        }
+    } else if config.test_split.is_some() {
+        match count_lines_cli::test_split::print_test_split(&config) {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(e) => failure("Application Error", &e),
+        }
+    } else if config.audit_whitespace {
+        match count_lines_cli::whitespace::print_whitespace_audit(&config) {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(e) => failure("Application Error", &e),
+        }
+    } else if let Some(addr) = config.serve_metrics {
+        match count_lines_cli::metrics::serve(addr, &config) {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(e) => failure("Metrics Error", &e),
+        }
+    } else if config.watch && config.watch_tui {
+        match count_lines_cli::tui::run(&config) {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(e) => failure("Watch Error", &e),
+        }
     } else if config.watch {
-        // Define the callback for the watch loop
-        let run_cycle = || {
-            presentation::print_clear_screen(&config.watch_output);
-
-            match count_lines_engine::run(&config) {
-                Ok(result) => {
+        // Each cycle updates the previous result with the changed files.
+        let mut reporter = WatchReporter::new();
+        let mut result = RunResult::default();
+        let run_cycle =
+            |changes: &Changes| match watch::apply_changes(&mut result, changes, &config) {
+                Ok(()) => {
                     for (path, err) in &result.errors {
-                        eprintln!("Error processing {}: {err}", path.display());
+                        log::warn!("Error processing {}: {err}", path.display());
+                    }
+                    if let Err(e) = reporter.report(&result, &config) {
+                        log::error!("Output Error: {e}");
                     }
-                    presentation::print_results(&result.stats, &config);
                 }
-                Err(e) => eprintln!("Error in watch cycle: {e}"),
-            }
-        };
+                Err(e) => log::error!("Error in watch cycle: {e}"),
+            };
 
-        if let Err(e) = count_lines_engine::watch::watch_loop(&config, run_cycle) {
+        if let Err(e) = watch::watch_loop(&config, run_cycle) {
             eprintln!("Watch Error: {e}");
             ExitCode::FAILURE
         } else {
             ExitCode::SUCCESS
         }
     } else {
-        match count_lines_engine::run(&config) {
+        install_interrupt_handler(&config);
+        let started_at = chrono::Local::now();
+        let result = match &config.stdin {
+            Some(name) => count_lines_engine::run_reader(&mut io::stdin().lock(), name, &config),
+            None if config.progress => {
+                let bar = ProgressBar::start();
+                let result = count_lines_engine::run_with_progress(&config, bar.clone());
+                bar.finish();
+                result
+            }
+            None => count_lines_engine::run(&config),
+        };
+        match result {
             Ok(result) => {
                 for (path, err) in &result.errors {
-                    eprintln!("Error processing {}: {err}", path.display());
+                    log::warn!("Error processing {}: {err}", path.display());
                 }
 
-                presentation::print_results(&result.stats, &config);
-                ExitCode::SUCCESS
+                let printing = Instant::now();
+                if let Err(e) = presentation::print_results(&result, &config) {
+                    eprintln!("Output Error: {e}");
+                    return ExitCode::FAILURE;
+                }
+                if config.timings && !presentation::embeds_timings(&config) {
+                    let output = printing.elapsed();
+                    let _ = presentation::write_timings(&mut io::stderr().lock(), &result, output);
+                }
+                if result.timed_out {
+                    log::warn!("--timeout exceeded; results are partial.");
+                }
+                if let Some(limit) = result.truncated {
+                    log::warn!("Scan stopped at {limit}; results are partial.");
+                }
+                if result.partial {
+                    eprintln!("[count_lines] Interrupted: results are partial.");
+                    return ExitCode::from(EXIT_INTERRUPTED);
+                }
+                if let Some(db) = &config.output_db
+                    && let Err(e) = database::write_run(db, &result, &config, started_at)
+                {
+                    return failure("Database Error", &e);
+                }
+                // A partial run would read as a drop in the trend.
+                if result.truncated.is_none()
+                    && !result.timed_out
+                    && let Err(e) = trend::record(&mut io::stderr().lock(), &result, &config)
+                {
+                    log::warn!("Trend Error: {e}");
+                }
+                let mut summary = Summary::default();
+                for stats in result.stats.iter().filter(|s| !s.is_binary) {
+                    summary.add(stats);
+                }
+                let checked = threshold::check(&config, &|name| summary.field(name));
+                if config.format == OutputFormat::Github && config.output.is_none() {
+                    let _ = github::write_threshold_error(&mut io::stdout().lock(), &checked);
+                }
+                match checked {
+                    Ok(()) => ExitCode::SUCCESS,
+                    Err(e) => failure("Application Error", &e),
+                }
             }
             Err(e) => {
                 eprintln!("Application Error: {e}");
@@ -0,0 +1,325 @@
+// crates/cli/src/manual.rs
+//! Extended help: `count_lines explain <FLAG>` and `count_lines man`.
+//!
+//! Both are built from the clap definition of the arguments plus
+//! [`FLAG_DOCS`], so a flag's help line, value names and choices never
+//! drift from what the parser accepts.
+
+use crate::args::{Args, FLAG_DOCS, FlagDoc};
+use crate::error::{AppError, Result};
+use clap::{Arg, CommandFactory};
+use std::fmt::Write as _;
+use std::io::{self, Write};
+
+fn command() -> clap::Command {
+    let mut command = Args::command();
+    command.build();
+    command
+}
+
+fn doc_for(long: &str) -> Option<&'static FlagDoc> {
+    FLAG_DOCS.iter().find(|d| d.flag == long)
+}
+
+/// `--long <VALUE>` or `-s, --long <VALUE>`.
+fn signature(arg: &Arg) -> String {
+    let mut names = Vec::new();
+    names.extend(arg.get_short().map(|s| format!("-{s}")));
+    names.extend(arg.get_long().map(|l| format!("--{l}")));
+    let mut signature = names.join(", ");
+    if arg.get_action().takes_values() {
+        for name in arg.get_value_names().unwrap_or_default() {
+            let _ = write!(signature, " <{name}>");
+        }
+    }
+    signature
+}
+
+/// Finds the top-level flag `query` names: `--filter`, `filter` or `-o`.
+fn find_arg<'a>(command: &'a clap::Command, query: &str) -> Option<&'a Arg> {
+    let args = || command.get_arguments().filter(|a| !a.is_positional());
+    if let Some(long) = query.strip_prefix("--") {
+        return args().find(|a| a.get_long() == Some(long));
+    }
+    let mut chars = query.strip_prefix('-').unwrap_or(query).chars();
+    if let (Some(short), None, true) = (chars.next(), chars.next(), query.starts_with('-')) {
+        return args().find(|a| a.get_short() == Some(short));
+    }
+    args().find(|a| a.get_long() == Some(query))
+}
+
+/// Writes what `explain` prints for `query`.
+///
+/// # Errors
+/// Returns [`AppError::UnknownFlag`] if no flag is named `query`, or an
+/// IO error if writing fails.
+pub fn write_explain(out: &mut dyn Write, query: Option<&str>) -> Result<()> {
+    let command = command();
+    let Some(query) = query else {
+        writeln!(
+            out,
+            "詳しい説明のあるオプション (count_lines explain <FLAG>):"
+        )?;
+        for doc in FLAG_DOCS {
+            let help = find_arg(&command, doc.flag)
+                .and_then(Arg::get_help)
+                .map(ToString::to_string)
+                .unwrap_or_default();
+            writeln!(out, "  --{:<14}{help}", doc.flag)?;
+        }
+        return Ok(());
+    };
+
+    let arg = find_arg(&command, query).ok_or_else(|| {
+        let name = query.trim_start_matches('-');
+        let similar: Vec<String> = command
+            .get_arguments()
+            .filter_map(Arg::get_long)
+            .filter(|l| !name.is_empty() && (l.contains(name) || name.contains(*l)))
+            .map(|l| format!("--{l}"))
+            .collect();
+        if similar.is_empty() {
+            AppError::UnknownFlag(query.to_string())
+        } else {
+            AppError::UnknownFlag(format!("{query} (候補: {})", similar.join(", ")))
+        }
+    })?;
+
+    writeln!(out, "{}", signature(arg))?;
+    if let Some(help) = arg.get_help() {
+        writeln!(out, "    {help}")?;
+    }
+    if let Some(heading) = arg.get_help_heading() {
+        writeln!(out, "    分類: {heading}")?;
+    }
+    let choices: Vec<_> = arg
+        .get_possible_values()
+        .into_iter()
+        .filter(|v| !v.is_hide_set())
+        .collect();
+    if !choices.is_empty() {
+        writeln!(out)?;
+        writeln!(out, "値:")?;
+        for value in choices {
+            match value.get_help() {
+                Some(help) => writeln!(out, "    {:<12}{help}", value.get_name())?,
+                None => writeln!(out, "    {}", value.get_name())?,
+            }
+        }
+    }
+    let defaults: Vec<_> = arg
+        .get_default_values()
+        .iter()
+        .map(|v| v.to_string_lossy())
+        .collect();
+    if !defaults.is_empty() {
+        writeln!(out, "    既定値: {}", defaults.join(","))?;
+    }
+
+    if let Some(doc) = arg.get_long().and_then(doc_for) {
+        writeln!(out)?;
+        writeln!(out, "{}", doc.details)?;
+        if !doc.examples.is_empty() {
+            writeln!(out)?;
+            writeln!(out, "例:")?;
+            for example in doc.examples {
+                writeln!(out, "    {example}")?;
+            }
+        }
+        if !doc.see_also.is_empty() {
+            writeln!(out)?;
+            let related: Vec<_> = doc.see_also.iter().map(|f| format!("--{f}")).collect();
+            writeln!(out, "関連: {}", related.join(", "))?;
+        }
+    }
+    Ok(())
+}
+
+/// Prints `explain` for `query` to stdout.
+///
+/// # Errors
+/// Returns an error if the flag is unknown or stdout can't be written.
+pub fn print_explain(query: Option<&str>) -> Result<()> {
+    let stdout = io::stdout();
+    let mut out = stdout.lock();
+    write_explain(&mut out, query)
+}
+
+/// Escapes text for roff: backslashes, dashes and control characters at
+/// the start of a line.
+fn roff(text: &str) -> String {
+    text.lines()
+        .map(|line| {
+            let line = line.replace('\\', "\\e").replace('-', "\\-");
+            if line.starts_with(['.', '\'']) {
+                format!("\\&{line}")
+            } else {
+                line
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// One `.TP` entry of the OPTIONS section.
+fn write_man_option(s: &mut String, arg: &Arg) {
+    let _ = writeln!(s, ".TP");
+    let _ = writeln!(s, "\\fB{}\\fR", roff(&signature(arg)));
+    if let Some(help) = arg.get_help() {
+        let _ = writeln!(s, "{}", roff(&help.to_string()));
+    }
+    let choices: Vec<_> = arg
+        .get_possible_values()
+        .iter()
+        .filter(|v| !v.is_hide_set())
+        .map(|v| v.get_name().to_string())
+        .collect();
+    if !choices.is_empty() {
+        let _ = writeln!(s, ".br");
+        let _ = writeln!(s, "[{}]", roff(&choices.join(", ")));
+    }
+}
+
+/// The man page in roff format.
+#[must_use]
+pub fn man_page() -> String {
+    let command = command();
+    let name = command.get_name();
+    let mut s = String::new();
+    let _ = writeln!(
+        s,
+        ".TH {} 1 \"\" \"{name} {}\" \"User Commands\"",
+        name.to_uppercase(),
+        crate::VERSION
+    );
+    let _ = writeln!(s, ".SH NAME");
+    let about = command
+        .get_about()
+        .map(ToString::to_string)
+        .unwrap_or_default();
+    let _ = writeln!(s, "{} \\- {}", roff(name), roff(&about));
+    let _ = writeln!(s, ".SH SYNOPSIS");
+    let _ = writeln!(
+        s,
+        "\\fB{}\\fR [\\fIOPTIONS\\fR] [\\fIPATHS\\fR]... [\\fICOMMAND\\fR]",
+        roff(name)
+    );
+
+    let _ = writeln!(s, ".SH OPTIONS");
+    let options: Vec<&Arg> = command
+        .get_arguments()
+        .filter(|a| !a.is_positional() && !a.is_hide_set())
+        .collect();
+    // Options of one heading are spread over several structs; keep the
+    // order in which the headings first appear.
+    let mut headings: Vec<Option<&str>> = Vec::new();
+    for arg in &options {
+        if !headings.contains(&arg.get_help_heading()) {
+            headings.push(arg.get_help_heading());
+        }
+    }
+    for heading in headings {
+        if let Some(heading) = heading {
+            let _ = writeln!(s, ".SS {}", roff(heading));
+        }
+        for arg in options.iter().filter(|a| a.get_help_heading() == heading) {
+            write_man_option(&mut s, arg);
+        }
+    }
+
+    let _ = writeln!(s, ".SH COMMANDS");
+    for sub in command
+        .get_subcommands()
+        .filter(|c| !c.is_hide_set() && c.get_name() != "help")
+    {
+        let _ = writeln!(s, ".TP");
+        let _ = writeln!(s, "\\fB{}\\fR", roff(sub.get_name()));
+        if let Some(about) = sub.get_about() {
+            let _ = writeln!(s, "{}", roff(&about.to_string()));
+        }
+    }
+
+    let _ = writeln!(s, ".SH DETAILS");
+    for doc in FLAG_DOCS {
+        let _ = writeln!(s, ".SS \\-\\-{}", roff(doc.flag));
+        let _ = writeln!(s, ".nf");
+        let _ = writeln!(s, "{}", roff(doc.details));
+        let _ = writeln!(s, ".fi");
+        if !doc.examples.is_empty() {
+            let _ = writeln!(s, ".PP");
+            let _ = writeln!(s, ".nf");
+            for example in doc.examples {
+                let _ = writeln!(s, "  {}", roff(example));
+            }
+            let _ = writeln!(s, ".fi");
+        }
+    }
+    s
+}
+
+/// Prints the man page to stdout.
+///
+/// # Errors
+/// Returns an error if stdout can't be written.
+pub fn print_man() -> io::Result<()> {
+    let stdout = io::stdout();
+    let mut out = stdout.lock();
+    out.write_all(man_page().as_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn explain(query: Option<&str>) -> Result<String> {
+        let mut out = Vec::new();
+        write_explain(&mut out, query)?;
+        Ok(String::from_utf8(out).unwrap())
+    }
+
+    #[test]
+    fn test_flag_docs_name_real_flags() {
+        let command = command();
+        for doc in FLAG_DOCS {
+            assert!(find_arg(&command, doc.flag).is_some(), "--{}", doc.flag);
+            for flag in doc.see_also {
+                assert!(find_arg(&command, flag).is_some(), "--{flag}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_explain_flag() {
+        let text = explain(Some("--filter")).unwrap();
+        assert!(text.starts_with("--filter <EXPR>\n"));
+        assert!(text.contains("比較演算子"));
+        assert!(text.contains("関連: --fail-if, --min-lines, --max-lines"));
+
+        let text = explain(Some("-o")).unwrap();
+        assert!(text.starts_with("-o, --output <OUTPUT>\n"));
+
+        let text = explain(Some("format")).unwrap();
+        assert!(text.contains("    jsonl"));
+        assert!(text.contains("既定値: table"));
+
+        assert!(explain(None).unwrap().contains("  --filter"));
+    }
+
+    #[test]
+    fn test_explain_unknown_flag() {
+        let err = explain(Some("--filt")).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Unknown flag: --filt (候補: --group-filter, --filter)"
+        );
+        assert!(explain(Some("--nope")).is_err());
+    }
+
+    #[test]
+    fn test_man_page() {
+        let page = man_page();
+        assert!(page.starts_with(".TH COUNT_LINES 1"));
+        assert!(page.contains("\\fB\\-\\-filter <EXPR>\\fR"));
+        assert!(page.contains(".SS \\-\\-fail\\-if"));
+    }
+}
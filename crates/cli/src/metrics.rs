@@ -0,0 +1,279 @@
+// crates/cli/src/metrics.rs
+//! Prometheus exporter (`--serve-metrics`).
+//!
+//! The count runs every `--metrics-interval`, or on file events with
+//! `--watch`, and the latest totals are served on `/metrics` in the
+//! Prometheus text format by a minimal HTTP/1.1 responder.
+
+use crate::config::Config;
+use crate::error::Result;
+use crate::http;
+use count_lines_engine::grouping::group_stats;
+use count_lines_engine::options::GroupBy;
+use count_lines_engine::stats::{RunResult, Summary};
+use count_lines_engine::watch::{self, Changes};
+use std::fmt::Write as _;
+use std::io;
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::{Arc, PoisonError, RwLock};
+use std::time::{Duration, Instant};
+
+/// Counts periodically (or on watch events) and serves the totals on
+/// `http://{addr}/metrics` until the process is stopped.
+///
+/// # Errors
+/// Returns an error if `addr` can't be bound or the first count fails.
+pub fn serve(addr: SocketAddr, config: &Config) -> Result<()> {
+    let listener = TcpListener::bind(addr)?;
+    eprintln!(
+        "[count_lines] Serving metrics on http://{}/metrics",
+        listener.local_addr()?
+    );
+
+    let started = Instant::now();
+    let mut result = count_lines_engine::run(config)?;
+    let body = Arc::new(RwLock::new(render(
+        &result,
+        config,
+        started.elapsed(),
+        chrono::Utc::now(),
+    )));
+
+    let latest = body.clone();
+    let updater = config.clone();
+    std::thread::spawn(move || {
+        let config = updater;
+        let publish = |result: &RunResult, elapsed| {
+            let text = render(result, &config, elapsed, chrono::Utc::now());
+            *latest.write().unwrap_or_else(PoisonError::into_inner) = text;
+        };
+        if config.watch {
+            let watched = watch::watch_loop(&config, |changes: &Changes| {
+                let started = Instant::now();
+                match watch::apply_changes(&mut result, changes, &config) {
+                    Ok(()) => publish(&result, started.elapsed()),
+                    Err(e) => log::error!("Error in watch cycle: {e}"),
+                }
+            });
+            if let Err(e) = watched {
+                log::error!("Watch Error: {e}");
+            }
+        } else {
+            loop {
+                std::thread::sleep(config.metrics_interval);
+                let started = Instant::now();
+                match count_lines_engine::run(&config) {
+                    Ok(result) => publish(&result, started.elapsed()),
+                    Err(e) => log::error!("Error in metrics run: {e}"),
+                }
+            }
+        }
+    });
+
+    for stream in listener.incoming() {
+        let handled = stream.and_then(|stream| {
+            let body = body.read().unwrap_or_else(PoisonError::into_inner).clone();
+            respond(&stream, &body)
+        });
+        if let Err(e) = handled {
+            log::warn!("Metrics connection error: {e}");
+        }
+    }
+    Ok(())
+}
+
+/// Answers one request: `GET /metrics` gets `body`, anything else a 404.
+fn respond(stream: &TcpStream, body: &str) -> io::Result<()> {
+    let request = http::read_request(stream)?;
+    if request.method == "GET" && request.path == "/metrics" {
+        http::write_response(
+            stream,
+            "200 OK",
+            "text/plain; version=0.0.4; charset=utf-8",
+            body,
+        )
+    } else {
+        http::write_response(
+            stream,
+            "404 Not Found",
+            "text/plain; charset=utf-8",
+            "not found\n",
+        )
+    }
+}
+
+/// Renders the totals and per-language gauges of `result`.
+#[must_use]
+#[allow(clippy::cast_precision_loss)]
+pub fn render(
+    result: &RunResult,
+    config: &Config,
+    elapsed: Duration,
+    now: chrono::DateTime<chrono::Utc>,
+) -> String {
+    let summary = Summary::from_stats(&result.stats);
+    let groups = group_stats(&result.stats, GroupBy::Language, config);
+    let mut out = String::new();
+
+    let mut gauge = |name: &str, help: &str, samples: &[(String, f64)]| {
+        let _ = writeln!(out, "# HELP count_lines_{name} {help}");
+        let _ = writeln!(out, "# TYPE count_lines_{name} gauge");
+        for (labels, value) in samples {
+            let _ = writeln!(out, "count_lines_{name}{labels} {value}");
+        }
+    };
+    let total = |value: usize| vec![(String::new(), value as f64)];
+    gauge("files", "Files counted.", &total(summary.files));
+    gauge(
+        "lines",
+        "Lines in the counted files.",
+        &total(summary.lines),
+    );
+    gauge(
+        "chars",
+        "Characters in the counted files.",
+        &total(summary.chars),
+    );
+    if let Some(sloc) = summary.sloc {
+        gauge("sloc", "Source lines of code.", &total(sloc));
+    }
+    gauge(
+        "size_bytes",
+        "Size of the counted files in bytes.",
+        &[(String::new(), summary.size as f64)],
+    );
+    gauge(
+        "errors",
+        "Files that couldn't be counted in the last run.",
+        &total(result.error_count()),
+    );
+
+    let by_language = |value: fn(&Summary) -> Option<usize>| -> Vec<(String, f64)> {
+        groups
+            .iter()
+            .filter_map(|g| {
+                let labels = format!("{{language=\"{}\"}}", escape_label(&g.key));
+                Some((labels, value(&g.summary)? as f64))
+            })
+            .collect()
+    };
+    gauge(
+        "language_files",
+        "Files counted per language.",
+        &by_language(|s| Some(s.files)),
+    );
+    gauge(
+        "language_lines",
+        "Lines per language.",
+        &by_language(|s| Some(s.lines)),
+    );
+    if summary.sloc.is_some() {
+        gauge(
+            "language_sloc",
+            "Source lines of code per language.",
+            &by_language(|s| s.sloc),
+        );
+    }
+
+    gauge(
+        "last_run_timestamp_seconds",
+        "Unix time of the last count.",
+        &[(String::new(), now.timestamp() as f64)],
+    );
+    gauge(
+        "run_duration_seconds",
+        "How long the last count took.",
+        &[(String::new(), elapsed.as_secs_f64())],
+    );
+    out
+}
+
+/// Escapes a label value as the text format requires.
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use count_lines_engine::stats::FileStats;
+    use std::io::{Read, Write};
+    use std::path::PathBuf;
+
+    fn result() -> RunResult {
+        let file = |path: &str, ext: &str, lines| FileStats {
+            path: PathBuf::from(path),
+            ext: ext.to_string(),
+            lines,
+            sloc: Some(lines / 2),
+            ..FileStats::default()
+        };
+        RunResult {
+            stats: vec![
+                file("a.rs", "rs", 10),
+                file("b.rs", "rs", 4),
+                file("c.py", "py", 6),
+            ],
+            ..RunResult::default()
+        }
+    }
+
+    #[test]
+    fn test_render_totals_and_language_gauges() {
+        let now = chrono::DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+        let text = render(
+            &result(),
+            &Config::default(),
+            Duration::from_millis(250),
+            now,
+        );
+        let lines: Vec<&str> = text.lines().collect();
+
+        assert!(lines.contains(&"# TYPE count_lines_files gauge"));
+        assert!(lines.contains(&"count_lines_files 3"));
+        assert!(lines.contains(&"count_lines_lines 20"));
+        assert!(lines.contains(&"count_lines_sloc 10"));
+        assert!(lines.contains(&"count_lines_language_lines{language=\"Rust\"} 14"));
+        assert!(lines.contains(&"count_lines_language_sloc{language=\"Python\"} 3"));
+        assert!(lines.contains(&"count_lines_last_run_timestamp_seconds 1700000000"));
+        assert!(lines.contains(&"count_lines_run_duration_seconds 0.25"));
+    }
+
+    #[test]
+    fn test_escape_label() {
+        assert_eq!(escape_label("a\"b\\c\nd"), "a\\\"b\\\\c\\nd");
+    }
+
+    #[test]
+    fn test_respond_serves_metrics_only() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let request = |path: &'static str| {
+            std::thread::spawn(move || {
+                let mut stream = TcpStream::connect(addr).unwrap();
+                write!(stream, "GET {path} HTTP/1.1\r\nHost: x\r\n\r\n").unwrap();
+                let mut response = String::new();
+                stream.read_to_string(&mut response).unwrap();
+                response
+            })
+        };
+
+        let client = request("/metrics");
+        respond(&listener.accept().unwrap().0, "count_lines_files 1\n").unwrap();
+        let response = client.join().unwrap();
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(response.ends_with("\r\n\r\ncount_lines_files 1\n"));
+
+        let client = request("/");
+        respond(&listener.accept().unwrap().0, "count_lines_files 1\n").unwrap();
+        assert!(
+            client
+                .join()
+                .unwrap()
+                .starts_with("HTTP/1.1 404 Not Found\r\n")
+        );
+    }
+}
@@ -0,0 +1,182 @@
+// crates/cli/src/numbers.rs
+//! Number formatting for the outputs read by people (table, tree,
+//! Markdown): `--human`, `--thousands` and `--bytes`. CSV, JSON and the
+//! other machine-readable formats always get plain numbers.
+
+use crate::config::Config;
+
+/// How counts and byte sizes are written.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct NumberFormat {
+    /// Abbreviate counts (`1.2M`) and write sizes in binary units (`3.4 GiB`).
+    pub human: bool,
+    /// Digit group separator for unabbreviated numbers.
+    pub separator: Option<String>,
+    /// Write sizes as byte counts even with `human`.
+    pub raw_bytes: bool,
+}
+
+impl NumberFormat {
+    #[must_use]
+    pub fn new(config: &Config) -> Self {
+        Self {
+            human: config.human_numbers,
+            separator: config.thousands_separator.clone(),
+            raw_bytes: config.raw_bytes,
+        }
+    }
+
+    /// A line, character or file count.
+    #[must_use]
+    pub fn count(&self, value: usize) -> String {
+        if self.human {
+            abbreviate(value)
+        } else {
+            self.digits(value as u64)
+        }
+    }
+
+    /// A count that may not have been measured; empty if it wasn't.
+    #[must_use]
+    pub fn optional(&self, value: Option<usize>) -> String {
+        value.map(|v| self.count(v)).unwrap_or_default()
+    }
+
+    /// A size in bytes.
+    #[must_use]
+    pub fn bytes(&self, value: u64) -> String {
+        if self.human && !self.raw_bytes {
+            size_label(value)
+        } else {
+            self.digits(value)
+        }
+    }
+
+    fn digits(&self, value: u64) -> String {
+        let digits = value.to_string();
+        let Some(separator) = &self.separator else {
+            return digits;
+        };
+        let mut out = String::with_capacity(digits.len() + digits.len() / 3 * separator.len());
+        for (i, digit) in digits.chars().enumerate() {
+            if i > 0 && (digits.len() - i).is_multiple_of(3) {
+                out.push_str(separator);
+            }
+            out.push(digit);
+        }
+        out
+    }
+}
+
+/// The digit group separator of the locale in `LC_ALL`, `LC_NUMERIC` or
+/// `LANG`, e.g. `.` for `de_DE.UTF-8`; `,` when unset or unknown.
+#[must_use]
+pub fn locale_separator() -> &'static str {
+    let locale = ["LC_ALL", "LC_NUMERIC", "LANG"]
+        .into_iter()
+        .filter_map(|name| std::env::var(name).ok())
+        .find(|value| !value.is_empty())
+        .unwrap_or_default();
+    separator_for(&locale)
+}
+
+fn separator_for(locale: &str) -> &'static str {
+    let tag = locale.split(['.', '@']).next().unwrap_or_default();
+    let (language, region) = tag.split_once(['_', '-']).unwrap_or((tag, ""));
+    match (language, region) {
+        ("de" | "it" | "fr", "CH") => "'",
+        ("de" | "es" | "it" | "nl" | "pt" | "id" | "tr" | "da" | "el", _) => ".",
+        ("fr" | "ru" | "pl" | "cs" | "sk" | "sv" | "fi" | "nb" | "no" | "uk" | "hu", _) => " ",
+        _ => ",",
+    }
+}
+
+/// Shortens large counts: `12345` → `12.3k`, `2500000` → `2.5M`.
+#[allow(clippy::cast_precision_loss)]
+#[must_use]
+pub fn abbreviate(value: usize) -> String {
+    let (scaled, suffix) = match value {
+        0..1_000 => return value.to_string(),
+        1_000..1_000_000 => (value as f64 / 1_000.0, "k"),
+        1_000_000..1_000_000_000 => (value as f64 / 1_000_000.0, "M"),
+        _ => (value as f64 / 1_000_000_000.0, "G"),
+    };
+    let text = format!("{scaled:.1}");
+    format!("{}{suffix}", text.strip_suffix(".0").unwrap_or(&text))
+}
+
+/// A byte count in the largest binary unit it fills, e.g. `16 KiB`, `1.5 MiB`.
+#[allow(clippy::cast_precision_loss)]
+#[must_use]
+pub fn size_label(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KiB", "MiB", "GiB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit + 1 < UNITS.len() {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if value.fract() == 0.0 {
+        format!("{value:.0} {}", UNITS[unit])
+    } else {
+        format!("{value:.1} {}", UNITS[unit])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_abbreviate() {
+        assert_eq!(abbreviate(999), "999");
+        assert_eq!(abbreviate(1_000), "1k");
+        assert_eq!(abbreviate(12_345), "12.3k");
+        assert_eq!(abbreviate(2_500_000), "2.5M");
+        assert_eq!(abbreviate(3_400_000_000), "3.4G");
+    }
+
+    #[test]
+    fn test_size_label() {
+        assert_eq!(size_label(512), "512 B");
+        assert_eq!(size_label(16 << 10), "16 KiB");
+        assert_eq!(size_label(3 << 19), "1.5 MiB");
+    }
+
+    #[test]
+    fn test_number_format() {
+        let plain = NumberFormat::default();
+        assert_eq!(plain.count(1_234_567), "1234567");
+        assert_eq!(plain.bytes(2048), "2048");
+
+        let grouped = NumberFormat {
+            separator: Some(",".to_string()),
+            ..NumberFormat::default()
+        };
+        assert_eq!(grouped.count(1_234_567), "1,234,567");
+        assert_eq!(grouped.count(123), "123");
+        assert_eq!(grouped.optional(None), "");
+
+        let human = NumberFormat {
+            human: true,
+            ..grouped
+        };
+        assert_eq!(human.count(1_234_567), "1.2M");
+        assert_eq!(human.bytes(3 << 30), "3 GiB");
+        let raw = NumberFormat {
+            raw_bytes: true,
+            ..human
+        };
+        assert_eq!(raw.bytes(3 << 30), "3,221,225,472");
+    }
+
+    #[test]
+    fn test_locale_separators() {
+        assert_eq!(separator_for("de_DE.UTF-8"), ".");
+        assert_eq!(separator_for("de_CH.UTF-8"), "'");
+        assert_eq!(separator_for("fr_FR"), " ");
+        assert_eq!(separator_for("ja_JP.UTF-8"), ",");
+        assert_eq!(separator_for("C"), ",");
+        assert_eq!(separator_for(""), ",");
+    }
+}
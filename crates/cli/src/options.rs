@@ -1,5 +1,6 @@
 // crates/cli/src/options.rs
 use clap::ValueEnum;
+use count_lines_engine::options::Column;
 use serde::{Deserialize, Serialize};
 use std::str::FromStr;
 
@@ -11,17 +12,186 @@ pub enum OutputFormat {
     Tsv,
     Json,
     Yaml,
+    /// ファイルごとの Markdown テーブル
     Md,
+    /// 言語別 (または --by のグループ別) の Markdown サマリ表
+    Markdown,
     Jsonl,
+    /// ディレクトリごとの集計をツリー表示
+    Tree,
+    /// GitHub Actions の注釈 (::warning / ::notice ワークフローコマンド)
+    Github,
+    /// SARIF 2.1.0 (--highlight-over・--fail-if・lint の違反をコードスキャン結果として出力)
+    Sarif,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Serialize, Deserialize)]
+#[value(rename_all = "lowercase")]
+pub enum GroupBy {
+    /// 言語名ごと
+    #[value(alias = "lang")]
+    Language,
+    /// 拡張子ごと
+    Ext,
+    /// .gitattributes の Linguist 属性ごと (vendored / documentation / source)
+    Linguist,
+    /// 最終更新からの経過時間ごと (区切りは --age-buckets)
+    Age,
+    /// 走査ルート直下のディレクトリごと
+    #[value(name = "root-dir")]
+    RootDir,
+    /// 最も近い Cargo.toml の crate ごと
+    Crate,
+    /// CODEOWNERS の担当者ごと
+    Owner,
+    /// git サブモジュールごと (--include-submodules と併用)
+    Submodule,
+    /// 走査ルートごと (複数のルートを指定した場合にルート間を比較)
+    Root,
+    /// git blame で行を書いた作者ごと (ファイルごとに git blame を実行するため遅い。結果はキャッシュ)
+    Author,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Serialize, Deserialize)]
+#[value(rename_all = "lowercase")]
+pub enum RatioMetric {
+    /// ファイル数
+    Files,
+    /// 行数
+    Lines,
+    /// SLOC
+    Sloc,
+    /// 文字数
+    Chars,
+    /// 単語数
+    Words,
+    /// バイト数
+    Size,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum, Serialize, Deserialize)]
+#[value(rename_all = "lowercase")]
+pub enum RatioBase {
+    /// 全体の合計に対する割合
+    #[default]
+    Total,
+    /// 1 つ外側のグループの小計に対する割合 (--by を複数指定したとき)
+    Group,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum, Serialize, Deserialize)]
+#[value(rename_all = "lowercase")]
+pub enum LimitAction {
+    /// エラーで終了
+    #[default]
+    Abort,
+    /// 走査を打ち切り、それまでに見つかったファイルの結果を警告付きで出力
+    Truncate,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum, Serialize, Deserialize)]
+#[value(rename_all = "lowercase")]
+pub enum ColorChoice {
+    /// 出力先が端末で NO_COLOR が未設定のときだけ色を付ける
+    #[default]
+    Auto,
+    /// 常に色を付ける (NO_COLOR より優先)
+    Always,
+    /// 色を付けない
+    Never,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum, Serialize, Deserialize)]
+#[value(rename_all = "lowercase")]
+pub enum Theme {
+    /// 暗い背景向けの明るい色
+    #[default]
+    Default,
+    /// 明るい背景向けの濃い色
+    Light,
+    /// 色を使わず太字・下線・反転のみ
+    Mono,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Serialize, Deserialize)]
+#[value(rename_all = "lowercase")]
+pub enum Shell {
+    Bash,
+    Zsh,
+    Fish,
+    /// PowerShell
+    Powershell,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Serialize, Deserialize)]
+#[value(rename_all = "lowercase")]
+pub enum LinguistMode {
+    /// vendored / documentation のファイルを除外
+    Exclude,
+    /// 除外せず JSON の linguist フィールドに分類を記録
+    Tag,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Serialize, Deserialize)]
+#[value(rename_all = "lowercase")]
+pub enum CacheKey {
+    /// サイズと更新日時が同じなら未変更とみなす (読み込み不要で高速)
+    Mtime,
+    /// 内容のハッシュが同じなら未変更とみなす (更新日時が保たれた書き換えも検出)
+    Hash,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Serialize, Deserialize)]
+#[value(rename_all = "lowercase")]
+pub enum LogLevel {
+    Off,
+    Error,
+    Warn,
+    Info,
+    /// 走査・計測・集計の各フェーズの所要時間やキャッシュのヒット数
+    Debug,
+    /// 依存クレート (ignore など) のログも含める
+    Trace,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Serialize, Deserialize)]
+#[value(rename_all = "lowercase")]
+pub enum LogFormat {
+    /// `[count_lines] WARN メッセージ` 形式
+    Text,
+    /// 1 行 1 オブジェクトの JSON (timestamp, level, target, message)
+    Json,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Serialize, Deserialize)]
 #[value(rename_all = "lowercase")]
 pub enum WatchOutput {
     Full,
+    /// 変更ファイルとサイクルごとのサマリを JSON Lines で出力
+    #[value(alias = "ndjson")]
     Jsonl,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Serialize, Deserialize)]
+#[value(rename_all = "lowercase")]
+pub enum CompareFormat {
+    Table,
+    Json,
+    #[value(alias = "md")]
+    Markdown,
+    /// GitHub Actions の注釈 (変更ファイルごとの ::notice)
+    Github,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Serialize, Deserialize)]
+#[value(rename_all = "lowercase")]
+pub enum CompareSort {
+    /// パス順
+    Path,
+    /// 行数の増減 (絶対値) が大きい順
+    Delta,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum SortKey {
     Lines,
@@ -32,8 +202,139 @@ pub enum SortKey {
     Ext,
     /// SLOC (Source Lines of Code)
     Sloc,
+    /// Longest line length
+    MaxLine,
+    /// Average line length
+    AvgLine,
+    /// Full path
+    Path,
+    /// Share of the total line count
+    Ratio,
+    /// Deepest indentation level
+    MaxDepth,
+    /// Average indentation level
+    AvgDepth,
 }
 
+/// `--columns`: comma-separated column names, in output order.
+#[derive(Debug, Clone)]
+pub struct ColumnSpec(pub Vec<Column>);
+
+impl FromStr for ColumnSpec {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let columns = s
+            .split(',')
+            .map(str::trim)
+            .filter(|c| !c.is_empty())
+            .map(|name| {
+                Column::ALL
+                    .into_iter()
+                    .find(|c| c.name().eq_ignore_ascii_case(name))
+                    .ok_or_else(|| {
+                        let valid: Vec<_> = Column::ALL.iter().map(|c| c.name()).collect();
+                        format!(
+                            "Unknown column: {name} (valid columns: {})",
+                            valid.join(", ")
+                        )
+                    })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        if columns.is_empty() {
+            return Err("No columns given".to_string());
+        }
+        Ok(Self(columns))
+    }
+}
+
+/// `--badge`: `METRIC[:LANGUAGE]`, e.g. `lines` or `sloc:rust`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BadgeSpec {
+    pub metric: RatioMetric,
+    pub language: Option<String>,
+}
+
+impl FromStr for BadgeSpec {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (metric, language) = match s.split_once(':') {
+            Some((metric, language)) => (metric.trim(), Some(language.trim())),
+            None => (s.trim(), None),
+        };
+        let metric = <RatioMetric as ValueEnum>::from_str(metric, true)
+            .map_err(|_| format!("Unknown badge metric: {metric}"))?;
+        if language == Some("") {
+            return Err("Empty language after ':'".to_string());
+        }
+        Ok(Self {
+            metric,
+            language: language.map(str::to_string),
+        })
+    }
+}
+
+/// `--badge-colors`: comma-separated `MIN=COLOR` steps, e.g.
+/// `0=red,10k=yellow,100k=brightgreen`, sorted by `MIN`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BadgeColors(pub Vec<(u64, String)>);
+
+impl FromStr for BadgeColors {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut steps = s
+            .split(',')
+            .map(str::trim)
+            .filter(|p| !p.is_empty())
+            .map(|step| {
+                let (min, color) = step
+                    .split_once('=')
+                    .ok_or_else(|| format!("Expected MIN=COLOR: {step}"))?;
+                let color = color.trim();
+                if color.is_empty() {
+                    return Err(format!("Missing color: {step}"));
+                }
+                Ok((parse_count(min.trim())?, color.to_string()))
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+        if steps.is_empty() {
+            return Err("No colors given".to_string());
+        }
+        steps.sort_by_key(|&(min, _)| min);
+        Ok(Self(steps))
+    }
+}
+
+/// A count with an optional decimal `k` / `M` / `G` suffix (`10k`, `1.5M`).
+fn parse_count(s: &str) -> Result<u64, String> {
+    let lower = s.replace('_', "").to_ascii_lowercase();
+    let (number, multiplier) = match lower.strip_suffix(['k', 'm', 'g']) {
+        Some(number) => {
+            let multiplier = match lower.chars().last() {
+                Some('k') => 1e3,
+                Some('m') => 1e6,
+                _ => 1e9,
+            };
+            (number, multiplier)
+        }
+        None => (lower.as_str(), 1.0),
+    };
+    let value: f64 = number.parse().map_err(|_| format!("Invalid count: {s}"))?;
+    if !value.is_finite() || value < 0.0 {
+        return Err(format!("Invalid count: {s}"));
+    }
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    Ok((value * multiplier).round() as u64)
+}
+
+/// The key names accepted by `--sort`.
+pub const SORT_KEYS: [&str; 13] = [
+    "lines", "chars", "words", "size", "name", "ext", "sloc", "maxline", "avgline", "path",
+    "ratio", "maxdepth", "avgdepth",
+];
+
 #[derive(Debug, Clone)]
 pub struct SortSpec(pub Vec<(SortKey, bool)>);
 
@@ -53,9 +354,18 @@ impl FromStr for SortSpec {
 }
 
 fn parse_single_spec(part: &str) -> Result<(SortKey, bool), String> {
-    let (key_str, desc) = part.split_once(':').map_or((part, false), |(k, d)| {
-        (k.trim(), matches!(d.trim(), "desc" | "DESC"))
-    });
+    let (key_str, direction) = part
+        .split_once(':')
+        .map_or((part, ""), |(k, d)| (k.trim(), d.trim()));
+    let desc = match direction.to_ascii_lowercase().as_str() {
+        "" | "asc" => false,
+        "desc" => true,
+        other => {
+            return Err(format!(
+                "Unknown sort direction: {other} (expected asc or desc)"
+            ));
+        }
+    };
 
     let key = parse_sort_key(key_str)?;
     Ok((key, desc))
@@ -70,6 +380,59 @@ fn parse_sort_key(key_str: &str) -> Result<SortKey, String> {
         "name" => Ok(SortKey::Name),
         "ext" => Ok(SortKey::Ext),
         "sloc" => Ok(SortKey::Sloc),
+        "maxline" => Ok(SortKey::MaxLine),
+        "avgline" => Ok(SortKey::AvgLine),
+        "path" => Ok(SortKey::Path),
+        "ratio" => Ok(SortKey::Ratio),
+        "maxdepth" => Ok(SortKey::MaxDepth),
+        "avgdepth" => Ok(SortKey::AvgDepth),
         other => Err(format!("Unknown sort key: {other}")),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sort_spec() {
+        let spec: SortSpec = "sloc:desc, path:ASC,ratio".parse().unwrap();
+        assert_eq!(
+            spec.0,
+            [
+                (SortKey::Sloc, true),
+                (SortKey::Path, false),
+                (SortKey::Ratio, false)
+            ]
+        );
+        let err = "lines:down".parse::<SortSpec>().unwrap_err();
+        assert_eq!(err, "Unknown sort direction: down (expected asc or desc)");
+        assert!("comments".parse::<SortSpec>().is_err());
+    }
+
+    #[test]
+    fn test_badge_spec() {
+        let spec: BadgeSpec = "SLOC:Rust".parse().unwrap();
+        assert_eq!(spec.metric, RatioMetric::Sloc);
+        assert_eq!(spec.language.as_deref(), Some("Rust"));
+        assert_eq!("lines".parse::<BadgeSpec>().unwrap().language, None);
+        assert!("comments".parse::<BadgeSpec>().is_err());
+        assert!("lines:".parse::<BadgeSpec>().is_err());
+    }
+
+    #[test]
+    fn test_badge_colors() {
+        let colors: BadgeColors = "100k=brightgreen, 0=red,1.5k=yellow".parse().unwrap();
+        assert_eq!(
+            colors.0,
+            [
+                (0, "red".to_string()),
+                (1500, "yellow".to_string()),
+                (100_000, "brightgreen".to_string())
+            ]
+        );
+        assert!("10k".parse::<BadgeColors>().is_err());
+        assert!("ten=red".parse::<BadgeColors>().is_err());
+        assert!("10=".parse::<BadgeColors>().is_err());
+    }
+}
@@ -1,5 +1,12 @@
 // crates/cli/src/parsers.rs
 use chrono::{DateTime, FixedOffset, Local, NaiveDate, NaiveDateTime, TimeZone};
+use clap::builder::{PossibleValue, StringValueParser, TypedValueParser};
+use count_lines_core::language::LanguageDefinition;
+use count_lines_engine::encoding::Encoding;
+use count_lines_engine::plugin::Plugin;
+use serde::Deserialize;
+use std::ffi::OsStr;
+use std::marker::PhantomData;
 use std::{fmt::Display, str::FromStr};
 
 /// Wrapper type to parse sizes with optional suffixes (e.g. 10K, 5MiB).
@@ -75,6 +82,68 @@ fn try_date_format(s: &str) -> Option<DateTimeArg> {
         .map(DateTimeArg)
 }
 
+/// Wrapper type to load language definitions from a TOML file.
+///
+/// The file may contain a single definition at the top level, or several
+/// under `[[language]]` tables.
+#[derive(Debug, Clone)]
+pub struct LanguageDefArg(pub Vec<LanguageDefinition>);
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum LanguageDefFile {
+    Many { language: Vec<LanguageDefinition> },
+    One(LanguageDefinition),
+}
+
+impl std::str::FromStr for LanguageDefArg {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let content = std::fs::read_to_string(s)
+            .map_err(|err| format!("Cannot read language definition '{s}': {err}"))?;
+        parse_language_defs(&content)
+            .map_err(|err| format!("Invalid language definition '{s}': {err}"))
+    }
+}
+
+fn parse_language_defs(content: &str) -> Result<LanguageDefArg, String> {
+    let defs = match toml::from_str(content).map_err(|err| err.to_string())? {
+        LanguageDefFile::Many { language } => language,
+        LanguageDefFile::One(def) => vec![def],
+    };
+    for def in &defs {
+        def.validate()
+            .map_err(|err| format!("{}: {err}", def.name))?;
+    }
+    Ok(LanguageDefArg(defs))
+}
+
+/// Wrapper type to load glob patterns from a file (`--include-from` /
+/// `--exclude-from`): one pattern per line, blank lines and lines starting
+/// with `#` ignored.
+#[derive(Debug, Clone)]
+pub struct PatternFileArg(pub Vec<String>);
+
+impl std::str::FromStr for PatternFileArg {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let content = std::fs::read_to_string(s)
+            .map_err(|err| format!("Cannot read pattern file '{s}': {err}"))?;
+        Ok(parse_pattern_lines(&content))
+    }
+}
+
+fn parse_pattern_lines(content: &str) -> PatternFileArg {
+    PatternFileArg(
+        content
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(ToString::to_string)
+            .collect(),
+    )
+}
+
 fn parse_bounded_number<T>(s: &str, min: T, max: Option<T>) -> Result<T, String>
 where
     T: Copy + PartialOrd + Display + FromStr,
@@ -118,6 +187,96 @@ pub fn parse_positive_u64(s: &str) -> Result<u64, String> {
     parse_bounded_number(s, 1, None)
 }
 
+/// Parse a timeout such as `500ms`, `10s`, `10m` or `1h`.
+///
+/// # Errors
+/// Returns an error if the number or the unit is missing or invalid, or the
+/// timeout is zero.
+pub fn parse_timeout(s: &str) -> Result<std::time::Duration, String> {
+    let s = s.trim();
+    let split = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+    let (number, unit) = s.split_at(split);
+    let n: u64 = number
+        .parse()
+        .map_err(|_| format!("invalid timeout '{s}': expected e.g. 10s, 10m"))?;
+    let millis = match unit {
+        "ms" => Some(n),
+        "s" => n.checked_mul(1000),
+        "m" => n.checked_mul(60_000),
+        "h" => n.checked_mul(3_600_000),
+        _ => return Err(format!("invalid timeout unit in '{s}': use ms, s, m or h")),
+    };
+    match millis {
+        Some(0) => Err(format!("timeout must be positive: '{s}'")),
+        Some(millis) => Ok(std::time::Duration::from_millis(millis)),
+        None => Err(format!("timeout out of range: '{s}'")),
+    }
+}
+
+/// Parse an age such as `30d`, `12h`, `90m`, `45s` or `2w`.
+///
+/// # Errors
+/// Returns an error if the number or the unit is missing or invalid.
+pub fn parse_age(s: &str) -> Result<chrono::Duration, String> {
+    let s = s.trim();
+    let split = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+    let (number, unit) = s.split_at(split);
+    let n: i64 = number
+        .parse()
+        .map_err(|_| format!("invalid age '{s}': expected e.g. 30d, 12h, 90m"))?;
+    let age = match unit {
+        "s" => chrono::Duration::try_seconds(n),
+        "m" => chrono::Duration::try_minutes(n),
+        "h" => chrono::Duration::try_hours(n),
+        "d" => chrono::Duration::try_days(n),
+        "w" => chrono::Duration::try_weeks(n),
+        _ => return Err(format!("invalid age unit in '{s}': use s, m, h, d or w")),
+    };
+    age.ok_or_else(|| format!("age out of range: '{s}'"))
+}
+
+/// Parses a point in time given as an age before now ([`parse_age`], e.g.
+/// `90d`) or as a date ([`DateTimeArg`]).
+///
+/// # Errors
+/// Returns an error if the value is neither.
+pub fn parse_since(s: &str) -> Result<DateTimeArg, String> {
+    if s.starts_with(|c: char| c.is_ascii_digit())
+        && let Ok(age) = parse_age(s)
+    {
+        return Ok(DateTimeArg(Local::now() - age));
+    }
+    s.parse()
+        .map_err(|_| format!("invalid time '{s}': expected an age (e.g. 90d) or a date"))
+}
+
+/// Parses one `--age-buckets` boundary: an [`parse_age`] value, or a number
+/// of months (`mo`, 30 days) or years (`y`, 365 days).
+///
+/// # Errors
+/// Returns an error if the age is malformed or not positive.
+pub fn parse_age_bucket(s: &str) -> Result<chrono::Duration, String> {
+    let s = s.trim();
+    let calendar = [("mo", 30), ("y", 365)]
+        .into_iter()
+        .find_map(|(unit, days)| Some((s.strip_suffix(unit)?, days)));
+    let age = match calendar {
+        Some((number, days)) => {
+            let n: i64 = number
+                .parse()
+                .map_err(|_| format!("invalid age '{s}': expected e.g. 1w, 6mo, 1y"))?;
+            n.checked_mul(days)
+                .and_then(chrono::Duration::try_days)
+                .ok_or_else(|| format!("age out of range: '{s}'"))?
+        }
+        None => parse_age(s)?,
+    };
+    if age <= chrono::Duration::zero() {
+        return Err(format!("age must be positive: '{s}'"));
+    }
+    Ok(age)
+}
+
 /// Parse a key=value pair string into a tuple.
 ///
 /// # Errors
@@ -128,6 +287,96 @@ pub fn parse_key_val(s: &str) -> Result<(String, String), String> {
         .ok_or_else(|| format!("Expected key=val: {s}"))
 }
 
+/// Parse an extension mapping such as `inc=php` or `.TXT=sql`.
+///
+/// Both sides are normalized to lowercase without a leading dot, so the
+/// mapping applies regardless of how the extension is spelled on disk.
+///
+/// # Errors
+/// Returns an error if the input is not `ext=lang` or either side is empty.
+pub fn parse_ext_mapping(s: &str) -> Result<(String, String), String> {
+    let (ext, lang) = parse_key_val(s)?;
+    let normalize = |v: &str| v.trim().trim_start_matches('.').to_lowercase();
+    let (ext, lang) = (normalize(&ext), normalize(&lang));
+    if ext.is_empty() || lang.is_empty() {
+        return Err(format!("Expected ext=lang (e.g. inc=php): {s}"));
+    }
+    Ok((ext, lang))
+}
+
+/// Parse a plugin such as `dat=./my_counter` or `cfg,ini=/opt/bin/counter`.
+///
+/// The extensions are normalized like those of [`parse_ext_mapping`].
+///
+/// # Errors
+/// Returns an error if the input is not `ext[,ext...]=command` or an
+/// extension or the command is empty.
+pub fn parse_plugin(s: &str) -> Result<Plugin, String> {
+    let usage = || format!("Expected ext[,ext...]=command (e.g. dat=./my_counter): {s}");
+    let (exts, command) = s.split_once('=').ok_or_else(usage)?;
+    let extensions: Vec<String> = exts
+        .split(',')
+        .map(|e| e.trim().trim_start_matches('.').to_lowercase())
+        .collect();
+    if command.trim().is_empty() || extensions.iter().any(String::is_empty) {
+        return Err(usage());
+    }
+    Ok(Plugin {
+        extensions,
+        command: command.trim().into(),
+    })
+}
+
+/// Parse an encoding label such as `sjis`, `euc-jp` or `latin1`.
+///
+/// # Errors
+/// Returns an error if the label is not a known WHATWG encoding label.
+pub fn parse_encoding(s: &str) -> Result<&'static Encoding, String> {
+    count_lines_engine::encoding::for_label(s).ok_or_else(|| {
+        format!("Unknown encoding '{s}' (e.g. utf-8, sjis, euc-jp, latin1, utf-16le)")
+    })
+}
+
+/// Parses a comma-separated list (`--sort`, `--columns`) with its `FromStr`,
+/// offering `names` as the possible values so shell completion knows them.
+#[derive(Debug, Clone)]
+pub struct ListParser<T> {
+    names: Vec<&'static str>,
+    value: PhantomData<fn() -> T>,
+}
+
+impl<T> ListParser<T> {
+    #[must_use]
+    pub fn new(names: impl IntoIterator<Item = &'static str>) -> Self {
+        Self {
+            names: names.into_iter().collect(),
+            value: PhantomData,
+        }
+    }
+}
+
+impl<T> TypedValueParser for ListParser<T>
+where
+    T: FromStr<Err = String> + Clone + Send + Sync + 'static,
+{
+    type Value = T;
+
+    fn parse_ref(
+        &self,
+        cmd: &clap::Command,
+        arg: Option<&clap::Arg>,
+        value: &OsStr,
+    ) -> Result<T, clap::Error> {
+        StringValueParser::new()
+            .try_map(|s| s.parse::<T>())
+            .parse_ref(cmd, arg, value)
+    }
+
+    fn possible_values(&self) -> Option<Box<dyn Iterator<Item = PossibleValue> + '_>> {
+        Some(Box::new(self.names.iter().copied().map(PossibleValue::new)))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -172,6 +421,128 @@ mod tests {
     fn test_parse_key_val_error() {
         assert!(parse_key_val("no_equals").is_err());
     }
+
+    #[test]
+    fn test_parse_ext_mapping_normalizes() {
+        assert_eq!(
+            parse_ext_mapping(".INC=PHP").unwrap(),
+            ("inc".to_string(), "php".to_string())
+        );
+        assert_eq!(
+            parse_ext_mapping("txt = sql").unwrap(),
+            ("txt".to_string(), "sql".to_string())
+        );
+        assert!(parse_ext_mapping("=php").is_err());
+        assert!(parse_ext_mapping("inc=").is_err());
+        assert!(parse_ext_mapping("inc").is_err());
+    }
+
+    #[test]
+    fn test_parse_plugin() {
+        let plugin = parse_plugin(".DAT, rec=./bin/my_counter").unwrap();
+        assert_eq!(plugin.extensions, ["dat", "rec"]);
+        assert_eq!(plugin.command, std::path::PathBuf::from("./bin/my_counter"));
+        assert!(parse_plugin("./my_counter").is_err());
+        assert!(parse_plugin("dat=").is_err());
+        assert!(parse_plugin("dat,=./my_counter").is_err());
+    }
+
+    #[test]
+    fn test_language_def_single() {
+        let defs = parse_language_defs(
+            r#"
+            name = "mylang"
+            extensions = ["myl"]
+            line_comments = ["//"]
+            block_comments = [["/*", "*/"]]
+            nested_comments = true
+            "#,
+        )
+        .unwrap();
+        assert_eq!(defs.0.len(), 1);
+        assert_eq!(defs.0[0].block_comments[0].1, "*/");
+        assert!(defs.0[0].nested_comments);
+    }
+
+    #[test]
+    fn test_language_def_many() {
+        let defs = parse_language_defs(
+            r#"
+            [[language]]
+            name = "a"
+            extensions = ["a"]
+
+            [[language]]
+            name = "b"
+            extensions = ["b"]
+            line_comments = [";"]
+            "#,
+        )
+        .unwrap();
+        assert_eq!(defs.0.len(), 2);
+    }
+
+    #[test]
+    fn test_parse_timeout() {
+        use std::time::Duration;
+        assert_eq!(parse_timeout("10s"), Ok(Duration::from_secs(10)));
+        assert_eq!(parse_timeout("10m"), Ok(Duration::from_secs(600)));
+        assert_eq!(parse_timeout("250ms"), Ok(Duration::from_millis(250)));
+        assert!(parse_timeout("0s").is_err());
+        assert!(parse_timeout("10").is_err());
+        assert!(parse_timeout("1d").is_err());
+    }
+
+    #[test]
+    fn test_parse_age() {
+        assert_eq!(parse_age("30d"), Ok(chrono::Duration::days(30)));
+        assert_eq!(parse_age("12h"), Ok(chrono::Duration::hours(12)));
+        assert_eq!(parse_age("2w"), Ok(chrono::Duration::weeks(2)));
+        assert!(parse_age("30").is_err());
+        assert!(parse_age("d").is_err());
+        assert!(parse_age("5y").is_err());
+    }
+
+    #[test]
+    fn test_parse_since() {
+        let since = parse_since("90d").unwrap().0;
+        let age = Local::now() - since;
+        assert!(age >= chrono::Duration::days(90) && age < chrono::Duration::days(91));
+        assert_eq!(
+            parse_since("2024-05-01").unwrap().0,
+            Local.with_ymd_and_hms(2024, 5, 1, 0, 0, 0).unwrap()
+        );
+        assert!(parse_since("yesterday").is_err());
+    }
+
+    #[test]
+    fn test_parse_age_bucket() {
+        assert_eq!(parse_age_bucket("6mo"), Ok(chrono::Duration::days(180)));
+        assert_eq!(parse_age_bucket("1y"), Ok(chrono::Duration::days(365)));
+        assert_eq!(parse_age_bucket("2w"), Ok(chrono::Duration::weeks(2)));
+        assert!(parse_age_bucket("0d").is_err());
+        assert!(parse_age_bucket("mo").is_err());
+    }
+
+    #[test]
+    fn test_pattern_lines_skip_comments_and_blanks() {
+        let patterns = parse_pattern_lines("# generated\n\ntarget/**\n  *.min.js  \r\n#*.md\n");
+        assert_eq!(patterns.0, vec!["target/**", "*.min.js"]);
+    }
+
+    #[test]
+    fn test_pattern_file_missing() {
+        let err = "/nonexistent/patterns.txt"
+            .parse::<PatternFileArg>()
+            .unwrap_err();
+        assert!(err.starts_with("Cannot read pattern file"));
+    }
+
+    #[test]
+    fn test_language_def_rejects_invalid() {
+        assert!(parse_language_defs("name = \"x\"").is_err());
+        assert!(parse_language_defs("name = \"x\"\nextensions = [\"x\"]\nunknown = 1").is_err());
+    }
 }
 
 #[cfg(test)]
@@ -1,9 +1,29 @@
 // crates/cli/src/presentation.rs
 use crate::config::Config;
-use count_lines_engine::options::{OutputFormat, SortKey, WatchOutput};
-use count_lines_engine::stats::FileStats;
+use crate::github::{Annotation, Level};
+use crate::numbers::{NumberFormat, abbreviate, size_label};
+use crate::report::{GroupReport, JsonReport, RunMetadata, RunTimings};
+use crate::style::Styler;
+use count_lines_engine::encoding::EncodingReport;
+use count_lines_engine::grouping::{self, DirStats, DirTree, GroupStats, Ratio};
+use count_lines_engine::options::{
+    Badge, Column, GroupBy, OutputFormat, RatioMetric, SortKey, WatchOutput,
+};
+use count_lines_engine::processor::LICENSE_HEADER_LINES;
+use count_lines_engine::similarity::SimilarPair;
+use count_lines_engine::stats::{
+    Distribution, ErrorRecord, FileStats, LinkReport, RunResult, Summary, SymlinkCycle,
+};
 use std::cmp::Ordering;
-use std::fmt::Write;
+use std::fmt::Write as _;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::{Component, Path, PathBuf};
+use std::time::Duration;
+
+pub mod sarif;
+
+use sarif::{Finding, Rule};
 
 pub fn print_clear_screen(output: &WatchOutput) {
     if matches!(output, WatchOutput::Full) {
@@ -11,68 +31,475 @@ pub fn print_clear_screen(output: &WatchOutput) {
     }
 }
 
-pub fn print_results(stats: &[FileStats], config: &Config) {
+/// Prints the results to stdout, or to `config.output` when set. With
+/// `--quiet` nothing goes to stdout.
+///
+/// # Errors
+/// Returns an error if the output file cannot be created or written.
+pub fn print_results(result: &RunResult, config: &Config) -> io::Result<()> {
+    if let Some(path) = &config.output {
+        let mut out = BufWriter::new(File::create(path)?);
+        write_results(&mut out, result, config)?;
+        out.flush()
+    } else if config.quiet {
+        Ok(())
+    } else {
+        let stdout = io::stdout();
+        let mut out = stdout.lock();
+        write_results(&mut out, result, config)
+    }
+}
+
+/// Writes the results in the configured format to `out`.
+///
+/// # Errors
+/// Returns an error if writing to `out` fails.
+pub fn write_results(out: &mut dyn Write, result: &RunResult, config: &Config) -> io::Result<()> {
+    if config.errors_only {
+        return write_errors(out, &error_records(result, config), config.format);
+    }
+
     // Filter out binary files
-    let mut stats: Vec<_> = stats.iter().filter(|s| !s.is_binary).cloned().collect();
-    if !config.sort.is_empty() {
-        stats.sort_by(|a, b| {
-            for (key, desc) in &config.sort {
-                let order = match key {
-                    SortKey::Lines => a.lines.cmp(&b.lines),
-                    SortKey::Chars => a.chars.cmp(&b.chars),
-                    SortKey::Size => a.size.cmp(&b.size),
-                    SortKey::Name => a.name.cmp(&b.name),
-                    SortKey::Ext => a.ext.cmp(&b.ext),
-                    SortKey::Sloc => a.sloc.unwrap_or(0).cmp(&b.sloc.unwrap_or(0)),
-                    SortKey::Words => a.words.unwrap_or(0).cmp(&b.words.unwrap_or(0)),
-                };
-                if order != Ordering::Equal {
-                    return if *desc { order.reverse() } else { order };
+    let mut stats: Vec<_> = result
+        .stats
+        .iter()
+        .filter(|s| !s.is_binary)
+        .map(|s| FileStats {
+            path: display_path(&s.path, config),
+            ..s.clone()
+        })
+        .collect();
+    sort_stats(&mut stats, &config.sort);
+
+    if config.null_separated {
+        if let Some(n) = config.top {
+            stats.truncate(n);
+        }
+        return write_raw_paths(out, stats.iter().map(|s| s.path.as_path()), b'\0');
+    }
+    if config.oneline {
+        return print_oneline(out, &Summary::from_stats(&stats), config);
+    }
+    if let Some(badge) = &config.badge {
+        return print_badge(out, &stats, badge, config);
+    }
+
+    let metadata = config
+        .with_metadata
+        .then(|| RunMetadata::collect(result, config));
+    if let Some(metadata) = &metadata {
+        write_metadata_header(out, metadata, config.format)?;
+    }
+
+    let group_by: &[GroupBy] = match config.format {
+        OutputFormat::Tree | OutputFormat::Github | OutputFormat::Sarif => &[],
+        OutputFormat::Markdown if config.group_by.is_empty() => &[GroupBy::Language],
+        _ => &config.group_by,
+    };
+    if !group_by.is_empty() {
+        let timings = config.timings.then(|| RunTimings::collect(result));
+        return write_groups(out, &stats, group_by, metadata, timings, config);
+    }
+    if config.format == OutputFormat::Tree {
+        return print_tree(out, &DirTree::build(&stats), config);
+    }
+    if config.format == OutputFormat::Sarif {
+        return print_sarif(out, &stats, &error_records(result, config), config);
+    }
+    // Annotations and their summary cover every file, not only `--top`.
+    if config.format == OutputFormat::Github {
+        return print_github(out, &stats, config);
+    }
+
+    // Directories are ranked and the encoding audited over every file, before
+    // `--top` cuts the list.
+    let top_dirs = config.top_dirs.map(|n| DirTree::build(&stats).top_dirs(n));
+    let distribution = config
+        .distribution
+        .then(|| Distribution::from_stats(&stats));
+    let encoding = config
+        .audit_encoding
+        .then(|| EncodingReport::from_stats(&stats));
+    // `ratio` columns are shares of every file, not only the shown ones.
+    let total_lines = stats.iter().map(|s| s.lines).sum();
+    if let Some(n) = config.top {
+        stats.truncate(n);
+    }
+
+    match config.format {
+        OutputFormat::Json => {
+            let mut report = json_report(stats, result, config);
+            report.top_dirs = top_dirs;
+            report.distribution = distribution;
+            report.encoding = encoding;
+            report.metadata = metadata;
+            report.timings = config.timings.then(|| RunTimings::collect(result));
+            let json = serde_json::to_string_pretty(&report).map_err(io::Error::other)?;
+            writeln!(out, "{json}")
+        }
+        OutputFormat::Yaml => print_yaml(out, &stats),
+        OutputFormat::Jsonl => print_jsonl(out, &stats, &error_records(result, config)),
+        OutputFormat::Md | OutputFormat::Markdown => {
+            print_markdown(out, &stats, config)?;
+            if !result.minified.is_empty() {
+                let m = Summary::from_stats(&result.minified);
+                writeln!(
+                    out,
+                    "_Minified files excluded: {} files, {} lines, {} chars._",
+                    m.files, m.lines, m.chars
+                )?;
+                writeln!(out)?;
+            }
+            if let Some(dirs) = top_dirs {
+                print_top_dirs_markdown(out, &dirs, config)?;
+            }
+            if let Some(threshold) = config.detect_similar {
+                print_similar_markdown(out, &similar_pairs(result, config), threshold)?;
+            }
+            if config.walk.report_links {
+                print_links_markdown(out, &link_report(result, config))?;
+            }
+            if let Some(encoding) = &encoding {
+                print_encoding_markdown(out, encoding)?;
+            }
+            print_long_lines_markdown(out, &stats, config)?;
+            Ok(())
+        }
+        OutputFormat::Tree => print_tree(out, &DirTree::build(&stats), config),
+        OutputFormat::Github => print_github(out, &stats, config),
+        OutputFormat::Sarif => print_sarif(out, &stats, &error_records(result, config), config),
+        OutputFormat::Csv => print_sv(
+            out,
+            &stats,
+            &error_records(result, config),
+            total_lines,
+            config,
+            Delimiter::Comma,
+        ),
+        OutputFormat::Tsv => print_sv(
+            out,
+            &stats,
+            &error_records(result, config),
+            total_lines,
+            config,
+            Delimiter::Tab,
+        ),
+        OutputFormat::Table => {
+            if config.columns.is_empty() {
+                print_table(out, &stats, &result.minified, config)?;
+            } else {
+                print_columns_table(out, &stats, total_lines, config)?;
+            }
+            if let Some(dirs) = top_dirs {
+                print_top_dirs_table(out, &dirs, config)?;
+            }
+            if let Some(distribution) = &distribution {
+                print_distribution_table(out, distribution, config)?;
+            }
+            if let Some(threshold) = config.detect_similar {
+                print_similar_table(out, &similar_pairs(result, config), threshold)?;
+            }
+            if config.walk.report_links {
+                print_links_table(out, &link_report(result, config))?;
+            }
+            if let Some(encoding) = &encoding {
+                print_encoding_table(out, encoding)?;
+            }
+            print_long_lines_table(out, &stats, config)?;
+            Ok(())
+        }
+    }
+}
+
+/// Writes the totals as `key=value` pairs on one line for scripts:
+/// `files=123 lines=45678 sloc=34567 chars=901234`.
+fn print_oneline(out: &mut dyn Write, summary: &Summary, config: &Config) -> io::Result<()> {
+    let mut line = format!("files={} lines={}", summary.files, summary.lines);
+    if config.count_sloc {
+        write!(line, " sloc={}", summary.sloc.unwrap_or(0)).unwrap();
+    }
+    write!(line, " chars={}", summary.chars).unwrap();
+    if config.count_words {
+        write!(line, " words={}", summary.words.unwrap_or(0)).unwrap();
+    }
+    writeln!(out, "{line}")
+}
+
+/// `path` as it is displayed: relative to `--relative-to` when set.
+#[must_use]
+pub fn display_path(path: &Path, config: &Config) -> PathBuf {
+    match &config.relative_to {
+        // The name given to `--stdin` is not a path on disk.
+        Some(base) if config.stdin.is_none() => relative_path(path, base),
+        _ => path.to_path_buf(),
+    }
+}
+
+/// `path` relative to the absolute directory `base`, climbing with `..` as
+/// needed. A path on another drive or share than `base` has no relative
+/// form and is returned absolute.
+#[must_use]
+pub fn relative_path(path: &Path, base: &Path) -> PathBuf {
+    let path = normalize(&std::path::absolute(path).unwrap_or_else(|_| path.to_path_buf()));
+    let base = normalize(base);
+    let path_parts: Vec<Component> = path.components().collect();
+    let base_parts: Vec<Component> = base.components().collect();
+
+    let common = path_parts
+        .iter()
+        .zip(&base_parts)
+        .take_while(|(a, b)| same_component(**a, **b))
+        .count();
+    if common == 0 {
+        return path;
+    }
+    let relative: PathBuf = std::iter::repeat_n(Component::ParentDir, base_parts.len() - common)
+        .chain(path_parts[common..].iter().copied())
+        .collect();
+    if relative.as_os_str().is_empty() {
+        PathBuf::from(".")
+    } else {
+        relative
+    }
+}
+
+/// Drive letters compare case-insensitively (`C:` is `c:`).
+fn same_component(a: Component, b: Component) -> bool {
+    match (a, b) {
+        (Component::Prefix(a), Component::Prefix(b)) => {
+            a.as_os_str().eq_ignore_ascii_case(b.as_os_str())
+        }
+        _ => a == b,
+    }
+}
+
+/// Resolves `.` and `..` without touching the file system.
+fn normalize(path: &Path) -> PathBuf {
+    let mut out = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                if !out.pop() {
+                    out.push(component);
                 }
             }
-            Ordering::Equal
-        });
+            _ => out.push(component),
+        }
     }
+    out
+}
 
-    match config.format {
-        OutputFormat::Json => print_json(&stats),
-        OutputFormat::Yaml => print_yaml(&stats),
-        OutputFormat::Jsonl => print_jsonl(&stats),
-        OutputFormat::Md => print_markdown(&stats, config),
-        OutputFormat::Csv => print_sv(&stats, config, ","),
-        OutputFormat::Tsv => print_sv(&stats, config, "\t"),
-        OutputFormat::Table => print_table(&stats, config),
+/// The failures of `result`, with their paths as displayed.
+fn error_records(result: &RunResult, config: &Config) -> Vec<ErrorRecord> {
+    result
+        .error_records()
+        .into_iter()
+        .map(|e| ErrorRecord {
+            path: display_path(&e.path, config),
+            ..e
+        })
+        .collect()
+}
+
+/// The similar pairs of `result`, with their paths as displayed.
+fn similar_pairs(result: &RunResult, config: &Config) -> Vec<SimilarPair> {
+    result
+        .similar
+        .iter()
+        .map(|p| SimilarPair {
+            a: display_path(&p.a, config),
+            b: display_path(&p.b, config),
+            ..p.clone()
+        })
+        .collect()
+}
+
+/// The symlink report of `result`, with its paths as displayed.
+fn link_report(result: &RunResult, config: &Config) -> LinkReport {
+    let links = &result.links;
+    LinkReport {
+        followed: links.followed,
+        cycles: links
+            .cycles
+            .iter()
+            .map(|c| SymlinkCycle {
+                link: display_path(&c.link, config),
+                target: display_path(&c.target, config),
+            })
+            .collect(),
+        broken: links
+            .broken
+            .iter()
+            .map(|p| display_path(p, config))
+            .collect(),
+    }
+}
+
+/// Writes only the failures (`--errors-only`): a JSON array, one JSONL
+/// record each, a `path,kind,message` table, or plain lines.
+fn write_errors(
+    out: &mut dyn Write,
+    errors: &[ErrorRecord],
+    format: OutputFormat,
+) -> io::Result<()> {
+    match format {
+        OutputFormat::Json => {
+            let json = serde_json::to_string_pretty(errors).map_err(io::Error::other)?;
+            writeln!(out, "{json}")
+        }
+        OutputFormat::Yaml => {
+            let yaml = serde_yaml::to_string(errors).map_err(io::Error::other)?;
+            writeln!(out, "{yaml}")
+        }
+        OutputFormat::Jsonl => errors
+            .iter()
+            .try_for_each(|e| writeln!(out, "{}", error_line(e))),
+        OutputFormat::Csv | OutputFormat::Tsv => {
+            let delimiter = if format == OutputFormat::Csv {
+                Delimiter::Comma
+            } else {
+                Delimiter::Tab
+            };
+            let sep = delimiter.as_str();
+            writeln!(out, "{}", ["path", "kind", "message"].join(sep))?;
+            for e in errors {
+                let fields = [
+                    delimiter.escape(&e.path.display().to_string()),
+                    delimiter.escape(&e.kind),
+                    delimiter.escape(&e.message),
+                ];
+                writeln!(out, "{}", fields.join(sep))?;
+            }
+            Ok(())
+        }
+        OutputFormat::Sarif => print_sarif(out, &[], errors, &Config::default()),
+        OutputFormat::Github => errors.iter().try_for_each(|e| {
+            let annotation = Annotation::new(Level::Error, &e.kind, &e.message).file(&e.path);
+            writeln!(out, "{annotation}")
+        }),
+        OutputFormat::Table | OutputFormat::Md | OutputFormat::Markdown | OutputFormat::Tree => {
+            errors
+                .iter()
+                .try_for_each(|e| writeln!(out, "{}\t{}\t{}", e.path.display(), e.kind, e.message))
+        }
     }
 }
 
-fn print_table(stats: &[FileStats], config: &Config) {
+/// An `{"type":"error",...}` JSONL record.
+fn error_line(error: &ErrorRecord) -> serde_json::Value {
+    serde_json::json!({
+        "type": "error",
+        "path": error.path,
+        "kind": error.kind,
+        "message": error.message,
+    })
+}
+
+/// Sorts `stats` by the `--sort` keys in order; `true` sorts a key
+/// descending.
+///
+/// Files equal on every key are ordered by path, so the result doesn't
+/// depend on the order in which parallel workers finished.
+pub fn sort_stats(stats: &mut [FileStats], sort: &[(SortKey, bool)]) {
+    stats.sort_by(|a, b| {
+        for (key, desc) in sort {
+            let order = match key {
+                // A file's ratio is its share of the same total line count.
+                SortKey::Lines | SortKey::Ratio => a.lines.cmp(&b.lines),
+                SortKey::Chars => a.chars.cmp(&b.chars),
+                SortKey::Size => a.size.cmp(&b.size),
+                SortKey::Name => a.name.cmp(&b.name),
+                SortKey::Ext => a.ext.cmp(&b.ext),
+                SortKey::Sloc => a.sloc.unwrap_or(0).cmp(&b.sloc.unwrap_or(0)),
+                SortKey::Words => a.words.unwrap_or(0).cmp(&b.words.unwrap_or(0)),
+                SortKey::MaxLine => a.max_line_length.cmp(&b.max_line_length),
+                SortKey::AvgLine => a.avg_line_length.total_cmp(&b.avg_line_length),
+                SortKey::Path => a.path.cmp(&b.path),
+                SortKey::MaxDepth => a.max_depth.unwrap_or(0).cmp(&b.max_depth.unwrap_or(0)),
+                SortKey::AvgDepth => a
+                    .avg_depth
+                    .unwrap_or(0.0)
+                    .total_cmp(&b.avg_depth.unwrap_or(0.0)),
+            };
+            if order != Ordering::Equal {
+                return if *desc { order.reverse() } else { order };
+            }
+        }
+        a.path.cmp(&b.path)
+    });
+}
+
+/// Writes each path verbatim followed by `terminator`, bypassing any
+/// formatting, so names containing spaces or newlines survive (`--print0`).
+///
+/// # Errors
+/// Returns an error if writing to `out` fails.
+pub fn write_raw_paths<'a>(
+    out: &mut dyn Write,
+    paths: impl Iterator<Item = &'a Path>,
+    terminator: u8,
+) -> io::Result<()> {
+    for path in paths {
+        out.write_all(path.as_os_str().as_encoded_bytes())?;
+        out.write_all(&[terminator])?;
+    }
+    Ok(())
+}
+
+fn print_table(
+    out: &mut dyn Write,
+    stats: &[FileStats],
+    minified: &[FileStats],
+    config: &Config,
+) -> io::Result<()> {
     // Get number of threads for parallel info
     let threads = config.walk.threads;
 
     // Print version header
-    println!("count_lines v{} · parallel={threads}", crate::VERSION);
-    println!();
+    writeln!(out, "count_lines v{} · parallel={threads}", crate::VERSION)?;
+    writeln!(out)?;
 
     // Print column header
+    let style = Styler::new(config);
     if config.count_sloc {
-        println!("    LINES            SLOC        CHARACTERS     FILE");
+        writeln!(
+            out,
+            "{}",
+            style.header("    LINES            SLOC        CHARACTERS     FILE")
+        )?;
     } else {
-        println!("    LINES        CHARACTERS     FILE");
+        writeln!(
+            out,
+            "{}",
+            style.header("    LINES        CHARACTERS     FILE")
+        )?;
     }
-    println!("----------------------------------------------");
+    writeln!(out, "----------------------------------------------")?;
 
     // Print each file
+    let fmt = NumberFormat::new(config);
     for s in stats {
-        if config.count_sloc {
-            println!(
+        let row = if config.count_sloc {
+            format!(
                 "{:>9}{:>16}{:>16}      {}",
-                s.lines,
-                s.sloc.map(|v| v.to_string()).unwrap_or_default(),
-                s.chars,
+                fmt.count(s.lines),
+                fmt.optional(s.sloc),
+                fmt.count(s.chars),
                 s.path.display()
-            );
+            )
+        } else {
+            format!(
+                "{:>9}{:>16}      {}",
+                fmt.count(s.lines),
+                fmt.count(s.chars),
+                s.path.display()
+            )
+        };
+        if over_highlight(s, config) {
+            writeln!(out, "{}", style.highlight(&row))?;
         } else {
-            println!("{:>9}{:>16}      {}", s.lines, s.chars, s.path.display());
+            writeln!(out, "{row}")?;
         }
     }
 
@@ -82,42 +509,153 @@ fn print_table(stats: &[FileStats], config: &Config) {
     let total_sloc: usize = stats.iter().filter_map(|s| s.sloc).sum();
     let file_count = stats.len();
 
-    println!("---");
-    if config.count_sloc {
-        println!(
-            "{total_lines:>9}{total_sloc:>16}{total_chars:>16}      TOTAL ({file_count} files)"
-        );
+    let (total_lines, total_sloc, total_chars) = (
+        fmt.count(total_lines),
+        fmt.count(total_sloc),
+        fmt.count(total_chars),
+    );
+    writeln!(out, "---")?;
+    let total = if config.count_sloc {
+        format!("{total_lines:>9}{total_sloc:>16}{total_chars:>16}      TOTAL ({file_count} files)")
     } else {
-        println!("{total_lines:>9}{total_chars:>16}      TOTAL ({file_count} files)");
+        format!("{total_lines:>9}{total_chars:>16}      TOTAL ({file_count} files)")
+    };
+    writeln!(out, "{}", style.total(&total))?;
+    if !minified.is_empty() {
+        let m = Summary::from_stats(minified);
+        let sloc = if config.count_sloc {
+            format!("{:>16}", 0)
+        } else {
+            String::new()
+        };
+        writeln!(
+            out,
+            "{:>9}{sloc}{:>16}      MINIFIED ({} files, not in TOTAL; --include-minified to count them)",
+            fmt.count(m.lines),
+            fmt.count(m.chars),
+            m.files
+        )?;
     }
 
     // Print completion message
-    println!();
-    println!("[count_lines] Completed: {file_count} files processed.");
+    writeln!(out)?;
+    writeln!(
+        out,
+        "[count_lines] Completed: {file_count} files processed."
+    )?;
+    Ok(())
 }
 
-fn print_json(stats: &[FileStats]) {
-    if let Ok(json) = serde_json::to_string_pretty(stats) {
-        println!("{json}");
+/// Returns true if `--timings` goes into the written JSON document instead
+/// of the stderr report of [`write_timings`].
+#[must_use]
+pub fn embeds_timings(config: &Config) -> bool {
+    config.format == OutputFormat::Json
+        && !(config.errors_only
+            || config.null_separated
+            || config.oneline
+            || config.badge.is_some())
+}
+
+/// Writes the `--timings` report: the time of each stage, of writing the
+/// output, and the cache hits.
+///
+/// # Errors
+/// Returns an error if writing to `out` fails.
+pub fn write_timings(out: &mut dyn Write, result: &RunResult, output: Duration) -> io::Result<()> {
+    let t = result.timings;
+    writeln!(out, "[count_lines] Timings:")?;
+    for (stage, elapsed) in [
+        ("walk", t.walk),
+        ("detect", t.detect),
+        ("measure", t.measure),
+        ("aggregate", t.aggregate),
+        ("output", output),
+        ("total", t.total + output),
+    ] {
+        writeln!(
+            out,
+            "  {stage:<10}{:>10.1} ms",
+            elapsed.as_secs_f64() * 1000.0
+        )?;
+    }
+    if let Some(cache) = result.cache {
+        writeln!(
+            out,
+            "  {:<10}{:>10} hits, {} misses",
+            "cache", cache.hits, cache.misses
+        )?;
     }
+    Ok(())
 }
 
-fn print_yaml(stats: &[FileStats]) {
-    if let Ok(yaml) = serde_yaml::to_string(stats) {
-        println!("{yaml}");
+fn json_report(stats: Vec<FileStats>, result: &RunResult, config: &Config) -> JsonReport {
+    let mut report = JsonReport::new(stats, error_records(result, config));
+    report.partial = result.partial;
+    report.truncated = result.truncated;
+    report.timed_out = result.timed_out;
+    report.similar = config.detect_similar.map(|_| similar_pairs(result, config));
+    report.minified = (!result.minified.is_empty()).then(|| Summary::from_stats(&result.minified));
+    report.links = config
+        .walk
+        .report_links
+        .then(|| link_report(result, config));
+    report
+}
+
+/// Writes the `--with-metadata` header of the formats without a place for it
+/// in the document: a `type=metadata` JSONL record, a leading YAML document,
+/// or `# key: value` comment lines above the CSV/TSV header.
+fn write_metadata_header(
+    out: &mut dyn Write,
+    metadata: &RunMetadata,
+    format: OutputFormat,
+) -> io::Result<()> {
+    match format {
+        OutputFormat::Jsonl => {
+            let mut v = serde_json::to_value(metadata).map_err(io::Error::other)?;
+            if let Some(obj) = v.as_object_mut() {
+                obj.insert("type".to_string(), "metadata".into());
+            }
+            writeln!(out, "{v}")
+        }
+        OutputFormat::Yaml => {
+            let yaml = serde_yaml::to_string(metadata).map_err(io::Error::other)?;
+            writeln!(out, "{yaml}---")
+        }
+        OutputFormat::Csv | OutputFormat::Tsv => {
+            let v = serde_json::to_value(metadata).map_err(io::Error::other)?;
+            for (key, value) in v.as_object().into_iter().flatten() {
+                match value {
+                    serde_json::Value::String(s) => writeln!(out, "# {key}: {s}")?,
+                    serde_json::Value::Null => writeln!(out, "# {key}:")?,
+                    other => writeln!(out, "# {key}: {other}")?,
+                }
+            }
+            Ok(())
+        }
+        _ => Ok(()),
     }
 }
 
-fn print_jsonl(stats: &[FileStats]) {
+fn print_yaml(out: &mut dyn Write, stats: &[FileStats]) -> io::Result<()> {
+    let yaml = serde_yaml::to_string(stats).map_err(io::Error::other)?;
+    writeln!(out, "{yaml}")
+}
+
+fn print_jsonl(out: &mut dyn Write, stats: &[FileStats], errors: &[ErrorRecord]) -> io::Result<()> {
     let version = crate::VERSION;
     for s in stats {
         if let Ok(mut v) = serde_json::to_value(s) {
             if let Some(obj) = v.as_object_mut() {
                 obj.insert("type".to_string(), "file".into());
             }
-            println!("{}", serde_json::to_string(&v).unwrap_or_default());
+            writeln!(out, "{}", serde_json::to_string(&v).unwrap_or_default())?;
         }
     }
+    for e in errors {
+        writeln!(out, "{}", error_line(e))?;
+    }
 
     let total_lines: usize = stats.iter().map(|s| s.lines).sum();
     let total_chars: usize = stats.iter().map(|s| s.chars).sum();
@@ -133,13 +671,14 @@ fn print_jsonl(stats: &[FileStats]) {
         "chars": total_chars,
         "words": total_words,
         "sloc": total_sloc,
+        "errors": errors.len(),
     });
-    println!("{total_obj}");
+    writeln!(out, "{total_obj}")
 }
 
-fn print_markdown(stats: &[FileStats], config: &Config) {
-    println!("### File Statistics");
-    println!();
+fn print_markdown(out: &mut dyn Write, stats: &[FileStats], config: &Config) -> io::Result<()> {
+    writeln!(out, "### File Statistics")?;
+    writeln!(out)?;
     let mut header = String::from("| Lines |");
     let mut separator = String::from("|:---:|");
 
@@ -159,98 +698,2179 @@ fn print_markdown(stats: &[FileStats], config: &Config) {
     header.push_str(" File |");
     separator.push_str(":---|");
 
-    println!("{header}");
-    println!("{separator}");
+    writeln!(out, "{header}")?;
+    writeln!(out, "{separator}")?;
 
+    let fmt = NumberFormat::new(config);
     for s in stats {
-        let mut row = format!("| {} |", s.lines);
+        let mut row = format!("| {} |", fmt.count(s.lines));
 
         if config.count_sloc {
-            write!(row, " {} |", s.sloc.unwrap_or(0)).unwrap();
+            write!(row, " {} |", fmt.count(s.sloc.unwrap_or(0))).unwrap();
         }
 
-        write!(row, " {} |", s.chars).unwrap();
+        write!(row, " {} |", fmt.count(s.chars)).unwrap();
 
         if config.count_words {
-            write!(row, " {} |", s.words.unwrap_or(0)).unwrap();
+            write!(row, " {} |", fmt.count(s.words.unwrap_or(0))).unwrap();
         }
 
         let path_str = s.path.display().to_string().replace('|', "\\|");
         write!(row, " {path_str} |").unwrap();
 
-        println!("{row}");
+        writeln!(out, "{row}")?;
     }
-    println!();
+    writeln!(out)
 }
 
-fn print_sv(stats: &[FileStats], config: &Config, delimiter: &str) {
-    let mut header = String::from("lines");
-    if config.count_sloc {
-        header.push_str(delimiter);
-        header.push_str("sloc");
+/// Writes one row per group instead of per file (`--by`, `--format markdown`).
+///
+/// With several keys, JSON, YAML, JSONL and the table nest the breakdowns
+/// under each group; CSV, TSV and Markdown write one row per innermost
+/// group with a column per key.
+fn write_groups(
+    out: &mut dyn Write,
+    stats: &[FileStats],
+    by: &[GroupBy],
+    metadata: Option<RunMetadata>,
+    timings: Option<RunTimings>,
+    config: &Config,
+) -> io::Result<()> {
+    let mut groups = grouping::nested_group_stats(stats, by, config);
+    let summary = Summary::from_stats(stats);
+    if let Some(metric) = config.ratio_of {
+        grouping::apply_ratios(&mut groups, by, metric, config.ratio_base, &summary);
     }
-    header.push_str(delimiter);
-    header.push_str("chars");
 
-    if config.count_words {
-        header.push_str(delimiter);
-        header.push_str("words");
+    match config.format {
+        OutputFormat::Json => {
+            let mut report = GroupReport::new(groups, summary);
+            report.metadata = metadata;
+            report.timings = timings;
+            let json = serde_json::to_string_pretty(&report).map_err(io::Error::other)?;
+            writeln!(out, "{json}")
+        }
+        OutputFormat::Yaml => {
+            let yaml = serde_yaml::to_string(&groups).map_err(io::Error::other)?;
+            writeln!(out, "{yaml}")
+        }
+        OutputFormat::Jsonl => {
+            for group in &groups {
+                let mut v = serde_json::to_value(group).map_err(io::Error::other)?;
+                if let Some(obj) = v.as_object_mut() {
+                    obj.insert("type".to_string(), "group".into());
+                }
+                writeln!(out, "{v}")?;
+            }
+            let mut total = serde_json::to_value(&summary).map_err(io::Error::other)?;
+            if let Some(obj) = total.as_object_mut() {
+                obj.insert("type".to_string(), "total".into());
+                obj.insert("version".to_string(), crate::VERSION.into());
+            }
+            writeln!(out, "{total}")
+        }
+        OutputFormat::Md | OutputFormat::Markdown => {
+            print_markdown_summary(out, &groups, &summary, by, config)
+        }
+        OutputFormat::Csv => print_groups_sv(out, &groups, &summary, by, config, Delimiter::Comma),
+        OutputFormat::Tsv => print_groups_sv(out, &groups, &summary, by, config, Delimiter::Tab),
+        OutputFormat::Table | OutputFormat::Tree | OutputFormat::Github | OutputFormat::Sarif => {
+            print_groups_table(out, &groups, &summary, by, config)
+        }
+    }
+}
+
+/// Writes a `::warning` annotation for every file over `--highlight-over`
+/// and every file missing `--license-header`, then a `::notice` with the
+/// totals.
+fn print_github(out: &mut dyn Write, stats: &[FileStats], config: &Config) -> io::Result<()> {
+    if let Some(limit) = config.highlight_over {
+        for s in stats.iter().filter(|s| over_highlight(s, config)) {
+            let message = format!("{} lines (limit {limit})", s.lines);
+            let annotation =
+                Annotation::new(Level::Warning, "Oversized file", &message).file(&s.path);
+            writeln!(out, "{annotation}")?;
+        }
+    }
+    if let Some(header) = &config.license_header {
+        let message = format!("\"{header}\" not found in the first {LICENSE_HEADER_LINES} lines");
+        for s in stats.iter().filter(|s| s.missing_license_header) {
+            let annotation =
+                Annotation::new(Level::Warning, "Missing license header", &message).file(&s.path);
+            writeln!(out, "{annotation}")?;
+        }
+    }
+    let summary = Summary::from_stats(stats);
+    let mut message = format!("{} files, {} lines", summary.files, summary.lines);
+    if let Some(sloc) = summary.sloc {
+        let _ = write!(message, ", {sloc} SLOC");
     }
+    writeln!(
+        out,
+        "{}",
+        Annotation::new(Level::Notice, "count_lines", &message)
+    )
+}
 
-    header.push_str(delimiter);
-    header.push_str("path");
-    println!("{header}");
+/// Writes a SARIF log with a result for every file over `--highlight-over`,
+/// every exceeded `--fail-if` threshold and every unreadable file.
+fn print_sarif(
+    out: &mut dyn Write,
+    stats: &[FileStats],
+    errors: &[ErrorRecord],
+    config: &Config,
+) -> io::Result<()> {
+    let mut findings = Vec::new();
+    if let Some(limit) = config.highlight_over {
+        for s in stats.iter().filter(|s| over_highlight(s, config)) {
+            let message = format!("{} lines (limit {limit})", s.lines);
+            findings.push(Finding::new(Rule::HighlightOver, message).file(&s.path));
+        }
+    }
+    let summary = Summary::from_stats(stats);
+    // An invalid threshold is reported by the check after the output.
+    if let Ok(violations) = crate::threshold::violations(config, &|name| summary.field(name)) {
+        findings.extend(
+            violations
+                .into_iter()
+                .map(|v| Finding::new(Rule::FailIf, v)),
+        );
+    }
+    for e in errors {
+        let message = format!("{}: {}", e.kind, e.message);
+        findings.push(Finding::new(Rule::ReadError, message).file(&e.path));
+    }
+    sarif::write_sarif(out, &findings)
+}
 
-    for s in stats {
-        let mut row = format!("{}", s.lines);
+/// Whether `s` has more lines than `--highlight-over`.
+fn over_highlight(s: &FileStats, config: &Config) -> bool {
+    config.highlight_over.is_some_and(|limit| s.lines > limit)
+}
 
+fn print_top_dirs_table(out: &mut dyn Write, dirs: &[DirStats], config: &Config) -> io::Result<()> {
+    writeln!(out)?;
+    let header = format!("Top {} directories:", dirs.len());
+    writeln!(out, "{}", Styler::new(config).header(&header))?;
+    let fmt = NumberFormat::new(config);
+    for d in dirs {
+        let s = &d.summary;
         if config.count_sloc {
-            row.push_str(delimiter);
-            row.push_str(&s.sloc.unwrap_or(0).to_string());
+            writeln!(
+                out,
+                "{:>9}{:>16}{:>8} files  {}/",
+                fmt.count(s.lines),
+                fmt.optional(s.sloc),
+                fmt.count(s.files),
+                d.path
+            )?;
+        } else {
+            writeln!(
+                out,
+                "{:>9}{:>8} files  {}/",
+                fmt.count(s.lines),
+                fmt.count(s.files),
+                d.path
+            )?;
         }
+    }
+    Ok(())
+}
 
-        row.push_str(delimiter);
-        row.push_str(&s.chars.to_string());
+/// Width of the longest `--distribution` histogram bar.
+const HISTOGRAM_WIDTH: usize = 40;
 
-        if config.count_words {
-            row.push_str(delimiter);
-            row.push_str(&s.words.unwrap_or(0).to_string());
+fn print_distribution_table(
+    out: &mut dyn Write,
+    distribution: &Distribution,
+    config: &Config,
+) -> io::Result<()> {
+    let (lines, size) = (&distribution.lines, &distribution.size);
+    // Sizes are in binary units here unless `--bytes` asks otherwise.
+    let fmt = NumberFormat::new(config);
+    let sizes = NumberFormat {
+        human: true,
+        ..fmt.clone()
+    };
+    let count = |value: u64| fmt.count(usize::try_from(value).unwrap_or(usize::MAX));
+    writeln!(out)?;
+    writeln!(out, "Distribution:")?;
+    writeln!(
+        out,
+        "{:>8}{:>12}{:>12}{:>12}{:>12}",
+        "", "MEDIAN", "P90", "P99", "MAX"
+    )?;
+    writeln!(
+        out,
+        "{:>8}{:>12}{:>12}{:>12}{:>12}",
+        "LINES",
+        count(lines.median),
+        count(lines.p90),
+        count(lines.p99),
+        count(lines.max)
+    )?;
+    writeln!(
+        out,
+        "{:>8}{:>12}{:>12}{:>12}{:>12}",
+        "SIZE",
+        sizes.bytes(size.median),
+        sizes.bytes(size.p90),
+        sizes.bytes(size.p99),
+        sizes.bytes(size.max)
+    )?;
+
+    writeln!(out)?;
+    writeln!(out, "File sizes:")?;
+    let most = distribution
+        .size_histogram
+        .iter()
+        .map(|b| b.files)
+        .max()
+        .unwrap_or(0);
+    for bucket in &distribution.size_histogram {
+        let range = match bucket.max {
+            Some(max) if bucket.min == 0 => format!("< {}", size_label(max)),
+            Some(max) => format!("{} - {}", size_label(bucket.min), size_label(max)),
+            None => format!(">= {}", size_label(bucket.min)),
+        };
+        let bar = (bucket.files * HISTOGRAM_WIDTH).div_ceil(most.max(1));
+        writeln!(
+            out,
+            "{range:>20}  {:<HISTOGRAM_WIDTH$} {}",
+            "#".repeat(bar),
+            bucket.files
+        )?;
+    }
+    Ok(())
+}
+
+fn print_top_dirs_markdown(
+    out: &mut dyn Write,
+    dirs: &[DirStats],
+    config: &Config,
+) -> io::Result<()> {
+    writeln!(out, "### Top Directories")?;
+    writeln!(out)?;
+    if config.count_sloc {
+        writeln!(out, "| Lines | SLOC | Files | Directory |")?;
+        writeln!(out, "|---:|---:|---:|:---|")?;
+    } else {
+        writeln!(out, "| Lines | Files | Directory |")?;
+        writeln!(out, "|---:|---:|:---|")?;
+    }
+    let fmt = NumberFormat::new(config);
+    for d in dirs {
+        let s = &d.summary;
+        let path = d.path.replace('|', "\\|");
+        let (lines, files) = (fmt.count(s.lines), fmt.count(s.files));
+        if config.count_sloc {
+            let sloc = fmt.count(s.sloc.unwrap_or(0));
+            writeln!(out, "| {lines} | {sloc} | {files} | {path}/ |")?;
+        } else {
+            writeln!(out, "| {lines} | {files} | {path}/ |")?;
+        }
+    }
+    writeln!(out)
+}
+
+fn print_similar_table(
+    out: &mut dyn Write,
+    pairs: &[SimilarPair],
+    threshold: u8,
+) -> io::Result<()> {
+    writeln!(out)?;
+    if pairs.is_empty() {
+        writeln!(out, "No similar files (>= {threshold}%).")?;
+        return Ok(());
+    }
+    writeln!(out, "Similar files (>= {threshold}%):")?;
+    for p in pairs {
+        writeln!(
+            out,
+            "{:>7.1}%{:>8} shared  {}  <->  {}",
+            p.similarity,
+            p.shared_lines,
+            p.a.display(),
+            p.b.display()
+        )?;
+    }
+    Ok(())
+}
+
+fn print_similar_markdown(
+    out: &mut dyn Write,
+    pairs: &[SimilarPair],
+    threshold: u8,
+) -> io::Result<()> {
+    writeln!(out, "### Similar Files (>= {threshold}%)")?;
+    writeln!(out)?;
+    if pairs.is_empty() {
+        writeln!(out, "None.")?;
+        return writeln!(out);
+    }
+    writeln!(out, "| Similarity | Shared Lines | File | File |")?;
+    writeln!(out, "|---:|---:|:---|:---|")?;
+    for p in pairs {
+        writeln!(
+            out,
+            "| {:.1}% | {} | {} | {} |",
+            p.similarity,
+            p.shared_lines,
+            p.a.display().to_string().replace('|', "\\|"),
+            p.b.display().to_string().replace('|', "\\|")
+        )?;
+    }
+    writeln!(out)
+}
+
+fn print_links_table(out: &mut dyn Write, links: &LinkReport) -> io::Result<()> {
+    writeln!(out)?;
+    writeln!(
+        out,
+        "Symlinks: {} followed, {} cycles skipped, {} broken",
+        links.followed,
+        links.cycles.len(),
+        links.broken.len()
+    )?;
+    for c in &links.cycles {
+        writeln!(
+            out,
+            "  cycle   {} -> {}",
+            c.link.display(),
+            c.target.display()
+        )?;
+    }
+    for path in &links.broken {
+        writeln!(out, "  broken  {}", path.display())?;
+    }
+    Ok(())
+}
+
+/// Lists the files with a line over `--long-line-threshold`, which is
+/// counted without the indentation and whitespace checks.
+fn print_long_lines_table(
+    out: &mut dyn Write,
+    stats: &[FileStats],
+    config: &Config,
+) -> io::Result<()> {
+    let long: Vec<&FileStats> = stats.iter().filter(|s| s.has_long_lines()).collect();
+    if long.is_empty() {
+        return Ok(());
+    }
+    let fmt = NumberFormat::new(config);
+    writeln!(out)?;
+    writeln!(
+        out,
+        "Long lines: {} files with lines over {}, not checked for indentation or whitespace",
+        long.len(),
+        size_label(config.long_line_threshold)
+    )?;
+    for s in long {
+        writeln!(
+            out,
+            "  {:>14} chars  {}",
+            fmt.count(s.max_line_length),
+            s.path.display()
+        )?;
+    }
+    Ok(())
+}
+
+fn print_long_lines_markdown(
+    out: &mut dyn Write,
+    stats: &[FileStats],
+    config: &Config,
+) -> io::Result<()> {
+    let long: Vec<&FileStats> = stats.iter().filter(|s| s.has_long_lines()).collect();
+    if long.is_empty() {
+        return Ok(());
+    }
+    writeln!(
+        out,
+        "_Lines over {} not checked for indentation or whitespace in: {}._",
+        size_label(config.long_line_threshold),
+        long.iter()
+            .map(|s| format!("`{}`", s.path.display()))
+            .collect::<Vec<_>>()
+            .join(", ")
+    )?;
+    writeln!(out)
+}
+
+fn print_encoding_table(out: &mut dyn Write, report: &EncodingReport) -> io::Result<()> {
+    writeln!(out)?;
+    writeln!(
+        out,
+        "Encoding: {} UTF-8 BOM, {} UTF-16 BOM, {} UTF-32 BOM, {} without BOM, {} invalid UTF-8",
+        report.utf8_bom,
+        report.utf16_bom,
+        report.utf32_bom,
+        report.no_bom,
+        report.invalid_utf8.len()
+    )?;
+    for path in &report.invalid_utf8 {
+        writeln!(out, "  invalid UTF-8  {}", path.display())?;
+    }
+    Ok(())
+}
+
+fn print_encoding_markdown(out: &mut dyn Write, report: &EncodingReport) -> io::Result<()> {
+    writeln!(out, "### Encoding")?;
+    writeln!(out)?;
+    writeln!(
+        out,
+        "| UTF-8 BOM | UTF-16 BOM | UTF-32 BOM | No BOM | Invalid UTF-8 |"
+    )?;
+    writeln!(out, "|---:|---:|---:|---:|---:|")?;
+    writeln!(
+        out,
+        "| {} | {} | {} | {} | {} |",
+        report.utf8_bom,
+        report.utf16_bom,
+        report.utf32_bom,
+        report.no_bom,
+        report.invalid_utf8.len()
+    )?;
+    writeln!(out)?;
+    if report.invalid_utf8.is_empty() {
+        return Ok(());
+    }
+    writeln!(out, "Invalid UTF-8:")?;
+    writeln!(out)?;
+    for path in &report.invalid_utf8 {
+        writeln!(out, "- {}", path.display())?;
+    }
+    writeln!(out)
+}
+
+fn print_links_markdown(out: &mut dyn Write, links: &LinkReport) -> io::Result<()> {
+    writeln!(out, "### Symlinks")?;
+    writeln!(out)?;
+    writeln!(
+        out,
+        "{} followed, {} cycles skipped, {} broken.",
+        links.followed,
+        links.cycles.len(),
+        links.broken.len()
+    )?;
+    writeln!(out)?;
+    if links.cycles.is_empty() && links.broken.is_empty() {
+        return Ok(());
+    }
+    writeln!(out, "| Kind | Link | Target |")?;
+    writeln!(out, "|:---|:---|:---|")?;
+    for c in &links.cycles {
+        writeln!(
+            out,
+            "| cycle | {} | {} |",
+            c.link.display().to_string().replace('|', "\\|"),
+            c.target.display().to_string().replace('|', "\\|")
+        )?;
+    }
+    for path in &links.broken {
+        writeln!(
+            out,
+            "| broken | {} | |",
+            path.display().to_string().replace('|', "\\|")
+        )?;
+    }
+    writeln!(out)
+}
+
+/// `du`-style directory tree: totals on the left, hierarchy on the right.
+fn print_tree(out: &mut dyn Write, tree: &DirTree, config: &Config) -> io::Result<()> {
+    fn write_node(
+        out: &mut dyn Write,
+        node: &DirTree,
+        prefix: &str,
+        connector: &str,
+        depth: usize,
+        config: &Config,
+    ) -> io::Result<()> {
+        let s = &node.summary;
+        let fmt = NumberFormat::new(config);
+        if config.count_sloc {
+            write!(out, "{:>9}{:>10}", fmt.count(s.lines), fmt.optional(s.sloc))?;
+        } else {
+            write!(out, "{:>9}", fmt.count(s.lines))?;
+        }
+        writeln!(
+            out,
+            "{:>8}  {prefix}{connector}{}/",
+            fmt.count(s.files),
+            node.name
+        )?;
+
+        if config.tree_depth.is_some_and(|max| depth >= max) {
+            return Ok(());
+        }
+        let child_prefix = match connector {
+            "├── " => format!("{prefix}│   "),
+            "└── " => format!("{prefix}    "),
+            _ => prefix.to_string(),
+        };
+        for (i, child) in node.children.iter().enumerate() {
+            let connector = if i + 1 == node.children.len() {
+                "└── "
+            } else {
+                "├── "
+            };
+            write_node(out, child, &child_prefix, connector, depth + 1, config)?;
         }
+        Ok(())
+    }
 
-        row.push_str(delimiter);
-        let path = s.path.display().to_string();
-        if delimiter == "," && (path.contains(',') || path.contains('"') || path.contains('\n')) {
-            let escaped = path.replace('"', "\"\"");
-            write!(row, "\"{escaped}\"").unwrap();
+    let header = if config.count_sloc {
+        "    LINES      SLOC   FILES  DIRECTORY"
+    } else {
+        "    LINES   FILES  DIRECTORY"
+    };
+    writeln!(out, "{}", Styler::new(config).header(header))?;
+    write_node(out, tree, "", "", 0, config)
+}
+
+const fn group_heading(by: GroupBy) -> &'static str {
+    match by {
+        GroupBy::Language => "Language",
+        GroupBy::Ext => "Extension",
+        GroupBy::Linguist => "Linguist",
+        GroupBy::Age => "Age",
+        GroupBy::RootDir => "Directory",
+        GroupBy::Crate => "Crate",
+        GroupBy::Owner => "Owner",
+        GroupBy::Submodule => "Submodule",
+        GroupBy::Root => "Root",
+        GroupBy::Author => "Author",
+    }
+}
+
+/// The innermost groups of a nested grouping with the keys leading to them.
+fn leaf_groups(groups: &[GroupStats]) -> Vec<(Vec<&str>, &GroupStats)> {
+    let mut leaves = Vec::new();
+    for g in groups {
+        if g.groups.is_empty() {
+            leaves.push((vec![g.key.as_str()], g));
         } else {
-            row.push_str(&path);
+            for (mut keys, leaf) in leaf_groups(&g.groups) {
+                keys.insert(0, g.key.as_str());
+                leaves.push((keys, leaf));
+            }
+        }
+    }
+    leaves
+}
+
+/// The `--ratio-of` share of the totals row.
+const TOTAL_RATIO: Ratio = Ratio {
+    percent: 100.0,
+    cumulative: None,
+};
+
+/// A percentage with one decimal, empty when absent.
+fn percent_field(value: Option<f64>) -> String {
+    value.map(|v| format!("{v:.1}")).unwrap_or_default()
+}
+
+fn print_groups_table(
+    out: &mut dyn Write,
+    groups: &[GroupStats],
+    summary: &Summary,
+    by: &[GroupBy],
+    config: &Config,
+) -> io::Result<()> {
+    let fmt = NumberFormat::new(config);
+    let row = |s: &Summary, ratio: Option<&Ratio>, key: &str| {
+        let mut row = format!("{:>7}{:>12}", fmt.count(s.files), fmt.count(s.lines));
+        if config.count_sloc {
+            row.push_str(&format!("{:>12}", fmt.optional(s.sloc)));
         }
+        if config.count_docs {
+            row.push_str(&format!(
+                "{:>10}{:>8}",
+                fmt.optional(s.doc_lines),
+                percent_field(s.doc_density())
+            ));
+        }
+        row.push_str(&format!("{:>16}", fmt.count(s.chars)));
+        if config.ratio_of.is_some() {
+            row.push_str(&format!(
+                "{:>8}{:>8}",
+                percent_field(ratio.map(|r| r.percent)),
+                percent_field(ratio.and_then(|r| r.cumulative))
+            ));
+        }
+        format!("{row}      {key}")
+    };
 
-        println!("{row}");
+    let heading = by
+        .iter()
+        .map(|&b| group_heading(b).to_uppercase())
+        .collect::<Vec<_>>()
+        .join(" / ");
+    let mut header = String::from("  FILES       LINES");
+    if config.count_sloc {
+        header.push_str("        SLOC");
     }
+    if config.count_docs {
+        header.push_str("       DOC    DOC%");
+    }
+    header.push_str("      CHARACTERS");
+    if config.ratio_of.is_some() {
+        header.push_str("       %    CUM%");
+    }
+    let style = Styler::new(config);
+    writeln!(out, "{}", style.header(&format!("{header}      {heading}")))?;
+    writeln!(out, "----------------------------------------------")?;
+    let mut pending: Vec<(usize, &GroupStats)> = groups.iter().rev().map(|g| (0, g)).collect();
+    while let Some((depth, g)) = pending.pop() {
+        let key = format!("{}{}", "  ".repeat(depth), g.key);
+        writeln!(out, "{}", row(&g.summary, g.ratio.as_ref(), &key))?;
+        pending.extend(g.groups.iter().rev().map(|child| (depth + 1, child)));
+    }
+    writeln!(out, "---")?;
+    writeln!(
+        out,
+        "{}",
+        style.total(&row(summary, Some(&TOTAL_RATIO), "TOTAL"))
+    )
+}
 
+const GROUP_SV_COLUMNS: [&str; 7] = ["group", "files", "lines", "sloc", "chars", "words", "size"];
+
+fn print_groups_sv(
+    out: &mut dyn Write,
+    groups: &[GroupStats],
+    summary: &Summary,
+    by: &[GroupBy],
+    config: &Config,
+    delimiter: Delimiter,
+) -> io::Result<()> {
+    let sep = delimiter.as_str();
+    // Nested groupings get one key column per level, named after the key.
+    let mut header: Vec<String> = if by.len() > 1 {
+        by.iter()
+            .map(|&b| group_heading(b).to_lowercase())
+            .collect()
+    } else {
+        vec![GROUP_SV_COLUMNS[0].to_string()]
+    };
+    header.extend(GROUP_SV_COLUMNS[1..].iter().map(ToString::to_string));
+    if config.count_docs {
+        header.extend([
+            Column::Doc.name().to_string(),
+            Column::DocDensity.name().to_string(),
+        ]);
+    }
+    if config.ratio_of.is_some() {
+        header.extend(["ratio".to_string(), "cumulative".to_string()]);
+    }
+    writeln!(out, "{}", header.join(sep))?;
+
+    let fields = |keys: &[&str], s: &Summary, ratio: Option<&Ratio>| {
+        let mut fields: Vec<String> = (0..by.len().max(1))
+            .map(|i| delimiter.escape(keys.get(i).copied().unwrap_or("")))
+            .collect();
+        fields.extend([
+            s.files.to_string(),
+            s.lines.to_string(),
+            optional_field(s.sloc),
+            s.chars.to_string(),
+            optional_field(s.words),
+            s.size.to_string(),
+        ]);
+        if config.count_docs {
+            fields.push(optional_field(s.doc_lines));
+            fields.push(percent_field(s.doc_density()));
+        }
+        if config.ratio_of.is_some() {
+            fields.push(percent_field(ratio.map(|r| r.percent)));
+            fields.push(percent_field(ratio.and_then(|r| r.cumulative)));
+        }
+        fields.join(sep)
+    };
+    for (keys, g) in leaf_groups(groups) {
+        writeln!(out, "{}", fields(&keys, &g.summary, g.ratio.as_ref()))?;
+    }
     if config.total_row {
-        let total_lines: usize = stats.iter().map(|s| s.lines).sum();
-        let total_sloc: usize = stats.iter().filter_map(|s| s.sloc).sum();
-        let total_chars: usize = stats.iter().map(|s| s.chars).sum();
-        let total_words: usize = stats.iter().filter_map(|s| s.words).sum();
+        writeln!(out, "{}", fields(&["TOTAL"], summary, Some(&TOTAL_RATIO)))?;
+    }
+    Ok(())
+}
 
-        let mut row = format!("{total_lines}");
+/// GitHub-flavored Markdown summary with a bold total row, suitable for
+/// injecting into a README.
+fn print_markdown_summary(
+    out: &mut dyn Write,
+    groups: &[GroupStats],
+    summary: &Summary,
+    by: &[GroupBy],
+    config: &Config,
+) -> io::Result<()> {
+    if config.markdown_badges {
+        let mut badges = vec![
+            shields_badge("files", summary.files),
+            shields_badge("lines", summary.lines),
+        ];
         if config.count_sloc {
-            row.push_str(delimiter);
-            row.push_str(&total_sloc.to_string());
+            badges.push(shields_badge("sloc", summary.sloc.unwrap_or(0)));
         }
+        writeln!(out, "{}", badges.join(" "))?;
+        writeln!(out)?;
+    }
 
-        row.push_str(delimiter);
-        row.push_str(&total_chars.to_string());
+    let mut header = String::from("|");
+    let mut separator = String::from("|");
+    for &b in by {
+        header.push_str(&format!(" {} |", group_heading(b)));
+        separator.push_str(":---|");
+    }
+    header.push_str(" Files | Lines |");
+    separator.push_str("---:|---:|");
+    if config.count_sloc {
+        header.push_str(" SLOC |");
+        separator.push_str("---:|");
+    }
+    header.push_str(" Chars |");
+    separator.push_str("---:|");
+    if config.count_words {
+        header.push_str(" Words |");
+        separator.push_str("---:|");
+    }
+    if config.ratio_of.is_some() {
+        header.push_str(" % | Cum. % |");
+        separator.push_str("---:|---:|");
+    }
+    writeln!(out, "{header}")?;
+    writeln!(out, "{separator}")?;
 
+    let fmt = NumberFormat::new(config);
+    let row = |keys: &[&str], s: &Summary, ratio: Option<&Ratio>, bold: bool| {
+        let cell = |value: String| {
+            if bold && !value.is_empty() {
+                format!(" **{value}** |")
+            } else {
+                format!(" {value} |")
+            }
+        };
+        let mut row = String::from("|");
+        for i in 0..by.len() {
+            row.push_str(&cell(keys.get(i).unwrap_or(&"").replace('|', "\\|")));
+        }
+        row.push_str(&cell(fmt.count(s.files)));
+        row.push_str(&cell(fmt.count(s.lines)));
+        if config.count_sloc {
+            row.push_str(&cell(fmt.count(s.sloc.unwrap_or(0))));
+        }
+        row.push_str(&cell(fmt.count(s.chars)));
         if config.count_words {
-            row.push_str(delimiter);
-            row.push_str(&total_words.to_string());
+            row.push_str(&cell(fmt.count(s.words.unwrap_or(0))));
+        }
+        if config.ratio_of.is_some() {
+            row.push_str(&cell(percent_field(ratio.map(|r| r.percent))));
+            row.push_str(&cell(percent_field(ratio.and_then(|r| r.cumulative))));
+        }
+        row
+    };
+    for (keys, g) in leaf_groups(groups) {
+        writeln!(out, "{}", row(&keys, &g.summary, g.ratio.as_ref(), false))?;
+    }
+    writeln!(
+        out,
+        "{}",
+        row(&["Total"], summary, Some(&TOTAL_RATIO), true)
+    )
+}
+
+/// Writes shields.io endpoint JSON for `badge`:
+/// `{"schemaVersion":1,"label":"lines","message":"12.3k","color":"blue"}`.
+fn print_badge(
+    out: &mut dyn Write,
+    stats: &[FileStats],
+    badge: &Badge,
+    config: &Config,
+) -> io::Result<()> {
+    let metric = match badge.metric {
+        RatioMetric::Files => "files",
+        RatioMetric::Lines => "lines",
+        RatioMetric::Sloc => "sloc",
+        RatioMetric::Chars => "chars",
+        RatioMetric::Words => "words",
+        RatioMetric::Size => "size",
+    };
+    let (label, summary) = match &badge.language {
+        Some(language) => {
+            let group = grouping::group_stats(stats, GroupBy::Language, config)
+                .into_iter()
+                .find(|g| g.key.eq_ignore_ascii_case(language));
+            // Spelled as the language's name, or as given if nothing matched.
+            let name = group.as_ref().map_or(language.as_str(), |g| g.key.as_str());
+            let label = format!("{name} {metric}");
+            (label, group.map(|g| g.summary).unwrap_or_default())
+        }
+        None => (metric.to_string(), Summary::from_stats(stats)),
+    };
+    let value = grouping::metric_value(&summary, badge.metric);
+    let message = if badge.metric == RatioMetric::Size {
+        size_label(value)
+    } else {
+        abbreviate(usize::try_from(value).unwrap_or(usize::MAX))
+    };
+    let color = badge
+        .colors
+        .iter()
+        .rev()
+        .find(|(min, _)| value >= *min)
+        .map_or("blue", |(_, color)| color.as_str());
+    let json = serde_json::json!({
+        "schemaVersion": 1,
+        "label": label,
+        "message": message,
+        "color": color,
+    });
+    writeln!(out, "{json}")
+}
+
+/// A static shields.io badge image, e.g. `![lines](https://img.shields.io/badge/lines-12.3k-blue)`.
+fn shields_badge(label: &str, value: usize) -> String {
+    format!(
+        "![{label}](https://img.shields.io/badge/{label}-{}-blue)",
+        abbreviate(value)
+    )
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Delimiter {
+    Comma,
+    Tab,
+}
+
+impl Delimiter {
+    pub(crate) const fn as_str(self) -> &'static str {
+        match self {
+            Self::Comma => ",",
+            Self::Tab => "\t",
+        }
+    }
+
+    /// Escapes a field so it can't break the record structure.
+    ///
+    /// CSV follows RFC 4180 quoting. TSV has no quoting, so tabs, newlines and
+    /// backslashes are written as backslash escapes instead.
+    pub(crate) fn escape(self, field: &str) -> String {
+        match self {
+            Self::Comma => {
+                if field.contains([',', '"', '\n', '\r']) {
+                    format!("\"{}\"", field.replace('"', "\"\""))
+                } else {
+                    field.to_string()
+                }
+            }
+            Self::Tab => {
+                let mut escaped = String::with_capacity(field.len());
+                for c in field.chars() {
+                    match c {
+                        '\\' => escaped.push_str("\\\\"),
+                        '\t' => escaped.push_str("\\t"),
+                        '\n' => escaped.push_str("\\n"),
+                        '\r' => escaped.push_str("\\r"),
+                        _ => escaped.push(c),
+                    }
+                }
+                escaped
+            }
+        }
+    }
+}
+
+/// `--columns`, or the default CSV/TSV columns.
+fn sv_columns(config: &Config) -> &[Column] {
+    if config.columns.is_empty() {
+        &Column::DEFAULT_SV
+    } else {
+        &config.columns
+    }
+}
+
+/// The value of `column` for one file, numbers written with `fmt`;
+/// `total_lines` is the base of `ratio`.
+#[allow(clippy::cast_precision_loss)]
+fn column_value(column: Column, s: &FileStats, total_lines: usize, fmt: &NumberFormat) -> String {
+    match column {
+        Column::Path => s.path.display().to_string(),
+        Column::Name => s.name.clone(),
+        Column::Ext => s.ext.clone(),
+        Column::Lines => fmt.count(s.lines),
+        Column::Sloc => fmt.optional(s.sloc),
+        Column::Chars => fmt.count(s.chars),
+        Column::Words => fmt.optional(s.words),
+        Column::Size => fmt.bytes(s.size),
+        Column::Mtime => s.mtime.map(|t| t.to_rfc3339()).unwrap_or_default(),
+        Column::MaxLine => fmt.count(s.max_line_length),
+        Column::AvgLine => format!("{:.1}", s.avg_line_length),
+        Column::Ratio => {
+            percent_field((total_lines > 0).then(|| s.lines as f64 * 100.0 / total_lines as f64))
+        }
+        Column::Root => s
+            .root
+            .as_ref()
+            .map(|root| root.display().to_string())
+            .unwrap_or_default(),
+        Column::Doc => fmt.optional(s.doc_lines),
+        Column::DocDensity => percent_field(s.doc_density()),
+        Column::MaxDepth => fmt.optional(s.max_depth),
+        Column::AvgDepth => s.avg_depth.map(|v| format!("{v:.1}")).unwrap_or_default(),
+    }
+}
+
+/// The value of `column` in the TOTAL row over `stats`; the label goes in
+/// the path or name column.
+#[allow(clippy::cast_precision_loss)]
+fn column_total(
+    column: Column,
+    stats: &[FileStats],
+    summary: &Summary,
+    total_lines: usize,
+    fmt: &NumberFormat,
+) -> String {
+    match column {
+        Column::Path | Column::Name => "TOTAL".to_string(),
+        Column::Ext | Column::Mtime | Column::AvgLine | Column::Root | Column::AvgDepth => {
+            String::new()
+        }
+        Column::Lines => fmt.count(summary.lines),
+        Column::Sloc => fmt.optional(summary.sloc),
+        Column::Chars => fmt.count(summary.chars),
+        Column::Words => fmt.optional(summary.words),
+        Column::Size => fmt.bytes(summary.size),
+        Column::MaxLine => fmt.count(stats.iter().map(|s| s.max_line_length).max().unwrap_or(0)),
+        Column::Ratio => percent_field(
+            (total_lines > 0).then(|| summary.lines as f64 * 100.0 / total_lines as f64),
+        ),
+        Column::Doc => fmt.optional(summary.doc_lines),
+        Column::DocDensity => percent_field(summary.doc_density()),
+        Column::MaxDepth => fmt.optional(stats.iter().filter_map(|s| s.max_depth).max()),
+    }
+}
+
+/// The per-file table with the `--columns` layout: numbers right-aligned,
+/// text left-aligned, each column as wide as its widest value.
+fn print_columns_table(
+    out: &mut dyn Write,
+    stats: &[FileStats],
+    total_lines: usize,
+    config: &Config,
+) -> io::Result<()> {
+    let columns = &config.columns;
+    let fmt = NumberFormat::new(config);
+    let mut rows: Vec<Vec<String>> =
+        vec![columns.iter().map(|c| c.name().to_uppercase()).collect()];
+    rows.extend(stats.iter().map(|s| {
+        columns
+            .iter()
+            .map(|&c| column_value(c, s, total_lines, &fmt))
+            .collect()
+    }));
+    let summary = Summary::from_stats(stats);
+    rows.push(
+        columns
+            .iter()
+            .map(|&c| column_total(c, stats, &summary, total_lines, &fmt))
+            .collect(),
+    );
+    let widths: Vec<usize> = (0..columns.len())
+        .map(|i| rows.iter().map(|r| r[i].chars().count()).max().unwrap_or(0))
+        .collect();
+
+    let format_row = |row: &[String]| {
+        let cells: Vec<String> = row
+            .iter()
+            .zip(columns.iter().zip(&widths))
+            .enumerate()
+            .map(|(i, (value, (column, &width)))| {
+                if !column.is_text() {
+                    format!("{value:>width$}")
+                } else if i + 1 == columns.len() {
+                    value.clone()
+                } else {
+                    format!("{value:<width$}")
+                }
+            })
+            .collect();
+        cells.join("  ")
+    };
+    let style = Styler::new(config);
+    let last = rows.len() - 1;
+    for (i, row) in rows.iter().enumerate() {
+        let line = format_row(row);
+        let line = line.trim_end();
+        if i == 0 {
+            writeln!(out, "{}", style.header(line))?;
+            writeln!(out, "----------------------------------------------")?;
+        } else if i == last {
+            writeln!(out, "---")?;
+            writeln!(out, "{}", style.total(line))?;
+        } else if over_highlight(&stats[i - 1], config) {
+            writeln!(out, "{}", style.highlight(line))?;
+        } else {
+            writeln!(out, "{line}")?;
         }
+    }
+    Ok(())
+}
+
+fn optional_field(value: Option<usize>) -> String {
+    value.map(|v| v.to_string()).unwrap_or_default()
+}
+
+/// Writes one row per file. When some files failed, `error_kind` and
+/// `error` columns are added and each failure gets a row with empty counts.
+fn print_sv(
+    out: &mut dyn Write,
+    stats: &[FileStats],
+    errors: &[ErrorRecord],
+    total_lines: usize,
+    config: &Config,
+    delimiter: Delimiter,
+) -> io::Result<()> {
+    let sep = delimiter.as_str();
+    let columns = sv_columns(config);
+    let raw = NumberFormat::default();
+    let with_errors = !errors.is_empty();
+    let mut header: Vec<&str> = columns.iter().map(|c| c.name()).collect();
+    if with_errors {
+        header.extend(["error_kind", "error"]);
+    }
+    writeln!(out, "{}", header.join(sep))?;
+    let error_fields = if with_errors { 2 } else { 0 };
+
+    for s in stats {
+        let mut fields: Vec<String> = columns
+            .iter()
+            .map(|&c| delimiter.escape(&column_value(c, s, total_lines, &raw)))
+            .collect();
+        fields.resize(fields.len() + error_fields, String::new());
+        writeln!(out, "{}", fields.join(sep))?;
+    }
+    for e in errors {
+        let mut fields: Vec<String> = columns
+            .iter()
+            .map(|c| match c {
+                Column::Path => delimiter.escape(&e.path.display().to_string()),
+                Column::Name => e
+                    .path
+                    .file_name()
+                    .map(|n| delimiter.escape(&n.to_string_lossy()))
+                    .unwrap_or_default(),
+                _ => String::new(),
+            })
+            .collect();
+        fields.extend([delimiter.escape(&e.kind), delimiter.escape(&e.message)]);
+        writeln!(out, "{}", fields.join(sep))?;
+    }
+
+    if config.total_row {
+        let summary = Summary::from_stats(stats);
+        let mut fields: Vec<String> = columns
+            .iter()
+            .map(|&c| column_total(c, stats, &summary, total_lines, &raw))
+            .collect();
+        fields.resize(fields.len() + error_fields, String::new());
+        writeln!(out, "{}", fields.join(sep))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use count_lines_engine::encoding::Bom;
+    use count_lines_engine::options::RatioMetric;
+    use count_lines_engine::stats::CacheCounts;
+    use std::path::PathBuf;
+
+    fn render(stats: Vec<FileStats>, config: &Config) -> String {
+        let result = RunResult {
+            stats,
+            ..RunResult::default()
+        };
+        let mut buf = Vec::new();
+        write_results(&mut buf, &result, config).unwrap();
+        String::from_utf8(buf).unwrap()
+    }
+
+    #[test]
+    fn test_csv_columns_and_quoting() {
+        let config = Config {
+            format: OutputFormat::Csv,
+            total_row: true,
+            ..Config::default()
+        };
+        let stats = vec![FileStats {
+            path: PathBuf::from("dir,with \"quote\"/a.rs"),
+            lines: 3,
+            chars: 10,
+            sloc: Some(2),
+            size: 12,
+            ..FileStats::default()
+        }];
+        let out = render(stats, &config);
+        let mut lines = out.lines();
+        assert_eq!(lines.next(), Some("path,lines,sloc,chars,words,size,mtime"));
+        assert_eq!(
+            lines.next(),
+            Some("\"dir,with \"\"quote\"\"/a.rs\",3,2,10,,12,")
+        );
+        assert_eq!(lines.next(), Some("TOTAL,3,2,10,,12,"));
+    }
+
+    #[test]
+    fn test_chosen_columns() {
+        let config = Config {
+            format: OutputFormat::Csv,
+            columns: vec![Column::Name, Column::Lines, Column::Ratio],
+            total_row: true,
+            ..Config::default()
+        };
+        let out = render(markdown_stats(), &config);
+        let lines: Vec<_> = out.lines().collect();
+        assert_eq!(
+            lines,
+            [
+                "name,lines,ratio",
+                "a.rs,10,28.6",
+                "b.rs,20,57.1",
+                "c.py,5,14.3",
+                "TOTAL,35,100.0"
+            ]
+        );
+
+        let config = Config {
+            format: OutputFormat::Table,
+            columns: vec![Column::Lines, Column::Path],
+            ..Config::default()
+        };
+        let out = render(markdown_stats(), &config);
+        let lines: Vec<_> = out.lines().collect();
+        assert_eq!(lines[0], "LINES  PATH");
+        assert_eq!(lines[2], "   10  a.rs");
+        assert_eq!(lines[6], "   35  TOTAL");
+    }
+
+    #[test]
+    fn test_doc_columns() {
+        let stats = || {
+            [("a.rs", 10, 6, 2), ("b.py", 5, 3, 0)]
+                .into_iter()
+                .map(|(path, lines, sloc, doc)| FileStats {
+                    lines,
+                    sloc: Some(sloc),
+                    doc_lines: Some(doc),
+                    ..FileStats::new(PathBuf::from(path))
+                })
+                .collect::<Vec<_>>()
+        };
+        let config = Config {
+            format: OutputFormat::Csv,
+            columns: Column::metric_defaults(true, false),
+            count_docs: true,
+            total_row: true,
+            ..Config::default()
+        };
+        let out = render(stats(), &config);
+        let lines: Vec<_> = out.lines().collect();
+        assert_eq!(
+            lines,
+            [
+                "lines,sloc,doc,doc%,path",
+                "10,6,2,25.0,a.rs",
+                "5,3,0,0.0,b.py",
+                "15,9,2,18.2,TOTAL"
+            ]
+        );
+
+        let config = Config {
+            format: OutputFormat::Csv,
+            group_by: vec![GroupBy::Ext],
+            count_docs: true,
+            ..Config::default()
+        };
+        let out = render(stats(), &config);
+        let lines: Vec<_> = out.lines().collect();
+        assert_eq!(
+            lines,
+            [
+                "group,files,lines,sloc,chars,words,size,doc,doc%",
+                "rs,1,10,6,0,,0,2,25.0",
+                "py,1,5,3,0,,0,0,0.0",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_indent_columns() {
+        let stats = [("flat.py", 8, 1, 0.5), ("deep.rs", 20, 5, 2.25)]
+            .into_iter()
+            .map(|(path, lines, max, avg)| FileStats {
+                lines,
+                max_depth: Some(max),
+                avg_depth: Some(avg),
+                ..FileStats::new(PathBuf::from(path))
+            })
+            .collect();
+        let config = Config {
+            format: OutputFormat::Csv,
+            columns: Column::metric_defaults(false, true),
+            sort: vec![(SortKey::MaxDepth, true)],
+            total_row: true,
+            ..Config::default()
+        };
+        let out = render(stats, &config);
+        let lines: Vec<_> = out.lines().collect();
+        assert_eq!(
+            lines,
+            [
+                "lines,maxdepth,avgdepth,path",
+                "20,5,2.2,deep.rs",
+                "8,1,0.5,flat.py",
+                "28,5,,TOTAL"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_encoding_section() {
+        let stats = || {
+            [
+                ("a.rs", Some(Bom::Utf8), true),
+                ("b.cs", Some(Bom::Utf16Le), false),
+                ("c.txt", None, false),
+                ("d.rs", None, false),
+            ]
+            .into_iter()
+            .map(|(path, bom, invalid_utf8)| FileStats {
+                lines: 1,
+                bom,
+                invalid_utf8,
+                ..FileStats::new(PathBuf::from(path))
+            })
+            .collect::<Vec<_>>()
+        };
+        let config = Config {
+            format: OutputFormat::Table,
+            audit_encoding: true,
+            ..Config::default()
+        };
+        let out = render(stats(), &config);
+        assert!(out.ends_with(
+            "Encoding: 1 UTF-8 BOM, 1 UTF-16 BOM, 0 UTF-32 BOM, 2 without BOM, 1 invalid UTF-8\n\
+             \x20 invalid UTF-8  a.rs\n"
+        ));
+
+        let config = Config {
+            format: OutputFormat::Json,
+            ..config
+        };
+        let json: serde_json::Value = serde_json::from_str(&render(stats(), &config)).unwrap();
+        assert_eq!(json["encoding"]["no_bom"], 2);
+        assert_eq!(
+            json["encoding"]["invalid_utf8"],
+            serde_json::json!(["a.rs"])
+        );
+        assert_eq!(json["files"][0]["bom"], "utf8");
+
+        // Paths are made relative once.
+        let config = Config {
+            relative_to: Some(std::env::current_dir().unwrap().join("src")),
+            ..config
+        };
+        let moved = stats()
+            .into_iter()
+            .map(|s| FileStats {
+                path: Path::new("src").join(&s.path),
+                ..s
+            })
+            .collect();
+        let json: serde_json::Value = serde_json::from_str(&render(moved, &config)).unwrap();
+        assert_eq!(
+            json["encoding"]["invalid_utf8"],
+            serde_json::json!(["a.rs"])
+        );
+
+        // `--top` limits the files listed, not the ones audited.
+        let config = Config {
+            top: Some(1),
+            ..config
+        };
+        let json: serde_json::Value = serde_json::from_str(&render(stats(), &config)).unwrap();
+        assert_eq!(json["files"].as_array().map(Vec::len), Some(1));
+        assert_eq!(json["encoding"]["no_bom"], 2);
+        let config = Config {
+            format: OutputFormat::Table,
+            ..config
+        };
+        assert!(render(stats(), &config).contains("2 without BOM"));
+    }
+
+    #[test]
+    fn test_long_lines_notice() {
+        let stats = vec![
+            FileStats {
+                lines: 2,
+                max_line_length: 3_000_000,
+                long_lines: 1,
+                ..FileStats::new(PathBuf::from("dist/bundle.js"))
+            },
+            FileStats {
+                lines: 5,
+                ..FileStats::new(PathBuf::from("src/a.rs"))
+            },
+        ];
+        let config = Config {
+            format: OutputFormat::Table,
+            thousands_separator: Some(",".to_string()),
+            ..Config::default()
+        };
+        let out = render(stats.clone(), &config);
+        assert!(out.ends_with(
+            "Long lines: 1 files with lines over 1 MiB, not checked for indentation or whitespace\n\
+             \x20      3,000,000 chars  dist/bundle.js\n"
+        ));
+
+        let config = Config {
+            format: OutputFormat::Md,
+            ..config
+        };
+        assert!(render(stats, &config).contains(
+            "_Lines over 1 MiB not checked for indentation or whitespace in: `dist/bundle.js`._"
+        ));
+    }
+
+    #[test]
+    fn test_number_formatting_skips_csv() {
+        let stats = || {
+            vec![FileStats {
+                path: PathBuf::from("big.rs"),
+                name: "big.rs".to_string(),
+                lines: 1_234_567,
+                size: 3 << 20,
+                ..FileStats::default()
+            }]
+        };
+        let columns = vec![Column::Lines, Column::Size, Column::Name];
+        let config = Config {
+            format: OutputFormat::Table,
+            columns: columns.clone(),
+            thousands_separator: Some(",".to_string()),
+            ..Config::default()
+        };
+        let out = render(stats(), &config);
+        assert_eq!(out.lines().nth(2), Some("1,234,567  3,145,728  big.rs"));
+
+        let config = Config {
+            human_numbers: true,
+            ..config
+        };
+        let out = render(stats(), &config);
+        assert_eq!(out.lines().nth(2), Some(" 1.2M  3 MiB  big.rs"));
+
+        let config = Config {
+            format: OutputFormat::Csv,
+            ..config
+        };
+        let out = render(stats(), &config);
+        assert_eq!(out.lines().nth(1), Some("1234567,3145728,big.rs"));
+    }
+
+    #[test]
+    fn test_colored_table() {
+        let config = Config {
+            format: OutputFormat::Table,
+            color: true,
+            highlight_over: Some(15),
+            ..Config::default()
+        };
+        let out = render(markdown_stats(), &config);
+        let lines: Vec<_> = out.lines().collect();
+        assert!(lines[2].starts_with("\u{1b}[38;5;14m\u{1b}[1m    LINES"));
+        assert_eq!(lines[4], "       10             100      a.rs");
+        assert!(lines[5].starts_with("\u{1b}[38;5;9m\u{1b}[1m       20"));
+        assert!(lines[8].starts_with("\u{1b}[1m       35"));
+
+        let config = Config {
+            format: OutputFormat::Json,
+            ..config
+        };
+        assert!(!render(markdown_stats(), &config).contains('\u{1b}'));
+    }
+
+    #[test]
+    fn test_tsv_escapes_control_characters() {
+        let config = Config {
+            format: OutputFormat::Tsv,
+            ..Config::default()
+        };
+        let stats = vec![FileStats {
+            path: PathBuf::from("a\tb\nc.rs"),
+            lines: 1,
+            ..FileStats::default()
+        }];
+        let out = render(stats, &config);
+        assert_eq!(out.lines().nth(1), Some("a\\tb\\nc.rs\t1\t\t0\t\t0\t"));
+    }
+
+    fn failed_run() -> RunResult {
+        RunResult {
+            stats: vec![FileStats {
+                path: PathBuf::from("ok.rs"),
+                lines: 2,
+                ..FileStats::default()
+            }],
+            errors: vec![(
+                PathBuf::from("secret.rs"),
+                count_lines_engine::error::EngineError::FileRead {
+                    path: PathBuf::from("secret.rs"),
+                    source: io::Error::from(io::ErrorKind::PermissionDenied),
+                },
+            )],
+            ..RunResult::default()
+        }
+    }
+
+    fn render_result(result: &RunResult, config: &Config) -> String {
+        let mut buf = Vec::new();
+        write_results(&mut buf, result, config).unwrap();
+        String::from_utf8(buf).unwrap()
+    }
 
-        row.push_str(delimiter);
-        row.push_str("TOTAL");
-        println!("{row}");
+    #[test]
+    fn test_github_annotations() {
+        let result = RunResult {
+            stats: vec![
+                FileStats {
+                    lines: 1200,
+                    ..FileStats::new(PathBuf::from("big.rs"))
+                },
+                FileStats {
+                    lines: 10,
+                    missing_license_header: true,
+                    ..FileStats::new(PathBuf::from("small.rs"))
+                },
+            ],
+            ..RunResult::default()
+        };
+        let config = Config {
+            format: OutputFormat::Github,
+            highlight_over: Some(800),
+            license_header: Some("SPDX".to_string()),
+            top: Some(1),
+            ..Config::default()
+        };
+        assert_eq!(
+            render_result(&result, &config),
+            "::warning file=big.rs,title=Oversized file::1200 lines (limit 800)\n\
+             ::warning file=small.rs,title=Missing license header::\"SPDX\" not found in the first 20 lines\n\
+             ::notice title=count_lines::2 files, 1210 lines\n"
+        );
+    }
+
+    #[test]
+    fn test_sarif_findings() {
+        let mut result = failed_run();
+        result.stats.push(FileStats {
+            lines: 1200,
+            ..FileStats::new(PathBuf::from("big.rs"))
+        });
+        let config = Config {
+            format: OutputFormat::Sarif,
+            highlight_over: Some(800),
+            fail_if: vec!["total_files > 1".parse().unwrap()],
+            ..Config::default()
+        };
+        let log: serde_json::Value =
+            serde_json::from_str(&render_result(&result, &config)).unwrap();
+        let rule_ids: Vec<_> = log["runs"][0]["results"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|r| r["ruleId"].as_str().unwrap())
+            .collect();
+        assert_eq!(
+            rule_ids,
+            [
+                "count-lines/highlight-over",
+                "count-lines/fail-if",
+                "count-lines/read-error"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_errors_in_machine_readable_outputs() {
+        let result = failed_run();
+        let config = |format| Config {
+            format,
+            ..Config::default()
+        };
+
+        let jsonl = render_result(&result, &config(OutputFormat::Jsonl));
+        let records: Vec<serde_json::Value> = jsonl
+            .lines()
+            .map(|l| serde_json::from_str(l).unwrap())
+            .collect();
+        assert_eq!(records[1]["type"], "error");
+        assert_eq!(records[1]["path"], "secret.rs");
+        assert_eq!(records[1]["kind"], "permission_denied");
+        assert_eq!(records[2]["errors"], 1);
+
+        let csv = render_result(&result, &config(OutputFormat::Csv));
+        let mut lines = csv.lines();
+        assert_eq!(
+            lines.next(),
+            Some("path,lines,sloc,chars,words,size,mtime,error_kind,error")
+        );
+        assert_eq!(lines.next(), Some("ok.rs,2,,0,,0,,,"));
+        assert!(
+            lines
+                .next()
+                .unwrap()
+                .starts_with("secret.rs,,,,,,,permission_denied,")
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_relative_path() {
+        let base = Path::new("/work/repo/src");
+        let rel = |p: &str| relative_path(Path::new(p), base);
+        assert_eq!(rel("/work/repo/src/a.rs"), PathBuf::from("a.rs"));
+        assert_eq!(
+            rel("/work/repo/docs/../lib/b.rs"),
+            PathBuf::from("../lib/b.rs")
+        );
+        assert_eq!(rel("/other/c.rs"), PathBuf::from("../../../other/c.rs"));
+        assert_eq!(rel("/work/repo/src"), PathBuf::from("."));
+
+        let config = Config {
+            format: OutputFormat::Csv,
+            relative_to: Some(PathBuf::from("/work/repo")),
+            ..Config::default()
+        };
+        let stats = vec![FileStats {
+            path: PathBuf::from("/work/repo/src/main.rs"),
+            lines: 1,
+            ..FileStats::default()
+        }];
+        assert!(render(stats, &config).contains("\nsrc/main.rs,1,"));
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn test_relative_path_across_drives() {
+        let base = Path::new(r"C:\work");
+        assert_eq!(
+            relative_path(Path::new(r"c:\work\src\a.rs"), base),
+            PathBuf::from(r"src\a.rs")
+        );
+        assert_eq!(
+            relative_path(Path::new(r"D:\data\a.rs"), base),
+            PathBuf::from(r"D:\data\a.rs")
+        );
+    }
+
+    #[test]
+    fn test_errors_only() {
+        let result = failed_run();
+        let config = |format| Config {
+            format,
+            errors_only: true,
+            ..Config::default()
+        };
+
+        let csv = render_result(&result, &config(OutputFormat::Csv));
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines[0], "path,kind,message");
+        assert!(lines[1].starts_with("secret.rs,permission_denied,"));
+        assert_eq!(lines.len(), 2);
+
+        let json = render_result(&result, &config(OutputFormat::Json));
+        let errors: Vec<ErrorRecord> = serde_json::from_str(&json).unwrap();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].kind, "permission_denied");
+
+        let table = render_result(&result, &config(OutputFormat::Table));
+        assert!(table.starts_with("secret.rs\tpermission_denied\t"));
+    }
+
+    #[test]
+    fn test_print0_writes_sorted_raw_paths() {
+        let config = Config {
+            sort: vec![(SortKey::Lines, true)],
+            top: Some(2),
+            null_separated: true,
+            ..Config::default()
+        };
+        let stats = ["a b.rs", "new\nline.rs", "c.rs"]
+            .iter()
+            .zip([1, 3, 2])
+            .map(|(path, lines)| FileStats {
+                path: PathBuf::from(path),
+                lines,
+                ..FileStats::default()
+            })
+            .collect();
+        assert_eq!(render(stats, &config), "new\nline.rs\0c.rs\0");
+    }
+
+    #[test]
+    fn test_sort_is_stable_across_input_orders() {
+        let stats = |order: [usize; 4]| -> Vec<FileStats> {
+            let files = [
+                ("b/x.rs", 5, 3),
+                ("a/y.rs", 5, 3),
+                ("c.rs", 9, 1),
+                ("d.rs", 5, 4),
+            ];
+            order
+                .iter()
+                .map(|&i| {
+                    let (path, lines, sloc) = files[i];
+                    FileStats {
+                        lines,
+                        sloc: Some(sloc),
+                        ..FileStats::new(PathBuf::from(path))
+                    }
+                })
+                .collect()
+        };
+        let sorted = |order, sort: &[(SortKey, bool)]| {
+            let mut stats = stats(order);
+            sort_stats(&mut stats, sort);
+            stats
+                .iter()
+                .map(|s| s.path.to_string_lossy().into_owned())
+                .collect::<Vec<_>>()
+        };
+
+        let by_sloc = [(SortKey::Sloc, true)];
+        let expected = ["d.rs", "a/y.rs", "b/x.rs", "c.rs"];
+        assert_eq!(sorted([0, 1, 2, 3], &by_sloc), expected);
+        assert_eq!(sorted([3, 2, 1, 0], &by_sloc), expected);
+
+        let by_ratio = [(SortKey::Ratio, true), (SortKey::Path, true)];
+        assert_eq!(
+            sorted([1, 3, 0, 2], &by_ratio),
+            ["c.rs", "d.rs", "b/x.rs", "a/y.rs"]
+        );
+        assert_eq!(
+            sorted([2, 0, 3, 1], &[]),
+            ["a/y.rs", "b/x.rs", "c.rs", "d.rs"]
+        );
+    }
+
+    fn markdown_stats() -> Vec<FileStats> {
+        ["a.rs", "b.rs", "c.py"]
+            .iter()
+            .zip([10, 20, 5])
+            .map(|(path, lines)| FileStats {
+                lines,
+                chars: lines * 10,
+                sloc: Some(lines - 1),
+                ..FileStats::new(PathBuf::from(path))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_markdown_summary_by_language() {
+        let config = Config {
+            format: OutputFormat::Markdown,
+            count_sloc: true,
+            ..Config::default()
+        };
+        let out = render(markdown_stats(), &config);
+        assert_eq!(
+            out,
+            "| Language | Files | Lines | SLOC | Chars |\n\
+             |:---|---:|---:|---:|---:|\n\
+             | Rust | 2 | 30 | 28 | 300 |\n\
+             | Python | 1 | 5 | 4 | 50 |\n\
+             | **Total** | **3** | **35** | **32** | **350** |\n"
+        );
+    }
+
+    #[test]
+    fn test_timings() {
+        let mut result = failed_run();
+        result.timings.walk = Duration::from_millis(4);
+        result.timings.total = Duration::from_millis(10);
+        result.cache = Some(CacheCounts { hits: 3, misses: 1 });
+
+        let mut buf = Vec::new();
+        write_timings(&mut buf, &result, Duration::from_millis(2)).unwrap();
+        let report = String::from_utf8(buf).unwrap();
+        assert!(report.contains("  walk             4.0 ms\n"), "{report}");
+        assert!(report.contains("  output           2.0 ms\n"), "{report}");
+        assert!(report.contains("  total           12.0 ms\n"), "{report}");
+        assert!(
+            report.ends_with("  cache              3 hits, 1 misses\n"),
+            "{report}"
+        );
+
+        let config = Config {
+            format: OutputFormat::Json,
+            timings: true,
+            ..Config::default()
+        };
+        assert!(embeds_timings(&config));
+        let json: serde_json::Value =
+            serde_json::from_str(&render_result(&result, &config)).unwrap();
+        assert_eq!(json["timings"]["total_ms"], 10.0);
+        assert_eq!(json["timings"]["stages_ms"]["walk"], 4.0);
+        assert_eq!(json["timings"]["cache"]["hits"], 3);
+        assert!(!embeds_timings(&Config {
+            timings: true,
+            ..Config::default()
+        }));
+    }
+
+    #[test]
+    fn test_with_metadata() {
+        let mut result = failed_run();
+        result.timings.total = std::time::Duration::from_millis(12);
+        let config = |format| Config {
+            format,
+            with_metadata: true,
+            ..Config::default()
+        };
+
+        let json: serde_json::Value =
+            serde_json::from_str(&render_result(&result, &config(OutputFormat::Json))).unwrap();
+        assert_eq!(json["metadata"]["version"], crate::VERSION);
+        assert!(json["metadata"]["roots"].is_array());
+        assert_eq!(json["metadata"]["duration_ms"], 12.0);
+        assert!(json["metadata"]["timings_ms"]["walk"].is_number());
+
+        let jsonl = render_result(&result, &config(OutputFormat::Jsonl));
+        let first: serde_json::Value = serde_json::from_str(jsonl.lines().next().unwrap()).unwrap();
+        assert_eq!(first["type"], "metadata");
+
+        let csv = render_result(&result, &config(OutputFormat::Csv));
+        let comments: Vec<_> = csv.lines().take_while(|l| l.starts_with('#')).collect();
+        assert!(comments.contains(&"# duration_ms: 12.0"), "{comments:?}");
+        assert!(
+            csv.lines()
+                .nth(comments.len())
+                .unwrap()
+                .starts_with("path,")
+        );
+
+        let plain = render_result(&result, &Config::default());
+        assert!(!plain.contains("duration_ms"));
+    }
+
+    #[test]
+    fn test_badge_endpoint_json() {
+        let stats = vec![
+            FileStats {
+                lines: 12_000,
+                sloc: Some(9_000),
+                ext: "rs".to_string(),
+                ..FileStats::new(PathBuf::from("a.rs"))
+            },
+            FileStats {
+                lines: 500,
+                sloc: Some(400),
+                ext: "py".to_string(),
+                ..FileStats::new(PathBuf::from("b.py"))
+            },
+        ];
+        let render = |badge: Badge| {
+            let config = Config {
+                badge: Some(badge),
+                ..Config::default()
+            };
+            let mut buf = Vec::new();
+            print_badge(&mut buf, &stats, config.badge.as_ref().unwrap(), &config).unwrap();
+            serde_json::from_slice::<serde_json::Value>(&buf).unwrap()
+        };
+        let colors = vec![(0, "red".to_string()), (10_000, "green".to_string())];
+
+        let json = render(Badge {
+            metric: RatioMetric::Lines,
+            language: None,
+            colors: colors.clone(),
+        });
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "schemaVersion": 1,
+                "label": "lines",
+                "message": "12.5k",
+                "color": "green",
+            })
+        );
+
+        let json = render(Badge {
+            metric: RatioMetric::Sloc,
+            language: Some("python".to_string()),
+            colors,
+        });
+        assert_eq!(json["label"], "Python sloc");
+        assert_eq!(json["message"], "400");
+        assert_eq!(json["color"], "red");
+
+        let json = render(Badge {
+            metric: RatioMetric::Files,
+            language: Some("Go".to_string()),
+            colors: vec![],
+        });
+        assert_eq!(json["message"], "0");
+        assert_eq!(json["color"], "blue");
+    }
+
+    #[test]
+    fn test_markdown_badges_and_ext_grouping() {
+        let config = Config {
+            format: OutputFormat::Markdown,
+            group_by: vec![GroupBy::Ext],
+            markdown_badges: true,
+            ..Config::default()
+        };
+        let out = render(markdown_stats(), &config);
+        let mut lines = out.lines();
+        assert_eq!(
+            lines.next(),
+            Some(
+                "![files](https://img.shields.io/badge/files-3-blue) \
+                 ![lines](https://img.shields.io/badge/lines-35-blue)"
+            )
+        );
+        assert_eq!(lines.nth(1), Some("| Extension | Files | Lines | Chars |"));
+    }
+
+    #[test]
+    fn test_group_csv_with_total_row() {
+        let config = Config {
+            format: OutputFormat::Csv,
+            group_by: vec![GroupBy::Ext],
+            total_row: true,
+            ..Config::default()
+        };
+        let out = render(markdown_stats(), &config);
+        let lines: Vec<_> = out.lines().collect();
+        assert_eq!(
+            lines,
+            vec![
+                "group,files,lines,sloc,chars,words,size",
+                "rs,2,30,28,300,,0",
+                "py,1,5,4,50,,0",
+                "TOTAL,3,35,32,350,,0",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_nested_groups_table_and_csv() {
+        let stats: Vec<_> = [("src/a.rs", 10), ("src/b.py", 4), ("tests/c.rs", 2)]
+            .into_iter()
+            .map(|(path, lines)| FileStats {
+                lines,
+                ..FileStats::new(PathBuf::from(path))
+            })
+            .collect();
+        let config = Config {
+            group_by: vec![GroupBy::RootDir, GroupBy::Ext],
+            ..Config::default()
+        };
+        let out = render(stats.clone(), &config);
+        let keys: Vec<_> = out
+            .lines()
+            .filter_map(|l| l.split("      ").last())
+            .collect();
+        assert_eq!(keys[0], "DIRECTORY / EXTENSION");
+        assert_eq!(keys[2..6], ["src", "  rs", "  py", "tests"]);
+
+        let config = Config {
+            format: OutputFormat::Csv,
+            ..config
+        };
+        let out = render(stats, &config);
+        let lines: Vec<_> = out.lines().collect();
+        assert_eq!(
+            lines,
+            [
+                "directory,extension,files,lines,sloc,chars,words,size",
+                "src,rs,1,10,,0,,0",
+                "src,py,1,4,,0,,0",
+                "tests,rs,1,2,,0,,0",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_group_csv_with_ratios() {
+        let config = Config {
+            format: OutputFormat::Csv,
+            group_by: vec![GroupBy::Ext],
+            ratio_of: Some(RatioMetric::Files),
+            total_row: true,
+            ..Config::default()
+        };
+        let out = render(markdown_stats(), &config);
+        let lines: Vec<_> = out.lines().collect();
+        assert_eq!(
+            lines,
+            vec![
+                "group,files,lines,sloc,chars,words,size,ratio,cumulative",
+                "rs,2,30,28,300,,0,66.7,66.7",
+                "py,1,5,4,50,,0,33.3,100.0",
+                "TOTAL,3,35,32,350,,0,100.0,",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_tree_with_depth_limit() {
+        let stats = ["./src/main.rs", "./src/cli/args.rs", "./tests/it.rs"]
+            .iter()
+            .map(|path| FileStats {
+                lines: 10,
+                ..FileStats::new(PathBuf::from(path))
+            })
+            .collect();
+        let config = Config {
+            format: OutputFormat::Tree,
+            tree_depth: Some(1),
+            ..Config::default()
+        };
+        assert_eq!(
+            render(stats, &config),
+            "    LINES   FILES  DIRECTORY\n\
+             \x20      30       3  ./\n\
+             \x20      20       2  ├── src/\n\
+             \x20      10       1  └── tests/\n"
+        );
+    }
+
+    #[test]
+    fn test_distribution_covers_files_before_top() {
+        let stats: Vec<_> = [("a.rs", 10, 500), ("b.rs", 30, 3000), ("c.rs", 900, 40_000)]
+            .into_iter()
+            .map(|(path, lines, size)| FileStats {
+                lines,
+                size,
+                ..FileStats::new(PathBuf::from(path))
+            })
+            .collect();
+        let config = Config {
+            format: OutputFormat::Json,
+            top: Some(1),
+            distribution: true,
+            ..Config::default()
+        };
+        let json: serde_json::Value =
+            serde_json::from_str(&render(stats.clone(), &config)).unwrap();
+        assert_eq!(json["distribution"]["lines"]["median"], 30);
+        assert_eq!(json["distribution"]["lines"]["max"], 900);
+        assert_eq!(json["distribution"]["size_histogram"][2]["files"], 0);
+        assert_eq!(json["distribution"]["size_histogram"][3]["files"], 1);
+
+        let config = Config {
+            format: OutputFormat::Table,
+            ..config
+        };
+        let out = render(stats, &config);
+        assert!(out.contains("    SIZE     2.9 KiB    39.1 KiB    39.1 KiB    39.1 KiB\n"));
+        assert!(out.contains(" 1 KiB - 4 KiB  ######################################## 1\n"));
+    }
+
+    #[test]
+    fn test_top_and_top_dirs() {
+        let stats = [("src/a.rs", 5), ("src/b.rs", 7), ("tests/c.rs", 20)]
+            .iter()
+            .map(|(path, lines)| FileStats {
+                lines: *lines,
+                ..FileStats::new(PathBuf::from(path))
+            })
+            .collect();
+        let config = Config {
+            sort: vec![(SortKey::Lines, true)],
+            top: Some(1),
+            top_dirs: Some(2),
+            ..Config::default()
+        };
+        let out = render(stats, &config);
+        assert!(out.contains("TOTAL (1 files)"));
+        assert!(out.ends_with(
+            "Top 2 directories:\n\
+             \x20      20       1 files  tests/\n\
+             \x20      12       2 files  src/\n"
+        ));
+    }
+
+    /// Invariants of sorting, `--top` and grouping over arbitrary results.
+    mod properties {
+        use super::*;
+        use count_lines_engine::expr::FilterExpr;
+        use proptest::prelude::*;
+
+        const SORT_KEYS: [SortKey; 13] = [
+            SortKey::Lines,
+            SortKey::Ratio,
+            SortKey::Chars,
+            SortKey::Size,
+            SortKey::Name,
+            SortKey::Ext,
+            SortKey::Sloc,
+            SortKey::Words,
+            SortKey::MaxLine,
+            SortKey::AvgLine,
+            SortKey::Path,
+            SortKey::MaxDepth,
+            SortKey::AvgDepth,
+        ];
+
+        fn file_stats() -> impl Strategy<Value = Vec<FileStats>> {
+            let file = (
+                0usize..4,
+                prop::sample::select(vec!["rs", "py", "md", ""]),
+                0usize..50,
+                prop::option::of(0usize..50),
+                prop::option::of(0usize..200),
+                0u64..4000,
+            );
+            prop::collection::vec(file, 0..40).prop_map(|files| {
+                files
+                    .into_iter()
+                    .enumerate()
+                    .map(|(i, (dir, ext, lines, sloc, words, size))| {
+                        let name = if ext.is_empty() {
+                            format!("f{i}")
+                        } else {
+                            format!("f{i}.{ext}")
+                        };
+                        FileStats {
+                            path: PathBuf::from(format!("d{dir}/sub{}/{name}", i % 2)),
+                            lines,
+                            chars: lines * 7 % 300,
+                            sloc: sloc.map(|s| s.min(lines)),
+                            words,
+                            size,
+                            max_line_length: lines % 13,
+                            #[allow(clippy::cast_precision_loss)]
+                            avg_line_length: (lines % 5) as f64 / 2.0,
+                            ext: ext.to_string(),
+                            name,
+                            ..FileStats::default()
+                        }
+                    })
+                    .collect()
+            })
+        }
+
+        fn sort_keys() -> impl Strategy<Value = Vec<(SortKey, bool)>> {
+            prop::collection::vec(
+                (prop::sample::select(SORT_KEYS.to_vec()), any::<bool>()),
+                0..3,
+            )
+        }
+
+        /// The `"type":"file"` records of JSONL output.
+        fn file_records(output: &str) -> Vec<&str> {
+            output
+                .lines()
+                .filter(|l| l.contains(r#""type":"file""#))
+                .collect()
+        }
+
+        fn assert_totals(groups: &[GroupStats], total: &Summary) {
+            let sum =
+                |f: fn(&Summary) -> usize| groups.iter().map(|g| f(&g.summary)).sum::<usize>();
+            assert_eq!(sum(|s| s.files), total.files);
+            assert_eq!(sum(|s| s.lines), total.lines);
+            assert_eq!(sum(|s| s.chars), total.chars);
+            assert_eq!(sum(|s| s.sloc.unwrap_or(0)), total.sloc.unwrap_or(0));
+            assert_eq!(sum(|s| s.words.unwrap_or(0)), total.words.unwrap_or(0));
+            assert_eq!(
+                groups.iter().map(|g| g.summary.size).sum::<u64>(),
+                total.size
+            );
+        }
+
+        proptest! {
+            #[test]
+            fn test_sort_commutes_with_filter(
+                stats in file_stats(),
+                sort in sort_keys(),
+                min_lines in 0usize..50,
+            ) {
+                let filter: FilterExpr = format!("lines >= {min_lines}").parse().unwrap();
+
+                let mut sorted_first = stats.clone();
+                sort_stats(&mut sorted_first, &sort);
+                sorted_first.retain(|s| filter.matches(s));
+
+                let mut filtered_first: Vec<_> =
+                    stats.into_iter().filter(|s| filter.matches(s)).collect();
+                sort_stats(&mut filtered_first, &sort);
+
+                prop_assert_eq!(sorted_first, filtered_first);
+            }
+
+            #[test]
+            fn test_sort_ignores_input_order(
+                stats in file_stats(),
+                sort in sort_keys(),
+            ) {
+                let mut forward = stats.clone();
+                let mut reversed: Vec<_> = stats.into_iter().rev().collect();
+                sort_stats(&mut forward, &sort);
+                sort_stats(&mut reversed, &sort);
+                prop_assert_eq!(forward, reversed);
+            }
+
+            #[test]
+            fn test_top_is_prefix_of_full_output(
+                stats in file_stats(),
+                sort in sort_keys(),
+                top in 0usize..50,
+            ) {
+                let config = Config {
+                    format: OutputFormat::Jsonl,
+                    sort: sort.clone(),
+                    ..Config::default()
+                };
+                let full = render(stats.clone(), &config);
+                let limited = render(stats, &Config { top: Some(top), ..config });
+                let full = file_records(&full);
+                let limited = file_records(&limited);
+                prop_assert_eq!(limited.len(), top.min(full.len()));
+                prop_assert_eq!(&full[..limited.len()], &limited[..]);
+            }
+
+            #[test]
+            fn test_top_dirs_is_prefix_of_all_dirs(stats in file_stats(), n in 0usize..20) {
+                let tree = DirTree::build(&stats);
+                let all = tree.top_dirs(usize::MAX);
+                let top = tree.top_dirs(n);
+                prop_assert_eq!(&all[..top.len()], &top[..]);
+            }
+
+            #[test]
+            fn test_group_totals_equal_grand_total(stats in file_stats()) {
+                let config = Config::default();
+                let total = Summary::from_stats(&stats);
+                for by in [GroupBy::Language, GroupBy::Ext, GroupBy::Linguist, GroupBy::RootDir] {
+                    assert_totals(&grouping::group_stats(&stats, by, &config), &total);
+                }
+
+                let by = [GroupBy::Ext, GroupBy::RootDir];
+                let nested = grouping::nested_group_stats(&stats, &by, &config);
+                assert_totals(&nested, &total);
+                for group in &nested {
+                    assert_totals(&group.groups, &group.summary);
+                }
+            }
+        }
     }
 }
@@ -0,0 +1,210 @@
+// crates/cli/src/presentation/sarif.rs
+//! SARIF 2.1.0 output (`--format sarif`) for code scanning dashboards: each
+//! policy violation becomes a result with its rule id, severity and file.
+
+use serde_json::{Value, json};
+use std::fmt::Write as _;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+const SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+const INFORMATION_URI: &str = "https://github.com/jungamer-64/count_lines";
+
+/// The policies a finding can violate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rule {
+    /// `lint --max-file-lines`.
+    MaxFileLines,
+    /// `lint --max-file-chars`.
+    MaxFileChars,
+    /// `--highlight-over`.
+    HighlightOver,
+    /// `--fail-if`.
+    FailIf,
+    /// A file that could not be read.
+    ReadError,
+}
+
+impl Rule {
+    const ALL: [Self; 5] = [
+        Self::MaxFileLines,
+        Self::MaxFileChars,
+        Self::HighlightOver,
+        Self::FailIf,
+        Self::ReadError,
+    ];
+
+    #[must_use]
+    pub const fn id(self) -> &'static str {
+        match self {
+            Self::MaxFileLines => "count-lines/max-file-lines",
+            Self::MaxFileChars => "count-lines/max-file-chars",
+            Self::HighlightOver => "count-lines/highlight-over",
+            Self::FailIf => "count-lines/fail-if",
+            Self::ReadError => "count-lines/read-error",
+        }
+    }
+
+    const fn description(self) -> &'static str {
+        match self {
+            Self::MaxFileLines => "File has more lines than the lint limit",
+            Self::MaxFileChars => "File has more characters than the lint limit",
+            Self::HighlightOver => "File has more lines than --highlight-over",
+            Self::FailIf => "A --fail-if threshold is exceeded",
+            Self::ReadError => "File could not be read",
+        }
+    }
+
+    /// The SARIF `level`: violations that fail the run are errors.
+    #[must_use]
+    pub const fn level(self) -> &'static str {
+        match self {
+            Self::MaxFileLines | Self::MaxFileChars | Self::FailIf => "error",
+            Self::HighlightOver | Self::ReadError => "warning",
+        }
+    }
+
+    fn index(self) -> usize {
+        Self::ALL
+            .iter()
+            .position(|&r| r == self)
+            .unwrap_or_default()
+    }
+}
+
+/// One violation of a [`Rule`], optionally located in a file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Finding {
+    pub rule: Rule,
+    pub message: String,
+    pub path: Option<PathBuf>,
+}
+
+impl Finding {
+    #[must_use]
+    pub fn new(rule: Rule, message: impl Into<String>) -> Self {
+        Self {
+            rule,
+            message: message.into(),
+            path: None,
+        }
+    }
+
+    #[must_use]
+    pub fn file(mut self, path: &Path) -> Self {
+        self.path = Some(path.to_path_buf());
+        self
+    }
+
+    fn to_result(&self) -> Value {
+        let mut result = json!({
+            "ruleId": self.rule.id(),
+            "ruleIndex": self.rule.index(),
+            "level": self.rule.level(),
+            "message": { "text": self.message },
+        });
+        if let Some(path) = &self.path {
+            result["locations"] = json!([{
+                "physicalLocation": { "artifactLocation": { "uri": uri(path) } }
+            }]);
+        }
+        result
+    }
+}
+
+/// The SARIF log of one run with every rule and the given findings.
+#[must_use]
+pub fn log(findings: &[Finding]) -> Value {
+    let rules: Vec<Value> = Rule::ALL
+        .iter()
+        .map(|rule| {
+            json!({
+                "id": rule.id(),
+                "shortDescription": { "text": rule.description() },
+                "defaultConfiguration": { "level": rule.level() },
+            })
+        })
+        .collect();
+    json!({
+        "$schema": SCHEMA,
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "count_lines",
+                    "version": crate::VERSION,
+                    "informationUri": INFORMATION_URI,
+                    "rules": rules,
+                }
+            },
+            "results": findings.iter().map(Finding::to_result).collect::<Vec<_>>(),
+        }],
+    })
+}
+
+/// Writes the SARIF log of `findings` as pretty-printed JSON.
+///
+/// # Errors
+/// Returns an error if writing to `out` fails.
+pub fn write_sarif(out: &mut dyn Write, findings: &[Finding]) -> io::Result<()> {
+    let json = serde_json::to_string_pretty(&log(findings)).map_err(io::Error::other)?;
+    writeln!(out, "{json}")
+}
+
+/// A relative URI reference for `path`: `/` separators, with everything but
+/// unreserved characters percent-encoded.
+fn uri(path: &Path) -> String {
+    let path = path.to_string_lossy().replace('\\', "/");
+    let mut uri = String::with_capacity(path.len());
+    for byte in path.bytes() {
+        if byte.is_ascii_alphanumeric() || b"-._~/".contains(&byte) {
+            uri.push(char::from(byte));
+        } else {
+            let _ = write!(uri, "%{byte:02X}");
+        }
+    }
+    uri
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_log_structure() {
+        let findings = [
+            Finding::new(Rule::MaxFileLines, "lines 1200 > 800").file(Path::new("src/a b.rs")),
+            Finding::new(Rule::FailIf, "total_lines > 10 (total_lines = 12)"),
+        ];
+        let log = log(&findings);
+        assert_eq!(log["version"], "2.1.0");
+        let run = &log["runs"][0];
+        assert_eq!(run["tool"]["driver"]["name"], "count_lines");
+        assert_eq!(
+            run["tool"]["driver"]["rules"][0]["id"],
+            "count-lines/max-file-lines"
+        );
+
+        let results = run["results"].as_array().unwrap();
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0]["ruleId"], "count-lines/max-file-lines");
+        assert_eq!(results[0]["level"], "error");
+        assert_eq!(
+            results[0]["locations"][0]["physicalLocation"]["artifactLocation"]["uri"],
+            "src/a%20b.rs"
+        );
+        assert_eq!(results[1]["ruleIndex"], 3);
+        assert!(results[1].get("locations").is_none());
+    }
+
+    #[test]
+    fn test_rule_indices_match_rules() {
+        let log = log(&[]);
+        let rules = log["runs"][0]["tool"]["driver"]["rules"]
+            .as_array()
+            .unwrap();
+        for rule in Rule::ALL {
+            assert_eq!(rules[rule.index()]["id"], rule.id());
+        }
+    }
+}
@@ -0,0 +1,237 @@
+// crates/cli/src/progress.rs
+use count_lines_engine::progress::Progress;
+use std::io::{self, IsTerminal, Write};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+/// Redraw interval on a terminal.
+const TTY_INTERVAL: Duration = Duration::from_millis(100);
+/// Interval between log lines when stderr is not a terminal.
+const LOG_INTERVAL: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Default)]
+struct Counters {
+    discovered_files: AtomicU64,
+    discovered_bytes: AtomicU64,
+    processed_files: AtomicU64,
+    processed_bytes: AtomicU64,
+    discovery_done: AtomicBool,
+}
+
+impl Progress for Counters {
+    fn discovered(&self, size: u64) {
+        self.discovered_files.fetch_add(1, Ordering::Relaxed);
+        self.discovered_bytes.fetch_add(size, Ordering::Relaxed);
+    }
+
+    fn discovery_finished(&self) {
+        self.discovery_done.store(true, Ordering::Relaxed);
+    }
+
+    fn processed(&self, size: u64) {
+        self.processed_files.fetch_add(1, Ordering::Relaxed);
+        self.processed_bytes.fetch_add(size, Ordering::Relaxed);
+    }
+}
+
+/// Point-in-time view of the counters, used for rendering.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Snapshot {
+    discovered_files: u64,
+    discovered_bytes: u64,
+    processed_files: u64,
+    processed_bytes: u64,
+    discovery_done: bool,
+    elapsed: Duration,
+}
+
+impl Snapshot {
+    fn take(counters: &Counters, started: Instant) -> Self {
+        Self {
+            discovered_files: counters.discovered_files.load(Ordering::Relaxed),
+            discovered_bytes: counters.discovered_bytes.load(Ordering::Relaxed),
+            processed_files: counters.processed_files.load(Ordering::Relaxed),
+            processed_bytes: counters.processed_bytes.load(Ordering::Relaxed),
+            discovery_done: counters.discovery_done.load(Ordering::Relaxed),
+            elapsed: started.elapsed(),
+        }
+    }
+
+    /// Remaining time, once the total is known and some bytes were counted.
+    #[allow(clippy::cast_precision_loss)]
+    fn eta(&self) -> Option<Duration> {
+        if !self.discovery_done || self.processed_bytes == 0 {
+            return None;
+        }
+        let remaining = self.discovered_bytes.saturating_sub(self.processed_bytes);
+        let rate = self.processed_bytes as f64 / self.elapsed.as_secs_f64().max(1e-3);
+        Some(Duration::from_secs_f64(remaining as f64 / rate))
+    }
+
+    #[allow(clippy::cast_precision_loss)]
+    fn render(&self) -> String {
+        let secs = self.elapsed.as_secs_f64().max(1e-3);
+        let total = if self.discovery_done {
+            self.discovered_files.to_string()
+        } else {
+            format!("{}+", self.discovered_files)
+        };
+        let eta = self.eta().map_or_else(|| "--".to_string(), format_duration);
+        format!(
+            "[count_lines] {}/{total} files · {:.0} files/s · {}/s · elapsed {} · ETA {eta}",
+            self.processed_files,
+            self.processed_files as f64 / secs,
+            format_bytes(self.processed_bytes as f64 / secs),
+            format_duration(self.elapsed),
+        )
+    }
+}
+
+/// Progress reporter for `--progress`, drawn on stderr.
+///
+/// On a terminal the status line is redrawn in place; otherwise a line is
+/// logged every few seconds so CI logs stay readable.
+pub struct ProgressBar {
+    counters: Arc<Counters>,
+    stop: Arc<AtomicBool>,
+    handle: Mutex<Option<JoinHandle<()>>>,
+}
+
+impl ProgressBar {
+    /// Starts the render thread.
+    #[must_use]
+    pub fn start() -> Arc<Self> {
+        let counters = Arc::new(Counters::default());
+        let stop = Arc::new(AtomicBool::new(false));
+        let tty = io::stderr().is_terminal();
+        let started = Instant::now();
+
+        let handle = {
+            let counters = counters.clone();
+            let stop = stop.clone();
+            std::thread::spawn(move || {
+                let interval = if tty { TTY_INTERVAL } else { LOG_INTERVAL };
+                let mut last = Instant::now();
+                while !stop.load(Ordering::Relaxed) {
+                    std::thread::sleep(TTY_INTERVAL.min(interval));
+                    if last.elapsed() < interval {
+                        continue;
+                    }
+                    last = Instant::now();
+                    let line = Snapshot::take(&counters, started).render();
+                    let mut err = io::stderr().lock();
+                    let _ = if tty {
+                        write!(err, "\r\x1B[2K{line}")
+                    } else {
+                        writeln!(err, "{line}")
+                    };
+                    let _ = err.flush();
+                }
+                let line = Snapshot::take(&counters, started).render();
+                let mut err = io::stderr().lock();
+                let _ = if tty {
+                    writeln!(err, "\r\x1B[2K{line}")
+                } else {
+                    writeln!(err, "{line}")
+                };
+            })
+        };
+
+        Arc::new(Self {
+            counters,
+            stop,
+            handle: Mutex::new(Some(handle)),
+        })
+    }
+
+    /// Stops the render thread after printing the final status line.
+    pub fn finish(&self) {
+        self.stop.store(true, Ordering::Relaxed);
+        let handle = self.handle.lock().ok().and_then(|mut h| h.take());
+        if let Some(handle) = handle {
+            let _ = handle.join();
+        }
+    }
+}
+
+impl Progress for ProgressBar {
+    fn discovered(&self, size: u64) {
+        self.counters.discovered(size);
+    }
+
+    fn discovery_finished(&self) {
+        self.counters.discovery_finished();
+    }
+
+    fn processed(&self, size: u64) {
+        self.counters.processed(size);
+    }
+}
+
+impl Drop for ProgressBar {
+    fn drop(&mut self) {
+        self.finish();
+    }
+}
+
+fn format_duration(d: Duration) -> String {
+    let secs = d.as_secs();
+    if secs >= 3600 {
+        format!("{}h{:02}m{:02}s", secs / 3600, secs / 60 % 60, secs % 60)
+    } else if secs >= 60 {
+        format!("{}m{:02}s", secs / 60, secs % 60)
+    } else {
+        format!("{:.1}s", d.as_secs_f64())
+    }
+}
+
+fn format_bytes(bytes: f64) -> String {
+    const UNITS: [&str; 4] = ["B", "KiB", "MiB", "GiB"];
+    let mut value = bytes;
+    let mut unit = 0;
+    while value >= 1024.0 && unit + 1 < UNITS.len() {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{value:.1} {}", UNITS[unit])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot(discovery_done: bool) -> Snapshot {
+        Snapshot {
+            discovered_files: 10,
+            discovered_bytes: 4096,
+            processed_files: 5,
+            processed_bytes: 1024,
+            discovery_done,
+            elapsed: Duration::from_secs(2),
+        }
+    }
+
+    #[test]
+    fn test_eta_needs_complete_discovery() {
+        assert_eq!(snapshot(false).eta(), None);
+        assert_eq!(snapshot(true).eta(), Some(Duration::from_secs(6)));
+    }
+
+    #[test]
+    fn test_render() {
+        assert_eq!(
+            snapshot(false).render(),
+            "[count_lines] 5/10+ files · 2 files/s · 512.0 B/s · elapsed 2.0s · ETA --"
+        );
+        assert!(snapshot(true).render().ends_with("ETA 6.0s"));
+    }
+
+    #[test]
+    fn test_format_helpers() {
+        assert_eq!(format_duration(Duration::from_secs(3725)), "1h02m05s");
+        assert_eq!(format_duration(Duration::from_secs(65)), "1m05s");
+        assert_eq!(format_bytes(1536.0), "1.5 KiB");
+    }
+}
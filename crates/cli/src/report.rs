@@ -0,0 +1,256 @@
+// crates/cli/src/report.rs
+use crate::config::Config;
+use chrono::{DateTime, Local};
+use count_lines_engine::encoding::EncodingReport;
+use count_lines_engine::git;
+use count_lines_engine::grouping::{DirStats, GroupStats};
+use count_lines_engine::limits::ScanLimit;
+use count_lines_engine::similarity::SimilarPair;
+use count_lines_engine::stats::{
+    CacheCounts, Distribution, ErrorRecord, FileStats, LinkReport, RunResult, StageTimings, Summary,
+};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// Where and how a run happened, added to the structured outputs by
+/// `--with-metadata`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RunMetadata {
+    /// Version of `count_lines` that made the run.
+    pub version: String,
+    pub started_at: Option<DateTime<Local>>,
+    pub host: Option<String>,
+    /// The walked roots, as given.
+    pub roots: Vec<PathBuf>,
+    /// `HEAD` of the repository of the first root, if it is in one.
+    pub git_commit: Option<String>,
+    /// Wall time of the whole run in milliseconds.
+    pub duration_ms: f64,
+    /// Wall time of each stage in milliseconds; the walk and the measuring
+    /// overlap.
+    pub timings_ms: StageMillis,
+}
+
+/// [`StageTimings`] in milliseconds.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct StageMillis {
+    pub walk: f64,
+    /// Encoding detection, summed over the counting threads; part of
+    /// `measure`.
+    #[serde(default)]
+    pub detect: f64,
+    pub measure: f64,
+    pub aggregate: f64,
+}
+
+/// Stage timings and cache use of a run, added to `--format json` by
+/// `--timings`.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct RunTimings {
+    /// Wall time of the whole run in milliseconds, without the output.
+    pub total_ms: f64,
+    pub stages_ms: StageMillis,
+    /// Hits and misses of the incremental cache, when it was used.
+    pub cache: Option<CacheCounts>,
+}
+
+impl RunTimings {
+    #[must_use]
+    pub fn collect(result: &RunResult) -> Self {
+        Self {
+            total_ms: millis(result.timings.total),
+            stages_ms: StageMillis::from(result.timings),
+            cache: result.cache,
+        }
+    }
+}
+
+impl RunMetadata {
+    /// Collects the metadata of `result`, looking up the git commit and the
+    /// host name.
+    #[must_use]
+    pub fn collect(result: &RunResult, config: &Config) -> Self {
+        let host = gethostname::gethostname().into_string().ok();
+        Self {
+            version: crate::VERSION.to_string(),
+            started_at: result.started_at,
+            host: host.filter(|h| !h.is_empty()),
+            roots: config.walk.roots.clone(),
+            git_commit: git::head_commit(config).ok(),
+            duration_ms: millis(result.timings.total),
+            timings_ms: StageMillis::from(result.timings),
+        }
+    }
+}
+
+impl From<StageTimings> for StageMillis {
+    fn from(timings: StageTimings) -> Self {
+        Self {
+            walk: millis(timings.walk),
+            detect: millis(timings.detect),
+            measure: millis(timings.measure),
+            aggregate: millis(timings.aggregate),
+        }
+    }
+}
+
+fn millis(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+/// Stable JSON document emitted by `--format json`.
+///
+/// The schema is versioned through `version` so snapshots written by older
+/// releases can still be recognised by `--compare`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JsonReport {
+    /// Version of `count_lines` that produced the report.
+    pub version: String,
+    /// Per-file statistics, in output order.
+    pub files: Vec<FileStats>,
+    /// Totals over `files`.
+    pub summary: Summary,
+    /// Files that could not be processed.
+    #[serde(default)]
+    pub errors: Vec<ErrorRecord>,
+    /// True if the run was interrupted and `files` is incomplete.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub partial: bool,
+    /// The scan limit that stopped the walk, with `--on-limit truncate`;
+    /// `files` is then incomplete.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub truncated: Option<ScanLimit>,
+    /// True if the run ran past `--timeout`; the files it didn't get to are
+    /// in `errors`.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub timed_out: bool,
+    /// Heaviest directories, when requested with `--top-dirs`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub top_dirs: Option<Vec<DirStats>>,
+    /// Percentiles and size histogram, when requested with `--distribution`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub distribution: Option<Distribution>,
+    /// Near-duplicate file pairs, when requested with `--detect-similar`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub similar: Option<Vec<SimilarPair>>,
+    /// Totals of the minified files left out of `files` and `summary`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub minified: Option<Summary>,
+    /// Symlinks met during the walk, when requested with `--report-links`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub links: Option<LinkReport>,
+    /// BOMs and invalid UTF-8, when requested with `--audit-encoding`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub encoding: Option<EncodingReport>,
+    /// The run itself, when requested with `--with-metadata`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<RunMetadata>,
+    /// Stage timings and cache use, when requested with `--timings`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timings: Option<RunTimings>,
+}
+
+impl JsonReport {
+    #[must_use]
+    pub fn new(files: Vec<FileStats>, errors: Vec<ErrorRecord>) -> Self {
+        let summary = Summary::from_stats(&files);
+        Self {
+            version: crate::VERSION.to_string(),
+            files,
+            summary,
+            errors,
+            partial: false,
+            truncated: None,
+            timed_out: false,
+            top_dirs: None,
+            distribution: None,
+            similar: None,
+            minified: None,
+            links: None,
+            encoding: None,
+            metadata: None,
+            timings: None,
+        }
+    }
+}
+
+/// JSON document emitted by `--format json --by <group>`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GroupReport {
+    /// Version of `count_lines` that produced the report.
+    pub version: String,
+    /// Per-group totals, largest first.
+    pub groups: Vec<GroupStats>,
+    /// Totals over all files.
+    pub summary: Summary,
+    /// The run itself, when requested with `--with-metadata`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<RunMetadata>,
+    /// Stage timings and cache use, when requested with `--timings`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timings: Option<RunTimings>,
+}
+
+impl GroupReport {
+    #[must_use]
+    pub fn new(groups: Vec<GroupStats>, summary: Summary) -> Self {
+        Self {
+            version: crate::VERSION.to_string(),
+            groups,
+            summary,
+            metadata: None,
+            timings: None,
+        }
+    }
+}
+
+/// Any JSON document accepted as a snapshot.
+///
+/// Older releases wrote a bare array of file statistics.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub enum Snapshot {
+    Report(Box<JsonReport>),
+    Legacy(Vec<FileStats>),
+}
+
+impl Snapshot {
+    #[must_use]
+    pub fn into_files(self) -> Vec<FileStats> {
+        match self {
+            Self::Report(report) => report.files,
+            Self::Legacy(files) => files,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_report_roundtrip() {
+        let files = vec![FileStats {
+            lines: 3,
+            path: PathBuf::from("a.rs"),
+            ..Default::default()
+        }];
+        let report = JsonReport::new(files, vec![]);
+        assert_eq!(report.summary.lines, 3);
+
+        let json = serde_json::to_string(&report).unwrap();
+        let snapshot: Snapshot = serde_json::from_str(&json).unwrap();
+        assert!(matches!(snapshot, Snapshot::Report(_)));
+        assert_eq!(snapshot.into_files().len(), 1);
+    }
+
+    #[test]
+    fn test_legacy_snapshot() {
+        let json = r#"[{"path":"a.rs","lines":1,"chars":2,"words":null,"size":3,"mtime":null,"ext":"rs","name":"a.rs","is_binary":false}]"#;
+        let snapshot: Snapshot = serde_json::from_str(json).unwrap();
+        assert!(matches!(snapshot, Snapshot::Legacy(_)));
+        assert_eq!(snapshot.into_files()[0].lines, 1);
+    }
+}
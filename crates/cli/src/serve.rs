@@ -0,0 +1,256 @@
+// crates/cli/src/serve.rs
+//! `count_lines serve`: keeps the latest count in memory and answers JSON
+//! queries over HTTP, recounting on `POST /rescan` through the incremental
+//! cache so only changed files are read again.
+
+use crate::args::ServeArgs;
+use crate::config::Config;
+use crate::error::Result;
+use crate::http::{self, Request};
+use crate::options::SortSpec;
+use crate::presentation;
+use count_lines_engine::grouping::group_stats;
+use count_lines_engine::options::{GroupBy, SortKey};
+use count_lines_engine::stats::{FileStats, RunResult, Summary};
+use std::net::TcpListener;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex, PoisonError, RwLock};
+
+/// The count being served and when it was taken.
+struct Snapshot {
+    result: RunResult,
+    generated_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// State shared by the connection threads.
+pub struct Server {
+    config: Config,
+    snapshot: RwLock<Snapshot>,
+    /// Serialises rescans so two don't write the cache at once.
+    rescanning: Mutex<()>,
+}
+
+impl Server {
+    /// Counts once and keeps the result.
+    ///
+    /// # Errors
+    /// Returns an error if the count fails.
+    pub fn new(mut config: Config) -> Result<Self> {
+        config.incremental = true;
+        let result = count_lines_engine::run(&config)?;
+        Ok(Self {
+            config,
+            snapshot: RwLock::new(Snapshot {
+                result,
+                generated_at: chrono::Utc::now(),
+            }),
+            rescanning: Mutex::new(()),
+        })
+    }
+
+    /// Routes one request, returning the status line and a JSON body.
+    #[must_use]
+    pub fn handle(&self, request: &Request) -> (&'static str, serde_json::Value) {
+        match (request.method.as_str(), request.path.as_str()) {
+            ("GET", "/summary") => ("200 OK", self.summary()),
+            ("GET", "/files") => match self.files(request) {
+                Ok(files) => ("200 OK", files),
+                Err(message) => ("400 Bad Request", error(&message)),
+            },
+            ("POST", "/rescan") => match self.rescan() {
+                Ok(()) => ("200 OK", self.summary()),
+                Err(e) => ("500 Internal Server Error", error(&e.to_string())),
+            },
+            (_, "/summary" | "/files" | "/rescan") => {
+                ("405 Method Not Allowed", error("method not allowed"))
+            }
+            _ => ("404 Not Found", error("not found")),
+        }
+    }
+
+    /// Totals, per-language totals and errors of the current count.
+    fn summary(&self) -> serde_json::Value {
+        let snapshot = self.snapshot.read().unwrap_or_else(PoisonError::into_inner);
+        let result = &snapshot.result;
+        serde_json::json!({
+            "version": crate::VERSION,
+            "generated_at": snapshot.generated_at.to_rfc3339(),
+            "partial": result.partial,
+            "summary": Summary::from_stats(&result.stats),
+            "by_language": group_stats(&result.stats, GroupBy::Language, &self.config),
+            "errors": result.error_records(),
+        })
+    }
+
+    /// The files of the current count: `sort` takes `--sort` keys, `ext`
+    /// keeps one extension and `top` keeps the first N.
+    fn files(&self, request: &Request) -> std::result::Result<serde_json::Value, String> {
+        let sort: Vec<(SortKey, bool)> = match request.param("sort") {
+            Some(spec) => SortSpec::from_str(spec)?
+                .0
+                .into_iter()
+                .map(|(key, desc)| (SortKey::from(key), desc))
+                .collect(),
+            None => self.config.sort.clone(),
+        };
+        let top = request
+            .param("top")
+            .map(|n| n.parse::<usize>().map_err(|_| format!("invalid top: {n}")))
+            .transpose()?;
+        let ext = request.param("ext").map(|e| e.trim_start_matches('.'));
+
+        let snapshot = self.snapshot.read().unwrap_or_else(PoisonError::into_inner);
+        let mut files: Vec<FileStats> = snapshot
+            .result
+            .stats
+            .iter()
+            .filter(|s| !s.is_binary)
+            .filter(|s| ext.is_none_or(|e| s.ext.eq_ignore_ascii_case(e)))
+            .cloned()
+            .collect();
+        drop(snapshot);
+        presentation::sort_stats(&mut files, &sort);
+        if let Some(n) = top {
+            files.truncate(n);
+        }
+        serde_json::to_value(files).map_err(|e| e.to_string())
+    }
+
+    /// Counts again and replaces the served result.
+    fn rescan(&self) -> Result<()> {
+        let _rescanning = self
+            .rescanning
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        let result = count_lines_engine::run(&self.config)?;
+        *self
+            .snapshot
+            .write()
+            .unwrap_or_else(PoisonError::into_inner) = Snapshot {
+            result,
+            generated_at: chrono::Utc::now(),
+        };
+        Ok(())
+    }
+}
+
+fn error(message: &str) -> serde_json::Value {
+    serde_json::json!({ "error": message })
+}
+
+/// Runs the API server on `args.listen` until the process is stopped.
+///
+/// # Errors
+/// Returns an error if the address can't be bound or the first count fails.
+pub fn run(args: ServeArgs, mut config: Config) -> Result<()> {
+    if !args.paths.is_empty() {
+        config.walk.roots = args.paths;
+    }
+    let listener = TcpListener::bind(args.listen)?;
+    let server = Arc::new(Server::new(config)?);
+    eprintln!(
+        "[count_lines] Serving the API on http://{}",
+        listener.local_addr()?
+    );
+
+    for stream in listener.incoming() {
+        let stream = match stream {
+            Ok(stream) => stream,
+            Err(e) => {
+                log::warn!("Connection error: {e}");
+                continue;
+            }
+        };
+        let server = server.clone();
+        std::thread::spawn(move || {
+            let handled = http::read_request(&stream).and_then(|request| {
+                let (status, body) = server.handle(&request);
+                http::write_response(&stream, status, "application/json", &format!("{body}\n"))
+            });
+            if let Err(e) = handled {
+                log::warn!("Connection error: {e}");
+            }
+        });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use count_lines_engine::config::WalkOptions;
+
+    fn request(method: &str, target: &str) -> Request {
+        let (path, query) = target.split_once('?').unwrap_or((target, ""));
+        Request {
+            method: method.to_string(),
+            path: path.to_string(),
+            query: query
+                .split('&')
+                .filter_map(|p| p.split_once('='))
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+        }
+    }
+
+    fn server(dir: &std::path::Path) -> Server {
+        std::fs::write(dir.join("a.rs"), "a\n").unwrap();
+        std::fs::write(dir.join("b.rs"), "b\nb\nb\n").unwrap();
+        std::fs::write(dir.join("c.py"), "c\nc\n").unwrap();
+        Server::new(Config {
+            walk: WalkOptions {
+                roots: vec![dir.to_path_buf()],
+                ..WalkOptions::default()
+            },
+            cache_dir: Some(dir.join(".cache")),
+            ..Config::default()
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn test_summary_and_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let server = server(dir.path());
+
+        let (status, summary) = server.handle(&request("GET", "/summary"));
+        assert_eq!(status, "200 OK");
+        assert_eq!(summary["summary"]["files"], 3);
+        assert_eq!(summary["summary"]["lines"], 6);
+        assert_eq!(summary["by_language"][0]["key"], "Rust");
+
+        let (_, files) = server.handle(&request("GET", "/files?sort=lines:desc&top=2"));
+        let names: Vec<&str> = files
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|f| f["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(names, ["b.rs", "c.py"]);
+
+        let (_, files) = server.handle(&request("GET", "/files?ext=py"));
+        assert_eq!(files.as_array().unwrap().len(), 1);
+
+        let (status, _) = server.handle(&request("GET", "/files?sort=bogus"));
+        assert_eq!(status, "400 Bad Request");
+    }
+
+    #[test]
+    fn test_rescan_picks_up_changes() {
+        let dir = tempfile::tempdir().unwrap();
+        let server = server(dir.path());
+        std::fs::write(dir.path().join("d.rs"), "d\n").unwrap();
+
+        let (_, summary) = server.handle(&request("GET", "/summary"));
+        assert_eq!(summary["summary"]["files"], 3);
+        let (status, summary) = server.handle(&request("POST", "/rescan"));
+        assert_eq!(status, "200 OK");
+        assert_eq!(summary["summary"]["files"], 4);
+
+        assert_eq!(
+            server.handle(&request("GET", "/rescan")).0,
+            "405 Method Not Allowed"
+        );
+        assert_eq!(server.handle(&request("GET", "/nope")).0, "404 Not Found");
+    }
+}
@@ -0,0 +1,322 @@
+// crates/cli/src/snapshot.rs
+use crate::args::SnapshotAction;
+use crate::compare;
+use crate::config::Config;
+use crate::error::{AppError, Result};
+use crate::report::JsonReport;
+use chrono::{DateTime, Local, NaiveDateTime, TimeZone};
+use count_lines_engine::stats::{FileStats, RunResult};
+use std::fs::{self, OpenOptions};
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+/// Millisecond precision, so snapshots saved within one second get
+/// different names.
+const TIMESTAMP_FORMAT: &str = "%Y%m%dT%H%M%S%3f";
+/// Names of snapshots saved before the milliseconds were added.
+const LEGACY_TIMESTAMP_FORMAT: &str = "%Y%m%dT%H%M%S";
+
+/// A snapshot saved under `<cache dir>/snapshots`.
+///
+/// The file name encodes the creation time and optional tag
+/// (`20240101T120000123_v1.2.json`), so listing doesn't need to parse the files.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SnapshotEntry {
+    pub path: PathBuf,
+    pub created_at: DateTime<Local>,
+    pub tag: Option<String>,
+}
+
+impl SnapshotEntry {
+    fn from_path(path: PathBuf) -> Option<Self> {
+        if path.extension().is_none_or(|ext| ext != "json") {
+            return None;
+        }
+        let stem = path.file_stem()?.to_str()?;
+        let (timestamp, tag) = match stem.split_once('_') {
+            Some((timestamp, tag)) => (timestamp, Some(tag.to_string())),
+            None => (stem, None),
+        };
+        let naive = NaiveDateTime::parse_from_str(timestamp, TIMESTAMP_FORMAT)
+            .or_else(|_| NaiveDateTime::parse_from_str(timestamp, LEGACY_TIMESTAMP_FORMAT))
+            .ok()?;
+        let created_at = Local.from_local_datetime(&naive).earliest()?;
+        Some(Self {
+            path,
+            created_at,
+            tag,
+        })
+    }
+
+    /// File name without the `.json` extension.
+    #[must_use]
+    pub fn name(&self) -> String {
+        self.path
+            .file_stem()
+            .map(|s| s.to_string_lossy().into_owned())
+            .unwrap_or_default()
+    }
+}
+
+/// Timestamped snapshot history stored in the cache directory.
+#[derive(Debug, Clone)]
+pub struct SnapshotStore {
+    dir: PathBuf,
+}
+
+impl SnapshotStore {
+    #[must_use]
+    pub fn new(cache_dir: &Path) -> Self {
+        Self {
+            dir: cache_dir.join("snapshots"),
+        }
+    }
+
+    /// Saves `report` as a new snapshot.
+    ///
+    /// # Errors
+    /// Returns an error if the tag is invalid or already used, or the file
+    /// cannot be written.
+    pub fn save(
+        &self,
+        report: &JsonReport,
+        tag: Option<&str>,
+        now: DateTime<Local>,
+    ) -> Result<SnapshotEntry> {
+        if let Some(tag) = tag {
+            validate_tag(tag)?;
+            if self.list()?.iter().any(|e| e.tag.as_deref() == Some(tag)) {
+                return Err(AppError::Snapshot(format!("tag '{tag}' already exists")));
+            }
+        }
+
+        fs::create_dir_all(&self.dir)?;
+        let timestamp = now.format(TIMESTAMP_FORMAT);
+        let file_name = match tag {
+            Some(tag) => format!("{timestamp}_{tag}.json"),
+            None => format!("{timestamp}.json"),
+        };
+        let path = self.dir.join(file_name);
+
+        let file = OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&path)
+            .map_err(|err| match err.kind() {
+                std::io::ErrorKind::AlreadyExists => {
+                    AppError::Snapshot(format!("snapshot {} already exists", path.display()))
+                }
+                _ => AppError::Io(err),
+            })?;
+        let mut out = BufWriter::new(file);
+        serde_json::to_writer_pretty(&mut out, report)?;
+        out.flush()?;
+
+        SnapshotEntry::from_path(path)
+            .ok_or_else(|| AppError::Snapshot("failed to name snapshot".to_string()))
+    }
+
+    /// Lists saved snapshots, oldest first.
+    ///
+    /// # Errors
+    /// Returns an error if the snapshot directory cannot be read.
+    pub fn list(&self) -> Result<Vec<SnapshotEntry>> {
+        if !self.dir.exists() {
+            return Ok(vec![]);
+        }
+        let mut entries: Vec<SnapshotEntry> = fs::read_dir(&self.dir)?
+            .filter_map(|entry| entry.ok())
+            .filter_map(|entry| SnapshotEntry::from_path(entry.path()))
+            .collect();
+        entries.sort_by(|a, b| a.created_at.cmp(&b.created_at).then(a.path.cmp(&b.path)));
+        Ok(entries)
+    }
+
+    /// Finds a snapshot by tag, by name, or `latest`.
+    ///
+    /// # Errors
+    /// Returns an error if no snapshot matches.
+    pub fn resolve(&self, name: &str) -> Result<SnapshotEntry> {
+        let entries = self.list()?;
+        let found = if name == "latest" {
+            entries.last()
+        } else {
+            entries
+                .iter()
+                .rev()
+                .find(|e| e.tag.as_deref() == Some(name) || e.name() == name)
+        };
+        found
+            .cloned()
+            .ok_or_else(|| AppError::Snapshot(format!("snapshot '{name}' not found")))
+    }
+}
+
+fn validate_tag(tag: &str) -> Result<()> {
+    let valid = !tag.is_empty()
+        && tag
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_'));
+    if valid {
+        Ok(())
+    } else {
+        Err(AppError::Snapshot(format!(
+            "invalid tag '{tag}': use letters, digits, '.', '-' or '_'"
+        )))
+    }
+}
+
+/// Builds the report that is stored as a snapshot (binary files excluded, sorted by path).
+#[must_use]
+pub fn report_from_result(result: &RunResult) -> JsonReport {
+    let mut files: Vec<FileStats> = result
+        .stats
+        .iter()
+        .filter(|s| !s.is_binary)
+        .cloned()
+        .collect();
+    files.sort_by(|a, b| a.path.cmp(&b.path));
+    JsonReport::new(files, result.error_records())
+}
+
+/// Runs a `snapshot` subcommand.
+///
+/// # Errors
+/// Returns an error if the scan fails or a snapshot cannot be read or written.
+pub fn run(action: SnapshotAction, mut config: Config) -> Result<()> {
+    let store = SnapshotStore::new(&config.resolved_cache_dir());
+
+    match action {
+        SnapshotAction::Save { tag, paths } => {
+            set_roots(&mut config, paths);
+            let result = scan(&config)?;
+            let entry = store.save(&report_from_result(&result), tag.as_deref(), Local::now())?;
+            println!("Saved snapshot {}", entry.path.display());
+        }
+        SnapshotAction::Diff {
+            baseline,
+            to,
+            paths,
+        } => {
+            let old = compare::load_stats(&store.resolve(&baseline)?.path)?;
+            let new = if let Some(to) = to {
+                compare::load_stats(&store.resolve(&to)?.path)?
+            } else {
+                set_roots(&mut config, paths);
+                report_from_result(&scan(&config)?).files
+            };
+            compare::print_comparison(&old, &new, &config)?;
+        }
+        SnapshotAction::List => {
+            for entry in store.list()? {
+                println!(
+                    "{}  {}  {}",
+                    entry.name(),
+                    entry.created_at.to_rfc3339(),
+                    entry.tag.as_deref().unwrap_or("-")
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
+fn set_roots(config: &mut Config, paths: Vec<PathBuf>) {
+    if !paths.is_empty() {
+        config.walk.roots = paths;
+    }
+}
+
+fn scan(config: &Config) -> Result<RunResult> {
+    let result = count_lines_engine::run(config)?;
+    for (path, err) in &result.errors {
+        log::warn!("Error processing {}: {err}", path.display());
+    }
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn report(lines: usize) -> JsonReport {
+        JsonReport::new(
+            vec![FileStats {
+                lines,
+                ..FileStats::new(PathBuf::from("a.rs"))
+            }],
+            vec![],
+        )
+    }
+
+    #[test]
+    fn test_save_list_resolve() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = SnapshotStore::new(dir.path());
+        let now = Local::now();
+
+        let first = store.save(&report(1), Some("v1.2"), now).unwrap();
+        let second = store
+            .save(&report(2), None, now + Duration::seconds(1))
+            .unwrap();
+
+        let entries = store.list().unwrap();
+        assert_eq!(entries, vec![first.clone(), second.clone()]);
+        assert_eq!(store.resolve("v1.2").unwrap(), first);
+        assert_eq!(store.resolve("latest").unwrap(), second);
+        assert_eq!(store.resolve(&second.name()).unwrap(), second);
+        assert!(store.resolve("v9").is_err());
+
+        let stats = compare::load_stats(&first.path).unwrap();
+        assert_eq!(stats[0].lines, 1);
+    }
+
+    #[test]
+    fn test_snapshots_within_one_second_dont_collide() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = SnapshotStore::new(dir.path());
+        let at = Local.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).unwrap();
+
+        let first = store.save(&report(1), None, at).unwrap();
+        let second = store
+            .save(&report(2), None, at + Duration::milliseconds(250))
+            .unwrap();
+        assert_eq!(first.name(), "20240101T120000000");
+        assert_eq!(second.name(), "20240101T120000250");
+        assert_eq!(store.list().unwrap(), vec![first, second.clone()]);
+        assert_eq!(store.resolve("latest").unwrap(), second);
+    }
+
+    #[test]
+    fn test_lists_legacy_names() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = SnapshotStore::new(dir.path());
+        std::fs::create_dir_all(dir.path().join("snapshots")).unwrap();
+        let old = dir.path().join("snapshots/20240101T120000_v1.json");
+        std::fs::write(&old, "{}").unwrap();
+        let new = store.save(&report(1), None, Local::now()).unwrap();
+
+        let entries = store.list().unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].path, old);
+        assert_eq!(entries[0].tag.as_deref(), Some("v1"));
+        assert_eq!(entries[1], new);
+    }
+
+    #[test]
+    fn test_rejects_duplicate_and_invalid_tags() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = SnapshotStore::new(dir.path());
+        let now = Local::now();
+
+        store.save(&report(1), Some("v1"), now).unwrap();
+        assert!(
+            store
+                .save(&report(1), Some("v1"), now + Duration::seconds(1))
+                .is_err()
+        );
+        assert!(store.save(&report(1), Some("../x"), now).is_err());
+        assert!(store.save(&report(1), Some(""), now).is_err());
+    }
+}
@@ -0,0 +1,136 @@
+// crates/cli/src/style.rs
+//! Terminal styling of the table and tree outputs (`--color`, `--theme`).
+//!
+//! Rows are padded before they are styled, so the escape codes never count
+//! towards column widths. Every other format is written unstyled.
+
+use crate::config::Config;
+use crate::options::ColorChoice;
+use count_lines_engine::options::{OutputFormat, Theme};
+use crossterm::style::{Attribute, Color, ContentStyle, Stylize};
+use std::io::IsTerminal;
+
+/// Whether output should be colored: `always` and `never` are taken as
+/// is, `auto` colors a terminal stdout unless `NO_COLOR` is set.
+#[must_use]
+pub fn use_color(choice: ColorChoice, to_file: bool) -> bool {
+    match choice {
+        ColorChoice::Always => true,
+        ColorChoice::Never => false,
+        ColorChoice::Auto => {
+            !to_file
+                && std::env::var_os("NO_COLOR").is_none_or(|v| v.is_empty())
+                && std::io::stdout().is_terminal()
+        }
+    }
+}
+
+/// Styles for the parts of a table.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Styler {
+    /// `None` when output is not colored.
+    palette: Option<Palette>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Palette {
+    header: ContentStyle,
+    total: ContentStyle,
+    highlight: ContentStyle,
+}
+
+impl Palette {
+    fn of(theme: Theme) -> Self {
+        let base = ContentStyle::new();
+        match theme {
+            Theme::Default => Self {
+                header: base.cyan().bold(),
+                total: base.bold(),
+                highlight: base.red().bold(),
+            },
+            Theme::Light => Self {
+                header: base.dark_blue().bold(),
+                total: base.bold(),
+                highlight: base.with(Color::DarkRed).bold(),
+            },
+            Theme::Mono => Self {
+                header: base.underlined(),
+                total: base.bold(),
+                highlight: base.attribute(Attribute::Reverse),
+            },
+        }
+    }
+}
+
+impl Styler {
+    /// The styles for `config`; plain unless color is on and the format is
+    /// the table or tree.
+    #[must_use]
+    pub fn new(config: &Config) -> Self {
+        let styled =
+            config.color && matches!(config.format, OutputFormat::Table | OutputFormat::Tree);
+        Self {
+            palette: styled.then(|| Palette::of(config.theme)),
+        }
+    }
+
+    fn apply(&self, text: &str, pick: fn(&Palette) -> ContentStyle) -> String {
+        match &self.palette {
+            Some(palette) if !text.is_empty() => pick(palette).apply(text).to_string(),
+            _ => text.to_string(),
+        }
+    }
+
+    /// A column header line.
+    #[must_use]
+    pub fn header(&self, text: &str) -> String {
+        self.apply(text, |p| p.header)
+    }
+
+    /// The TOTAL row.
+    #[must_use]
+    pub fn total(&self, text: &str) -> String {
+        self.apply(text, |p| p.total)
+    }
+
+    /// A row over the `--highlight-over` threshold.
+    #[must_use]
+    pub fn highlight(&self, text: &str) -> String {
+        self.apply(text, |p| p.highlight)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_styles_only_colored_tables() {
+        let plain = Styler::new(&Config::default());
+        assert_eq!(plain.header("LINES"), "LINES");
+
+        let colored = Styler::new(&Config {
+            color: true,
+            ..Config::default()
+        });
+        assert_eq!(
+            colored.header("LINES"),
+            "\u{1b}[38;5;14m\u{1b}[1mLINES\u{1b}[0m"
+        );
+        assert_eq!(colored.total(""), "");
+
+        let csv = Styler::new(&Config {
+            color: true,
+            format: OutputFormat::Csv,
+            ..Config::default()
+        });
+        assert_eq!(csv.total("TOTAL"), "TOTAL");
+    }
+
+    #[test]
+    fn test_color_choice() {
+        assert!(use_color(ColorChoice::Always, true));
+        assert!(!use_color(ColorChoice::Never, false));
+        assert!(!use_color(ColorChoice::Auto, true));
+    }
+}
@@ -0,0 +1,189 @@
+// crates/cli/src/test_split.rs
+use crate::config::Config;
+use crate::error::Result;
+use crate::presentation::Delimiter;
+use count_lines_engine::options::OutputFormat;
+use count_lines_engine::test_code::{self, LanguageTestSplit};
+use serde::Serialize;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+
+#[derive(Serialize)]
+struct Report<'a> {
+    languages: &'a [LanguageTestSplit],
+    total: &'a LanguageTestSplit,
+}
+
+/// Counts the production and test code configured by `--test-split` and
+/// prints them per language to stdout, or to `config.output` when set.
+///
+/// # Errors
+/// Returns an error if the count or the output fails.
+pub fn print_test_split(config: &Config) -> Result<()> {
+    let options = config.test_split.clone().unwrap_or_default();
+    let splits = test_code::split_tests(config, &options)?;
+
+    if let Some(path) = &config.output {
+        let mut out = BufWriter::new(File::create(path)?);
+        write_test_split(&mut out, &splits, config.format)?;
+        out.flush()?;
+    } else {
+        let stdout = io::stdout();
+        let mut out = stdout.lock();
+        write_test_split(&mut out, &splits, config.format)?;
+    }
+    Ok(())
+}
+
+/// Writes the languages and their total in `format`.
+///
+/// `json` holds the languages and the total; `jsonl`/`csv`/`tsv` have one
+/// row per language; other formats fall back to a plain table.
+///
+/// # Errors
+/// Returns an error if writing to `out` fails.
+pub fn write_test_split(
+    out: &mut dyn Write,
+    splits: &[LanguageTestSplit],
+    format: OutputFormat,
+) -> io::Result<()> {
+    match format {
+        OutputFormat::Json => {
+            let report = Report {
+                languages: splits,
+                total: &LanguageTestSplit::total(splits),
+            };
+            let json = serde_json::to_string_pretty(&report).map_err(io::Error::other)?;
+            writeln!(out, "{json}")
+        }
+        OutputFormat::Jsonl => {
+            for split in splits {
+                let json = serde_json::to_string(split).map_err(io::Error::other)?;
+                writeln!(out, "{json}")?;
+            }
+            Ok(())
+        }
+        OutputFormat::Csv => write_sv(out, splits, Delimiter::Comma),
+        OutputFormat::Tsv => write_sv(out, splits, Delimiter::Tab),
+        _ => write_table(out, splits),
+    }
+}
+
+fn write_sv(
+    out: &mut dyn Write,
+    splits: &[LanguageTestSplit],
+    delimiter: Delimiter,
+) -> io::Result<()> {
+    let header = [
+        "language",
+        "production_files",
+        "test_files",
+        "production_lines",
+        "test_lines",
+        "production_sloc",
+        "test_sloc",
+        "ratio",
+    ];
+    writeln!(out, "{}", header.join(delimiter.as_str()))?;
+    for s in splits {
+        let fields = [
+            delimiter.escape(&s.language),
+            s.production_files.to_string(),
+            s.test_files.to_string(),
+            s.production_lines.to_string(),
+            s.test_lines.to_string(),
+            s.production_sloc.map(|v| v.to_string()).unwrap_or_default(),
+            s.test_sloc.map(|v| v.to_string()).unwrap_or_default(),
+            s.ratio.map(|r| format!("{r:.3}")).unwrap_or_default(),
+        ];
+        writeln!(out, "{}", fields.join(delimiter.as_str()))?;
+    }
+    Ok(())
+}
+
+fn write_table(out: &mut dyn Write, splits: &[LanguageTestSplit]) -> io::Result<()> {
+    writeln!(
+        out,
+        "{:>12}{:>12}{:>12}{:>12}{:>11}  LANGUAGE",
+        "PROD FILES", "TEST FILES", "PROD LINES", "TEST LINES", "TEST/PROD"
+    )?;
+    writeln!(out, "{}", "-".repeat(71))?;
+    let row = |out: &mut dyn Write, s: &LanguageTestSplit| {
+        writeln!(
+            out,
+            "{:>12}{:>12}{:>12}{:>12}{:>11}  {}",
+            s.production_files,
+            s.test_files,
+            s.production_lines,
+            s.test_lines,
+            s.ratio
+                .map_or_else(|| "-".to_string(), |r| format!("{r:.2}")),
+            s.language
+        )
+    };
+    for s in splits {
+        row(out, s)?;
+    }
+    writeln!(out, "{}", "-".repeat(71))?;
+    row(out, &LanguageTestSplit::total(splits))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn splits() -> Vec<LanguageTestSplit> {
+        vec![
+            LanguageTestSplit {
+                language: "Rust".to_string(),
+                production_files: 3,
+                test_files: 1,
+                production_lines: 300,
+                test_lines: 100,
+                ratio: Some(1.0 / 3.0),
+                ..LanguageTestSplit::default()
+            },
+            LanguageTestSplit {
+                language: "Shell, POSIX".to_string(),
+                test_files: 2,
+                test_lines: 20,
+                ..LanguageTestSplit::default()
+            },
+        ]
+    }
+
+    #[test]
+    fn test_csv() {
+        let mut buf = Vec::new();
+        write_test_split(&mut buf, &splits(), OutputFormat::Csv).unwrap();
+        let csv = String::from_utf8(buf).unwrap();
+        let lines: Vec<_> = csv.lines().collect();
+        assert_eq!(
+            lines,
+            [
+                "language,production_files,test_files,production_lines,test_lines,production_sloc,test_sloc,ratio",
+                "Rust,3,1,300,100,,,0.333",
+                "\"Shell, POSIX\",0,2,0,20,,,",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_json_and_table() {
+        let mut buf = Vec::new();
+        write_test_split(&mut buf, &splits(), OutputFormat::Json).unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&buf).unwrap();
+        assert_eq!(json["languages"][1]["test_lines"], 20);
+        assert!(json["languages"][1]["ratio"].is_null());
+        assert_eq!(json["total"]["test_lines"], 120);
+        assert_eq!(json["total"]["ratio"], 0.4);
+
+        let mut buf = Vec::new();
+        write_test_split(&mut buf, &splits(), OutputFormat::Table).unwrap();
+        let table = String::from_utf8(buf).unwrap();
+        let rows: Vec<_> = table.lines().collect();
+        assert!(rows[2].ends_with("0.33  Rust"));
+        assert!(rows[3].ends_with("-  Shell, POSIX"));
+        assert!(rows[5].ends_with("0.40  Total"));
+    }
+}
@@ -0,0 +1,96 @@
+// crates/cli/src/threshold.rs
+use crate::config::Config;
+use crate::error::{AppError, Result};
+use count_lines_engine::expr::Threshold;
+
+/// Evaluates the `--fail-if` thresholds of `config`, resolving fields with
+/// `lookup`.
+///
+/// # Errors
+/// Returns [`AppError::Threshold`] listing every exceeded threshold with the
+/// values it saw, or [`AppError::InvalidThreshold`] naming a field `lookup`
+/// cannot resolve (a comparison field outside of a comparison).
+pub fn check(config: &Config, lookup: &dyn Fn(&str) -> Option<f64>) -> Result<()> {
+    let violations = violations(config, lookup)?;
+    if violations.is_empty() {
+        Ok(())
+    } else {
+        Err(AppError::Threshold(violations.join("; ")))
+    }
+}
+
+/// Describes each exceeded `--fail-if` threshold of `config` with the values
+/// it saw.
+///
+/// # Errors
+/// Returns [`AppError::InvalidThreshold`] naming a field `lookup` cannot
+/// resolve.
+pub fn violations(config: &Config, lookup: &dyn Fn(&str) -> Option<f64>) -> Result<Vec<String>> {
+    let mut violations = Vec::new();
+    for threshold in &config.fail_if {
+        if let Some(field) = threshold.unresolved(lookup) {
+            return Err(AppError::InvalidThreshold(format!(
+                "'{threshold}': '{field}' is only available with --compare, --compare-ref or snapshot diff"
+            )));
+        }
+        if threshold.violated(lookup) {
+            violations.push(describe(threshold, lookup));
+        }
+    }
+    Ok(violations)
+}
+
+/// `total_lines > 500000 (total_lines = 612345)`
+fn describe(threshold: &Threshold, lookup: &dyn Fn(&str) -> Option<f64>) -> String {
+    let mut values: Vec<String> = Vec::new();
+    for field in threshold.expr.fields() {
+        if let Some(value) = lookup(field) {
+            let value = format!("{field} = {value}");
+            if !values.contains(&value) {
+                values.push(value);
+            }
+        }
+    }
+    format!("{threshold} ({})", values.join(", "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(exprs: &[&str]) -> Config {
+        Config {
+            fail_if: exprs.iter().map(|e| e.parse().unwrap()).collect(),
+            ..Config::default()
+        }
+    }
+
+    fn summary(name: &str) -> Option<f64> {
+        match name {
+            "total_lines" => Some(612_345.0),
+            "total_files" => Some(10.0),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn test_check_reports_violations() {
+        assert!(check(&config(&["total_lines > 1_000_000"]), &summary).is_ok());
+
+        let err = check(
+            &config(&["total_lines > 500000", "total_files > 100"]),
+            &summary,
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Threshold exceeded: total_lines > 500000 (total_lines = 612345)"
+        );
+    }
+
+    #[test]
+    fn test_check_rejects_delta_fields_outside_comparison() {
+        let err = check(&config(&["sloc_delta > 1000"]), &summary).unwrap_err();
+        assert!(err.to_string().contains("'sloc_delta' is only available"));
+    }
+}
@@ -0,0 +1,239 @@
+// crates/cli/src/trend.rs
+//! `--trend`: appends the totals of each run to `trend.jsonl` in the cache
+//! directory and reports how they moved since the previous run and since
+//! `N` runs ago, without saving snapshots by hand.
+//!
+//! Runs over different paths are kept apart: only the runs with the same
+//! scan roots are compared.
+
+use crate::config::Config;
+use crate::error::Result;
+use crate::numbers::NumberFormat;
+use chrono::{DateTime, Local};
+use count_lines_engine::stats::{RunResult, Summary};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+
+const TREND_FILE: &str = "trend.jsonl";
+
+/// The totals of one recorded run.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TrendPoint {
+    pub at: DateTime<Local>,
+    pub roots: Vec<PathBuf>,
+    #[serde(flatten)]
+    pub summary: Summary,
+}
+
+/// The run history in the cache directory.
+#[derive(Debug, Clone)]
+pub struct TrendLog {
+    path: PathBuf,
+}
+
+impl TrendLog {
+    #[must_use]
+    pub fn new(cache_dir: &Path) -> Self {
+        Self {
+            path: cache_dir.join(TREND_FILE),
+        }
+    }
+
+    /// The recorded runs over `roots`, oldest first. Lines that can't be
+    /// parsed are skipped.
+    ///
+    /// # Errors
+    /// Returns an error if the log exists but cannot be read.
+    pub fn load(&self, roots: &[PathBuf]) -> Result<Vec<TrendPoint>> {
+        let file = match fs::File::open(&self.path) {
+            Ok(file) => file,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(err) => return Err(err.into()),
+        };
+        let mut points = Vec::new();
+        for line in BufReader::new(file).lines() {
+            if let Ok(point) = serde_json::from_str::<TrendPoint>(&line?)
+                && point.roots == roots
+            {
+                points.push(point);
+            }
+        }
+        Ok(points)
+    }
+
+    /// Appends `point` to the log.
+    ///
+    /// # Errors
+    /// Returns an error if the log cannot be written.
+    pub fn append(&self, point: &TrendPoint) -> Result<()> {
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        writeln!(file, "{}", serde_json::to_string(point)?)?;
+        Ok(())
+    }
+}
+
+/// Records the totals of `result` and writes to `out` how they moved since
+/// the previous run and since `config.trend` runs ago.
+///
+/// # Errors
+/// Returns an error if the log cannot be read or written, or writing to
+/// `out` fails.
+pub fn record(out: &mut dyn Write, result: &RunResult, config: &Config) -> Result<()> {
+    let Some(runs_ago) = config.trend else {
+        return Ok(());
+    };
+    let log = TrendLog::new(&config.resolved_cache_dir());
+    let history = log.load(&config.walk.roots)?;
+    let text_files: Vec<_> = result
+        .stats
+        .iter()
+        .filter(|s| !s.is_binary)
+        .cloned()
+        .collect();
+    let current = TrendPoint {
+        at: result.started_at.unwrap_or_else(Local::now),
+        roots: config.walk.roots.clone(),
+        summary: Summary::from_stats(&text_files),
+    };
+
+    write_trend(
+        out,
+        &current,
+        &history,
+        runs_ago,
+        &NumberFormat::new(config),
+    )?;
+    log.append(&current)
+}
+
+/// Writes the moves of `current` against the last run of `history` and the
+/// run `runs_ago` runs back.
+///
+/// # Errors
+/// Returns an error if writing to `out` fails.
+pub fn write_trend(
+    out: &mut dyn Write,
+    current: &TrendPoint,
+    history: &[TrendPoint],
+    runs_ago: usize,
+    fmt: &NumberFormat,
+) -> io::Result<()> {
+    let Some(previous) = history.last() else {
+        return writeln!(out, "[count_lines] Trend: first recorded run");
+    };
+    writeln!(
+        out,
+        "[count_lines] Trend: {} since the previous run ({})",
+        describe(&current.summary, &previous.summary, fmt),
+        previous.at.format("%Y-%m-%d %H:%M")
+    )?;
+    if runs_ago > 1
+        && let Some(older) = history.len().checked_sub(runs_ago).map(|i| &history[i])
+    {
+        writeln!(
+            out,
+            "[count_lines] Trend: {} since {runs_ago} runs ago ({})",
+            describe(&current.summary, &older.summary, fmt),
+            older.at.format("%Y-%m-%d %H:%M")
+        )?;
+    }
+    Ok(())
+}
+
+/// `+1,234 lines, +5 files, -12 SLOC`; SLOC only when both runs counted it.
+fn describe(current: &Summary, base: &Summary, fmt: &NumberFormat) -> String {
+    let mut parts = vec![
+        format!("{} lines", signed(current.lines, base.lines, fmt)),
+        format!("{} files", signed(current.files, base.files, fmt)),
+    ];
+    if let (Some(now), Some(then)) = (current.sloc, base.sloc) {
+        parts.push(format!("{} SLOC", signed(now, then, fmt)));
+    }
+    parts.join(", ")
+}
+
+fn signed(now: usize, then: usize, fmt: &NumberFormat) -> String {
+    if now >= then {
+        format!("+{}", fmt.count(now - then))
+    } else {
+        format!("-{}", fmt.count(then - now))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn point(day: u32, lines: usize, files: usize) -> TrendPoint {
+        TrendPoint {
+            at: Local.with_ymd_and_hms(2024, 5, day, 9, 0, 0).unwrap(),
+            roots: vec![PathBuf::from("src")],
+            summary: Summary {
+                files,
+                lines,
+                ..Summary::default()
+            },
+        }
+    }
+
+    fn trend(current: &TrendPoint, history: &[TrendPoint], runs_ago: usize) -> String {
+        let fmt = NumberFormat {
+            separator: Some(",".to_string()),
+            ..NumberFormat::default()
+        };
+        let mut out = Vec::new();
+        write_trend(&mut out, current, history, runs_ago, &fmt).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn test_write_trend() {
+        let history = [point(1, 1000, 10), point(2, 5000, 20), point(3, 4000, 25)];
+        assert_eq!(
+            trend(&point(4, 5234, 30), &history, 3),
+            "[count_lines] Trend: +1,234 lines, +5 files since the previous run (2024-05-03 09:00)\n\
+             [count_lines] Trend: +4,234 lines, +20 files since 3 runs ago (2024-05-01 09:00)\n"
+        );
+        // Not enough history for the longer comparison.
+        assert_eq!(
+            trend(&point(4, 3000, 25), &history[2..], 3),
+            "[count_lines] Trend: -1,000 lines, +0 files since the previous run (2024-05-03 09:00)\n"
+        );
+        assert_eq!(
+            trend(&point(4, 1, 1), &[], 3),
+            "[count_lines] Trend: first recorded run\n"
+        );
+    }
+
+    #[test]
+    fn test_log_keeps_roots_apart() {
+        let dir = tempfile::tempdir().unwrap();
+        let log = TrendLog::new(dir.path());
+        assert!(log.load(&[PathBuf::from("src")]).unwrap().is_empty());
+
+        log.append(&point(1, 10, 1)).unwrap();
+        log.append(&TrendPoint {
+            roots: vec![PathBuf::from("docs")],
+            ..point(2, 20, 2)
+        })
+        .unwrap();
+        log.append(&point(3, 30, 3)).unwrap();
+
+        let lines: Vec<_> = log
+            .load(&[PathBuf::from("src")])
+            .unwrap()
+            .iter()
+            .map(|p| p.summary.lines)
+            .collect();
+        assert_eq!(lines, [10, 30]);
+    }
+}
@@ -0,0 +1,451 @@
+// crates/cli/src/tui.rs
+//! Terminal dashboard for `--watch --tui`.
+//!
+//! crossterm is built without its event reader here, so keys are read as
+//! raw bytes from stdin on a thread of their own; both that thread and the
+//! watch cycles redraw the shared [`Dashboard`].
+
+use crate::config::Config;
+use crate::error::Result;
+use count_lines_engine::grouping::{GroupStats, group_stats};
+use count_lines_engine::options::GroupBy;
+use count_lines_engine::stats::{RunResult, Summary};
+use count_lines_engine::watch::{self, Changes};
+use crossterm::{cursor, execute, queue, terminal};
+use hashbrown::HashMap;
+use std::cmp::Reverse;
+use std::collections::VecDeque;
+use std::io::{self, Read, Write};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex, PoisonError};
+
+/// Recently changed files kept on screen.
+const RECENT_CHANGES: usize = 10;
+/// Totals kept for the sparkline.
+const HISTORY_LEN: usize = 120;
+const SPARK_BARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// Sort order of the language table, cycled with `s`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TuiSort {
+    #[default]
+    Lines,
+    Sloc,
+    Files,
+    Name,
+}
+
+impl TuiSort {
+    const fn next(self) -> Self {
+        match self {
+            Self::Lines => Self::Sloc,
+            Self::Sloc => Self::Files,
+            Self::Files => Self::Name,
+            Self::Name => Self::Lines,
+        }
+    }
+
+    const fn as_str(self) -> &'static str {
+        match self {
+            Self::Lines => "lines",
+            Self::Sloc => "sloc",
+            Self::Files => "files",
+            Self::Name => "name",
+        }
+    }
+}
+
+/// What a key press asks of the dashboard.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyAction {
+    Redraw,
+    Quit,
+    None,
+}
+
+/// A file that changed in a watch cycle.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct FileChange {
+    path: PathBuf,
+    /// Lines now, or `None` once removed.
+    lines: Option<usize>,
+    delta: i64,
+}
+
+/// State of the dashboard across watch cycles.
+#[derive(Debug, Default)]
+pub struct Dashboard {
+    cycle: u64,
+    summary: Summary,
+    groups: Vec<GroupStats>,
+    errors: usize,
+    previous: HashMap<PathBuf, usize>,
+    recent: VecDeque<FileChange>,
+    history: VecDeque<usize>,
+    sort: TuiSort,
+    filter: String,
+    /// The filter being typed after `/`.
+    editing: Option<String>,
+}
+
+impl Dashboard {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Takes in the result of a watch cycle: the totals, the per-language
+    /// table and the files whose line count changed since the last one.
+    pub fn update(&mut self, result: &RunResult, config: &Config) {
+        self.cycle += 1;
+        self.summary = Summary::from_stats(&result.stats);
+        self.groups = group_stats(&result.stats, GroupBy::Language, config);
+        self.errors = result.error_count();
+
+        let current: HashMap<PathBuf, usize> = result
+            .stats
+            .iter()
+            .map(|s| (s.path.clone(), s.lines))
+            .collect();
+        // The first cycle would list every file as added.
+        if self.cycle > 1 {
+            let mut changes: Vec<FileChange> = current
+                .iter()
+                .filter(|(path, lines)| self.previous.get(*path) != Some(*lines))
+                .map(|(path, &lines)| FileChange {
+                    path: path.clone(),
+                    lines: Some(lines),
+                    delta: signed(lines) - signed(self.previous.get(path).copied().unwrap_or(0)),
+                })
+                .chain(
+                    self.previous
+                        .iter()
+                        .filter(|(path, _)| !current.contains_key(*path))
+                        .map(|(path, &lines)| FileChange {
+                            path: path.clone(),
+                            lines: None,
+                            delta: -signed(lines),
+                        }),
+                )
+                .collect();
+            changes.sort_by(|a, b| a.path.cmp(&b.path));
+            for change in changes {
+                self.recent.retain(|c| c.path != change.path);
+                self.recent.push_front(change);
+            }
+            self.recent.truncate(RECENT_CHANGES);
+        }
+        self.previous = current;
+
+        if self.history.len() == HISTORY_LEN {
+            self.history.pop_front();
+        }
+        self.history
+            .push_back(self.summary.sloc.unwrap_or(self.summary.lines));
+    }
+
+    /// Handles one byte read from the terminal: `s` cycles the sort order,
+    /// `/` starts a filter (Enter applies, Esc clears), `q` or Ctrl-C quits.
+    pub fn handle_key(&mut self, byte: u8) -> KeyAction {
+        if let Some(editing) = &mut self.editing {
+            match byte {
+                b'\r' | b'\n' => self.filter = self.editing.take().unwrap_or_default(),
+                0x1b => {
+                    self.editing = None;
+                    self.filter.clear();
+                }
+                0x7f | 0x08 => {
+                    editing.pop();
+                }
+                0x03 => return KeyAction::Quit,
+                b if b.is_ascii_graphic() || b == b' ' => editing.push(char::from(b)),
+                _ => return KeyAction::None,
+            }
+            return KeyAction::Redraw;
+        }
+        match byte {
+            b'q' | 0x03 => KeyAction::Quit,
+            b's' => {
+                self.sort = self.sort.next();
+                KeyAction::Redraw
+            }
+            b'/' => {
+                self.editing = Some(self.filter.clone());
+                KeyAction::Redraw
+            }
+            0x1b if !self.filter.is_empty() => {
+                self.filter.clear();
+                KeyAction::Redraw
+            }
+            _ => KeyAction::None,
+        }
+    }
+
+    /// Lays the dashboard out in `height` lines of at most `width` chars.
+    #[must_use]
+    pub fn render(&self, width: u16, height: u16) -> Vec<String> {
+        let width = usize::from(width);
+        let metric = if self.summary.sloc.is_some() {
+            "SLOC"
+        } else {
+            "lines"
+        };
+        let mut lines = vec![
+            format!(
+                "count_lines watch  cycle {}  files {}  lines {}  SLOC {}  errors {}",
+                self.cycle,
+                self.summary.files,
+                self.summary.lines,
+                self.summary
+                    .sloc
+                    .map_or_else(|| "-".to_string(), |s| s.to_string()),
+                self.errors
+            ),
+            format!(
+                "{metric:<6}{}",
+                sparkline(&self.history, width.saturating_sub(6))
+            ),
+            String::new(),
+            format!(
+                "{:<20} {:>8} {:>10} {:>10}",
+                "Language", "Files", "Lines", "SLOC"
+            ),
+        ];
+
+        let filter = self.filter.to_lowercase();
+        let mut groups: Vec<&GroupStats> = self
+            .groups
+            .iter()
+            .filter(|g| g.key.to_lowercase().contains(&filter))
+            .collect();
+        match self.sort {
+            TuiSort::Lines => groups.sort_by_key(|g| Reverse(g.summary.lines)),
+            TuiSort::Sloc => groups.sort_by_key(|g| Reverse(g.summary.sloc)),
+            TuiSort::Files => groups.sort_by_key(|g| Reverse(g.summary.files)),
+            TuiSort::Name => groups.sort_by(|a, b| a.key.cmp(&b.key)),
+        }
+        let recent: Vec<&FileChange> = self
+            .recent
+            .iter()
+            .filter(|c| c.path.to_string_lossy().to_lowercase().contains(&filter))
+            .collect();
+
+        // Header, table heading, blank, "Recent changes", changes, footer.
+        let fixed = lines.len() + 3 + recent.len();
+        let rows = usize::from(height).saturating_sub(fixed).max(1);
+        for group in groups.iter().take(rows) {
+            lines.push(format!(
+                "{:<20} {:>8} {:>10} {:>10}",
+                group.key,
+                group.summary.files,
+                group.summary.lines,
+                group
+                    .summary
+                    .sloc
+                    .map_or_else(|| "-".to_string(), |s| s.to_string())
+            ));
+        }
+
+        lines.push(String::new());
+        lines.push("Recent changes".to_string());
+        for change in recent {
+            lines.push(match change.lines {
+                Some(now) => format!(
+                    "  {:>+7}  {} ({now} lines)",
+                    change.delta,
+                    change.path.display()
+                ),
+                None => format!(
+                    "  {:>+7}  {} (removed)",
+                    change.delta,
+                    change.path.display()
+                ),
+            });
+        }
+
+        lines.push(match &self.editing {
+            Some(editing) => format!("filter: {editing}_   Enter: apply  Esc: clear"),
+            None => format!(
+                "s: sort ({})  /: filter{}  q: quit",
+                self.sort.as_str(),
+                if self.filter.is_empty() {
+                    String::new()
+                } else {
+                    format!(" ({})", self.filter)
+                }
+            ),
+        });
+
+        lines.truncate(usize::from(height));
+        for line in &mut lines {
+            if let Some((end, _)) = line.char_indices().nth(width) {
+                line.truncate(end);
+            }
+        }
+        lines
+    }
+}
+
+/// Scales the last `width` values of `history` to block characters.
+fn sparkline(history: &VecDeque<usize>, width: usize) -> String {
+    let values: Vec<usize> = history
+        .iter()
+        .skip(history.len().saturating_sub(width))
+        .copied()
+        .collect();
+    let (Some(&min), Some(&max)) = (values.iter().min(), values.iter().max()) else {
+        return String::new();
+    };
+    let range = max - min;
+    values
+        .iter()
+        .map(|&v| {
+            let level = ((v - min) * (SPARK_BARS.len() - 1))
+                .checked_div(range)
+                .unwrap_or(SPARK_BARS.len() / 2);
+            SPARK_BARS[level]
+        })
+        .collect()
+}
+
+fn signed(n: usize) -> i64 {
+    i64::try_from(n).unwrap_or(i64::MAX)
+}
+
+/// Runs watch mode with the dashboard on the alternate screen until `q`.
+///
+/// # Errors
+/// Returns an error if the terminal can't be set up or watching fails.
+pub fn run(config: &Config) -> Result<()> {
+    let dashboard = Arc::new(Mutex::new(Dashboard::new()));
+    terminal::enable_raw_mode()?;
+    execute!(io::stdout(), terminal::EnterAlternateScreen, cursor::Hide)?;
+
+    let keys = dashboard.clone();
+    std::thread::spawn(move || {
+        let mut byte = [0u8; 1];
+        while io::stdin().read(&mut byte).is_ok_and(|n| n == 1) {
+            let mut dashboard = keys.lock().unwrap_or_else(PoisonError::into_inner);
+            match dashboard.handle_key(byte[0]) {
+                KeyAction::Redraw => {
+                    let _ = draw(&dashboard);
+                }
+                KeyAction::Quit => {
+                    restore_terminal();
+                    std::process::exit(0);
+                }
+                KeyAction::None => {}
+            }
+        }
+    });
+
+    let mut result = RunResult::default();
+    let watched = watch::watch_loop(config, |changes: &Changes| {
+        let mut dashboard = dashboard.lock().unwrap_or_else(PoisonError::into_inner);
+        if watch::apply_changes(&mut result, changes, config).is_ok() {
+            dashboard.update(&result, config);
+        }
+        let _ = draw(&dashboard);
+    });
+    restore_terminal();
+    Ok(watched?)
+}
+
+fn draw(dashboard: &Dashboard) -> io::Result<()> {
+    // Some pseudo-terminals report 0x0 until resized.
+    let (width, height) = terminal::size()
+        .ok()
+        .filter(|&(w, h)| w > 0 && h > 0)
+        .unwrap_or((80, 24));
+    let mut out = io::stdout().lock();
+    queue!(
+        out,
+        cursor::MoveTo(0, 0),
+        terminal::Clear(terminal::ClearType::All)
+    )?;
+    for line in dashboard.render(width, height) {
+        // Raw mode doesn't turn `\n` into a carriage return.
+        write!(out, "{line}\r\n")?;
+    }
+    out.flush()
+}
+
+fn restore_terminal() {
+    let _ = execute!(io::stdout(), cursor::Show, terminal::LeaveAlternateScreen);
+    let _ = terminal::disable_raw_mode();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use count_lines_engine::stats::FileStats;
+
+    fn result(files: &[(&str, usize)]) -> RunResult {
+        RunResult {
+            stats: files
+                .iter()
+                .map(|(path, lines)| FileStats {
+                    path: PathBuf::from(path),
+                    ext: path.rsplit('.').next().unwrap_or_default().to_string(),
+                    lines: *lines,
+                    ..FileStats::default()
+                })
+                .collect(),
+            ..RunResult::default()
+        }
+    }
+
+    #[test]
+    fn test_update_tracks_deltas_and_history() {
+        let config = Config::default();
+        let mut dashboard = Dashboard::new();
+        dashboard.update(&result(&[("a.rs", 10), ("b.py", 5)]), &config);
+        assert!(dashboard.recent.is_empty());
+
+        dashboard.update(&result(&[("a.rs", 12)]), &config);
+        let recent: Vec<(String, i64)> = dashboard
+            .recent
+            .iter()
+            .map(|c| (c.path.display().to_string(), c.delta))
+            .collect();
+        assert_eq!(recent, [("b.py".to_string(), -5), ("a.rs".to_string(), 2)]);
+        assert_eq!(dashboard.history, [15, 12]);
+    }
+
+    #[test]
+    fn test_keys_change_sort_and_filter() {
+        let mut dashboard = Dashboard::new();
+        assert_eq!(dashboard.handle_key(b's'), KeyAction::Redraw);
+        assert_eq!(dashboard.sort, TuiSort::Sloc);
+
+        for byte in b"/rus\r" {
+            dashboard.handle_key(*byte);
+        }
+        assert_eq!(dashboard.filter, "rus");
+        assert_eq!(dashboard.editing, None);
+        assert_eq!(dashboard.handle_key(0x1b), KeyAction::Redraw);
+        assert!(dashboard.filter.is_empty());
+        assert_eq!(dashboard.handle_key(b'q'), KeyAction::Quit);
+    }
+
+    #[test]
+    fn test_render_filters_languages_and_fits_screen() {
+        let config = Config::default();
+        let mut dashboard = Dashboard::new();
+        dashboard.update(&result(&[("a.rs", 10), ("b.py", 5)]), &config);
+        dashboard.filter = "py".to_string();
+
+        let screen = dashboard.render(40, 20);
+        assert!(screen.iter().all(|l| l.chars().count() <= 40));
+        assert!(screen.iter().any(|l| l.starts_with("Python")));
+        assert!(!screen.iter().any(|l| l.starts_with("Rust")));
+        assert!(dashboard.render(80, 3).len() <= 3);
+    }
+
+    #[test]
+    fn test_sparkline_scales_to_range() {
+        let history = VecDeque::from([1, 5, 9]);
+        assert_eq!(sparkline(&history, 10), "▁▄█");
+        assert_eq!(sparkline(&history, 2), "▁█");
+    }
+}
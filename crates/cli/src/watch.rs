@@ -0,0 +1,263 @@
+// crates/cli/src/watch.rs
+use crate::compare::{ChangeKind, Comparison};
+use crate::config::Config;
+use crate::presentation;
+use count_lines_engine::options::WatchOutput;
+use count_lines_engine::stats::{FileStats, RunResult, Summary};
+use hashbrown::HashMap;
+use std::io::{self, Write};
+use std::path::PathBuf;
+
+/// Reports the result of each watch cycle according to `--watch-output`.
+///
+/// In `jsonl` (`ndjson`) mode only files that changed since the previous
+/// cycle are emitted, followed by one summary object per cycle. Both modes
+/// report the line deltas against the previous cycle.
+#[derive(Debug, Default)]
+pub struct WatchReporter {
+    previous: HashMap<PathBuf, FileStats>,
+    cycle: u64,
+}
+
+impl WatchReporter {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Reports one cycle to stdout.
+    ///
+    /// # Errors
+    /// Returns an error if writing the output fails.
+    pub fn report(&mut self, result: &RunResult, config: &Config) -> io::Result<()> {
+        match config.watch_output {
+            WatchOutput::Full => {
+                presentation::print_clear_screen(&config.watch_output);
+                presentation::print_results(result, config)?;
+                self.cycle += 1;
+                let current = Self::counted(result, config);
+                if self.cycle > 1 {
+                    let stdout = io::stdout();
+                    let mut out = stdout.lock();
+                    write_delta(&mut out, &self.delta(&current))?;
+                }
+                self.previous = current;
+                Ok(())
+            }
+            WatchOutput::Jsonl => {
+                let stdout = io::stdout();
+                let mut out = stdout.lock();
+                self.write_ndjson(&mut out, result, config)?;
+                out.flush()
+            }
+        }
+    }
+
+    /// The counted files by path, as displayed.
+    fn counted(result: &RunResult, config: &Config) -> HashMap<PathBuf, FileStats> {
+        result
+            .stats
+            .iter()
+            .filter(|s| !s.is_binary)
+            .map(|s| {
+                let path = presentation::display_path(&s.path, config);
+                (path.clone(), FileStats { path, ..s.clone() })
+            })
+            .collect()
+    }
+
+    /// Compares `current` with the files of the previous cycle.
+    fn delta(&self, current: &HashMap<PathBuf, FileStats>) -> Comparison {
+        let old: Vec<FileStats> = self.previous.values().cloned().collect();
+        let new: Vec<FileStats> = current.values().cloned().collect();
+        Comparison::new(&old, &new)
+    }
+
+    fn write_ndjson(
+        &mut self,
+        out: &mut dyn Write,
+        result: &RunResult,
+        config: &Config,
+    ) -> io::Result<()> {
+        self.cycle += 1;
+
+        let current = Self::counted(result, config);
+        let delta = self.delta(&current);
+        let lines_delta: HashMap<&PathBuf, isize> =
+            delta.files.iter().map(|d| (&d.path, d.lines)).collect();
+
+        let mut changed: Vec<&FileStats> = current
+            .values()
+            .filter(|s| self.previous.get(&s.path) != Some(*s))
+            .collect();
+        changed.sort_by(|a, b| a.path.cmp(&b.path));
+
+        let mut removed: Vec<&PathBuf> = self
+            .previous
+            .keys()
+            .filter(|p| !current.contains_key(*p))
+            .collect();
+        removed.sort();
+
+        for s in &changed {
+            let mut value = serde_json::to_value(s).map_err(io::Error::other)?;
+            if let Some(obj) = value.as_object_mut() {
+                obj.insert("type".to_string(), "file".into());
+                obj.insert("cycle".to_string(), self.cycle.into());
+                let lines = lines_delta.get(&s.path).copied().unwrap_or(0);
+                obj.insert("lines_delta".to_string(), lines.into());
+            }
+            writeln!(out, "{value}")?;
+        }
+
+        for path in &removed {
+            let value = serde_json::json!({
+                "type": "removed",
+                "cycle": self.cycle,
+                "path": path,
+                "lines_delta": lines_delta.get(path).copied().unwrap_or(0),
+            });
+            writeln!(out, "{value}")?;
+        }
+
+        let stats: Vec<FileStats> = current.values().cloned().collect();
+        let summary = Summary::from_stats(&stats);
+        let (lines_added, lines_removed) = added_removed(&delta);
+        let value = serde_json::json!({
+            "type": "summary",
+            "version": crate::VERSION,
+            "cycle": self.cycle,
+            "changed_files": changed.len(),
+            "removed_files": removed.len(),
+            "lines_added": lines_added,
+            "lines_removed": lines_removed,
+            "lines_delta": delta.summary.diff_lines,
+            "errors": result.error_count(),
+            "summary": summary,
+        });
+        writeln!(out, "{value}")?;
+
+        self.previous = current;
+        Ok(())
+    }
+}
+
+/// Lines gained by growing or new files and lost by shrinking or removed
+/// ones.
+fn added_removed(delta: &Comparison) -> (usize, usize) {
+    delta.files.iter().fold((0, 0), |(added, removed), d| {
+        if d.lines >= 0 {
+            (added + d.lines.unsigned_abs(), removed)
+        } else {
+            (added, removed + d.lines.unsigned_abs())
+        }
+    })
+}
+
+/// Writes the changes of a cycle below the full report: the totals, then
+/// one line per changed file.
+fn write_delta(out: &mut dyn Write, delta: &Comparison) -> io::Result<()> {
+    let changed: Vec<_> = delta
+        .files
+        .iter()
+        .filter(|d| d.status != ChangeKind::Unchanged)
+        .collect();
+    if changed.is_empty() {
+        return writeln!(out, "\nNo changes since the previous cycle");
+    }
+    let (added, removed) = added_removed(delta);
+    writeln!(
+        out,
+        "\nChanges since the previous cycle: +{added} -{removed} lines (net {:+}) in {} files",
+        delta.summary.diff_lines,
+        changed.len()
+    )?;
+    for d in changed {
+        let note = match d.status {
+            ChangeKind::Added => " (added)",
+            ChangeKind::Removed => " (removed)",
+            ChangeKind::Modified | ChangeKind::Unchanged => "",
+        };
+        writeln!(out, "  {:>+7}  {}{note}", d.lines, d.path.display())?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file(path: &str, lines: usize) -> FileStats {
+        FileStats {
+            path: PathBuf::from(path),
+            lines,
+            ..FileStats::default()
+        }
+    }
+
+    fn cycle(reporter: &mut WatchReporter, stats: Vec<FileStats>) -> Vec<serde_json::Value> {
+        let result = RunResult {
+            stats,
+            ..RunResult::default()
+        };
+        let mut buf = Vec::new();
+        reporter
+            .write_ndjson(&mut buf, &result, &Config::default())
+            .unwrap();
+        String::from_utf8(buf)
+            .unwrap()
+            .lines()
+            .map(|l| serde_json::from_str(l).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_ndjson_emits_only_changes() {
+        let mut reporter = WatchReporter::new();
+
+        let first = cycle(&mut reporter, vec![file("a.rs", 1), file("b.rs", 2)]);
+        assert_eq!(first.len(), 3);
+        assert_eq!(first[2]["type"], "summary");
+        assert_eq!(first[2]["changed_files"], 2);
+
+        let second = cycle(&mut reporter, vec![file("a.rs", 5)]);
+        assert_eq!(second.len(), 3);
+        assert_eq!(second[0]["type"], "file");
+        assert_eq!(second[0]["path"], "a.rs");
+        assert_eq!(second[1]["type"], "removed");
+        assert_eq!(second[1]["path"], "b.rs");
+        assert_eq!(second[2]["cycle"], 2);
+        assert_eq!(second[2]["summary"]["lines"], 5);
+        assert_eq!(second[0]["lines_delta"], 4);
+        assert_eq!(second[1]["lines_delta"], -2);
+        assert_eq!(second[2]["lines_added"], 4);
+        assert_eq!(second[2]["lines_removed"], 2);
+        assert_eq!(second[2]["lines_delta"], 2);
+
+        let third = cycle(&mut reporter, vec![file("a.rs", 5)]);
+        assert_eq!(third.len(), 1);
+        assert_eq!(third[0]["changed_files"], 0);
+    }
+
+    #[test]
+    fn test_delta_lists_changed_files() {
+        let old = [file("a.rs", 1), file("b.rs", 2)];
+        let new = [file("a.rs", 5), file("c.rs", 3)];
+        let mut buf = Vec::new();
+        write_delta(&mut buf, &Comparison::new(&old, &new)).unwrap();
+        assert_eq!(
+            String::from_utf8(buf).unwrap(),
+            "\nChanges since the previous cycle: +7 -2 lines (net +5) in 3 files\n\
+             \x20      +4  a.rs\n\
+             \x20      -2  b.rs (removed)\n\
+             \x20      +3  c.rs (added)\n"
+        );
+
+        let mut buf = Vec::new();
+        write_delta(&mut buf, &Comparison::new(&new, &new)).unwrap();
+        assert_eq!(
+            String::from_utf8(buf).unwrap(),
+            "\nNo changes since the previous cycle\n"
+        );
+    }
+}
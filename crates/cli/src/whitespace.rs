@@ -0,0 +1,217 @@
+// crates/cli/src/whitespace.rs
+use crate::config::Config;
+use crate::error::Result;
+use crate::presentation::Delimiter;
+use count_lines_engine::options::OutputFormat;
+use count_lines_engine::whitespace::{self, DirectoryWhitespace, FileWhitespace, WhitespaceAudit};
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+
+/// Audits the files selected by `config` for whitespace problems
+/// (`--audit-whitespace`) and prints the findings to stdout, or to
+/// `config.output` when set.
+///
+/// # Errors
+/// Returns an error if the count or the output fails.
+pub fn print_whitespace_audit(config: &Config) -> Result<()> {
+    let audit = whitespace::audit_whitespace(config)?;
+
+    if let Some(path) = &config.output {
+        let mut out = BufWriter::new(File::create(path)?);
+        write_whitespace_audit(&mut out, &audit, config.format)?;
+        out.flush()?;
+    } else {
+        let stdout = io::stdout();
+        let mut out = stdout.lock();
+        write_whitespace_audit(&mut out, &audit, config.format)?;
+    }
+    Ok(())
+}
+
+/// Writes the audit in `format`.
+///
+/// `json` holds the files and the directories; `jsonl`/`csv`/`tsv` have
+/// one row per file with findings; other formats fall back to plain tables
+/// of the files and the directories.
+///
+/// # Errors
+/// Returns an error if writing to `out` fails.
+pub fn write_whitespace_audit(
+    out: &mut dyn Write,
+    audit: &WhitespaceAudit,
+    format: OutputFormat,
+) -> io::Result<()> {
+    match format {
+        OutputFormat::Json => {
+            let json = serde_json::to_string_pretty(audit).map_err(io::Error::other)?;
+            writeln!(out, "{json}")
+        }
+        OutputFormat::Jsonl => {
+            for file in &audit.files {
+                let json = serde_json::to_string(file).map_err(io::Error::other)?;
+                writeln!(out, "{json}")?;
+            }
+            Ok(())
+        }
+        OutputFormat::Csv => write_sv(out, &audit.files, Delimiter::Comma),
+        OutputFormat::Tsv => write_sv(out, &audit.files, Delimiter::Tab),
+        _ => {
+            write_files_table(out, &audit.files)?;
+            writeln!(out)?;
+            write_directories_table(out, &audit.directories)
+        }
+    }
+}
+
+fn write_sv(out: &mut dyn Write, files: &[FileWhitespace], delimiter: Delimiter) -> io::Result<()> {
+    let header = [
+        "path",
+        "trailing_whitespace",
+        "mixed_indent",
+        "crlf",
+        "lf",
+        "missing_final_newline",
+    ];
+    writeln!(out, "{}", header.join(delimiter.as_str()))?;
+    for f in files {
+        let fields = [
+            delimiter.escape(&f.path.display().to_string()),
+            f.counts.trailing_whitespace.to_string(),
+            f.counts.mixed_indent.to_string(),
+            f.counts.crlf.to_string(),
+            f.counts.lf.to_string(),
+            f.counts.missing_final_newline.to_string(),
+        ];
+        writeln!(out, "{}", fields.join(delimiter.as_str()))?;
+    }
+    Ok(())
+}
+
+fn write_files_table(out: &mut dyn Write, files: &[FileWhitespace]) -> io::Result<()> {
+    writeln!(
+        out,
+        "{:>10}{:>8}{:>8}{:>8}{:>8}  FILE",
+        "TRAILING", "MIXED", "CRLF", "LF", "NO EOL"
+    )?;
+    writeln!(out, "{}", "-".repeat(48))?;
+    if files.is_empty() {
+        return writeln!(out, "(no whitespace issues)");
+    }
+    for f in files {
+        writeln!(
+            out,
+            "{:>10}{:>8}{:>8}{:>8}{:>8}  {}",
+            f.counts.trailing_whitespace,
+            f.counts.mixed_indent,
+            f.counts.crlf,
+            f.counts.lf,
+            if f.counts.missing_final_newline {
+                "yes"
+            } else {
+                ""
+            },
+            f.path.display()
+        )?;
+    }
+    Ok(())
+}
+
+fn write_directories_table(out: &mut dyn Write, dirs: &[DirectoryWhitespace]) -> io::Result<()> {
+    writeln!(
+        out,
+        "{:>7}{:>8}{:>10}{:>8}{:>8}{:>6}{:>6}{:>7}  DIRECTORY",
+        "FILES", "ISSUES", "TRAILING", "MIXED", "NO EOL", "CRLF", "LF", "BOTH"
+    )?;
+    writeln!(out, "{}", "-".repeat(71))?;
+    for d in dirs {
+        writeln!(
+            out,
+            "{:>7}{:>8}{:>10}{:>8}{:>8}{:>6}{:>6}{:>7}  {}",
+            d.files,
+            d.files_with_issues,
+            d.trailing_whitespace,
+            d.mixed_indent,
+            d.missing_final_newline,
+            d.crlf_files,
+            d.lf_files,
+            d.mixed_line_endings,
+            d.directory.display()
+        )?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use count_lines_core::stats::WhitespaceCounts;
+    use std::path::PathBuf;
+
+    fn audit() -> WhitespaceAudit {
+        WhitespaceAudit {
+            files: vec![FileWhitespace {
+                path: PathBuf::from("src/a, b.rs"),
+                counts: WhitespaceCounts {
+                    trailing_whitespace: 3,
+                    mixed_indent: 1,
+                    crlf: 2,
+                    lf: 8,
+                    missing_final_newline: true,
+                },
+            }],
+            directories: vec![DirectoryWhitespace {
+                directory: PathBuf::from("src"),
+                files: 4,
+                files_with_issues: 1,
+                trailing_whitespace: 3,
+                mixed_indent: 1,
+                missing_final_newline: 1,
+                lf_files: 3,
+                mixed_line_endings: 1,
+                ..DirectoryWhitespace::default()
+            }],
+        }
+    }
+
+    #[test]
+    fn test_csv_and_json() {
+        let mut buf = Vec::new();
+        write_whitespace_audit(&mut buf, &audit(), OutputFormat::Csv).unwrap();
+        let csv = String::from_utf8(buf).unwrap();
+        let lines: Vec<_> = csv.lines().collect();
+        assert_eq!(
+            lines,
+            [
+                "path,trailing_whitespace,mixed_indent,crlf,lf,missing_final_newline",
+                "\"src/a, b.rs\",3,1,2,8,true",
+            ]
+        );
+
+        let mut buf = Vec::new();
+        write_whitespace_audit(&mut buf, &audit(), OutputFormat::Json).unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&buf).unwrap();
+        assert_eq!(json["files"][0]["trailing_whitespace"], 3);
+        assert_eq!(json["directories"][0]["lf_files"], 3);
+    }
+
+    #[test]
+    fn test_table() {
+        let mut buf = Vec::new();
+        write_whitespace_audit(&mut buf, &audit(), OutputFormat::Table).unwrap();
+        let table = String::from_utf8(buf).unwrap();
+        let lines: Vec<_> = table.lines().collect();
+        assert_eq!(
+            lines[2],
+            "         3       1       2       8     yes  src/a, b.rs"
+        );
+        assert_eq!(
+            lines[6],
+            "      4       1         3       1       1     0     3      1  src"
+        );
+
+        let mut buf = Vec::new();
+        write_whitespace_audit(&mut buf, &WhitespaceAudit::default(), OutputFormat::Table).unwrap();
+        let table = String::from_utf8(buf).unwrap();
+        assert_eq!(table.lines().nth(2), Some("(no whitespace issues)"));
+    }
+}
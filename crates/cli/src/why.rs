@@ -0,0 +1,113 @@
+// crates/cli/src/why.rs
+use crate::config::Config;
+use crate::error::Result;
+use count_lines_engine::explain::{self, Explanation};
+use count_lines_engine::options::OutputFormat;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+/// Explains why `path` is counted or excluded (`--why`) and prints the
+/// rules evaluated to stdout, or to `config.output` when set.
+///
+/// # Errors
+/// Returns an error if `path` doesn't exist or the output fails.
+pub fn print_why(path: &Path, config: &Config) -> Result<()> {
+    let explanation = explain::explain(path, config)?;
+
+    if let Some(output) = &config.output {
+        let mut out = BufWriter::new(File::create(output)?);
+        write_explanation(&mut out, &explanation, config.format)?;
+        out.flush()?;
+    } else {
+        let stdout = io::stdout();
+        let mut out = stdout.lock();
+        write_explanation(&mut out, &explanation, config.format)?;
+    }
+    Ok(())
+}
+
+/// Writes one line per rule, then the verdict, similar to
+/// `git check-ignore -v`. `json` writes the same as a document.
+///
+/// # Errors
+/// Returns an error if writing to `out` fails.
+pub fn write_explanation(
+    out: &mut dyn Write,
+    explanation: &Explanation,
+    format: OutputFormat,
+) -> io::Result<()> {
+    let excluded_by = explanation.excluded_by();
+    if format == OutputFormat::Json {
+        let json = serde_json::json!({
+            "path": explanation.path,
+            "counted": excluded_by.is_none(),
+            "excluded_by": excluded_by,
+            "checks": explanation.checks,
+        });
+        let json = serde_json::to_string_pretty(&json).map_err(io::Error::other)?;
+        return writeln!(out, "{json}");
+    }
+
+    for check in &explanation.checks {
+        let status = if check.excluded { "EXCLUDE" } else { "pass" };
+        writeln!(out, "{status:<8} {:<18} {}", check.rule, check.detail)?;
+    }
+    match excluded_by {
+        Some(check) => writeln!(
+            out,
+            "{}: excluded by {} ({})",
+            explanation.path.display(),
+            check.rule,
+            check.detail
+        ),
+        None => writeln!(out, "{}: counted", explanation.path.display()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use count_lines_engine::explain::Check;
+    use std::path::PathBuf;
+
+    fn explanation() -> Explanation {
+        Explanation {
+            path: PathBuf::from("src/gen/foo.rs"),
+            checks: vec![
+                Check {
+                    rule: "root".to_string(),
+                    detail: ".".to_string(),
+                    excluded: false,
+                },
+                Check {
+                    rule: ".gitignore".to_string(),
+                    detail: "src/gen: .gitignore:3:src/gen/".to_string(),
+                    excluded: true,
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn test_text_lists_rules_and_verdict() {
+        let mut buf = Vec::new();
+        write_explanation(&mut buf, &explanation(), OutputFormat::Table).unwrap();
+        assert_eq!(
+            String::from_utf8(buf).unwrap(),
+            "pass     root               .\n\
+             EXCLUDE  .gitignore         src/gen: .gitignore:3:src/gen/\n\
+             src/gen/foo.rs: excluded by .gitignore (src/gen: .gitignore:3:src/gen/)\n"
+        );
+    }
+
+    #[test]
+    fn test_json_names_first_excluding_rule() {
+        let mut buf = Vec::new();
+        write_explanation(&mut buf, &explanation(), OutputFormat::Json).unwrap();
+        let value: serde_json::Value = serde_json::from_slice(&buf).unwrap();
+        assert_eq!(value["counted"], false);
+        assert_eq!(value["excluded_by"]["rule"], ".gitignore");
+        assert_eq!(value["checks"].as_array().unwrap().len(), 2);
+    }
+}
@@ -28,6 +28,48 @@ fn test_scan_sample_json() {
     let json: Value = serde_json::from_slice(&output.stdout).expect("Failed to parse JSON output");
 
     assert_json_snapshot!(json, {
-        "[].mtime" => "[MTIME]",
+        ".version" => "[VERSION]",
+        ".files[].mtime" => "[MTIME]",
     });
 }
+
+#[test]
+fn test_stdin_uses_pseudo_name() {
+    #[allow(deprecated)]
+    let mut cmd = Command::cargo_bin("count_lines").unwrap();
+    cmd.args([
+        "--stdin",
+        "--stdin-name",
+        "foo.rs",
+        "--sloc",
+        "--format",
+        "json",
+    ])
+    .write_stdin("// comment\nfn main() {}\n\n");
+
+    let assert = cmd.assert().success();
+    let json: Value =
+        serde_json::from_slice(&assert.get_output().stdout).expect("Failed to parse JSON output");
+
+    let file = &json["files"][0];
+    assert_eq!(file["path"], "foo.rs");
+    assert_eq!(file["lines"], 3);
+    assert_eq!(file["sloc"], 1);
+    assert_eq!(json["summary"]["files"], 1);
+}
+
+#[test]
+fn test_quiet_and_oneline() {
+    for (flag, expected) in [("--quiet", ""), ("-1", "files=1 lines=3 sloc=1 chars=22\n")] {
+        #[allow(deprecated)]
+        let mut cmd = Command::cargo_bin("count_lines").unwrap();
+        cmd.args(["--stdin", "--stdin-name", "foo.rs", flag])
+            .write_stdin("// comment\nfn main() {}\n\n");
+
+        let assert = cmd.assert().success();
+        assert_eq!(
+            String::from_utf8_lossy(&assert.get_output().stdout),
+            expected
+        );
+    }
+}
@@ -0,0 +1,34 @@
+// crates/core/fuzz/fuzz_targets/count_bytes.rs
+//! Feeds arbitrary bytes through `count_bytes` for every processor family.
+//!
+//! The first input byte picks the extension; the rest is the file content.
+#![no_main]
+
+use count_lines_core::config::AnalysisConfig;
+use count_lines_core::counter::count_bytes;
+use libfuzzer_sys::fuzz_target;
+
+/// One extension per line processor (and the plain-text fallback).
+const EXTENSIONS: &[&str] = &[
+    "rs", "c", "cpp", "cs", "java", "kt", "go", "swift", "js", "jsx", "ts", "tsx", "dart", "m",
+    "d", "php", "py", "rb", "pl", "sh", "tf", "ps1", "lua", "html", "vue", "svelte", "cshtml",
+    "sql", "hs", "jl", "nim", "ml", "lisp", "erl", "f", "mat", "bat", "asm", "vhd", "vb", "cbl",
+    "abap", "rpg", "v", "odin", "gleam", "txt",
+];
+
+fuzz_target!(|data: &[u8]| {
+    let Some((&selector, content)) = data.split_first() else {
+        return;
+    };
+    let extension = EXTENSIONS[usize::from(selector) % EXTENSIONS.len()];
+    let config = AnalysisConfig {
+        count_sloc: true,
+        count_words: true,
+        ..AnalysisConfig::default()
+    };
+
+    let stats = count_bytes(content, extension, &config);
+    if let Some(sloc) = stats.sloc {
+        assert!(sloc <= stats.lines, "more SLOC than lines for .{extension}");
+    }
+});
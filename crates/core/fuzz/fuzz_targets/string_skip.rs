@@ -0,0 +1,64 @@
+// crates/core/fuzz/fuzz_targets/string_skip.rs
+//! Runs the `string_utils` skip and search helpers on arbitrary input.
+//!
+//! The first two bytes are the `StringSkipOptions` flags; the rest is the line.
+//! Every skip must stay inside the line it was given.
+#![no_main]
+
+use count_lines_core::language::string_utils::{
+    StringSkipOptions, check_docstring_start, find_any_outside_string, find_hash_outside_string,
+    find_outside_string_sql, find_outside_string_swift, find_outside_string_with_options,
+    try_skip_byte_string, try_skip_char_literal, try_skip_cpp_raw_string,
+    try_skip_csharp_verbatim_string, try_skip_prefixed_string, try_skip_quoted_string,
+    try_skip_raw_string, try_skip_regex, try_skip_regex_literal, try_skip_swift_string,
+    try_skip_text_block,
+};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let Some((flags, line)) = data.split_first_chunk::<2>() else {
+        return;
+    };
+    let options = StringSkipOptions::default().with_flag(u16::from_le_bytes(*flags));
+
+    for skip in [
+        try_skip_raw_string(line),
+        try_skip_byte_string(line),
+        try_skip_cpp_raw_string(line),
+        try_skip_csharp_verbatim_string(line),
+        try_skip_text_block(line),
+        try_skip_swift_string(line),
+    ] {
+        assert!(skip.skip_count() <= line.len());
+    }
+    if let Some(len) = try_skip_char_literal(line) {
+        assert!(len <= line.len());
+    }
+
+    for i in 0..line.len() {
+        for skip in [
+            try_skip_prefixed_string(line, i, options),
+            try_skip_quoted_string(line, i, options),
+            try_skip_regex(line, i, options),
+            try_skip_regex_literal(line, i),
+        ] {
+            assert!(i + skip.skip_count() <= line.len());
+        }
+    }
+
+    if let Ok(text) = core::str::from_utf8(line) {
+        for pos in [
+            find_hash_outside_string(text),
+            find_outside_string_swift(text, "//"),
+            find_outside_string_sql(text, "--"),
+            find_outside_string_with_options(text, "//", options),
+            find_any_outside_string(text, &["//", "/*", "#"], options).map(|m| m.position),
+        ]
+        .into_iter()
+        .flatten()
+        {
+            assert!(pos < text.len());
+        }
+        let _ = check_docstring_start(text.trim());
+    }
+});
@@ -1,8 +1,11 @@
 // crates/core/src/config.rs
 use alloc::string::String;
+use alloc::vec::Vec;
 
 use hashbrown::HashMap;
 
+use crate::language::LanguageDefinition;
+
 /// Configuration for content analysis.
 #[derive(Debug, Clone, Default)]
 pub struct AnalysisConfig {
@@ -14,4 +17,35 @@ pub struct AnalysisConfig {
     pub count_newlines_in_chars: bool,
     /// Extension mapping (e.g. `h` → `cpp`).
     pub map_ext: HashMap<String, String>,
+    /// User-defined languages, checked before the built-in languages.
+    pub languages: Vec<LanguageDefinition>,
+    /// Pick the language from a Vim modeline or Emacs mode header when present.
+    pub detect_modeline: bool,
+    /// Whether to measure indentation depth.
+    pub count_indent: bool,
+    /// Columns per indentation level, and the tab stop width; `0` means
+    /// [`DEFAULT_TAB_WIDTH`].
+    pub tab_width: usize,
+    /// Whether to look for whitespace and line-ending problems.
+    pub audit_whitespace: bool,
+    /// Lines longer than this many bytes are measured in place, without
+    /// indentation or the whitespace audit, and counted in
+    /// [`crate::stats::AnalysisResult::long_lines`]; `0` measures every line
+    /// in full.
+    pub long_line_threshold: usize,
+}
+
+/// Columns per indentation level when [`AnalysisConfig::tab_width`] is unset.
+pub const DEFAULT_TAB_WIDTH: usize = 4;
+
+impl AnalysisConfig {
+    /// Columns per indentation level.
+    #[must_use]
+    pub const fn indent_width(&self) -> usize {
+        if self.tab_width == 0 {
+            DEFAULT_TAB_WIDTH
+        } else {
+            self.tab_width
+        }
+    }
 }
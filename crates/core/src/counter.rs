@@ -1,7 +1,11 @@
 // crates/core/src/counter.rs
 use crate::config::AnalysisConfig;
-use crate::language::get_processor;
-use crate::stats::AnalysisResult;
+use crate::language::modeline::detect_modeline;
+use crate::language::processor_for;
+use crate::language::processor_trait::LineProcessor;
+use crate::stats::{AnalysisResult, WhitespaceCounts};
+use alloc::boxed::Box;
+use alloc::vec::Vec;
 
 /// Count lines/chars/words/sloc in a byte slice.
 ///
@@ -9,26 +13,216 @@ use crate::stats::AnalysisResult;
 /// Processes in-memory bytes with binary detection and per-line SLOC analysis.
 #[must_use]
 pub fn count_bytes(input: &[u8], extension: &str, config: &AnalysisConfig) -> AnalysisResult {
-    let mut stats = AnalysisResult::new();
-
     // Binary check: skip counting for binary files
     if is_binary(input) {
+        let mut stats = AnalysisResult::new();
         stats.is_binary = true;
         return stats;
     }
 
-    // 2. Process line by line
-    let mut processor = get_processor(extension, &config.map_ext);
+    let mut processor = processor_for_content(input, extension, config);
+    let mut stats = tally_lines(input, &mut processor, config).into_result(config);
+    stats.embedded = processor.embedded();
+    stats
+}
 
-    let mut lines = 0;
-    let mut chars = 0;
-    let mut words = 0;
-    let mut sloc = 0;
+/// Creates the SLOC processor for `input`: the language named by a modeline
+/// when `config.detect_modeline` is set, otherwise the one of `extension`.
+#[must_use]
+pub fn processor_for_content(
+    input: &[u8],
+    extension: &str,
+    config: &AnalysisConfig,
+) -> Box<dyn LineProcessor> {
+    let detected = if config.detect_modeline {
+        detect_modeline(input)
+    } else {
+        None
+    };
+    processor_for(detected.as_deref().unwrap_or(extension), config)
+}
+
+/// Running totals of a line-by-line count.
+///
+/// Tallies of consecutive pieces of a file can be merged, so a large file
+/// can be counted in chunks split at line breaks.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct LineTally {
+    /// Number of lines.
+    pub lines: usize,
+    /// Number of characters.
+    pub chars: usize,
+    /// Number of words.
+    pub words: usize,
+    /// Source lines of code.
+    pub sloc: usize,
+    /// Lines holding only documentation comments.
+    pub doc_lines: usize,
+    /// Length of the longest line, excluding the line break.
+    pub max_line_length: usize,
+    /// Sum of the line lengths, excluding line breaks.
+    pub total_line_length: usize,
+    /// Widest leading whitespace of a non-blank line, in columns.
+    pub max_indent: usize,
+    /// Sum of the leading whitespace of the non-blank lines, in columns.
+    pub total_indent: usize,
+    /// Non-blank lines whose indentation was measured.
+    pub indented_lines: usize,
+    /// Whitespace findings, when audited.
+    pub whitespace: WhitespaceTally,
+    /// Lines over the long line threshold.
+    pub long_lines: usize,
+}
+
+/// Running whitespace findings of a line-by-line count.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct WhitespaceTally {
+    /// Lines ending in spaces or tabs.
+    pub trailing_whitespace: usize,
+    /// Lines with a space before a tab in their indentation.
+    pub space_before_tab: usize,
+    /// Non-blank lines indented with a tab.
+    pub tab_indented: usize,
+    /// Non-blank lines indented with spaces only.
+    pub space_indented: usize,
+    /// Lines ending in `\r\n`.
+    pub crlf: usize,
+    /// Lines ending in a bare `\n`.
+    pub lf: usize,
+    /// Whether the last line counted has no line break.
+    pub unterminated: bool,
+}
+
+impl WhitespaceTally {
+    /// Records the findings of `line`, including its line break.
+    fn observe(&mut self, line: &str) {
+        let content = &line[..line.len() - self.observe_ending(line.as_bytes())];
+        if content.ends_with([' ', '\t']) {
+            self.trailing_whitespace += 1;
+        }
+
+        let code = content.trim_start_matches([' ', '\t']);
+        let indent = &content[..content.len() - code.len()];
+        if code.is_empty() || indent.is_empty() {
+            return;
+        }
+        if indent.contains(" \t") {
+            self.space_before_tab += 1;
+        } else if indent.starts_with('\t') {
+            self.tab_indented += 1;
+        } else {
+            self.space_indented += 1;
+        }
+    }
+
+    /// Records the line break of `line` and returns its length in bytes.
+    fn observe_ending(&mut self, line: &[u8]) -> usize {
+        self.unterminated = !line.ends_with(b"\n");
+        if line.ends_with(b"\r\n") {
+            self.crlf += 1;
+            2
+        } else if line.ends_with(b"\n") {
+            self.lf += 1;
+            1
+        } else {
+            0
+        }
+    }
+
+    /// Adds the findings of the piece that follows this one.
+    fn merge(&mut self, other: &Self, other_lines: usize) {
+        self.trailing_whitespace += other.trailing_whitespace;
+        self.space_before_tab += other.space_before_tab;
+        self.tab_indented += other.tab_indented;
+        self.space_indented += other.space_indented;
+        self.crlf += other.crlf;
+        self.lf += other.lf;
+        if other_lines > 0 {
+            self.unterminated = other.unterminated;
+        }
+    }
+
+    /// The findings of the counted content.
+    #[must_use]
+    pub fn counts(&self) -> WhitespaceCounts {
+        WhitespaceCounts {
+            trailing_whitespace: self.trailing_whitespace,
+            mixed_indent: self.space_before_tab + self.tab_indented.min(self.space_indented),
+            crlf: self.crlf,
+            lf: self.lf,
+            missing_final_newline: self.unterminated,
+        }
+    }
+}
+
+impl LineTally {
+    /// Adds the totals of the piece that follows this one.
+    pub fn merge(&mut self, other: &Self) {
+        self.lines += other.lines;
+        self.chars += other.chars;
+        self.words += other.words;
+        self.sloc += other.sloc;
+        self.doc_lines += other.doc_lines;
+        self.max_line_length = self.max_line_length.max(other.max_line_length);
+        self.total_line_length += other.total_line_length;
+        self.max_indent = self.max_indent.max(other.max_indent);
+        self.total_indent += other.total_indent;
+        self.indented_lines += other.indented_lines;
+        self.whitespace.merge(&other.whitespace, other.lines);
+        self.long_lines += other.long_lines;
+    }
+
+    /// The analysis result of the counted content.
+    #[must_use]
+    pub fn into_result(self, config: &AnalysisConfig) -> AnalysisResult {
+        let mut stats = AnalysisResult::new();
+        stats.lines = self.lines;
+        stats.chars = self.chars;
+        if config.count_words {
+            stats.words = Some(self.words);
+        }
+        stats.sloc = Some(self.sloc);
+        stats.doc_lines = self.doc_lines;
+        stats.max_line_length = self.max_line_length;
+        if self.lines > 0 {
+            #[allow(clippy::cast_precision_loss)]
+            let avg = self.total_line_length as f64 / self.lines as f64;
+            stats.avg_line_length = avg;
+        }
+        let width = config.indent_width();
+        stats.max_indent_depth = self.max_indent / width;
+        if self.indented_lines > 0 {
+            #[allow(clippy::cast_precision_loss)]
+            let avg = self.total_indent as f64 / (width * self.indented_lines) as f64;
+            stats.avg_indent_depth = avg;
+        }
+        if config.audit_whitespace {
+            stats.whitespace = Some(self.whitespace.counts());
+        }
+        stats.long_lines = self.long_lines;
+        stats
+    }
+}
+
+/// Counts the lines of `input` with `processor`, continuing from whatever
+/// state it is in.
+#[must_use]
+pub fn tally_lines(
+    input: &[u8],
+    processor: &mut dyn LineProcessor,
+    config: &AnalysisConfig,
+) -> LineTally {
+    let mut tally = LineTally::default();
 
     // Use split_inclusive on bytes to avoid allocating a full String for the file
     // if it contains invalid UTF-8.
     for line_bytes in input.split_inclusive(|&b| b == b'\n') {
-        lines += 1;
+        tally.lines += 1;
+
+        if is_long_line(line_bytes, config) {
+            tally_long_line(line_bytes, processor, &mut tally, config);
+            continue;
+        }
 
         // Convert line to lossy string (zero-copy if valid UTF-8)
         let line = crate::language::string_utils::from_utf8_lossy(line_bytes);
@@ -36,25 +230,322 @@ pub fn count_bytes(input: &[u8], extension: &str, config: &AnalysisConfig) -> An
         let l_stats =
             processor.process_line_stats(&line, config.count_words, config.count_newlines_in_chars);
 
-        chars += l_stats.chars;
-        sloc += l_stats.sloc;
+        tally.chars += l_stats.chars;
+
+        let line_length = if config.count_newlines_in_chars {
+            l_stats.chars - (line.len() - line.trim_end_matches(['\n', '\r']).len())
+        } else {
+            l_stats.chars
+        };
+        tally.max_line_length = tally.max_line_length.max(line_length);
+        tally.total_line_length += line_length;
+        tally.sloc += l_stats.sloc;
+        tally.doc_lines += l_stats.doc;
         if config.count_words {
-            words += l_stats.words;
+            tally.words += l_stats.words;
+        }
+        if config.count_indent
+            && let Some(indent) = indent_columns(&line, config.indent_width())
+        {
+            tally.max_indent = tally.max_indent.max(indent);
+            tally.total_indent += indent;
+            tally.indented_lines += 1;
+        }
+        if config.audit_whitespace {
+            tally.whitespace.observe(&line);
+        }
+    }
+
+    tally
+}
+
+/// Whether `line` is over the long line threshold of `config`.
+#[must_use]
+pub const fn is_long_line(line: &[u8], config: &AnalysisConfig) -> bool {
+    config.long_line_threshold > 0 && line.len() > config.long_line_threshold
+}
+
+/// Counts an over-long line: the bytes are walked once in place for the
+/// characters and words, invalid UTF-8 sequences counting as one character
+/// each as in a lossy conversion. The processor then reads the whole line
+/// again for SLOC, so a comment or string it opens or closes is followed;
+/// that pass borrows a valid UTF-8 line but copies one with invalid
+/// sequences. Indentation isn't measured and the whitespace audit only
+/// records its line break.
+fn tally_long_line(
+    line: &[u8],
+    processor: &mut dyn LineProcessor,
+    tally: &mut LineTally,
+    config: &AnalysisConfig,
+) {
+    let mut chars = 0;
+    let mut words = 0;
+    let mut in_word = false;
+    for chunk in line.utf8_chunks() {
+        for c in chunk.valid().chars() {
+            chars += 1;
+            if c.is_whitespace() {
+                in_word = false;
+            } else if !in_word {
+                in_word = true;
+                words += 1;
+            }
+        }
+        if !chunk.invalid().is_empty() {
+            chars += 1;
+            if !in_word {
+                in_word = true;
+                words += 1;
+            }
         }
     }
+    let line_break = if line.ends_with(b"\r\n") {
+        2
+    } else {
+        usize::from(line.ends_with(b"\n"))
+    };
+    let line_length = chars - line_break;
 
-    stats.lines = lines;
-    stats.chars = chars;
+    tally.long_lines += 1;
+    tally.chars += if config.count_newlines_in_chars {
+        chars
+    } else {
+        line_length
+    };
+    tally.max_line_length = tally.max_line_length.max(line_length);
+    tally.total_line_length += line_length;
+    let kind = processor.process_line_stats(
+        &crate::language::string_utils::from_utf8_lossy(line),
+        false,
+        false,
+    );
+    tally.sloc += kind.sloc;
+    tally.doc_lines += kind.doc;
     if config.count_words {
-        stats.words = Some(words);
+        tally.words += words;
+    }
+    if config.audit_whitespace {
+        tally.whitespace.observe_ending(line);
     }
-    stats.sloc = Some(sloc);
+}
 
-    stats
+/// Width of the leading whitespace of `line` in columns, a tab advancing to
+/// the next multiple of `tab_width`; `None` for a blank line.
+fn indent_columns(line: &str, tab_width: usize) -> Option<usize> {
+    let mut columns = 0;
+    for c in line.chars() {
+        match c {
+            ' ' => columns += 1,
+            '\t' => columns += tab_width - columns % tab_width,
+            '\n' | '\r' => return None,
+            c if c.is_whitespace() => columns += 1,
+            _ => return Some(columns),
+        }
+    }
+    None
+}
+
+/// Splits `input` into pieces of at least `chunk_size` bytes that end at a
+/// line break (the last one at the end of `input`).
+#[must_use]
+pub fn split_at_lines(input: &[u8], chunk_size: usize) -> Vec<&[u8]> {
+    let mut chunks = Vec::new();
+    let mut rest = input;
+    while rest.len() > chunk_size {
+        let Some(newline) = rest[chunk_size..].iter().position(|&b| b == b'\n') else {
+            break;
+        };
+        let (chunk, tail) = rest.split_at(chunk_size + newline + 1);
+        chunks.push(chunk);
+        rest = tail;
+    }
+    if !rest.is_empty() {
+        chunks.push(rest);
+    }
+    chunks
 }
 
-fn is_binary(input: &[u8]) -> bool {
+/// Whether `input` looks binary (a NUL byte in the first 8 KiB).
+#[must_use]
+pub fn is_binary(input: &[u8]) -> bool {
     // Check for NUL bytes in the first 8KB to detect binary content
     let len = input.len().min(8 * 1024);
     input[..len].contains(&0)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_line_length_metrics_exclude_line_breaks() {
+        for count_newlines_in_chars in [false, true] {
+            let config = AnalysisConfig {
+                count_newlines_in_chars,
+                ..AnalysisConfig::default()
+            };
+            let stats = count_bytes("ab\r\nabcdef\nabcd".as_bytes(), "txt", &config);
+            assert_eq!(stats.max_line_length, 6);
+            assert!((stats.avg_line_length - 4.0).abs() < f64::EPSILON);
+        }
+    }
+
+    #[test]
+    fn test_split_at_lines() {
+        let input = b"aa\nbbbb\nc\nd";
+        assert_eq!(split_at_lines(input, 3), [&b"aa\nbbbb\n"[..], &b"c\nd"[..]]);
+        assert_eq!(split_at_lines(input, 100), [&input[..]]);
+        assert!(split_at_lines(b"", 3).is_empty());
+    }
+
+    #[test]
+    fn test_merged_tallies_match_whole_count() {
+        let input = b"int a;\n/* x */\n\nint b; // y\nint c;\n";
+        let config = AnalysisConfig {
+            count_words: true,
+            ..AnalysisConfig::default()
+        };
+        let whole = count_bytes(input, "c", &config);
+
+        let mut merged = LineTally::default();
+        for chunk in split_at_lines(input, 8) {
+            let mut processor = processor_for_content(input, "c", &config);
+            merged.merge(&tally_lines(chunk, &mut processor, &config));
+        }
+        assert_eq!(merged.into_result(&config), whole);
+    }
+
+    #[test]
+    fn test_doc_lines() {
+        let config = AnalysisConfig::default();
+        let doc_lines =
+            |source: &str, ext: &str| count_bytes(source.as_bytes(), ext, &config).doc_lines;
+
+        let rust =
+            "//! Crate.\n\n/// Adds.\n// plain\nfn add() {}\n/**\n * Block.\n */\nfn b() {}\n";
+        assert_eq!(doc_lines(rust, "rs"), 5);
+        let java = "/**\n * Adds.\n */\nint add();\n/* plain */\n/// no\n";
+        assert_eq!(doc_lines(java, "java"), 3);
+        let ts = "/** TSDoc. */\nexport const a = 1;\n/// <reference path=\"x\" />\n";
+        assert_eq!(doc_lines(ts, "ts"), 1);
+        let python = "def f():\n    \"\"\"Docs.\n\n    More.\n    \"\"\"\n    x = \"\"\"data\"\"\"\n    # plain\n";
+        assert_eq!(doc_lines(python, "py"), 4);
+        assert_eq!(doc_lines("# plain\n", "sh"), 0);
+    }
+
+    #[test]
+    fn test_indent_depth() {
+        let input = b"fn a() {\n    if x {\n\t\ty();\n  \t}\n\n        \n}\n";
+        let stats = count_bytes(input, "rs", &AnalysisConfig::default());
+        assert_eq!(stats.max_indent_depth, 0);
+
+        let config = AnalysisConfig {
+            count_indent: true,
+            ..AnalysisConfig::default()
+        };
+        let stats = count_bytes(input, "rs", &config);
+        // Columns 0, 4, 8, 4 and 0; blank lines are left out.
+        assert_eq!(stats.max_indent_depth, 2);
+        assert!((stats.avg_indent_depth - 0.8).abs() < 1e-9);
+
+        let config = AnalysisConfig {
+            tab_width: 2,
+            ..config
+        };
+        let stats = count_bytes(input, "rs", &config);
+        // Columns 0, 4, 4, 4 and 0.
+        assert_eq!(stats.max_indent_depth, 2);
+        assert!((stats.avg_indent_depth - 1.2).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_long_lines_are_scanned_in_place() {
+        let long = alloc::format!("/* {} */\r\n", "a\u{e9} ".repeat(20));
+        let input = alloc::format!("int a;\n{long}   \n// c\n");
+        let config = AnalysisConfig {
+            count_words: true,
+            ..AnalysisConfig::default()
+        };
+        let whole = count_bytes(input.as_bytes(), "c", &config);
+        assert_eq!(whole.long_lines, 0);
+        assert_eq!(whole.sloc, Some(1));
+
+        let guarded = AnalysisConfig {
+            long_line_threshold: 32,
+            ..config.clone()
+        };
+        let stats = count_bytes(input.as_bytes(), "c", &guarded);
+        assert_eq!(stats.long_lines, 1);
+        assert_eq!(
+            (stats.sloc, stats.chars, stats.words, stats.max_line_length),
+            (whole.sloc, whole.chars, whole.words, whole.max_line_length)
+        );
+
+        let invalid = b"ab\xFF\xFEcd \xC3\n";
+        let stats = count_bytes(invalid, "txt", &guarded);
+        let lossy = count_bytes(invalid, "txt", &config);
+        let short = AnalysisConfig {
+            long_line_threshold: 4,
+            ..guarded.clone()
+        };
+        let stats_short = count_bytes(invalid, "txt", &short);
+        assert_eq!(stats.long_lines, 0);
+        assert_eq!(stats_short.long_lines, 1);
+        assert_eq!(
+            (stats_short.chars, stats_short.words),
+            (lossy.chars, lossy.words)
+        );
+    }
+
+    #[test]
+    fn test_long_line_opening_a_block_comment() {
+        let long = "x ".repeat(40);
+        let input = alloc::format!("int a;\n/** {long}\n * b\n */\nint c;\n/* {long} */ int d;\n");
+        let config = AnalysisConfig::default();
+        let whole = count_bytes(input.as_bytes(), "c", &config);
+        let guarded = AnalysisConfig {
+            long_line_threshold: 32,
+            ..config
+        };
+        let stats = count_bytes(input.as_bytes(), "c", &guarded);
+        assert_eq!(stats.long_lines, 2);
+        assert_eq!((whole.sloc, whole.doc_lines), (Some(3), 3));
+        assert_eq!((stats.sloc, stats.doc_lines), (whole.sloc, whole.doc_lines));
+    }
+
+    #[test]
+    fn test_whitespace_audit() {
+        let config = AnalysisConfig {
+            audit_whitespace: true,
+            ..AnalysisConfig::default()
+        };
+        let audit = |input: &str| count_bytes(input.as_bytes(), "c", &config).whitespace;
+
+        let input = "int a; \r\n\tb();\n\tc();\n    d();\n \te();\n\t\n}";
+        assert_eq!(
+            audit(input),
+            Some(WhitespaceCounts {
+                trailing_whitespace: 2,
+                mixed_indent: 2,
+                crlf: 1,
+                lf: 5,
+                missing_final_newline: true,
+            })
+        );
+        let clean = audit("a\n\tb\n\t  c\n\n").unwrap();
+        assert!(!clean.has_issues());
+        assert_eq!(audit(""), Some(WhitespaceCounts::default()));
+        assert_eq!(
+            count_bytes(input.as_bytes(), "c", &AnalysisConfig::default()).whitespace,
+            None
+        );
+
+        // Chunks merge to the same findings.
+        let mut merged = LineTally::default();
+        for chunk in split_at_lines(input.as_bytes(), 10) {
+            let mut processor = processor_for_content(input.as_bytes(), "c", &config);
+            merged.merge(&tally_lines(chunk, &mut processor, &config));
+        }
+        assert_eq!(merged.into_result(&config).whitespace, audit(input));
+    }
+}
@@ -0,0 +1,85 @@
+// crates/core/src/fingerprint.rs
+//! Normalized line fingerprints for near-duplicate detection.
+
+use alloc::vec::Vec;
+
+use crate::config::AnalysisConfig;
+use crate::counter::{is_binary, is_long_line};
+use crate::language::modeline::detect_modeline;
+use crate::language::processor_for;
+use crate::language::string_utils::from_utf8_lossy;
+
+const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+/// Returns one hash per code line of `input`, sorted.
+///
+/// Lines are trimmed and runs of whitespace collapsed before hashing, so
+/// re-indented code still matches. Comment and blank lines (as decided by
+/// the SLOC processor for `extension`) are skipped, as are lines without any
+/// alphanumeric character, such as lone braces, which every file shares,
+/// and lines over the long line threshold. Binary input yields no fingerprints.
+#[must_use]
+pub fn line_fingerprints(input: &[u8], extension: &str, config: &AnalysisConfig) -> Vec<u64> {
+    if is_binary(input) {
+        return Vec::new();
+    }
+    let detected = if config.detect_modeline {
+        detect_modeline(input)
+    } else {
+        None
+    };
+    let mut processor = processor_for(detected.as_deref().unwrap_or(extension), config);
+
+    let mut hashes = Vec::new();
+    for line_bytes in input.split_inclusive(|&b| b == b'\n') {
+        let line = from_utf8_lossy(line_bytes);
+        // Long lines still go through the processor to keep its state.
+        if processor.process_line(&line) == 0
+            || is_long_line(line_bytes, config)
+            || !line.chars().any(char::is_alphanumeric)
+        {
+            continue;
+        }
+        hashes.push(hash_normalized(&line));
+    }
+    hashes.sort_unstable();
+    hashes
+}
+
+/// FNV-1a over the whitespace-separated words of `line`, joined by a space.
+fn hash_normalized(line: &str) -> u64 {
+    let mut hash = FNV_OFFSET;
+    for (i, word) in line.split_whitespace().enumerate() {
+        let separator: &[u8] = if i == 0 { b"" } else { b" " };
+        for &b in separator.iter().chain(word.as_bytes()) {
+            hash ^= u64::from(b);
+            hash = hash.wrapping_mul(FNV_PRIME);
+        }
+    }
+    hash
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ignores_comments_layout_and_punctuation() {
+        let config = AnalysisConfig::default();
+        let a = line_fingerprints(b"fn main() {\n    let x = 1;\n}\n// note\n", "rs", &config);
+        let b = line_fingerprints(
+            b"/* header */\nfn   main() {\nlet x = 1;\n\n  }\n",
+            "rs",
+            &config,
+        );
+        assert_eq!(a.len(), 2);
+        assert_eq!(a, b);
+        assert!(a.contains(&hash_normalized("  let   x = 1;")));
+    }
+
+    #[test]
+    fn test_binary_has_no_fingerprints() {
+        assert!(line_fingerprints(b"a\0b\n", "rs", &AnalysisConfig::default()).is_empty());
+    }
+}
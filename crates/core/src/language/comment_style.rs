@@ -17,12 +17,16 @@ pub enum CommentStyle {
     /// 単純な Hash スタイル (#) - Shell, YAML, Config系等
     /// 複雑な文字列処理不要、# のみでコメント判定
     SimpleHash,
+    /// HCL/Terraform: #, //, /* */ とヒアドキュメント
+    Hcl,
     /// `PowerShell`: # と <# #>
     PowerShell,
     /// Lua: -- と --[[ ]]
     Lua,
     /// HTML/XML: <!-- -->
     Html,
+    /// Razor: @* *@, <!-- -->, 行頭の // と /* */
+    Razor,
     /// SQL: -- と /* */
     Sql,
     /// Haskell: -- と {- -} (ネスト対応)
@@ -37,6 +41,8 @@ pub enum CommentStyle {
     Matlab,
     /// Julia: # と #= =# (ネスト対応)
     Julia,
+    /// Nim: # と #[ ]# (ネスト対応)
+    Nim,
     /// OCaml/F#/Pascal: (* *) (ネスト対応)
     OCaml,
     /// D言語: //, /* */, /+ +/ (ネスト対応)
@@ -51,6 +57,12 @@ pub enum CommentStyle {
     Vhdl,
     /// Visual Basic/VBA/VBS: ' と REM
     VisualBasic,
+    /// COBOL: カラム7の * / と *>
+    Cobol,
+    /// ABAP: カラム1の * と "
+    Abap,
+    /// RPG: カラム7の * と //
+    Rpg,
     /// コメント構文なし（全ての非空行をカウント）
     None,
 }
@@ -65,7 +77,8 @@ impl CommentStyle {
             | "java" | "js" | "mjs" | "cjs" | "jsx" | "ts" | "tsx" | "mts" | "cts" | "rs"
             | "go" | "swift" | "kt" | "kts" | "scala" | "sc" | "dart" | "v" | "sv" | "svh"
             | "zig" | "m" | "mm" | "groovy" | "gradle" | "css" | "scss" | "sass" | "less"
-            | "json" | "jsonc" | "proto" | "thrift" | "sol" | "ld" | "lds" => Self::CStyle,
+            | "json" | "jsonc" | "proto" | "thrift" | "sol" | "ld" | "lds" | "vv" | "vsh"
+            | "odin" | "gleam" => Self::CStyle,
 
             // D言語 (//, /* */, /+ +/)
             "d" => Self::DLang,
@@ -77,6 +90,7 @@ impl CommentStyle {
             "py" | "pyw" | "pyi" => Self::Python,
 
             // Ruby: # と =begin/=end
+            // Crystal は # のみだが、#{} 補間とヒアドキュメントが Ruby と同じ
             "rb" | "rake" | "gemspec" | "cr" => Self::Ruby,
 
             // Perl: # と POD
@@ -84,9 +98,12 @@ impl CommentStyle {
 
             // 単純な Hash スタイル (#)
             "sh" | "bash" | "zsh" | "fish" | "yml" | "yaml" | "toml" | "dockerfile"
-            | "makefile" | "mk" | "cmake" | "nim" | "ex" | "exs" | "coffee" | "tcl" | "awk"
-            | "sed" | "tf" | "tfvars" | "r" | "rmd" | "ini" | "conf" | "cfg" | "properties"
-            | "graphql" | "gql" | "nix" => Self::SimpleHash,
+            | "makefile" | "mk" | "cmake" | "ex" | "exs" | "coffee" | "tcl" | "awk" | "sed"
+            | "r" | "rmd" | "ini" | "conf" | "cfg" | "properties" | "graphql" | "gql" | "nix"
+            | "pp" => Self::SimpleHash,
+
+            // HCL/Terraform (#, //, /* */)
+            "tf" | "tfvars" | "hcl" => Self::Hcl,
 
             // PowerShell (# と <# #>)
             "ps1" | "psm1" | "psd1" => Self::PowerShell,
@@ -95,7 +112,11 @@ impl CommentStyle {
             "lua" => Self::Lua,
 
             // HTML/XML (<!-- -->)
-            "html" | "htm" | "xhtml" | "xml" | "xsl" | "xslt" | "xsd" | "svg" | "vue" => Self::Html,
+            "html" | "htm" | "xhtml" | "xml" | "xsl" | "xslt" | "xsd" | "svg" | "vue"
+            | "svelte" => Self::Html,
+
+            // Razor (@* *@)
+            "cshtml" | "razor" => Self::Razor,
 
             // SQL (-- と /* */)
             "sql" => Self::Sql,
@@ -106,9 +127,12 @@ impl CommentStyle {
             // Julia (# と #= =#)
             "jl" => Self::Julia,
 
+            // Nim (# と #[ ]#)
+            "nim" | "nims" | "nimble" => Self::Nim,
+
             // OCaml/F#/Pascal (* *)
-            "ml" | "mli" | "fs" | "fsi" | "fsx" | "fsscript" | "pas" | "pp" | "dpr" | "dpk"
-            | "sml" | "sig" | "fun" => Self::OCaml,
+            "ml" | "mli" | "fs" | "fsi" | "fsx" | "fsscript" | "pas" | "dpr" | "dpk" | "sml"
+            | "sig" | "fun" => Self::OCaml,
 
             // Lisp系 (;)
             "lisp" | "lsp" | "cl" | "el" | "clj" | "cljs" | "cljc" | "edn" | "scm" | "ss"
@@ -138,6 +162,15 @@ impl CommentStyle {
             // Visual Basic / VBA / VBScript (' と REM)
             "vb" | "vbs" | "bas" | "cls" | "frm" => Self::VisualBasic,
 
+            // COBOL (カラム7の標識と *>)
+            "cbl" | "cob" | "cpy" => Self::Cobol,
+
+            // ABAP (カラム1の * と ")
+            "abap" => Self::Abap,
+
+            // RPG (カラム7の * と //)
+            "rpg" | "rpgle" | "sqlrpgle" | "rpgleinc" => Self::Rpg,
+
             // その他
             _ => Self::None,
         }
@@ -0,0 +1,108 @@
+//! 実行時に登録するユーザー定義言語
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use serde::{Deserialize, Serialize};
+
+/// User-defined language registered at runtime.
+///
+/// Definitions are matched against the (mapped) file extension before the
+/// built-in [`CommentStyle`](super::comment_style::CommentStyle) table, so they
+/// can also override a built-in language.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LanguageDefinition {
+    /// Language name. Can be used as the target of an extension mapping.
+    pub name: String,
+    /// File extensions (without the leading dot) handled by this language.
+    pub extensions: Vec<String>,
+    /// Line comment prefixes, e.g. `//` or `#`.
+    pub line_comments: Vec<String>,
+    /// Block comment delimiter pairs, e.g. `("/*", "*/")`.
+    pub block_comments: Vec<(String, String)>,
+    /// Whether block comments nest.
+    pub nested_comments: bool,
+    /// String delimiters. Comment markers inside strings are ignored.
+    pub string_delimiters: Vec<String>,
+}
+
+impl LanguageDefinition {
+    /// Returns `true` if this definition handles the given extension or language name.
+    #[must_use]
+    pub fn matches(&self, ext_or_name: &str) -> bool {
+        let key = ext_or_name.trim_start_matches('.');
+        self.name.eq_ignore_ascii_case(key)
+            || self
+                .extensions
+                .iter()
+                .any(|e| e.trim_start_matches('.').eq_ignore_ascii_case(key))
+    }
+
+    /// Checks that the definition is usable.
+    ///
+    /// # Errors
+    ///
+    /// Returns a description of the first problem found: a missing name or
+    /// extension list, or an empty comment/string delimiter.
+    pub fn validate(&self) -> Result<(), &'static str> {
+        if self.name.trim().is_empty() {
+            return Err("language name must not be empty");
+        }
+        if self
+            .extensions
+            .iter()
+            .all(|e| e.trim_start_matches('.').is_empty())
+        {
+            return Err("at least one extension is required");
+        }
+        if self.line_comments.iter().any(String::is_empty) {
+            return Err("line comment prefixes must not be empty");
+        }
+        if self
+            .block_comments
+            .iter()
+            .any(|(start, end)| start.is_empty() || end.is_empty())
+        {
+            return Err("block comment delimiters must not be empty");
+        }
+        if self.string_delimiters.iter().any(String::is_empty) {
+            return Err("string delimiters must not be empty");
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+    use alloc::vec;
+
+    fn def() -> LanguageDefinition {
+        LanguageDefinition {
+            name: "MyLang".to_string(),
+            extensions: vec![".myl".to_string()],
+            line_comments: vec!["--".to_string()],
+            ..LanguageDefinition::default()
+        }
+    }
+
+    #[test]
+    fn test_matches_name_and_extension() {
+        let d = def();
+        assert!(d.matches("myl"));
+        assert!(d.matches("MYL"));
+        assert!(d.matches("mylang"));
+        assert!(!d.matches("rs"));
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(def().validate().is_ok());
+        assert!(LanguageDefinition::default().validate().is_err());
+
+        let mut bad = def();
+        bad.block_comments = vec![("/*".to_string(), String::new())];
+        assert!(bad.validate().is_err());
+    }
+}
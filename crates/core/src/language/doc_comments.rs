@@ -0,0 +1,141 @@
+// crates/core/src/language/doc_comments.rs
+//! Documentation comment detection for the C-family processors.
+//!
+//! A line counts as documentation when it has no code and is part of a doc
+//! comment: a `/** ... */` block (Javadoc, `TSDoc`, `KDoc`, Doxygen) or, for
+//! Rust, a `///` / `//!` line or a `/*! ... */` block. Empty `/**/`
+//! comments and `////` separator lines are ordinary comments.
+
+/// Which comment markers start documentation.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DocSyntax {
+    /// No documentation comments.
+    #[default]
+    None,
+    /// `/** ... */` blocks.
+    Javadoc,
+    /// `///` and `//!` lines, `/** ... */` and `/*! ... */` blocks.
+    Rust,
+}
+
+/// Tracks whether the block comment a processor is in is a doc comment.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DocComments {
+    syntax: DocSyntax,
+    in_doc_block: bool,
+}
+
+impl DocComments {
+    /// A tracker for `syntax`.
+    #[must_use]
+    pub const fn new(syntax: DocSyntax) -> Self {
+        Self {
+            syntax,
+            in_doc_block: false,
+        }
+    }
+
+    /// Classifies `line` after the processor has counted it.
+    ///
+    /// `was_in_block` and `in_block` tell whether the processor was inside a
+    /// block comment before and after the line.
+    pub fn observe(
+        &mut self,
+        line: &str,
+        has_code: bool,
+        was_in_block: bool,
+        in_block: bool,
+    ) -> bool {
+        let doc = if was_in_block {
+            self.in_doc_block
+        } else {
+            let trimmed = line.trim_start();
+            let opens_block = self.opens_doc_block(trimmed);
+            self.in_doc_block = opens_block;
+            opens_block || self.is_doc_line_comment(trimmed)
+        };
+        if !in_block {
+            self.in_doc_block = false;
+        }
+        doc && !has_code
+    }
+
+    /// Forgets an open doc block.
+    pub fn reset(&mut self) {
+        self.in_doc_block = false;
+    }
+
+    fn opens_doc_block(&self, trimmed: &str) -> bool {
+        let javadoc = trimmed
+            .strip_prefix("/**")
+            .is_some_and(|rest| !rest.starts_with(['*', '/']));
+        match self.syntax {
+            DocSyntax::None => false,
+            DocSyntax::Javadoc => javadoc,
+            DocSyntax::Rust => javadoc || trimmed.starts_with("/*!"),
+        }
+    }
+
+    fn is_doc_line_comment(&self, trimmed: &str) -> bool {
+        self.syntax == DocSyntax::Rust
+            && (trimmed.starts_with("//!")
+                || trimmed
+                    .strip_prefix("///")
+                    .is_some_and(|rest| !rest.starts_with('/')))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn classify(syntax: DocSyntax, lines: &[(&str, bool, bool)]) -> alloc::vec::Vec<bool> {
+        let mut docs = DocComments::new(syntax);
+        let mut in_block = false;
+        lines
+            .iter()
+            .map(|&(line, has_code, block_after)| {
+                let doc = docs.observe(line, has_code, in_block, block_after);
+                in_block = block_after;
+                doc
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_javadoc_blocks() {
+        let lines = [
+            ("/**", false, true),
+            (" * Adds.", false, true),
+            (" */", false, false),
+            ("int add();", true, false),
+            ("/* plain", false, true),
+            ("   still plain */", false, false),
+            ("/**/", false, false),
+            ("/** one line */", false, false),
+            ("/// not javadoc", false, false),
+        ];
+        assert_eq!(
+            classify(DocSyntax::Javadoc, &lines),
+            [true, true, true, false, false, false, false, true, false]
+        );
+    }
+
+    #[test]
+    fn test_rust_markers() {
+        let lines = [
+            ("//! Crate docs.", false, false),
+            ("/// Item docs.", false, false),
+            ("//// separator", false, false),
+            ("// plain", false, false),
+            ("/*! inner", false, true),
+            ("*/", false, false),
+            ("fn a() {} /// trailing", true, false),
+        ];
+        assert_eq!(
+            classify(DocSyntax::Rust, &lines),
+            [true, true, false, false, true, true, false]
+        );
+        assert_eq!(classify(DocSyntax::None, &lines), [false; 7]);
+    }
+}
@@ -17,9 +17,8 @@ pub struct HeredocEntry {
 
 /// ヒアドキュメントの状態管理
 ///
-/// 複数のヒアドキュメントが1行に記述される場合（スタック）には今のところ対応していませんが、
-/// 将来的な拡張のためにベクタで管理します。
-/// 現状は先頭の要素のみを使用します。
+/// 1行に複数のヒアドキュメントが記述される場合 (`foo(<<A, <<B)`) は、
+/// 開始された順に本文と終了識別子が続くため、先頭から順に終了を待ちます。
 #[derive(Debug, Default, Clone)]
 pub struct HeredocContext {
     stack: Vec<HeredocEntry>,
@@ -54,7 +53,7 @@ impl HeredocContext {
             return false;
         }
 
-        let entry = &self.stack[0]; // 現在は単一レベルのみ簡易サポート
+        let entry = &self.stack[0];
 
         let is_end = if entry.allow_indent {
             // インデント許可: トリムして比較
@@ -74,6 +73,29 @@ impl HeredocContext {
         false
     }
 
+    /// 終了識別子の後に識別子以外の文字 (`;`, `,`, `)` など) が続く行も終了とみなす
+    ///
+    /// PHP 7.3 以降の柔軟なヒアドキュメント構文 (`EOT);` など) 向けです。
+    /// 終了した場合は true を返します。
+    pub fn check_end_marker(&mut self, line: &str) -> bool {
+        let Some(entry) = self.stack.first() else {
+            return false;
+        };
+        let text = if entry.allow_indent {
+            line.trim_start()
+        } else {
+            line
+        };
+        let is_end = text
+            .strip_prefix(entry.identifier.as_str())
+            .is_some_and(|rest| !rest.starts_with(|c: char| c.is_alphanumeric() || c == '_'));
+
+        if is_end {
+            self.stack.remove(0);
+        }
+        is_end
+    }
+
     /// 強制リセット
     pub fn reset(&mut self) {
         self.stack.clear();
@@ -104,4 +126,28 @@ mod tests {
         assert!(ctx.check_end("EOF")); // exact match
         assert!(!ctx.is_in_heredoc());
     }
+
+    #[test]
+    fn test_heredocs_end_in_order() {
+        let mut ctx = HeredocContext::new();
+        ctx.push("A".to_string(), false);
+        ctx.push("B".to_string(), false);
+        assert!(!ctx.check_end("B"));
+        assert!(ctx.check_end("A"));
+        assert!(ctx.check_end("B"));
+        assert!(!ctx.is_in_heredoc());
+    }
+
+    #[test]
+    fn test_check_end_marker() {
+        let mut ctx = HeredocContext::new();
+        ctx.push("EOT".to_string(), true);
+        assert!(!ctx.check_end_marker("  EOTX;"));
+        assert!(!ctx.check_end_marker("  text EOT"));
+        assert!(ctx.check_end_marker("    EOT, 'next');"));
+
+        ctx.push("EOT".to_string(), false);
+        assert!(!ctx.check_end_marker("  EOT;"));
+        assert!(ctx.check_end_marker("EOT;"));
+    }
 }
@@ -1,12 +1,23 @@
 // crates/core/src/language/mod.rs
 pub mod comment_style;
+/// User-defined languages registered at runtime.
+pub mod definition;
+/// Documentation comment detection (`--docs`).
+pub mod doc_comments;
 pub mod heredoc_utils;
+/// Vim/Emacs modeline based language detection.
+pub mod modeline;
+/// Display names of languages by extension.
+pub mod names;
 pub mod processor_trait;
 /// Language-specific SLOC processor implementations.
 pub mod processors;
 pub mod string_utils;
 
 use comment_style::CommentStyle;
+pub use definition::LanguageDefinition;
+use doc_comments::DocSyntax;
+pub use names::language_name;
 pub use processor_trait::{LineProcessor, LineStats, StatefulProcessor};
 #[allow(clippy::wildcard_imports)]
 use processors::*;
@@ -16,15 +27,45 @@ use alloc::boxed::Box;
 use alloc::string::String;
 use hashbrown::HashMap;
 
+use crate::config::AnalysisConfig;
+
 fn new_box<T: LineProcessor + 'static>(p: T) -> Box<dyn LineProcessor> {
     Box::new(p)
 }
 
+/// 拡張子マッピングを適用した拡張子を返す
+///
+/// マッピングのキーは小文字・先頭ドットなしで登録されている前提で、
+/// 拡張子の大文字小文字は区別しません (`FOO.INC` も `inc=php` に一致)。
+#[must_use]
+pub fn resolve_extension<'a>(extension: &'a str, map: &'a HashMap<String, String>) -> &'a str {
+    if map.is_empty() {
+        return extension;
+    }
+    map.get(extension)
+        .or_else(|| map.get(extension.to_lowercase().as_str()))
+        .map_or(extension, String::as_str)
+}
+
+/// 設定（拡張子マッピング・ユーザー定義言語）を考慮してプロセッサを生成する
+///
+/// ユーザー定義言語は組み込みの言語判定より優先されます。
+#[must_use]
+pub fn processor_for(extension: &str, config: &AnalysisConfig) -> Box<dyn LineProcessor> {
+    let effective_ext = resolve_extension(extension, &config.map_ext);
+
+    if let Some(definition) = config.languages.iter().find(|d| d.matches(effective_ext)) {
+        return new_box(CustomLanguageProcessor::new(definition.clone()));
+    }
+
+    get_processor(extension, &config.map_ext)
+}
+
 /// 拡張子に応じたプロセッサを生成する
 #[must_use]
 pub fn get_processor(extension: &str, map: &HashMap<String, String>) -> Box<dyn LineProcessor> {
     // マッピングを確認 (なければそのまま)
-    let effective_ext = map.get(extension).map_or(extension, String::as_str);
+    let effective_ext = resolve_extension(extension, map);
 
     let style = CommentStyle::from_extension(effective_ext);
     let ext_lower = effective_ext.to_lowercase();
@@ -34,8 +75,16 @@ pub fn get_processor(extension: &str, map: &HashMap<String, String>) -> Box<dyn
         CommentStyle::CStyle => {
             if ext_lower == "swift" {
                 new_box(SwiftProcessor::new())
-            } else if matches!(ext_lower.as_str(), "rs" | "kt" | "kts" | "scala" | "sc") {
-                new_box(NestingCStyleProcessor::new(string_opts))
+            } else if matches!(
+                ext_lower.as_str(),
+                "rs" | "kt" | "kts" | "scala" | "sc" | "odin" | "vv" | "vsh"
+            ) {
+                let docs = if ext_lower == "rs" {
+                    DocSyntax::Rust
+                } else {
+                    DocSyntax::Javadoc
+                };
+                new_box(NestingCStyleProcessor::new(string_opts).with_doc_syntax(docs))
             } else if matches!(
                 ext_lower.as_str(),
                 "js" | "jsx" | "mjs" | "cjs" | "ts" | "tsx" | "mts" | "cts"
@@ -49,12 +98,24 @@ pub fn get_processor(extension: &str, map: &HashMap<String, String>) -> Box<dyn
         CommentStyle::Ruby => new_box(RubyProcessor::default()),
         CommentStyle::Perl => new_box(PerlProcessor::default()),
         CommentStyle::Php => new_box(PhpProcessor::new()),
+        CommentStyle::Hcl => new_box(HclProcessor::new()),
         CommentStyle::PowerShell => new_box(PowerShellProcessor::new()),
         CommentStyle::Lua => new_box(LuaProcessor::new()),
-        CommentStyle::Html => new_box(HtmlProcessor::new()),
+        CommentStyle::Html => {
+            if matches!(
+                ext_lower.as_str(),
+                "html" | "htm" | "xhtml" | "vue" | "svelte"
+            ) {
+                new_box(EmbeddedProcessor::new())
+            } else {
+                new_box(HtmlProcessor::new())
+            }
+        }
+        CommentStyle::Razor => new_box(RazorProcessor::new()),
         CommentStyle::Sql => new_box(SqlProcessor::new()),
         CommentStyle::Haskell => new_box(HaskellProcessor::new()),
         CommentStyle::Julia => new_box(JuliaProcessor::new()),
+        CommentStyle::Nim => new_box(NimProcessor::new()),
         CommentStyle::OCaml => new_box(OCamlProcessor::new()),
         CommentStyle::DLang => new_box(DLangProcessor::new()),
         CommentStyle::Matlab => new_box(MatlabProcessor::new()),
@@ -71,8 +132,11 @@ pub fn get_processor(extension: &str, map: &HashMap<String, String>) -> Box<dyn
         CommentStyle::Lisp => new_box(SimplePrefixProcessor::lisp()),
         CommentStyle::Assembly => new_box(SimplePrefixProcessor::assembly()),
         CommentStyle::Fortran => new_box(FortranProcessor::new()),
-        CommentStyle::Batch => new_box(SimplePrefixProcessor::batch()),
+        CommentStyle::Batch => new_box(BatchProcessor::new()),
         CommentStyle::VisualBasic => new_box(SimplePrefixProcessor::visual_basic()),
+        CommentStyle::Cobol => new_box(CobolProcessor::new()),
+        CommentStyle::Abap => new_box(AbapProcessor::new()),
+        CommentStyle::Rpg => new_box(RpgProcessor::new()),
         CommentStyle::None => new_box(NoCommentProcessor),
     }
 }
@@ -84,4 +148,98 @@ impl LineProcessor for NoCommentProcessor {
     fn process_line(&mut self, line: &str) -> usize {
         usize::from(!line.trim().is_empty())
     }
+
+    fn is_neutral(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+
+    fn map(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| ((*k).to_string(), (*v).to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_resolve_extension_is_case_insensitive() {
+        let m = map(&[("inc", "php"), ("txt", "sql")]);
+        assert_eq!(resolve_extension("inc", &m), "php");
+        assert_eq!(resolve_extension("INC", &m), "php");
+        assert_eq!(resolve_extension("txt", &m), "sql");
+        assert_eq!(resolve_extension("rs", &m), "rs");
+    }
+
+    #[test]
+    fn test_mapped_extension_selects_processor() {
+        let m = map(&[("inc", "php"), ("txt", "sql")]);
+
+        let mut php = get_processor("inc", &m);
+        assert_eq!(php.process_line("# comment"), 0);
+        assert_eq!(php.process_line("echo 1;"), 1);
+
+        let mut sql = get_processor("TXT", &m);
+        assert_eq!(sql.process_line("-- comment"), 0);
+        assert_eq!(sql.process_line("SELECT 1;"), 1);
+
+        let mut plain = get_processor("txt", &HashMap::new());
+        assert_eq!(plain.process_line("-- comment"), 1);
+    }
+
+    #[test]
+    fn test_newer_languages() {
+        let none = HashMap::new();
+        let mut nim = get_processor("nim", &none);
+        assert_eq!(nim.process_line("#[ block"), 0);
+        assert_eq!(nim.process_line("]#"), 0);
+
+        let mut crystal = get_processor("cr", &none);
+        assert_eq!(crystal.process_line("puts \"#{x}\" # comment"), 1);
+        assert_eq!(crystal.process_line("# comment"), 0);
+
+        for ext in ["odin", "vv"] {
+            let mut p = get_processor(ext, &none);
+            assert_eq!(p.process_line("/* outer /* inner */"), 0);
+            assert_eq!(p.process_line("still comment */"), 0);
+            assert_eq!(p.process_line("x := `//` // comment"), 1);
+        }
+
+        let mut gleam = get_processor("gleam", &none);
+        assert_eq!(gleam.process_line("/// doc"), 0);
+        assert_eq!(gleam.process_line("let s = \"// text\""), 1);
+    }
+
+    #[test]
+    fn test_terraform_and_puppet() {
+        let none = HashMap::new();
+        let mut tf = get_processor("tf", &none);
+        assert_eq!(tf.process_line("// comment"), 0);
+        assert_eq!(tf.process_line("/* block */"), 0);
+        assert_eq!(tf.process_line("locals {"), 1);
+
+        let mut puppet = get_processor("pp", &none);
+        assert_eq!(puppet.process_line("# comment"), 0);
+        assert_eq!(puppet.process_line("(* not pascal *)"), 1);
+    }
+
+    #[test]
+    fn test_fixed_format_languages() {
+        let none = HashMap::new();
+        let mut cobol = get_processor("CBL", &none);
+        assert_eq!(cobol.process_line("000100* comment"), 0);
+        assert_eq!(cobol.process_line("000200 PROCEDURE DIVISION."), 1);
+
+        let mut abap = get_processor("abap", &none);
+        assert_eq!(abap.process_line("\" comment"), 0);
+        assert_eq!(abap.process_line("WRITE 'x'."), 1);
+
+        let mut rpg = get_processor("rpgle", &none);
+        assert_eq!(rpg.process_line("     C* comment"), 0);
+        assert_eq!(rpg.process_line("     C                   RETURN"), 1);
+    }
 }
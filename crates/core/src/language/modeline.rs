@@ -0,0 +1,173 @@
+//! Vim モードライン / Emacs `-*- mode -*-` ヘッダーによる言語判定
+//!
+//! 拡張子のないファイルや、拡張子と中身の言語が一致しないファイルのために、
+//! 先頭・末尾の数行に書かれたエディタ向けの言語指定を読み取ります。
+//!
+//! - Vim: `# vim: ft=python`, `/* vim: set filetype=c : */`
+//! - Emacs: `# -*- mode: ruby -*-`, `;; -*- lisp -*-`
+
+use alloc::string::{String, ToString};
+
+/// Number of lines scanned at the start and end of the input (Vim's default `modelines`).
+const SCAN_LINES: usize = 5;
+
+/// Detects a language from a Vim modeline or Emacs mode header.
+///
+/// Only the first and last [`SCAN_LINES`] lines are inspected. The detected
+/// name is normalized to an extension understood by the built-in processors
+/// (e.g. `python` → `py`); unknown names are returned lowercased so they can
+/// still match a user-defined language or an extension mapping.
+#[must_use]
+pub fn detect_modeline(input: &[u8]) -> Option<String> {
+    let lines: alloc::vec::Vec<&[u8]> = input.split(|&b| b == b'\n').collect();
+    let head = lines.iter().take(SCAN_LINES);
+    let tail = lines
+        .iter()
+        .skip(SCAN_LINES.max(lines.len().saturating_sub(SCAN_LINES)));
+
+    head.chain(tail).find_map(|line| {
+        let line = super::string_utils::from_utf8_lossy(line);
+        parse_emacs(&line)
+            .or_else(|| parse_vim(&line))
+            .map(normalize_language)
+    })
+}
+
+/// Parses `-*- mode: ruby -*-`, `-*- ruby -*-` and `-*- mode: ruby; coding: utf-8 -*-`.
+fn parse_emacs(line: &str) -> Option<&str> {
+    let start = line.find("-*-")? + 3;
+    let end = start + line[start..].find("-*-")?;
+    let body = line[start..end].trim();
+
+    if !body.contains(':') {
+        return Some(body).filter(|name| !name.is_empty());
+    }
+
+    body.split(';').find_map(|var| {
+        let (key, value) = var.split_once(':')?;
+        key.trim()
+            .eq_ignore_ascii_case("mode")
+            .then(|| value.trim())
+            .filter(|name| !name.is_empty())
+    })
+}
+
+/// Parses `vim: ft=python`, `vi: set filetype=c :` and `vim600: syntax=sh`.
+fn parse_vim(line: &str) -> Option<&str> {
+    let rest = ["vim", "vi", "ex"].iter().find_map(|marker| {
+        line.match_indices(marker).find_map(|(idx, _)| {
+            // Like Vim, the marker must follow whitespace ("nvim:" doesn't
+            // count); only "vim:"/"vi:" may also start the line.
+            let word_start = match line[..idx].chars().next_back() {
+                Some(prev) => prev.is_whitespace(),
+                None => *marker != "ex",
+            };
+            if !word_start {
+                return None;
+            }
+            line[idx + marker.len()..]
+                .trim_start_matches(|c: char| c.is_ascii_digit() || matches!(c, '<' | '=' | '>'))
+                .strip_prefix(':')
+        })
+    })?;
+
+    rest.split(|c: char| c == ':' || c.is_whitespace())
+        .find_map(|option| {
+            let (key, value) = option.split_once('=')?;
+            matches!(key, "ft" | "filetype" | "syn" | "syntax").then_some(value)
+        })
+        .filter(|name| !name.is_empty())
+}
+
+/// Maps Vim filetypes and Emacs mode names to the extension used for processor lookup.
+fn normalize_language(name: &str) -> String {
+    let name = name.trim().to_lowercase();
+    let name = name.strip_suffix("-mode").unwrap_or(&name);
+    let ext = match name {
+        "python" | "python3" => "py",
+        "ruby" => "rb",
+        "perl" | "cperl" => "pl",
+        "javascript" | "js2" => "js",
+        "typescript" => "ts",
+        "rust" => "rs",
+        "c++" => "cpp",
+        "csharp" => "cs",
+        "golang" => "go",
+        "kotlin" => "kt",
+        "shell-script" | "zsh" | "bash" => "sh",
+        "make" | "makefile-gmake" => "makefile",
+        "haskell" => "hs",
+        "julia" => "jl",
+        "ocaml" | "tuareg" => "ml",
+        "fsharp" => "fs",
+        "emacs-lisp" | "elisp" | "lisp-interaction" => "el",
+        "common-lisp" => "lisp",
+        "clojure" => "clj",
+        "scheme" => "scm",
+        "racket" => "rkt",
+        "erlang" => "erl",
+        "latex" | "plaintex" => "tex",
+        "fortran" | "f90" => "f90",
+        "matlab" | "octave" => "mat",
+        "dosbatch" | "bat" => "bat",
+        "nasm" => "asm",
+        "vbnet" => "vb",
+        "powershell" | "ps1" => "ps1",
+        "elixir" => "ex",
+        "markdown" => "md",
+        other => other,
+    };
+    ext.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn detect(input: &str) -> Option<String> {
+        detect_modeline(input.as_bytes())
+    }
+
+    #[test]
+    fn test_vim_modelines() {
+        assert_eq!(detect("# vim: ft=python\nx = 1\n").as_deref(), Some("py"));
+        assert_eq!(
+            detect("/* vim: set filetype=c : */\nint x;\n").as_deref(),
+            Some("c")
+        );
+        assert_eq!(detect("# vim600: syntax=sh\n").as_deref(), Some("sh"));
+        assert_eq!(detect("# nvim: ft=python\n"), None);
+    }
+
+    #[test]
+    fn test_emacs_headers() {
+        assert_eq!(
+            detect("# -*- mode: ruby -*-\nputs 1\n").as_deref(),
+            Some("rb")
+        );
+        assert_eq!(detect(";; -*- lisp -*-\n").as_deref(), Some("lisp"));
+        assert_eq!(
+            detect("#!/bin/sh\n# -*- mode: Shell-Script; coding: utf-8 -*-\n").as_deref(),
+            Some("sh")
+        );
+        assert_eq!(detect("# -*- coding: utf-8 -*-\n"), None);
+    }
+
+    #[test]
+    fn test_scans_only_head_and_tail() {
+        let mut body = String::new();
+        for _ in 0..20 {
+            body.push_str("line\n");
+        }
+        let middle = alloc::format!("{body}# vim: ft=python\n{body}");
+        assert_eq!(detect(&middle), None);
+
+        let tail = alloc::format!("{body}-- vim: ft=sql");
+        assert_eq!(detect(&tail).as_deref(), Some("sql"));
+    }
+
+    #[test]
+    fn test_unknown_names_pass_through() {
+        assert_eq!(detect("# vim: ft=MyLang\n").as_deref(), Some("mylang"));
+    }
+}
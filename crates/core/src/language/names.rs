@@ -0,0 +1,116 @@
+//! 拡張子から表示用の言語名への対応表
+//!
+//! 言語別の集計 (`--by language`) で使う人間向けの名前です。
+//! SLOC のプロセッサ選択 ([`super::comment_style::CommentStyle`]) とは独立しています。
+
+/// Returns the display name of the language for `extension`.
+///
+/// The lookup is case-insensitive and expects the extension without the
+/// leading dot. Returns `None` for extensions that aren't known.
+#[must_use]
+pub fn language_name(extension: &str) -> Option<&'static str> {
+    let ext = extension.to_ascii_lowercase();
+    let name = match ext.as_str() {
+        "c" | "h" => "C",
+        "cpp" | "cc" | "cxx" | "c++" | "hpp" | "hh" | "hxx" | "h++" => "C++",
+        "cs" => "C#",
+        "java" => "Java",
+        "js" | "mjs" | "cjs" | "jsx" => "JavaScript",
+        "ts" | "tsx" | "mts" | "cts" => "TypeScript",
+        "rs" => "Rust",
+        "go" => "Go",
+        "swift" => "Swift",
+        "kt" | "kts" => "Kotlin",
+        "scala" | "sc" => "Scala",
+        "dart" => "Dart",
+        "v" | "sv" | "svh" => "Verilog",
+        "zig" => "Zig",
+        "m" | "mm" => "Objective-C",
+        "groovy" | "gradle" => "Groovy",
+        "css" => "CSS",
+        "scss" | "sass" => "Sass",
+        "less" => "Less",
+        "json" | "jsonc" => "JSON",
+        "proto" => "Protocol Buffers",
+        "thrift" => "Thrift",
+        "sol" => "Solidity",
+        "d" => "D",
+        "php" => "PHP",
+        "py" | "pyw" | "pyi" => "Python",
+        "rb" | "rake" | "gemspec" => "Ruby",
+        "cr" => "Crystal",
+        "pl" | "pm" | "perl" => "Perl",
+        "sh" | "bash" | "zsh" | "fish" => "Shell",
+        "yml" | "yaml" => "YAML",
+        "toml" => "TOML",
+        "dockerfile" => "Dockerfile",
+        "makefile" | "mk" => "Makefile",
+        "cmake" => "CMake",
+        "nim" | "nims" | "nimble" => "Nim",
+        "vv" | "vsh" => "V",
+        "odin" => "Odin",
+        "gleam" => "Gleam",
+        "ex" | "exs" => "Elixir",
+        "coffee" => "CoffeeScript",
+        "tcl" => "Tcl",
+        "awk" => "Awk",
+        "tf" | "tfvars" | "hcl" => "Terraform",
+        "pp" => "Puppet",
+        "r" | "rmd" => "R",
+        "ini" | "conf" | "cfg" | "properties" => "INI",
+        "graphql" | "gql" => "GraphQL",
+        "nix" => "Nix",
+        "ps1" | "psm1" | "psd1" => "PowerShell",
+        "lua" => "Lua",
+        "html" | "htm" | "xhtml" => "HTML",
+        "xml" | "xsl" | "xslt" | "xsd" => "XML",
+        "svg" => "SVG",
+        "vue" => "Vue",
+        "svelte" => "Svelte",
+        "cshtml" | "razor" => "Razor",
+        "sql" => "SQL",
+        "hs" | "lhs" => "Haskell",
+        "elm" => "Elm",
+        "purs" => "PureScript",
+        "jl" => "Julia",
+        "ml" | "mli" => "OCaml",
+        "fs" | "fsi" | "fsx" | "fsscript" => "F#",
+        "pas" | "dpr" | "dpk" => "Pascal",
+        "sml" | "sig" | "fun" => "Standard ML",
+        "lisp" | "lsp" | "cl" => "Common Lisp",
+        "el" => "Emacs Lisp",
+        "clj" | "cljs" | "cljc" | "edn" => "Clojure",
+        "scm" | "ss" => "Scheme",
+        "rkt" => "Racket",
+        "erl" | "hrl" => "Erlang",
+        "tex" | "sty" | "ltx" => "TeX",
+        "bib" => "BibTeX",
+        "f" | "f90" | "f95" | "f03" | "f08" | "for" | "ftn" => "Fortran",
+        "mat" | "mlx" | "oct" => "MATLAB",
+        "bat" | "cmd" => "Batch",
+        "asm" | "nasm" | "masm" | "inc" | "s" => "Assembly",
+        "vhd" | "vhdl" => "VHDL",
+        "vb" | "vbs" | "bas" | "cls" | "frm" => "Visual Basic",
+        "cbl" | "cob" | "cpy" => "COBOL",
+        "abap" => "ABAP",
+        "rpg" | "rpgle" | "sqlrpgle" | "rpgleinc" => "RPG",
+        "md" | "markdown" => "Markdown",
+        "txt" => "Text",
+        _ => return None,
+    };
+    Some(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_language_name() {
+        assert_eq!(language_name("rs"), Some("Rust"));
+        assert_eq!(language_name("TSX"), Some("TypeScript"));
+        assert_eq!(language_name("hpp"), Some("C++"));
+        assert_eq!(language_name("unknown"), None);
+        assert_eq!(language_name(""), None);
+    }
+}
@@ -21,7 +21,9 @@
 //! }
 //! ```
 
+use crate::stats::EmbeddedCount;
 use alloc::boxed::Box;
+use alloc::vec::Vec;
 
 /// 行統計情報
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
@@ -32,6 +34,8 @@ pub struct LineStats {
     pub chars: usize,
     /// 単語数
     pub words: usize,
+    /// ドキュメントコメント行かどうか (0 or 1)
+    pub doc: usize,
 }
 
 /// SLOC行処理トレイト
@@ -114,7 +118,12 @@ pub trait LineProcessor: Send {
             }
         }
 
-        LineStats { sloc, chars, words }
+        LineStats {
+            sloc,
+            chars,
+            words,
+            doc: usize::from(self.is_doc_line()),
+        }
     }
 
     /// 処理状態をリセット
@@ -124,6 +133,14 @@ pub trait LineProcessor: Send {
         // Default: no-op. Override if needed.
     }
 
+    /// 直前に処理した行がドキュメントコメント（`///`、`/** */`、docstring など）
+    /// だけの行だったかどうかを返す
+    ///
+    /// ドキュメントコメントを区別しないプロセッサは`false`を返します。
+    fn is_doc_line(&self) -> bool {
+        false
+    }
+
     /// 現在ブロックコメント内かどうかを返す（デバッグ用）
     ///
     /// すべてのプロセッサがブロックコメントをサポートしているわけではないため、
@@ -131,6 +148,22 @@ pub trait LineProcessor: Send {
     fn is_in_block_comment(&self) -> bool {
         false
     }
+
+    /// 初期状態と同じく、複数行にまたがる構文の途中にいないかどうかを返す
+    ///
+    /// `true` なら次の行以降を新しいプロセッサで数えても結果が変わらないため、
+    /// 大きなファイルを行単位のチャンクに分けて並列に数えられます。
+    /// 判定できないプロセッサは分割させないよう、デフォルトでは`false`を返します。
+    fn is_neutral(&self) -> bool {
+        false
+    }
+
+    /// 埋め込み言語 (`<script>` / `<style>` など) ごとの行数を返す
+    ///
+    /// 他の言語を埋め込めないプロセッサは空を返します。
+    fn embedded(&self) -> Vec<EmbeddedCount> {
+        Vec::new()
+    }
 }
 
 /// Stateful processor trait for processors with persistent state across lines.
@@ -225,9 +258,21 @@ impl LineProcessor for Box<dyn LineProcessor> {
         (**self).reset();
     }
 
+    fn is_doc_line(&self) -> bool {
+        (**self).is_doc_line()
+    }
+
     fn is_in_block_comment(&self) -> bool {
         (**self).is_in_block_comment()
     }
+
+    fn is_neutral(&self) -> bool {
+        (**self).is_neutral()
+    }
+
+    fn embedded(&self) -> Vec<EmbeddedCount> {
+        (**self).embedded()
+    }
 }
 
 #[cfg(test)]
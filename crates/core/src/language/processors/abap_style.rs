@@ -0,0 +1,64 @@
+// crates/core/src/language/processors/abap_style.rs
+//! ABAP言語のコメント処理
+//!
+//! ABAP固有の対応:
+//! - 全行コメント: カラム1 (行の1文字目) が `*` の行
+//! - 行末コメント: `"` 以降 (トリム後 `"` で始まる行はコメント行)
+//!
+//! ABAP の文字列は `'...'`, `` `...` ``, `|...|` で、`"` を含みません。
+//! そのため `"` の前にコードがあるかだけで判定できます。
+
+use crate::language::processor_trait::LineProcessor;
+
+/// ABAPプロセッサ
+/// ABAP SLOC processor.
+#[derive(Default, Clone, Debug)]
+pub struct AbapProcessor;
+
+impl LineProcessor for AbapProcessor {
+    fn process_line(&mut self, line: &str) -> usize {
+        self.process(line)
+    }
+
+    fn is_in_block_comment(&self) -> bool {
+        false
+    }
+}
+
+impl AbapProcessor {
+    /// 新しいABAPプロセッサを作成
+    #[must_use]
+    pub const fn new() -> Self {
+        Self
+    }
+
+    /// 行を処理し、SLOCカウント (0 or 1) を返す
+    #[must_use]
+    pub fn process(&self, line: &str) -> usize {
+        // カラム1の * のみが全行コメント (インデントされた * は演算子など)
+        if line.starts_with('*') {
+            return 0;
+        }
+        let trimmed = line.trim();
+        usize::from(!trimmed.is_empty() && !trimmed.starts_with('"'))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_abap_comments() {
+        let p = AbapProcessor::new();
+        assert_eq!(p.process("* full line comment"), 0);
+        assert_eq!(
+            p.process("*&---------------------------------------------*"),
+            0
+        );
+        assert_eq!(p.process("  \" quoted comment"), 0);
+        assert_eq!(p.process("DATA lv_count TYPE i. \" trailing comment"), 1);
+        assert_eq!(p.process("  lv_total = lv_a * lv_b."), 1);
+        assert_eq!(p.process("   "), 0);
+    }
+}
@@ -6,40 +6,63 @@
 //! - `::` で始まる行 (ラベルの特殊用法としてのコメント)
 //! - `@REM` で始まる行
 
-/// Batch スタイル (REM と ::) の処理
+use crate::language::processor_trait::LineProcessor;
+
+/// Batch プロセッサ
 ///
 /// Windows バッチファイルのコメント:
-/// - `REM` (大文字小文字不問) で始まる行
+/// - `REM` (大文字小文字不問) だけの行、または `REM` の後に空白が続く行
+/// - `@REM`, `@ REM` (エコー抑止付き)
 /// - `::` で始まる行 (ラベルの特殊用法としてのコメント)
-#[cfg(test)]
-fn process_batch_style(line: &str, count: &mut usize) {
-    let trimmed = line.trim();
-
-    // REM コメント (大文字小文字不問)
-    // "REM" の後にスペースか行末が必要
-    let upper = trimmed.to_uppercase();
-    if upper == "REM" || upper.starts_with("REM ") || upper.starts_with("REM\t") {
-        return;
+///
+/// `::` は行頭にある場合のみコメントで、`if ... (` ～ `)` の括弧ブロック内で
+/// インデントされていても同様に扱います。`echo a & REM x` のように
+/// コマンドの後に続くものはコードの行です。`:label` はラベルなのでコードです。
+/// Batch SLOC processor.
+#[derive(Debug, Default, Clone)]
+pub struct BatchProcessor;
+
+impl LineProcessor for BatchProcessor {
+    fn process_line(&mut self, line: &str) -> usize {
+        self.process(line)
     }
 
-    // :: コメント (ラベルの特殊用法)
-    if trimmed.starts_with("::") {
-        return;
+    fn is_in_block_comment(&self) -> bool {
+        false
+    }
+
+    fn is_neutral(&self) -> bool {
+        true
+    }
+}
+
+impl BatchProcessor {
+    /// 新しいBatchプロセッサを作成
+    #[must_use]
+    pub const fn new() -> Self {
+        Self
     }
 
-    // @ プレフィックス付きの REM
-    if let Some(stripped) = trimmed.strip_prefix('@') {
-        let after_at = stripped.trim_start();
-        let upper_after = after_at.to_uppercase();
-        if upper_after == "REM"
-            || upper_after.starts_with("REM ")
-            || upper_after.starts_with("REM\t")
-        {
-            return;
+    /// 行を処理し、SLOCカウント (0 or 1) を返す
+    #[must_use]
+    pub fn process(&self, line: &str) -> usize {
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with("::") {
+            return 0;
         }
+
+        // @ プレフィックス付きの REM
+        let command = trimmed.strip_prefix('@').map_or(trimmed, str::trim_start);
+        usize::from(!is_rem(command))
     }
+}
 
-    *count += 1;
+/// `REM` (大文字小文字不問) の後が行末か空白か
+fn is_rem(command: &str) -> bool {
+    command
+        .get(..3)
+        .is_some_and(|head| head.eq_ignore_ascii_case("rem"))
+        && command[3..].chars().next().is_none_or(char::is_whitespace)
 }
 
 #[cfg(test)]
@@ -48,58 +71,69 @@ mod tests {
 
     #[test]
     fn test_rem_comment() {
-        let mut count = 0;
-        process_batch_style("REM This is a comment", &mut count);
-        assert_eq!(count, 0);
+        assert_eq!(BatchProcessor::new().process("REM This is a comment"), 0);
     }
 
     #[test]
     fn test_rem_lowercase() {
-        let mut count = 0;
-        process_batch_style("rem lowercase comment", &mut count);
-        assert_eq!(count, 0);
+        assert_eq!(BatchProcessor::new().process("rem lowercase comment"), 0);
     }
 
     #[test]
     fn test_double_colon_comment() {
-        let mut count = 0;
-        process_batch_style(":: This is a label comment", &mut count);
-        assert_eq!(count, 0);
+        assert_eq!(
+            BatchProcessor::new().process(":: This is a label comment"),
+            0
+        );
     }
 
     #[test]
     fn test_at_rem() {
-        let mut count = 0;
-        process_batch_style("@REM Suppress output and comment", &mut count);
-        assert_eq!(count, 0);
+        assert_eq!(
+            BatchProcessor::new().process("@REM Suppress output and comment"),
+            0
+        );
     }
 
     #[test]
     fn test_code_line() {
-        let mut count = 0;
-        process_batch_style("echo Hello", &mut count);
-        assert_eq!(count, 1);
+        assert_eq!(BatchProcessor::new().process("echo Hello"), 1);
     }
 
     #[test]
     fn test_rem_only() {
-        let mut count = 0;
-        process_batch_style("REM", &mut count);
-        assert_eq!(count, 0);
+        assert_eq!(BatchProcessor::new().process("REM"), 0);
     }
 
     #[test]
     fn test_not_rem_if_no_space() {
         // "REMARK" は REM コメントではない
-        let mut count = 0;
-        process_batch_style("echo REMARK", &mut count);
-        assert_eq!(count, 1);
+        assert_eq!(BatchProcessor::new().process("echo REMARK"), 1);
     }
 
     #[test]
     fn test_rem_with_tab() {
-        let mut count = 0;
-        process_batch_style("REM\tcomment with tab", &mut count);
-        assert_eq!(count, 0);
+        assert_eq!(BatchProcessor::new().process("REM\tcomment with tab"), 0);
+    }
+
+    #[test]
+    fn test_rem_variants() {
+        let p = BatchProcessor::new();
+        assert_eq!(p.process("@rem"), 0);
+        assert_eq!(p.process("@ REM spaced"), 0);
+        assert_eq!(p.process("REMARK"), 1);
+        assert_eq!(p.process("echo done & REM trailing"), 1);
+        assert_eq!(p.process("echo done & :: trailing"), 1);
+    }
+
+    #[test]
+    fn test_double_colon_in_parenthesized_block() {
+        let p = BatchProcessor::new();
+        assert_eq!(p.process("if exist out ("), 1);
+        assert_eq!(p.process("    :: comment inside the block"), 0);
+        assert_eq!(p.process("    rem another"), 0);
+        assert_eq!(p.process("    echo a::b"), 1);
+        assert_eq!(p.process("    :label"), 1);
+        assert_eq!(p.process(")"), 1);
     }
 }
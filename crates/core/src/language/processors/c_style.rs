@@ -50,6 +50,7 @@
 //! assert_eq!(proc.process_line("int y = 2; // inline"), 1);
 //! ```
 
+use crate::language::doc_comments::{DocComments, DocSyntax};
 use crate::language::processor_trait::LineProcessor;
 use crate::language::string_utils::{
     SkipResult, StringSkipOptions, try_skip_prefixed_string, try_skip_quoted_string, try_skip_regex,
@@ -63,6 +64,7 @@ pub struct CStyleProcessor {
     in_string: bool,
     quote_char: Option<u8>,
     in_word: bool,
+    docs: DocComments,
 }
 
 impl LineProcessor for CStyleProcessor {
@@ -83,11 +85,16 @@ impl LineProcessor for CStyleProcessor {
         self.in_block_comment
     }
 
+    fn is_neutral(&self) -> bool {
+        !self.in_block_comment && !self.in_string && !self.in_word
+    }
+
     fn reset(&mut self) {
         self.in_block_comment = false;
         self.in_string = false;
         self.quote_char = None;
         self.in_word = false;
+        self.docs.reset();
     }
 }
 
@@ -103,6 +110,7 @@ impl CStyleProcessor {
             in_string: false,
             quote_char: None,
             in_word: false,
+            docs: DocComments::new(DocSyntax::Javadoc),
         }
     }
 
@@ -118,6 +126,7 @@ impl CStyleProcessor {
         count_words: bool,
         count_newlines_in_chars: bool,
     ) -> LineStats {
+        let was_in_block = self.is_in_block_comment();
         let bytes = line.as_bytes();
         let mut i = 0;
         let mut has_code = false;
@@ -318,10 +327,12 @@ impl CStyleProcessor {
             i += c.len_utf8();
         }
 
+        let in_block = self.is_in_block_comment();
         LineStats {
             sloc: usize::from(has_code),
             chars,
             words,
+            doc: usize::from(self.docs.observe(line, has_code, was_in_block, in_block)),
         }
     }
 
@@ -373,6 +384,7 @@ pub struct NestingCStyleProcessor {
     in_string: bool,
     quote_char: Option<u8>,
     in_word: bool,
+    docs: DocComments,
 }
 
 impl LineProcessor for NestingCStyleProcessor {
@@ -384,12 +396,17 @@ impl LineProcessor for NestingCStyleProcessor {
         self.in_block_comment || self.block_comment_depth > 0
     }
 
+    fn is_neutral(&self) -> bool {
+        !self.is_in_block_comment() && !self.in_string && !self.in_word
+    }
+
     fn reset(&mut self) {
         self.in_block_comment = false;
         self.block_comment_depth = 0;
         self.in_string = false;
         self.quote_char = None;
         self.in_word = false;
+        self.docs.reset();
     }
 
     fn process_line_stats(
@@ -402,6 +419,7 @@ impl LineProcessor for NestingCStyleProcessor {
         // Actually, let's just use the specialized logic that handles nesting.
         // For line stats, we can reuse CStyleProcessor's loop but with depth.
 
+        let was_in_block = self.is_in_block_comment();
         let bytes = line.as_bytes();
         let mut i = 0;
         let mut has_code = false;
@@ -618,10 +636,12 @@ impl LineProcessor for NestingCStyleProcessor {
             i += c.len_utf8();
         }
 
+        let in_block = self.is_in_block_comment();
         LineStats {
             sloc: usize::from(has_code),
             chars,
             words,
+            doc: usize::from(self.docs.observe(line, has_code, was_in_block, in_block)),
         }
     }
 }
@@ -637,9 +657,17 @@ impl NestingCStyleProcessor {
             in_string: false,
             quote_char: None,
             in_word: false,
+            docs: DocComments::new(DocSyntax::Javadoc),
         }
     }
 
+    /// Uses the doc comment markers of `syntax` (`/** */` by default).
+    #[must_use]
+    pub const fn with_doc_syntax(mut self, syntax: DocSyntax) -> Self {
+        self.docs = DocComments::new(syntax);
+        self
+    }
+
     /// Processes a line and returns the SLOC count.
     pub fn process(&mut self, line: &str) -> usize {
         self.process_line_stats(line, false, false).sloc
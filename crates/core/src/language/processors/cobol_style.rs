@@ -0,0 +1,105 @@
+// crates/core/src/language/processors/cobol_style.rs
+//! COBOL言語のコメント処理
+//!
+//! COBOL固有の対応:
+//! - 固定形式: カラム7 (標識領域) が `*` または `/` の行
+//! - `*>` で始まるコメント (COBOL 2002 / 自由形式)
+//! - カラム1-6 (一連番号領域) とカラム73以降 (見出し領域) はコードとみなさない
+//!
+//! カラム1-6 が数字と空白だけの行を固定形式として扱い、
+//! それ以外は自由形式としてトリム後の内容で判定します。
+
+use crate::language::processor_trait::LineProcessor;
+
+/// 固定形式の標識領域の位置 (0始まり)
+const INDICATOR_COLUMN: usize = 6;
+/// 固定形式のプログラム領域の終わり (カラム72)
+const PROGRAM_AREA_END: usize = 72;
+
+/// COBOLプロセッサ
+/// COBOL SLOC processor.
+#[derive(Default, Clone, Debug)]
+pub struct CobolProcessor;
+
+impl LineProcessor for CobolProcessor {
+    fn process_line(&mut self, line: &str) -> usize {
+        self.process(line)
+    }
+
+    fn is_in_block_comment(&self) -> bool {
+        false
+    }
+}
+
+impl CobolProcessor {
+    /// 新しいCOBOLプロセッサを作成
+    #[must_use]
+    pub const fn new() -> Self {
+        Self
+    }
+
+    /// 行を処理し、SLOCカウント (0 or 1) を返す
+    #[must_use]
+    pub fn process(&self, line: &str) -> usize {
+        let is_fixed = line
+            .chars()
+            .take(INDICATOR_COLUMN)
+            .all(|c| c.is_ascii_digit() || c == ' ');
+
+        let code = if is_fixed {
+            if matches!(line.chars().nth(INDICATOR_COLUMN), Some('*' | '/')) {
+                return 0;
+            }
+            let start = line
+                .char_indices()
+                .nth(INDICATOR_COLUMN + 1)
+                .map_or(line.len(), |(i, _)| i);
+            let end = line
+                .char_indices()
+                .nth(PROGRAM_AREA_END)
+                .map_or(line.len(), |(i, _)| i);
+            &line[start..end]
+        } else {
+            line
+        };
+
+        let trimmed = code.trim();
+        usize::from(!trimmed.is_empty() && !trimmed.starts_with("*>"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cobol_fixed_format() {
+        let p = CobolProcessor::new();
+        assert_eq!(p.process("000100* COMMENT LINE"), 0);
+        assert_eq!(p.process("      / PAGE EJECT"), 0);
+        assert_eq!(p.process("000200 IDENTIFICATION DIVISION."), 1);
+        assert_eq!(
+            p.process("000300     MOVE 1 TO WS-COUNT.                                   PROG0001"),
+            1
+        );
+        assert_eq!(p.process("000400"), 0);
+        assert_eq!(
+            p.process(
+                "000500                                                                  PROG0001"
+            ),
+            0
+        );
+        assert_eq!(p.process("      *> inline style comment"), 0);
+        // カラム7より前の * はコメントではない
+        assert_eq!(p.process("     * not an indicator"), 1);
+    }
+
+    #[test]
+    fn test_cobol_free_format() {
+        let p = CobolProcessor::new();
+        assert_eq!(p.process("*> free format comment"), 0);
+        assert_eq!(p.process("STOP RUN."), 1);
+        assert_eq!(p.process("   DISPLAY 'HELLO'. *> trailing"), 1);
+        assert_eq!(p.process(""), 0);
+    }
+}
@@ -0,0 +1,208 @@
+// crates/core/src/language/processors/custom_style.rs
+//! ユーザー定義言語のコメント処理
+//!
+//! [`LanguageDefinition`] に記述された構文をそのまま解釈する汎用プロセッサ。
+//!
+//! - 行コメント: 任意個のプレフィックス
+//! - ブロックコメント: 任意個の開始/終了ペア (ネスト指定可)
+//! - 文字列: 任意個の区切り文字 (`\` エスケープ対応、複数行可)
+
+use alloc::string::String;
+
+use crate::language::definition::LanguageDefinition;
+use crate::language::processor_trait::LineProcessor;
+
+/// SLOC processor driven by a runtime [`LanguageDefinition`].
+#[derive(Debug)]
+pub struct CustomLanguageProcessor {
+    definition: LanguageDefinition,
+    /// Index into `block_comments` of the currently open block comment.
+    open_block: Option<usize>,
+    block_depth: usize,
+    /// Index into `string_delimiters` of the currently open string.
+    open_string: Option<usize>,
+}
+
+impl LineProcessor for CustomLanguageProcessor {
+    fn process_line(&mut self, line: &str) -> usize {
+        self.process(line)
+    }
+
+    fn reset(&mut self) {
+        self.open_block = None;
+        self.block_depth = 0;
+        self.open_string = None;
+    }
+
+    fn is_in_block_comment(&self) -> bool {
+        self.open_block.is_some()
+    }
+}
+
+impl CustomLanguageProcessor {
+    /// Creates a processor for the given definition.
+    #[must_use]
+    pub const fn new(definition: LanguageDefinition) -> Self {
+        Self {
+            definition,
+            open_block: None,
+            block_depth: 0,
+            open_string: None,
+        }
+    }
+
+    /// Processes a line and returns the SLOC count.
+    pub fn process(&mut self, line: &str) -> usize {
+        let bytes = line.as_bytes();
+        let mut i = 0;
+        let mut has_code = false;
+
+        while i < bytes.len() {
+            let rest = &bytes[i..];
+
+            if let Some(idx) = self.open_block {
+                let (start, end) = &self.definition.block_comments[idx];
+                if self.definition.nested_comments && rest.starts_with(start.as_bytes()) {
+                    self.block_depth += 1;
+                    i += start.len();
+                } else if rest.starts_with(end.as_bytes()) {
+                    self.block_depth -= 1;
+                    if self.block_depth == 0 {
+                        self.open_block = None;
+                    }
+                    i += end.len();
+                } else {
+                    i += 1;
+                }
+                continue;
+            }
+
+            if let Some(idx) = self.open_string {
+                has_code = true;
+                let delimiter = &self.definition.string_delimiters[idx];
+                if rest[0] == b'\\' {
+                    i += 2;
+                } else if rest.starts_with(delimiter.as_bytes()) {
+                    self.open_string = None;
+                    i += delimiter.len();
+                } else {
+                    i += 1;
+                }
+                continue;
+            }
+
+            if rest[0].is_ascii_whitespace() {
+                i += 1;
+                continue;
+            }
+
+            if self
+                .definition
+                .line_comments
+                .iter()
+                .any(|p| rest.starts_with(p.as_bytes()))
+            {
+                break;
+            }
+
+            if let Some(idx) = longest_match(
+                rest,
+                self.definition
+                    .block_comments
+                    .iter()
+                    .map(|(s, _)| s.as_str()),
+            ) {
+                self.open_block = Some(idx);
+                self.block_depth = 1;
+                i += self.definition.block_comments[idx].0.len();
+                continue;
+            }
+
+            if let Some(idx) = longest_match(
+                rest,
+                self.definition.string_delimiters.iter().map(String::as_str),
+            ) {
+                self.open_string = Some(idx);
+                has_code = true;
+                i += self.definition.string_delimiters[idx].len();
+                continue;
+            }
+
+            has_code = true;
+            i += 1;
+        }
+
+        usize::from(has_code)
+    }
+}
+
+/// Returns the index of the longest pattern that `input` starts with.
+///
+/// Preferring the longest match lets `"""` win over `"` when both are defined.
+fn longest_match<'a>(input: &[u8], patterns: impl Iterator<Item = &'a str>) -> Option<usize> {
+    patterns
+        .enumerate()
+        .filter(|(_, p)| input.starts_with(p.as_bytes()))
+        .max_by_key(|(_, p)| p.len())
+        .map(|(idx, _)| idx)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+    use alloc::vec;
+
+    fn processor(nested: bool) -> CustomLanguageProcessor {
+        CustomLanguageProcessor::new(LanguageDefinition {
+            name: "demo".to_string(),
+            extensions: vec!["demo".to_string()],
+            line_comments: vec!["--".to_string(), "#".to_string()],
+            block_comments: vec![("{-".to_string(), "-}".to_string())],
+            nested_comments: nested,
+            string_delimiters: vec!["\"".to_string(), "\"\"\"".to_string()],
+        })
+    }
+
+    #[test]
+    fn test_line_comments() {
+        let mut p = processor(false);
+        assert_eq!(p.process("-- comment"), 0);
+        assert_eq!(p.process("   # comment"), 0);
+        assert_eq!(p.process("x = 1 -- trailing"), 1);
+        assert_eq!(p.process(""), 0);
+    }
+
+    #[test]
+    fn test_block_comments() {
+        let mut p = processor(false);
+        assert_eq!(p.process("{- start"), 0);
+        assert!(p.is_in_block_comment());
+        assert_eq!(p.process("still comment"), 0);
+        assert_eq!(p.process("end -} x = 1"), 1);
+        assert!(!p.is_in_block_comment());
+    }
+
+    #[test]
+    fn test_nested_block_comments() {
+        let mut p = processor(true);
+        assert_eq!(p.process("{- outer {- inner -}"), 0);
+        assert_eq!(p.process("still outer"), 0);
+        assert_eq!(p.process("-}"), 0);
+        assert_eq!(p.process("code"), 1);
+
+        let mut flat = processor(false);
+        assert_eq!(flat.process("{- outer {- inner -}"), 0);
+        assert_eq!(flat.process("code"), 1);
+    }
+
+    #[test]
+    fn test_comment_markers_in_strings() {
+        let mut p = processor(false);
+        assert_eq!(p.process("\"-- not a comment\""), 1);
+        assert_eq!(p.process("\"\"\"doc"), 1);
+        assert_eq!(p.process("-- inside multi-line string"), 1);
+        assert_eq!(p.process("\"\"\""), 1);
+        assert_eq!(p.process("-- comment again"), 0);
+    }
+}
@@ -0,0 +1,348 @@
+// crates/core/src/language/processors/embedded_style.rs
+//! 他の言語を埋め込むマークアップのコメント処理
+//!
+//! HTML / Vue / Svelte の `<script>` の中身は JavaScript / TypeScript、
+//! `<style>` の中身は CSS (`lang` 属性に応じて Sass / Less) のプロセッサで数え、
+//! それ以外の行は [`HtmlProcessor`] で数えます。開始タグ・終了タグの行は
+//! マークアップの行として扱います。
+
+use super::{CStyleProcessor, HtmlProcessor, JavaScriptProcessor};
+use crate::language::names::language_name;
+use crate::language::processor_trait::LineProcessor;
+use crate::language::string_utils::StringSkipOptions;
+use crate::stats::EmbeddedCount;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Tag {
+    Script,
+    Style,
+}
+
+impl Tag {
+    const fn open(self) -> &'static str {
+        match self {
+            Self::Script => "<script",
+            Self::Style => "<style",
+        }
+    }
+
+    const fn close(self) -> &'static str {
+        match self {
+            Self::Script => "</script",
+            Self::Style => "</style",
+        }
+    }
+}
+
+/// The processor of an open `<script>` or `<style>` section.
+#[derive(Debug)]
+enum Section {
+    Script(JavaScriptProcessor),
+    Style(CStyleProcessor),
+}
+
+impl Section {
+    fn process(&mut self, line: &str) -> usize {
+        match self {
+            Self::Script(p) => p.process_line(line),
+            Self::Style(p) => p.process_line(line),
+        }
+    }
+
+    fn is_in_block_comment(&self) -> bool {
+        match self {
+            Self::Script(p) => p.is_in_block_comment(),
+            Self::Style(p) => p.is_in_block_comment(),
+        }
+    }
+}
+
+/// HTML / Vue / Svelte SLOC processor that counts `<script>` and `<style>`
+/// sections with the processor of their language.
+#[derive(Debug, Default)]
+pub struct EmbeddedProcessor {
+    markup: HtmlProcessor,
+    /// The open section, its tag and its index in `counts`.
+    section: Option<(Tag, usize, Section)>,
+    /// An opening tag whose `>` is on a later line, with its attributes so far.
+    opening: Option<(Tag, String)>,
+    counts: Vec<EmbeddedCount>,
+}
+
+impl LineProcessor for EmbeddedProcessor {
+    fn process_line(&mut self, line: &str) -> usize {
+        self.process(line)
+    }
+
+    fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    fn is_in_block_comment(&self) -> bool {
+        match &self.section {
+            Some((_, _, section)) => section.is_in_block_comment(),
+            None => self.markup.is_in_block_comment(),
+        }
+    }
+
+    fn embedded(&self) -> Vec<EmbeddedCount> {
+        self.counts
+            .iter()
+            .filter(|c| c.lines > 0)
+            .cloned()
+            .collect()
+    }
+}
+
+impl EmbeddedProcessor {
+    /// Creates a new `EmbeddedProcessor`.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 行を処理し、SLOCカウント (0 or 1) を返す
+    /// Processes a line and returns the SLOC count.
+    pub fn process(&mut self, line: &str) -> usize {
+        if let Some((tag, index, section)) = &mut self.section {
+            if let Some(pos) = line.to_ascii_lowercase().find(tag.close()) {
+                self.section = None;
+                return self.markup.process(&line[pos..]);
+            }
+            let sloc = section.process(line);
+            let count = &mut self.counts[*index];
+            count.lines += 1;
+            count.sloc += sloc;
+            return sloc;
+        }
+
+        let in_comment = self.markup.is_in_block_comment();
+        let sloc = self.markup.process(line);
+        if in_comment || !line.contains(['<', '>']) && self.opening.is_none() {
+            return sloc;
+        }
+
+        let lower = line.to_ascii_lowercase();
+        let (tag, attrs, rest) = match self.opening.take() {
+            Some((tag, mut attrs)) => match lower.find('>') {
+                Some(end) => {
+                    attrs.push(' ');
+                    attrs.push_str(&lower[..end]);
+                    (tag, attrs, &lower[end + 1..])
+                }
+                None => {
+                    attrs.push(' ');
+                    attrs.push_str(&lower);
+                    self.opening = Some((tag, attrs));
+                    return sloc;
+                }
+            },
+            None => {
+                let Some((tag, start)) = find_open_tag(&lower) else {
+                    return sloc;
+                };
+                let after = &lower[start + tag.open().len()..];
+                match after.find('>') {
+                    Some(end) => (tag, after[..end].to_string(), &after[end + 1..]),
+                    None => {
+                        self.opening = Some((tag, after.to_string()));
+                        return sloc;
+                    }
+                }
+            }
+        };
+
+        // `<script src="..."></script>` and `<script ... />` have no body.
+        if attrs.trim_end().ends_with('/') || rest.contains(tag.close()) {
+            return sloc;
+        }
+        if let Some(ext) = section_extension(tag, &attrs) {
+            let section = match tag {
+                Tag::Script => Section::Script(JavaScriptProcessor::new()),
+                Tag::Style => {
+                    Section::Style(CStyleProcessor::new(StringSkipOptions::from_extension(ext)))
+                }
+            };
+            let language = language_name(ext).unwrap_or(ext);
+            let index = match self.counts.iter().position(|c| c.language == language) {
+                Some(index) => index,
+                None => {
+                    self.counts.push(EmbeddedCount {
+                        language: language.to_string(),
+                        ..EmbeddedCount::default()
+                    });
+                    self.counts.len() - 1
+                }
+            };
+            self.section = Some((tag, index, section));
+        }
+        sloc
+    }
+}
+
+/// The first `<script` or `<style` tag of a lowercased line, outside an
+/// HTML comment on the same line.
+fn find_open_tag(lower: &str) -> Option<(Tag, usize)> {
+    [Tag::Script, Tag::Style]
+        .into_iter()
+        .filter_map(|tag| {
+            let mut from = 0;
+            while let Some(pos) = lower[from..].find(tag.open()).map(|p| p + from) {
+                let next = lower[pos + tag.open().len()..].chars().next();
+                if next.is_none_or(|c| c == '>' || c == '/' || c.is_whitespace()) {
+                    return Some((tag, pos));
+                }
+                from = pos + 1;
+            }
+            None
+        })
+        .min_by_key(|&(_, pos)| pos)
+        .filter(|&(_, pos)| {
+            let before = &lower[..pos];
+            before
+                .rfind("<!--")
+                .is_none_or(|c| before[c..].contains("-->"))
+        })
+}
+
+/// The value of attribute `name` in lowercased `attrs`, without quotes.
+fn attribute<'a>(attrs: &'a str, name: &str) -> Option<&'a str> {
+    let mut from = 0;
+    while let Some(pos) = attrs[from..].find(name).map(|p| p + from) {
+        let starts_word = attrs[..pos].ends_with(|c: char| c.is_whitespace()) || pos == 0;
+        let value = attrs[pos + name.len()..].trim_start();
+        if let (true, Some(value)) = (starts_word, value.strip_prefix('=')) {
+            let value = value.trim_start();
+            return Some(match value.chars().next() {
+                Some(quote @ ('"' | '\'')) => value[1..].split(quote).next().unwrap_or(""),
+                _ => value
+                    .split(|c: char| c.is_whitespace())
+                    .next()
+                    .unwrap_or(""),
+            });
+        }
+        from = pos + 1;
+    }
+    None
+}
+
+/// The extension whose processor counts the section, or `None` if the
+/// section isn't code (e.g. `<script type="text/template">`).
+fn section_extension(tag: Tag, attrs: &str) -> Option<&'static str> {
+    let lang = attribute(attrs, "lang");
+    match tag {
+        Tag::Script => match (lang, attribute(attrs, "type")) {
+            (Some("ts" | "tsx" | "typescript"), _) => Some("ts"),
+            (Some("js" | "jsx" | "javascript"), _) => Some("js"),
+            (Some(_), _) => None,
+            (None, Some(ty)) if ty.contains("typescript") => Some("ts"),
+            (None, Some(ty))
+                if !(ty.is_empty()
+                    || ty == "module"
+                    || ty.contains("javascript")
+                    || ty.contains("ecmascript")
+                    || ty.contains("babel")
+                    || ty.contains("jsx")) =>
+            {
+                None
+            }
+            (None, _) => Some("js"),
+        },
+        Tag::Style => match lang {
+            Some("scss") => Some("scss"),
+            Some("sass") => Some("sass"),
+            Some("less") => Some("less"),
+            _ => Some("css"),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn count(lines: &[&str]) -> (usize, Vec<(String, usize, usize)>) {
+        let mut p = EmbeddedProcessor::new();
+        let sloc = lines.iter().map(|l| p.process(l)).sum();
+        let embedded = p
+            .embedded()
+            .into_iter()
+            .map(|c| (c.language, c.lines, c.sloc))
+            .collect();
+        (sloc, embedded)
+    }
+
+    #[test]
+    fn test_html_sections() {
+        let (sloc, embedded) = count(&[
+            "<html>",
+            "<!-- <script> -->",
+            "<script>",
+            "  // setup",
+            "  let x = '<!--';",
+            "",
+            "</script>",
+            "<style>",
+            "  /* theme */",
+            "  p { color: red; }",
+            "</style>",
+            "<script src=\"app.js\"></script>",
+            "</html>",
+        ]);
+        assert_eq!(
+            embedded,
+            [("JavaScript".to_string(), 3, 1), ("CSS".to_string(), 2, 1)]
+        );
+        // html, 2 tags per section, the src script and the closing html
+        assert_eq!(sloc, 1 + 4 + 1 + 1 + 1 + 1);
+    }
+
+    #[test]
+    fn test_vue_component() {
+        let (sloc, embedded) = count(&[
+            "<template>",
+            "  <div>{{ msg }}</div>",
+            "</template>",
+            "<script",
+            "  setup",
+            "  lang=\"ts\"",
+            ">",
+            "const msg: string = 'hi'",
+            "</script>",
+            "<style lang='scss' scoped>",
+            "// nested",
+            "div { p { margin: 0 } }",
+            "</style>",
+        ]);
+        assert_eq!(
+            embedded,
+            [("TypeScript".to_string(), 1, 1), ("Sass".to_string(), 2, 1)]
+        );
+        assert_eq!(sloc, 13 - 1);
+    }
+
+    #[test]
+    fn test_non_code_scripts_stay_markup() {
+        let (sloc, embedded) = count(&[
+            "<script type=\"text/x-template\">",
+            "  // not a comment here",
+            "</script>",
+            "<script type=\"module\" />",
+            "<SCRIPT TYPE=\"text/javascript\">",
+            "go()",
+            "</SCRIPT>",
+        ]);
+        assert_eq!(embedded, [("JavaScript".to_string(), 1, 1)]);
+        assert_eq!(sloc, 7);
+    }
+
+    #[test]
+    fn test_attribute() {
+        assert_eq!(attribute(" setup lang=\"ts\"", "lang"), Some("ts"));
+        assert_eq!(attribute(" lang = 'scss' scoped", "lang"), Some("scss"));
+        assert_eq!(attribute(" type=module", "type"), Some("module"));
+        assert_eq!(attribute(" xml:lang=\"en\"", "lang"), None);
+    }
+}
@@ -41,7 +41,8 @@ impl HaskellProcessor {
             return 0;
         }
 
-        if line.starts_with("--") {
+        let trimmed = line.trim();
+        if trimmed.is_empty() || is_line_comment(trimmed) {
             return 0;
         }
 
@@ -86,6 +87,13 @@ impl HaskellProcessor {
     }
 }
 
+/// `--` で始まる行コメントか (`-->` や `--|` などの演算子は除く)
+fn is_line_comment(trimmed: &str) -> bool {
+    let rest = trimmed.trim_start_matches('-');
+    trimmed.len() - rest.len() >= 2
+        && !rest.starts_with(|c: char| "!#$%&*+./<=>?@\\^|~:".contains(c))
+}
+
 // ============================================================================
 // StatefulProcessor implementation
 // ============================================================================
@@ -138,4 +146,13 @@ mod tests {
         assert!(!p.is_in_block_comment());
         assert_eq!(p.process("y = 2"), 1);
     }
+
+    #[test]
+    fn test_haskell_processor_blank_and_indented() {
+        let mut p = HaskellProcessor::new();
+        assert_eq!(p.process(""), 0);
+        assert_eq!(p.process("  -- indented comment"), 0);
+        assert_eq!(p.process("---------"), 0);
+        assert_eq!(p.process("--> operator"), 1);
+    }
 }
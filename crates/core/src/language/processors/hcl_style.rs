@@ -0,0 +1,177 @@
+// crates/core/src/language/processors/hcl_style.rs
+//! HCL (Terraform) のコメント・ヒアドキュメント処理
+//!
+//! HCL固有の対応:
+//! - 行コメント: `#`, `//`
+//! - ブロックコメント: `/* */`
+//! - 文字列: `"..."` (`${...}` 補間を含む)
+//! - ヒアドキュメント: `<<EOT`, `<<-EOT` (終了識別子のインデント可)
+//!
+//! ヒアドキュメント内の `#` や `//` はコメントとして扱われません。
+
+use crate::language::heredoc_utils::HeredocContext;
+use crate::language::processor_trait::LineProcessor;
+use alloc::string::ToString;
+
+/// HCL プロセッサ
+/// HCL SLOC processor.
+#[derive(Debug, Default, Clone)]
+pub struct HclProcessor {
+    in_block_comment: bool,
+    heredoc_ctx: HeredocContext,
+}
+
+impl LineProcessor for HclProcessor {
+    fn process_line(&mut self, line: &str) -> usize {
+        self.process(line)
+    }
+
+    fn reset(&mut self) {
+        self.in_block_comment = false;
+        self.heredoc_ctx.reset();
+    }
+
+    fn is_in_block_comment(&self) -> bool {
+        self.in_block_comment || self.heredoc_ctx.is_in_heredoc()
+    }
+}
+
+impl HclProcessor {
+    #[must_use]
+    /// Creates a new `HclProcessor`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 行を処理し、SLOCカウント (0 or 1) を返す
+    /// Processes a line and returns the SLOC count.
+    pub fn process(&mut self, line: &str) -> usize {
+        if self.heredoc_ctx.is_in_heredoc() {
+            self.heredoc_ctx.check_end(line);
+            return usize::from(!line.trim().is_empty());
+        }
+
+        let bytes = line.as_bytes();
+        let mut has_code = false;
+        let mut i = 0;
+
+        while i < bytes.len() {
+            if self.in_block_comment {
+                match line[i..].find("*/") {
+                    Some(end) => {
+                        self.in_block_comment = false;
+                        i += end + 2;
+                        continue;
+                    }
+                    None => break,
+                }
+            }
+
+            let rest = &line[i..];
+            match bytes[i] {
+                b'#' => break,
+                b'/' if rest.starts_with("//") => break,
+                b'/' if rest.starts_with("/*") => {
+                    self.in_block_comment = true;
+                    i += 2;
+                }
+                b'"' => {
+                    has_code = true;
+                    i = skip_string(bytes, i);
+                }
+                b'<' if rest.starts_with("<<") => {
+                    has_code = true;
+                    let marker = rest[2..].strip_prefix('-').unwrap_or(&rest[2..]);
+                    let ident_len = marker
+                        .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+                        .unwrap_or(marker.len());
+                    if ident_len > 0 && !marker.starts_with(|c: char| c.is_ascii_digit()) {
+                        self.heredoc_ctx.push(marker[..ident_len].to_string(), true);
+                    }
+                    i += 2;
+                }
+                _ => {
+                    let c = rest.chars().next().unwrap_or_default();
+                    has_code |= !c.is_whitespace();
+                    i += c.len_utf8();
+                }
+            }
+        }
+
+        usize::from(has_code)
+    }
+}
+
+/// `start` の `"` で始まる文字列の終わりの次の位置 (閉じていなければ行末)
+///
+/// `${ ... }` 補間内の `"` は入れ子の文字列として読み飛ばします。
+fn skip_string(bytes: &[u8], start: usize) -> usize {
+    let mut i = start + 1;
+    let mut depth = 0usize;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' => i += 2,
+            b'$' if depth == 0 && bytes.get(i + 1) == Some(&b'{') => {
+                depth = 1;
+                i += 2;
+            }
+            b'{' if depth > 0 => {
+                depth += 1;
+                i += 1;
+            }
+            b'}' if depth > 0 => {
+                depth -= 1;
+                i += 1;
+            }
+            b'"' if depth > 0 => i = skip_string(bytes, i),
+            b'"' => return i + 1,
+            _ => i += 1,
+        }
+    }
+    bytes.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hcl_comments() {
+        let mut p = HclProcessor::new();
+        assert_eq!(p.process("# comment"), 0);
+        assert_eq!(p.process("  // comment"), 0);
+        assert_eq!(p.process("/* block"), 0);
+        assert!(p.is_in_block_comment());
+        assert_eq!(p.process("   still */"), 0);
+        assert_eq!(
+            p.process("resource \"aws_instance\" \"web\" { # trailing"),
+            1
+        );
+        assert_eq!(p.process("  url = \"http://example.com/#top\""), 1);
+        assert_eq!(
+            p.process("  name = \"${var.prefix}-#${count.index}\" // x"),
+            1
+        );
+        assert_eq!(p.process("/* a */ count = 2"), 1);
+        assert_eq!(p.process("  é = \"\\é\" # ü"), 1);
+    }
+
+    #[test]
+    fn test_hcl_heredoc() {
+        let mut p = HclProcessor::new();
+        assert_eq!(p.process("  user_data = <<-EOT"), 1);
+        assert_eq!(p.process("    #!/bin/bash"), 1);
+        assert_eq!(p.process("    // not a comment"), 1);
+        assert_eq!(p.process(""), 0);
+        assert_eq!(p.process("  EOT"), 1);
+        assert!(!p.is_in_block_comment());
+        assert_eq!(p.process("# comment"), 0);
+    }
+
+    #[test]
+    fn test_hcl_heredoc_in_string_is_ignored() {
+        let mut p = HclProcessor::new();
+        assert_eq!(p.process("  text = \"<<EOT\""), 1);
+        assert_eq!(p.process("# comment"), 0);
+    }
+}
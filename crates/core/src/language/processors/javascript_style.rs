@@ -7,6 +7,7 @@
 //!
 //! - **Line comments**: `//`
 //! - **Block comments**: `/* */`
+//! - **JSX comments**: `{/* */}` (the braces count as part of the comment)
 //! - **String literals**: `"..."`, `'...'`, `` `...` ``
 //! - **Template literals**: `` `${...}` `` with interpolation
 //! - **Regex literals**: `/pattern/flags`
@@ -15,7 +16,8 @@
 //! ## Limitations
 //!
 //! - Nested comment-like syntax within regex patterns is not detected
-//! - JSX/TSX special syntax (`<Component />`) is not fully supported
+//! - JSX/TSX markup (`<Component />`) is counted as code; only `{/* */}`
+//!   comments inside it are recognized
 //! - Automatic semicolon insertion (ASI) edge cases may affect accuracy
 //!
 //! ## Performance Characteristics
@@ -43,6 +45,7 @@
 //! assert_eq!(proc.process_line("const re = /pattern/;"), 1);
 //! ```
 
+use crate::language::doc_comments::{DocComments, DocSyntax};
 use crate::language::processor_trait::LineProcessor;
 use alloc::vec::Vec;
 
@@ -53,6 +56,8 @@ pub enum JsScope {
     Interpolation, // ${ ... }
     /// Block comment scope.
     BlockComment, // /* ... */
+    /// JSX comment scope, a block comment opened by `{/*`.
+    JsxComment, // {/* ... */}
     /// String literal scope.
     String(u8), // " ' `
     /// Regex literal scope.
@@ -69,15 +74,28 @@ pub struct JavaScriptProcessor {
     // Track if last token was value-like (for regex heuristics)
     last_token_is_value: bool,
     line_count: usize,
+    docs: DocComments,
+    doc_line: bool,
 }
 
 impl LineProcessor for JavaScriptProcessor {
     fn process_line(&mut self, line: &str) -> usize {
-        self.process(line)
+        let was_in_block = self.is_in_block_comment();
+        let sloc = self.process(line);
+        let in_block = self.is_in_block_comment();
+        self.doc_line = self.docs.observe(line, sloc > 0, was_in_block, in_block);
+        sloc
+    }
+
+    fn is_doc_line(&self) -> bool {
+        self.doc_line
     }
 
     fn is_in_block_comment(&self) -> bool {
-        matches!(self.stack.last(), Some(JsScope::BlockComment))
+        matches!(
+            self.stack.last(),
+            Some(JsScope::BlockComment | JsScope::JsxComment)
+        )
     }
 }
 
@@ -85,7 +103,10 @@ impl JavaScriptProcessor {
     #[must_use]
     /// Creates a new `JavaScriptProcessor`.
     pub fn new() -> Self {
-        Self::default()
+        Self {
+            docs: DocComments::new(DocSyntax::Javadoc),
+            ..Self::default()
+        }
     }
 
     /// Processes a line and returns the SLOC count.
@@ -106,7 +127,7 @@ impl JavaScriptProcessor {
 
         let mut chars = line.char_indices().peekable();
 
-        while let Some((_, c)) = chars.next() {
+        while let Some((i, c)) = chars.next() {
             match self.stack.last().cloned() {
                 Some(JsScope::BlockComment) => {
                     // Check for */
@@ -115,6 +136,24 @@ impl JavaScriptProcessor {
                         self.stack.pop();
                     }
                 }
+                Some(JsScope::JsxComment) => {
+                    if c == '*' && chars.peek().is_some_and(|(_, next_c)| *next_c == '/') {
+                        chars.next();
+                        self.stack.pop();
+                        if line[i + 2..].trim_start().starts_with('}') {
+                            // Consume the closing brace of `{/* ... */}`
+                            for (_, skipped) in chars.by_ref() {
+                                if skipped == '}' {
+                                    break;
+                                }
+                            }
+                        } else {
+                            // `{ /* ... */ expr }`: the brace was code after all
+                            has_code_token = true;
+                            self.last_token_is_value = false;
+                        }
+                    }
+                }
                 Some(JsScope::String(quote)) => {
                     // Check escape
                     if c == '\\' {
@@ -194,6 +233,14 @@ impl JavaScriptProcessor {
                         // Division operator
                         self.last_token_is_value = false; // Operator
                         has_code_token = true;
+                    } else if c == '{' && line[i + 1..].trim_start().starts_with("/*") {
+                        // JSX comment: consume up to and including `/*`
+                        for (_, skipped) in chars.by_ref() {
+                            if skipped == '*' {
+                                break;
+                            }
+                        }
+                        self.stack.push(JsScope::JsxComment);
                     } else if c == '"' || c == '\'' || c == '`' {
                         self.stack.push(JsScope::String(c as u8));
                         has_code_token = true;
@@ -218,6 +265,13 @@ impl JavaScriptProcessor {
             }
         }
 
+        // Regex literals can't span lines: an unclosed one was a misread `/`
+        // such as the one in a JSX closing tag (`</div>`).
+        if matches!(self.stack.last(), Some(JsScope::Regex { .. })) {
+            self.stack.pop();
+            self.last_token_is_value = false;
+        }
+
         usize::from(has_code_token)
     }
 
@@ -244,6 +298,8 @@ impl JavaScriptProcessor {
         self.stack.clear();
         self.last_token_is_value = false;
         self.line_count = 0;
+        self.docs.reset();
+        self.doc_line = false;
     }
 }
 
@@ -329,4 +385,26 @@ mod tests {
         assert_eq!(p.process("x = /regex/"), 1);
         assert_eq!(p.process("return /regex/"), 1);
     }
+
+    #[test]
+    fn test_jsx_closing_tag_is_not_a_regex() {
+        let mut p = JavaScriptProcessor::new();
+        assert_eq!(p.process("  <span>{count}</span>"), 1);
+        assert_eq!(p.process("  // comment"), 0);
+        assert!(!p.is_in_multiline_context());
+    }
+
+    #[test]
+    fn test_jsx_comment() {
+        let mut p = JavaScriptProcessor::new();
+        assert_eq!(p.process("  <div>"), 1);
+        assert_eq!(p.process("    {/* single line */}"), 0);
+        assert_eq!(p.process("    { /*"), 0);
+        assert!(p.is_in_block_comment());
+        assert_eq!(p.process("      <Old />"), 0);
+        assert_eq!(p.process("    */ }"), 0);
+        assert_eq!(p.process("    <span>{/* note */}{count}</span>"), 1);
+        assert_eq!(p.process("    {/* note */ count}"), 1);
+        assert_eq!(p.process("  </div>"), 1);
+    }
 }
@@ -45,8 +45,13 @@ impl LuaProcessor {
             return 0;
         }
 
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            return 0;
+        }
+
         // 行コメント
-        if let Some(stripped) = line.strip_prefix("--") {
+        if let Some(stripped) = trimmed.strip_prefix("--") {
             if let Some(level) = check_lua_block_start(stripped) {
                 let after_open = skip_lua_block_open(stripped);
                 if find_lua_block_end(after_open, level).is_some() {
@@ -184,4 +189,13 @@ mod tests {
         assert_eq!(p.process("--[[ single line block ]]"), 0);
         assert_eq!(p.process("local a = 1"), 1);
     }
+
+    #[test]
+    fn test_lua_processor_blank_and_indented() {
+        let mut p = LuaProcessor::new();
+        assert_eq!(p.process("   "), 0);
+        assert_eq!(p.process("  -- indented comment"), 0);
+        assert_eq!(p.process("  --[[ indented"), 0);
+        assert_eq!(p.process("  ]]"), 0);
+    }
 }
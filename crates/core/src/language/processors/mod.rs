@@ -1,23 +1,31 @@
 // crates/core/src/language/processors/mod.rs
+pub mod abap_style;
 pub mod assembly_style;
 pub mod batch_style;
 pub mod c_style;
+pub mod cobol_style;
+pub mod custom_style;
 pub mod dlang_style;
+pub mod embedded_style;
 pub mod erlang_style;
 pub mod fortran_processor;
 pub mod fortran_style;
 pub mod haskell_style;
+pub mod hcl_style;
 pub mod javascript_style;
 pub mod julia_style;
 pub mod lisp_style;
 pub mod lua_style;
 pub mod markup_style;
 pub mod matlab_style;
+pub mod nim_style;
 pub mod ocaml_style;
 pub mod perl_style;
 pub mod php_style;
 pub mod powershell_style;
 pub mod python_style;
+pub mod razor_style;
+pub mod rpg_style;
 pub mod ruby_style;
 pub mod shell_style;
 pub mod simple_hash_style;
@@ -27,21 +35,30 @@ pub mod swift_style;
 pub mod vhdl_style;
 pub mod visual_basic_style;
 
+pub use abap_style::AbapProcessor;
 pub use assembly_style::GasAssemblyProcessor;
+pub use batch_style::BatchProcessor;
 pub use c_style::{CStyleProcessor, CStyleState, NestingCStyleProcessor, NestingCStyleState};
+pub use cobol_style::CobolProcessor;
+pub use custom_style::CustomLanguageProcessor;
 pub use dlang_style::DLangProcessor;
+pub use embedded_style::EmbeddedProcessor;
 pub use fortran_processor::FortranProcessor;
 pub use haskell_style::{HaskellProcessor, HaskellState};
+pub use hcl_style::HclProcessor;
 pub use javascript_style::{JavaScriptProcessor, JavaScriptState, JsScope};
 pub use julia_style::JuliaProcessor;
 pub use lua_style::{LuaProcessor, LuaState};
 pub use markup_style::{HtmlProcessor, HtmlState};
 pub use matlab_style::MatlabProcessor;
+pub use nim_style::NimProcessor;
 pub use ocaml_style::OCamlProcessor;
 pub use perl_style::{PerlProcessor, PerlState};
 pub use php_style::{PhpProcessor, PhpState};
 pub use powershell_style::PowerShellProcessor;
 pub use python_style::{PythonProcessor, PythonScope, PythonState, PythonStringState};
+pub use razor_style::RazorProcessor;
+pub use rpg_style::RpgProcessor;
 pub use ruby_style::{RubyProcessor, RubyScope, RubyState};
 pub use shell_style::{ShellProcessor, ShellState};
 pub use simple_hash_style::SimpleHashProcessor;
@@ -0,0 +1,170 @@
+// crates/core/src/language/processors/nim_style.rs
+//! Nim言語のコメント処理
+//!
+//! Nim固有の対応:
+//! - 行コメント: `#`, ドキュメントコメント `##`
+//! - ブロックコメント: `#[` ～ `]#`, `##[` ～ `]##` (ネスト対応)
+//! - 文字列: `"..."`, 複数行の `"""..."""`, 文字リテラル `'c'`
+//!
+//! 数値の型接尾辞 (`1'i32`) の `'` は文字リテラルとみなしません。
+
+use crate::language::processor_trait::LineProcessor;
+
+/// Nim プロセッサ
+/// Nim SLOC processor.
+#[derive(Debug, Default, Clone)]
+pub struct NimProcessor {
+    block_comment_depth: usize,
+    in_triple_string: bool,
+}
+
+impl LineProcessor for NimProcessor {
+    fn process_line(&mut self, line: &str) -> usize {
+        self.process(line)
+    }
+
+    fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    fn is_in_block_comment(&self) -> bool {
+        self.block_comment_depth > 0
+    }
+}
+
+impl NimProcessor {
+    #[must_use]
+    /// Creates a new `NimProcessor`.
+    pub const fn new() -> Self {
+        Self {
+            block_comment_depth: 0,
+            in_triple_string: false,
+        }
+    }
+
+    /// 行を処理し、SLOCカウント (0 or 1) を返す
+    /// Processes a line and returns the SLOC count.
+    pub fn process(&mut self, line: &str) -> usize {
+        usize::from(self.scan(line))
+    }
+
+    /// 行を走査し、コメント以外の内容があれば true を返す
+    fn scan(&mut self, line: &str) -> bool {
+        let bytes = line.as_bytes();
+        let mut has_code = false;
+        let mut i = 0;
+
+        if self.in_triple_string {
+            let Some(end) = line.find("\"\"\"") else {
+                return !line.trim().is_empty();
+            };
+            self.in_triple_string = false;
+            has_code = true;
+            i = end + 3;
+        }
+
+        while i < bytes.len() {
+            if self.block_comment_depth > 0 {
+                if bytes[i..].starts_with(b"#[") {
+                    self.block_comment_depth += 1;
+                    i += 2;
+                } else if bytes[i..].starts_with(b"]#") {
+                    self.block_comment_depth -= 1;
+                    i += 2;
+                } else {
+                    i += 1;
+                }
+                continue;
+            }
+
+            match bytes[i] {
+                b'#' => {
+                    let rest = &line[i..];
+                    if rest.starts_with("#[") || rest.starts_with("##[") {
+                        self.block_comment_depth = 1;
+                        i += if rest.starts_with("##[") { 3 } else { 2 };
+                        continue;
+                    }
+                    // 行コメント
+                    break;
+                }
+                b'"' if line[i..].starts_with("\"\"\"") => {
+                    has_code = true;
+                    match line[i + 3..].find("\"\"\"") {
+                        Some(end) => i += 3 + end + 3,
+                        None => {
+                            self.in_triple_string = true;
+                            break;
+                        }
+                    }
+                }
+                b'"' => {
+                    has_code = true;
+                    i = skip_quoted(bytes, i, b'"');
+                }
+                b'\'' if i == 0 || !bytes[i - 1].is_ascii_alphanumeric() => {
+                    has_code = true;
+                    i = skip_quoted(bytes, i, b'\'');
+                }
+                c => {
+                    has_code |= !c.is_ascii_whitespace();
+                    i += 1;
+                }
+            }
+        }
+        has_code
+    }
+}
+
+/// `start` の引用符で始まる文字列の終わりの次の位置 (閉じていなければ行末)
+fn skip_quoted(bytes: &[u8], start: usize, quote: u8) -> usize {
+    let mut i = start + 1;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' => i += 2,
+            c if c == quote => return i + 1,
+            _ => i += 1,
+        }
+    }
+    bytes.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_nim_line_comments() {
+        let mut p = NimProcessor::new();
+        assert_eq!(p.process("# comment"), 0);
+        assert_eq!(p.process("## doc comment"), 0);
+        assert_eq!(p.process("echo \"# not a comment\" # comment"), 1);
+        assert_eq!(p.process("let c = '#'"), 1);
+        assert_eq!(p.process("let x = 1'i32 # typed"), 1);
+    }
+
+    #[test]
+    fn test_nim_nested_block_comment() {
+        let mut p = NimProcessor::new();
+        assert_eq!(p.process("#[ outer"), 0);
+        assert!(p.is_in_block_comment());
+        assert_eq!(p.process("  #[ nested ]#"), 0);
+        assert_eq!(p.process("  still comment"), 0);
+        assert_eq!(p.process("]# let y = 2"), 1);
+        assert!(!p.is_in_block_comment());
+        assert_eq!(p.process("##[ doc ]##"), 0);
+        assert_eq!(p.process("proc f() = #[ inline ]# discard"), 1);
+        assert_eq!(p.process("#[ ü ]# é"), 1);
+    }
+
+    #[test]
+    fn test_nim_triple_quoted_string() {
+        let mut p = NimProcessor::new();
+        assert_eq!(p.process("const s = \"\"\""), 1);
+        assert_eq!(p.process("# inside string"), 1);
+        assert_eq!(p.process("#[ also inside"), 1);
+        assert_eq!(p.process("\"\"\""), 1);
+        assert!(!p.is_in_block_comment());
+        assert_eq!(p.process("# comment"), 0);
+    }
+}
@@ -4,7 +4,10 @@
 //! Perl固有の対応:
 //! - `#` 行コメント
 //! - POD: `=pod`, `=head1` 等 ～ `=cut` (行頭必須)
-//! - ヒアドキュメント: `<<EOF`, `<<'EOF'`, `<<"EOF"`
+//! - ヒアドキュメント: `<<EOF`, `<<'EOF'`, `<<"EOF"`, インデント可の `<<~EOF`
+//!
+//! ヒアドキュメント内の `#` や `=pod` はコメントとして扱われません。
+//! `<<` の直後が識別子でない場合 (`$x << 2`) はシフト演算子とみなします。
 
 use alloc::string::ToString;
 use regex::Regex;
@@ -50,7 +53,10 @@ impl PerlProcessor {
             in_pod: false,
             line_count: 0,
             heredoc_ctx: HeredocContext::new(),
-            heredoc_re: Regex::new(r"<<\s*(?:([\w]+)|'([\w]+)'|\x22([\w]+)\x22)").unwrap(),
+            heredoc_re: Regex::new(
+                r"<<(~?)(?:([A-Za-z_]\w*)|[ \t]*'([^']*)'|[ \t]*\x22([^\x22]*)\x22)",
+            )
+            .unwrap(),
         }
     }
 
@@ -99,28 +105,29 @@ impl PerlProcessor {
             return 0;
         }
 
-        // ヒアドキュメント開始検出
-        // Perl: <<\s*(?:([\w]+)|'([\w]+)'|\x22([\w]+)\x22)
-
-        for caps in self.heredoc_re.captures_iter(line) {
+        // ヒアドキュメント開始検出 (# コメントより前にあるもののみ)
+        let hash_pos = find_hash_outside_simple_string(line);
+        let code = &line[..hash_pos.unwrap_or(line.len())];
+        for caps in self.heredoc_re.captures_iter(code) {
             if let Some(matches) = caps.get(0) {
                 let start = matches.start();
-                if !is_inside_string(line, start) {
-                    // Group 1: unquoted, Group 2: single, Group 3: double
+                if !is_inside_string(code, start) {
+                    // Group 1: `~`, Group 2: unquoted, Group 3: single, Group 4: double
+                    let allow_indent = caps.get(1).is_some_and(|m| !m.as_str().is_empty());
                     let ident = caps
-                        .get(1)
-                        .or_else(|| caps.get(2))
+                        .get(2)
                         .or_else(|| caps.get(3))
+                        .or_else(|| caps.get(4))
                         .unwrap()
                         .as_str()
                         .to_string();
-                    self.heredoc_ctx.push(ident, false);
+                    self.heredoc_ctx.push(ident, allow_indent);
                 }
             }
         }
 
         // # より前にコードがあるか
-        if let Some(hash_pos) = find_hash_outside_simple_string(line) {
+        if let Some(hash_pos) = hash_pos {
             let before = &line[..hash_pos];
             if !before.trim().is_empty() {
                 return 1;
@@ -246,6 +253,45 @@ mod tests {
         assert_eq!(p.process("EOF"), 1);
     }
 
+    #[test]
+    fn test_perl_heredoc_hides_comments_and_pod() {
+        let mut p = PerlProcessor::default();
+        assert_eq!(p.process("my $s = <<\"END\";"), 1);
+        assert_eq!(p.process("# not a comment"), 1);
+        assert_eq!(p.process("=pod"), 1);
+        assert_eq!(p.process(""), 0);
+        assert_eq!(p.process("END"), 1);
+        assert_eq!(p.process("# comment"), 0);
+    }
+
+    #[test]
+    fn test_perl_indented_heredoc() {
+        let mut p = PerlProcessor::default();
+        assert_eq!(p.process("    print <<~EOT;"), 1);
+        assert_eq!(p.process("        # text"), 1);
+        assert_eq!(p.process("    EOT"), 1);
+        assert_eq!(p.process("# comment"), 0);
+    }
+
+    #[test]
+    fn test_perl_stacked_heredocs() {
+        let mut p = PerlProcessor::default();
+        assert_eq!(p.process("print <<A, <<'B';"), 1);
+        assert_eq!(p.process("# a"), 1);
+        assert_eq!(p.process("A"), 1);
+        assert_eq!(p.process("# b"), 1);
+        assert_eq!(p.process("B"), 1);
+        assert_eq!(p.process("# comment"), 0);
+    }
+
+    #[test]
+    fn test_perl_shift_and_commented_heredoc() {
+        let mut p = PerlProcessor::default();
+        assert_eq!(p.process("my $x = $y << 2;"), 1);
+        assert_eq!(p.process("my $z = 1; # see <<EOF"), 1);
+        assert_eq!(p.process("# comment"), 0);
+    }
+
     #[test]
     fn test_perl_pod() {
         let mut p = PerlProcessor::default();
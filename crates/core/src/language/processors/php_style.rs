@@ -46,7 +46,8 @@ impl PhpProcessor {
         Self {
             in_block_comment: false,
             heredoc_ctx: HeredocContext::new(),
-            heredoc_re: Regex::new(r"<<<(?:([\w]+)|'([\w]+)'|\x22([\w]+)\x22)").unwrap(),
+            heredoc_re: Regex::new(r"<<<[ \t]*(?:([A-Za-z_]\w*)|'([\w]+)'|\x22([\w]+)\x22)")
+                .unwrap(),
         }
     }
 
@@ -57,11 +58,8 @@ impl PhpProcessor {
     pub fn process(&mut self, line: &str) -> usize {
         // ヒアドキュメント処理
         if self.heredoc_ctx.is_in_heredoc() {
-            // PHP end: `EOF;` or `EOF`
-            let trimmed = line.trim();
-            let check_target = trimmed.strip_suffix(';').unwrap_or(trimmed);
-
-            if self.heredoc_ctx.check_end(check_target) {
+            // PHP end: `EOF`, `EOF;` or (PHP 7.3+) `EOF, $y);`
+            if self.heredoc_ctx.check_end_marker(line) {
                 return 1;
             }
 
@@ -246,4 +244,25 @@ mod tests {
         assert_eq!(p.process("Content"), 1);
         assert_eq!(p.process("EOF;"), 1);
     }
+
+    #[test]
+    fn test_php_heredoc_hides_comments() {
+        let mut p = PhpProcessor::new();
+        assert_eq!(p.process("$sql = <<< SQL"), 1);
+        assert_eq!(p.process("    -- # not a comment"), 1);
+        assert_eq!(p.process("    // nor this"), 1);
+        assert_eq!(p.process("    /* or this"), 1);
+        assert_eq!(p.process("    SQL;"), 1);
+        assert!(!p.is_in_block_comment());
+        assert_eq!(p.process("# comment"), 0);
+    }
+
+    #[test]
+    fn test_php_flexible_closing_marker() {
+        let mut p = PhpProcessor::new();
+        assert_eq!(p.process("foo(<<<EOT"), 1);
+        assert_eq!(p.process("    EOTX is still text"), 1);
+        assert_eq!(p.process("    EOT, $bar);"), 1);
+        assert_eq!(p.process("// comment"), 0);
+    }
 }
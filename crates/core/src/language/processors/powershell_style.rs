@@ -2,6 +2,9 @@
 //! `PowerShell` のコメント処理
 //!
 //! `PowerShell` は `#` 行コメントと `<# #>` ブロックコメントを使用します。
+//!
+//! ヒア文字列 (`@"` ～ `"@`, `@'` ～ `'@`) の中の `#` や `<#` はコメントとして扱いません。
+//! ヒア文字列は開始記号が行末にあり、終了記号が行頭にある場合のみ認識します。
 
 use crate::language::processor_trait::LineProcessor;
 use crate::language::string_utils::find_hash_outside_string;
@@ -11,6 +14,8 @@ use crate::language::string_utils::find_hash_outside_string;
 #[derive(Debug, Default)]
 pub struct PowerShellProcessor {
     in_block_comment: bool,
+    /// 開いているヒア文字列の引用符 (`"` or `'`)
+    here_string: Option<char>,
 }
 
 impl LineProcessor for PowerShellProcessor {
@@ -18,6 +23,10 @@ impl LineProcessor for PowerShellProcessor {
         self.process(line)
     }
 
+    fn reset(&mut self) {
+        *self = Self::new();
+    }
+
     fn is_in_block_comment(&self) -> bool {
         self.in_block_comment
     }
@@ -29,11 +38,29 @@ impl PowerShellProcessor {
     pub const fn new() -> Self {
         Self {
             in_block_comment: false,
+            here_string: None,
         }
     }
 
     /// Processes a line and returns the SLOC count.
     pub fn process(&mut self, line: &str) -> usize {
+        if let Some(quote) = self.here_string {
+            let trimmed = line.trim_start();
+            if trimmed.starts_with(quote) && trimmed[1..].starts_with('@') {
+                self.here_string = None;
+                return 1;
+            }
+            return usize::from(!trimmed.is_empty());
+        }
+
+        let count = self.process_code(line);
+        if !self.in_block_comment {
+            self.here_string = here_string_start(line);
+        }
+        count
+    }
+
+    fn process_code(&mut self, line: &str) -> usize {
         let trimmed = line.trim();
 
         if self.in_block_comment {
@@ -87,6 +114,19 @@ impl PowerShellProcessor {
     }
 }
 
+/// 行末の `@"` / `@'` がコメントの外にあれば、その引用符を返す
+fn here_string_start(line: &str) -> Option<char> {
+    let code = line.trim_end();
+    let quote = code
+        .strip_suffix("@\"")
+        .map(|_| '"')
+        .or_else(|| code.strip_suffix("@'").map(|_| '\''))?;
+    let start = code.len() - 2;
+    find_hash_outside_string(&code[..start])
+        .is_none()
+        .then_some(quote)
+}
+
 fn find_block_comment_start(line: &str) -> Option<usize> {
     let bytes = line.as_bytes();
     let mut in_single = false;
@@ -138,6 +178,24 @@ mod tests {
         assert_eq!(p.process("$y = 2"), 1);
     }
 
+    #[test]
+    fn test_powershell_here_strings() {
+        let mut p = PowerShellProcessor::new();
+        assert_eq!(p.process("$script = @\""), 1);
+        assert_eq!(p.process("# not a comment"), 1);
+        assert_eq!(p.process("<# nor a block comment"), 1);
+        assert_eq!(p.process(""), 0);
+        assert_eq!(p.process("\"@"), 1);
+        assert!(!p.is_in_block_comment());
+        assert_eq!(p.process("# comment"), 0);
+
+        assert_eq!(p.process("Set-Content out.txt -Value @'"), 1);
+        assert_eq!(p.process("  # literal"), 1);
+        assert_eq!(p.process("'@"), 1);
+        assert_eq!(p.process("# comment @'"), 0);
+        assert_eq!(p.process("# still a comment"), 0);
+    }
+
     #[test]
     fn test_powershell_processor_inline_comment() {
         let mut p = PowerShellProcessor::new();
@@ -90,6 +90,8 @@ struct PrefixParseResult {
 pub struct PythonProcessor {
     stack: Vec<PythonScope>,
     line_count: usize,
+    /// Whether the current line is only docstring.
+    doc_line: bool,
 }
 
 impl LineProcessor for PythonProcessor {
@@ -104,6 +106,10 @@ impl LineProcessor for PythonProcessor {
         }
         false
     }
+
+    fn is_doc_line(&self) -> bool {
+        self.doc_line
+    }
 }
 
 impl PythonProcessor {
@@ -116,6 +122,7 @@ impl PythonProcessor {
     /// 行を処理し、SLOCカウント (0 or 1) を返す
     pub fn process(&mut self, line: &str) -> usize {
         let trimmed = line.trim();
+        self.doc_line = self.is_in_block_comment();
 
         // shebang行を除外 (最初の行のみ)
         if trimmed.starts_with("#!") && self.line_count == 0 {
@@ -147,6 +154,7 @@ impl PythonProcessor {
             }
         }
 
+        self.doc_line &= !has_code_token;
         usize::from(has_code_token)
     }
 
@@ -303,7 +311,9 @@ impl PythonProcessor {
         if c == '"' || c == '\'' || is_prefix {
             if let Some(string_state) = Self::try_parse_string_start(chars, c, has_code_token) {
                 self.stack.push(PythonScope::String(string_state.clone()));
-                if !string_state.is_doc_comment {
+                if string_state.is_doc_comment {
+                    self.doc_line = true;
+                } else {
                     has_code_token = true;
                 }
                 return has_code_token;
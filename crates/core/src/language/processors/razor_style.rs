@@ -0,0 +1,139 @@
+// crates/core/src/language/processors/razor_style.rs
+//! Razor (ASP.NET `.cshtml` / Blazor `.razor`) のコメント処理
+//!
+//! - Razor コメント: `@* ... *@` (複数行可)
+//! - HTML コメント: `<!-- ... -->`
+//! - C# コメント: 行頭の `//` と `/* ... */`
+//!
+//! マークアップ中の `//` (URL など) を誤認しないよう、C# コメントは
+//! 行頭にあるものだけを扱います。
+
+use crate::language::processor_trait::LineProcessor;
+
+/// コメントブロックの種類
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Block {
+    /// `@* ... *@`
+    Razor,
+    /// `<!-- ... -->`
+    Html,
+    /// `/* ... */`
+    CSharp,
+}
+
+impl Block {
+    const fn open(self) -> &'static str {
+        match self {
+            Self::Razor => "@*",
+            Self::Html => "<!--",
+            Self::CSharp => "/*",
+        }
+    }
+
+    const fn close(self) -> &'static str {
+        match self {
+            Self::Razor => "*@",
+            Self::Html => "-->",
+            Self::CSharp => "*/",
+        }
+    }
+}
+
+/// Razor プロセッサ
+/// Razor SLOC processor.
+#[derive(Debug, Default)]
+pub struct RazorProcessor {
+    block: Option<Block>,
+}
+
+impl LineProcessor for RazorProcessor {
+    fn process_line(&mut self, line: &str) -> usize {
+        self.process(line)
+    }
+
+    fn reset(&mut self) {
+        self.block = None;
+    }
+
+    fn is_in_block_comment(&self) -> bool {
+        self.block.is_some()
+    }
+}
+
+impl RazorProcessor {
+    #[must_use]
+    /// Creates a new `RazorProcessor`.
+    pub const fn new() -> Self {
+        Self { block: None }
+    }
+
+    /// 行を処理し、SLOCカウント (0 or 1) を返す
+    /// Processes a line and returns the SLOC count.
+    pub fn process(&mut self, line: &str) -> usize {
+        let mut rest = line;
+        let mut has_code = false;
+
+        loop {
+            if let Some(block) = self.block {
+                let Some(end) = rest.find(block.close()) else {
+                    return usize::from(has_code);
+                };
+                rest = &rest[end + block.close().len()..];
+                self.block = None;
+            }
+
+            let trimmed = rest.trim_start();
+            if !has_code {
+                if trimmed.starts_with("//") {
+                    return 0;
+                }
+                if let Some(after) = trimmed.strip_prefix("/*") {
+                    self.block = Some(Block::CSharp);
+                    rest = after;
+                    continue;
+                }
+            }
+
+            let next = [Block::Razor, Block::Html]
+                .into_iter()
+                .filter_map(|block| rest.find(block.open()).map(|pos| (block, pos)))
+                .min_by_key(|&(_, pos)| pos);
+            let Some((block, pos)) = next else {
+                return usize::from(has_code || !rest.trim().is_empty());
+            };
+            has_code |= !rest[..pos].trim().is_empty();
+            self.block = Some(block);
+            rest = &rest[pos + block.open().len()..];
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_razor_comments() {
+        let mut p = RazorProcessor::new();
+        assert_eq!(p.process("@* single line *@"), 0);
+        assert_eq!(p.process("<p>@Model.Name</p> @* trailing *@"), 1);
+        assert_eq!(p.process("@* leading *@ <p>text</p>"), 1);
+        assert_eq!(p.process("@*"), 0);
+        assert!(p.is_in_block_comment());
+        assert_eq!(p.process("  <p>commented out</p>"), 0);
+        assert_eq!(p.process("*@"), 0);
+        assert_eq!(p.process("<!-- html -->"), 0);
+    }
+
+    #[test]
+    fn test_razor_code_block() {
+        let mut p = RazorProcessor::new();
+        assert_eq!(p.process("@code {"), 1);
+        assert_eq!(p.process("    // C# comment"), 0);
+        assert_eq!(p.process("    /* block"), 0);
+        assert_eq!(p.process("       comment */"), 0);
+        assert_eq!(p.process("    private int count = 0;"), 1);
+        assert_eq!(p.process("}"), 1);
+        assert_eq!(p.process("<a href=\"https://example.com\">link</a>"), 1);
+    }
+}
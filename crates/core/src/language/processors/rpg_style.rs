@@ -0,0 +1,110 @@
+// crates/core/src/language/processors/rpg_style.rs
+//! RPG言語のコメント処理
+//!
+//! RPG固有の対応:
+//! - 固定形式: カラム7 が `*` の行
+//! - 自由形式 (RPG IV): トリム後 `//` で始まる行
+//! - 1行目が `**FREE` のファイルは完全自由形式とし、カラム7の判定を行わない
+//!
+//! カラム1-5 (一連番号) とカラム6 (仕様書タイプ) だけの行は空行とみなします。
+
+use crate::language::processor_trait::LineProcessor;
+
+/// 固定形式のコメント標識の位置 (0始まり)
+const COMMENT_COLUMN: usize = 6;
+/// 仕様書タイプの位置 (0始まり)
+const SPEC_TYPE_COLUMN: usize = 5;
+
+/// RPGプロセッサ
+/// RPG SLOC processor.
+#[derive(Default, Clone, Debug)]
+pub struct RpgProcessor {
+    /// `**FREE` で始まる完全自由形式のファイル
+    free: bool,
+    line_count: usize,
+}
+
+impl LineProcessor for RpgProcessor {
+    fn process_line(&mut self, line: &str) -> usize {
+        self.process(line)
+    }
+
+    fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    fn is_in_block_comment(&self) -> bool {
+        false
+    }
+}
+
+impl RpgProcessor {
+    /// 新しいRPGプロセッサを作成
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            free: false,
+            line_count: 0,
+        }
+    }
+
+    /// 行を処理し、SLOCカウント (0 or 1) を返す
+    pub fn process(&mut self, line: &str) -> usize {
+        self.line_count += 1;
+        if self.line_count == 1 && line.trim_end().eq_ignore_ascii_case("**free") {
+            self.free = true;
+            return 0;
+        }
+
+        let code = if self.free {
+            line
+        } else {
+            if line.chars().nth(COMMENT_COLUMN) == Some('*') {
+                return 0;
+            }
+            // 一連番号を除いた仕様書タイプ以降
+            line.char_indices()
+                .nth(SPEC_TYPE_COLUMN)
+                .map_or("", |(i, _)| &line[i..])
+        };
+
+        let trimmed = code.trim();
+        if trimmed.is_empty() || trimmed.starts_with("//") {
+            return 0;
+        }
+        // 仕様書タイプだけの行 (`     C`) は空行
+        if !self.free && trimmed.len() == 1 && code.starts_with(trimmed) {
+            return 0;
+        }
+        1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rpg_fixed_format() {
+        let mut p = RpgProcessor::new();
+        assert_eq!(p.process("     H DFTACTGRP(*NO)"), 1);
+        assert_eq!(p.process("     C* comment in column 7"), 0);
+        assert_eq!(p.process("00010 * comment with sequence number"), 0);
+        assert_eq!(p.process("     C                   EVAL      X = X + 1"), 1);
+        assert_eq!(p.process("     C"), 0);
+        assert_eq!(p.process("       // free-form comment"), 0);
+        assert_eq!(p.process("       dcl-s count int(10); // trailing"), 1);
+    }
+
+    #[test]
+    fn test_rpg_fully_free() {
+        let mut p = RpgProcessor::new();
+        assert_eq!(p.process("**FREE"), 0);
+        assert_eq!(p.process("// comment"), 0);
+        assert_eq!(p.process("dcl-s x int(10);"), 1);
+        assert_eq!(p.process("      *inlr = *on;"), 1);
+
+        p.reset();
+        assert_eq!(p.process("      *inlr = *on;"), 0);
+    }
+}
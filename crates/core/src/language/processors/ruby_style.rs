@@ -302,6 +302,29 @@ mod tests {
         assert_eq!(p.process("B"), 1);
     }
 
+    #[test]
+    fn test_ruby_heredoc_hides_comments() {
+        let mut p = RubyProcessor::default();
+        assert_eq!(p.process("  sql = <<~'SQL'.strip"), 1);
+        assert_eq!(p.process("    # not a comment"), 1);
+        assert_eq!(p.process("    <<~INNER"), 1);
+        assert_eq!(p.process("=begin"), 1);
+        assert_eq!(p.process("    INNER"), 1);
+        assert_eq!(p.process("  SQL"), 1);
+        assert_eq!(p.process("  # comment"), 0);
+    }
+
+    #[test]
+    fn test_ruby_stacked_squiggly_heredocs() {
+        let mut p = RubyProcessor::default();
+        assert_eq!(p.process("  call(<<~A, <<-B) # trailing comment"), 1);
+        assert_eq!(p.process("    # a"), 1);
+        assert_eq!(p.process("  A"), 1);
+        assert_eq!(p.process("    # b"), 1);
+        assert_eq!(p.process("  B"), 1);
+        assert_eq!(p.process("  # comment"), 0);
+    }
+
     #[test]
     fn test_ruby_multiline_string_content() {
         let mut p = RubyProcessor::default();
@@ -6,9 +6,12 @@
 //! - ヒアドキュメント: `<<EOF`, `<<-EOF`, `<<'EOF'`, `<<"EOF"`
 //!
 //! ヒアドキュメント内の `#` はコメントとして扱われません。
+//! 1行に複数のヒアドキュメントがある場合は開始順に終了を待ちます。
+//! ヒアストリング `<<<` と算術式のシフト (`$((1 << 2))`) は開始とみなしません。
 
 use alloc::boxed::Box;
 use alloc::string::{String, ToString};
+use alloc::vec::Vec;
 use once_cell::race::OnceBox;
 use regex::Regex;
 
@@ -17,7 +20,7 @@ static HEREDOC_RE: OnceBox<Regex> = OnceBox::new();
 fn get_heredoc_re() -> &'static Regex {
     HEREDOC_RE.get_or_init(|| {
         Box::new(
-            Regex::new(r"<<(-?)[ \t]*(?:([^ \t\x22'>|&;]+)|'([^']+)'|\x22([^\x22]+)\x22)")
+            Regex::new(r"<<(-?)[ \t]*(?:([^ \t\x22'<>|&;()]+)|'([^']+)'|\x22([^\x22]+)\x22)")
                 .expect("Heredoc regex compilation failed"),
         )
     })
@@ -98,10 +101,8 @@ impl ShellProcessor {
 
         // ヒアドキュメント検出 (<<[-] ["']?WORD["']?)
 
-        if let Some(captures) = Self::find_heredoc_start(effective_line) {
-            let allow_indent = captures.allow_indent;
-            let ident = captures.ident;
-            self.heredoc_ctx.push(ident, allow_indent);
+        for start in Self::find_heredoc_starts(effective_line) {
+            self.heredoc_ctx.push(start.ident, start.allow_indent);
         }
 
         // 4. Comment check (# inline)
@@ -121,31 +122,41 @@ impl ShellProcessor {
 
         1
     }
-    fn find_heredoc_start(line: &str) -> Option<HeredocStart> {
+    fn find_heredoc_starts(line: &str) -> Vec<HeredocStart> {
         // Regex pattern using alternation to avoid backreferences:
-        // <<(-?)\s*(?:([^\s"'>|&;]+)|'([^']+)'|"([^"]+)")
+        // <<(-?)\s*(?:([^\s"'<>|&;()]+)|'([^']+)'|"([^"]+)")
 
-        // 見つかった位置が文字列内かチェック
+        let mut starts = Vec::new();
         for caps in get_heredoc_re().captures_iter(line) {
-            if let Some(matches) = caps.get(0) {
-                let start = matches.start();
-                if !is_inside_string(line, start) {
-                    let allow_indent = caps.get(1).is_some_and(|m| m.as_str() == "-");
-                    let ident = caps
-                        .get(2)
-                        .or_else(|| caps.get(3))
-                        .or_else(|| caps.get(4))
-                        .unwrap()
-                        .as_str()
-                        .to_string();
-                    return Some(HeredocStart {
-                        ident,
-                        allow_indent,
-                    });
-                }
+            let Some(matches) = caps.get(0) else {
+                continue;
+            };
+            let start = matches.start();
+            // ヒアストリング `<<<` と文字列内の `<<` は除外
+            if line[..start].ends_with('<') || is_inside_string(line, start) {
+                continue;
             }
+            // 数字で始まる識別子は算術シフト (`1 << 2`) とみなす
+            if caps
+                .get(2)
+                .is_some_and(|m| m.as_str().starts_with(|c: char| c.is_ascii_digit()))
+            {
+                continue;
+            }
+            let allow_indent = caps.get(1).is_some_and(|m| m.as_str() == "-");
+            let ident = caps
+                .get(2)
+                .or_else(|| caps.get(3))
+                .or_else(|| caps.get(4))
+                .unwrap()
+                .as_str()
+                .to_string();
+            starts.push(HeredocStart {
+                ident,
+                allow_indent,
+            });
         }
-        None
+        starts
     }
 }
 
@@ -268,4 +279,24 @@ mod tests {
         assert_eq!(p.process("echo '<<EOF'"), 1);
         assert_eq!(p.process("# comment"), 0); // should be comment
     }
+
+    #[test]
+    fn test_shell_here_string_and_shift() {
+        let mut p = ShellProcessor::new();
+        assert_eq!(p.process("grep foo <<< \"$text\""), 1);
+        assert_eq!(p.process("read -r x <<<$line"), 1);
+        assert_eq!(p.process("echo $((1 << 2))"), 1);
+        assert_eq!(p.process("# comment"), 0);
+    }
+
+    #[test]
+    fn test_shell_stacked_heredocs() {
+        let mut p = ShellProcessor::new();
+        assert_eq!(p.process("paste <(cat <<A) <(cat <<-'B') # two docs"), 1);
+        assert_eq!(p.process("# a"), 1);
+        assert_eq!(p.process("A"), 1);
+        assert_eq!(p.process("\t# b"), 1);
+        assert_eq!(p.process("\tB"), 1);
+        assert_eq!(p.process("# comment"), 0);
+    }
 }
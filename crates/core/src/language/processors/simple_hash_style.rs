@@ -17,6 +17,11 @@ impl LineProcessor for SimpleHashProcessor {
     fn process_line(&mut self, line: &str) -> usize {
         self.process(line)
     }
+
+    // Only the first line is special (a shebang), and it's a comment anyway.
+    fn is_neutral(&self) -> bool {
+        true
+    }
 }
 
 impl SimpleHashProcessor {
@@ -82,6 +82,10 @@ impl LineProcessor for SimplePrefixProcessor {
     fn is_in_block_comment(&self) -> bool {
         false
     }
+
+    fn is_neutral(&self) -> bool {
+        true
+    }
 }
 
 impl SimplePrefixProcessor {
@@ -24,6 +24,10 @@ impl LineProcessor for SqlProcessor {
     fn is_in_block_comment(&self) -> bool {
         self.in_block_comment
     }
+
+    fn is_neutral(&self) -> bool {
+        !self.in_block_comment
+    }
 }
 
 impl SqlProcessor {
@@ -208,6 +208,25 @@ impl StringSkipOptions {
         Self::default().with_flag(Self::DOUBLE_QUOTE)
     }
 
+    /// Nim 用オプション
+    ///
+    /// Nim は `"""..."""` の複数行文字列と `'c'` の文字リテラルを持つ
+    #[must_use]
+    pub fn nim() -> Self {
+        Self::default()
+            .with_flag(Self::TEXT_BLOCK)
+            .with_flag(Self::DOUBLE_QUOTE)
+            .with_flag(Self::SINGLE_QUOTE)
+    }
+
+    /// Gleam 用オプション
+    ///
+    /// Gleam の文字列は `"..."` のみ (文字リテラルなし)
+    #[must_use]
+    pub fn gleam() -> Self {
+        Self::default().with_flag(Self::DOUBLE_QUOTE)
+    }
+
     /// Dart 用オプション
     ///
     /// Dart はバッククォートなし、三重クォートあり
@@ -256,8 +275,14 @@ impl StringSkipOptions {
             // Java/Kotlin/Scala/Groovy
             "java" | "kt" | "kts" | "scala" | "sc" | "groovy" | "gradle" => Self::java_kotlin(),
 
-            // Go (バッククォート対応、正規表現リテラルなし)
-            "go" => Self::go(),
+            // Go / Odin (バッククォートの raw 文字列) / V (バッククォートの rune)
+            "go" | "odin" | "vv" | "vsh" => Self::go(),
+
+            // Nim (三重引用符対応)
+            "nim" | "nims" | "nimble" => Self::nim(),
+
+            // Gleam (二重引用符のみ)
+            "gleam" => Self::gleam(),
 
             // JavaScript/TypeScript (バッククォート + 正規表現リテラル対応)
             "js" | "mjs" | "cjs" | "jsx" | "ts" | "tsx" | "mts" | "cts" => Self::javascript(),
@@ -275,7 +300,7 @@ impl StringSkipOptions {
             "v" | "sv" | "svh" => Self::verilog(),
 
             // Ruby (正規表現リテラル対応)
-            "rb" | "rake" | "gemspec" | "podspec" | "jbuilder" | "erb" => Self::ruby(),
+            "rb" | "rake" | "gemspec" | "podspec" | "jbuilder" | "erb" | "cr" => Self::ruby(),
 
             // Perl (正規表現リテラル対応)
             "pl" | "pm" | "t" | "psgi" => Self::perl(),
@@ -295,6 +295,25 @@ mod suite {
             );
         }
 
+        #[test]
+        fn test_newer_language_options() {
+            assert_eq!(
+                StringSkipOptions::from_extension("odin"),
+                StringSkipOptions::go()
+            );
+            assert_eq!(
+                StringSkipOptions::from_extension("VV"),
+                StringSkipOptions::go()
+            );
+            assert_eq!(
+                StringSkipOptions::from_extension("cr"),
+                StringSkipOptions::ruby()
+            );
+            assert!(StringSkipOptions::from_extension("nim").text_block());
+            let gleam = StringSkipOptions::from_extension("gleam");
+            assert!(gleam.double_quote() && !gleam.single_quote());
+        }
+
         #[test]
         fn test_rust_options() {
             let options = StringSkipOptions::rust();
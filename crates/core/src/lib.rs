@@ -29,6 +29,7 @@
 //! ## Architecture
 //!
 //! - [`counter`]: Main entry point (`count_bytes`).
+//! - [`fingerprint`]: Normalized line hashes (`line_fingerprints`).
 //! - [`language`]: Language-specific SLOC processors.
 //! - [`stats`]: Statistical data structures.
 //! - [`config`]: Configuration options.
@@ -41,6 +42,8 @@ extern crate alloc;
 pub mod config;
 /// Main counting entry point.
 pub mod counter;
+/// Normalized line fingerprints for near-duplicate detection.
+pub mod fingerprint;
 /// Language-specific SLOC processors.
 pub mod language;
 /// Statistical result types.
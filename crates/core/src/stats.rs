@@ -1,8 +1,57 @@
 // crates/core/src/stats.rs
+use alloc::string::String;
+use alloc::vec::Vec;
 use serde::{Deserialize, Serialize};
 
-/// Pure analysis result, independent of file system metadata.
+/// Lines of one language embedded in a container file, e.g. the `<script>`
+/// sections of an HTML page or a Vue component.
 #[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EmbeddedCount {
+    /// Display name of the embedded language (see
+    /// [`language_name`](crate::language::language_name)).
+    pub language: String,
+    /// Lines inside the sections, excluding the opening and closing tags.
+    pub lines: usize,
+    /// Source lines of code inside the sections.
+    pub sloc: usize,
+}
+
+/// Whitespace and line-ending findings of one file.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WhitespaceCounts {
+    /// Lines ending in spaces or tabs.
+    pub trailing_whitespace: usize,
+    /// Lines with a space before a tab in their indentation, plus the lines
+    /// indented in the file's less common style (tabs or spaces).
+    pub mixed_indent: usize,
+    /// Lines ending in `\r\n`.
+    pub crlf: usize,
+    /// Lines ending in a bare `\n`.
+    pub lf: usize,
+    /// Whether the last line has no line break.
+    pub missing_final_newline: bool,
+}
+
+impl WhitespaceCounts {
+    /// Whether the file ends lines both with `\r\n` and `\n`.
+    #[must_use]
+    pub const fn mixed_line_endings(&self) -> bool {
+        self.crlf > 0 && self.lf > 0
+    }
+
+    /// Whether anything was found: trailing whitespace, mixed indentation,
+    /// mixed line endings or a missing final newline.
+    #[must_use]
+    pub const fn has_issues(&self) -> bool {
+        self.trailing_whitespace > 0
+            || self.mixed_indent > 0
+            || self.mixed_line_endings()
+            || self.missing_final_newline
+    }
+}
+
+/// Pure analysis result, independent of file system metadata.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
 pub struct AnalysisResult {
     /// Number of lines.
     pub lines: usize,
@@ -12,8 +61,32 @@ pub struct AnalysisResult {
     pub words: Option<usize>,
     /// Source Lines of Code (if counted).
     pub sloc: Option<usize>,
+    /// Length of the longest line in characters, excluding the line break.
+    pub max_line_length: usize,
+    /// Average line length in characters, excluding line breaks.
+    pub avg_line_length: f64,
+    /// Lines holding only documentation comments (`///`, `/** */`,
+    /// docstrings), for the languages whose processor tells them apart.
+    #[serde(default)]
+    pub doc_lines: usize,
+    /// Deepest indentation level of a non-blank line, when measured.
+    #[serde(default)]
+    pub max_indent_depth: usize,
+    /// Average indentation level of the non-blank lines, when measured.
+    #[serde(default)]
+    pub avg_indent_depth: f64,
+    /// Whitespace findings, when audited.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub whitespace: Option<WhitespaceCounts>,
+    /// Lines over [`AnalysisConfig::long_line_threshold`](crate::config::AnalysisConfig::long_line_threshold)
+    /// bytes, scanned without the language processor.
+    #[serde(default)]
+    pub long_lines: usize,
     /// Whether the content was detected as binary.
     pub is_binary: bool,
+    /// Per-language totals of embedded sections, in order of appearance.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub embedded: Vec<EmbeddedCount>,
 }
 
 impl AnalysisResult {
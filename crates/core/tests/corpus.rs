@@ -0,0 +1,143 @@
+// crates/core/tests/corpus.rs
+//! Conformance suite of the SLOC processors against annotated samples.
+//!
+//! Run with `cargo test -p count_lines_core --features corpus`.
+//!
+//! Every file under `tests/corpus/<language>/` is a source sample whose
+//! lines start with `+` (counted as SLOC) or `-` (a comment or blank line),
+//! followed by a space and the source line. The directory is the language
+//! name of the file's extension in lower case, with spaces replaced by `-`.
+#![cfg(feature = "corpus")]
+
+use count_lines_core::config::AnalysisConfig;
+use count_lines_core::counter::count_bytes;
+use count_lines_core::language::{get_processor, language_name};
+use hashbrown::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+struct Sample {
+    path: PathBuf,
+    extension: String,
+    /// Whether each line is SLOC, and the line without its marker.
+    lines: Vec<(bool, String)>,
+}
+
+fn corpus_root() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/corpus")
+}
+
+fn load_samples() -> Vec<Sample> {
+    let mut paths = Vec::new();
+    for dir in fs::read_dir(corpus_root()).expect("tests/corpus exists") {
+        let dir = dir.unwrap().path();
+        for file in fs::read_dir(&dir).unwrap() {
+            paths.push(file.unwrap().path());
+        }
+    }
+    paths.sort();
+
+    paths
+        .into_iter()
+        .map(|path| {
+            let text = fs::read_to_string(&path).unwrap();
+            let lines = text
+                .lines()
+                .enumerate()
+                .map(|(i, line)| {
+                    let (marker, rest) = line.split_at_checked(1).unwrap_or(("", ""));
+                    let source = rest.strip_prefix(' ').unwrap_or(rest).to_string();
+                    match marker {
+                        "+" => (true, source),
+                        "-" => (false, source),
+                        _ => panic!("{}:{}: line must start with + or -", path.display(), i + 1),
+                    }
+                })
+                .collect();
+            let extension = path
+                .extension()
+                .and_then(|e| e.to_str())
+                .unwrap_or_default()
+                .to_string();
+            Sample {
+                path,
+                extension,
+                lines,
+            }
+        })
+        .collect()
+}
+
+fn relative(path: &Path) -> String {
+    path.strip_prefix(corpus_root())
+        .unwrap_or(path)
+        .display()
+        .to_string()
+}
+
+#[test]
+fn test_samples_are_filed_by_language() {
+    let samples = load_samples();
+    assert!(!samples.is_empty());
+    for sample in &samples {
+        let dir = sample.path.parent().unwrap().file_name().unwrap();
+        let language = language_name(&sample.extension)
+            .unwrap_or_else(|| panic!("{}: unknown extension", relative(&sample.path)));
+        assert_eq!(
+            dir.to_str().unwrap(),
+            language.to_lowercase().replace(' ', "-"),
+            "{}",
+            relative(&sample.path)
+        );
+    }
+}
+
+#[test]
+fn test_processors_match_annotations() {
+    let mut failures = Vec::new();
+    for sample in load_samples() {
+        let mut processor = get_processor(&sample.extension, &HashMap::new());
+        for (i, (expected, source)) in sample.lines.iter().enumerate() {
+            let counted = processor.process_line(source) > 0;
+            if counted != *expected {
+                failures.push(format!(
+                    "{}:{}: expected {}, counted {}: {source}",
+                    relative(&sample.path),
+                    i + 1,
+                    if *expected { "+" } else { "-" },
+                    if counted { "+" } else { "-" },
+                ));
+            }
+        }
+    }
+    assert!(
+        failures.is_empty(),
+        "{} corpus lines differ:\n{}",
+        failures.len(),
+        failures.join("\n")
+    );
+}
+
+#[test]
+fn test_file_totals_match_annotations() {
+    let config = AnalysisConfig {
+        count_sloc: true,
+        ..AnalysisConfig::default()
+    };
+    for sample in load_samples() {
+        let content: String = sample
+            .lines
+            .iter()
+            .map(|(_, source)| format!("{source}\n"))
+            .collect();
+        let stats = count_bytes(content.as_bytes(), &sample.extension, &config);
+        let expected = sample.lines.iter().filter(|(sloc, _)| *sloc).count();
+        assert_eq!(
+            stats.lines,
+            sample.lines.len(),
+            "{}",
+            relative(&sample.path)
+        );
+        assert_eq!(stats.sloc, Some(expected), "{}", relative(&sample.path));
+    }
+}
@@ -0,0 +1,8 @@
++ @echo off
+- REM Build script
+- :: Another comment
++ if exist out (
+-     :: inside the block
++     echo cleaning
++ )
++ echo done & rem trailing
@@ -0,0 +1,17 @@
+- /*
+-  * Ring buffer.
+-  */
++ #include <stdio.h>
+-
++ static const char *DIVIDER = "/* ---- */";
+-
++ int next(int i, int size) {
+-     // wrap around
++     return (i + 1) % size; /* modulo */
++ }
+-
+- /* single line block */
++ int main(void) {
++     printf("%s // %d\n", DIVIDER, next(3, 4));
++     return 0;
++ }
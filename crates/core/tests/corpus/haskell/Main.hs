@@ -0,0 +1,8 @@
+- -- | Entry point.
++ module Main where
+-
+- {- block
+-    {- nested -}
+-    comment -}
++ main :: IO ()
++ main = putStrLn "-- not a comment" -- trailing
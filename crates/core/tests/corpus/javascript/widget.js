@@ -0,0 +1,13 @@
+- // Widget helpers
++ const PATTERN = /\/\*.*\*\//g;
++ const url = "http://example.com";
+-
+- /**
+-  * Renders the widget.
+-  */
++ export function render(items) {
++   const html = `<ul>${items.map((i) => `<li>${i}</li>`).join("")}</ul>`;
++   return html; // returned as a string
++ }
+-
++ const ratio = total / count / 2;
@@ -0,0 +1,9 @@
+- -- Configuration
++ local config = {
++   name = "-- not a comment",
++ }
+-
+- --[[
+-   Multi-line comment
+- ]]
++ return config
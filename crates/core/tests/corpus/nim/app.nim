@@ -0,0 +1,10 @@
+- ## Application entry.
++ import strutils
+-
+- #[ block
+-   #[ nested ]#
+- ]#
++ let doc = """
++ # not a comment
++ """
++ echo doc.strip() # trailing
@@ -0,0 +1,16 @@
++ use strict;
++ use warnings;
+-
+- # Parse the input.
++ my $text = <<~"END";
++     # part of the text
++     END
+-
+- =pod
+-
+- Documentation.
+-
+- =cut
+-
++ my $shift = 1 << 3;
++ print "# $text\n"; # trailing
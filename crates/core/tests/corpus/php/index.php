@@ -0,0 +1,12 @@
++ <?php
+- // Entry point
++ $sql = <<<SQL
++     -- not a comment
++     # still text
++     SQL;
+-
+- /* block
+-    comment */
++ function greet(string $name): string {
++     return "Hello, {$name}"; # inline
++ }
@@ -0,0 +1,10 @@
+- # Build script
++ $script = @"
++ # not a comment
++ "@
+-
+- <#
+-   .SYNOPSIS
+-   Builds the project.
+- #>
++ Write-Host "#1" # trailing
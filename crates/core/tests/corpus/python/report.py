@@ -0,0 +1,20 @@
+- #!/usr/bin/env python3
+- # -*- coding: utf-8 -*-
+- """Module docstring.
+-
+- # not a comment inside the docstring
+- """
+-
++ import sys
+-
+-
++ def main(argv):
+-     """Print the arguments."""
++     for arg in argv:  # every argument
++         print("# %s" % arg)
+-     # done
++     return 0
+-
+-
++ if __name__ == "__main__":
++     sys.exit(main(sys.argv[1:]))
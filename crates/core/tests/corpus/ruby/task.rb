@@ -0,0 +1,19 @@
+- # frozen_string_literal: true
+-
++ require "json"
+-
+- # A task runner.
++ class Task
++   TEMPLATE = <<~SQL
++     # not a comment
++     SELECT 1;
++   SQL
+-
+- =begin
+- Documentation block
+- =end
+-
++   def run
++     puts "#{name} # done" # trailing
++   end
++ end
@@ -0,0 +1,31 @@
+- //! A tiny lexer.
+-
++ use std::fmt;
+-
+- /// A token of the input.
++ #[derive(Debug)]
++ pub enum Token<'a> {
++     Ident(&'a str),
+-     // Strings keep their quotes.
++     Str(&'a str),
++ }
+-
+- /* The pattern below is not a comment: */
++ const PATTERN: &str = r#"/* // "#;
++ const SLASHES: &str = "// not a comment";
+-
++ fn label(c: char) -> &'static str {
++     match c {
++         '/' => "slash", // inline comment
++         _ => "other",
++     }
++ }
+-
+- /* outer
+-    /* nested */
+-    still a comment */
++ impl fmt::Display for Token<'_> {
++     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
++         write!(f, "{self:?}") /* trailing */
++     }
++ }
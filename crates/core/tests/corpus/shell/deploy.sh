@@ -0,0 +1,13 @@
+- #!/bin/sh
+- # Deploy the site.
+-
++ set -eu
++ count=$((1 << 2))
++ grep "#" <<< "$count"
+-
++ cat <<-EOF > config.ini
++ 	# not a comment
++ 	name=site
++ 	EOF
+-
++ echo "# done" # comment
@@ -0,0 +1,8 @@
+- -- Monthly report
++ SELECT id,
++        name -- trailing
++   FROM users
+- /* filter
+-    inactive */
++  WHERE active = 1
++    AND note <> '-- not a comment';
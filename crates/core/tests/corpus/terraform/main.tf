@@ -0,0 +1,10 @@
+- # Main configuration
++ resource "aws_instance" "web" {
++   ami = "ami-123" // trailing
+-   /* tags
+-      below */
++   user_data = <<-EOT
++     #!/bin/bash
++     echo "// hi"
++   EOT
++ }
@@ -0,0 +1,16 @@
++ import { useState } from "react";
+-
+- // A counter component.
++ export function Counter(): JSX.Element {
++   const [count, setCount] = useState<number>(0);
++   return (
++     <div>
+-       {/* The label */}
++       <span>{count}</span>
+-       {/*
+-         <button>old</button>
+-       */}
++       <button onClick={() => setCount(count + 1)}>+</button>
++     </div>
++   );
++ }
@@ -0,0 +1,198 @@
+//! High-level facade for embedding the counter in other tools.
+//!
+//! [`CountPaths`] hides the walk/filter/config plumbing behind a builder and
+//! returns typed results, so callers don't need `clap` or the CLI `Config`
+//! conversion.
+//!
+//! # Example
+//!
+//! ```rust
+//! use count_lines_engine::CountPaths;
+//!
+//! let dir = tempfile::tempdir()?;
+//! std::fs::write(dir.path().join("main.rs"), "fn main() {}\n// done\n")?;
+//!
+//! let output = CountPaths::builder()
+//!     .paths([dir.path()])
+//!     .sloc(true)
+//!     .run()?;
+//!
+//! assert_eq!(output.summary.files, 1);
+//! assert_eq!(output.summary.lines, 2);
+//! assert_eq!(output.summary.sloc, Some(1));
+//! assert!(output.errors.is_empty());
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+use crate::config::{Config, FilterConfig, WalkOptions};
+use crate::error::{EngineError, Result};
+use crate::stats::{FileStats, Summary};
+use derive_builder::Builder;
+use std::path::PathBuf;
+
+/// A configured counting run over one or more paths.
+#[derive(Debug, Clone, Builder)]
+#[builder(setter(into), build_fn(error = "EngineError"))]
+pub struct CountPaths {
+    /// Files or directories to scan. Defaults to the current directory.
+    #[builder(setter(custom), default)]
+    paths: Vec<PathBuf>,
+    /// Path, extension, size and count filters.
+    #[builder(default)]
+    filters: FilterConfig,
+    /// Count words.
+    #[builder(default)]
+    words: bool,
+    /// Count SLOC.
+    #[builder(default)]
+    sloc: bool,
+    /// Include newline characters in the character count.
+    #[builder(default)]
+    count_newlines_in_chars: bool,
+    /// Number of walker threads.
+    #[builder(default = "num_cpus::get()")]
+    threads: usize,
+    /// Include hidden files.
+    #[builder(default)]
+    hidden: bool,
+    /// Honour `.gitignore` files.
+    #[builder(default = "true")]
+    git_ignore: bool,
+    /// Follow symbolic links.
+    #[builder(default)]
+    follow_links: bool,
+    /// Maximum directory depth.
+    #[builder(default)]
+    max_depth: Option<usize>,
+    /// Fail on the first unreadable file instead of collecting errors.
+    #[builder(default)]
+    strict: bool,
+}
+
+/// Typed result of a [`CountPaths`] run.
+#[derive(Debug, Default)]
+pub struct CountOutput {
+    /// Statistics for every counted text file, sorted by path.
+    ///
+    /// Binary files are excluded, matching the CLI output.
+    pub files: Vec<FileStats>,
+    /// Totals over `files`.
+    pub summary: Summary,
+    /// Files that could not be processed (non-strict mode only).
+    pub errors: Vec<(PathBuf, EngineError)>,
+}
+
+impl CountPaths {
+    /// Starts building a counting run.
+    #[must_use]
+    pub fn builder() -> CountPathsBuilder {
+        CountPathsBuilder::default()
+    }
+
+    /// Returns the engine configuration this run will use.
+    #[must_use]
+    pub fn config(&self) -> Config {
+        let roots = if self.paths.is_empty() {
+            vec![PathBuf::from(".")]
+        } else {
+            self.paths.clone()
+        };
+
+        Config {
+            walk: WalkOptions {
+                roots,
+                threads: self.threads.max(1),
+                hidden: self.hidden,
+                git_ignore: self.git_ignore,
+                max_depth: self.max_depth,
+                follow_links: self.follow_links,
+                ..WalkOptions::default()
+            },
+            filter: self.filters.clone(),
+            count_words: self.words
+                || self.filters.min_words.is_some()
+                || self.filters.max_words.is_some(),
+            count_sloc: self.sloc,
+            count_newlines_in_chars: self.count_newlines_in_chars,
+            strict: self.strict,
+            ..Config::default()
+        }
+    }
+
+    /// Runs the count.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the walk cannot start (e.g. an unsafe root path or
+    /// an invalid pattern), or on the first file error in strict mode.
+    pub fn run(&self) -> Result<CountOutput> {
+        let result = crate::run(&self.config())?;
+
+        let mut files: Vec<FileStats> = result.stats.into_iter().filter(|s| !s.is_binary).collect();
+        files.sort_by(|a, b| a.path.cmp(&b.path));
+        let summary = Summary::from_stats(&files);
+
+        Ok(CountOutput {
+            files,
+            summary,
+            errors: result.errors,
+        })
+    }
+}
+
+impl CountPathsBuilder {
+    /// Sets the files or directories to scan.
+    pub fn paths<I, P>(&mut self, paths: I) -> &mut Self
+    where
+        I: IntoIterator<Item = P>,
+        P: Into<PathBuf>,
+    {
+        self.paths = Some(paths.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Builds the run and executes it.
+    ///
+    /// # Errors
+    ///
+    /// See [`CountPaths::run`].
+    pub fn run(&self) -> Result<CountOutput> {
+        self.build()?.run()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::FilterConfigBuilder;
+
+    #[test]
+    fn test_defaults_to_current_dir() {
+        let run = CountPaths::builder().build().unwrap();
+        assert_eq!(run.config().walk.roots, vec![PathBuf::from(".")]);
+        assert!(run.config().walk.git_ignore);
+    }
+
+    #[test]
+    fn test_filters_and_sorting() -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let dir = tempfile::tempdir()?;
+        std::fs::write(dir.path().join("b.rs"), "one two\n")?;
+        std::fs::write(dir.path().join("a.rs"), "x\ny\n")?;
+        std::fs::write(dir.path().join("c.py"), "print(1)\n")?;
+
+        let filters = FilterConfigBuilder::default()
+            .allow_ext(vec!["rs".to_string()])
+            .build()?;
+        let output = CountPaths::builder()
+            .paths([dir.path()])
+            .filters(filters)
+            .words(true)
+            .run()?;
+
+        let names: Vec<_> = output.files.iter().map(|f| f.name.as_str()).collect();
+        assert_eq!(names, ["a.rs", "b.rs"]);
+        assert_eq!(output.summary.lines, 3);
+        assert_eq!(output.summary.words, Some(4));
+        Ok(())
+    }
+}
@@ -0,0 +1,349 @@
+// crates/engine/src/archive.rs
+//! In-memory scanning of `.zip`, `.tar` and `.tar.gz` archives (`--scan-archives`).
+//!
+//! Members are read without extracting anything to disk and are reported as
+//! `archive.zip!inner/path.rs`, so the inner extension selects the language.
+
+use crate::config::Config;
+use crate::error::{EngineError, Result};
+use crate::filesystem::{collect_normalized_exts, matches_ext_and_size};
+use crate::processor;
+use crate::stats::FileStats;
+use std::fs::File;
+use std::io::{BufReader, Read};
+use std::path::{Path, PathBuf};
+
+/// Members larger than this are skipped, so a small archive can't expand
+/// into an unbounded allocation. A member whose header claims less but
+/// whose content turns out larger is reported as an error.
+pub const MAX_MEMBER_SIZE: u64 = 64 * 1024 * 1024;
+
+/// Separator between the archive path and the member path.
+pub const MEMBER_SEPARATOR: char = '!';
+
+/// Archive formats understood by `--scan-archives`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArchiveKind {
+    Zip,
+    Tar,
+    TarGz,
+}
+
+impl ArchiveKind {
+    /// Detects the archive format from the file name.
+    #[must_use]
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let name = path.file_name()?.to_str()?.to_ascii_lowercase();
+        if name.ends_with(".zip") {
+            Some(Self::Zip)
+        } else if name.ends_with(".tar.gz") || name.ends_with(".tgz") {
+            Some(Self::TarGz)
+        } else if name.ends_with(".tar") {
+            Some(Self::Tar)
+        } else {
+            None
+        }
+    }
+}
+
+/// Returns the path reported for `member` inside `archive`.
+#[must_use]
+pub fn member_path(archive: &Path, member: &str) -> PathBuf {
+    PathBuf::from(format!(
+        "{}{MEMBER_SEPARATOR}{}",
+        archive.display(),
+        member.trim_start_matches("./")
+    ))
+}
+
+/// Counts every regular file inside `archive` that passes the extension and
+/// size filters.
+///
+/// Per-member read errors are reported through `emit` like per-file errors
+/// of a normal walk; `mtime` of every member is the archive's.
+///
+/// # Errors
+/// Returns an error if the archive cannot be opened or its index read.
+pub fn scan_archive(
+    archive: &Path,
+    kind: ArchiveKind,
+    mtime: Option<chrono::DateTime<chrono::Local>>,
+    config: &Config,
+    emit: &mut dyn FnMut(Result<FileStats>),
+) -> Result<()> {
+    scan_archive_within(archive, kind, mtime, config, MAX_MEMBER_SIZE, emit)
+}
+
+/// [`scan_archive`] with members capped at `max_member_size` bytes.
+fn scan_archive_within(
+    archive: &Path,
+    kind: ArchiveKind,
+    mtime: Option<chrono::DateTime<chrono::Local>>,
+    config: &Config,
+    max_member_size: u64,
+    emit: &mut dyn FnMut(Result<FileStats>),
+) -> Result<()> {
+    let allow_ext = collect_normalized_exts(&config.filter.allow_ext);
+    let deny_ext = collect_normalized_exts(&config.filter.deny_ext);
+    let wanted = |path: &Path, size: u64| {
+        size <= max_member_size
+            && matches_ext_and_size(path, size, &config.filter, &allow_ext, &deny_ext)
+    };
+    // Name, extension and language come from the member path alone; only
+    // the reported path carries the archive prefix. The declared size may
+    // lie, so the read itself is bounded too.
+    let count = |member: &str, reader: &mut dyn Read| {
+        let path = member_path(archive, member);
+        let mut content = Vec::new();
+        let read = reader
+            .take(max_member_size + 1)
+            .read_to_end(&mut content)
+            .and_then(|n| {
+                if n as u64 > max_member_size {
+                    Err(std::io::Error::new(
+                        std::io::ErrorKind::InvalidData,
+                        format!(
+                            "member is larger than its header says (over {max_member_size} bytes)"
+                        ),
+                    ))
+                } else {
+                    Ok(())
+                }
+            });
+        read.map_err(|source| EngineError::FileRead {
+            path: path.clone(),
+            source,
+        })?;
+        let mut stats = processor::count_content(PathBuf::from(member), &content, config)?;
+        stats.path = path;
+        stats.mtime = mtime;
+        Ok(stats)
+    };
+
+    let file = File::open(archive).map_err(|source| read_error(archive, source))?;
+    match kind {
+        ArchiveKind::Zip => {
+            let mut zip = zip::ZipArchive::new(BufReader::new(file))
+                .map_err(|err| read_error(archive, std::io::Error::other(err)))?;
+            for index in 0..zip.len() {
+                let mut entry = match zip.by_index(index) {
+                    Ok(entry) => entry,
+                    Err(err) => {
+                        emit(Err(read_error(archive, std::io::Error::other(err))));
+                        continue;
+                    }
+                };
+                if !entry.is_file() {
+                    continue;
+                }
+                let name = entry.name().to_string();
+                if wanted(Path::new(&name), entry.size()) {
+                    emit(count(&name, &mut entry));
+                }
+            }
+        }
+        ArchiveKind::Tar => scan_tar(archive, BufReader::new(file), &wanted, &count, emit)?,
+        ArchiveKind::TarGz => scan_tar(
+            archive,
+            flate2::read::GzDecoder::new(BufReader::new(file)),
+            &wanted,
+            &count,
+            emit,
+        )?,
+    }
+    Ok(())
+}
+
+fn scan_tar(
+    archive: &Path,
+    reader: impl Read,
+    wanted: &dyn Fn(&Path, u64) -> bool,
+    count: &dyn Fn(&str, &mut dyn Read) -> Result<FileStats>,
+    emit: &mut dyn FnMut(Result<FileStats>),
+) -> Result<()> {
+    let mut tar = tar::Archive::new(reader);
+    let entries = tar
+        .entries()
+        .map_err(|source| read_error(archive, source))?;
+    for entry in entries {
+        let mut entry = entry.map_err(|source| read_error(archive, source))?;
+        if !entry.header().entry_type().is_file() {
+            continue;
+        }
+        let name = entry
+            .path()
+            .map(|p| p.to_string_lossy().into_owned())
+            .map_err(|source| read_error(archive, source))?;
+        if wanted(Path::new(&name), entry.size()) {
+            emit(count(&name, &mut entry));
+        }
+    }
+    Ok(())
+}
+
+fn read_error(archive: &Path, source: std::io::Error) -> EngineError {
+    EngineError::FileRead {
+        path: archive.to_path_buf(),
+        source,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    fn scan(path: &Path, config: &Config) -> Vec<FileStats> {
+        let kind = ArchiveKind::from_path(path).unwrap();
+        let mut stats = Vec::new();
+        scan_archive(path, kind, None, config, &mut |res| {
+            stats.push(res.unwrap())
+        })
+        .unwrap();
+        stats.sort_by(|a, b| a.path.cmp(&b.path));
+        stats
+    }
+
+    fn tar_bytes() -> Vec<u8> {
+        let mut builder = tar::Builder::new(Vec::new());
+        for (name, content) in [
+            ("src/lib.rs", "fn a() {}\n\nfn b() {}\n"),
+            ("README", "hi\n"),
+        ] {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(content.len() as u64);
+            header.set_mode(0o644);
+            header.set_cksum();
+            builder
+                .append_data(&mut header, name, content.as_bytes())
+                .unwrap();
+        }
+        builder.into_inner().unwrap()
+    }
+
+    #[test]
+    fn test_kind_from_path() {
+        assert_eq!(
+            ArchiveKind::from_path(Path::new("a.ZIP")),
+            Some(ArchiveKind::Zip)
+        );
+        assert_eq!(
+            ArchiveKind::from_path(Path::new("a.tar.gz")),
+            Some(ArchiveKind::TarGz)
+        );
+        assert_eq!(
+            ArchiveKind::from_path(Path::new("a.tgz")),
+            Some(ArchiveKind::TarGz)
+        );
+        assert_eq!(
+            ArchiveKind::from_path(Path::new("a.tar")),
+            Some(ArchiveKind::Tar)
+        );
+        assert_eq!(ArchiveKind::from_path(Path::new("a.gz")), None);
+    }
+
+    #[test]
+    fn test_scan_zip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("release.zip");
+        let mut zip = zip::ZipWriter::new(File::create(&path).unwrap());
+        let options = zip::write::SimpleFileOptions::default();
+        zip.add_directory("src/", options).unwrap();
+        zip.start_file("src/main.rs", options).unwrap();
+        zip.write_all(b"fn main() {}\n// done\n").unwrap();
+        zip.start_file("notes.txt", options).unwrap();
+        zip.write_all(b"a\nb\nc\n").unwrap();
+        zip.finish().unwrap();
+
+        let config = Config {
+            count_sloc: true,
+            ..Config::default()
+        };
+        let stats = scan(&path, &config);
+        assert_eq!(stats.len(), 2);
+        assert_eq!(
+            stats[1].path,
+            PathBuf::from(format!("{}!src/main.rs", path.display()))
+        );
+        assert_eq!(stats[1].ext, "rs");
+        assert_eq!(stats[1].lines, 2);
+        assert_eq!(stats[1].sloc, Some(1));
+    }
+
+    #[test]
+    fn test_scan_tar_gz_honours_ext_filter() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("src.tar.gz");
+        let mut gz = flate2::write::GzEncoder::new(
+            File::create(&path).unwrap(),
+            flate2::Compression::default(),
+        );
+        gz.write_all(&tar_bytes()).unwrap();
+        gz.finish().unwrap();
+
+        let mut config = Config::default();
+        config.filter.allow_ext = vec!["rs".to_string()];
+        let stats = scan(&path, &config);
+        assert_eq!(stats.len(), 1);
+        assert!(
+            stats[0]
+                .path
+                .to_string_lossy()
+                .ends_with("src.tar.gz!src/lib.rs")
+        );
+        assert_eq!(stats[0].lines, 3);
+    }
+
+    #[test]
+    fn test_zip_member_larger_than_declared_is_an_error() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("lying.zip");
+        let mut zip = zip::ZipWriter::new(File::create(&path).unwrap());
+        let options = zip::write::SimpleFileOptions::default()
+            .compression_method(zip::CompressionMethod::Deflated);
+        zip.start_file("big.txt", options).unwrap();
+        zip.write_all(&b"line\n".repeat(1000)).unwrap();
+        zip.finish().unwrap();
+
+        // Claim 10 bytes in the local and central headers.
+        let mut bytes = std::fs::read(&path).unwrap();
+        for (signature, offset) in [(b"PK\x03\x04", 22), (b"PK\x01\x02", 24)] {
+            let at = bytes.windows(4).position(|w| w == signature).unwrap() + offset;
+            bytes[at..at + 4].copy_from_slice(&10u32.to_le_bytes());
+        }
+        std::fs::write(&path, bytes).unwrap();
+
+        let mut results = Vec::new();
+        scan_archive_within(
+            &path,
+            ArchiveKind::Zip,
+            None,
+            &Config::default(),
+            100,
+            &mut |res| results.push(res),
+        )
+        .unwrap();
+        assert_eq!(results.len(), 1);
+        match &results[0] {
+            Err(EngineError::FileRead {
+                path: member,
+                source,
+            }) => {
+                assert!(member.to_string_lossy().ends_with("lying.zip!big.txt"));
+                assert_eq!(source.kind(), std::io::ErrorKind::InvalidData);
+            }
+            other => panic!("expected a read error, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_scan_plain_tar() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("src.tar");
+        std::fs::write(&path, tar_bytes()).unwrap();
+
+        let stats = scan(&path, &Config::default());
+        let names: Vec<_> = stats.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, vec!["README", "lib.rs"]);
+    }
+}
@@ -0,0 +1,254 @@
+// crates/engine/src/attributes.rs
+//! `.gitattributes` lookup for the GitHub Linguist attributes (`--linguist`).
+//!
+//! Only `linguist-vendored` and `linguist-documentation` are read. As in git,
+//! every `.gitattributes` from the repository root down to the file's
+//! directory applies, deeper files and later lines taking precedence.
+
+use globset::{GlobBuilder, GlobMatcher};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+/// Classification of a file by its Linguist attributes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LinguistClass {
+    /// `linguist-vendored`: third-party code checked into the repository.
+    Vendored,
+    /// `linguist-documentation`.
+    Documentation,
+}
+
+impl LinguistClass {
+    const ALL: [Self; 2] = [Self::Vendored, Self::Documentation];
+
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Vendored => "vendored",
+            Self::Documentation => "documentation",
+        }
+    }
+
+    const fn attribute(self) -> &'static str {
+        match self {
+            Self::Vendored => "linguist-vendored",
+            Self::Documentation => "linguist-documentation",
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Rule {
+    matcher: GlobMatcher,
+    settings: Vec<(LinguistClass, bool)>,
+}
+
+/// The `.gitattributes` rules applying to one directory.
+#[derive(Debug)]
+struct Chain {
+    /// Canonical path of the directory.
+    dir: PathBuf,
+    /// Rules of each `.gitattributes` above it, root first, with the
+    /// directory their patterns are relative to.
+    rule_sets: Vec<(PathBuf, Arc<Vec<Rule>>)>,
+}
+
+/// Classifies files by the `.gitattributes` files above them.
+///
+/// Parsed files are cached per directory, so one instance should be shared
+/// by the whole walk.
+#[derive(Debug, Default)]
+pub struct LinguistAttributes {
+    /// Applicable rules per directory, as walked.
+    chains: Mutex<HashMap<PathBuf, Arc<Chain>>>,
+    /// Parsed `.gitattributes` per canonical directory.
+    files: Mutex<HashMap<PathBuf, Arc<Vec<Rule>>>>,
+}
+
+impl LinguistAttributes {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the Linguist class of `path`, or `None` for regular source.
+    /// `linguist-vendored` wins when both attributes are set.
+    #[must_use]
+    pub fn classify(&self, path: &Path) -> Option<LinguistClass> {
+        let dir = path.parent().unwrap_or_else(|| Path::new(""));
+        let name = path.file_name()?;
+        let chain = self.chain(dir);
+
+        let mut state = [false; LinguistClass::ALL.len()];
+        for (base, rules) in &chain.rule_sets {
+            let Ok(relative) = chain.dir.strip_prefix(base) else {
+                continue;
+            };
+            let relative = relative.join(name);
+            for rule in rules.iter().filter(|r| r.matcher.is_match(&relative)) {
+                for &(class, value) in &rule.settings {
+                    state[class as usize] = value;
+                }
+            }
+        }
+        LinguistClass::ALL
+            .into_iter()
+            .find(|&class| state[class as usize])
+    }
+
+    fn chain(&self, dir: &Path) -> Arc<Chain> {
+        if let Some(chain) = lock(&self.chains).get(dir) {
+            return chain.clone();
+        }
+        let canonical = dir.canonicalize().unwrap_or_else(|_| dir.to_path_buf());
+        let mut rule_sets = Vec::new();
+        for ancestor in canonical.ancestors() {
+            let rules = self.rules(ancestor);
+            if !rules.is_empty() {
+                rule_sets.push((ancestor.to_path_buf(), rules));
+            }
+            if ancestor.join(".git").exists() {
+                break;
+            }
+        }
+        rule_sets.reverse();
+        let chain = Arc::new(Chain {
+            dir: canonical,
+            rule_sets,
+        });
+        lock(&self.chains).insert(dir.to_path_buf(), chain.clone());
+        chain
+    }
+
+    fn rules(&self, dir: &Path) -> Arc<Vec<Rule>> {
+        if let Some(rules) = lock(&self.files).get(dir) {
+            return rules.clone();
+        }
+        let rules = Arc::new(
+            std::fs::read_to_string(dir.join(".gitattributes"))
+                .map(|content| parse(&content))
+                .unwrap_or_default(),
+        );
+        lock(&self.files).insert(dir.to_path_buf(), rules.clone());
+        rules
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> std::sync::MutexGuard<'_, T> {
+    mutex
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner)
+}
+
+/// Parses the Linguist settings of a `.gitattributes` file.
+fn parse(content: &str) -> Vec<Rule> {
+    content
+        .lines()
+        .filter_map(|line| {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') || line.starts_with("[attr]") {
+                return None;
+            }
+            let mut fields = line.split_whitespace();
+            let pattern = fields.next()?;
+            let settings: Vec<_> = fields.filter_map(parse_setting).collect();
+            if settings.is_empty() {
+                return None;
+            }
+            Some(Rule {
+                matcher: matcher_for(pattern)?,
+                settings,
+            })
+        })
+        .collect()
+}
+
+/// `attr`, `attr=true` set an attribute; `-attr`, `!attr`, `attr=false`
+/// unset it.
+fn parse_setting(field: &str) -> Option<(LinguistClass, bool)> {
+    let (name, value) = match field.split_once('=') {
+        Some((name, value)) => (name, value != "false"),
+        None => match field.strip_prefix(['-', '!']) {
+            Some(name) => (name, false),
+            None => (field, true),
+        },
+    };
+    LinguistClass::ALL
+        .into_iter()
+        .find(|class| class.attribute() == name)
+        .map(|class| (class, value))
+}
+
+/// Git pattern semantics: a pattern without `/` matches the file name at any
+/// depth; otherwise it is anchored at the `.gitattributes` directory.
+/// Directory-only patterns (`vendor/`) never match files in git.
+fn matcher_for(pattern: &str) -> Option<GlobMatcher> {
+    if pattern.ends_with('/') {
+        return None;
+    }
+    let glob = if pattern.contains('/') {
+        pattern.trim_start_matches('/').to_string()
+    } else {
+        format!("**/{pattern}")
+    };
+    GlobBuilder::new(&glob)
+        .literal_separator(true)
+        .build()
+        .ok()
+        .map(|glob| glob.compile_matcher())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_parse_settings() {
+        assert_eq!(
+            parse_setting("linguist-vendored"),
+            Some((LinguistClass::Vendored, true))
+        );
+        assert_eq!(
+            parse_setting("linguist-documentation=false"),
+            Some((LinguistClass::Documentation, false))
+        );
+        assert_eq!(
+            parse_setting("-linguist-vendored"),
+            Some((LinguistClass::Vendored, false))
+        );
+        assert_eq!(parse_setting("linguist-generated"), None);
+    }
+
+    #[test]
+    fn test_classify_with_nested_attributes() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        fs::create_dir(root.join(".git")).unwrap();
+        fs::create_dir_all(root.join("third_party/keep")).unwrap();
+        fs::create_dir_all(root.join("docs")).unwrap();
+        fs::write(
+            root.join(".gitattributes"),
+            "# linguist\nthird_party/** linguist-vendored\ndocs/* linguist-documentation\n*.md text\n",
+        )
+        .unwrap();
+        fs::write(
+            root.join("third_party/keep/.gitattributes"),
+            "*.rs -linguist-vendored\n",
+        )
+        .unwrap();
+
+        let attributes = LinguistAttributes::new();
+        let classify = |path: &str| attributes.classify(&root.join(path));
+        assert_eq!(classify("third_party/lib.c"), Some(LinguistClass::Vendored));
+        assert_eq!(classify("third_party/keep/a.rs"), None);
+        assert_eq!(
+            classify("docs/guide.md"),
+            Some(LinguistClass::Documentation)
+        );
+        assert_eq!(classify("src/main.rs"), None);
+    }
+}
@@ -0,0 +1,349 @@
+// crates/engine/src/blame.rs
+//! `--by author`: attributes the lines of each file to the authors
+//! `git blame` names for them.
+//!
+//! Blaming is slow, so the results are kept in `blame-cache.json` in the
+//! cache directory, keyed by the `HEAD` commit and the blob id and path of
+//! each file: a file that didn't change since the last run, with no commit
+//! in between, is not blamed again. A new commit can give lines that were
+//! uncommitted an author, so it invalidates every entry.
+
+use crate::config::Config;
+use crate::git::GitRepo;
+use crate::stats::FileStats;
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+pub const BLAME_CACHE_FILE: &str = "blame-cache.json";
+
+/// Key used for lines not committed yet when grouping by author.
+pub const UNCOMMITTED: &str = "(uncommitted)";
+
+/// Lines per author of one file, most lines first.
+type Authors = Vec<(String, usize)>;
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct BlameCache {
+    /// `"<HEAD commit>:<blob id>:<path>"` → lines per author.
+    entries: HashMap<String, Authors>,
+}
+
+/// Splits every file of `stats` into one slice per author, each holding
+/// that author's share of the file's counts. Files `git blame` can't
+/// attribute (binary, untracked or outside the repository of the first
+/// root) are kept whole, without an author.
+#[must_use]
+pub fn split_by_author(stats: &[FileStats], config: &Config) -> Vec<FileStats> {
+    let repo = match crate::git::discover_for(config) {
+        Ok(repo) => repo,
+        Err(err) => {
+            log::debug!("blame: {err}");
+            return stats.to_vec();
+        }
+    };
+    let keys = match cache_keys(&repo, stats) {
+        Ok(keys) => keys,
+        Err(err) => {
+            log::debug!("blame: {err}");
+            return stats.to_vec();
+        }
+    };
+
+    let cache_path = config.resolved_cache_dir().join(BLAME_CACHE_FILE);
+    let cache: BlameCache = std::fs::read(&cache_path)
+        .ok()
+        .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+        .unwrap_or_default();
+
+    let blamed: Vec<(String, Authors)> = keys
+        .par_iter()
+        .filter(|(_, (_, key))| !cache.entries.contains_key(key))
+        .filter_map(|(_, (relative, key))| Some((key.clone(), blame(&repo, relative)?)))
+        .collect();
+    let changed = !blamed.is_empty();
+    let mut entries = cache.entries;
+    entries.extend(blamed);
+    // Only the files of this run are kept, so deleted files and the entries
+    // of older commits drop out.
+    let current: HashSet<&String> = keys.values().map(|(_, key)| key).collect();
+    entries.retain(|key, _| current.contains(key));
+
+    if changed
+        && let Ok(json) = serde_json::to_vec(&BlameCache {
+            entries: entries.clone(),
+        })
+        && let Err(err) = std::fs::create_dir_all(config.resolved_cache_dir())
+            .and_then(|()| std::fs::write(&cache_path, json))
+    {
+        log::debug!("blame: cannot save {}: {err}", cache_path.display());
+    }
+
+    stats
+        .iter()
+        .flat_map(|s| {
+            let authors = keys
+                .get(s.path.as_path())
+                .and_then(|(_, key)| entries.get(key));
+            match authors {
+                Some(authors) if !authors.is_empty() => split(s, authors),
+                _ => vec![s.clone()],
+            }
+        })
+        .collect()
+}
+
+/// The path relative to the root of `repo` and the cache key of each text
+/// file of `stats` inside it.
+fn cache_keys<'a>(
+    repo: &GitRepo,
+    stats: &'a [FileStats],
+) -> crate::error::Result<HashMap<&'a Path, (PathBuf, String)>> {
+    let head = repo.resolve("HEAD")?;
+    let files: Vec<(&Path, PathBuf)> = stats
+        .iter()
+        .filter(|s| !s.is_binary)
+        .filter_map(|s| Some((s.path.as_path(), repo.relative_path(&s.path)?)))
+        .collect();
+    let relative: Vec<PathBuf> = files.iter().map(|(_, r)| r.clone()).collect();
+    let oids = repo.hash_objects(&relative)?;
+    Ok(files
+        .into_iter()
+        .zip(oids)
+        .map(|((path, relative), oid)| {
+            let key = format!("{head}:{oid}:{}", relative.display());
+            (path, (relative, key))
+        })
+        .collect())
+}
+
+/// Lines per author of `path` (relative to the root of `repo`), from
+/// `git blame --line-porcelain`; `None` if it can't be blamed.
+fn blame(repo: &GitRepo, path: &Path) -> Option<Authors> {
+    let output = repo.blame(path).ok()?;
+    log::debug!("blame: {}", path.display());
+    Some(parse_line_porcelain(&String::from_utf8_lossy(&output)))
+}
+
+/// Counts the `author` header of every line of `git blame --line-porcelain`
+/// output.
+fn parse_line_porcelain(output: &str) -> Authors {
+    let mut lines: HashMap<&str, usize> = HashMap::new();
+    for author in output.lines().filter_map(|l| l.strip_prefix("author ")) {
+        let author = if author == "Not Committed Yet" {
+            UNCOMMITTED
+        } else {
+            author
+        };
+        *lines.entry(author).or_default() += 1;
+    }
+    let mut authors: Authors = lines
+        .into_iter()
+        .map(|(author, n)| (author.to_string(), n))
+        .collect();
+    authors.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    authors
+}
+
+/// One slice of `s` per author, with the counts divided in proportion to
+/// the blamed lines.
+fn split(s: &FileStats, authors: &[(String, usize)]) -> Vec<FileStats> {
+    let weights: Vec<usize> = authors.iter().map(|(_, n)| *n).collect();
+    let lines = divide(s.lines, &weights);
+    let chars = divide(s.chars, &weights);
+    let words = s.words.map(|w| divide(w, &weights));
+    let sloc = s.sloc.map(|v| divide(v, &weights));
+    let doc_lines = s.doc_lines.map(|v| divide(v, &weights));
+    let size = divide(usize::try_from(s.size).unwrap_or(usize::MAX), &weights);
+
+    authors
+        .iter()
+        .enumerate()
+        .map(|(i, (author, _))| FileStats {
+            lines: lines[i],
+            chars: chars[i],
+            words: words.as_ref().map(|w| w[i]),
+            sloc: sloc.as_ref().map(|v| v[i]),
+            doc_lines: doc_lines.as_ref().map(|v| v[i]),
+            size: size[i] as u64,
+            // Embedded sections stay with one slice, so they are moved once.
+            embedded: if i == 0 {
+                s.embedded.clone()
+            } else {
+                Vec::new()
+            },
+            author: Some(author.clone()),
+            ..s.clone()
+        })
+        .collect()
+}
+
+/// Divides `value` in proportion to `weights`; what rounding leaves over
+/// goes to the first part, so the parts add up to `value`.
+fn divide(value: usize, weights: &[usize]) -> Vec<usize> {
+    let total: usize = weights.iter().sum();
+    let mut parts: Vec<usize> = weights
+        .iter()
+        .map(|&w| usize::try_from(value as u128 * w as u128 / total.max(1) as u128).unwrap_or(0))
+        .collect();
+    let rest = value - parts.iter().sum::<usize>();
+    if let Some(first) = parts.first_mut() {
+        *first += rest;
+    }
+    parts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::WalkOptions;
+    use crate::testing::git;
+
+    /// Runs `git` in `dir`, committing as `author`.
+    fn git_as(dir: &Path, author: &str, args: &[&str]) {
+        let name = format!("user.name={author}");
+        git(dir, &[&["-c", name.as_str()], args].concat());
+    }
+
+    fn config(root: &Path) -> Config {
+        Config {
+            walk: WalkOptions {
+                roots: vec![root.to_path_buf()],
+                ..WalkOptions::default()
+            },
+            cache_dir: Some(root.join(".cache")),
+            ..Config::default()
+        }
+    }
+
+    fn authors(slices: Vec<FileStats>) -> Vec<(Option<String>, usize)> {
+        slices.into_iter().map(|s| (s.author, s.lines)).collect()
+    }
+
+    #[test]
+    fn test_parse_line_porcelain() {
+        let output = "\
+abc 1 1 2
+author Alice
+author-mail <a@example.com>
+\tfn a() {}
+abc 2 2
+author Alice
+\tfn b() {}
+0000 3 3 1
+author Not Committed Yet
+\t// new
+def 4 4 1
+author Bob
+\tfn c() {}
+";
+        assert_eq!(
+            parse_line_porcelain(output),
+            [
+                ("Alice".to_string(), 2),
+                ("(uncommitted)".to_string(), 1),
+                ("Bob".to_string(), 1),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_split_adds_up_to_the_file() {
+        let file = FileStats {
+            lines: 10,
+            chars: 101,
+            sloc: Some(7),
+            size: 103,
+            ..FileStats::new(PathBuf::from("a.rs"))
+        };
+        let slices = split(&file, &[("Alice".to_string(), 2), ("Bob".to_string(), 1)]);
+        let rows: Vec<_> = slices
+            .iter()
+            .map(|s| {
+                (
+                    s.author.as_deref().unwrap(),
+                    s.lines,
+                    s.chars,
+                    s.sloc,
+                    s.size,
+                )
+            })
+            .collect();
+        assert_eq!(
+            rows,
+            [("Alice", 7, 68, Some(5), 69), ("Bob", 3, 33, Some(2), 34),]
+        );
+    }
+
+    #[test]
+    fn test_split_by_author_caches_blame() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        git(root, &["init", "-q"]);
+        std::fs::write(root.join("a.rs"), "fn a() {}\nfn b() {}\n").unwrap();
+        git(root, &["add", "."]);
+        git_as(root, "Alice", &["commit", "-q", "-m", "first"]);
+        std::fs::write(root.join("a.rs"), "fn a() {}\nfn b() {}\nfn c() {}\n").unwrap();
+        git_as(root, "Bob", &["commit", "-q", "-am", "second"]);
+
+        let config = config(root);
+        let file = FileStats {
+            lines: 3,
+            ..FileStats::new(root.join("a.rs"))
+        };
+        let expected = [(Some("Alice".to_string()), 2), (Some("Bob".to_string()), 1)];
+        assert_eq!(
+            authors(split_by_author(std::slice::from_ref(&file), &config)),
+            expected
+        );
+
+        // The second run answers from the cache.
+        let cache_path = root.join(".cache").join(BLAME_CACHE_FILE);
+        let cached = std::fs::read_to_string(&cache_path).unwrap();
+        std::fs::write(&cache_path, cached.replace("Bob", "Carol")).unwrap();
+        assert_eq!(
+            authors(split_by_author(std::slice::from_ref(&file), &config)),
+            [
+                (Some("Alice".to_string()), 2),
+                (Some("Carol".to_string()), 1)
+            ]
+        );
+
+        // Files outside a repository are kept whole.
+        let outside = tempfile::tempdir().unwrap();
+        std::fs::write(outside.path().join("b.rs"), "x\n").unwrap();
+        let stray = FileStats::new(outside.path().join("b.rs"));
+        assert_eq!(authors(split_by_author(&[stray], &config)), [(None, 0)]);
+    }
+    #[test]
+    fn test_committing_invalidates_the_cache() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        git(root, &["init", "-q"]);
+        std::fs::write(root.join("a.rs"), "fn a() {}\n").unwrap();
+        git(root, &["add", "."]);
+        git_as(root, "Alice", &["commit", "-q", "-m", "first"]);
+        std::fs::write(root.join("a.rs"), "fn a() {}\nfn b() {}\n").unwrap();
+
+        let config = config(root);
+        let file = FileStats {
+            lines: 2,
+            ..FileStats::new(root.join("a.rs"))
+        };
+        assert_eq!(
+            authors(split_by_author(std::slice::from_ref(&file), &config)),
+            [
+                (Some("(uncommitted)".to_string()), 1),
+                (Some("Alice".to_string()), 1)
+            ]
+        );
+
+        // The same content, now committed, is blamed again.
+        git_as(root, "Bob", &["commit", "-q", "-am", "second"]);
+        assert_eq!(
+            authors(split_by_author(std::slice::from_ref(&file), &config)),
+            [(Some("Alice".to_string()), 1), (Some("Bob".to_string()), 1)]
+        );
+    }
+}
@@ -0,0 +1,568 @@
+// crates/engine/src/cache.rs
+//! Persistent per-file results for `--incremental`.
+//!
+//! Results are stored in `<cache dir>/file-cache.json` and reused for files
+//! that haven't changed since the previous run. A file is unchanged if its
+//! size and mtime match ([`CacheKey::Mtime`], the default) or, with
+//! [`CacheKey::Hash`], if the xxh3 hash of its content matches. The whole
+//! cache is discarded when a setting that affects the counts changes.
+//!
+//! Entries of files a run didn't see are kept; [`gc`] prunes the ones that
+//! haven't been used for a while.
+//!
+//! With `shared_cache` entries are keyed by the path relative to the
+//! repository (or walk root) and always checked by hash, so checkouts of
+//! the same repository in different places can share one cache directory.
+//!
+//! Runs sharing a cache directory may save at the same time. Each writes
+//! its own temporary file and renames it over the cache, and merges the
+//! entries saved since it loaded, so only the entries of a run that saves
+//! in between the read and the rename of another are lost; the next run
+//! counts those files again.
+
+use crate::config::Config;
+use crate::error::{EngineError, Result};
+use crate::options::CacheKey;
+use crate::processor;
+use crate::stats::FileStats;
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::UNIX_EPOCH;
+use xxhash_rust::xxh3::xxh3_64;
+
+/// File name of the cache inside the cache directory.
+pub const CACHE_FILE: &str = "file-cache.json";
+
+/// Bumped whenever the format of the cache or of the counts changes.
+const CACHE_VERSION: u32 = 2;
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct CacheFile {
+    version: u32,
+    /// Fingerprint of the settings the entries were counted with.
+    settings: u64,
+    entries: HashMap<PathBuf, CacheEntry>,
+    /// Hit rate of the run that wrote the cache.
+    #[serde(default)]
+    last_run: Option<CacheRun>,
+}
+
+/// Hits and misses of one `--incremental` run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CacheRun {
+    pub at: DateTime<Local>,
+    pub hits: usize,
+    pub misses: usize,
+}
+
+/// What `cache stats` reports about a cache directory.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CacheInfo {
+    pub path: PathBuf,
+    /// Size of the cache file in bytes; 0 if there is none.
+    pub size: u64,
+    pub entries: usize,
+    pub last_run: Option<CacheRun>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CacheEntry {
+    size: u64,
+    /// Modification time in nanoseconds since the Unix epoch.
+    #[serde(default)]
+    mtime_ns: Option<u64>,
+    /// xxh3 hash of the content, recorded with [`CacheKey::Hash`].
+    #[serde(default)]
+    hash: Option<u64>,
+    /// Unix time of the last run that counted or reused the entry.
+    #[serde(default)]
+    last_used: i64,
+    stats: FileStats,
+}
+
+/// The cache of one run: entries of the previous run are looked up, and
+/// the entries of every file counted in this run are saved back.
+#[derive(Debug)]
+pub struct StatsCache {
+    path: PathBuf,
+    key: CacheKey,
+    /// Walk roots and their path from the repository root, for
+    /// `shared_cache`.
+    shared_roots: Option<Vec<(PathBuf, PathBuf)>>,
+    settings: u64,
+    previous: HashMap<PathBuf, CacheEntry>,
+    current: Mutex<HashMap<PathBuf, CacheEntry>>,
+    hits: AtomicUsize,
+    misses: AtomicUsize,
+}
+
+impl StatsCache {
+    /// Loads the cache of `config`'s cache directory. A missing, unreadable
+    /// or outdated cache starts empty.
+    #[must_use]
+    pub fn load(config: &Config) -> Self {
+        let path = config.resolved_cache_dir().join(CACHE_FILE);
+        let settings = settings_fingerprint(config);
+        let previous = read_cache(&path)
+            .filter(|file| file.version == CACHE_VERSION && file.settings == settings)
+            .map(|file| file.entries)
+            .unwrap_or_default();
+        Self {
+            path,
+            key: if config.shared_cache {
+                CacheKey::Hash
+            } else {
+                config.cache_key
+            },
+            shared_roots: config.shared_cache.then(|| shared_roots(config)),
+            settings,
+            previous,
+            current: Mutex::new(HashMap::new()),
+            hits: AtomicUsize::new(0),
+            misses: AtomicUsize::new(0),
+        }
+    }
+
+    /// Counts `path`, reusing the previous result if the file is unchanged.
+    ///
+    /// # Errors
+    /// Returns an error if the file cannot be read.
+    pub fn process_file(
+        &self,
+        path: PathBuf,
+        meta: &std::fs::Metadata,
+        config: &Config,
+    ) -> Result<FileStats> {
+        let size = meta.len();
+        let mtime_ns = mtime_ns(meta);
+        let key = self.entry_key(&path);
+        let cached = self.previous.get(&key).filter(|entry| entry.size == size);
+
+        let (stats, hash) = match self.key {
+            CacheKey::Mtime => {
+                match cached.filter(|e| mtime_ns.is_some() && e.mtime_ns == mtime_ns) {
+                    Some(entry) => (self.hit(entry), entry.hash),
+                    None => {
+                        let stats = processor::process_file((path.clone(), meta.clone()), config)?;
+                        (self.miss(stats), None)
+                    }
+                }
+            }
+            CacheKey::Hash => {
+                let content =
+                    config
+                        .io_limit
+                        .read(&path)
+                        .map_err(|source| EngineError::FileRead {
+                            path: path.clone(),
+                            source,
+                        })?;
+                let hash = xxh3_64(&content);
+                match cached.filter(|e| e.hash == Some(hash)) {
+                    Some(entry) => (self.hit(entry), Some(hash)),
+                    None => {
+                        let stats =
+                            processor::process_content(path.clone(), &content, meta, config)?;
+                        (self.miss(stats), Some(hash))
+                    }
+                }
+            }
+        };
+
+        let mut stats = stats;
+        stats.path.clone_from(&path);
+        stats.mtime = meta
+            .modified()
+            .ok()
+            .map(chrono::DateTime::<chrono::Local>::from);
+        self.current
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .insert(
+                key,
+                CacheEntry {
+                    size,
+                    mtime_ns,
+                    hash,
+                    last_used: Local::now().timestamp(),
+                    stats: stats.clone(),
+                },
+            );
+        Ok(stats)
+    }
+
+    /// The path itself, or with `shared_cache` the path below the
+    /// repository root.
+    fn entry_key(&self, path: &Path) -> PathBuf {
+        let Some(roots) = &self.shared_roots else {
+            return path.to_path_buf();
+        };
+        roots
+            .iter()
+            .filter_map(|(root, prefix)| Some(prefix.join(path.strip_prefix(root).ok()?)))
+            .next()
+            .unwrap_or_else(|| path.to_path_buf())
+    }
+
+    fn hit(&self, entry: &CacheEntry) -> FileStats {
+        self.hits.fetch_add(1, Ordering::Relaxed);
+        entry.stats.clone()
+    }
+
+    fn miss(&self, stats: FileStats) -> FileStats {
+        self.misses.fetch_add(1, Ordering::Relaxed);
+        stats
+    }
+
+    /// Number of files whose previous result was reused.
+    #[must_use]
+    pub fn hits(&self) -> usize {
+        self.hits.load(Ordering::Relaxed)
+    }
+
+    /// Number of files that had to be counted.
+    #[must_use]
+    pub fn misses(&self) -> usize {
+        self.misses.load(Ordering::Relaxed)
+    }
+
+    /// Writes the entries of this run over those saved since the cache was
+    /// loaded, or else those of the previous run, with the hit rate of this
+    /// run.
+    ///
+    /// # Errors
+    /// Returns an error if the cache file cannot be written.
+    pub fn save(&self) -> Result<()> {
+        let mut entries = std::mem::take(
+            &mut *self
+                .current
+                .lock()
+                .unwrap_or_else(std::sync::PoisonError::into_inner),
+        );
+        // Another run sharing the directory may have saved in the meantime.
+        let saved = read_cache(&self.path)
+            .filter(|file| file.version == CACHE_VERSION && file.settings == self.settings)
+            .map(|file| file.entries)
+            .unwrap_or_default();
+        for (path, entry) in saved {
+            entries.entry(path).or_insert(entry);
+        }
+        for (path, entry) in &self.previous {
+            entries.entry(path.clone()).or_insert_with(|| entry.clone());
+        }
+        let file = CacheFile {
+            version: CACHE_VERSION,
+            settings: self.settings,
+            entries,
+            last_run: Some(CacheRun {
+                at: Local::now(),
+                hits: self.hits(),
+                misses: self.misses(),
+            }),
+        };
+        write_cache(&self.path, &file)
+    }
+}
+
+/// Reports the size, entry count and last hit rate of the cache in `dir`.
+///
+/// # Errors
+/// Returns an error if the cache file exists but cannot be read.
+pub fn info(dir: &Path) -> Result<CacheInfo> {
+    let path = dir.join(CACHE_FILE);
+    let size = match std::fs::metadata(&path) {
+        Ok(meta) => meta.len(),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => 0,
+        Err(err) => return Err(EngineError::Cache(format!("{}: {err}", path.display()))),
+    };
+    let file = read_cache(&path).unwrap_or_default();
+    Ok(CacheInfo {
+        path,
+        size,
+        entries: file.entries.len(),
+        last_run: file.last_run,
+    })
+}
+
+/// Deletes the cache in `dir`. Returns false if there was none.
+///
+/// # Errors
+/// Returns an error if the cache file cannot be removed.
+pub fn clear(dir: &Path) -> Result<bool> {
+    let path = dir.join(CACHE_FILE);
+    match std::fs::remove_file(&path) {
+        Ok(()) => Ok(true),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(false),
+        Err(err) => Err(EngineError::Cache(format!("{}: {err}", path.display()))),
+    }
+}
+
+/// Removes the entries of the cache in `dir` that no run has used since
+/// `now - max_age`. Returns the number of entries removed.
+///
+/// # Errors
+/// Returns an error if the cache file cannot be rewritten.
+pub fn gc(dir: &Path, max_age: chrono::Duration, now: DateTime<Local>) -> Result<usize> {
+    let path = dir.join(CACHE_FILE);
+    let Some(mut file) = read_cache(&path) else {
+        return Ok(0);
+    };
+    let cutoff = (now - max_age).timestamp();
+    let before = file.entries.len();
+    file.entries.retain(|_, entry| entry.last_used >= cutoff);
+    let removed = before - file.entries.len();
+    if removed > 0 {
+        write_cache(&path, &file)?;
+    }
+    Ok(removed)
+}
+
+fn read_cache(path: &Path) -> Option<CacheFile> {
+    let bytes = std::fs::read(path).ok()?;
+    serde_json::from_slice(&bytes).ok()
+}
+
+fn write_cache(path: &Path, file: &CacheFile) -> Result<()> {
+    let json = serde_json::to_vec(file).map_err(|err| EngineError::Cache(err.to_string()))?;
+    write_atomic(path, &json)
+}
+
+/// Writes through a temporary file of its own so an interrupted run can't
+/// leave a truncated cache behind, and runs writing at once don't clobber
+/// each other's half-written file.
+fn write_atomic(path: &Path, bytes: &[u8]) -> Result<()> {
+    let cache_error =
+        |err: std::io::Error| EngineError::Cache(format!("{}: {err}", path.display()));
+    let dir = path
+        .parent()
+        .filter(|d| !d.as_os_str().is_empty())
+        .unwrap_or_else(|| Path::new("."));
+    std::fs::create_dir_all(dir).map_err(cache_error)?;
+    let mut tmp = tempfile::NamedTempFile::new_in(dir).map_err(cache_error)?;
+    std::io::Write::write_all(&mut tmp, bytes).map_err(cache_error)?;
+    tmp.persist(path).map_err(|err| cache_error(err.error))?;
+    Ok(())
+}
+
+/// Each walk root, deepest first, with its path from the root of the
+/// repository it's in (`.git` directory or worktree file), or from itself
+/// outside a repository.
+fn shared_roots(config: &Config) -> Vec<(PathBuf, PathBuf)> {
+    let mut roots: Vec<(PathBuf, PathBuf)> = config
+        .walk
+        .roots
+        .iter()
+        .map(|root| {
+            let prefix = root
+                .canonicalize()
+                .ok()
+                .and_then(|abs| {
+                    let repo = abs.ancestors().find(|dir| dir.join(".git").exists())?;
+                    Some(abs.strip_prefix(repo).ok()?.to_path_buf())
+                })
+                .unwrap_or_default();
+            (root.clone(), prefix)
+        })
+        .collect();
+    roots.sort_by_key(|(root, _)| std::cmp::Reverse(root.components().count()));
+    roots
+}
+
+fn mtime_ns(meta: &std::fs::Metadata) -> Option<u64> {
+    let since_epoch = meta.modified().ok()?.duration_since(UNIX_EPOCH).ok()?;
+    u64::try_from(since_epoch.as_nanos()).ok()
+}
+
+/// Hash of every setting that changes the counts of a file.
+fn settings_fingerprint(config: &Config) -> u64 {
+    let mut map_ext: Vec<_> = config.filter.map_ext.iter().collect();
+    map_ext.sort();
+    let settings = serde_json::json!({
+        "engine": env!("CARGO_PKG_VERSION"),
+        "count_words": config.count_words,
+        "count_sloc": config.count_sloc,
+        "count_docs": config.count_docs,
+        "count_indent": config.count_indent,
+        "tab_width": config.tab_width,
+        "audit_whitespace": config.audit_whitespace,
+        "audit_encoding": config.audit_encoding,
+        "license_header": config.license_header,
+        "long_line_threshold": config.long_line_threshold,
+        "count_newlines_in_chars": config.count_newlines_in_chars,
+        "map_ext": map_ext,
+        "languages": config.languages,
+        "detect_modeline": config.detect_modeline,
+        "include_minified": config.include_minified,
+        "encoding": config.encoding.map(|e| e.name()),
+        "plugins": config.plugins,
+    });
+    xxh3_64(settings.to_string().as_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(dir: &Path, key: CacheKey) -> Config {
+        Config {
+            cache_dir: Some(dir.join("cache")),
+            cache_key: key,
+            ..Config::default()
+        }
+    }
+
+    fn count(cache: &StatsCache, path: &Path, config: &Config) -> FileStats {
+        let meta = std::fs::metadata(path).unwrap();
+        cache
+            .process_file(path.to_path_buf(), &meta, config)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_mtime_key_reuses_unchanged_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("a.rs");
+        std::fs::write(&file, "fn a() {}\n").unwrap();
+        let config = config(dir.path(), CacheKey::Mtime);
+
+        let cache = StatsCache::load(&config);
+        assert_eq!(count(&cache, &file, &config).lines, 1);
+        assert_eq!((cache.hits(), cache.misses()), (0, 1));
+        cache.save().unwrap();
+
+        let cache = StatsCache::load(&config);
+        assert_eq!(count(&cache, &file, &config).lines, 1);
+        assert_eq!((cache.hits(), cache.misses()), (1, 0));
+    }
+
+    #[test]
+    fn test_concurrent_runs_keep_each_others_entries() {
+        let dir = tempfile::tempdir().unwrap();
+        let (a, b) = (dir.path().join("a.rs"), dir.path().join("b.rs"));
+        std::fs::write(&a, "fn a() {}\n").unwrap();
+        std::fs::write(&b, "fn b() {}\n").unwrap();
+        let config = config(dir.path(), CacheKey::Mtime);
+
+        // Both runs load the empty cache, then save one after the other.
+        let first = StatsCache::load(&config);
+        let second = StatsCache::load(&config);
+        count(&first, &a, &config);
+        count(&second, &b, &config);
+        first.save().unwrap();
+        second.save().unwrap();
+
+        let cache = StatsCache::load(&config);
+        count(&cache, &a, &config);
+        count(&cache, &b, &config);
+        assert_eq!((cache.hits(), cache.misses()), (2, 0));
+        let leftovers: Vec<_> = std::fs::read_dir(dir.path().join("cache"))
+            .unwrap()
+            .map(|e| e.unwrap().file_name())
+            .collect();
+        assert_eq!(leftovers, [CACHE_FILE]);
+    }
+
+    #[test]
+    fn test_hash_key_detects_same_size_rewrites() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("a.txt");
+        std::fs::write(&file, "aaaa\n").unwrap();
+        let config = config(dir.path(), CacheKey::Hash);
+
+        let cache = StatsCache::load(&config);
+        count(&cache, &file, &config);
+        cache.save().unwrap();
+
+        // Same size; the mtime may or may not change within the test.
+        std::fs::write(&file, "a\na\n").unwrap();
+        let cache = StatsCache::load(&config);
+        assert_eq!(count(&cache, &file, &config).lines, 2);
+        assert_eq!((cache.hits(), cache.misses()), (0, 1));
+    }
+
+    #[test]
+    fn test_shared_cache_across_checkouts() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut config = config(dir.path(), CacheKey::Mtime);
+        config.shared_cache = true;
+        for checkout in ["wt1", "wt2"] {
+            let root = dir.path().join(checkout);
+            std::fs::create_dir_all(root.join(".git")).unwrap();
+            std::fs::create_dir_all(root.join("src")).unwrap();
+            std::fs::write(root.join("src/a.rs"), "fn a() {}\n").unwrap();
+        }
+
+        let run = |checkout: &str| {
+            let root = dir.path().join(checkout);
+            let config = Config {
+                walk: crate::config::WalkOptions {
+                    roots: vec![root.join("src")],
+                    ..crate::config::WalkOptions::default()
+                },
+                ..config.clone()
+            };
+            let cache = StatsCache::load(&config);
+            let stats = count(&cache, &root.join("src/a.rs"), &config);
+            cache.save().unwrap();
+            assert_eq!(stats.path, root.join("src/a.rs"));
+            (cache.hits(), cache.misses())
+        };
+        assert_eq!(run("wt1"), (0, 1));
+        assert_eq!(run("wt2"), (1, 0));
+        let cache_dir = dir.path().join("cache");
+        assert_eq!(info(&cache_dir).unwrap().entries, 1);
+    }
+
+    #[test]
+    fn test_info_and_gc() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache_dir = dir.path().join("cache");
+        let config = config(dir.path(), CacheKey::Mtime);
+        assert_eq!(info(&cache_dir).unwrap().entries, 0);
+        assert!(!clear(&cache_dir).unwrap());
+
+        for name in ["a.rs", "b.rs"] {
+            std::fs::write(dir.path().join(name), "x\n").unwrap();
+        }
+        let cache = StatsCache::load(&config);
+        count(&cache, &dir.path().join("a.rs"), &config);
+        count(&cache, &dir.path().join("b.rs"), &config);
+        cache.save().unwrap();
+
+        // A run that only sees a.rs keeps the entry of b.rs.
+        let cache = StatsCache::load(&config);
+        count(&cache, &dir.path().join("a.rs"), &config);
+        cache.save().unwrap();
+        let stats = info(&cache_dir).unwrap();
+        assert_eq!(stats.entries, 2);
+        let last_run = stats.last_run.unwrap();
+        assert_eq!((last_run.hits, last_run.misses), (1, 0));
+
+        let day = chrono::Duration::days(1);
+        assert_eq!(gc(&cache_dir, day, Local::now()).unwrap(), 0);
+        assert_eq!(gc(&cache_dir, day, Local::now() + day * 2).unwrap(), 2);
+        assert_eq!(info(&cache_dir).unwrap().entries, 0);
+        assert!(clear(&cache_dir).unwrap());
+    }
+
+    #[test]
+    fn test_settings_change_discards_cache() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("a.rs");
+        std::fs::write(&file, "// c\nfn a() {}\n").unwrap();
+        let mut config = config(dir.path(), CacheKey::Mtime);
+
+        let cache = StatsCache::load(&config);
+        count(&cache, &file, &config);
+        cache.save().unwrap();
+
+        config.count_sloc = true;
+        let cache = StatsCache::load(&config);
+        assert_eq!(count(&cache, &file, &config).sloc, Some(1));
+        assert_eq!(cache.misses(), 1);
+    }
+}
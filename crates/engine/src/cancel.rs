@@ -0,0 +1,96 @@
+// crates/engine/src/cancel.rs
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Shared flag used to stop a run early (e.g. on Ctrl-C).
+///
+/// Clones share the same flag. Once cancelled, the walk stops enumerating,
+/// queued files are skipped, and the run returns the results collected so
+/// far with [`crate::stats::RunResult::partial`] set.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken {
+    flag: Arc<AtomicBool>,
+    /// The flag of the token this one was made from with [`Self::child`].
+    parent: Option<Arc<AtomicBool>>,
+}
+
+impl CancellationToken {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Requests cancellation.
+    pub fn cancel(&self) {
+        self.flag.store(true, Ordering::Relaxed);
+    }
+
+    /// A token that is cancelled with this one, but can also be cancelled
+    /// on its own without cancelling this one.
+    #[must_use]
+    pub fn child(&self) -> Self {
+        Self {
+            flag: Arc::default(),
+            parent: Some(self.flag.clone()),
+        }
+    }
+
+    /// Returns true once cancellation was requested.
+    #[must_use]
+    pub fn is_cancelled(&self) -> bool {
+        self.flag.load(Ordering::Relaxed)
+            || self
+                .parent
+                .as_ref()
+                .is_some_and(|parent| parent.load(Ordering::Relaxed))
+    }
+
+    /// The underlying flag, e.g. for registering a signal handler that sets it.
+    #[must_use]
+    pub fn flag(&self) -> Arc<AtomicBool> {
+        self.flag.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CancellationToken;
+    use crate::config::{Config, WalkOptions};
+
+    #[test]
+    fn test_child_token() {
+        let parent = CancellationToken::new();
+        let child = parent.child();
+        child.cancel();
+        assert!(child.is_cancelled());
+        assert!(!parent.is_cancelled());
+
+        let child = parent.child();
+        parent.cancel();
+        assert!(child.is_cancelled());
+    }
+
+    #[test]
+    fn test_cancelled_run_is_partial() {
+        let dir = tempfile::tempdir().unwrap();
+        for i in 0..3 {
+            std::fs::write(dir.path().join(format!("{i}.rs")), "fn f() {}\n").unwrap();
+        }
+        let config = Config {
+            walk: WalkOptions {
+                roots: vec![dir.path().to_path_buf()],
+                ..WalkOptions::default()
+            },
+            ..Config::default()
+        };
+
+        let complete = crate::run(&config).unwrap();
+        assert_eq!(complete.stats.len(), 3);
+        assert!(!complete.partial);
+
+        config.cancel.cancel();
+        let partial = crate::run(&config).unwrap();
+        assert!(partial.partial);
+        assert!(partial.stats.is_empty());
+    }
+}
@@ -0,0 +1,188 @@
+// crates/engine/src/codeowners.rs
+//! `CODEOWNERS` matching for `--by owner`.
+//!
+//! Patterns follow GitHub's rules: they are relative to the repository root,
+//! a pattern without a leading or inner `/` matches at any depth, a
+//! directory pattern covers everything below it, and the last matching line
+//! wins. A matching line without owners leaves the file unowned.
+
+use globset::{GlobBuilder, GlobMatcher};
+use std::path::{Path, PathBuf};
+
+/// Where GitHub looks for the file, relative to the repository root.
+const LOCATIONS: [&str; 3] = [".github/CODEOWNERS", "CODEOWNERS", "docs/CODEOWNERS"];
+
+#[derive(Debug)]
+struct Rule {
+    matchers: Vec<GlobMatcher>,
+    owners: Vec<String>,
+}
+
+/// The rules of one `CODEOWNERS` file.
+#[derive(Debug)]
+pub struct CodeOwners {
+    /// Directory the patterns are relative to.
+    base: PathBuf,
+    rules: Vec<Rule>,
+}
+
+impl CodeOwners {
+    /// Parses `content` with patterns relative to `base`.
+    #[must_use]
+    pub fn parse(content: &str, base: PathBuf) -> Self {
+        let rules = content
+            .lines()
+            .filter_map(|line| {
+                let line = line.trim();
+                if line.is_empty() || line.starts_with('#') {
+                    return None;
+                }
+                let mut fields = line.split_whitespace();
+                let matchers = matchers_for(fields.next()?)?;
+                let owners = fields
+                    .take_while(|f| !f.starts_with('#'))
+                    .map(str::to_string)
+                    .collect();
+                Some(Rule { matchers, owners })
+            })
+            .collect();
+        Self {
+            base: std::path::absolute(&base).unwrap_or(base),
+            rules,
+        }
+    }
+
+    /// Reads `file`; its patterns are relative to the repository root, the
+    /// directory above `.github/` or `docs/` for files kept there.
+    ///
+    /// # Errors
+    /// Returns an error if the file can't be read.
+    pub fn load(file: &Path) -> std::io::Result<Self> {
+        let content = std::fs::read_to_string(file)?;
+        let dir = std::path::absolute(file)?
+            .parent()
+            .map(Path::to_path_buf)
+            .unwrap_or_default();
+        let base = match dir.file_name().and_then(|n| n.to_str()) {
+            Some(".github" | "docs") => dir.parent().map(Path::to_path_buf).unwrap_or(dir),
+            _ => dir,
+        };
+        Ok(Self::parse(&content, base))
+    }
+
+    /// Looks for a `CODEOWNERS` file in `start` and the directories above
+    /// it, stopping at the repository root.
+    #[must_use]
+    pub fn find(start: &Path) -> Option<Self> {
+        let start = std::path::absolute(start).ok()?;
+        for dir in start.ancestors() {
+            if let Some(file) = LOCATIONS.iter().map(|l| dir.join(l)).find(|f| f.is_file()) {
+                return Self::load(&file).ok();
+            }
+            if dir.join(".git").exists() {
+                break;
+            }
+        }
+        None
+    }
+
+    /// The owners of `path`, or `None` if no line assigns any.
+    #[must_use]
+    pub fn owners(&self, path: &Path) -> Option<&[String]> {
+        let absolute = std::path::absolute(path).ok()?;
+        let relative = absolute.strip_prefix(&self.base).ok()?;
+        self.rules
+            .iter()
+            .rev()
+            .find(|rule| rule.matchers.iter().any(|m| m.is_match(relative)))
+            .map(|rule| rule.owners.as_slice())
+            .filter(|owners| !owners.is_empty())
+    }
+}
+
+/// Matchers for the path itself and, for directories, everything below.
+pub(crate) fn matchers_for(pattern: &str) -> Option<Vec<GlobMatcher>> {
+    let (pattern, dir_only) = match pattern.strip_suffix('/') {
+        Some(dir) => (dir, true),
+        None => (pattern, false),
+    };
+    let glob = if pattern.contains('/') {
+        pattern.trim_start_matches('/').to_string()
+    } else {
+        format!("**/{pattern}")
+    };
+    let build = |glob: &str| {
+        GlobBuilder::new(glob)
+            .literal_separator(true)
+            .build()
+            .ok()
+            .map(|g| g.compile_matcher())
+    };
+    let mut matchers = vec![build(&format!("{glob}/**"))?];
+    if !dir_only {
+        matchers.push(build(&glob)?);
+    }
+    Some(matchers)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONTENT: &str = "\
+# Default owners
+*       @org/core
+*.md    @org/docs  # prose
+/crates/cli/ @org/cli @alice
+docs/generated/
+build/**/*.rs @org/build
+";
+
+    fn owners(codeowners: &CodeOwners, path: &str) -> Option<String> {
+        codeowners
+            .owners(&Path::new("/repo").join(path))
+            .map(|o| o.join(" "))
+    }
+
+    #[test]
+    fn test_last_matching_line_wins() {
+        let codeowners = CodeOwners::parse(CONTENT, PathBuf::from("/repo"));
+        assert_eq!(
+            owners(&codeowners, "src/lib.rs").as_deref(),
+            Some("@org/core")
+        );
+        assert_eq!(
+            owners(&codeowners, "guide/intro.md").as_deref(),
+            Some("@org/docs")
+        );
+        assert_eq!(
+            owners(&codeowners, "crates/cli/src/main.rs").as_deref(),
+            Some("@org/cli @alice")
+        );
+        assert_eq!(owners(&codeowners, "docs/generated/api.md"), None);
+        assert_eq!(
+            owners(&codeowners, "build/x/y/gen.rs").as_deref(),
+            Some("@org/build")
+        );
+        assert_eq!(
+            owners(&codeowners, "other/crates/cli/x.rs").as_deref(),
+            Some("@org/core")
+        );
+    }
+
+    #[test]
+    fn test_find_in_github_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join(".github")).unwrap();
+        std::fs::create_dir_all(dir.path().join("src")).unwrap();
+        std::fs::create_dir(dir.path().join(".git")).unwrap();
+        std::fs::write(dir.path().join(".github/CODEOWNERS"), "/src/ @org/src\n").unwrap();
+
+        let codeowners = CodeOwners::find(&dir.path().join("src")).unwrap();
+        assert_eq!(
+            codeowners.owners(&dir.path().join("src/a.rs")),
+            Some(&["@org/src".to_string()][..])
+        );
+        assert_eq!(codeowners.owners(&dir.path().join("README.md")), None);
+    }
+}
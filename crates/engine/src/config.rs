@@ -1,5 +1,17 @@
 // crates/engine/src/config.rs
-use crate::options::{OutputFormat, SortKey, WatchOutput};
+use crate::cancel::CancellationToken;
+use crate::encoding::Encoding;
+use crate::expr::{FilterExpr, GroupFilter, Threshold};
+use crate::git::{ChurnOptions, HistoryOptions};
+use crate::io_limit::IoLimit;
+use crate::options::{
+    Badge, CacheKey, Column, CompareFormat, CompareSort, GroupBy, LimitAction, LinguistMode,
+    OutputFormat, RatioBase, RatioMetric, SortKey, Theme, WatchOutput,
+};
+use crate::plugin::{Plugin, PluginLimits};
+use crate::profile::Stopwatch;
+use crate::test_code::TestSplitOptions;
+use count_lines_core::language::LanguageDefinition;
 use derive_builder::Builder;
 use std::path::PathBuf;
 use std::time::Duration;
@@ -19,12 +31,36 @@ pub struct WalkOptions {
     pub max_depth: Option<usize>,
     #[builder(default)]
     pub follow_links: bool,
+    /// Enter Windows junctions and other directory reparse points, which
+    /// the walk skips by default even with `follow_links`.
+    #[builder(default)]
+    pub follow_junctions: bool,
+    /// Walk into the git submodules listed in `.gitmodules`, which are
+    /// skipped by default.
+    #[builder(default)]
+    pub include_submodules: bool,
     #[builder(default)]
     pub override_include: Vec<String>,
     #[builder(default)]
     pub override_exclude: Vec<String>,
     #[builder(default, setter(strip_option))]
     pub types: Option<ignore::types::Types>,
+    /// Count the members of `.zip` / `.tar` / `.tar.gz` files instead of the
+    /// archives themselves.
+    #[builder(default)]
+    pub scan_archives: bool,
+    /// Honour the `linguist-vendored` / `linguist-documentation` attributes
+    /// of `.gitattributes`.
+    #[builder(default)]
+    pub linguist: Option<LinguistMode>,
+    /// Count FIFOs, sockets and device nodes instead of skipping them; a
+    /// read from one may block until `--file-timeout`.
+    #[builder(default)]
+    pub include_special: bool,
+    /// Collect the symlinks followed, skipped as cycles, or broken
+    /// (`--report-links`).
+    #[builder(default)]
+    pub report_links: bool,
 }
 
 impl Default for WalkOptions {
@@ -36,9 +72,15 @@ impl Default for WalkOptions {
             git_ignore: true,
             max_depth: None,
             follow_links: false,
+            follow_junctions: false,
+            include_submodules: false,
             override_include: vec![],
             override_exclude: vec![],
             types: None,
+            scan_archives: false,
+            linguist: None,
+            include_special: false,
+            report_links: false,
         }
     }
 }
@@ -77,8 +119,14 @@ pub struct FilterConfig {
     pub include_patterns: Vec<String>,
     #[builder(default)]
     pub exclude_patterns: Vec<String>,
+    /// Match the include/exclude and override patterns ignoring case.
+    #[builder(default)]
+    pub glob_case_insensitive: bool,
     #[builder(default)]
     pub map_ext: hashbrown::HashMap<String, String>,
+    /// `--filter` expressions; a file must satisfy all of them.
+    #[builder(default)]
+    pub expressions: Vec<FilterExpr>,
 }
 
 #[derive(Debug, Clone, Builder)]
@@ -99,11 +147,197 @@ pub struct Config {
     pub count_newlines_in_chars: bool,
     #[builder(default)]
     pub progress: bool,
-
+    /// Write results to this file instead of stdout.
+    #[builder(default)]
+    pub output: Option<PathBuf>,
+    /// Append each run to this SQLite database (`--output-db`).
+    #[builder(default)]
+    pub output_db: Option<PathBuf>,
+    /// Display paths relative to this absolute directory (`--relative-to`).
+    #[builder(default)]
+    pub relative_to: Option<PathBuf>,
+    /// Aggregate the results into one row per group instead of per file;
+    /// further keys break each group down (`--by ext --by root-dir`).
+    #[builder(default)]
+    pub group_by: Vec<GroupBy>,
+    /// `--group-filter` expressions; a group must satisfy all of them to be
+    /// reported.
+    #[builder(default)]
+    pub group_filter: Vec<GroupFilter>,
+    /// Upper bounds of the `--by age` buckets, ascending; files older than
+    /// the last one fall into a final open-ended bucket.
+    #[builder(default = "Config::default_age_buckets()")]
+    pub age_buckets: Vec<chrono::Duration>,
+    /// `CODEOWNERS` file for `--by owner`; found in the repository when unset.
+    #[builder(default)]
+    pub codeowners: Option<PathBuf>,
+    /// Show only the first N files after sorting.
+    #[builder(default)]
+    pub top: Option<usize>,
+    /// Also rank the N heaviest directories by total lines.
+    #[builder(default)]
+    pub top_dirs: Option<usize>,
+    /// Columns of the per-file table and CSV/TSV output, in order; empty
+    /// keeps the default layout (`--columns`).
+    #[builder(default)]
+    pub columns: Vec<Column>,
+    /// Abbreviate counts and write sizes in binary units in the table,
+    /// tree and Markdown outputs (`--human`).
+    #[builder(default)]
+    pub human_numbers: bool,
+    /// Digit group separator for those outputs (`--thousands`).
+    #[builder(default)]
+    pub thousands_separator: Option<String>,
+    /// Write sizes as byte counts even with `human_numbers` (`--bytes`).
+    #[builder(default)]
+    pub raw_bytes: bool,
+    /// Write ANSI colors in the table and tree outputs; the CLI resolves
+    /// `--color auto` against the terminal and `NO_COLOR`.
+    #[builder(default)]
+    pub color: bool,
+    #[builder(default)]
+    pub theme: Theme,
+    /// Highlight files with more lines than this in the table (`--highlight-over`).
+    #[builder(default)]
+    pub highlight_over: Option<usize>,
+    /// Also report percentiles and a size histogram (`--distribution`).
+    #[builder(default)]
+    pub distribution: bool,
+    /// Add each group's share of this total to grouped output (`--ratio-of`).
+    #[builder(default)]
+    pub ratio_of: Option<RatioMetric>,
+    /// Whether `ratio_of` shares are of the grand total or the enclosing group.
+    #[builder(default)]
+    pub ratio_base: RatioBase,
+    /// Deepest directory level shown by `--format tree` (root is 0).
+    #[builder(default)]
+    pub tree_depth: Option<usize>,
+    /// Prefix Markdown summaries with shields.io badges for the totals.
+    #[builder(default)]
+    pub markdown_badges: bool,
+    /// Write a shields.io endpoint badge instead of the results.
+    #[builder(default)]
+    pub badge: Option<Badge>,
+    /// Add the run metadata (version, start time, roots, git commit,
+    /// timings) to the structured outputs (`--with-metadata`).
+    #[builder(default)]
+    pub with_metadata: bool,
+    /// Report how long each stage took and the cache hits (`--timings`).
+    #[builder(default)]
+    pub timings: bool,
+    /// Record each run's totals in the cache directory and report the
+    /// change since the previous run and since this many runs ago
+    /// (`--trend`).
+    #[builder(default)]
+    pub trend: Option<usize>,
+    /// Report file pairs sharing at least this percentage of their
+    /// normalized code lines.
+    #[builder(default)]
+    pub detect_similar: Option<u8>,
     #[builder(default)]
     pub count_words: bool,
     #[builder(default)]
     pub count_sloc: bool,
+    /// Count documentation comment lines (`--docs`); needs `count_sloc`.
+    #[builder(default)]
+    pub count_docs: bool,
+    /// Measure the indentation depth of each file (`--indent`).
+    #[builder(default)]
+    pub count_indent: bool,
+    /// Columns per indentation level and tab stop width; `0` means 4.
+    #[builder(default)]
+    pub tab_width: usize,
+    /// User-defined languages, checked before the built-in languages.
+    #[builder(default)]
+    pub languages: Vec<LanguageDefinition>,
+    /// Detect the language from Vim modelines / Emacs mode headers.
+    #[builder(default)]
+    pub detect_modeline: bool,
+    /// Count minified JS/CSS like any other file instead of reporting them
+    /// separately in `RunResult::minified`.
+    #[builder(default)]
+    pub include_minified: bool,
+    /// Files larger than this many bytes are split at line breaks into
+    /// chunks of about this size that are counted in parallel
+    /// (`--chunk-size`).
+    #[builder(default)]
+    pub chunk_size: Option<u64>,
+    /// Lines longer than this many bytes are measured in place, skipping the
+    /// indentation and whitespace checks, and flagged
+    /// (`--long-line-threshold`); `0` turns the guard off.
+    #[builder(default = "Config::DEFAULT_LONG_LINE_THRESHOLD")]
+    pub long_line_threshold: u64,
+    /// Decode files without a BOM that aren't valid UTF-8 with this
+    /// encoding instead of guessing it.
+    #[builder(default)]
+    pub encoding: Option<&'static Encoding>,
+    /// External counters for the files of some extensions (`--plugin`).
+    #[builder(default)]
+    pub plugins: Vec<Plugin>,
+    /// The time and memory limits of each plugin invocation.
+    #[builder(default)]
+    pub plugin_limits: PluginLimits,
+
+    /// Count standard input as a single file with this (pseudo) path
+    /// instead of walking `walk.roots`.
+    #[builder(default)]
+    pub stdin: Option<PathBuf>,
+    /// Explain why this path is counted or excluded instead of counting.
+    #[builder(default)]
+    pub why: Option<PathBuf>,
+    /// Only list the files that would be counted (`--list-only`).
+    #[builder(default)]
+    pub list_only: bool,
+    /// Write only the files that failed to process (`--errors-only`).
+    #[builder(default)]
+    pub errors_only: bool,
+    /// Write only the paths of the files, NUL-terminated (`--print0`),
+    /// instead of the formatted results.
+    #[builder(default)]
+    pub null_separated: bool,
+    /// Write nothing to stdout; failures are still logged (`--quiet`).
+    #[builder(default)]
+    pub quiet: bool,
+    /// Write the totals as one `key=value` line (`--oneline`).
+    #[builder(default)]
+    pub oneline: bool,
+
+    /// Token checked by the walk and counting stages to stop early.
+    #[builder(default)]
+    pub cancel: CancellationToken,
+    /// Collects the time spent detecting the encoding of the counted files.
+    #[builder(default)]
+    pub detect_time: Stopwatch,
+    /// Caps how many files are read at once (`--io-concurrency`).
+    #[builder(default)]
+    pub io_limit: IoLimit,
+    /// Capacity of each queue between the walk, counting and aggregation
+    /// stages (`--max-in-flight`); a full queue makes the stage before it wait.
+    #[builder(default = "Config::DEFAULT_MAX_IN_FLIGHT")]
+    pub max_in_flight: usize,
+    /// Stop the walk after this many files (`--max-files`).
+    #[builder(default)]
+    pub max_files: Option<usize>,
+    /// Stop the walk once the walked files exceed this many bytes
+    /// (`--max-total-bytes`).
+    #[builder(default)]
+    pub max_total_bytes: Option<u64>,
+    /// Whether exceeding `max_files` or `max_total_bytes` fails the run or
+    /// truncates it.
+    #[builder(default)]
+    pub on_limit: LimitAction,
+    /// Give up on a file that takes longer than this to read and count
+    /// (`--file-timeout`); it is reported as an error.
+    #[builder(default)]
+    pub file_timeout: Option<Duration>,
+    /// Stop the walk and give up on the remaining files once the run takes
+    /// longer than this (`--timeout`).
+    #[builder(default)]
+    pub timeout: Option<Duration>,
+
+    /// `--fail-if` thresholds; the run fails if any of them is true.
+    #[builder(default)]
+    pub fail_if: Vec<Threshold>,
 
     #[builder(default)]
     pub strict: bool,
@@ -111,11 +345,65 @@ pub struct Config {
     pub watch: bool,
     #[builder(default = "Duration::from_secs(1)")]
     pub watch_interval: Duration,
+    /// Quiet period after a file event before the watch cycle runs.
+    #[builder(default = "Duration::from_millis(200)")]
+    pub watch_debounce: Duration,
     #[builder(default = "WatchOutput::Full")]
     pub watch_output: WatchOutput,
+    /// Show the terminal dashboard instead of `watch_output`.
+    #[builder(default)]
+    pub watch_tui: bool,
+    /// Serve the totals in the Prometheus text format on this address.
+    #[builder(default)]
+    pub serve_metrics: Option<std::net::SocketAddr>,
+    /// How often `serve_metrics` recounts when not watching.
+    #[builder(default = "Duration::from_secs(60)")]
+    pub metrics_interval: Duration,
 
     #[builder(default)]
     pub compare: Option<(PathBuf, PathBuf)>,
+    /// Git revision range to compare (`A..B`, or `A` against the working tree).
+    #[builder(default)]
+    pub compare_ref: Option<String>,
+    /// Count each commit of the git history instead of the working tree.
+    #[builder(default)]
+    pub history: Option<HistoryOptions>,
+    /// Report the files that changed most in the git history (`--churn`).
+    #[builder(default)]
+    pub churn: Option<ChurnOptions>,
+    /// Report production and test code per language (`--test-split`).
+    #[builder(default)]
+    pub test_split: Option<TestSplitOptions>,
+    /// Report whitespace and line-ending problems (`--audit-whitespace`).
+    #[builder(default)]
+    pub audit_whitespace: bool,
+    /// Record BOMs and invalid UTF-8 of each file (`--audit-encoding`).
+    #[builder(default)]
+    pub audit_encoding: bool,
+    /// Text expected near the top of every file (`--license-header`).
+    #[builder(default)]
+    pub license_header: Option<String>,
+    #[builder(default = "CompareFormat::Table")]
+    pub compare_format: CompareFormat,
+    #[builder(default = "CompareSort::Path")]
+    pub compare_sort: CompareSort,
+    /// Also list unchanged files in comparisons.
+    #[builder(default)]
+    pub compare_unchanged: bool,
+
+    /// Directory for snapshots and other persisted state.
+    #[builder(default)]
+    pub cache_dir: Option<PathBuf>,
+    /// Reuse the results of unchanged files from the previous run.
+    #[builder(default)]
+    pub incremental: bool,
+    /// How unchanged files are recognised with `incremental`.
+    #[builder(default)]
+    pub cache_key: CacheKey,
+    /// Key cache entries by repository-relative path and content hash so
+    /// several checkouts can share one cache directory.
+    #[builder(default)]
+    pub shared_cache: bool,
 }
 
 impl Default for Config {
@@ -128,13 +416,114 @@ impl Default for Config {
             total_row: false,
             count_newlines_in_chars: false,
             progress: false,
+            output: None,
+            output_db: None,
+            relative_to: None,
+            group_by: vec![],
+            group_filter: vec![],
+            age_buckets: Self::default_age_buckets(),
+            codeowners: None,
+            top: None,
+            top_dirs: None,
+            columns: vec![],
+            human_numbers: false,
+            thousands_separator: None,
+            raw_bytes: false,
+            color: false,
+            theme: Theme::default(),
+            highlight_over: None,
+            distribution: false,
+            ratio_of: None,
+            ratio_base: RatioBase::Total,
+            tree_depth: None,
+            markdown_badges: false,
+            badge: None,
+            with_metadata: false,
+            timings: false,
+            trend: None,
+            detect_similar: None,
             count_words: false,
             count_sloc: false,
+            count_docs: false,
+            count_indent: false,
+            tab_width: 0,
+            languages: vec![],
+            detect_modeline: false,
+            include_minified: false,
+            chunk_size: None,
+            long_line_threshold: Self::DEFAULT_LONG_LINE_THRESHOLD,
+            encoding: None,
+            plugins: vec![],
+            plugin_limits: PluginLimits::default(),
+            stdin: None,
+            why: None,
+            list_only: false,
+            errors_only: false,
+            quiet: false,
+            oneline: false,
+            null_separated: false,
+            cancel: CancellationToken::default(),
+            detect_time: Stopwatch::default(),
+            io_limit: IoLimit::default(),
+            fail_if: vec![],
             strict: false,
             watch: false,
             watch_interval: Duration::from_secs(1),
+            watch_debounce: Duration::from_millis(200),
             watch_output: WatchOutput::Full,
+            watch_tui: false,
+            serve_metrics: None,
+            metrics_interval: Duration::from_secs(60),
             compare: None,
+            compare_ref: None,
+            history: None,
+            churn: None,
+            test_split: None,
+            audit_whitespace: false,
+            audit_encoding: false,
+            license_header: None,
+            compare_format: CompareFormat::Table,
+            compare_sort: CompareSort::Path,
+            compare_unchanged: false,
+            cache_dir: None,
+            incremental: false,
+            cache_key: CacheKey::Mtime,
+            shared_cache: false,
+            max_in_flight: Self::DEFAULT_MAX_IN_FLIGHT,
+            max_files: None,
+            max_total_bytes: None,
+            on_limit: LimitAction::Abort,
+            file_timeout: None,
+            timeout: None,
         }
     }
 }
+
+impl Config {
+    /// Default cache directory, relative to the working directory.
+    pub const DEFAULT_CACHE_DIR: &'static str = ".count_lines";
+
+    /// Default for [`Self::max_in_flight`].
+    pub const DEFAULT_MAX_IN_FLIGHT: usize = 1024;
+
+    /// Default of [`Self::long_line_threshold`]: 1 MiB.
+    pub const DEFAULT_LONG_LINE_THRESHOLD: u64 = 1 << 20;
+
+    /// Default for [`Self::age_buckets`]: one week, one month, six months.
+    #[must_use]
+    pub fn default_age_buckets() -> Vec<chrono::Duration> {
+        vec![
+            chrono::Duration::weeks(1),
+            chrono::Duration::days(30),
+            chrono::Duration::days(180),
+        ]
+    }
+
+    /// Returns the configured cache directory, or [`Self::DEFAULT_CACHE_DIR`].
+    #[must_use]
+    pub fn resolved_cache_dir(&self) -> PathBuf {
+        self.cache_dir
+            .clone()
+            .unwrap_or_else(|| PathBuf::from(Self::DEFAULT_CACHE_DIR))
+    }
+}
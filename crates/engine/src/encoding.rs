@@ -0,0 +1,280 @@
+// crates/engine/src/encoding.rs
+//! Encoding detection and transcoding to UTF-8 before counting.
+//!
+//! A BOM always wins; otherwise UTF-8 is used as is, and anything else is
+//! decoded with the `--encoding` override or the encoding guessed from the
+//! content (Shift_JIS, EUC-JP, Windows-1252, ...). Content that is mostly
+//! UTF-8, with a few stray invalid bytes, is still read as UTF-8.
+
+use std::borrow::Cow;
+use std::path::PathBuf;
+
+use chardetng::EncodingDetector;
+pub use encoding_rs::Encoding;
+use serde::{Deserialize, Serialize};
+
+use crate::stats::FileStats;
+
+/// Extensions of formats that are UTF-8 by specification or convention;
+/// invalid UTF-8 in them is reported by `--audit-encoding`.
+pub const UTF8_EXTENSIONS: &[&str] = &[
+    "rs", "go", "py", "js", "mjs", "cjs", "jsx", "ts", "tsx", "json", "toml", "yaml", "yml", "md",
+    "html", "css", "scss", "svelte", "vue", "swift", "kt", "dart", "zig", "ex", "exs", "rb",
+];
+
+/// A byte order mark at the start of a file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Bom {
+    Utf8,
+    Utf16Le,
+    Utf16Be,
+    Utf32Le,
+    Utf32Be,
+}
+
+impl Bom {
+    /// The BOM `content` starts with, if any.
+    #[must_use]
+    pub fn detect(content: &[u8]) -> Option<Self> {
+        // The UTF-32LE BOM starts with the UTF-16LE one.
+        if content.starts_with(b"\xFF\xFE\0\0") {
+            Some(Self::Utf32Le)
+        } else if content.starts_with(b"\0\0\xFE\xFF") {
+            Some(Self::Utf32Be)
+        } else if content.starts_with(b"\xEF\xBB\xBF") {
+            Some(Self::Utf8)
+        } else if content.starts_with(b"\xFF\xFE") {
+            Some(Self::Utf16Le)
+        } else if content.starts_with(b"\xFE\xFF") {
+            Some(Self::Utf16Be)
+        } else {
+            None
+        }
+    }
+}
+
+/// BOM use and invalid UTF-8 over the counted files (`--audit-encoding`).
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EncodingReport {
+    /// Files starting with a UTF-8 BOM.
+    pub utf8_bom: usize,
+    /// Files starting with a UTF-16 (LE or BE) BOM.
+    pub utf16_bom: usize,
+    /// Files starting with a UTF-32 (LE or BE) BOM.
+    pub utf32_bom: usize,
+    /// Text files without a BOM.
+    pub no_bom: usize,
+    /// Files with a [`UTF8_EXTENSIONS`] extension holding invalid UTF-8.
+    pub invalid_utf8: Vec<PathBuf>,
+}
+
+impl EncodingReport {
+    /// Sums the findings recorded in `stats`. Binary files count only when
+    /// they start with a BOM.
+    #[must_use]
+    pub fn from_stats(stats: &[FileStats]) -> Self {
+        let mut report = Self::default();
+        for s in stats {
+            match s.bom {
+                Some(Bom::Utf8) => report.utf8_bom += 1,
+                Some(Bom::Utf16Le | Bom::Utf16Be) => report.utf16_bom += 1,
+                Some(Bom::Utf32Le | Bom::Utf32Be) => report.utf32_bom += 1,
+                None if !s.is_binary => report.no_bom += 1,
+                None => {}
+            }
+            if s.invalid_utf8 {
+                report.invalid_utf8.push(s.path.clone());
+            }
+        }
+        report.invalid_utf8.sort();
+        report
+    }
+}
+
+/// Whether `content`, named with `extension`, should be UTF-8 but isn't.
+/// A UTF-8 BOM is allowed; other BOMs name their encoding.
+#[must_use]
+pub fn is_invalid_utf8(content: &[u8], extension: &str) -> bool {
+    let content = match Bom::detect(content) {
+        Some(Bom::Utf8) => &content[3..],
+        Some(_) => return false,
+        None => content,
+    };
+    UTF8_EXTENSIONS
+        .iter()
+        .any(|ext| ext.eq_ignore_ascii_case(extension))
+        && std::str::from_utf8(content).is_err()
+}
+
+/// Content converted to UTF-8.
+#[derive(Debug)]
+pub struct Decoded<'a> {
+    pub content: Cow<'a, [u8]>,
+    /// Name of the source encoding, or `None` for plain UTF-8 (and ASCII).
+    pub encoding: Option<&'static str>,
+}
+
+/// Looks up an encoding by one of its WHATWG labels (`sjis`, `euc-jp`,
+/// `latin1`, `utf-16le`, ...), case-insensitively.
+#[must_use]
+pub fn for_label(label: &str) -> Option<&'static Encoding> {
+    Encoding::for_label(label.trim().as_bytes())
+}
+
+/// Converts `content` to UTF-8.
+///
+/// UTF-8 content, BOM included, is returned unchanged. Binary content (a
+/// NUL byte in the first 8 KiB without a UTF-16 or UTF-32 BOM) is too, so
+/// it is still reported as binary.
+#[must_use]
+pub fn decode<'a>(content: &'a [u8], forced: Option<&'static Encoding>) -> Decoded<'a> {
+    let unchanged = |content| Decoded {
+        content: Cow::Borrowed(content),
+        encoding: None,
+    };
+
+    match Bom::detect(content) {
+        Some(Bom::Utf8) => return unchanged(content),
+        Some(Bom::Utf16Le) => return transcode(&content[2..], encoding_rs::UTF_16LE),
+        Some(Bom::Utf16Be) => return transcode(&content[2..], encoding_rs::UTF_16BE),
+        Some(Bom::Utf32Le) => return decode_utf32(&content[4..], u32::from_le_bytes, "UTF-32LE"),
+        Some(Bom::Utf32Be) => return decode_utf32(&content[4..], u32::from_be_bytes, "UTF-32BE"),
+        None => {}
+    }
+    if content[..content.len().min(8 * 1024)].contains(&0) {
+        return unchanged(content);
+    }
+    match forced {
+        Some(encoding) if encoding != encoding_rs::UTF_8 => transcode(content, encoding),
+        Some(_) => unchanged(content),
+        None if is_mostly_utf8(content) => unchanged(content),
+        None => {
+            let mut detector = EncodingDetector::new();
+            detector.feed(content, true);
+            transcode(content, detector.guess(None, true))
+        }
+    }
+}
+
+/// Whether `content` is UTF-8, allowing for a few invalid bytes: its valid
+/// non-ASCII characters outnumber its invalid sequences. Legacy encodings
+/// rarely happen to form valid multi-byte sequences.
+fn is_mostly_utf8(content: &[u8]) -> bool {
+    let mut non_ascii = 0usize;
+    let mut invalid = 0usize;
+    for chunk in content.utf8_chunks() {
+        non_ascii += chunk.valid().chars().filter(|c| !c.is_ascii()).count();
+        invalid += usize::from(!chunk.invalid().is_empty());
+    }
+    invalid == 0 || non_ascii > invalid
+}
+
+/// Decodes UTF-32 (after its BOM), which `encoding_rs` doesn't support.
+/// Invalid code points and a trailing partial unit become U+FFFD.
+fn decode_utf32(content: &[u8], unit: fn([u8; 4]) -> u32, name: &'static str) -> Decoded<'static> {
+    let mut text = String::with_capacity(content.len() / 4);
+    let mut units = content.chunks_exact(4);
+    for bytes in &mut units {
+        let code = unit(bytes.try_into().expect("chunks of 4 bytes"));
+        text.push(char::from_u32(code).unwrap_or(char::REPLACEMENT_CHARACTER));
+    }
+    if !units.remainder().is_empty() {
+        text.push(char::REPLACEMENT_CHARACTER);
+    }
+    Decoded {
+        content: Cow::Owned(text.into_bytes()),
+        encoding: Some(name),
+    }
+}
+
+fn transcode(content: &[u8], encoding: &'static Encoding) -> Decoded<'static> {
+    let (text, _) = encoding.decode_without_bom_handling(content);
+    Decoded {
+        content: Cow::Owned(text.into_owned().into_bytes()),
+        encoding: Some(encoding.name()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_utf8_is_borrowed() {
+        let decoded = decode("日本語\n".as_bytes(), None);
+        assert!(matches!(decoded.content, Cow::Borrowed(_)));
+        assert_eq!(decoded.encoding, None);
+    }
+
+    #[test]
+    fn test_detects_shift_jis() {
+        let (sjis, _, _) =
+            encoding_rs::SHIFT_JIS.encode("こんにちは、世界。日本語のテキストです。\n");
+        let decoded = decode(&sjis, None);
+        assert_eq!(decoded.encoding, Some("Shift_JIS"));
+        assert_eq!(
+            std::str::from_utf8(&decoded.content).unwrap(),
+            "こんにちは、世界。日本語のテキストです。\n"
+        );
+    }
+
+    #[test]
+    fn test_bom_and_override() {
+        let decoded = decode(b"\xFF\xFEa\0\n\0", None);
+        assert_eq!(decoded.encoding, Some("UTF-16LE"));
+        assert_eq!(&decoded.content[..], b"a\n");
+
+        let decoded = decode(b"caf\xE9\n", for_label("latin1"));
+        assert_eq!(decoded.encoding, Some("windows-1252"));
+        assert_eq!(&decoded.content[..], "café\n".as_bytes());
+        assert_eq!(for_label("SJIS"), Some(encoding_rs::SHIFT_JIS));
+    }
+
+    #[test]
+    fn test_stray_invalid_byte_stays_utf8() {
+        let mut bytes = "// 日本語のコメント\nfn main() {}\n".as_bytes().to_vec();
+        bytes.insert(3, 0xFF);
+        let decoded = decode(&bytes, None);
+        assert_eq!(decoded.encoding, None);
+        assert!(matches!(decoded.content, Cow::Borrowed(_)));
+    }
+
+    #[test]
+    fn test_utf8_bom_is_kept() {
+        let decoded = decode(b"\xEF\xBB\xBFabc", None);
+        assert_eq!(decoded.encoding, None);
+        assert_eq!(&decoded.content[..], b"\xEF\xBB\xBFabc");
+    }
+
+    #[test]
+    fn test_utf32_bom() {
+        let decoded = decode(b"\xFF\xFE\0\0a\0\0\0\n\0\0\0", None);
+        assert_eq!(decoded.encoding, Some("UTF-32LE"));
+        assert_eq!(&decoded.content[..], b"a\n");
+
+        let decoded = decode(b"\0\0\xFE\xFF\0\0\x30\x42\0\0\0", None);
+        assert_eq!(decoded.encoding, Some("UTF-32BE"));
+        assert_eq!(&decoded.content[..], "\u{3042}\u{FFFD}".as_bytes());
+    }
+
+    #[test]
+    fn test_detect_bom() {
+        assert_eq!(Bom::detect(b"\xEF\xBB\xBFfn"), Some(Bom::Utf8));
+        assert_eq!(Bom::detect(b"\xFF\xFEa\0"), Some(Bom::Utf16Le));
+        assert_eq!(Bom::detect(b"\xFE\xFF\0a"), Some(Bom::Utf16Be));
+        assert_eq!(Bom::detect(b"\xFF\xFE\0\0a\0\0\0"), Some(Bom::Utf32Le));
+        assert_eq!(Bom::detect(b"\0\0\xFE\xFF"), Some(Bom::Utf32Be));
+        assert_eq!(Bom::detect(b"fn main() {}"), None);
+    }
+
+    #[test]
+    fn test_is_invalid_utf8() {
+        assert!(is_invalid_utf8(b"let s = \"caf\xE9\";\n", "rs"));
+        assert!(is_invalid_utf8(b"\xEF\xBB\xBF\xE9", "JSON"));
+        assert!(!is_invalid_utf8("café\n".as_bytes(), "rs"));
+        // Legacy encodings are expected outside the UTF-8 formats.
+        assert!(!is_invalid_utf8(b"caf\xE9\n", "txt"));
+        assert!(!is_invalid_utf8(b"\xFF\xFEa\0", "rs"));
+    }
+}
@@ -1,4 +1,5 @@
 // crates/engine/src/error.rs
+use crate::limits::ScanLimit;
 use thiserror::Error;
 
 #[derive(Debug, Error)]
@@ -46,14 +47,99 @@ pub enum EngineError {
     #[error("Text processing failed: {0}")]
     TextProcessing(String),
 
+    #[error("Git error: {0}")]
+    Git(String),
+
     #[error("Cache operation failed: {0}")]
     Cache(String),
 
     #[error("Unknown extension: {0}")]
     UnknownExtension(String),
 
+    #[error("Scan limit exceeded: {0}")]
+    LimitExceeded(ScanLimit),
+
+    #[error("Skipped {kind} '{path}' (use --include-special to count it)")]
+    SpecialFile {
+        path: std::path::PathBuf,
+        kind: &'static str,
+    },
+
+    #[error("Timed out after {after:?}: '{path}'")]
+    Timeout {
+        path: std::path::PathBuf,
+        after: std::time::Duration,
+    },
+
+    #[error("Plugin failed on '{path}': {message}")]
+    Plugin {
+        path: std::path::PathBuf,
+        message: String,
+    },
+
     #[error("IO error: {0}")]
     Io(std::io::Error),
 }
 
+impl EngineError {
+    /// A stable, machine-readable name for the error, e.g.
+    /// `permission_denied` for a file that can't be read.
+    #[must_use]
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::FileRead { source, .. } | Self::Io(source) => match source.kind() {
+                std::io::ErrorKind::NotFound => "not_found",
+                std::io::ErrorKind::PermissionDenied => "permission_denied",
+                std::io::ErrorKind::InvalidData => "invalid_data",
+                std::io::ErrorKind::Interrupted => "interrupted",
+                _ => "io",
+            },
+            Self::Walk(_) => "walk",
+            Self::Json(_) => "json",
+            Self::Regex(_) => "regex",
+            Self::Watch(_) => "watch",
+            Self::FileTooSmall { .. }
+            | Self::FileTooLarge { .. }
+            | Self::FileTooOld { .. }
+            | Self::ExtensionNotAllowed(_)
+            | Self::NoExtension
+            | Self::UnknownExtension(_) => "filtered",
+            Self::Config(_) | Self::InvalidExtMapping(_) => "config",
+            Self::TextProcessing(_) => "text_processing",
+            Self::Git(_) => "git",
+            Self::Cache(_) => "cache",
+            Self::LimitExceeded(_) => "limit",
+            Self::Timeout { .. } => "timeout",
+            Self::SpecialFile { .. } => "special_file",
+            Self::Plugin { .. } => "plugin",
+        }
+    }
+}
+
+impl From<derive_builder::UninitializedFieldError> for EngineError {
+    fn from(err: derive_builder::UninitializedFieldError) -> Self {
+        Self::Config(err.to_string())
+    }
+}
+
 pub type Result<T> = std::result::Result<T, EngineError>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_kind_names_io_failures() {
+        let read = |kind| EngineError::FileRead {
+            path: "a.rs".into(),
+            source: std::io::Error::from(kind),
+        };
+        assert_eq!(
+            read(std::io::ErrorKind::PermissionDenied).kind(),
+            "permission_denied"
+        );
+        assert_eq!(read(std::io::ErrorKind::NotFound).kind(), "not_found");
+        assert_eq!(read(std::io::ErrorKind::Other).kind(), "io");
+        assert_eq!(EngineError::Cache("x".into()).kind(), "cache");
+    }
+}
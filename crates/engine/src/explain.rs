@@ -0,0 +1,591 @@
+// crates/engine/src/explain.rs
+//! Explains why a single path is counted or excluded (`--why`).
+//!
+//! The rules are evaluated in the order the walk applies them: every
+//! directory between the scanned root and the file is checked against the
+//! overrides, the ignore files and the hidden-file rule, then the file itself
+//! against the path filters and, once counted, the result filters.
+
+use crate::archive::ArchiveKind;
+use crate::attributes::LinguistAttributes;
+use crate::config::Config;
+use crate::error::{EngineError, Result};
+use crate::filesystem::{IGNORE_FILENAMES, build_overrides, collect_normalized_exts, extension_of};
+use crate::options::LinguistMode;
+use crate::processor;
+use ignore::Match;
+use ignore::gitignore::{Gitignore, GitignoreBuilder, Glob};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// One rule evaluated for the explained path.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Check {
+    /// Rule name, e.g. `.gitignore`, `override`, `ext`.
+    pub rule: String,
+    /// What the rule saw: the matching pattern and its source, or the value
+    /// compared against the limit.
+    pub detail: String,
+    /// True if the rule excludes the path.
+    pub excluded: bool,
+}
+
+/// The rules evaluated for a path, in evaluation order.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Explanation {
+    pub path: PathBuf,
+    pub checks: Vec<Check>,
+}
+
+impl Explanation {
+    /// The first rule that excludes the path, if any.
+    #[must_use]
+    pub fn excluded_by(&self) -> Option<&Check> {
+        self.checks.iter().find(|c| c.excluded)
+    }
+
+    fn check(&mut self, rule: &str, detail: impl Into<String>, excluded: bool) {
+        self.checks.push(Check {
+            rule: rule.to_string(),
+            detail: detail.into(),
+            excluded,
+        });
+    }
+}
+
+/// Ignore file kinds, in the precedence order of the walk.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+enum IgnoreKind {
+    CountLines,
+    Ignore,
+    GitIgnore,
+    GitExclude,
+    Global,
+}
+
+impl IgnoreKind {
+    const fn as_str(self) -> &'static str {
+        match self {
+            Self::CountLines => ".count_linesignore",
+            Self::Ignore => ".ignore",
+            Self::GitIgnore => ".gitignore",
+            Self::GitExclude => "git-exclude",
+            Self::Global => "global-gitignore",
+        }
+    }
+}
+
+/// Runs the walk and filter rules of `config` for `path`.
+///
+/// # Errors
+/// Returns an error if `path` doesn't exist or the override patterns are
+/// invalid.
+pub fn explain(path: &Path, config: &Config) -> Result<Explanation> {
+    explain_with(path, config, true)
+}
+
+/// Whether the walk of `config` yields `path`, i.e. it passes every rule
+/// but the result filters, which need the file counted.
+///
+/// # Errors
+/// Same as [`explain`].
+pub(crate) fn is_walked(path: &Path, config: &Config) -> Result<bool> {
+    Ok(explain_with(path, config, false)?.excluded_by().is_none())
+}
+
+fn explain_with(path: &Path, config: &Config, count: bool) -> Result<Explanation> {
+    let read_error = |source| EngineError::FileRead {
+        path: path.to_path_buf(),
+        source,
+    };
+    let target = path.canonicalize().map_err(read_error)?;
+    let meta = std::fs::metadata(&target).map_err(read_error)?;
+    let mut explanation = Explanation {
+        path: path.to_path_buf(),
+        checks: Vec::new(),
+    };
+
+    let roots = if config.walk.roots.is_empty() {
+        vec![PathBuf::from(".")]
+    } else {
+        config.walk.roots.clone()
+    };
+    let root = roots
+        .iter()
+        .filter_map(|r| r.canonicalize().ok().map(|c| (r, c)))
+        .find(|(_, c)| target.starts_with(c));
+    match &root {
+        Some((shown, _)) => explanation.check("root", shown.display().to_string(), false),
+        None => explanation.check("root", "not under any scanned path", true),
+    }
+
+    if let Some((_, root)) = &root {
+        explain_walk(&mut explanation, root, &target, meta.is_dir(), config)?;
+    }
+    if meta.is_dir() {
+        explanation.check("file-type", "directory", true);
+        return Ok(explanation);
+    }
+    explain_filters(&mut explanation, &target, &meta, config, count);
+    Ok(explanation)
+}
+
+/// Checks every entry from the root down to `target`, stopping at the
+/// first one the walk would skip (it never descends into it).
+fn explain_walk(
+    explanation: &mut Explanation,
+    root: &Path,
+    target: &Path,
+    is_dir: bool,
+    config: &Config,
+) -> Result<()> {
+    let walk = &config.walk;
+    let relative = target.strip_prefix(root).unwrap_or(target);
+    let depth = relative.components().count();
+    if let Some(max) = walk.max_depth {
+        explanation.check(
+            "max-depth",
+            format!("depth {depth}, max {max}"),
+            depth > max,
+        );
+    }
+
+    let overrides = build_overrides(root, walk, &config.filter)?;
+    let patterns = OverridePatterns::new(root, config);
+    let mut ignore_files = IgnoreFiles::new(walk.git_ignore);
+    let mut entry = root.to_path_buf();
+    for (i, component) in relative.components().enumerate() {
+        entry.push(component);
+        let entry_is_dir = i + 1 < depth || is_dir;
+        let shown = entry.strip_prefix(root).unwrap_or(&entry).display();
+
+        if let Some(overrides) = &overrides {
+            match overrides.matched(&entry, entry_is_dir) {
+                Match::Ignore(_) => {
+                    let source = patterns
+                        .source(&entry, entry_is_dir)
+                        .unwrap_or_else(|| "not matched by any include pattern".to_string());
+                    explanation.check("override", format!("{shown}: {source}"), true);
+                    return Ok(());
+                }
+                Match::Whitelist(_) => {
+                    let source = patterns.source(&entry, entry_is_dir).unwrap_or_default();
+                    explanation.check("override", format!("{shown}: {source}"), false);
+                    continue;
+                }
+                Match::None => {}
+            }
+        }
+
+        let (whitelisted, ignored) =
+            ignore_files.explain(explanation, &entry, entry_is_dir, &shown.to_string());
+        if ignored {
+            return Ok(());
+        }
+
+        let name = component.as_os_str().to_string_lossy();
+        if !walk.hidden && name.starts_with('.') && !whitelisted {
+            explanation.check("hidden", format!("{shown} (use --hidden)"), true);
+            return Ok(());
+        }
+    }
+    if ignore_files.consulted > 0 && !ignore_files.matched {
+        explanation.check(
+            "ignore-files",
+            format!("no match in {} file(s)", ignore_files.consulted),
+            false,
+        );
+    }
+    Ok(())
+}
+
+/// Path filters applied during the walk, then the result filters applied
+/// once the file is counted.
+fn explain_filters(
+    explanation: &mut Explanation,
+    target: &Path,
+    meta: &std::fs::Metadata,
+    config: &Config,
+    count: bool,
+) {
+    let filter = &config.filter;
+    if config.walk.linguist == Some(LinguistMode::Exclude) {
+        let class = LinguistAttributes::new().classify(target);
+        explanation.check(
+            "linguist",
+            class.map_or("no linguist attribute", |c| c.as_str()),
+            class.is_some(),
+        );
+    }
+    if config.walk.scan_archives && ArchiveKind::from_path(target).is_some() {
+        explanation.check("archive", "members are filtered individually", false);
+        return;
+    }
+
+    let ext = extension_of(target);
+    let shown_ext = ext.as_deref().unwrap_or("(none)");
+    let allow_ext = collect_normalized_exts(&filter.allow_ext);
+    if !allow_ext.is_empty() {
+        let allowed = ext.as_ref().is_some_and(|e| allow_ext.contains(e));
+        explanation.check("ext", format!("{shown_ext} (--ext)"), !allowed);
+    }
+    let deny_ext = collect_normalized_exts(&filter.deny_ext);
+    if !deny_ext.is_empty() {
+        let denied = ext.as_ref().is_some_and(|e| deny_ext.contains(e));
+        explanation.check("deny-ext", shown_ext, denied);
+    }
+    let size = meta.len();
+    if let Some(min) = filter.min_size {
+        explanation.check("min-size", format!("{size} < {min}"), size < min);
+    }
+    if let Some(max) = filter.max_size {
+        explanation.check("max-size", format!("{size} > {max}"), size > max);
+    }
+    if filter.mtime_since.is_some() || filter.mtime_until.is_some() {
+        let modified = meta
+            .modified()
+            .ok()
+            .map(chrono::DateTime::<chrono::Local>::from);
+        let shown = modified.map_or_else(|| "unknown".to_string(), |m| m.to_rfc3339());
+        let outside = modified.is_none_or(|m| {
+            filter.mtime_since.is_some_and(|since| m < since)
+                || filter.mtime_until.is_some_and(|until| m > until)
+        });
+        explanation.check("mtime", shown, outside);
+    }
+    if !count {
+        return;
+    }
+
+    let stats = match processor::process_file((target.to_path_buf(), meta.clone()), config) {
+        Ok(stats) => stats,
+        Err(err) => {
+            explanation.check("read", err.to_string(), true);
+            return;
+        }
+    };
+    let ranges = [
+        ("min-lines", stats.lines, filter.min_lines, false),
+        ("max-lines", stats.lines, filter.max_lines, true),
+        ("min-chars", stats.chars, filter.min_chars, false),
+        ("max-chars", stats.chars, filter.max_chars, true),
+    ];
+    for (rule, value, limit, is_max) in ranges {
+        if let Some(limit) = limit {
+            let excluded = if is_max { value > limit } else { value < limit };
+            explanation.check(rule, format!("{value}, limit {limit}"), excluded);
+        }
+    }
+    if filter.min_words.is_some() || filter.max_words.is_some() {
+        let excluded = stats.words.is_none_or(|w| {
+            filter.min_words.is_some_and(|min| w < min)
+                || filter.max_words.is_some_and(|max| w > max)
+        });
+        let shown = stats
+            .words
+            .map_or_else(|| "not counted".to_string(), |w| w.to_string());
+        explanation.check("words", shown, excluded);
+    }
+    for expr in &filter.expressions {
+        explanation.check("filter", expr.0.to_string(), !expr.matches(&stats));
+    }
+    if stats.minified && !config.include_minified {
+        explanation.check(
+            "minified",
+            "reported separately (use --include-minified)",
+            true,
+        );
+    }
+}
+
+/// The include/exclude patterns of the walk with the flag each came from.
+///
+/// The walk's `Override` doesn't expose the glob that matched, so the same
+/// patterns are matched again as a gitignore to find it.
+struct OverridePatterns {
+    matcher: Gitignore,
+    flags: HashMap<String, &'static str>,
+}
+
+impl OverridePatterns {
+    fn new(root: &Path, config: &Config) -> Self {
+        let (walk, filter) = (&config.walk, &config.filter);
+        let groups: [(&[String], &str, bool); 4] = [
+            (&walk.override_include, "--override-include", false),
+            (&walk.override_exclude, "--override-exclude", true),
+            (&filter.include_patterns, "--include", false),
+            (&filter.exclude_patterns, "--exclude", true),
+        ];
+        let mut builder = GitignoreBuilder::new(root);
+        let _ = builder.case_insensitive(filter.glob_case_insensitive);
+        let mut flags = HashMap::new();
+        for (patterns, flag, exclude) in groups {
+            for pattern in patterns {
+                let line = if exclude {
+                    format!("!{pattern}")
+                } else {
+                    pattern.clone()
+                };
+                if builder.add_line(None, &line).is_ok() {
+                    flags.insert(line, flag);
+                }
+            }
+        }
+        Self {
+            matcher: builder.build().unwrap_or_else(|_| Gitignore::empty()),
+            flags,
+        }
+    }
+
+    /// `--flag pattern` of the last pattern matching `entry`.
+    fn source(&self, entry: &Path, is_dir: bool) -> Option<String> {
+        let glob = match self.matcher.matched(entry, is_dir) {
+            Match::Ignore(glob) | Match::Whitelist(glob) => glob,
+            Match::None => return None,
+        };
+        let line = glob.original();
+        let flag = self.flags.get(line).copied().unwrap_or("override");
+        Some(format!("{flag} {}", line.trim_start_matches('!')))
+    }
+}
+
+/// Loads the ignore files above an entry on demand, one matcher per file.
+struct IgnoreFiles {
+    git_ignore: bool,
+    global: Option<Option<Gitignore>>,
+    dirs: HashMap<PathBuf, Vec<(IgnoreKind, Gitignore)>>,
+    consulted: usize,
+    matched: bool,
+}
+
+impl IgnoreFiles {
+    fn new(git_ignore: bool) -> Self {
+        Self {
+            git_ignore,
+            global: None,
+            dirs: HashMap::new(),
+            consulted: 0,
+            matched: false,
+        }
+    }
+
+    /// Records the deciding ignore file match for `entry`, and returns
+    /// whether the entry is whitelisted or ignored.
+    ///
+    /// As in the walk, the deepest match of each kind wins, and kinds are
+    /// ranked `.count_linesignore` > `.ignore` > `.gitignore` >
+    /// `.git/info/exclude` > global.
+    fn explain(
+        &mut self,
+        explanation: &mut Explanation,
+        entry: &Path,
+        is_dir: bool,
+        shown: &str,
+    ) -> (bool, bool) {
+        let repo = entry
+            .ancestors()
+            .skip(1)
+            .find(|dir| dir.join(".git").exists())
+            .map(Path::to_path_buf);
+        let mut best: HashMap<IgnoreKind, (bool, String)> = HashMap::new();
+        let mut record = |kind, matched: Match<&Glob>| {
+            if best.contains_key(&kind) {
+                return;
+            }
+            match matched {
+                Match::Ignore(glob) => best.insert(kind, (true, locate(glob))),
+                Match::Whitelist(glob) => best.insert(kind, (false, locate(glob))),
+                Match::None => None,
+            };
+        };
+
+        let mut in_repo = repo.is_some();
+        for dir in entry.ancestors().skip(1) {
+            for (kind, matcher) in self.load(dir, in_repo) {
+                record(*kind, matcher.matched(entry, is_dir));
+            }
+            if repo.as_deref() == Some(dir) {
+                in_repo = false;
+            }
+        }
+        if let (true, Some(repo)) = (self.git_ignore, &repo) {
+            let exclude = load_matcher(repo, &repo.join(".git/info/exclude"));
+            if let Some(matcher) = &exclude {
+                record(IgnoreKind::GitExclude, matcher.matched(entry, is_dir));
+            }
+            let global = self.global.get_or_insert_with(|| {
+                ignore::gitignore::gitconfig_excludes_path()
+                    .and_then(|path| load_matcher(repo, &path))
+            });
+            if let Some(matcher) = global {
+                record(IgnoreKind::Global, matcher.matched(entry, is_dir));
+            }
+        }
+
+        let mut kinds: Vec<_> = best.into_iter().collect();
+        kinds.sort_by_key(|(kind, _)| *kind);
+        let deciding = kinds.first().map(|(_, (ignored, _))| *ignored);
+        self.matched |= deciding.is_some();
+        for (i, (kind, (ignored, source))) in kinds.into_iter().enumerate() {
+            let detail = if i == 0 {
+                format!("{shown}: {source}")
+            } else {
+                format!("{shown}: {source} (overridden)")
+            };
+            explanation.check(kind.as_str(), detail, i == 0 && ignored);
+        }
+        (deciding == Some(false), deciding == Some(true))
+    }
+
+    /// The `.count_linesignore`, `.ignore` and, inside a repository,
+    /// `.gitignore` of `dir`.
+    fn load(&mut self, dir: &Path, in_repo: bool) -> &[(IgnoreKind, Gitignore)] {
+        let git_ignore = self.git_ignore && in_repo;
+        if !self.dirs.contains_key(dir) {
+            let mut matchers = Vec::new();
+            // The walk merges both names into one matcher per directory.
+            let mut builder = GitignoreBuilder::new(dir);
+            for name in IGNORE_FILENAMES {
+                let file = dir.join(name);
+                if file.is_file() {
+                    builder.add(file);
+                }
+            }
+            if let Some(m) = builder.build().ok().filter(|m| !m.is_empty()) {
+                matchers.push((IgnoreKind::CountLines, m));
+            }
+            if let Some(m) = load_matcher(dir, &dir.join(".ignore")) {
+                matchers.push((IgnoreKind::Ignore, m));
+            }
+            if git_ignore && let Some(m) = load_matcher(dir, &dir.join(".gitignore")) {
+                matchers.push((IgnoreKind::GitIgnore, m));
+            }
+            self.consulted += matchers.len();
+            self.dirs.insert(dir.to_path_buf(), matchers);
+        }
+        &self.dirs[dir]
+    }
+}
+
+fn load_matcher(root: &Path, file: &Path) -> Option<Gitignore> {
+    if !file.is_file() {
+        return None;
+    }
+    let mut builder = GitignoreBuilder::new(root);
+    builder.add(file);
+    builder.build().ok().filter(|m| !m.is_empty())
+}
+
+/// Formats a matching pattern as `file:line:pattern`, like
+/// `git check-ignore -v`.
+fn locate(glob: &Glob) -> String {
+    let Some(from) = glob.from() else {
+        return glob.original().to_string();
+    };
+    let line = std::fs::read_to_string(from).ok().and_then(|content| {
+        content
+            .lines()
+            .enumerate()
+            .filter(|(_, l)| l.trim_end() == glob.original())
+            .last()
+            .map(|(i, _)| i + 1)
+    });
+    // Shown relative to the working directory when below it, as git does.
+    let cwd = std::env::current_dir().and_then(|d| d.canonicalize()).ok();
+    let from = cwd
+        .as_deref()
+        .and_then(|cwd| from.strip_prefix(cwd).ok())
+        .unwrap_or(from);
+    match line {
+        Some(line) => format!("{}:{line}:{}", from.display(), glob.original()),
+        None => format!("{}:{}", from.display(), glob.original()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::WalkOptions;
+
+    fn config(root: &Path) -> Config {
+        Config {
+            walk: WalkOptions {
+                roots: vec![root.to_path_buf()],
+                ..WalkOptions::default()
+            },
+            ..Config::default()
+        }
+    }
+
+    #[test]
+    fn test_gitignore_directory_rule() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::create_dir_all(root.join(".git")).unwrap();
+        std::fs::create_dir_all(root.join("src/gen")).unwrap();
+        std::fs::write(root.join(".gitignore"), "# generated\nsrc/gen/\n").unwrap();
+        std::fs::write(root.join("src/gen/foo.rs"), "fn f() {}\n").unwrap();
+
+        let explanation = explain(&root.join("src/gen/foo.rs"), &config(root)).unwrap();
+        let by = explanation.excluded_by().unwrap();
+        assert_eq!(by.rule, ".gitignore");
+        assert!(by.detail.starts_with("src/gen: "));
+        assert!(by.detail.ends_with(".gitignore:2:src/gen/"));
+    }
+
+    #[test]
+    fn test_whitelist_and_filters() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::write(root.join(".ignore"), "*.log\n!keep.log\n").unwrap();
+        std::fs::write(root.join(".clignore"), "other.log\n").unwrap();
+        std::fs::write(root.join("keep.log"), "a\nb\nc\n").unwrap();
+
+        let mut config = config(root);
+        config.filter.max_lines = Some(2);
+        let explanation = explain(&root.join("keep.log"), &config).unwrap();
+        let ignore = &explanation.checks[1];
+        assert_eq!(ignore.rule, ".ignore");
+        assert!(!ignore.excluded);
+        assert!(ignore.detail.ends_with(":2:!keep.log"));
+        let by = explanation.excluded_by().unwrap();
+        assert_eq!(
+            (by.rule.as_str(), by.detail.as_str()),
+            ("max-lines", "3, limit 2")
+        );
+
+        std::fs::write(root.join("other.log"), "").unwrap();
+        let explanation = explain(&root.join("other.log"), &config).unwrap();
+        let by = explanation.excluded_by().unwrap();
+        assert_eq!(by.rule, ".count_linesignore");
+        assert!(by.detail.ends_with(".clignore:1:other.log"));
+    }
+
+    #[test]
+    fn test_hidden_and_override() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::create_dir_all(root.join(".cache")).unwrap();
+        std::fs::write(root.join(".cache/a.rs"), "").unwrap();
+        std::fs::write(root.join("b.rs"), "").unwrap();
+
+        let mut config = config(root);
+        let explanation = explain(&root.join(".cache/a.rs"), &config).unwrap();
+        assert_eq!(explanation.excluded_by().unwrap().rule, "hidden");
+
+        config.filter.exclude_patterns = vec!["b.*".to_string()];
+        let explanation = explain(&root.join("b.rs"), &config).unwrap();
+        let by = explanation.excluded_by().unwrap();
+        assert_eq!(
+            (by.rule.as_str(), by.detail.as_str()),
+            ("override", "b.rs: --exclude b.*")
+        );
+
+        config.filter.exclude_patterns.clear();
+        assert_eq!(
+            explain(&root.join("b.rs"), &config).unwrap().excluded_by(),
+            None
+        );
+    }
+}
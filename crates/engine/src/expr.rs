@@ -0,0 +1,860 @@
+// crates/engine/src/expr.rs
+//! Small boolean expression language used by `--filter`, `--group-filter`
+//! and `--fail-if`.
+//!
+//! ```text
+//! expr       := or
+//! or         := and (("||" | "or") and)*
+//! and        := unary (("&&" | "and") unary)*
+//! unary      := ("!" | "not") unary | "(" expr ")" | comparison
+//! comparison := operand ("<" | "<=" | ">" | ">=" | "==" | "!=") operand
+//!             | operand "contains" string
+//!             | operand "matches" string
+//! operand    := identifier | number | string
+//! string     := '"' chars '"' | "'" chars "'"
+//! ```
+//!
+//! Numbers may contain `_` separators (`500_000`). Strings compare with
+//! `==` / `!=`, `contains` tests for a substring and `matches` for a
+//! regular expression; `\` escapes the quote and itself, other escapes are
+//! kept for the regex. Identifiers are resolved by the caller at evaluation
+//! time, so the same machinery can evaluate per-file filters and summary
+//! thresholds.
+
+use regex::Regex;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::str::FromStr;
+use thiserror::Error;
+
+use crate::grouping::GroupStats;
+use crate::stats::FileStats;
+
+/// Error raised when an expression cannot be parsed.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("{message} (at position {position} in '{input}')")]
+pub struct ExprError {
+    /// What went wrong.
+    pub message: String,
+    /// Byte offset in the input.
+    pub position: usize,
+    /// The whole expression.
+    pub input: String,
+}
+
+/// Comparison operator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CmpOp {
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Eq,
+    Ne,
+    /// The string on the left contains the one on the right.
+    Contains,
+    /// The string on the left matches the regex on the right.
+    Matches,
+}
+
+impl CmpOp {
+    fn apply(self, lhs: f64, rhs: f64) -> bool {
+        match self {
+            Self::Lt => lhs < rhs,
+            Self::Le => lhs <= rhs,
+            Self::Gt => lhs > rhs,
+            Self::Ge => lhs >= rhs,
+            Self::Eq => (lhs - rhs).abs() < f64::EPSILON,
+            Self::Ne => (lhs - rhs).abs() >= f64::EPSILON,
+            Self::Contains | Self::Matches => false,
+        }
+    }
+
+    /// Whether the operator works on strings.
+    const fn is_textual(self) -> bool {
+        matches!(self, Self::Eq | Self::Ne | Self::Contains | Self::Matches)
+    }
+
+    const fn as_str(self) -> &'static str {
+        match self {
+            Self::Lt => "<",
+            Self::Le => "<=",
+            Self::Gt => ">",
+            Self::Ge => ">=",
+            Self::Eq => "==",
+            Self::Ne => "!=",
+            Self::Contains => "contains",
+            Self::Matches => "matches",
+        }
+    }
+}
+
+/// A compiled `matches` pattern; compared and serialized as its source.
+#[derive(Debug, Clone)]
+pub struct Pattern(pub Regex);
+
+impl PartialEq for Pattern {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.as_str() == other.0.as_str()
+    }
+}
+
+impl Serialize for Pattern {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.0.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for Pattern {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let source = String::deserialize(deserializer)?;
+        Regex::new(&source)
+            .map(Self)
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+/// The value of a field or literal during evaluation.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Number(f64),
+    Text(String),
+}
+
+/// Operand of a comparison.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Operand {
+    Field(String),
+    Number(f64),
+    Str(String),
+    /// The right side of `matches`.
+    Regex(Pattern),
+}
+
+impl Operand {
+    fn resolve(&self, lookup: &dyn Fn(&str) -> Option<Value>) -> Option<Value> {
+        match self {
+            Self::Field(name) => lookup(name),
+            Self::Number(value) => Some(Value::Number(*value)),
+            Self::Str(text) => Some(Value::Text(text.clone())),
+            Self::Regex(_) => None,
+        }
+    }
+}
+
+/// Writes `text` as a double-quoted string literal.
+fn quote(f: &mut fmt::Formatter<'_>, text: &str) -> fmt::Result {
+    write!(f, "\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Parsed expression tree.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Expr {
+    Compare(Operand, CmpOp, Operand),
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+    Not(Box<Expr>),
+}
+
+impl Expr {
+    /// Parses an expression.
+    ///
+    /// # Errors
+    /// Returns an error describing the first syntax error.
+    pub fn parse(input: &str) -> Result<Self, ExprError> {
+        let tokens = tokenize(input)?;
+        let mut parser = Parser {
+            input,
+            tokens,
+            pos: 0,
+        };
+        let expr = parser.parse_or()?;
+        match parser.peek() {
+            None => Ok(expr),
+            Some((token, position)) => {
+                Err(parser.error_at(format!("unexpected '{}'", token.text()), position))
+            }
+        }
+    }
+
+    /// Returns every field name referenced by the expression.
+    #[must_use]
+    pub fn fields(&self) -> Vec<&str> {
+        let mut fields = Vec::new();
+        self.collect_fields(&mut fields);
+        fields
+    }
+
+    fn collect_fields<'a>(&'a self, out: &mut Vec<&'a str>) {
+        match self {
+            Self::Compare(lhs, _, rhs) => {
+                for operand in [lhs, rhs] {
+                    if let Operand::Field(name) = operand {
+                        out.push(name);
+                    }
+                }
+            }
+            Self::And(lhs, rhs) | Self::Or(lhs, rhs) => {
+                lhs.collect_fields(out);
+                rhs.collect_fields(out);
+            }
+            Self::Not(inner) => inner.collect_fields(out),
+        }
+    }
+
+    /// Evaluates the expression over numeric fields resolved by `lookup`.
+    ///
+    /// A comparison involving a field that `lookup` cannot resolve (e.g.
+    /// `words` when words weren't counted) is false.
+    #[must_use]
+    pub fn eval(&self, lookup: &dyn Fn(&str) -> Option<f64>) -> bool {
+        self.eval_values(&|name| lookup(name).map(Value::Number))
+    }
+
+    /// Evaluates the expression over numeric and string fields. A
+    /// comparison between a number and a string is false.
+    #[must_use]
+    pub fn eval_values(&self, lookup: &dyn Fn(&str) -> Option<Value>) -> bool {
+        match self {
+            Self::Compare(lhs, CmpOp::Matches, Operand::Regex(pattern)) => {
+                matches!(lhs.resolve(lookup), Some(Value::Text(text)) if pattern.0.is_match(&text))
+            }
+            Self::Compare(lhs, op, rhs) => match (lhs.resolve(lookup), rhs.resolve(lookup)) {
+                (Some(Value::Number(lhs)), Some(Value::Number(rhs))) => op.apply(lhs, rhs),
+                (Some(Value::Text(lhs)), Some(Value::Text(rhs))) => match op {
+                    CmpOp::Eq => lhs == rhs,
+                    CmpOp::Ne => lhs != rhs,
+                    CmpOp::Contains => lhs.contains(&rhs),
+                    _ => false,
+                },
+                _ => false,
+            },
+            Self::And(lhs, rhs) => lhs.eval_values(lookup) && rhs.eval_values(lookup),
+            Self::Or(lhs, rhs) => lhs.eval_values(lookup) || rhs.eval_values(lookup),
+            Self::Not(inner) => !inner.eval_values(lookup),
+        }
+    }
+
+    /// Checks that strings are only compared with string operators and
+    /// fields of the right kind: `text_fields` hold strings, every other
+    /// field a number.
+    ///
+    /// # Errors
+    /// Returns an error describing the first mismatched comparison.
+    pub fn validate_types(&self, input: &str, text_fields: &[&str]) -> Result<(), ExprError> {
+        match self {
+            Self::Compare(lhs, op, rhs) => {
+                let is_text = |o: &Operand| match o {
+                    Operand::Field(name) => text_fields.contains(&name.as_str()),
+                    Operand::Number(_) => false,
+                    Operand::Str(_) | Operand::Regex(_) => true,
+                };
+                let (lhs_text, rhs_text) = (is_text(lhs), is_text(rhs));
+                let message = if lhs_text != rhs_text {
+                    Some(format!("cannot compare a string and a number in '{self}'"))
+                } else if lhs_text && !op.is_textual() {
+                    Some(format!(
+                        "strings can only be compared with ==, !=, contains or matches in '{self}'"
+                    ))
+                } else if !lhs_text && matches!(op, CmpOp::Contains | CmpOp::Matches) {
+                    Some(format!(
+                        "'{}' needs a string field in '{self}'",
+                        op.as_str()
+                    ))
+                } else {
+                    None
+                };
+                match message {
+                    Some(message) => Err(ExprError {
+                        message,
+                        position: 0,
+                        input: input.to_string(),
+                    }),
+                    None => Ok(()),
+                }
+            }
+            Self::And(lhs, rhs) | Self::Or(lhs, rhs) => {
+                lhs.validate_types(input, text_fields)?;
+                rhs.validate_types(input, text_fields)
+            }
+            Self::Not(inner) => inner.validate_types(input, text_fields),
+        }
+    }
+}
+
+impl fmt::Display for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fn operand(f: &mut fmt::Formatter<'_>, o: &Operand) -> fmt::Result {
+            match o {
+                Operand::Field(name) => write!(f, "{name}"),
+                Operand::Number(value) => write!(f, "{value}"),
+                Operand::Str(text) => quote(f, text),
+                Operand::Regex(pattern) => quote(f, pattern.0.as_str()),
+            }
+        }
+        match self {
+            Self::Compare(lhs, op, rhs) => {
+                operand(f, lhs)?;
+                write!(f, " {} ", op.as_str())?;
+                operand(f, rhs)
+            }
+            Self::And(lhs, rhs) => write!(f, "({lhs} && {rhs})"),
+            Self::Or(lhs, rhs) => write!(f, "({lhs} || {rhs})"),
+            Self::Not(inner) => write!(f, "!{inner}"),
+        }
+    }
+}
+
+/// A `--filter` expression over per-file statistics.
+///
+/// Field names are checked against [`FileStats::FIELDS`] and
+/// [`FileStats::TEXT_FIELDS`] when parsing.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FilterExpr(pub Expr);
+
+impl FilterExpr {
+    /// Returns true if `stats` satisfies the expression.
+    #[must_use]
+    pub fn matches(&self, stats: &FileStats) -> bool {
+        self.0.eval_values(&|name| stats.value(name))
+    }
+
+    /// Returns true if the expression references `field`.
+    #[must_use]
+    pub fn uses(&self, field: &str) -> bool {
+        self.0.fields().contains(&field)
+    }
+}
+
+impl FromStr for FilterExpr {
+    type Err = ExprError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let expr = Expr::parse(s)?;
+        let known: Vec<&str> = FileStats::FIELDS
+            .iter()
+            .chain(FileStats::TEXT_FIELDS)
+            .copied()
+            .collect();
+        validate_fields(&expr, s, &known)?;
+        expr.validate_types(s, FileStats::TEXT_FIELDS)?;
+        Ok(Self(expr))
+    }
+}
+
+/// A `--group-filter` expression over the totals of a `--by` group.
+///
+/// Field names are checked against [`GroupFilter::FIELDS`] and
+/// [`GroupFilter::TEXT_FIELDS`] when parsing.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GroupFilter(pub Expr);
+
+impl GroupFilter {
+    /// Numeric fields: the group's totals.
+    pub const FIELDS: &'static [&'static str] =
+        &["files", "lines", "chars", "words", "sloc", "size"];
+
+    /// String fields: the group name (extension, language, directory, ...).
+    pub const TEXT_FIELDS: &'static [&'static str] = &["key"];
+
+    /// Returns true if `group` satisfies the expression.
+    #[must_use]
+    pub fn matches(&self, group: &GroupStats) -> bool {
+        self.0.eval_values(&|name| group.value(name))
+    }
+
+    /// Returns true if the expression references `field`.
+    #[must_use]
+    pub fn uses(&self, field: &str) -> bool {
+        self.0.fields().contains(&field)
+    }
+}
+
+impl FromStr for GroupFilter {
+    type Err = ExprError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let expr = Expr::parse(s)?;
+        let known: Vec<&str> = Self::FIELDS
+            .iter()
+            .chain(Self::TEXT_FIELDS)
+            .copied()
+            .collect();
+        validate_fields(&expr, s, &known)?;
+        expr.validate_types(s, Self::TEXT_FIELDS)?;
+        Ok(Self(expr))
+    }
+}
+
+/// A `--fail-if` threshold over the run summary or, when comparing, the
+/// comparison summary. The run fails when the expression is true.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Threshold {
+    pub expr: Expr,
+    /// The expression as written, for error messages.
+    pub source: String,
+}
+
+impl Threshold {
+    /// Totals of the run (of the new side when comparing).
+    pub const SUMMARY_FIELDS: &'static [&'static str] = &[
+        "total_files",
+        "total_lines",
+        "total_chars",
+        "total_words",
+        "total_sloc",
+        "total_size",
+    ];
+
+    /// Changes between the two sides of a comparison.
+    pub const DELTA_FIELDS: &'static [&'static str] = &[
+        "files_added",
+        "files_removed",
+        "files_modified",
+        "lines_delta",
+        "chars_delta",
+        "words_delta",
+        "sloc_delta",
+    ];
+
+    /// Returns true if the threshold is exceeded.
+    #[must_use]
+    pub fn violated(&self, lookup: &dyn Fn(&str) -> Option<f64>) -> bool {
+        self.expr.eval(lookup)
+    }
+
+    /// Returns true if the expression references a field whose name
+    /// contains `word` (e.g. `sloc` for `total_sloc` and `sloc_delta`).
+    #[must_use]
+    pub fn uses(&self, word: &str) -> bool {
+        self.expr
+            .fields()
+            .iter()
+            .any(|f| f.split('_').any(|p| p == word))
+    }
+
+    /// Returns the first referenced field that `lookup` cannot resolve.
+    #[must_use]
+    pub fn unresolved<'a>(&'a self, lookup: &dyn Fn(&str) -> Option<f64>) -> Option<&'a str> {
+        self.expr.fields().into_iter().find(|f| lookup(f).is_none())
+    }
+}
+
+impl FromStr for Threshold {
+    type Err = ExprError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let expr = Expr::parse(s)?;
+        let known: Vec<&str> = Self::SUMMARY_FIELDS
+            .iter()
+            .chain(Self::DELTA_FIELDS)
+            .copied()
+            .collect();
+        validate_fields(&expr, s, &known)?;
+        expr.validate_types(s, &[])?;
+        Ok(Self {
+            expr,
+            source: s.trim().to_string(),
+        })
+    }
+}
+
+impl fmt::Display for Threshold {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.source)
+    }
+}
+
+/// Checks that every field referenced by `expr` is one of `known`.
+///
+/// # Errors
+/// Returns an error naming the first unknown field and the valid names.
+pub fn validate_fields(expr: &Expr, input: &str, known: &[&str]) -> Result<(), ExprError> {
+    match expr.fields().into_iter().find(|f| !known.contains(f)) {
+        Some(unknown) => Err(ExprError {
+            message: format!(
+                "unknown field '{unknown}' (expected one of: {})",
+                known.join(", ")
+            ),
+            position: input.find(unknown).unwrap_or(0),
+            input: input.to_string(),
+        }),
+        None => Ok(()),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Ident(String),
+    Number(f64),
+    Str(String),
+    Op(CmpOp),
+    And,
+    Or,
+    Not,
+    LParen,
+    RParen,
+}
+
+impl Token {
+    fn text(&self) -> String {
+        match self {
+            Self::Ident(name) => name.clone(),
+            Self::Number(value) => value.to_string(),
+            Self::Str(text) => format!("\"{text}\""),
+            Self::Op(op) => op.as_str().to_string(),
+            Self::And => "&&".to_string(),
+            Self::Or => "||".to_string(),
+            Self::Not => "!".to_string(),
+            Self::LParen => "(".to_string(),
+            Self::RParen => ")".to_string(),
+        }
+    }
+}
+
+fn tokenize(input: &str) -> Result<Vec<(Token, usize)>, ExprError> {
+    let error = |message: String, position: usize| ExprError {
+        message,
+        position,
+        input: input.to_string(),
+    };
+
+    let bytes = input.as_bytes();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        let c = bytes[i];
+        let start = i;
+        let two = input.get(i..i + 2).unwrap_or("");
+        let token = match c {
+            b' ' | b'\t' | b'\n' | b'\r' => {
+                i += 1;
+                continue;
+            }
+            b'(' => {
+                i += 1;
+                Token::LParen
+            }
+            b')' => {
+                i += 1;
+                Token::RParen
+            }
+            _ if two == "&&" => {
+                i += 2;
+                Token::And
+            }
+            _ if two == "||" => {
+                i += 2;
+                Token::Or
+            }
+            _ if matches!(two, "<=" | ">=" | "==" | "!=") => {
+                i += 2;
+                Token::Op(match two {
+                    "<=" => CmpOp::Le,
+                    ">=" => CmpOp::Ge,
+                    "==" => CmpOp::Eq,
+                    _ => CmpOp::Ne,
+                })
+            }
+            b'<' | b'>' | b'=' => {
+                i += 1;
+                Token::Op(match c {
+                    b'<' => CmpOp::Lt,
+                    b'>' => CmpOp::Gt,
+                    _ => CmpOp::Eq,
+                })
+            }
+            b'!' => {
+                i += 1;
+                Token::Not
+            }
+            b'"' | b'\'' => {
+                let mut text = String::new();
+                let mut chars = input[i + 1..].char_indices();
+                loop {
+                    match chars.next() {
+                        Some((end, ch)) if ch == char::from(c) => {
+                            i += 1 + end + 1;
+                            break;
+                        }
+                        Some((_, '\\')) => match chars.next() {
+                            Some((_, ch)) if ch == char::from(c) || ch == '\\' => text.push(ch),
+                            Some((_, ch)) => {
+                                text.push('\\');
+                                text.push(ch);
+                            }
+                            None => break,
+                        },
+                        Some((_, ch)) => text.push(ch),
+                        None => return Err(error("unterminated string".to_string(), start)),
+                    }
+                }
+                if i == start {
+                    return Err(error("unterminated string".to_string(), start));
+                }
+                Token::Str(text)
+            }
+            b'0'..=b'9' | b'.' => {
+                while i < bytes.len()
+                    && (bytes[i].is_ascii_digit() || matches!(bytes[i], b'.' | b'_'))
+                {
+                    i += 1;
+                }
+                let text = input[start..i].replace('_', "");
+                let value = text
+                    .parse()
+                    .map_err(|_| error(format!("invalid number '{}'", &input[start..i]), start))?;
+                Token::Number(value)
+            }
+            _ if c.is_ascii_alphabetic() || c == b'_' => {
+                while i < bytes.len() && (bytes[i].is_ascii_alphanumeric() || bytes[i] == b'_') {
+                    i += 1;
+                }
+                match &input[start..i] {
+                    "and" | "AND" => Token::And,
+                    "or" | "OR" => Token::Or,
+                    "not" | "NOT" => Token::Not,
+                    "contains" | "CONTAINS" => Token::Op(CmpOp::Contains),
+                    "matches" | "MATCHES" => Token::Op(CmpOp::Matches),
+                    name => Token::Ident(name.to_ascii_lowercase()),
+                }
+            }
+            _ => {
+                let ch = input[i..].chars().next().unwrap_or_default();
+                return Err(error(format!("unexpected character '{ch}'"), i));
+            }
+        };
+        tokens.push((token, start));
+    }
+    Ok(tokens)
+}
+
+struct Parser<'a> {
+    input: &'a str,
+    tokens: Vec<(Token, usize)>,
+    pos: usize,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<(&Token, usize)> {
+        self.tokens.get(self.pos).map(|(t, p)| (t, *p))
+    }
+
+    fn next(&mut self) -> Option<(Token, usize)> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn error_at(&self, message: String, position: usize) -> ExprError {
+        ExprError {
+            message,
+            position,
+            input: self.input.to_string(),
+        }
+    }
+
+    fn error_at_end(&self, message: &str) -> ExprError {
+        self.error_at(message.to_string(), self.input.len())
+    }
+
+    fn parse_or(&mut self) -> Result<Expr, ExprError> {
+        let mut lhs = self.parse_and()?;
+        while matches!(self.peek(), Some((Token::Or, _))) {
+            self.pos += 1;
+            lhs = Expr::Or(Box::new(lhs), Box::new(self.parse_and()?));
+        }
+        Ok(lhs)
+    }
+
+    fn parse_and(&mut self) -> Result<Expr, ExprError> {
+        let mut lhs = self.parse_unary()?;
+        while matches!(self.peek(), Some((Token::And, _))) {
+            self.pos += 1;
+            lhs = Expr::And(Box::new(lhs), Box::new(self.parse_unary()?));
+        }
+        Ok(lhs)
+    }
+
+    fn parse_unary(&mut self) -> Result<Expr, ExprError> {
+        match self.peek() {
+            Some((Token::Not, _)) => {
+                self.pos += 1;
+                Ok(Expr::Not(Box::new(self.parse_unary()?)))
+            }
+            Some((Token::LParen, _)) => {
+                self.pos += 1;
+                let expr = self.parse_or()?;
+                match self.next() {
+                    Some((Token::RParen, _)) => Ok(expr),
+                    Some((token, position)) => Err(self.error_at(
+                        format!("expected ')' but found '{}'", token.text()),
+                        position,
+                    )),
+                    None => Err(self.error_at_end("missing ')'")),
+                }
+            }
+            _ => self.parse_comparison(),
+        }
+    }
+
+    fn parse_comparison(&mut self) -> Result<Expr, ExprError> {
+        let lhs = self.parse_operand()?;
+        let op = match self.next() {
+            Some((Token::Op(op), _)) => op,
+            Some((token, position)) => {
+                return Err(self.error_at(
+                    format!(
+                        "expected a comparison operator but found '{}'",
+                        token.text()
+                    ),
+                    position,
+                ));
+            }
+            None => return Err(self.error_at_end("expected a comparison operator")),
+        };
+        let position = self.peek().map_or(self.input.len(), |(_, p)| p);
+        let rhs = match (op, self.parse_operand()?) {
+            (CmpOp::Matches, Operand::Str(source)) => Operand::Regex(Pattern(
+                Regex::new(&source)
+                    .map_err(|e| self.error_at(format!("invalid regex: {e}"), position))?,
+            )),
+            (CmpOp::Matches, _) => {
+                return Err(self.error_at("'matches' needs a string pattern".to_string(), position));
+            }
+            (_, rhs) => rhs,
+        };
+        Ok(Expr::Compare(lhs, op, rhs))
+    }
+
+    fn parse_operand(&mut self) -> Result<Operand, ExprError> {
+        match self.next() {
+            Some((Token::Ident(name), _)) => Ok(Operand::Field(name)),
+            Some((Token::Number(value), _)) => Ok(Operand::Number(value)),
+            Some((Token::Str(text), _)) => Ok(Operand::Str(text)),
+            Some((token, position)) => Err(self.error_at(
+                format!(
+                    "expected a field, number or string but found '{}'",
+                    token.text()
+                ),
+                position,
+            )),
+            None => Err(self.error_at_end("expected a field, number or string")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn eval(input: &str, lines: f64, words: Option<f64>) -> bool {
+        Expr::parse(input).unwrap().eval(&|name| match name {
+            "lines" => Some(lines),
+            "words" => words,
+            _ => None,
+        })
+    }
+
+    #[test]
+    fn test_comparisons_and_precedence() {
+        assert!(eval("lines > 10", 11.0, None));
+        assert!(!eval("lines > 10", 10.0, None));
+        assert!(eval("lines >= 1_000", 1000.0, None));
+        assert!(eval("10 < lines", 11.0, None));
+        assert!(eval("lines == 3 || lines > 100 && words < 5", 3.0, None));
+        assert!(!eval("(lines == 3 || lines > 100) && words < 5", 3.0, None));
+        assert!(eval("not lines < 5 and words != 0", 5.0, Some(1.0)));
+        assert!(eval("!(lines < 5)", 5.0, None));
+    }
+
+    #[test]
+    fn test_unresolved_field_is_false() {
+        assert!(!eval("words > 0", 1.0, None));
+        assert!(eval("!(words > 0)", 1.0, None));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        for input in [
+            "lines >",
+            "lines 10",
+            "(lines > 1",
+            "lines > 1 )",
+            "lines ~ 1",
+            "",
+        ] {
+            assert!(Expr::parse(input).is_err(), "{input}");
+        }
+        let err = Expr::parse("lines > 1 )").unwrap_err();
+        assert_eq!(err.position, 10);
+    }
+
+    #[test]
+    fn test_filter_expr_fields() {
+        let err = "maxlen > 1".parse::<FilterExpr>().unwrap_err();
+        assert!(err.message.contains("unknown field 'maxlen'"));
+
+        let filter: FilterExpr = "maxline > 80 && avgline < 40".parse().unwrap();
+        assert!(filter.uses("maxline"));
+        let stats = FileStats {
+            max_line_length: 120,
+            avg_line_length: 30.5,
+            ..FileStats::new(PathBuf::from("a.rs"))
+        };
+        assert!(filter.matches(&stats));
+    }
+
+    #[test]
+    fn test_string_predicates() {
+        let stats = FileStats::new(PathBuf::from("crates/cli/tests/lib_args.rs"));
+        let matches = |input: &str| input.parse::<FilterExpr>().unwrap().matches(&stats);
+        assert!(matches("ext == \"rs\""));
+        assert!(matches("ext != 'md' && name matches \"^lib_\""));
+        assert!(matches("path contains \"tests/\""));
+        assert!(matches("path MATCHES '\\.rs$'"));
+        assert!(!matches("name contains \"main\""));
+        assert!(!matches("not path contains 'cli/'"));
+
+        let filter: FilterExpr = "name == 'say \\'hi\\''".parse().unwrap();
+        assert_eq!(filter.0.to_string(), "name == \"say 'hi'\"");
+        assert_eq!(filter.0.to_string().parse::<FilterExpr>().unwrap(), filter);
+    }
+
+    #[test]
+    fn test_string_errors() {
+        for (input, message) in [
+            ("name > 'a'", "strings can only be compared"),
+            ("ext == 1", "cannot compare a string and a number"),
+            ("lines contains 'a'", "cannot compare a string and a number"),
+            ("lines > 'a'", "cannot compare a string and a number"),
+            ("name matches ext", "'matches' needs a string pattern"),
+            ("name matches '('", "invalid regex"),
+            ("name == 'open", "unterminated string"),
+        ] {
+            let err = input.parse::<FilterExpr>().unwrap_err();
+            assert!(err.message.contains(message), "{input}: {}", err.message);
+        }
+        assert!("total_lines == 'a'".parse::<Threshold>().is_err());
+    }
+
+    #[test]
+    fn test_threshold_fields() {
+        assert!("lines > 1".parse::<Threshold>().is_err());
+
+        let threshold: Threshold = " total_lines > 500_000 || sloc_delta > 1000 "
+            .parse()
+            .unwrap();
+        assert_eq!(
+            threshold.to_string(),
+            "total_lines > 500_000 || sloc_delta > 1000"
+        );
+        assert!(threshold.uses("sloc"));
+        assert!(!threshold.uses("words"));
+
+        let summary = |name: &str| (name == "total_lines").then_some(600_000.0);
+        assert_eq!(threshold.unresolved(&summary), Some("sloc_delta"));
+        assert!(threshold.violated(&summary));
+    }
+}
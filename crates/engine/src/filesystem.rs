@@ -1,9 +1,21 @@
+use crate::archive::ArchiveKind;
+use crate::attributes::LinguistAttributes;
+use crate::cancel::CancellationToken;
 use crate::config::{FilterConfig, WalkOptions};
 use crate::error::{EngineError, Result};
+use crate::options::LinguistMode;
 use crate::path_security::{PathSanitizeOptions, is_path_safe, sanitize_path};
+use crate::stats::{LinkReport, SymlinkCycle};
+use crate::submodules::Submodules;
 use hashbrown::HashSet;
 use ignore::WalkBuilder;
-use std::path::Path;
+use ignore::overrides::{Override, OverrideBuilder};
+use std::path::{Path, PathBuf};
+
+/// Tool-specific ignore files, in gitignore syntax. They're read in every
+/// directory like `.gitignore` but also apply with `--no-gitignore` and
+/// outside git repositories, and take precedence over the other ignore files.
+pub const IGNORE_FILENAMES: [&str; 2] = [".count_linesignore", ".clignore"];
 
 /// Parallel recursive directory walk.
 ///
@@ -12,12 +24,21 @@ use std::path::Path;
 /// # Errors
 /// Returns `Ok` if traversal completes. Errors during traversal are handled internally or ignored.
 /// Returns an error if any root path fails security validation.
-pub fn walk_parallel<F>(options: &WalkOptions, filters: &FilterConfig, processor: F) -> Result<()>
+///
+/// The walk stops early once `cancel` is cancelled. With
+/// [`WalkOptions::report_links`], symlinks met on the way are returned as a
+/// [`LinkReport`].
+pub fn walk_parallel<F>(
+    options: &WalkOptions,
+    filters: &FilterConfig,
+    cancel: &CancellationToken,
+    processor: F,
+) -> Result<LinkReport>
 where
     F: Fn(std::path::PathBuf, std::fs::Metadata) + Send + Sync + 'static,
 {
     if options.roots.is_empty() {
-        return Ok(());
+        return Ok(LinkReport::default());
     }
 
     // Validate root paths for security
@@ -47,55 +68,47 @@ where
         builder.add(root);
     }
 
+    // Junctions only exist on Windows; elsewhere the flag changes nothing.
+    let follow_junctions = options.follow_junctions && cfg!(windows);
     builder
         .threads(options.threads)
         .hidden(!options.hidden)
         .git_ignore(options.git_ignore)
-        .follow_links(options.follow_links);
+        .follow_links(options.follow_links || follow_junctions);
+    let submodules = (!options.include_submodules)
+        .then(|| Submodules::find(&options.roots))
+        .filter(|s| !s.is_empty());
+    if cfg!(windows) || submodules.is_some() {
+        let follow_links = options.follow_links;
+        builder.filter_entry(move |entry| {
+            if entry.depth() == 0 {
+                return true;
+            }
+            if let Some(submodules) = &submodules
+                && entry.file_type().is_some_and(|ft| ft.is_dir())
+                && submodules.is_submodule_dir(entry.path())
+            {
+                return false;
+            }
+            if !cfg!(windows) || !entry.path_is_symlink() {
+                return true;
+            }
+            if is_junction(entry.path()) {
+                follow_junctions
+            } else {
+                follow_links
+            }
+        });
+    }
+    for name in IGNORE_FILENAMES {
+        builder.add_custom_ignore_filename(name);
+    }
 
     if let Some(depth) = options.max_depth {
         builder.max_depth(Some(depth));
     }
 
-    // Build overrides (include + exclude) in a single OverrideBuilder
-    // ignore crate only supports one Overrides instance per WalkBuilder.
-    // Exclude patterns use the `!` prefix convention.
-    if !options.override_include.is_empty()
-        || !options.override_exclude.is_empty()
-        || !filters.include_patterns.is_empty()
-        || !filters.exclude_patterns.is_empty()
-    {
-        let mut ov_builder = ignore::overrides::OverrideBuilder::new(&options.roots[0]);
-
-        for ov in &options.override_include {
-            ov_builder.add(ov).map_err(|err| {
-                EngineError::Config(format!("Invalid override include pattern '{ov}': {err}"))
-            })?;
-        }
-
-        for ov in &options.override_exclude {
-            let pattern = format!("!{ov}");
-            ov_builder.add(&pattern).map_err(|err| {
-                EngineError::Config(format!("Invalid override exclude pattern '{ov}': {err}"))
-            })?;
-        }
-
-        for pattern in &filters.include_patterns {
-            ov_builder.add(pattern).map_err(|err| {
-                EngineError::Config(format!("Invalid filter include pattern '{pattern}': {err}"))
-            })?;
-        }
-
-        for pattern in &filters.exclude_patterns {
-            let exclusion = format!("!{pattern}");
-            ov_builder.add(&exclusion).map_err(|err| {
-                EngineError::Config(format!("Invalid filter exclude pattern '{pattern}': {err}"))
-            })?;
-        }
-
-        let overrides = ov_builder
-            .build()
-            .map_err(|err| EngineError::Config(format!("Failed to build overrides: {err}")))?;
+    if let Some(overrides) = build_overrides(&options.roots[0], options, filters)? {
         builder.overrides(overrides);
     }
 
@@ -106,21 +119,54 @@ where
     let allow_ext = collect_normalized_exts(&filters.allow_ext);
     let deny_ext = collect_normalized_exts(&filters.deny_ext);
 
+    let scan_archives = options.scan_archives;
+    let linguist = (options.linguist == Some(LinguistMode::Exclude))
+        .then(|| std::sync::Arc::new(LinguistAttributes::new()));
     let processor = std::sync::Arc::new(processor);
+    let links = std::sync::Arc::new(std::sync::Mutex::new(LinkReport::default()));
+    let follow_links = options.follow_links;
+    let report_links = options.report_links;
+    let include_special = options.include_special;
     let walker = builder.build_parallel();
     walker.run(|| {
         let processor = processor.clone();
         let allow_ext = allow_ext.clone();
         let deny_ext = deny_ext.clone();
         let filters = filters.clone();
+        let cancel = cancel.clone();
+        let linguist = linguist.clone();
+        let links = links.clone();
 
         Box::new(move |entry| {
+            if cancel.is_cancelled() {
+                return ignore::WalkState::Quit;
+            }
+            // Only links and errors are reported; the lock stays off the
+            // path of plain entries.
+            let linked = entry
+                .as_ref()
+                .map_or(true, ignore::DirEntry::path_is_symlink);
+            if report_links && linked {
+                record_link(&entry, follow_links, &links);
+            } else if let Err(err) = &entry {
+                log::debug!("walk: {err}");
+            }
             if let Ok(entry) = entry
-                && entry.file_type().is_some_and(|ft| ft.is_file())
+                && entry
+                    .file_type()
+                    .is_some_and(|ft| ft.is_file() || (include_special && is_special(ft)))
                 && let Ok(meta) = entry.metadata()
             {
                 let path = entry.path();
-                if matches_filter(path, &meta, &filters, &allow_ext, &deny_ext) {
+                if linguist
+                    .as_ref()
+                    .is_some_and(|l| l.classify(path).is_some())
+                {
+                    return ignore::WalkState::Continue;
+                }
+                // Archives are filtered member by member when they're scanned.
+                let is_archive = scan_archives && ArchiveKind::from_path(path).is_some();
+                if is_archive || matches_filter(path, &meta, &filters, &allow_ext, &deny_ext) {
                     processor(path.to_owned(), meta);
                 }
             }
@@ -128,26 +174,211 @@ where
         })
     });
 
-    Ok(())
+    let mut links = std::mem::take(
+        &mut *links
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner),
+    );
+    links.cycles.sort_by(|a, b| a.link.cmp(&b.link));
+    links.broken.sort();
+    Ok(links)
+}
+
+/// The kind of a FIFO, socket or device node, whose reads may block
+/// forever; `None` for regular files, directories and symlinks.
+#[must_use]
+pub fn special_file_kind(file_type: std::fs::FileType) -> Option<&'static str> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::FileTypeExt;
+        if file_type.is_fifo() {
+            return Some("FIFO");
+        }
+        if file_type.is_socket() {
+            return Some("socket");
+        }
+        if file_type.is_char_device() {
+            return Some("character device");
+        }
+        if file_type.is_block_device() {
+            return Some("block device");
+        }
+    }
+    #[cfg(not(unix))]
+    let _ = file_type;
+    None
+}
+
+/// Returns true if `path` is a directory reparse point: a junction, mount
+/// point or directory symlink, which the file attributes don't tell apart.
+///
+/// Without `--follow-junctions` the walk doesn't enter them, even with
+/// `--follow`, so a junction to another part of the tree isn't counted
+/// twice. Always false outside Windows.
+#[must_use]
+pub fn is_junction(path: &Path) -> bool {
+    #[cfg(windows)]
+    {
+        use std::os::windows::fs::MetadataExt;
+        const FILE_ATTRIBUTE_DIRECTORY: u32 = 0x10;
+        const FILE_ATTRIBUTE_REPARSE_POINT: u32 = 0x400;
+        const JUNCTION: u32 = FILE_ATTRIBUTE_DIRECTORY | FILE_ATTRIBUTE_REPARSE_POINT;
+        path.symlink_metadata()
+            .is_ok_and(|meta| meta.file_attributes() & JUNCTION == JUNCTION)
+    }
+    #[cfg(not(windows))]
+    {
+        let _ = path;
+        false
+    }
+}
+
+fn is_special(file_type: std::fs::FileType) -> bool {
+    special_file_kind(file_type).is_some()
+}
+
+/// The roots that name a special file, which the walk leaves out unless
+/// `--include-special`; a special file met inside a directory is skipped
+/// without a word, as before.
+#[must_use]
+pub fn skipped_special_roots(options: &WalkOptions) -> Vec<(PathBuf, EngineError)> {
+    if options.include_special {
+        return Vec::new();
+    }
+    options
+        .roots
+        .iter()
+        .filter_map(|root| {
+            let kind = special_file_kind(std::fs::metadata(root).ok()?.file_type())?;
+            let error = EngineError::SpecialFile {
+                path: root.clone(),
+                kind,
+            };
+            Some((root.clone(), error))
+        })
+        .collect()
+}
+
+/// Records a followed, looping or broken symlink in `links`.
+fn record_link(
+    entry: &std::result::Result<ignore::DirEntry, ignore::Error>,
+    follow_links: bool,
+    links: &std::sync::Mutex<LinkReport>,
+) {
+    let mut links = links
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner);
+    match entry {
+        Ok(entry) if entry.path_is_symlink() => {
+            // Without `--follow` the entry is the link itself.
+            if entry.path().metadata().is_err() {
+                links.broken.push(entry.path().to_path_buf());
+            } else if follow_links {
+                links.followed += 1;
+            }
+        }
+        Ok(_) => {}
+        Err(err) => {
+            log::warn!("walk: {err}");
+            record_link_error(err, None, &mut links);
+        }
+    }
+}
+
+fn record_link_error(err: &ignore::Error, path: Option<&Path>, links: &mut LinkReport) {
+    match err {
+        ignore::Error::WithPath { path, err } => record_link_error(err, Some(path), links),
+        ignore::Error::WithDepth { err, .. } | ignore::Error::WithLineNumber { err, .. } => {
+            record_link_error(err, path, links);
+        }
+        ignore::Error::Loop { ancestor, child } => links.cycles.push(SymlinkCycle {
+            link: child.clone(),
+            target: ancestor.clone(),
+        }),
+        ignore::Error::Io(io) if io.kind() == std::io::ErrorKind::NotFound => {
+            if let Some(path) = path.filter(|p| p.is_symlink()) {
+                links.broken.push(path.to_path_buf());
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Builds the include/exclude overrides for the walk, if any pattern is set.
+///
+/// # Errors
+/// Returns an error if a pattern is not a valid glob.
+pub(crate) fn build_overrides(
+    root: &Path,
+    options: &WalkOptions,
+    filters: &FilterConfig,
+) -> Result<Option<Override>> {
+    if options.override_include.is_empty()
+        && options.override_exclude.is_empty()
+        && filters.include_patterns.is_empty()
+        && filters.exclude_patterns.is_empty()
+    {
+        return Ok(None);
+    }
+
+    // Build overrides (include + exclude) in a single OverrideBuilder
+    // ignore crate only supports one Overrides instance per WalkBuilder.
+    // Exclude patterns use the `!` prefix convention.
+    let mut ov_builder = OverrideBuilder::new(root);
+    ov_builder
+        .case_insensitive(filters.glob_case_insensitive)
+        .map_err(|err| EngineError::Config(format!("Failed to build overrides: {err}")))?;
+
+    for ov in &options.override_include {
+        ov_builder.add(ov).map_err(|err| {
+            EngineError::Config(format!("Invalid override include pattern '{ov}': {err}"))
+        })?;
+    }
+
+    for ov in &options.override_exclude {
+        let pattern = format!("!{ov}");
+        ov_builder.add(&pattern).map_err(|err| {
+            EngineError::Config(format!("Invalid override exclude pattern '{ov}': {err}"))
+        })?;
+    }
+
+    for pattern in &filters.include_patterns {
+        ov_builder.add(pattern).map_err(|err| {
+            EngineError::Config(format!("Invalid filter include pattern '{pattern}': {err}"))
+        })?;
+    }
+
+    for pattern in &filters.exclude_patterns {
+        let exclusion = format!("!{pattern}");
+        ov_builder.add(&exclusion).map_err(|err| {
+            EngineError::Config(format!("Invalid filter exclude pattern '{pattern}': {err}"))
+        })?;
+    }
+
+    let overrides = ov_builder
+        .build()
+        .map_err(|err| EngineError::Config(format!("Failed to build overrides: {err}")))?;
+    Ok(Some(overrides))
 }
 
-fn collect_normalized_exts(exts: &[String]) -> HashSet<String> {
+pub(crate) fn collect_normalized_exts(exts: &[String]) -> HashSet<String> {
     exts.iter()
         .map(|ext| ext.trim().trim_start_matches('.').to_ascii_lowercase())
         .filter(|ext| !ext.is_empty())
         .collect()
 }
 
-fn extension_of(path: &Path) -> Option<String> {
+pub(crate) fn extension_of(path: &Path) -> Option<String> {
     path.extension()
         .and_then(|ext| ext.to_str())
         .map(|ext| ext.trim().trim_start_matches('.').to_ascii_lowercase())
         .filter(|ext| !ext.is_empty())
 }
 
-fn matches_filter(
+/// Extension and size checks shared by the filesystem walk and git revisions.
+pub(crate) fn matches_ext_and_size(
     path: &Path,
-    metadata: &std::fs::Metadata,
+    size: u64,
     filters: &FilterConfig,
     allow_ext: &HashSet<String>,
     deny_ext: &HashSet<String>,
@@ -162,13 +393,25 @@ fn matches_filter(
         return false;
     }
 
-    let size = metadata.len();
     if filters.min_size.is_some_and(|min| size < min) {
         return false;
     }
     if filters.max_size.is_some_and(|max| size > max) {
         return false;
     }
+    true
+}
+
+fn matches_filter(
+    path: &Path,
+    metadata: &std::fs::Metadata,
+    filters: &FilterConfig,
+    allow_ext: &HashSet<String>,
+    deny_ext: &HashSet<String>,
+) -> bool {
+    if !matches_ext_and_size(path, metadata.len(), filters, allow_ext, deny_ext) {
+        return false;
+    }
 
     if filters.mtime_since.is_some() || filters.mtime_until.is_some() {
         let Ok(modified) = metadata.modified() else {
@@ -186,3 +429,205 @@ fn matches_filter(
 
     true
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_overrides_case_insensitive() {
+        let mut filters = FilterConfig {
+            exclude_patterns: vec!["*.RS".to_string()],
+            ..FilterConfig::default()
+        };
+        let root = Path::new("/repo");
+        let matched = |filters: &FilterConfig| {
+            build_overrides(root, &WalkOptions::default(), filters)
+                .unwrap()
+                .unwrap()
+                .matched(root.join("src/lib.rs"), false)
+                .is_ignore()
+        };
+        assert!(!matched(&filters));
+        filters.glob_case_insensitive = true;
+        assert!(matched(&filters));
+    }
+
+    fn walk(options: &WalkOptions) -> Vec<std::path::PathBuf> {
+        let files = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let sink = files.clone();
+        walk_parallel(
+            options,
+            &FilterConfig::default(),
+            &CancellationToken::default(),
+            move |path, _| sink.lock().unwrap().push(path),
+        )
+        .unwrap();
+        let mut files = std::mem::take(&mut *files.lock().unwrap());
+        files.sort();
+        files
+    }
+
+    #[test]
+    fn test_count_linesignore_applies_without_gitignore() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::create_dir_all(root.join("sub/gen")).unwrap();
+        std::fs::write(root.join(".count_linesignore"), "*.lock\n").unwrap();
+        std::fs::write(root.join("sub/.clignore"), "gen/\n").unwrap();
+        for file in ["a.rs", "Cargo.lock", "sub/b.rs", "sub/gen/c.rs"] {
+            std::fs::write(root.join(file), "x\n").unwrap();
+        }
+
+        let options = WalkOptions {
+            roots: vec![root.to_path_buf()],
+            git_ignore: false,
+            ..WalkOptions::default()
+        };
+        assert_eq!(
+            walk(&options),
+            vec![root.join("a.rs"), root.join("sub/b.rs")]
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_walk_reports_cycles_and_broken_links() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::create_dir(root.join("sub")).unwrap();
+        std::fs::write(root.join("sub/a.txt"), "a\n").unwrap();
+        std::os::unix::fs::symlink(root, root.join("sub/loop")).unwrap();
+        std::os::unix::fs::symlink(root.join("sub/a.txt"), root.join("alias.txt")).unwrap();
+        std::os::unix::fs::symlink(root.join("missing"), root.join("dangling")).unwrap();
+
+        let walk = |report_links| {
+            let options = WalkOptions {
+                roots: vec![root.to_path_buf()],
+                follow_links: true,
+                report_links,
+                ..WalkOptions::default()
+            };
+            walk_parallel(
+                &options,
+                &FilterConfig::default(),
+                &CancellationToken::default(),
+                |_, _| {},
+            )
+            .unwrap()
+        };
+
+        let links = walk(true);
+        assert_eq!(links.followed, 1);
+        assert_eq!(links.cycles.len(), 1);
+        assert_eq!(links.cycles[0].link, root.join("sub/loop"));
+        assert_eq!(links.broken, vec![root.join("dangling")]);
+
+        // Nothing is collected unless asked for.
+        assert_eq!(walk(false), LinkReport::default());
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn test_junctions_are_skipped_unless_followed() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::create_dir(root.join("src")).unwrap();
+        std::fs::write(root.join("src/a.rs"), "x\n").unwrap();
+        let status = std::process::Command::new("cmd")
+            .args(["/C", "mklink", "/J"])
+            .arg(root.join("alias"))
+            .arg(root.join("src"))
+            .status()
+            .unwrap();
+        assert!(status.success());
+        assert!(is_junction(&root.join("alias")));
+        assert!(!is_junction(&root.join("src")));
+
+        let mut options = WalkOptions {
+            roots: vec![root.to_path_buf()],
+            follow_links: true,
+            ..WalkOptions::default()
+        };
+        assert_eq!(walk(&options), vec![root.join("src/a.rs")]);
+        options.follow_junctions = true;
+        assert_eq!(
+            walk(&options),
+            vec![root.join("alias/a.rs"), root.join("src/a.rs")]
+        );
+    }
+
+    #[test]
+    fn test_submodules_are_skipped_unless_included() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::create_dir_all(root.join(".git")).unwrap();
+        std::fs::create_dir_all(root.join("vendor/json")).unwrap();
+        std::fs::write(
+            root.join(".gitmodules"),
+            "[submodule \"json\"]\n\tpath = vendor/json\n",
+        )
+        .unwrap();
+        std::fs::write(root.join("a.rs"), "x\n").unwrap();
+        std::fs::write(root.join("vendor/json/json.hpp"), "x\n").unwrap();
+
+        let mut options = WalkOptions {
+            roots: vec![root.to_path_buf()],
+            ..WalkOptions::default()
+        };
+        assert_eq!(walk(&options), vec![root.join("a.rs")]);
+        options.include_submodules = true;
+        assert_eq!(
+            walk(&options),
+            vec![root.join("a.rs"), root.join("vendor/json/json.hpp")]
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_follow_junctions_is_a_no_op_outside_windows() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::create_dir(root.join("src")).unwrap();
+        std::fs::write(root.join("src/a.rs"), "x\n").unwrap();
+        std::os::unix::fs::symlink(root.join("src"), root.join("alias")).unwrap();
+        assert!(!is_junction(&root.join("alias")));
+
+        let options = WalkOptions {
+            roots: vec![root.to_path_buf()],
+            follow_junctions: true,
+            ..WalkOptions::default()
+        };
+        assert_eq!(walk(&options), vec![root.join("src/a.rs")]);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_special_files_are_skipped() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::write(root.join("a.rs"), "x\n").unwrap();
+        let fifo = root.join("pipe.rs");
+        let status = std::process::Command::new("mkfifo")
+            .arg(&fifo)
+            .status()
+            .unwrap();
+        assert!(status.success());
+
+        let mut options = WalkOptions {
+            roots: vec![root.to_path_buf(), fifo.clone()],
+            ..WalkOptions::default()
+        };
+        assert_eq!(walk(&options), vec![root.join("a.rs")]);
+        let skipped = skipped_special_roots(&options);
+        assert_eq!(skipped.len(), 1);
+        assert_eq!(skipped[0].0, fifo);
+        assert_eq!(skipped[0].1.kind(), "special_file");
+        assert!(skipped[0].1.to_string().contains("FIFO"));
+
+        options.include_special = true;
+        assert!(skipped_special_roots(&options).is_empty());
+        options.roots.pop();
+        assert_eq!(walk(&options), vec![root.join("a.rs"), fifo]);
+    }
+}
@@ -0,0 +1,1122 @@
+//! Counting files at a git revision.
+//!
+//! File contents are read straight from the object database
+//! (`git ls-tree` + `git cat-file --batch`), so no checkout or temporary
+//! worktree is needed and the working tree is left untouched.
+
+use crate::config::Config;
+use crate::error::{EngineError, Result};
+use crate::filesystem::{build_overrides, collect_normalized_exts, matches_ext_and_size};
+use crate::processor::count_content;
+use crate::stats::{FileStats, RunResult, Summary};
+use chrono::{DateTime, Local};
+use hashbrown::{HashMap, HashSet};
+use ignore::overrides::Override;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::path::{Component, Path, PathBuf};
+use std::process::{Command, Stdio};
+
+/// A file tracked at some revision.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TreeEntry {
+    /// Path relative to the repository root.
+    pub path: PathBuf,
+    /// Blob object id.
+    pub oid: String,
+    /// Blob size in bytes.
+    pub size: u64,
+}
+
+/// A git repository accessed through the `git` command.
+#[derive(Debug, Clone)]
+pub struct GitRepo {
+    root: PathBuf,
+}
+
+impl GitRepo {
+    /// Finds the repository containing `path`.
+    ///
+    /// # Errors
+    /// Returns an error if `git` is not available or `path` is not inside a
+    /// repository.
+    pub fn discover(path: &Path) -> Result<Self> {
+        let dir = if path.is_dir() {
+            path
+        } else {
+            path.parent()
+                .filter(|p| !p.as_os_str().is_empty())
+                .unwrap_or_else(|| Path::new("."))
+        };
+        let mut cmd = Command::new("git");
+        cmd.arg("-C")
+            .arg(dir)
+            .args(["rev-parse", "--show-toplevel"]);
+        let output = run_git(&mut cmd)?;
+        let root = PathBuf::from(String::from_utf8_lossy(&output).trim());
+        let root = root.canonicalize().map_err(EngineError::Io)?;
+        Ok(Self { root })
+    }
+
+    /// The repository's top-level directory.
+    #[must_use]
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    fn command(&self) -> Command {
+        let mut cmd = Command::new("git");
+        cmd.arg("-C").arg(&self.root);
+        cmd
+    }
+
+    /// Resolves a revision (branch, tag, `HEAD~5`, ...) to a commit id.
+    ///
+    /// # Errors
+    /// Returns an error if the revision does not name a commit.
+    pub fn resolve(&self, rev: &str) -> Result<String> {
+        if rev.is_empty() || rev.starts_with('-') {
+            return Err(EngineError::Git(format!("invalid revision '{rev}'")));
+        }
+        let output = run_git(self.command().args([
+            "rev-parse",
+            "--verify",
+            "--quiet",
+            &format!("{rev}^{{commit}}"),
+        ]))
+        .map_err(|_| EngineError::Git(format!("unknown revision '{rev}'")))?;
+        Ok(String::from_utf8_lossy(&output).trim().to_string())
+    }
+
+    /// Lists the blobs at `rev`, optionally limited to `pathspecs`
+    /// (relative to the repository root).
+    ///
+    /// Submodules and symbolic links are skipped.
+    ///
+    /// # Errors
+    /// Returns an error if `git ls-tree` fails.
+    pub fn ls_tree(&self, rev: &str, pathspecs: &[PathBuf]) -> Result<Vec<TreeEntry>> {
+        let mut cmd = self.command();
+        cmd.args(["ls-tree", "-r", "-l", "-z", "--full-tree", rev, "--"])
+            .args(pathspecs);
+        let output = run_git(&mut cmd)?;
+
+        Ok(output
+            .split(|&b| b == 0)
+            .filter_map(parse_ls_tree_record)
+            .collect())
+    }
+
+    /// Reads the contents of `entries` with a single `git cat-file --batch`
+    /// process and passes each one to `f`.
+    ///
+    /// # Errors
+    /// Returns an error if the process fails or an object is missing.
+    pub fn read_blobs<F>(&self, entries: &[TreeEntry], mut f: F) -> Result<()>
+    where
+        F: FnMut(&TreeEntry, &[u8]),
+    {
+        let mut child = self
+            .command()
+            .args(["cat-file", "--batch"])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(|err| EngineError::Git(format!("failed to run git: {err}")))?;
+
+        // Feed object ids from a separate thread so a full stdout pipe can't
+        // deadlock against a full stdin pipe.
+        let mut stdin = child.stdin.take().expect("stdin is piped");
+        let oids: Vec<String> = entries.iter().map(|e| e.oid.clone()).collect();
+        let writer = std::thread::spawn(move || -> std::io::Result<()> {
+            for oid in oids {
+                writeln!(stdin, "{oid}")?;
+            }
+            Ok(())
+        });
+
+        let mut reader = BufReader::new(child.stdout.take().expect("stdout is piped"));
+        let mut header = String::new();
+        let mut content = Vec::new();
+        for entry in entries {
+            header.clear();
+            reader.read_line(&mut header).map_err(EngineError::Io)?;
+            let size = header
+                .split_whitespace()
+                .nth(2)
+                .and_then(|s| s.parse::<usize>().ok())
+                .ok_or_else(|| {
+                    EngineError::Git(format!("cannot read object for {}", entry.path.display()))
+                })?;
+
+            content.resize(size + 1, 0);
+            reader.read_exact(&mut content).map_err(EngineError::Io)?;
+            f(entry, &content[..size]);
+        }
+
+        let _ = writer.join();
+        let _ = child.wait();
+        Ok(())
+    }
+
+    /// Lists the blobs staged in the index that differ from `HEAD` (added,
+    /// copied, modified or renamed), optionally limited to `pathspecs`.
+    ///
+    /// Deleted files, submodules and symbolic links are skipped.
+    ///
+    /// # Errors
+    /// Returns an error if `git diff` or `git cat-file` fails.
+    pub fn ls_staged(&self, pathspecs: &[PathBuf]) -> Result<Vec<TreeEntry>> {
+        let mut cmd = self.command();
+        cmd.args([
+            "diff",
+            "--cached",
+            "--raw",
+            "-z",
+            "--no-abbrev",
+            "--diff-filter=ACMR",
+            "--",
+        ])
+        .args(pathspecs);
+        let output = run_git(&mut cmd)?;
+
+        let mut fields = output.split(|&b| b == 0);
+        let mut staged = Vec::new();
+        while let Some(meta) = fields.next() {
+            let Some(meta) = std::str::from_utf8(meta)
+                .ok()
+                .and_then(|m| m.strip_prefix(':'))
+            else {
+                continue;
+            };
+            let meta: Vec<&str> = meta.split_whitespace().collect();
+            let [_, mode, _, oid, status] = meta[..] else {
+                continue;
+            };
+            // Renames and copies list the source path first.
+            if status.starts_with(['R', 'C']) {
+                fields.next();
+            }
+            let Some(path) = fields.next() else { break };
+            if mode.starts_with("100") {
+                staged.push((String::from_utf8_lossy(path).into_owned(), oid.to_string()));
+            }
+        }
+
+        let sizes = self.blob_sizes(staged.iter().map(|(_, oid)| oid.as_str()))?;
+        Ok(staged
+            .into_iter()
+            .zip(sizes)
+            .map(|((path, oid), size)| TreeEntry {
+                path: PathBuf::from(path),
+                oid,
+                size,
+            })
+            .collect())
+    }
+
+    /// Lines added and removed per staged file, as `git diff --cached
+    /// --numstat` reports them (renamed files under their new path).
+    ///
+    /// # Errors
+    /// Returns an error if `git diff` fails.
+    pub fn staged_line_changes(&self, pathspecs: &[PathBuf]) -> Result<Vec<LineChange>> {
+        let mut cmd = self.command();
+        cmd.args(["diff", "--cached", "--numstat", "-z", "-M", "--"])
+            .args(pathspecs);
+        Ok(parse_numstat(&run_git(&mut cmd)?))
+    }
+
+    /// Lines added and removed per file by each commit since `since` (all
+    /// of history when `None`), one entry per commit and file, as
+    /// `git log --numstat` reports them.
+    ///
+    /// # Errors
+    /// Returns an error if `git log` fails.
+    pub fn log_line_changes(
+        &self,
+        since: Option<DateTime<Local>>,
+        pathspecs: &[PathBuf],
+    ) -> Result<Vec<LineChange>> {
+        let mut cmd = self.command();
+        cmd.args(["log", "--numstat", "-z", "--format="]);
+        if let Some(since) = since {
+            cmd.arg(format!("--since={}", since.to_rfc3339()));
+        }
+        cmd.arg("--").args(pathspecs);
+        Ok(parse_numstat(&run_git(&mut cmd)?))
+    }
+
+    /// Sizes of the blobs `oids`, from one `git cat-file --batch-check`.
+    fn blob_sizes<'a>(&self, oids: impl Iterator<Item = &'a str>) -> Result<Vec<u64>> {
+        let input: String = oids.map(|oid| format!("{oid}\n")).collect();
+        if input.is_empty() {
+            return Ok(Vec::new());
+        }
+        let mut child = self
+            .command()
+            .args(["cat-file", "--batch-check"])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(|err| EngineError::Git(format!("failed to run git: {err}")))?;
+
+        let mut stdin = child.stdin.take().expect("stdin is piped");
+        let writer = std::thread::spawn(move || stdin.write_all(input.as_bytes()));
+        let output = child.wait_with_output().map_err(EngineError::Io)?;
+        let _ = writer.join();
+
+        Ok(String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .map(|line| {
+                line.split_whitespace()
+                    .nth(2)
+                    .and_then(|s| s.parse().ok())
+                    .unwrap_or(0)
+            })
+            .collect())
+    }
+
+    /// Blob ids of the working-tree files `paths` (relative to the
+    /// repository root), from one `git hash-object --stdin-paths`.
+    ///
+    /// # Errors
+    /// Returns an error if `git hash-object` fails.
+    pub fn hash_objects(&self, paths: &[PathBuf]) -> Result<Vec<String>> {
+        let input: String = paths.iter().map(|p| format!("{}\n", p.display())).collect();
+        let mut child = self
+            .command()
+            .args(["hash-object", "--no-filters", "--stdin-paths"])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|err| EngineError::Git(format!("failed to run git: {err}")))?;
+
+        let mut stdin = child.stdin.take().expect("stdin is piped");
+        let writer = std::thread::spawn(move || stdin.write_all(input.as_bytes()));
+        let output = child.wait_with_output().map_err(EngineError::Io)?;
+        let _ = writer.join();
+        if !output.status.success() {
+            return Err(EngineError::Git(
+                String::from_utf8_lossy(&output.stderr).trim().to_string(),
+            ));
+        }
+
+        let oids: Vec<String> = String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .map(str::to_string)
+            .collect();
+        if oids.len() != paths.len() {
+            return Err(EngineError::Git(
+                "git hash-object: missing blob ids".to_string(),
+            ));
+        }
+        Ok(oids)
+    }
+
+    /// `git blame --line-porcelain` of the working-tree file `path`
+    /// (relative to the repository root).
+    ///
+    /// # Errors
+    /// Returns an error if `git blame` fails, e.g. for an untracked file.
+    pub fn blame(&self, path: &Path) -> Result<Vec<u8>> {
+        run_git(
+            self.command()
+                .args(["blame", "--line-porcelain", "--"])
+                .arg(path),
+        )
+    }
+
+    /// Tracked files, optionally limited to `pathspecs`, that a sparse
+    /// checkout keeps out of the working tree (the skip-worktree bit,
+    /// tagged `S` by `git ls-files -t`).
+    ///
+    /// # Errors
+    /// Returns an error if `git ls-files` fails.
+    pub fn sparse_paths(&self, pathspecs: &[PathBuf]) -> Result<HashSet<PathBuf>> {
+        let mut cmd = self.command();
+        cmd.args(["ls-files", "-t", "-z", "--"]).args(pathspecs);
+        let output = run_git(&mut cmd)?;
+
+        Ok(output
+            .split(|&b| b == 0)
+            .filter_map(|record| record.strip_prefix(b"S "))
+            .map(|path| PathBuf::from(String::from_utf8_lossy(path).into_owned()))
+            .collect())
+    }
+
+    /// Returns a working-tree path relative to the repository root.
+    #[must_use]
+    pub fn relative_path(&self, path: &Path) -> Option<PathBuf> {
+        let canonical = path.canonicalize().ok()?;
+        canonical
+            .strip_prefix(&self.root)
+            .ok()
+            .map(Path::to_path_buf)
+    }
+}
+
+/// Lines added and removed in one file by a diff.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct LineChange {
+    /// Path relative to the repository root.
+    pub path: PathBuf,
+    /// Lines added, `None` for binary files.
+    pub added: Option<usize>,
+    /// Lines removed, `None` for binary files.
+    pub removed: Option<usize>,
+}
+
+/// Parses `<mode> <type> <oid> <size>\t<path>`.
+fn parse_ls_tree_record(record: &[u8]) -> Option<TreeEntry> {
+    let record = std::str::from_utf8(record).ok()?;
+    let (meta, path) = record.split_once('\t')?;
+    let mut fields = meta.split_whitespace();
+    let mode = fields.next()?;
+    let kind = fields.next()?;
+    let oid = fields.next()?;
+    let size = fields.next()?.parse().ok()?;
+
+    (kind == "blob" && mode != "120000").then(|| TreeEntry {
+        path: PathBuf::from(path),
+        oid: oid.to_string(),
+        size,
+    })
+}
+
+/// Parses `git diff --numstat -z` / `git log --numstat -z` output; renamed
+/// files are listed under their new path.
+fn parse_numstat(output: &[u8]) -> Vec<LineChange> {
+    let mut fields = output.split(|&b| b == 0);
+    let mut changes = Vec::new();
+    while let Some(record) = fields.next() {
+        // `git log` separates the commits with a line break.
+        let record = String::from_utf8_lossy(record.trim_ascii_start());
+        let mut parts = record.splitn(3, '\t');
+        let (Some(added), Some(removed), Some(path)) = (parts.next(), parts.next(), parts.next())
+        else {
+            continue;
+        };
+        // A rename leaves the path empty and lists both paths next.
+        let path = if path.is_empty() {
+            fields.next();
+            fields
+                .next()
+                .map(|p| String::from_utf8_lossy(p).into_owned())
+        } else {
+            Some(path.to_string())
+        };
+        let Some(path) = path else { break };
+        changes.push(LineChange {
+            path: PathBuf::from(path),
+            added: added.parse().ok(),
+            removed: removed.parse().ok(),
+        });
+    }
+    changes
+}
+
+fn run_git(cmd: &mut Command) -> Result<Vec<u8>> {
+    let output = cmd
+        .output()
+        .map_err(|err| EngineError::Git(format!("failed to run git: {err}")))?;
+    if output.status.success() {
+        Ok(output.stdout)
+    } else {
+        Err(EngineError::Git(
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        ))
+    }
+}
+
+fn is_hidden(path: &Path) -> bool {
+    path.components().any(|c| match c {
+        Component::Normal(name) => name.to_string_lossy().starts_with('.'),
+        _ => false,
+    })
+}
+
+/// Selects which tracked files of a revision are counted.
+struct EntrySelector {
+    pathspecs: Vec<PathBuf>,
+    overrides: Option<Override>,
+    allow_ext: HashSet<String>,
+    deny_ext: HashSet<String>,
+}
+
+impl EntrySelector {
+    fn new(repo: &GitRepo, config: &Config) -> Result<Self> {
+        let mut pathspecs = Vec::new();
+        for root in &config.walk.roots {
+            let relative = repo.relative_path(root).ok_or_else(|| {
+                EngineError::Git(format!(
+                    "{} is not inside the repository {}",
+                    root.display(),
+                    repo.root().display()
+                ))
+            })?;
+            if relative.as_os_str().is_empty() {
+                // The repository root itself: list everything.
+                pathspecs.clear();
+                break;
+            }
+            pathspecs.push(relative);
+        }
+
+        Ok(Self {
+            pathspecs,
+            overrides: build_overrides(repo.root(), &config.walk, &config.filter)?,
+            allow_ext: collect_normalized_exts(&config.filter.allow_ext),
+            deny_ext: collect_normalized_exts(&config.filter.deny_ext),
+        })
+    }
+
+    fn entries(&self, repo: &GitRepo, commit: &str, config: &Config) -> Result<Vec<TreeEntry>> {
+        Ok(self.select(repo.ls_tree(commit, &self.pathspecs)?, config))
+    }
+
+    fn select(&self, entries: Vec<TreeEntry>, config: &Config) -> Vec<TreeEntry> {
+        entries
+            .into_iter()
+            .filter(|e| config.walk.hidden || !is_hidden(&e.path))
+            .filter(|e| {
+                self.overrides
+                    .as_ref()
+                    .is_none_or(|ov| !ov.matched(&e.path, false).is_ignore())
+            })
+            .filter(|e| {
+                matches_ext_and_size(
+                    &e.path,
+                    e.size,
+                    &config.filter,
+                    &self.allow_ext,
+                    &self.deny_ext,
+                )
+            })
+            .collect()
+    }
+}
+
+/// Per-blob results, keyed by object id and extension (the extension picks
+/// the SLOC processor, so the same blob may count differently under another name).
+#[derive(Debug, Default)]
+struct BlobCache {
+    stats: HashMap<(String, String), FileStats>,
+}
+
+impl BlobCache {
+    fn key(entry: &TreeEntry) -> (String, String) {
+        let ext = entry
+            .path
+            .extension()
+            .map(|e| e.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        (entry.oid.clone(), ext)
+    }
+
+    /// Counts `entries`, reading only blobs that are not cached yet.
+    fn count(
+        &mut self,
+        repo: &GitRepo,
+        entries: &[TreeEntry],
+        config: &Config,
+    ) -> Result<Vec<FileStats>> {
+        let missing: Vec<TreeEntry> = entries
+            .iter()
+            .filter(|e| !self.stats.contains_key(&Self::key(e)))
+            .cloned()
+            .collect();
+        let mut failed = None;
+        repo.read_blobs(&missing, |entry, content| {
+            match count_content(entry.path.clone(), content, config) {
+                Ok(stats) => {
+                    self.stats.insert(Self::key(entry), stats);
+                }
+                Err(err) => {
+                    failed.get_or_insert(err);
+                }
+            }
+        })?;
+        if let Some(err) = failed {
+            return Err(err);
+        }
+
+        Ok(entries
+            .iter()
+            .filter_map(|entry| {
+                let cached = self.stats.get(&Self::key(entry))?;
+                let named = FileStats::new(entry.path.clone());
+                Some(FileStats {
+                    path: named.path,
+                    name: named.name,
+                    ..cached.clone()
+                })
+            })
+            .filter(|stats| crate::matches_result_filter(stats, &config.filter))
+            .collect())
+    }
+}
+
+pub(crate) fn discover_for(config: &Config) -> Result<GitRepo> {
+    let first_root = config
+        .walk
+        .roots
+        .first()
+        .cloned()
+        .unwrap_or_else(|| PathBuf::from("."));
+    GitRepo::discover(&first_root)
+}
+
+/// Counts the files under the configured roots as they were at `rev`.
+///
+/// Paths in the result are relative to the repository root. The same
+/// extension, size, include/exclude and count filters as a normal run apply;
+/// modification-time filters are ignored because blobs have no mtime.
+///
+/// # Errors
+/// Returns an error if the repository or revision cannot be read.
+pub fn count_revision(config: &Config, rev: &str) -> Result<RunResult> {
+    let repo = discover_for(config)?;
+    let commit = repo.resolve(rev)?;
+    let selector = EntrySelector::new(&repo, config)?;
+    let entries = selector.entries(&repo, &commit, config)?;
+
+    Ok(RunResult {
+        stats: BlobCache::default().count(&repo, &entries, config)?,
+        ..RunResult::default()
+    })
+}
+
+/// Like [`count_revision`], but leaves out the files a sparse checkout keeps
+/// out of the working tree, so the result lines up with
+/// [`count_working_tree`] instead of reporting them as removed.
+///
+/// Their blobs are never read, so a partial clone doesn't fetch them either.
+/// How many were left out is in [`RunResult::sparse_skipped`].
+///
+/// # Errors
+/// Returns an error if the repository or revision cannot be read.
+pub fn count_checked_out_revision(config: &Config, rev: &str) -> Result<RunResult> {
+    let repo = discover_for(config)?;
+    let commit = repo.resolve(rev)?;
+    let selector = EntrySelector::new(&repo, config)?;
+    let sparse = repo.sparse_paths(&selector.pathspecs)?;
+    let (skipped, entries): (Vec<TreeEntry>, Vec<TreeEntry>) = selector
+        .entries(&repo, &commit, config)?
+        .into_iter()
+        .partition(|e| sparse.contains(&e.path));
+
+    Ok(RunResult {
+        stats: BlobCache::default().count(&repo, &entries, config)?,
+        sparse_skipped: skipped.len(),
+        ..RunResult::default()
+    })
+}
+
+/// Counts the files under the configured roots that are staged for the next
+/// commit, as they are in the index.
+///
+/// Paths in the result are relative to the repository root and the same
+/// filters as [`count_revision`] apply. Files staged for deletion are left out.
+///
+/// # Errors
+/// Returns an error if the repository or the index cannot be read.
+pub fn count_staged(config: &Config) -> Result<RunResult> {
+    let repo = discover_for(config)?;
+    let selector = EntrySelector::new(&repo, config)?;
+    let entries = selector.select(repo.ls_staged(&selector.pathspecs)?, config);
+
+    Ok(RunResult {
+        stats: BlobCache::default().count(&repo, &entries, config)?,
+        ..RunResult::default()
+    })
+}
+
+/// Lines added and removed per file under the configured roots by the
+/// changes staged for the next commit.
+///
+/// # Errors
+/// Returns an error if the repository or the index cannot be read.
+pub fn staged_line_changes(config: &Config) -> Result<Vec<LineChange>> {
+    let repo = discover_for(config)?;
+    let selector = EntrySelector::new(&repo, config)?;
+    repo.staged_line_changes(&selector.pathspecs)
+}
+
+/// The path of `name` inside the `.git` directory of the repository of the
+/// first root (`hooks/pre-commit`, ...), honouring `core.hooksPath` and
+/// worktrees.
+///
+/// # Errors
+/// Returns an error if the repository cannot be found.
+pub fn git_path(config: &Config, name: &str) -> Result<PathBuf> {
+    let repo = discover_for(config)?;
+    let output = run_git(repo.command().args(["rev-parse", "--git-path", name]))?;
+    let path = PathBuf::from(String::from_utf8_lossy(&output).trim());
+    Ok(repo.root().join(path))
+}
+
+/// The commit `HEAD` points to in the repository of the first root.
+///
+/// # Errors
+/// Returns an error if the repository cannot be found or has no commits.
+pub fn head_commit(config: &Config) -> Result<String> {
+    discover_for(config)?.resolve("HEAD")
+}
+
+/// Which commits [`count_history`] visits.
+#[derive(Debug, Clone, Default)]
+pub struct HistoryOptions {
+    /// Only the most recent `n` commits.
+    pub max_count: Option<usize>,
+    /// Only commits made at or after this time.
+    pub since: Option<DateTime<Local>>,
+    /// Count every `step`-th commit (the newest commit is always counted).
+    pub step: usize,
+}
+
+/// Totals for one language (file extension) at a commit.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct LanguageTotals {
+    pub files: usize,
+    pub lines: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sloc: Option<usize>,
+}
+
+/// Counts at one commit of the history.
+#[derive(Debug, Clone, Serialize)]
+pub struct HistoryPoint {
+    /// Commit id.
+    pub commit: String,
+    /// Committer date.
+    pub date: DateTime<Local>,
+    /// Totals over all counted files.
+    pub summary: Summary,
+    /// Totals per lowercase extension (`(none)` for files without one).
+    pub languages: BTreeMap<String, LanguageTotals>,
+}
+
+impl HistoryPoint {
+    fn new(commit: String, date: DateTime<Local>, stats: &[FileStats]) -> Self {
+        let mut languages: BTreeMap<String, LanguageTotals> = BTreeMap::new();
+        for s in stats.iter().filter(|s| !s.is_binary) {
+            let key = if s.ext.is_empty() {
+                "(none)".to_string()
+            } else {
+                s.ext.to_lowercase()
+            };
+            let totals = languages.entry(key).or_default();
+            totals.files += 1;
+            totals.lines += s.lines;
+            if let Some(sloc) = s.sloc {
+                *totals.sloc.get_or_insert(0) += sloc;
+            }
+        }
+
+        let text: Vec<FileStats> = stats.iter().filter(|s| !s.is_binary).cloned().collect();
+        Self {
+            commit,
+            date,
+            summary: Summary::from_stats(&text),
+            languages,
+        }
+    }
+}
+
+/// Counts the configured roots at each commit of the first-parent history of
+/// `HEAD`, oldest first.
+///
+/// Blob results are cached for the whole walk, so unchanged files are only
+/// counted once.
+///
+/// # Errors
+/// Returns an error if the repository or history cannot be read.
+pub fn count_history(config: &Config, options: &HistoryOptions) -> Result<Vec<HistoryPoint>> {
+    let repo = discover_for(config)?;
+    let selector = EntrySelector::new(&repo, config)?;
+
+    let mut cmd = repo.command();
+    cmd.args(["log", "--first-parent", "--format=%H %cI"]);
+    if let Some(n) = options.max_count {
+        cmd.arg(format!("--max-count={n}"));
+    }
+    if let Some(since) = options.since {
+        cmd.arg(format!("--since={}", since.to_rfc3339()));
+    }
+    cmd.args(["HEAD", "--"]).args(&selector.pathspecs);
+    let output = run_git(&mut cmd)?;
+
+    // `git log` lists newest first; sample from the newest so HEAD is kept.
+    let step = options.step.max(1);
+    let mut commits: Vec<(String, DateTime<Local>)> = String::from_utf8_lossy(&output)
+        .lines()
+        .filter_map(|line| {
+            let (id, date) = line.split_once(' ')?;
+            let date = DateTime::parse_from_rfc3339(date)
+                .ok()?
+                .with_timezone(&Local);
+            Some((id.to_string(), date))
+        })
+        .step_by(step)
+        .collect();
+    commits.reverse();
+
+    let mut cache = BlobCache::default();
+    commits
+        .into_iter()
+        .map(|(commit, date)| {
+            let entries = selector.entries(&repo, &commit, config)?;
+            let stats = cache.count(&repo, &entries, config)?;
+            Ok(HistoryPoint::new(commit, date, &stats))
+        })
+        .collect()
+}
+
+/// Which commits [`count_churn`] reads.
+#[derive(Debug, Clone, Default)]
+pub struct ChurnOptions {
+    /// Only commits made at or after this time.
+    pub since: Option<DateTime<Local>>,
+}
+
+/// How much one file changed over the history, next to its current size.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct FileChurn {
+    /// Path relative to the repository root.
+    pub path: PathBuf,
+    /// Current number of lines.
+    pub lines: usize,
+    /// Current SLOC, if counted.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sloc: Option<usize>,
+    /// Lines added by the commits.
+    pub added: usize,
+    /// Lines removed by the commits.
+    pub removed: usize,
+    /// Commits that changed the file.
+    pub commits: usize,
+    /// `added + removed`.
+    pub churn: usize,
+    /// `churn * lines`: large files that keep changing score highest.
+    pub score: u64,
+}
+
+/// Sums the line changes of the commits selected by `options` per file and
+/// joins them with the current counts of the working tree.
+///
+/// Only files that exist now, pass the normal filters and changed at least
+/// once are listed, hotspots (highest [`FileChurn::score`]) first.
+///
+/// # Errors
+/// Returns an error if the repository cannot be read or the count fails.
+pub fn count_churn(config: &Config, options: &ChurnOptions) -> Result<Vec<FileChurn>> {
+    let repo = discover_for(config)?;
+    let selector = EntrySelector::new(&repo, config)?;
+    let mut changes: HashMap<PathBuf, (usize, usize, usize)> = HashMap::new();
+    for change in repo.log_line_changes(options.since, &selector.pathspecs)? {
+        let totals = changes.entry(change.path).or_default();
+        totals.0 += change.added.unwrap_or(0);
+        totals.1 += change.removed.unwrap_or(0);
+        totals.2 += 1;
+    }
+
+    let current = count_working_tree(config)?;
+    let mut churn: Vec<FileChurn> = current
+        .stats
+        .into_iter()
+        .filter(|s| !s.is_binary)
+        .filter_map(|s| {
+            let &(added, removed, commits) = changes.get(&s.path)?;
+            let total = added + removed;
+            Some(FileChurn {
+                lines: s.lines,
+                sloc: s.sloc,
+                added,
+                removed,
+                commits,
+                churn: total,
+                score: total as u64 * s.lines as u64,
+                path: s.path,
+            })
+        })
+        .collect();
+    churn.sort_by(|a, b| b.score.cmp(&a.score).then_with(|| a.path.cmp(&b.path)));
+    Ok(churn)
+}
+
+/// Runs a normal count and rewrites paths relative to the repository root,
+/// so the result can be compared with [`count_revision`].
+///
+/// # Errors
+/// Returns an error if the repository cannot be found or the run fails.
+pub fn count_working_tree(config: &Config) -> Result<RunResult> {
+    let repo = discover_for(config)?;
+
+    let mut result = crate::run(config)?;
+    for stats in &mut result.stats {
+        if let Some(relative) = repo.relative_path(&stats.path) {
+            stats.path = relative;
+        }
+    }
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::WalkOptions;
+    use crate::testing::git;
+
+    fn repo_with_history() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        git(root, &["init", "-q"]);
+        std::fs::create_dir(root.join("src")).unwrap();
+        std::fs::write(root.join("src/a.rs"), "fn a() {}\n").unwrap();
+        std::fs::write(root.join("notes.txt"), "x\n").unwrap();
+        git(root, &["add", "."]);
+        git(root, &["commit", "-q", "-m", "first"]);
+
+        std::fs::write(root.join("src/a.rs"), "fn a() {}\n// c\nfn b() {}\n").unwrap();
+        std::fs::write(root.join("src/b.rs"), "fn c() {}\n").unwrap();
+        git(root, &["add", "."]);
+        git(root, &["commit", "-q", "-m", "second"]);
+        dir
+    }
+
+    fn config(root: &Path) -> Config {
+        Config {
+            walk: WalkOptions {
+                roots: vec![root.to_path_buf()],
+                ..WalkOptions::default()
+            },
+            count_sloc: true,
+            ..Config::default()
+        }
+    }
+
+    #[test]
+    fn test_parse_ls_tree_record() {
+        let entry = parse_ls_tree_record(b"100644 blob abc123     42\tsrc/a b.rs").unwrap();
+        assert_eq!(entry.path, PathBuf::from("src/a b.rs"));
+        assert_eq!(entry.oid, "abc123");
+        assert_eq!(entry.size, 42);
+
+        assert!(parse_ls_tree_record(b"160000 commit abc123       -\tvendor").is_none());
+        assert!(parse_ls_tree_record(b"120000 blob abc123       3\tlink").is_none());
+    }
+
+    #[test]
+    fn test_count_revision() {
+        let dir = repo_with_history();
+        let config = config(dir.path());
+
+        let mut old = count_revision(&config, "HEAD~1").unwrap().stats;
+        old.sort_by(|a, b| a.path.cmp(&b.path));
+        let paths: Vec<_> = old.iter().map(|s| s.path.clone()).collect();
+        assert_eq!(
+            paths,
+            [PathBuf::from("notes.txt"), PathBuf::from("src/a.rs")]
+        );
+        assert_eq!(old[1].lines, 1);
+
+        let mut new = count_revision(&config, "HEAD").unwrap().stats;
+        new.sort_by(|a, b| a.path.cmp(&b.path));
+        assert_eq!(new.len(), 3);
+        assert_eq!(new[1].lines, 3);
+        assert_eq!(new[1].sloc, Some(2));
+
+        assert!(count_revision(&config, "no-such-ref").is_err());
+        assert!(count_revision(&config, "--output=x").is_err());
+    }
+
+    #[test]
+    fn test_count_revision_respects_roots_and_filters() {
+        let dir = repo_with_history();
+        let mut config = config(&dir.path().join("src"));
+        config.filter.exclude_patterns = vec!["b.rs".to_string()];
+
+        let stats = count_revision(&config, "HEAD").unwrap().stats;
+        let paths: Vec<_> = stats.iter().map(|s| s.path.clone()).collect();
+        assert_eq!(paths, [PathBuf::from("src/a.rs")]);
+    }
+
+    #[test]
+    fn test_count_history() {
+        let dir = repo_with_history();
+        let config = config(dir.path());
+
+        let points = count_history(&config, &HistoryOptions::default()).unwrap();
+        assert_eq!(points.len(), 2);
+        assert!(points[0].date <= points[1].date);
+        assert_eq!(points[0].summary.lines, 2);
+        assert_eq!(points[1].summary.lines, 5);
+        assert_eq!(points[1].summary.sloc, Some(4));
+        assert_eq!(
+            points[1].languages["rs"],
+            LanguageTotals {
+                files: 2,
+                lines: 4,
+                sloc: Some(3),
+            }
+        );
+
+        let latest = count_history(
+            &config,
+            &HistoryOptions {
+                max_count: Some(1),
+                ..HistoryOptions::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(latest.len(), 1);
+        assert_eq!(latest[0].commit, points[1].commit);
+
+        let sampled = count_history(
+            &config,
+            &HistoryOptions {
+                step: 2,
+                ..HistoryOptions::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(sampled.len(), 1);
+        assert_eq!(sampled[0].commit, points[1].commit);
+    }
+
+    #[test]
+    fn test_count_staged() {
+        let dir = repo_with_history();
+        let root = dir.path();
+        std::fs::write(root.join("src/a.rs"), "fn a() {}\n").unwrap();
+        std::fs::write(root.join("src/new.rs"), "fn n() {}\n// n\n").unwrap();
+        std::fs::write(root.join("notes.txt"), "unstaged\n").unwrap();
+        git(root, &["add", "src"]);
+        git(root, &["mv", "src/b.rs", "src/moved.rs"]);
+        // Working-tree edits after staging are not counted.
+        std::fs::write(root.join("src/new.rs"), "changed\n").unwrap();
+
+        let config = config(root);
+        let mut stats = count_staged(&config).unwrap().stats;
+        stats.sort_by(|a, b| a.path.cmp(&b.path));
+        let counted: Vec<_> = stats
+            .iter()
+            .map(|s| (s.path.to_str().unwrap(), s.lines))
+            .collect();
+        assert_eq!(
+            counted,
+            [("src/a.rs", 1), ("src/moved.rs", 1), ("src/new.rs", 2)]
+        );
+
+        let mut changes = staged_line_changes(&config).unwrap();
+        changes.sort_by(|a, b| a.path.cmp(&b.path));
+        let changed: Vec<_> = changes
+            .iter()
+            .map(|c| (c.path.to_str().unwrap(), c.added, c.removed))
+            .collect();
+        assert_eq!(
+            changed,
+            [
+                ("src/a.rs", Some(0), Some(2)),
+                ("src/moved.rs", Some(0), Some(0)),
+                ("src/new.rs", Some(2), Some(0)),
+            ]
+        );
+
+        let hook = git_path(&config, "hooks/pre-commit").unwrap();
+        assert!(hook.ends_with(".git/hooks/pre-commit"));
+    }
+
+    #[test]
+    fn test_count_working_tree_uses_repo_relative_paths() {
+        let dir = repo_with_history();
+        let config = config(&dir.path().join("src"));
+
+        let mut stats = count_working_tree(&config).unwrap().stats;
+        stats.sort_by(|a, b| a.path.cmp(&b.path));
+        let paths: Vec<_> = stats.iter().map(|s| s.path.clone()).collect();
+        assert_eq!(
+            paths,
+            [PathBuf::from("src/a.rs"), PathBuf::from("src/b.rs")]
+        );
+    }
+
+    #[test]
+    fn test_parse_log_numstat() {
+        let output = b"\n2\t1\tsrc/a.rs\x000\t0\t\x00old.rs\x00new.rs\x00\n-\t-\tlogo.png\x00";
+        let changes = parse_numstat(output);
+        let rows: Vec<_> = changes
+            .iter()
+            .map(|c| (c.path.to_str().unwrap(), c.added, c.removed))
+            .collect();
+        assert_eq!(
+            rows,
+            [
+                ("src/a.rs", Some(2), Some(1)),
+                ("new.rs", Some(0), Some(0)),
+                ("logo.png", None, None),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_count_churn() {
+        let dir = repo_with_history();
+        let root = dir.path();
+        let churn = count_churn(&config(root), &ChurnOptions::default()).unwrap();
+        let rows: Vec<_> = churn
+            .iter()
+            .map(|c| {
+                (
+                    c.path.to_str().unwrap(),
+                    c.added,
+                    c.removed,
+                    c.commits,
+                    c.score,
+                )
+            })
+            .collect();
+        // src/a.rs: 1 line, then 2 more; now 3 lines. Ties go by path.
+        assert_eq!(
+            rows,
+            [
+                ("src/a.rs", 3, 0, 2, 9),
+                ("notes.txt", 1, 0, 1, 1),
+                ("src/b.rs", 1, 0, 1, 1),
+            ]
+        );
+
+        // Nothing was committed in the future.
+        let since = Local::now() + chrono::Duration::days(1);
+        let options = ChurnOptions { since: Some(since) };
+        assert!(count_churn(&config(root), &options).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_count_checked_out_revision_skips_sparse_files() {
+        let dir = repo_with_history();
+        let root = dir.path();
+        git(root, &["sparse-checkout", "set", "--no-cone", "/src/"]);
+        assert!(!root.join("notes.txt").exists());
+        let config = config(root);
+
+        let old = count_checked_out_revision(&config, "HEAD").unwrap();
+        assert_eq!(old.sparse_skipped, 1);
+        let mut paths: Vec<_> = old.stats.iter().map(|s| s.path.clone()).collect();
+        paths.sort();
+        assert_eq!(
+            paths,
+            [PathBuf::from("src/a.rs"), PathBuf::from("src/b.rs")]
+        );
+
+        // Counting the revision itself still sees every tracked file.
+        let full = count_revision(&config, "HEAD").unwrap();
+        assert_eq!(full.stats.len(), 3);
+        assert_eq!(full.sparse_skipped, 0);
+    }
+}
@@ -0,0 +1,1018 @@
+// crates/engine/src/grouping.rs
+use crate::attributes::LinguistClass;
+use crate::codeowners::CodeOwners;
+use crate::config::Config;
+use crate::expr::Value;
+use crate::manifest::CrateIndex;
+use crate::options::{GroupBy, RatioBase, RatioMetric};
+use crate::stats::{FileStats, Summary};
+use crate::submodules::Submodules;
+use chrono::{DateTime, Duration, Local};
+use count_lines_core::language::{language_name, resolve_extension};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Component, Path, PathBuf};
+
+/// Key used for files without an extension when grouping by extension.
+pub const NO_EXTENSION: &str = "(none)";
+/// Key used for files whose language isn't known when grouping by language.
+pub const OTHER_LANGUAGE: &str = "Other";
+/// Key used for files without a Linguist class when grouping by Linguist class.
+pub const LINGUIST_SOURCE: &str = "source";
+/// Key used for files without a modification time when grouping by age.
+pub const UNKNOWN_AGE: &str = "(unknown)";
+/// Key used for files directly in a scan root when grouping by root directory.
+pub const ROOT_FILES: &str = ".";
+/// Key used for files outside any Cargo crate when grouping by crate.
+pub const NO_CRATE: &str = "(none)";
+/// Key used for files no `CODEOWNERS` line assigns when grouping by owner.
+pub const UNOWNED: &str = "(unowned)";
+/// Key used for files outside any submodule when grouping by submodule.
+pub const NO_SUBMODULE: &str = "(none)";
+/// Key used for files under none of the scan roots when grouping by root.
+pub const NO_ROOT: &str = "(none)";
+/// Key used for files `git blame` can't attribute when grouping by author.
+pub const NO_AUTHOR: &str = "(unknown)";
+
+/// Totals for one group of files.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GroupStats {
+    /// The group name (a language name or an extension).
+    pub key: String,
+    /// Totals over the files in the group.
+    #[serde(flatten)]
+    pub summary: Summary,
+    /// Share of the `--ratio-of` total, set by [`apply_ratios`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ratio: Option<Ratio>,
+    /// Breakdown by the next grouping key, when several are given.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub groups: Vec<GroupStats>,
+}
+
+impl GroupStats {
+    /// Returns the value of a `--group-filter` field (see
+    /// [`GroupFilter::FIELDS`](crate::expr::GroupFilter::FIELDS)), or
+    /// `None` if it wasn't counted (`words`, `sloc`) or is unknown.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn value(&self, name: &str) -> Option<Value> {
+        let s = &self.summary;
+        let value = match name {
+            "key" => return Some(Value::Text(self.key.clone())),
+            "files" => s.files as f64,
+            "lines" => s.lines as f64,
+            "chars" => s.chars as f64,
+            "words" => s.words? as f64,
+            "sloc" => s.sloc? as f64,
+            "size" => s.size as f64,
+            _ => return None,
+        };
+        Some(Value::Number(value))
+    }
+}
+
+/// A group's share of a total, in percent.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Ratio {
+    pub percent: f64,
+    /// Running sum of `percent` over this group and the larger ones before
+    /// it; absent where groups aren't ordered by size (`--by age`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cumulative: Option<f64>,
+}
+
+/// The value of `metric` in `summary`; totals that weren't counted are zero.
+#[must_use]
+pub fn metric_value(summary: &Summary, metric: RatioMetric) -> u64 {
+    let value = match metric {
+        RatioMetric::Files => summary.files,
+        RatioMetric::Lines => summary.lines,
+        RatioMetric::Sloc => summary.sloc.unwrap_or(0),
+        RatioMetric::Chars => summary.chars,
+        RatioMetric::Words => summary.words.unwrap_or(0),
+        RatioMetric::Size => return summary.size,
+    };
+    value as u64
+}
+
+/// Sets the [`Ratio`] of every group of `groups`, nested by `by`, against
+/// `total` or the enclosing group as `base` says.
+///
+/// Each level is reordered by `metric`, largest first, so the cumulative
+/// percentages read as "the top N groups hold X%"; age groups keep their
+/// chronological order and get no cumulative percentage.
+#[allow(clippy::cast_precision_loss)]
+pub fn apply_ratios(
+    groups: &mut [GroupStats],
+    by: &[GroupBy],
+    metric: RatioMetric,
+    base: RatioBase,
+    total: &Summary,
+) {
+    fn apply(
+        groups: &mut [GroupStats],
+        by: &[GroupBy],
+        metric: RatioMetric,
+        base: RatioBase,
+        denominator: u64,
+        total: u64,
+    ) {
+        let ordered = by.first() != Some(&GroupBy::Age);
+        if ordered {
+            groups.sort_by(|a, b| {
+                metric_value(&b.summary, metric)
+                    .cmp(&metric_value(&a.summary, metric))
+                    .then_with(|| a.key.cmp(&b.key))
+            });
+        }
+        let percent = |value: u64| {
+            if denominator == 0 {
+                0.0
+            } else {
+                value as f64 * 100.0 / denominator as f64
+            }
+        };
+        let mut running = 0;
+        for g in groups {
+            let value = metric_value(&g.summary, metric);
+            running += value;
+            g.ratio = Some(Ratio {
+                percent: percent(value),
+                cumulative: ordered.then(|| percent(running)),
+            });
+            let inner = match base {
+                RatioBase::Total => total,
+                RatioBase::Group => value,
+            };
+            apply(
+                &mut g.groups,
+                by.get(1..).unwrap_or_default(),
+                metric,
+                base,
+                inner,
+                total,
+            );
+        }
+    }
+
+    let total = metric_value(total, metric);
+    apply(groups, by, metric, base, total, total);
+}
+
+/// Returns the group key of a file.
+///
+/// Language grouping honours `--map-ext` and user-defined languages, so a
+/// file counted with a custom definition is reported under that name.
+#[must_use]
+pub fn group_key(stats: &FileStats, by: GroupBy, config: &Config) -> String {
+    Keys::new(by, config).key(stats)
+}
+
+/// Computes the group keys of one run, sharing lookups between files.
+struct Keys<'a> {
+    by: GroupBy,
+    config: &'a Config,
+    crates: CrateIndex,
+    owners: Option<CodeOwners>,
+    submodules: Submodules,
+    now: DateTime<Local>,
+}
+
+impl<'a> Keys<'a> {
+    fn new(by: GroupBy, config: &'a Config) -> Self {
+        Self {
+            by,
+            config,
+            crates: CrateIndex::new(),
+            owners: if by == GroupBy::Owner {
+                load_codeowners(config)
+            } else {
+                None
+            },
+            submodules: if by == GroupBy::Submodule {
+                Submodules::find(&config.walk.roots)
+            } else {
+                Submodules::default()
+            },
+            now: Local::now(),
+        }
+    }
+
+    fn key(&mut self, stats: &FileStats) -> String {
+        let config = self.config;
+        match self.by {
+            GroupBy::Ext => {
+                if stats.ext.is_empty() {
+                    NO_EXTENSION.to_string()
+                } else {
+                    stats.ext.to_lowercase()
+                }
+            }
+            GroupBy::Linguist => stats
+                .linguist
+                .map_or(LINGUIST_SOURCE, LinguistClass::as_str)
+                .to_string(),
+            GroupBy::Language => {
+                let ext = resolve_extension(&stats.ext, &config.filter.map_ext);
+                if let Some(definition) = config.languages.iter().find(|d| d.matches(ext)) {
+                    return definition.name.clone();
+                }
+                language_name(ext)
+                    .or_else(|| language_name(&stats.name))
+                    .unwrap_or(OTHER_LANGUAGE)
+                    .to_string()
+            }
+            GroupBy::Age => age_key(stats.mtime, self.now, &config.age_buckets),
+            GroupBy::RootDir => root_dir(&stats.path, &config.walk.roots),
+            GroupBy::Crate => self
+                .crates
+                .crate_of(&stats.path)
+                .unwrap_or_else(|| NO_CRATE.to_string()),
+            GroupBy::Owner => self
+                .owners
+                .as_ref()
+                .and_then(|o| o.owners(&stats.path))
+                .map_or_else(|| UNOWNED.to_string(), |owners| owners.join(" ")),
+            GroupBy::Submodule => self
+                .submodules
+                .submodule_of(&stats.path)
+                .unwrap_or(NO_SUBMODULE)
+                .to_string(),
+            GroupBy::Root => stats
+                .root
+                .as_deref()
+                .or_else(|| root_of(&stats.path, &config.walk.roots))
+                .map_or_else(|| NO_ROOT.to_string(), |root| root.display().to_string()),
+            GroupBy::Author => stats
+                .author
+                .clone()
+                .unwrap_or_else(|| NO_AUTHOR.to_string()),
+        }
+    }
+}
+
+/// The `--codeowners` file, or the `CODEOWNERS` of the repository of the
+/// first scan root.
+fn load_codeowners(config: &Config) -> Option<CodeOwners> {
+    if let Some(file) = &config.codeowners {
+        return CodeOwners::load(file)
+            .inspect_err(|e| log::warn!("Cannot read {}: {e}", file.display()))
+            .ok();
+    }
+    let root = config
+        .walk
+        .roots
+        .first()
+        .map_or(Path::new("."), PathBuf::as_path);
+    let start = if root.is_file() {
+        root.parent().unwrap_or(Path::new("."))
+    } else {
+        root
+    };
+    let codeowners = CodeOwners::find(start);
+    if codeowners.is_none() {
+        log::warn!("No CODEOWNERS file found; every file is {UNOWNED}");
+    }
+    codeowners
+}
+
+/// The deepest of the scan `roots` containing `path`.
+#[must_use]
+pub fn root_of<'a>(path: &Path, roots: &'a [PathBuf]) -> Option<&'a Path> {
+    roots
+        .iter()
+        .filter(|root| path.starts_with(root))
+        .max_by_key(|root| root.components().count())
+        .map(PathBuf::as_path)
+}
+
+/// The first path component of `path` below the deepest scan root
+/// containing it.
+fn root_dir(path: &Path, roots: &[PathBuf]) -> String {
+    let relative = root_of(path, roots)
+        .and_then(|root| path.strip_prefix(root).ok())
+        .unwrap_or(path);
+    let mut components = relative
+        .components()
+        .filter(|c| matches!(c, Component::Normal(_)));
+    match (components.next(), components.next()) {
+        (Some(first), Some(_)) => first.as_os_str().to_string_lossy().into_owned(),
+        _ => ROOT_FILES.to_string(),
+    }
+}
+
+/// Writes an age with the largest unit that divides it exactly
+/// (`1y`, `6mo`, `2w`, `36h`, ...).
+#[must_use]
+pub fn format_age(age: Duration) -> String {
+    const UNITS: [(&str, i64); 7] = [
+        ("y", 365 * 86_400),
+        ("mo", 30 * 86_400),
+        ("w", 7 * 86_400),
+        ("d", 86_400),
+        ("h", 3_600),
+        ("m", 60),
+        ("s", 1),
+    ];
+    let secs = age.num_seconds();
+    let (unit, size) = UNITS
+        .into_iter()
+        .find(|(_, size)| secs % size == 0)
+        .unwrap_or(("s", 1));
+    format!("{}{unit}", secs / size)
+}
+
+/// Labels of the age buckets bounded by `buckets` (ascending), youngest
+/// first: `<1w`, `1w-1mo`, ..., `>=6mo`.
+#[must_use]
+pub fn age_labels(buckets: &[Duration]) -> Vec<String> {
+    let Some(last) = buckets.last() else {
+        return vec!["all".to_string()];
+    };
+    let mut labels = vec![format!("<{}", format_age(buckets[0]))];
+    labels.extend(
+        buckets
+            .windows(2)
+            .map(|w| format!("{}-{}", format_age(w[0]), format_age(w[1]))),
+    );
+    labels.push(format!(">={}", format_age(*last)));
+    labels
+}
+
+/// The age bucket of a file modified at `mtime`, as seen at `now`.
+fn age_key(mtime: Option<DateTime<Local>>, now: DateTime<Local>, buckets: &[Duration]) -> String {
+    let Some(mtime) = mtime else {
+        return UNKNOWN_AGE.to_string();
+    };
+    let age = now - mtime;
+    let index = buckets.iter().take_while(|&&b| age >= b).count();
+    age_labels(buckets).swap_remove(index)
+}
+
+/// Aggregates `stats` into one [`GroupStats`] per group.
+///
+/// Groups are ordered by line count (largest first), then by key; age
+/// groups are ordered from youngest to oldest, unknown last.
+#[must_use]
+pub fn group_stats(stats: &[FileStats], by: GroupBy, config: &Config) -> Vec<GroupStats> {
+    nested_group_stats(stats, &[by], config)
+}
+
+/// Aggregates `stats` by the first key of `by`, then each group by the
+/// next key, and so on; every level is ordered as in [`group_stats`].
+///
+/// Groups failing a `--group-filter` expression are dropped at every level,
+/// together with their breakdowns. With [`GroupBy::Author`] the files are
+/// first split by `git blame` (see [`crate::blame`]).
+#[must_use]
+pub fn nested_group_stats(stats: &[FileStats], by: &[GroupBy], config: &Config) -> Vec<GroupStats> {
+    let mut keys: Vec<Keys> = by.iter().map(|&b| Keys::new(b, config)).collect();
+    let blamed;
+    let stats = if by.contains(&GroupBy::Author) {
+        blamed = crate::blame::split_by_author(stats, config);
+        &blamed[..]
+    } else {
+        stats
+    };
+    let stats: Vec<&FileStats> = stats.iter().collect();
+    group_level(&stats, &mut keys, config)
+}
+
+fn group_level(stats: &[&FileStats], keys: &mut [Keys], config: &Config) -> Vec<GroupStats> {
+    let Some((level, rest)) = keys.split_first_mut() else {
+        return Vec::new();
+    };
+    let mut buckets: HashMap<String, Vec<&FileStats>> = HashMap::new();
+    for &s in stats {
+        buckets.entry(level.key(s)).or_default().push(s);
+    }
+
+    let mut groups: Vec<GroupStats> = buckets
+        .into_iter()
+        .map(|(key, files)| {
+            let mut summary = Summary::default();
+            for s in &files {
+                summary.add(s);
+            }
+            GroupStats {
+                key,
+                summary,
+                ratio: None,
+                groups: group_level(&files, rest, config),
+            }
+        })
+        .collect();
+    if level.by == GroupBy::Language {
+        for &s in stats {
+            move_embedded(&mut groups, s, &level.key(s));
+        }
+    }
+    groups.retain(|g| config.group_filter.iter().all(|f| f.matches(g)));
+    if level.by == GroupBy::Age {
+        let labels = age_labels(&config.age_buckets);
+        let position = |key: &str| labels.iter().position(|l| l == key).unwrap_or(labels.len());
+        groups.sort_by_key(|g| position(&g.key));
+        return groups;
+    }
+    groups.sort_by(|a, b| {
+        b.summary
+            .lines
+            .cmp(&a.summary.lines)
+            .then_with(|| a.key.cmp(&b.key))
+    });
+    groups
+}
+
+/// Moves the lines of the `<script>` / `<style>` sections of `s` from its
+/// group `key` to the groups of their languages. The file itself stays
+/// counted in `key`, so a language known only from sections has no files.
+fn move_embedded(groups: &mut Vec<GroupStats>, s: &FileStats, key: &str) {
+    fn group<'a>(groups: &'a mut Vec<GroupStats>, key: &str) -> &'a mut Summary {
+        let index = match groups.iter().position(|g| g.key == key) {
+            Some(index) => index,
+            None => {
+                groups.push(GroupStats {
+                    key: key.to_string(),
+                    summary: Summary::default(),
+                    ratio: None,
+                    groups: Vec::new(),
+                });
+                groups.len() - 1
+            }
+        };
+        &mut groups[index].summary
+    }
+
+    for section in s.embedded.iter().filter(|e| e.language != key) {
+        let container = group(groups, key);
+        container.lines = container.lines.saturating_sub(section.lines);
+        container.sloc = container.sloc.map(|v| v.saturating_sub(section.sloc));
+        let counted_sloc = container.sloc.is_some();
+        let target = group(groups, &section.language);
+        target.lines += section.lines;
+        if counted_sloc {
+            target.sloc = Some(target.sloc.unwrap_or(0) + section.sloc);
+        }
+    }
+}
+
+/// Per-directory totals arranged as a tree (`--format tree`).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DirTree {
+    /// Directory name; the root holds the common path of all files.
+    pub name: String,
+    /// Totals over every file below this directory.
+    #[serde(flatten)]
+    pub summary: Summary,
+    /// Subdirectories, ordered by name.
+    pub children: Vec<DirTree>,
+}
+
+/// Totals of one directory subtree, as ranked by `--top-dirs`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DirStats {
+    /// Directory path, `/`-separated.
+    pub path: String,
+    /// Totals over every file below the directory.
+    #[serde(flatten)]
+    pub summary: Summary,
+}
+
+impl DirTree {
+    /// Builds the directory tree of `stats`.
+    ///
+    /// Leading directories shared by every file are folded into the root
+    /// name, so scanning `crates/engine` yields a `crates/engine` root rather
+    /// than a `.` → `crates` → `engine` chain.
+    #[must_use]
+    pub fn build(stats: &[FileStats]) -> Self {
+        #[derive(Default)]
+        struct Node {
+            summary: Summary,
+            children: BTreeMap<String, Node>,
+        }
+
+        impl Node {
+            fn into_tree(self, name: String) -> DirTree {
+                DirTree {
+                    name,
+                    summary: self.summary,
+                    children: self
+                        .children
+                        .into_iter()
+                        .map(|(name, node)| node.into_tree(name))
+                        .collect(),
+                }
+            }
+        }
+
+        let mut root = Node::default();
+        for s in stats {
+            root.summary.add(s);
+            let dirs = s.path.parent().into_iter().flat_map(|p| p.components());
+            let mut node = &mut root;
+            for component in dirs {
+                if matches!(component, Component::CurDir) {
+                    continue;
+                }
+                let name = component.as_os_str().to_string_lossy().into_owned();
+                node = node.children.entry(name).or_default();
+                node.summary.add(s);
+            }
+        }
+
+        let mut tree = root.into_tree(".".to_string());
+        while tree.children.len() == 1 && tree.direct_files() == 0 {
+            let child = tree.children.remove(0);
+            tree = Self {
+                name: if tree.name == "." {
+                    child.name
+                } else {
+                    format!("{}/{}", tree.name.trim_end_matches('/'), child.name)
+                },
+                ..child
+            };
+        }
+        tree
+    }
+
+    /// Ranks every directory below the root by total lines, largest first.
+    ///
+    /// Parents include their subdirectories, so a parent always ranks at
+    /// least as high as its children; ties are broken by path.
+    #[must_use]
+    pub fn top_dirs(&self, n: usize) -> Vec<DirStats> {
+        fn collect(node: &DirTree, parent: &str, out: &mut Vec<DirStats>) {
+            for child in &node.children {
+                let path = if parent.is_empty() {
+                    child.name.clone()
+                } else {
+                    format!("{}/{}", parent.trim_end_matches('/'), child.name)
+                };
+                out.push(DirStats {
+                    path: path.clone(),
+                    summary: child.summary.clone(),
+                });
+                collect(child, &path, out);
+            }
+        }
+
+        let mut dirs = Vec::new();
+        let root = if self.name == "." { "" } else { &self.name };
+        collect(self, root, &mut dirs);
+        dirs.sort_by(|a, b| {
+            b.summary
+                .lines
+                .cmp(&a.summary.lines)
+                .then_with(|| a.path.cmp(&b.path))
+        });
+        dirs.truncate(n);
+        dirs
+    }
+
+    /// Number of files directly in this directory (not in subdirectories).
+    #[must_use]
+    pub fn direct_files(&self) -> usize {
+        self.summary.files - self.children.iter().map(|c| c.summary.files).sum::<usize>()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use count_lines_core::language::LanguageDefinition;
+    use std::path::PathBuf;
+
+    fn file(path: &str, lines: usize) -> FileStats {
+        FileStats {
+            lines,
+            ..FileStats::new(PathBuf::from(path))
+        }
+    }
+
+    #[test]
+    fn test_group_by_language() {
+        let stats = [
+            file("a.rs", 10),
+            file("b.py", 30),
+            file("c.RS", 5),
+            file("Makefile", 2),
+            file("d.xyz", 1),
+        ];
+        let groups = group_stats(&stats, GroupBy::Language, &Config::default());
+        let rows: Vec<_> = groups
+            .iter()
+            .map(|g| (g.key.as_str(), g.summary.files, g.summary.lines))
+            .collect();
+        assert_eq!(
+            rows,
+            vec![
+                ("Python", 1, 30),
+                ("Rust", 2, 15),
+                ("Makefile", 1, 2),
+                ("Other", 1, 1)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_embedded_sections_roll_up_by_language() {
+        let section = |language: &str, lines, sloc| count_lines_core::stats::EmbeddedCount {
+            language: language.to_string(),
+            lines,
+            sloc,
+        };
+        let stats = [
+            FileStats {
+                sloc: Some(2),
+                ..file("a.js", 2)
+            },
+            FileStats {
+                sloc: Some(16),
+                embedded: vec![section("JavaScript", 8, 6), section("CSS", 4, 3)],
+                ..file("page.vue", 20)
+            },
+        ];
+        let groups = group_stats(&stats, GroupBy::Language, &Config::default());
+        let rows: Vec<_> = groups
+            .iter()
+            .map(|g| {
+                (
+                    g.key.as_str(),
+                    g.summary.files,
+                    g.summary.lines,
+                    g.summary.sloc,
+                )
+            })
+            .collect();
+        assert_eq!(
+            rows,
+            [
+                ("JavaScript", 1, 10, Some(8)),
+                ("Vue", 1, 8, Some(7)),
+                ("CSS", 0, 4, Some(3))
+            ]
+        );
+
+        // Other keys keep the whole file.
+        let groups = group_stats(&stats, GroupBy::Ext, &Config::default());
+        assert_eq!(groups[0].summary.lines, 20);
+    }
+
+    #[test]
+    fn test_group_by_ext() {
+        let stats = [file("a.rs", 1), file("b.RS", 1), file("LICENSE", 3)];
+        let groups = group_stats(&stats, GroupBy::Ext, &Config::default());
+        let keys: Vec<_> = groups.iter().map(|g| g.key.as_str()).collect();
+        assert_eq!(keys, vec![NO_EXTENSION, "rs"]);
+    }
+
+    #[test]
+    fn test_language_honours_mappings_and_definitions() {
+        let mut config = Config::default();
+        config
+            .filter
+            .map_ext
+            .insert("inc".to_string(), "php".to_string());
+        config.languages.push(LanguageDefinition {
+            name: "Foo".to_string(),
+            extensions: vec!["foo".to_string()],
+            ..LanguageDefinition::default()
+        });
+
+        assert_eq!(
+            group_key(&file("x.inc", 1), GroupBy::Language, &config),
+            "PHP"
+        );
+        assert_eq!(
+            group_key(&file("x.foo", 1), GroupBy::Language, &config),
+            "Foo"
+        );
+    }
+
+    #[test]
+    fn test_age_buckets() {
+        let buckets = Config::default_age_buckets();
+        assert_eq!(age_labels(&buckets), ["<1w", "1w-1mo", "1mo-6mo", ">=6mo"]);
+        assert_eq!(format_age(Duration::days(365)), "1y");
+        assert_eq!(format_age(Duration::hours(36)), "36h");
+
+        let now = Local::now();
+        let key = |days| age_key(Some(now - Duration::days(days)), now, &buckets);
+        assert_eq!(key(0), "<1w");
+        assert_eq!(key(7), "1w-1mo");
+        assert_eq!(key(90), "1mo-6mo");
+        assert_eq!(key(400), ">=6mo");
+        assert_eq!(age_key(None, now, &buckets), UNKNOWN_AGE);
+    }
+
+    #[test]
+    fn test_group_by_age_orders_youngest_first() {
+        let now = Local::now();
+        let aged = |path, lines, days| FileStats {
+            mtime: Some(now - Duration::days(days)),
+            ..file(path, lines)
+        };
+        let stats = [
+            aged("old.rs", 100, 1000),
+            file("stdin.rs", 50),
+            aged("new.rs", 1, 1),
+            aged("mid.rs", 10, 60),
+        ];
+        let groups = group_stats(&stats, GroupBy::Age, &Config::default());
+        let keys: Vec<_> = groups.iter().map(|g| g.key.as_str()).collect();
+        assert_eq!(keys, ["<1w", "1mo-6mo", ">=6mo", UNKNOWN_AGE]);
+    }
+
+    #[test]
+    fn test_root_dir() {
+        let roots = [PathBuf::from("."), PathBuf::from("./vendor")];
+        assert_eq!(
+            root_dir(Path::new("./crates/cli/src/main.rs"), &roots),
+            "crates"
+        );
+        assert_eq!(root_dir(Path::new("./vendor/zlib/zlib.c"), &roots), "zlib");
+        assert_eq!(root_dir(Path::new("./Cargo.toml"), &roots), ROOT_FILES);
+        assert_eq!(root_dir(Path::new("src/lib.rs"), &[]), "src");
+    }
+
+    #[test]
+    fn test_group_by_root() {
+        let stats = [
+            file("frontend/src/app.ts", 30),
+            file("backend/src/main.rs", 10),
+            file("backend/Cargo.toml", 5),
+            file("elsewhere/x.rs", 1),
+        ];
+        let config = Config {
+            walk: crate::config::WalkOptions {
+                roots: vec![PathBuf::from("frontend"), PathBuf::from("backend")],
+                ..Default::default()
+            },
+            ..Config::default()
+        };
+        let groups = group_stats(&stats, GroupBy::Root, &config);
+        let rows: Vec<_> = groups
+            .iter()
+            .map(|g| (g.key.as_str(), g.summary.lines))
+            .collect();
+        assert_eq!(rows, [("frontend", 30), ("backend", 15), (NO_ROOT, 1)]);
+    }
+
+    #[test]
+    fn test_group_by_crate() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::create_dir_all(root.join("app/src")).unwrap();
+        std::fs::write(root.join("app/Cargo.toml"), "[package]\nname = \"app\"\n").unwrap();
+        let stats = [
+            file(&root.join("app/src/main.rs").to_string_lossy(), 10),
+            file(&root.join("app/build.rs").to_string_lossy(), 2),
+            file(&root.join("notes.md").to_string_lossy(), 1),
+        ];
+        let groups = group_stats(&stats, GroupBy::Crate, &Config::default());
+        let rows: Vec<_> = groups
+            .iter()
+            .map(|g| (g.key.as_str(), g.summary.files))
+            .collect();
+        assert_eq!(rows, [("app", 2), (NO_CRATE, 1)]);
+    }
+
+    #[test]
+    fn test_group_by_owner() {
+        let dir = tempfile::tempdir().unwrap();
+        let codeowners = dir.path().join("CODEOWNERS");
+        std::fs::write(&codeowners, "*.rs @org/rust\n/web/ @org/web @bob\n").unwrap();
+        let path = |p: &str| dir.path().join(p).to_string_lossy().into_owned();
+        let stats = [
+            file(&path("src/lib.rs"), 10),
+            file(&path("web/app.js"), 5),
+            file(&path("web/index.html"), 5),
+            file(&path("README.md"), 1),
+        ];
+        let config = Config {
+            codeowners: Some(codeowners),
+            ..Config::default()
+        };
+        let groups = group_stats(&stats, GroupBy::Owner, &config);
+        let rows: Vec<_> = groups
+            .iter()
+            .map(|g| (g.key.as_str(), g.summary.lines))
+            .collect();
+        assert_eq!(
+            rows,
+            [("@org/rust", 10), ("@org/web @bob", 10), (UNOWNED, 1)]
+        );
+    }
+
+    #[test]
+    fn test_group_by_submodule() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join(".git")).unwrap();
+        std::fs::write(
+            dir.path().join(".gitmodules"),
+            "[submodule \"json\"]\n\tpath = vendor/json\n",
+        )
+        .unwrap();
+        let path = |p: &str| dir.path().join(p).to_string_lossy().into_owned();
+        let stats = [
+            file(&path("src/lib.rs"), 10),
+            file(&path("vendor/json/json.hpp"), 50),
+        ];
+        let config = Config {
+            walk: crate::config::WalkOptions {
+                roots: vec![dir.path().to_path_buf()],
+                ..Default::default()
+            },
+            ..Config::default()
+        };
+        let groups = group_stats(&stats, GroupBy::Submodule, &config);
+        let rows: Vec<_> = groups
+            .iter()
+            .map(|g| (g.key.as_str(), g.summary.lines))
+            .collect();
+        assert_eq!(rows, [("json", 50), (NO_SUBMODULE, 10)]);
+    }
+
+    #[test]
+    fn test_nested_groups() {
+        let stats = [
+            file("./src/main.rs", 10),
+            file("./src/util.py", 3),
+            file("./src/lib.rs", 5),
+            file("./tests/it.rs", 4),
+        ];
+        let config = Config {
+            walk: crate::config::WalkOptions {
+                roots: vec![PathBuf::from(".")],
+                ..Default::default()
+            },
+            ..Config::default()
+        };
+        let groups = nested_group_stats(&stats, &[GroupBy::RootDir, GroupBy::Ext], &config);
+        let src = &groups[0];
+        assert_eq!((src.key.as_str(), src.summary.lines), ("src", 18));
+        let rows: Vec<_> = src
+            .groups
+            .iter()
+            .map(|g| (g.key.as_str(), g.summary.files))
+            .collect();
+        assert_eq!(rows, [("rs", 2), ("py", 1)]);
+        assert_eq!(groups[1].groups[0].key, "rs");
+        assert!(groups[1].groups[0].groups.is_empty());
+    }
+
+    #[test]
+    fn test_group_filter() {
+        let stats = [
+            file("a.rs", 10),
+            file("b.rs", 5),
+            file("c.py", 30),
+            file("d.md", 2),
+        ];
+        let config = Config {
+            group_filter: vec!["lines > 10 || key == 'md'".parse().unwrap()],
+            ..Config::default()
+        };
+        let groups = group_stats(&stats, GroupBy::Ext, &config);
+        let keys: Vec<_> = groups.iter().map(|g| g.key.as_str()).collect();
+        assert_eq!(keys, ["py", "rs", "md"]);
+
+        // Unmeasured totals never match, as with --filter.
+        let config = Config {
+            group_filter: vec!["sloc >= 0".parse().unwrap()],
+            ..Config::default()
+        };
+        assert!(group_stats(&stats, GroupBy::Ext, &config).is_empty());
+    }
+
+    #[test]
+    fn test_ratios_of_total_and_group() {
+        let stats = [
+            FileStats {
+                sloc: Some(30),
+                ..file("./a/x.rs", 40)
+            },
+            FileStats {
+                sloc: Some(10),
+                ..file("./a/y.py", 10)
+            },
+            FileStats {
+                sloc: Some(60),
+                ..file("./b/z.rs", 60)
+            },
+        ];
+        let config = Config {
+            walk: crate::config::WalkOptions {
+                roots: vec![PathBuf::from(".")],
+                ..Default::default()
+            },
+            ..Config::default()
+        };
+        let by = [GroupBy::RootDir, GroupBy::Ext];
+        let total = Summary::from_stats(&stats);
+        let ratios = |groups: &[GroupStats]| -> Vec<_> {
+            groups
+                .iter()
+                .map(|g| {
+                    let r = g.ratio.unwrap();
+                    (g.key.clone(), r.percent, r.cumulative.unwrap())
+                })
+                .collect()
+        };
+
+        let mut groups = nested_group_stats(&stats, &by, &config);
+        apply_ratios(
+            &mut groups,
+            &by,
+            RatioMetric::Sloc,
+            RatioBase::Total,
+            &total,
+        );
+        assert_eq!(
+            ratios(&groups),
+            [
+                ("b".to_string(), 60.0, 60.0),
+                ("a".to_string(), 40.0, 100.0)
+            ]
+        );
+        assert_eq!(
+            ratios(&groups[1].groups),
+            [
+                ("rs".to_string(), 30.0, 30.0),
+                ("py".to_string(), 10.0, 40.0)
+            ]
+        );
+
+        apply_ratios(
+            &mut groups,
+            &by,
+            RatioMetric::Sloc,
+            RatioBase::Group,
+            &total,
+        );
+        assert_eq!(
+            ratios(&groups[1].groups),
+            [
+                ("rs".to_string(), 75.0, 75.0),
+                ("py".to_string(), 25.0, 100.0)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_dir_tree() {
+        let stats = [
+            file("./src/main.rs", 10),
+            file("./src/cli/args.rs", 5),
+            file("./src/cli/mod.rs", 1),
+            file("./build.rs", 2),
+        ];
+        let tree = DirTree::build(&stats);
+        assert_eq!(tree.name, ".");
+        assert_eq!(tree.summary.lines, 18);
+        assert_eq!(tree.direct_files(), 1);
+
+        let src = &tree.children[0];
+        assert_eq!((src.name.as_str(), src.summary.lines), ("src", 16));
+        let cli = &src.children[0];
+        assert_eq!((cli.name.as_str(), cli.summary.files), ("cli", 2));
+        assert!(cli.children.is_empty());
+    }
+
+    #[test]
+    fn test_dir_tree_folds_common_prefix() {
+        let stats = [
+            file("crates/engine/src/lib.rs", 3),
+            file("crates/engine/src/git/mod.rs", 4),
+        ];
+        let tree = DirTree::build(&stats);
+        assert_eq!(tree.name, "crates/engine/src");
+        assert_eq!(tree.children.len(), 1);
+        assert_eq!(tree.children[0].name, "git");
+    }
+
+    #[test]
+    fn test_top_dirs() {
+        let stats = [
+            file("crates/a/src/lib.rs", 10),
+            file("crates/a/src/deep/x.rs", 3),
+            file("crates/b/lib.rs", 20),
+        ];
+        let tree = DirTree::build(&stats);
+        let top: Vec<_> = tree
+            .top_dirs(3)
+            .into_iter()
+            .map(|d| (d.path, d.summary.lines))
+            .collect();
+        assert_eq!(
+            top,
+            vec![
+                ("crates/b".to_string(), 20),
+                ("crates/a".to_string(), 13),
+                ("crates/a/src".to_string(), 13),
+            ]
+        );
+    }
+}
@@ -0,0 +1,118 @@
+// crates/engine/src/io_limit.rs
+use std::path::Path;
+use std::sync::{Arc, Condvar, Mutex, PoisonError};
+
+/// Caps how many files are read at once (`--io-concurrency`), independently
+/// of the number of counting threads, so a run over an NFS or SMB mount
+/// doesn't flood the server.
+///
+/// Clones share the same permits. The default is unlimited.
+#[derive(Debug, Clone, Default)]
+pub struct IoLimit(Option<Arc<Permits>>);
+
+#[derive(Debug)]
+struct Permits {
+    limit: usize,
+    available: Mutex<usize>,
+    released: Condvar,
+}
+
+/// A read slot, given back when dropped.
+#[derive(Debug)]
+pub struct IoPermit<'a>(Option<&'a Permits>);
+
+impl IoLimit {
+    /// At most `limit` reads at a time; `None` (or 0) for no limit.
+    #[must_use]
+    pub fn new(limit: Option<usize>) -> Self {
+        Self(limit.filter(|&n| n > 0).map(|limit| {
+            Arc::new(Permits {
+                limit,
+                available: Mutex::new(limit),
+                released: Condvar::new(),
+            })
+        }))
+    }
+
+    /// The number of reads allowed at once, if limited.
+    #[must_use]
+    pub fn limit(&self) -> Option<usize> {
+        self.0.as_ref().map(|permits| permits.limit)
+    }
+
+    /// Waits for a free read slot.
+    #[must_use]
+    pub fn acquire(&self) -> IoPermit<'_> {
+        let Some(permits) = self.0.as_deref() else {
+            return IoPermit(None);
+        };
+        let mut available = permits
+            .available
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        while *available == 0 {
+            available = permits
+                .released
+                .wait(available)
+                .unwrap_or_else(PoisonError::into_inner);
+        }
+        *available -= 1;
+        IoPermit(Some(permits))
+    }
+
+    /// Reads the file at `path` within the limit.
+    ///
+    /// # Errors
+    /// Returns the error of [`std::fs::read`].
+    pub fn read(&self, path: &Path) -> std::io::Result<Vec<u8>> {
+        let _permit = self.acquire();
+        std::fs::read(path)
+    }
+}
+
+impl Drop for IoPermit<'_> {
+    fn drop(&mut self) {
+        if let Some(permits) = self.0 {
+            *permits
+                .available
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner) += 1;
+            permits.released.notify_one();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    #[test]
+    fn test_limits_concurrent_permits() {
+        let limit = IoLimit::new(Some(2));
+        assert_eq!(limit.limit(), Some(2));
+        let active = AtomicUsize::new(0);
+        let peak = AtomicUsize::new(0);
+        std::thread::scope(|scope| {
+            for _ in 0..8 {
+                scope.spawn(|| {
+                    let _permit = limit.acquire();
+                    let now = active.fetch_add(1, Ordering::SeqCst) + 1;
+                    peak.fetch_max(now, Ordering::SeqCst);
+                    std::thread::sleep(Duration::from_millis(10));
+                    active.fetch_sub(1, Ordering::SeqCst);
+                });
+            }
+        });
+        assert_eq!(peak.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn test_unlimited() {
+        let limit = IoLimit::new(None);
+        assert_eq!(limit.limit(), None);
+        let _permits: Vec<_> = (0..100).map(|_| limit.acquire()).collect();
+        assert_eq!(IoLimit::new(Some(0)).limit(), None);
+    }
+}
@@ -1,18 +1,57 @@
 // crates/engine/src/lib.rs
-use std::path::PathBuf;
+use rayon::iter::{ParallelBridge, ParallelIterator};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Instant;
 
+pub mod api;
+pub mod archive;
+pub mod attributes;
+pub mod blame;
+pub mod cache;
+pub mod cancel;
+pub mod codeowners;
 pub mod config;
+pub mod encoding;
 pub mod error;
+pub mod explain;
+pub mod expr;
 pub mod filesystem;
+pub mod git;
+pub mod grouping;
+pub mod io_limit;
+pub mod limits;
+pub mod manifest;
 pub mod options;
 pub mod path_security;
+pub mod plugin;
 pub mod processor;
+pub mod profile;
+pub mod progress;
+pub mod similarity;
 pub mod stats;
+pub mod submodules;
+pub mod test_code;
+#[cfg(any(test, feature = "testing"))]
+#[doc(hidden)]
+pub mod testing;
+pub mod timeout;
 pub mod watch;
+pub mod whitespace;
 
+pub use crate::api::{CountOutput, CountPaths, CountPathsBuilder};
+use crate::archive::ArchiveKind;
+use crate::attributes::LinguistAttributes;
+use crate::cache::StatsCache;
 use crate::config::Config;
 use crate::error::{EngineError, Result};
-use crate::stats::{FileStats, RunResult};
+use crate::limits::ScanBudget;
+use crate::options::{LimitAction, LinguistMode};
+use crate::progress::{NoProgress, Progress};
+use crate::stats::{CacheCounts, FileStats, RunResult};
+use crate::timeout::{Deadline, Workers};
 
 /// Run the file counting engine.
 ///
@@ -23,32 +62,139 @@ use crate::stats::{FileStats, RunResult};
 ///
 /// Returns an error only for critical failures (e.g., walk initialization).
 /// Individual file processing errors are collected in `RunResult::errors`.
+pub fn run(config: &Config) -> Result<RunResult> {
+    run_with_progress(config, Arc::new(NoProgress))
+}
+
+/// Like [`run`], reporting discovery and counting progress to `progress`.
 ///
-/// # Panics
+/// The walk runs on its own thread and queues files for a rayon pool that
+/// does the counting, so discovery can run ahead of measurement. Both queues
+/// hold at most `config.max_in_flight` entries, so a fast walk waits for
+/// the counting instead of buffering the whole tree.
 ///
-/// Panics if the partition results contain unexpected `Ok`/`Err` variants (should never happen).
-pub fn run(config: &Config) -> Result<RunResult> {
-    let (tx, rx) = crossbeam_channel::unbounded();
-    let (err_tx, err_rx) = std::sync::mpsc::channel();
+/// The `walk`, `measure` and `aggregate` phases each run in a `tracing`
+/// span of a `run` span, holding the file and error counts of the phase.
+///
+/// # Errors
+///
+/// Returns an error only for critical failures (e.g., walk initialization).
+pub fn run_with_progress(config: &Config, progress: Arc<dyn Progress>) -> Result<RunResult> {
+    let started_at = chrono::Local::now();
+    let started = Instant::now();
+    let run_span = tracing::info_span!("run");
+    let _run = run_span.enter();
+    let detect_before = config.detect_time.elapsed();
+    let capacity = config.max_in_flight.max(1);
+    let (path_tx, path_rx) = crossbeam_channel::bounded::<(PathBuf, std::fs::Metadata)>(capacity);
+    let (tx, rx) = crossbeam_channel::bounded(capacity);
+    let (walk_tx, walk_rx) = std::sync::mpsc::channel();
 
     let walk_cfg = config.walk.clone();
     let filter_cfg = config.filter.clone();
-    let config_inner = config.clone();
-
+    // Running into a scan limit stops the walk, but not the counting.
+    let cancel = config.cancel.child();
+    let deadline = Deadline::new(config);
+    let timer = deadline.arm(cancel.clone());
+    let budget = Arc::new(ScanBudget::new(config));
+    let walk_budget = budget.clone();
+    let walk_progress = progress.clone();
+    let walk_span = tracing::info_span!(parent: &run_span, "walk", files = tracing::field::Empty);
     std::thread::spawn(move || {
-        let tx = tx.clone();
+        let walk_span = walk_span.entered();
+        let started = Instant::now();
+        let progress = walk_progress.clone();
+        let found = Arc::new(AtomicUsize::new(0));
+        let counter = found.clone();
+        let stop = cancel.clone();
+        let walked =
+            crate::filesystem::walk_parallel(&walk_cfg, &filter_cfg, &cancel, move |path, meta| {
+                if !walk_budget.admit(meta.len()) {
+                    stop.cancel();
+                    return;
+                }
+                counter.fetch_add(1, Ordering::Relaxed);
+                progress.discovered(meta.len());
+                let _ = path_tx.send((path, meta));
+            });
+        let elapsed = started.elapsed();
+        walk_span.record("files", found.load(Ordering::Relaxed));
+        drop(walk_span);
+        let _ = walk_tx.send((walked, elapsed));
+        walk_progress.discovery_finished();
+    });
+
+    // Cached results don't carry the line fingerprints `--detect-similar` needs.
+    let cache = (config.incremental && config.detect_similar.is_none())
+        .then(|| Arc::new(StatsCache::load(config)));
+    let config_inner = Arc::new(config.clone());
+    let thread_cache = cache.clone();
+    let thread_deadline = deadline.clone();
+    let measure_span = tracing::info_span!(
+        parent: &run_span,
+        "measure",
+        files = tracing::field::Empty,
+        errors = tracing::field::Empty
+    );
+    let thread_span = measure_span.clone();
+    let measuring = std::thread::spawn(move || {
+        let span = thread_span;
+        let _entered = span.enter();
+        let started = Instant::now();
         let config = config_inner;
-        if let Err(e) =
-            crate::filesystem::walk_parallel(&walk_cfg, &filter_cfg, move |path, meta| {
-                let res = processor::process_file((path, meta), &config);
-                let _ = tx.send(res);
-            })
+        let cache = thread_cache;
+        let deadline = thread_deadline;
+        let linguist =
+            (config.walk.linguist == Some(LinguistMode::Tag)).then(LinguistAttributes::new);
+        let count = || {
+            // Counted on their own threads, to give up on files that block.
+            let workers = deadline
+                .file_limit()
+                .map(|_| Workers::new(rayon::current_num_threads(), &config, cache.as_ref()));
+            path_rx
+                .into_iter()
+                .par_bridge()
+                .for_each_with(tx, |tx, (path, meta)| {
+                    let _entered = span.enter();
+                    // Drain the queue without counting once cancelled.
+                    if config.cancel.is_cancelled() {
+                        return;
+                    }
+                    let size = meta.len();
+                    let class = linguist.as_ref().and_then(|l| l.classify(&path));
+                    let mut emit = |mut res: Result<FileStats>| {
+                        if let Ok(stats) = &mut res {
+                            stats.linguist = class;
+                        }
+                        let _ = tx.send(res);
+                    };
+                    if let Some(after) = deadline.timeout().filter(|_| deadline.expired()) {
+                        emit(Err(EngineError::Timeout { path, after }));
+                    } else if let (Some(workers), Some(limit)) = (&workers, deadline.file_limit()) {
+                        workers.process_entry_within(path, meta, limit, &mut emit);
+                    } else {
+                        process_entry(path, meta, &config, cache.as_deref(), &mut emit);
+                    }
+                    progress.processed(size);
+                });
+        };
+        match rayon::ThreadPoolBuilder::new()
+            .num_threads(config.walk.threads)
+            .build()
         {
-            let _ = err_tx.send(e);
+            Ok(pool) => pool.install(count),
+            Err(_) => count(),
         }
+        started.elapsed()
     });
 
     let mut result = RunResult::default();
+    for (path, e) in crate::filesystem::skipped_special_roots(&config.walk) {
+        if config.strict {
+            return Err(e);
+        }
+        result.errors.push((path, e));
+    }
 
     for res in rx {
         match res {
@@ -62,7 +208,9 @@ pub fn run(config: &Config) -> Result<RunResult> {
                     return Err(e);
                 }
                 let path = match &e {
-                    EngineError::FileRead { path, .. } => path.clone(),
+                    EngineError::FileRead { path, .. }
+                    | EngineError::Timeout { path, .. }
+                    | EngineError::Plugin { path, .. } => path.clone(),
                     _ => PathBuf::from("<unknown>"),
                 };
                 result.errors.push((path, e));
@@ -70,17 +218,193 @@ pub fn run(config: &Config) -> Result<RunResult> {
         }
     }
 
-    if let Ok(walk_err) = err_rx.try_recv() {
+    // The queue closes when the counting is done.
+    result.timings.measure = measuring.join().unwrap_or_default();
+    measure_span.record("files", result.stats.len());
+    measure_span.record("errors", result.errors.len());
+    drop(measure_span);
+    drop(timer);
+    result.timed_out = deadline.expired();
+    match walk_rx.try_recv() {
+        Ok((Ok(links), elapsed)) => {
+            result.links = links;
+            result.timings.walk = elapsed;
+        }
+        Ok((Err(walk_err), _)) => {
+            if config.strict {
+                return Err(walk_err);
+            }
+            result.errors.push((PathBuf::from("<walk>"), walk_err));
+        }
+        Err(_) => {}
+    }
+
+    if let Some(limit) = budget.exceeded() {
+        if config.on_limit == LimitAction::Abort {
+            return Err(EngineError::LimitExceeded(limit));
+        }
+        log::debug!("scan stopped at {limit}");
+        result.truncated = Some(limit);
+    }
+
+    let aggregating = Instant::now();
+    let aggregate_span = tracing::info_span!(
+        "aggregate",
+        files = tracing::field::Empty,
+        errors = tracing::field::Empty
+    );
+    let _aggregate = aggregate_span.enter();
+    result.partial = config.cancel.is_cancelled();
+    // A partial, truncated or timed out run would drop the entries of the
+    // files it didn't reach.
+    if let Some(cache) = &cache {
+        log::debug!("cache: {} hits, {} misses", cache.hits(), cache.misses());
+        result.cache = Some(CacheCounts {
+            hits: cache.hits(),
+            misses: cache.misses(),
+        });
+    }
+    if let Some(cache) =
+        cache.filter(|_| !result.partial && result.truncated.is_none() && !result.timed_out)
+        && let Err(e) = cache.save()
+    {
         if config.strict {
-            return Err(walk_err);
+            return Err(e);
         }
-        result.errors.push((PathBuf::from("<walk>"), walk_err));
+        result.errors.push((PathBuf::from("<cache>"), e));
     }
+    label_roots(&mut result, config);
+    split_minified(&mut result, config);
+    if let Some(threshold) = config.detect_similar {
+        result.similar = similarity::find_similar(&result.stats, f64::from(threshold));
+        for stats in &mut result.stats {
+            stats.line_hashes = Vec::new();
+        }
+    }
+    result.timings.aggregate = aggregating.elapsed();
+    result.timings.detect = config.detect_time.elapsed() - detect_before;
+    result.timings.total = started.elapsed();
+    result.started_at = Some(started_at);
+    aggregate_span.record("files", result.stats.len());
+    aggregate_span.record("errors", result.errors.len());
+    Ok(result)
+}
+
+/// Walks `config.walk.roots` with the path filters of `config` and returns
+/// the files that would be counted, sorted, without reading them.
+///
+/// Filters that need the counts (`--min-lines`, `--filter`, ...) are not
+/// applied; with `--scan-archives` archives are listed themselves.
+///
+/// # Errors
+///
+/// Returns an error if the walk cannot start (e.g. an unsafe root path).
+pub fn list_files(config: &Config) -> Result<Vec<PathBuf>> {
+    let files = Arc::new(std::sync::Mutex::new(Vec::new()));
+    let sink = files.clone();
+    crate::filesystem::walk_parallel(
+        &config.walk,
+        &config.filter,
+        &config.cancel,
+        move |path, _| {
+            sink.lock()
+                .unwrap_or_else(std::sync::PoisonError::into_inner)
+                .push(path);
+        },
+    )?;
+    let mut files = std::mem::take(
+        &mut *files
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner),
+    );
+    files.sort();
+    Ok(files)
+}
+
+/// Counts one walked file, or every member of it with `--scan-archives`.
+pub(crate) fn process_entry(
+    path: PathBuf,
+    meta: std::fs::Metadata,
+    config: &Config,
+    cache: Option<&StatsCache>,
+    emit: &mut dyn FnMut(Result<FileStats>),
+) {
+    let archive = ArchiveKind::from_path(&path).filter(|_| config.walk.scan_archives);
+    if let Some(kind) = archive {
+        // Archives are read as they are counted, so they keep their read
+        // slot until done.
+        let _permit = config.io_limit.acquire();
+        let mtime = meta.modified().ok().map(chrono::DateTime::from);
+        if let Err(e) = archive::scan_archive(&path, kind, mtime, config, emit) {
+            emit(Err(e));
+        }
+    } else if let Some(cache) = cache {
+        emit(cache.process_file(path, &meta, config));
+    } else {
+        emit(processor::process_file((path, meta), config));
+    }
+}
 
+/// Counts everything read from `reader` as a single file named `path`.
+///
+/// The extension of `path` selects the language, as for files on disk; the
+/// result filters still apply, so a filtered-out input yields no stats.
+///
+/// # Errors
+///
+/// Returns an error if reading from `reader` or the plugin configured for
+/// `path` fails.
+pub fn run_reader(reader: &mut dyn Read, path: &Path, config: &Config) -> Result<RunResult> {
+    let started_at = chrono::Local::now();
+    let started = Instant::now();
+    let mut content = Vec::new();
+    reader
+        .read_to_end(&mut content)
+        .map_err(|source| EngineError::FileRead {
+            path: path.to_path_buf(),
+            source,
+        })?;
+
+    let detect_before = config.detect_time.elapsed();
+    let stats = processor::count_content(path.to_path_buf(), &content, config)?;
+    let mut result = RunResult::default();
+    result.timings.detect = config.detect_time.elapsed() - detect_before;
+    if matches_result_filter(&stats, &config.filter) {
+        result.stats.push(stats);
+    }
+    split_minified(&mut result, config);
+    result.timings.measure = started.elapsed();
+    result.timings.total = result.timings.measure;
+    result.started_at = Some(started_at);
     Ok(result)
 }
 
-fn matches_result_filter(stats: &FileStats, filter: &crate::config::FilterConfig) -> bool {
+/// Records the scan root of each file when more than one root is scanned.
+fn label_roots(result: &mut RunResult, config: &Config) {
+    let roots = &config.walk.roots;
+    if roots.len() < 2 {
+        return;
+    }
+    for stats in &mut result.stats {
+        stats.root = grouping::root_of(&stats.path, roots).map(Path::to_path_buf);
+    }
+}
+
+/// Moves minified files into their own bucket unless `--include-minified`.
+fn split_minified(result: &mut RunResult, config: &Config) {
+    if !config.include_minified {
+        let (minified, stats) = std::mem::take(&mut result.stats)
+            .into_iter()
+            .partition(|s| s.minified);
+        result.stats = stats;
+        result.minified = minified;
+    }
+}
+
+pub(crate) fn matches_result_filter(
+    stats: &FileStats,
+    filter: &crate::config::FilterConfig,
+) -> bool {
     if filter.min_lines.is_some_and(|min| stats.lines < min) {
         return false;
     }
@@ -108,5 +432,141 @@ fn matches_result_filter(stats: &FileStats, filter: &crate::config::FilterConfig
         }
     }
 
-    true
+    filter.expressions.iter().all(|expr| expr.matches(stats))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::config::{Config, WalkOptions};
+    use crate::limits::ScanLimit;
+    use crate::options::LimitAction;
+
+    #[test]
+    fn test_run_with_single_slot_queues() {
+        let dir = tempfile::tempdir().unwrap();
+        for i in 0..50 {
+            std::fs::write(dir.path().join(format!("{i}.rs")), "a\nb\n").unwrap();
+        }
+        let config = Config {
+            walk: WalkOptions {
+                roots: vec![dir.path().to_path_buf()],
+                threads: 4,
+                ..WalkOptions::default()
+            },
+            max_in_flight: 1,
+            ..Config::default()
+        };
+
+        let result = crate::run(&config).unwrap();
+        assert_eq!(result.stats.len(), 50);
+        assert_eq!(result.summary().lines, 100);
+    }
+
+    #[test]
+    fn test_run_labels_roots_of_multi_root_scans() {
+        let dir = tempfile::tempdir().unwrap();
+        let (front, back) = (dir.path().join("frontend"), dir.path().join("backend"));
+        std::fs::create_dir(&front).unwrap();
+        std::fs::create_dir(&back).unwrap();
+        std::fs::write(front.join("app.ts"), "a\n").unwrap();
+        std::fs::write(back.join("main.rs"), "b\n").unwrap();
+        let config = |roots| Config {
+            walk: WalkOptions {
+                roots,
+                ..WalkOptions::default()
+            },
+            ..Config::default()
+        };
+
+        let result = crate::run(&config(vec![front.clone(), back.clone()])).unwrap();
+        let mut roots: Vec<_> = result
+            .stats
+            .iter()
+            .map(|s| (s.name.as_str(), s.root.clone()))
+            .collect();
+        roots.sort();
+        assert_eq!(
+            roots,
+            [
+                ("app.ts", Some(front.clone())),
+                ("main.rs", Some(back.clone()))
+            ]
+        );
+
+        // A single root is left unlabelled.
+        let single = crate::run(&config(vec![front])).unwrap();
+        assert_eq!(single.stats[0].root, None);
+    }
+
+    #[test]
+    fn test_run_reports_cache_counts() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("a.rs"), "a\n").unwrap();
+        std::fs::write(dir.path().join("b.rs"), "b\n").unwrap();
+        let config = Config {
+            walk: WalkOptions {
+                roots: vec![dir.path().to_path_buf()],
+                ..WalkOptions::default()
+            },
+            cache_dir: Some(dir.path().join(".cache")),
+            incremental: true,
+            ..Config::default()
+        };
+
+        let first = crate::run(&config).unwrap();
+        assert_eq!(first.cache.map(|c| (c.hits, c.misses)), Some((0, 2)));
+        assert!(first.timings.detect <= first.timings.total);
+        let second = crate::run(&config).unwrap();
+        assert_eq!(second.cache.map(|c| (c.hits, c.misses)), Some((2, 0)));
+
+        let uncached = crate::run(&Config {
+            incremental: false,
+            ..config
+        })
+        .unwrap();
+        assert_eq!(uncached.cache, None);
+    }
+
+    #[test]
+    fn test_run_scan_limits() {
+        let dir = tempfile::tempdir().unwrap();
+        for i in 0..5 {
+            std::fs::write(dir.path().join(format!("{i}.rs")), "fn f() {}\n").unwrap();
+        }
+        let config = Config {
+            walk: WalkOptions {
+                roots: vec![dir.path().to_path_buf()],
+                ..WalkOptions::default()
+            },
+            max_files: Some(3),
+            ..Config::default()
+        };
+
+        let err = crate::run(&config).unwrap_err();
+        assert!(matches!(
+            err,
+            crate::error::EngineError::LimitExceeded(ScanLimit::Files(3))
+        ));
+
+        let truncated = crate::run(&Config {
+            on_limit: LimitAction::Truncate,
+            ..config.clone()
+        })
+        .unwrap();
+        assert_eq!(truncated.stats.len(), 3);
+        assert_eq!(truncated.truncated, Some(ScanLimit::Files(3)));
+        assert!(!truncated.partial);
+        assert!(!config.cancel.is_cancelled());
+
+        let bytes = crate::run(&Config {
+            max_files: None,
+            max_total_bytes: Some(25),
+            ..config
+        })
+        .unwrap_err();
+        assert_eq!(
+            bytes.to_string(),
+            "Scan limit exceeded: --max-total-bytes 25"
+        );
+    }
 }
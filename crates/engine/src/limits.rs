@@ -0,0 +1,113 @@
+// crates/engine/src/limits.rs
+//! `--max-files` / `--max-total-bytes`: safety limits on how much a run
+//! walks, so a CI job pointed at `/` or a huge artifacts directory stops
+//! early instead of reading all of it.
+
+use crate::config::Config;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::sync::OnceLock;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+
+/// A scan limit a run ran into.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ScanLimit {
+    /// More files than `--max-files`.
+    Files(usize),
+    /// More bytes than `--max-total-bytes`.
+    TotalBytes(u64),
+}
+
+impl fmt::Display for ScanLimit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Files(max) => write!(f, "--max-files {max}"),
+            Self::TotalBytes(max) => write!(f, "--max-total-bytes {max}"),
+        }
+    }
+}
+
+/// The files and bytes a run may still walk, shared by the walk threads.
+#[derive(Debug, Default)]
+pub(crate) struct ScanBudget {
+    max_files: Option<usize>,
+    max_total_bytes: Option<u64>,
+    files: AtomicUsize,
+    bytes: AtomicU64,
+    exceeded: OnceLock<ScanLimit>,
+}
+
+impl ScanBudget {
+    pub(crate) fn new(config: &Config) -> Self {
+        Self {
+            max_files: config.max_files,
+            max_total_bytes: config.max_total_bytes,
+            ..Self::default()
+        }
+    }
+
+    /// Takes one file of `size` bytes from the budget. Returns false, and
+    /// records the limit, if the file doesn't fit; every later file is
+    /// refused too.
+    pub(crate) fn admit(&self, size: u64) -> bool {
+        if self.exceeded.get().is_some() {
+            return false;
+        }
+        let files = self.files.fetch_add(1, Ordering::Relaxed) + 1;
+        let bytes = self.bytes.fetch_add(size, Ordering::Relaxed) + size;
+        let limit = match (self.max_files, self.max_total_bytes) {
+            (Some(max), _) if files > max => ScanLimit::Files(max),
+            (_, Some(max)) if bytes > max => ScanLimit::TotalBytes(max),
+            _ => return true,
+        };
+        self.exceeded.get_or_init(|| limit);
+        false
+    }
+
+    /// The limit the run ran into, if any.
+    pub(crate) fn exceeded(&self) -> Option<ScanLimit> {
+        self.exceeded.get().copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_max_files() {
+        let budget = ScanBudget::new(&Config {
+            max_files: Some(2),
+            ..Config::default()
+        });
+        assert!(budget.admit(10));
+        assert!(budget.admit(10));
+        assert_eq!(budget.exceeded(), None);
+        assert!(!budget.admit(10));
+        assert_eq!(budget.exceeded(), Some(ScanLimit::Files(2)));
+    }
+
+    #[test]
+    fn test_max_total_bytes() {
+        let budget = ScanBudget::new(&Config {
+            max_total_bytes: Some(100),
+            ..Config::default()
+        });
+        assert!(budget.admit(60));
+        assert!(!budget.admit(60));
+        // Once exceeded, even a file that would fit is refused.
+        assert!(!budget.admit(1));
+        assert_eq!(budget.exceeded(), Some(ScanLimit::TotalBytes(100)));
+        assert_eq!(
+            ScanLimit::TotalBytes(100).to_string(),
+            "--max-total-bytes 100"
+        );
+    }
+
+    #[test]
+    fn test_unlimited() {
+        let budget = ScanBudget::new(&Config::default());
+        assert!((0..1000).all(|_| budget.admit(u64::MAX / 2000)));
+    }
+}
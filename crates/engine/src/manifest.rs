@@ -0,0 +1,110 @@
+// crates/engine/src/manifest.rs
+//! Cargo manifest lookup for `--by crate`.
+//!
+//! A file belongs to the crate of the nearest `Cargo.toml` above it that has
+//! a `[package]` table; virtual workspace manifests are skipped.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Finds the crate owning each file.
+///
+/// The answer is cached per directory, so one instance should be shared by
+/// every file of a run.
+#[derive(Debug, Default)]
+pub struct CrateIndex {
+    /// Package name per directory, `None` when no crate encloses it.
+    dirs: HashMap<PathBuf, Option<String>>,
+}
+
+impl CrateIndex {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the package name of the crate containing `path`.
+    pub fn crate_of(&mut self, path: &Path) -> Option<String> {
+        self.lookup(path.parent().unwrap_or_else(|| Path::new("")))
+    }
+
+    fn lookup(&mut self, dir: &Path) -> Option<String> {
+        if let Some(name) = self.dirs.get(dir) {
+            return name.clone();
+        }
+        let name = match std::fs::read_to_string(manifest_path(dir)) {
+            Ok(content) if package_name(&content).is_some() => package_name(&content),
+            _ => match parent(dir) {
+                Some(parent) => self.lookup(&parent),
+                None => None,
+            },
+        };
+        self.dirs.insert(dir.to_path_buf(), name.clone());
+        name
+    }
+}
+
+fn manifest_path(dir: &Path) -> PathBuf {
+    if dir.as_os_str().is_empty() {
+        PathBuf::from("Cargo.toml")
+    } else {
+        dir.join("Cargo.toml")
+    }
+}
+
+/// The directory above `dir`, resolving relative paths so the lookup can
+/// climb past the current directory.
+fn parent(dir: &Path) -> Option<PathBuf> {
+    match dir.parent() {
+        Some(parent) if !dir.as_os_str().is_empty() && dir != Path::new(".") => {
+            Some(parent.to_path_buf())
+        }
+        _ => std::path::absolute(dir)
+            .ok()?
+            .parent()
+            .map(Path::to_path_buf),
+    }
+}
+
+/// The `package.name` of a manifest, if it declares a package.
+fn package_name(manifest: &str) -> Option<String> {
+    let value: toml::Table = manifest.parse().ok()?;
+    value
+        .get("package")?
+        .get("name")?
+        .as_str()
+        .map(str::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_nearest_crate_skips_virtual_manifests() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::write(
+            root.join("Cargo.toml"),
+            "[workspace]\nmembers = [\"crates/*\"]\n",
+        )
+        .unwrap();
+        std::fs::create_dir_all(root.join("crates/core/src/nested")).unwrap();
+        std::fs::write(
+            root.join("crates/core/Cargo.toml"),
+            "[package]\nname = \"my-core\"\nversion = \"0.1.0\"\n",
+        )
+        .unwrap();
+
+        let mut index = CrateIndex::new();
+        assert_eq!(
+            index.crate_of(&root.join("crates/core/src/nested/a.rs")),
+            Some("my-core".to_string())
+        );
+        assert_eq!(
+            index.crate_of(&root.join("crates/core/build.rs")),
+            Some("my-core".to_string())
+        );
+        assert_eq!(index.crate_of(&root.join("README.md")), None);
+    }
+}
@@ -14,10 +14,18 @@ pub enum OutputFormat {
     Json,
     /// YAML format.
     Yaml,
-    /// Markdown table format.
+    /// Markdown table format, one row per file.
     Md,
+    /// Markdown summary table, one row per language (or `--by` group).
+    Markdown,
     /// JSON lines format.
     Jsonl,
+    /// Directory tree with per-directory totals.
+    Tree,
+    /// GitHub Actions workflow commands, shown as pull request annotations.
+    Github,
+    /// SARIF 2.1.0 log of policy violations, for code scanning dashboards.
+    Sarif,
 }
 
 /// Output format specifically for watch mode.
@@ -25,10 +33,241 @@ pub enum OutputFormat {
 pub enum WatchOutput {
     /// Full output updated per event.
     Full,
-    /// JSON lines output per event.
+    /// JSON lines output per event: changed files plus a per-cycle summary.
     Jsonl,
 }
 
+/// Output format for `--compare` and `snapshot diff`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CompareFormat {
+    /// Plain text summary and per-file table.
+    Table,
+    /// JSON document with summary, files and group deltas.
+    Json,
+    /// Markdown tables, e.g. for pull request comments.
+    Markdown,
+    /// GitHub Actions workflow commands, one annotation per changed file.
+    Github,
+}
+
+/// Ordering of the per-file rows in a comparison.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CompareSort {
+    /// Sort by path.
+    Path,
+    /// Sort by absolute line delta, largest first.
+    Delta,
+}
+
+/// How files are grouped into summary rows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GroupBy {
+    /// Group by language name (`Rust`, `Python`, ...).
+    Language,
+    /// Group by file extension.
+    Ext,
+    /// Group by `.gitattributes` Linguist class (vendored, documentation, source).
+    Linguist,
+    /// Group by time since last modification, bucketed by `Config::age_buckets`.
+    Age,
+    /// Group by the first directory below the scan root.
+    RootDir,
+    /// Group by the Cargo crate of the nearest `Cargo.toml` with a `[package]`.
+    Crate,
+    /// Group by the owners `CODEOWNERS` assigns.
+    Owner,
+    /// Group by the git submodule a file is in (`--include-submodules`).
+    Submodule,
+    /// Group by the scan root a file was found under.
+    Root,
+    /// Group the lines by the author `git blame` names for them.
+    Author,
+}
+
+/// A column of the per-file table and CSV/TSV output (`--columns`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Column {
+    Path,
+    Name,
+    Ext,
+    Lines,
+    Sloc,
+    Chars,
+    Words,
+    Size,
+    Mtime,
+    MaxLine,
+    AvgLine,
+    /// The file's share of the lines of every counted file, in percent.
+    Ratio,
+    /// The scan root the file was found under, in a multi-root scan.
+    Root,
+    /// Lines holding only documentation comments.
+    Doc,
+    /// Documentation lines per documentation and code line, in percent.
+    DocDensity,
+    /// Deepest indentation level of a line.
+    MaxDepth,
+    /// Average indentation level of the non-blank lines.
+    AvgDepth,
+}
+
+impl Column {
+    pub const ALL: [Self; 17] = [
+        Self::Path,
+        Self::Name,
+        Self::Ext,
+        Self::Lines,
+        Self::Sloc,
+        Self::Chars,
+        Self::Words,
+        Self::Size,
+        Self::Mtime,
+        Self::MaxLine,
+        Self::AvgLine,
+        Self::Ratio,
+        Self::Root,
+        Self::Doc,
+        Self::DocDensity,
+        Self::MaxDepth,
+        Self::AvgDepth,
+    ];
+
+    /// The CSV/TSV columns written when none are chosen.
+    pub const DEFAULT_SV: [Self; 7] = [
+        Self::Path,
+        Self::Lines,
+        Self::Sloc,
+        Self::Chars,
+        Self::Words,
+        Self::Size,
+        Self::Mtime,
+    ];
+
+    /// The columns of the per-file table and CSV/TSV output with `--docs`
+    /// and/or `--indent` when none are chosen.
+    #[must_use]
+    pub fn metric_defaults(docs: bool, indent: bool) -> Vec<Self> {
+        let mut columns = vec![Self::Lines];
+        if docs {
+            columns.extend([Self::Sloc, Self::Doc, Self::DocDensity]);
+        }
+        if indent {
+            columns.extend([Self::MaxDepth, Self::AvgDepth]);
+        }
+        columns.push(Self::Path);
+        columns
+    }
+
+    /// The name used on the command line and in CSV/TSV headers.
+    #[must_use]
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Path => "path",
+            Self::Name => "name",
+            Self::Ext => "ext",
+            Self::Lines => "lines",
+            Self::Sloc => "sloc",
+            Self::Chars => "chars",
+            Self::Words => "words",
+            Self::Size => "size",
+            Self::Mtime => "mtime",
+            Self::MaxLine => "maxline",
+            Self::AvgLine => "avgline",
+            Self::Ratio => "ratio",
+            Self::Root => "root",
+            Self::Doc => "doc",
+            Self::DocDensity => "doc%",
+            Self::MaxDepth => "maxdepth",
+            Self::AvgDepth => "avgdepth",
+        }
+    }
+
+    /// Whether the column holds text rather than a number.
+    #[must_use]
+    pub const fn is_text(self) -> bool {
+        matches!(
+            self,
+            Self::Path | Self::Name | Self::Ext | Self::Mtime | Self::Root
+        )
+    }
+}
+
+/// A total over the counted files: what a `--ratio-of` percentage is
+/// computed from, or the value shown by `--badge`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RatioMetric {
+    Files,
+    Lines,
+    Sloc,
+    Chars,
+    Words,
+    Size,
+}
+
+/// A shields.io endpoint badge (`--badge`).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Badge {
+    /// The total shown on the badge.
+    pub metric: RatioMetric,
+    /// Count only the files of this language, matched case-insensitively.
+    pub language: Option<String>,
+    /// `(minimum, color)` steps in ascending order: the last step the value
+    /// reaches picks the color.
+    pub colors: Vec<(u64, String)>,
+}
+
+/// What a run does when it exceeds `--max-files` or `--max-total-bytes`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LimitAction {
+    /// Fail the run.
+    #[default]
+    Abort,
+    /// Stop walking and report the files counted so far.
+    Truncate,
+}
+
+/// What a group's `--ratio-of` percentage is relative to.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RatioBase {
+    /// The grand total over every file.
+    #[default]
+    Total,
+    /// The enclosing group, for the inner levels of a nested `--by`.
+    Group,
+}
+
+/// Colors of the table and tree outputs (`--theme`).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Theme {
+    /// Bright colors for dark terminal backgrounds.
+    #[default]
+    Default,
+    /// Darker colors readable on light backgrounds.
+    Light,
+    /// Bold, underline and reverse video only, no colors.
+    Mono,
+}
+
+/// What `--linguist` does with vendored and documentation files.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LinguistMode {
+    /// Leave them out of the walk.
+    Exclude,
+    /// Count them and record their class in `FileStats::linguist`.
+    Tag,
+}
+
+/// How `--incremental` decides that a cached file is unchanged.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CacheKey {
+    /// Same size and modification time; no read needed.
+    #[default]
+    Mtime,
+    /// Same content hash; catches rewrites that keep the mtime.
+    Hash,
+}
+
 /// Keys to sort the resulting statistics by.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum SortKey {
@@ -46,4 +285,16 @@ pub enum SortKey {
     Ext,
     /// SLOC (Source Lines of Code)
     Sloc,
+    /// Longest line length
+    MaxLine,
+    /// Average line length
+    AvgLine,
+    /// Sort by the full path.
+    Path,
+    /// Share of the total line count; orders like [`Self::Lines`].
+    Ratio,
+    /// Deepest indentation level
+    MaxDepth,
+    /// Average indentation level
+    AvgDepth,
 }
@@ -0,0 +1,333 @@
+// crates/engine/src/plugin.rs
+//! `--plugin`: counts the files of some extensions with an external
+//! executable, for formats the built-in languages don't know.
+//!
+//! The plugin runs once per file, with the file's path as its only argument
+//! and the file's raw content on stdin, and prints one JSON object on
+//! stdout:
+//!
+//! ```json
+//! {"lines": 120, "sloc": 80, "doc_lines": 12, "words": 900, "chars": 4000}
+//! ```
+//!
+//! `lines` is required. Each other count it prints replaces the built-in
+//! one, if that count is enabled for the run; the rest keep their built-in
+//! values. A plugin that fails to start, exits non-zero, prints anything
+//! else or runs past its time limit fails the file with
+//! [`EngineError::Plugin`].
+
+use crate::config::Config;
+use crate::error::{EngineError, Result};
+use crate::stats::FileStats;
+use crossbeam_channel::{Receiver, RecvTimeoutError};
+use serde::{Deserialize, Serialize};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+
+/// How often a running plugin is checked for having exited.
+const POLL_INTERVAL: Duration = Duration::from_millis(2);
+
+/// An external counter and the extensions it counts.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Plugin {
+    /// Lowercase extensions, without the leading dot.
+    pub extensions: Vec<String>,
+    pub command: PathBuf,
+}
+
+impl Plugin {
+    /// Whether this plugin counts files with extension `ext`.
+    #[must_use]
+    pub fn handles(&self, ext: &str) -> bool {
+        self.extensions.iter().any(|e| e.eq_ignore_ascii_case(ext))
+    }
+}
+
+/// The limits of each plugin invocation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PluginLimits {
+    /// The plugin is killed after this long (`--plugin-timeout`).
+    pub timeout: Duration,
+    /// Caps the plugin's address space, in bytes (`--plugin-memory`).
+    /// Applied through `ulimit -v`, so it is ignored outside Unix.
+    pub memory: Option<u64>,
+}
+
+impl PluginLimits {
+    /// Default of [`Self::timeout`]: 10 seconds.
+    pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
+}
+
+impl Default for PluginLimits {
+    fn default() -> Self {
+        Self {
+            timeout: Self::DEFAULT_TIMEOUT,
+            memory: None,
+        }
+    }
+}
+
+/// The counts a plugin prints for one file.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PluginCounts {
+    pub lines: usize,
+    #[serde(default)]
+    pub sloc: Option<usize>,
+    #[serde(default)]
+    pub doc_lines: Option<usize>,
+    #[serde(default)]
+    pub words: Option<usize>,
+    #[serde(default)]
+    pub chars: Option<usize>,
+}
+
+impl PluginCounts {
+    /// Replaces the counts of `stats` with these. Counts the run doesn't
+    /// collect (e.g. words without `--words`) stay unset.
+    pub fn apply(&self, stats: &mut FileStats) {
+        fn replace(count: &mut Option<usize>, value: Option<usize>) {
+            if let (Some(count), Some(value)) = (count.as_mut(), value) {
+                *count = value;
+            }
+        }
+        stats.lines = self.lines;
+        stats.chars = self.chars.unwrap_or(stats.chars);
+        replace(&mut stats.sloc, self.sloc);
+        replace(&mut stats.doc_lines, self.doc_lines);
+        replace(&mut stats.words, self.words);
+        // The plugin knows the format, even one that looks binary.
+        stats.is_binary = false;
+    }
+}
+
+/// The first of `plugins` that counts files with extension `ext`.
+#[must_use]
+pub fn plugin_for<'a>(plugins: &'a [Plugin], ext: &str) -> Option<&'a Plugin> {
+    plugins.iter().find(|p| p.handles(ext))
+}
+
+/// Recounts `stats` with the plugin configured for its extension, if any,
+/// feeding it `content`.
+///
+/// # Errors
+/// Returns [`EngineError::Plugin`] if the plugin fails.
+pub fn apply(stats: &mut FileStats, content: &[u8], config: &Config) -> Result<()> {
+    let Some(plugin) = plugin_for(&config.plugins, &stats.ext) else {
+        return Ok(());
+    };
+    let counts = run(plugin, &stats.path, content, config.plugin_limits).map_err(|message| {
+        EngineError::Plugin {
+            path: stats.path.clone(),
+            message,
+        }
+    })?;
+    counts.apply(stats);
+    Ok(())
+}
+
+/// Runs `plugin` on the file at `path` with `content` on stdin, within
+/// `limits`, and parses what it prints.
+///
+/// # Errors
+/// Returns a description of the failure if the plugin can't be started,
+/// runs out of time, exits non-zero or prints invalid counts.
+pub fn run(
+    plugin: &Plugin,
+    path: &Path,
+    content: &[u8],
+    limits: PluginLimits,
+) -> std::result::Result<PluginCounts, String> {
+    let deadline = Instant::now() + limits.timeout;
+    let timed_out = || format!("timed out after {:?}", limits.timeout);
+    let mut child = command(plugin, path, limits)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|err| format!("cannot run '{}': {err}", plugin.command.display()))?;
+    log::debug!("plugin: {} {}", plugin.command.display(), path.display());
+
+    let mut stdin = child.stdin.take().expect("stdin is piped");
+    let input = content.to_vec();
+    // A plugin may exit without reading all of its input; the broken pipe
+    // is not an error.
+    std::thread::spawn(move || stdin.write_all(&input));
+    let stdout = read_all(child.stdout.take().expect("stdout is piped"));
+    let stderr = read_all(child.stderr.take().expect("stderr is piped"));
+
+    let status = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break status,
+            Ok(None) if Instant::now() < deadline => std::thread::sleep(POLL_INTERVAL),
+            Ok(None) => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(timed_out());
+            }
+            Err(err) => return Err(err.to_string()),
+        }
+    };
+    // A process the plugin left running may still hold the pipes open.
+    let output = |rx: Receiver<Vec<u8>>| match rx.recv_deadline(deadline) {
+        Ok(bytes) => Ok(bytes),
+        Err(RecvTimeoutError::Timeout) => Err(timed_out()),
+        Err(RecvTimeoutError::Disconnected) => Ok(Vec::new()),
+    };
+    if !status.success() {
+        let stderr = output(stderr)?;
+        let stderr = String::from_utf8_lossy(&stderr);
+        return Err(match stderr.trim().lines().last() {
+            Some(last) => format!("exited with {status}: {last}"),
+            None => format!("exited with {status}"),
+        });
+    }
+    serde_json::from_slice(&output(stdout)?).map_err(|err| format!("invalid output: {err}"))
+}
+
+/// The command running `plugin` on `path`: under `sh` with `ulimit -v` when
+/// the memory is limited.
+fn command(plugin: &Plugin, path: &Path, limits: PluginLimits) -> Command {
+    let mut command = match limits.memory {
+        #[cfg(unix)]
+        Some(bytes) => {
+            let mut command = Command::new("sh");
+            command
+                .args(["-c", r#"ulimit -v "$1" && shift && exec "$@""#, "sh"])
+                .arg((bytes / 1024).max(1).to_string())
+                .arg(&plugin.command);
+            command
+        }
+        _ => Command::new(&plugin.command),
+    };
+    command.arg(path);
+    command
+}
+
+/// Reads `pipe` to the end on its own thread.
+fn read_all(mut pipe: impl Read + Send + 'static) -> Receiver<Vec<u8>> {
+    let (tx, rx) = crossbeam_channel::bounded(1);
+    std::thread::spawn(move || {
+        let mut bytes = Vec::new();
+        if pipe.read_to_end(&mut bytes).is_ok() {
+            let _ = tx.send(bytes);
+        }
+    });
+    rx
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use crate::config::WalkOptions;
+    use std::os::unix::fs::PermissionsExt;
+
+    fn script(dir: &Path, name: &str, body: &str) -> PathBuf {
+        let path = dir.join(name);
+        std::fs::write(&path, format!("#!/bin/sh\n{body}\n")).unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+        path
+    }
+
+    fn plugin(command: PathBuf) -> Plugin {
+        Plugin {
+            extensions: vec!["dat".to_string()],
+            command,
+        }
+    }
+
+    #[test]
+    fn test_plugin_replaces_counts() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        let bin = tempfile::tempdir().unwrap();
+        // Counts the records of the input: one per `;`.
+        let counter = script(
+            bin.path(),
+            "counter",
+            r#"n=$(tr -cd ';' | wc -c); echo "{\"lines\": $n, \"sloc\": $n, \"words\": 99}""#,
+        );
+        std::fs::write(root.join("a.DAT"), "a;b;c;").unwrap();
+        std::fs::write(root.join("b.txt"), "a;b;c;").unwrap();
+
+        let config = Config {
+            walk: WalkOptions {
+                roots: vec![root.to_path_buf()],
+                ..WalkOptions::default()
+            },
+            count_sloc: true,
+            plugins: vec![plugin(counter)],
+            ..Config::default()
+        };
+        let mut stats = crate::run(&config).unwrap().stats;
+        stats.sort_by(|a, b| a.path.cmp(&b.path));
+        let rows: Vec<_> = stats
+            .iter()
+            .map(|s| (s.name.as_str(), s.lines, s.sloc, s.words, s.chars))
+            .collect();
+        assert_eq!(
+            rows,
+            [
+                ("a.DAT", 3, Some(3), None, 6),
+                ("b.txt", 1, Some(1), None, 6)
+            ]
+        );
+
+        // Git blobs, archive members and daemon queries are counted by the
+        // plugin too.
+        let stats = crate::processor::count_content(PathBuf::from("c.dat"), b"a;b;", &config);
+        assert_eq!(stats.map(|s| (s.lines, s.sloc)).ok(), Some((2, Some(2))));
+
+        // A failing plugin is reported against the file it was counting.
+        let config = Config {
+            plugins: vec![plugin(bin.path().join("missing"))],
+            ..config
+        };
+        let result = crate::run(&config).unwrap();
+        assert_eq!(result.stats.len(), 1);
+        assert_eq!(result.errors.len(), 1);
+        assert_eq!(result.errors[0].0, root.join("a.DAT"));
+        assert_eq!(result.errors[0].1.kind(), "plugin");
+    }
+
+    #[test]
+    fn test_plugin_failures() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        let limits = PluginLimits {
+            timeout: Duration::from_millis(300),
+            memory: None,
+        };
+        let fail = |body: &str| {
+            let command = script(root, "plugin", body);
+            run(&plugin(command), &root.join("a.dat"), b"x\n", limits).unwrap_err()
+        };
+
+        assert_eq!(
+            fail("echo broken >&2; exit 3"),
+            "exited with exit status: 3: broken"
+        );
+        assert!(fail("echo '{\"sloc\": 1}'").starts_with("invalid output: "));
+        let started = Instant::now();
+        assert_eq!(fail("sleep 5"), "timed out after 300ms");
+        assert!(started.elapsed() < Duration::from_secs(4));
+
+        let missing = plugin(root.join("missing"));
+        let err = run(&missing, &root.join("a.dat"), b"", limits).unwrap_err();
+        assert!(err.starts_with("cannot run "), "{err}");
+    }
+
+    #[test]
+    fn test_plugin_memory_limit() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        let command = script(root, "plugin", r#"echo "{\"lines\": $(ulimit -v)}""#);
+        let limits = PluginLimits {
+            memory: Some(512 * 1024 * 1024),
+            ..PluginLimits::default()
+        };
+        let counts = run(&plugin(command), &root.join("a.dat"), b"", limits).unwrap();
+        assert_eq!(counts.lines, 512 * 1024);
+    }
+}
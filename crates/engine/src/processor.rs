@@ -1,28 +1,96 @@
 // crates/engine/src/processor.rs
 use crate::config::Config;
+use crate::encoding::{Bom, decode, is_invalid_utf8};
 use crate::error::{EngineError, Result};
+use crate::plugin;
 use crate::stats::FileStats;
 use count_lines_core::config::AnalysisConfig;
-use count_lines_core::counter::count_bytes;
+use count_lines_core::counter::{
+    LineTally, count_bytes, is_binary, processor_for_content, split_at_lines, tally_lines,
+};
+use count_lines_core::fingerprint::line_fingerprints;
+use count_lines_core::stats::AnalysisResult;
+use rayon::prelude::*;
 use std::path::PathBuf;
 
+/// Average line length above which a JS/CSS file counts as minified.
+pub const MINIFIED_AVG_LINE_LENGTH: f64 = 300.0;
+/// A single-line JS/CSS file larger than this counts as minified.
+pub const MINIFIED_SINGLE_LINE_SIZE: u64 = 10 * 1024;
+
+const MINIFIABLE_EXTENSIONS: &[&str] = &["js", "mjs", "cjs", "css"];
+
+/// Number of leading lines searched for `--license-header`.
+pub const LICENSE_HEADER_LINES: usize = 20;
+
+/// Heuristic for minified JS/CSS: a `.min.js` / `.min.css` name, very long
+/// lines on average, or a single line over [`MINIFIED_SINGLE_LINE_SIZE`].
+#[must_use]
+pub fn is_minified(stats: &FileStats) -> bool {
+    let ext = stats.ext.to_ascii_lowercase();
+    if stats.is_binary || !MINIFIABLE_EXTENSIONS.contains(&ext.as_str()) {
+        return false;
+    }
+    let name = stats.name.to_ascii_lowercase();
+    name.ends_with(&format!(".min.{ext}"))
+        || stats.avg_line_length > MINIFIED_AVG_LINE_LENGTH
+        || (stats.lines <= 1 && stats.size > MINIFIED_SINGLE_LINE_SIZE)
+}
+
 pub fn process_file(
     (path, meta): (PathBuf, std::fs::Metadata),
     config: &Config,
 ) -> Result<FileStats> {
-    let mut stats = FileStats::new(path.clone());
+    let content = config
+        .io_limit
+        .read(&path)
+        .map_err(|source| EngineError::FileRead {
+            path: path.clone(),
+            source,
+        })?;
+    process_content(path, &content, &meta, config)
+}
+
+/// Counts the already read `content` of the file at `path`, taking `size`
+/// and `mtime` from its metadata.
+///
+/// # Errors
+/// Returns an error if the plugin configured for the file fails.
+pub fn process_content(
+    path: PathBuf,
+    content: &[u8],
+    meta: &std::fs::Metadata,
+    config: &Config,
+) -> Result<FileStats> {
+    let mut stats = count_content(path, content, config)?;
     stats.size = meta.len();
     stats.mtime = meta
         .modified()
         .ok()
         .map(chrono::DateTime::<chrono::Local>::from);
+    Ok(stats)
+}
 
-    let content = std::fs::read(&path).map_err(|source| EngineError::FileRead {
-        path: path.clone(),
-        source,
-    })?;
+/// Counts in-memory file content, e.g. a blob read from a git revision,
+/// with the plugin configured for its extension, if any.
+///
+/// `size` is taken from the content length and `mtime` is left unset.
+///
+/// # Errors
+/// Returns [`EngineError::Plugin`] if the plugin fails.
+pub fn count_content(path: PathBuf, content: &[u8], config: &Config) -> Result<FileStats> {
+    let mut stats = count_builtin(path, content, config);
+    plugin::apply(&mut stats, content, config)?;
+    Ok(stats)
+}
+
+/// Counts `content` with the built-in language rules only.
+fn count_builtin(path: PathBuf, content: &[u8], config: &Config) -> FileStats {
+    let mut stats = FileStats::new(path);
+    stats.size = content.len() as u64;
 
-    let extension = path
+    let extension = stats
+        .path
         .extension()
         .and_then(|value| value.to_str())
         .unwrap_or("");
@@ -31,8 +99,26 @@ pub fn process_file(
         count_sloc: config.count_sloc,
         count_newlines_in_chars: config.count_newlines_in_chars,
         map_ext: config.filter.map_ext.clone(),
+        languages: config.languages.clone(),
+        detect_modeline: config.detect_modeline,
+        count_indent: config.count_indent,
+        tab_width: config.tab_width,
+        audit_whitespace: config.audit_whitespace,
+        long_line_threshold: usize::try_from(config.long_line_threshold).unwrap_or(usize::MAX),
+    };
+    let (bom, invalid_utf8) = if config.audit_encoding {
+        (Bom::detect(content), is_invalid_utf8(content, extension))
+    } else {
+        (None, false)
+    };
+    let decoded = config.detect_time.time(|| decode(content, config.encoding));
+    let content = &decoded.content[..];
+    let analysis = match config.chunk_size {
+        Some(size) if size > 0 && content.len() as u64 > size => {
+            count_chunked(content, extension, &analysis_config, size)
+        }
+        _ => count_bytes(content, extension, &analysis_config),
     };
-    let analysis = count_bytes(&content, extension, &analysis_config);
 
     stats.lines = analysis.lines;
     stats.chars = analysis.chars;
@@ -42,9 +128,84 @@ pub fn process_file(
     } else {
         None
     };
+    stats.doc_lines = config.count_docs.then_some(analysis.doc_lines);
+    stats.max_line_length = analysis.max_line_length;
+    stats.avg_line_length = analysis.avg_line_length;
+    if config.count_indent {
+        stats.max_depth = Some(analysis.max_indent_depth);
+        stats.avg_depth = Some(analysis.avg_indent_depth);
+    }
+    stats.whitespace = analysis.whitespace;
+    stats.long_lines = analysis.long_lines;
+    stats.bom = bom;
+    stats.invalid_utf8 = invalid_utf8;
     stats.is_binary = analysis.is_binary;
+    stats.encoding = decoded.encoding.map(str::to_string);
+    stats.embedded = analysis.embedded;
+    stats.minified = is_minified(&stats);
+    stats.missing_license_header = !stats.is_binary
+        && config
+            .license_header
+            .as_deref()
+            .is_some_and(|header| !has_license_header(content, header));
+    if stats.minified && !config.include_minified {
+        stats.sloc = stats.sloc.map(|_| 0);
+    }
+    if config.detect_similar.is_some() && !stats.is_binary {
+        stats.line_hashes = line_fingerprints(content, extension, &analysis_config);
+    }
 
-    Ok(stats)
+    stats
+}
+
+/// Whether one of the first [`LICENSE_HEADER_LINES`] lines of `content`
+/// contains `header`.
+fn has_license_header(content: &[u8], header: &str) -> bool {
+    let header = header.as_bytes();
+    header.is_empty()
+        || content
+            .split(|&b| b == b'\n')
+            .take(LICENSE_HEADER_LINES)
+            .any(|line| line.windows(header.len()).any(|w| w == header))
+}
+
+/// Counts `content` in chunks of about `chunk_size` bytes on the rayon pool.
+///
+/// Each chunk starts from a fresh processor. A chunk that follows one ending
+/// inside a block comment or string is counted again, serially, from where
+/// the previous chunk left off; languages whose processors can't tell are
+/// counted serially from the start.
+fn count_chunked(
+    content: &[u8],
+    extension: &str,
+    config: &AnalysisConfig,
+    chunk_size: u64,
+) -> AnalysisResult {
+    if is_binary(content) || !processor_for_content(content, extension, config).is_neutral() {
+        return count_bytes(content, extension, config);
+    }
+    let chunks = split_at_lines(content, usize::try_from(chunk_size).unwrap_or(usize::MAX));
+    let counted: Vec<_> = chunks
+        .par_iter()
+        .map(|chunk| {
+            let mut processor = processor_for_content(content, extension, config);
+            (tally_lines(chunk, &mut processor, config), processor)
+        })
+        .collect();
+
+    let mut total = LineTally::default();
+    let mut carried = None;
+    for (chunk, (tally, processor)) in chunks.iter().zip(counted) {
+        let (tally, processor) = match carried.take() {
+            Some(mut previous) => (tally_lines(chunk, &mut previous, config), previous),
+            None => (tally, processor),
+        };
+        total.merge(&tally);
+        if !processor.is_neutral() {
+            carried = Some(processor);
+        }
+    }
+    total.into_result(config)
 }
 
 #[cfg(test)]
@@ -98,6 +259,133 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_count_docs() {
+        let content = b"/// Adds.\nfn add() {}\n// plain\n";
+        let mut config = Config {
+            count_sloc: true,
+            ..Config::default()
+        };
+        let stats = count_builtin(PathBuf::from("a.rs"), content, &config);
+        assert_eq!(stats.doc_lines, None);
+
+        config.count_docs = true;
+        let stats = count_builtin(PathBuf::from("a.rs"), content, &config);
+        assert_eq!((stats.sloc, stats.doc_lines), (Some(1), Some(1)));
+        assert_eq!(stats.doc_density(), Some(50.0));
+    }
+
+    #[test]
+    fn test_audit_encoding() {
+        let content = b"\xEF\xBB\xBFlet s = \"caf\xE9\";\n";
+        let mut config = Config::default();
+        let stats = count_builtin(PathBuf::from("a.rs"), content, &config);
+        assert_eq!((stats.bom, stats.invalid_utf8), (None, false));
+
+        config.audit_encoding = true;
+        let stats = count_builtin(PathBuf::from("a.rs"), content, &config);
+        assert_eq!((stats.bom, stats.invalid_utf8), (Some(Bom::Utf8), true));
+        let stats = count_builtin(PathBuf::from("b.txt"), b"plain\n", &config);
+        assert_eq!((stats.bom, stats.invalid_utf8), (None, false));
+    }
+
+    #[test]
+    fn test_license_header() {
+        let mut config = Config::default();
+        let stats = count_builtin(PathBuf::from("a.rs"), b"fn a() {}\n", &config);
+        assert!(!stats.missing_license_header);
+
+        config.license_header = Some("SPDX-License-Identifier".to_string());
+        let stats = count_builtin(PathBuf::from("a.rs"), b"fn a() {}\n", &config);
+        assert!(stats.missing_license_header);
+        let content = b"// SPDX-License-Identifier: MIT\nfn a() {}\n";
+        let stats = count_builtin(PathBuf::from("a.rs"), content, &config);
+        assert!(!stats.missing_license_header);
+        let mut late = "\n".repeat(LICENSE_HEADER_LINES).into_bytes();
+        late.extend_from_slice(content);
+        let stats = count_builtin(PathBuf::from("a.rs"), &late, &config);
+        assert!(stats.missing_license_header);
+    }
+
+    #[test]
+    fn test_long_line_guard() {
+        let mut content = b"fn a() {}\n// ".to_vec();
+        content.extend(std::iter::repeat_n(b'x', 100));
+        content.push(b'\n');
+        let mut config = Config {
+            count_sloc: true,
+            ..Config::default()
+        };
+        let stats = count_builtin(PathBuf::from("a.rs"), &content, &config);
+        assert_eq!((stats.sloc, stats.long_lines), (Some(1), 0));
+
+        config.long_line_threshold = 64;
+        let stats = count_builtin(PathBuf::from("a.rs"), &content, &config);
+        assert_eq!((stats.sloc, stats.long_lines), (Some(1), 1));
+        assert_eq!(stats.max_line_length, 103);
+        assert!(stats.has_long_lines());
+    }
+
+    #[test]
+    fn test_count_indent() {
+        let content = b"fn a() {\n\tif x {\n\t\ty();\n\t}\n}\n";
+        let mut config = Config::default();
+        let stats = count_builtin(PathBuf::from("a.rs"), content, &config);
+        assert_eq!((stats.max_depth, stats.avg_depth), (None, None));
+
+        config.count_indent = true;
+        config.tab_width = 2;
+        let stats = count_builtin(PathBuf::from("a.rs"), content, &config);
+        assert_eq!(stats.max_depth, Some(2));
+        assert_eq!(stats.field("avgdepth"), Some(0.8));
+    }
+
+    #[test]
+    fn test_custom_language_definition() -> std::result::Result<(), Box<dyn std::error::Error>> {
+        use count_lines_core::language::LanguageDefinition;
+
+        let mut file = tempfile::Builder::new().suffix(".dm").tempfile()?;
+        write!(file, "x = 1\n-- comment\n{{- block\nstill -}}\ny\n")?;
+        let path = file.path().to_path_buf();
+
+        let config = Config {
+            count_sloc: true,
+            languages: vec![LanguageDefinition {
+                name: "demo".to_string(),
+                extensions: vec!["dm".to_string()],
+                line_comments: vec!["--".to_string()],
+                block_comments: vec![("{-".to_string(), "-}".to_string())],
+                ..LanguageDefinition::default()
+            }],
+            ..Config::default()
+        };
+
+        let stats = process_file((path.clone(), std::fs::metadata(&path)?), &config)?;
+        assert_eq!(stats.lines, 5);
+        assert_eq!(stats.sloc, Some(2));
+        Ok(())
+    }
+
+    #[test]
+    fn test_detect_modeline_for_extensionless_file()
+    -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let mut file = NamedTempFile::new()?;
+        write!(file, "# -*- mode: python -*-\n\"\"\"doc\"\"\"\nx = 1\n")?;
+        let path = file.path().to_path_buf();
+
+        let mut config = Config {
+            count_sloc: true,
+            ..Config::default()
+        };
+        let plain = process_file((path.clone(), std::fs::metadata(&path)?), &config)?;
+        assert_eq!(plain.sloc, Some(3));
+
+        config.detect_modeline = true;
+        let detected = process_file((path.clone(), std::fs::metadata(&path)?), &config)?;
+        assert_eq!(detected.sloc, Some(1));
+        Ok(())
+    }
+
     #[test]
     fn test_binary_file_marks_binary() -> std::result::Result<(), Box<dyn std::error::Error>> {
         let mut file = NamedTempFile::new()?;
@@ -109,4 +397,75 @@ mod tests {
         assert_eq!(stats.lines, 0);
         Ok(())
     }
+
+    #[test]
+    fn test_minified_files_have_no_sloc() {
+        let line = format!("var a=1;{}\n", "b();".repeat(4000));
+        let mut config = Config {
+            count_sloc: true,
+            ..Config::default()
+        };
+        let stats = count_builtin(PathBuf::from("app.js"), line.as_bytes(), &config);
+        assert!(stats.minified);
+        assert_eq!(stats.sloc, Some(0));
+
+        config.include_minified = true;
+        let stats = count_builtin(PathBuf::from("app.js"), line.as_bytes(), &config);
+        assert!(stats.minified);
+        assert_eq!(stats.sloc, Some(1));
+
+        let named = count_builtin(PathBuf::from("lib.min.css"), b"a{}\n", &config);
+        assert!(named.minified);
+        let regular = count_builtin(PathBuf::from("app.rs"), line.as_bytes(), &config);
+        assert!(!regular.minified);
+    }
+
+    #[test]
+    fn test_embedded_sections_are_recorded() {
+        let config = Config {
+            count_sloc: true,
+            ..Config::default()
+        };
+        let page = b"<div>\n<script>\n// x\nrun()\n</script>\n</div>\n";
+        let stats = count_builtin(PathBuf::from("page.svelte"), page, &config);
+        assert_eq!(stats.sloc, Some(5));
+        let embedded: Vec<_> = stats
+            .embedded
+            .iter()
+            .map(|e| (e.language.as_str(), e.lines, e.sloc))
+            .collect();
+        assert_eq!(embedded, [("JavaScript", 2, 1)]);
+
+        let plain = count_builtin(PathBuf::from("a.xml"), page, &config);
+        assert!(plain.embedded.is_empty());
+    }
+
+    #[test]
+    fn test_chunked_count_matches_serial() {
+        let mut sql = String::new();
+        for i in 0..200 {
+            sql.push_str(&format!("INSERT INTO t VALUES ({i}, 'a b');\n"));
+            if i % 7 == 0 {
+                sql.push_str("/* a comment\nspanning\n\nlines */\n-- note\n");
+            }
+        }
+        let serial = Config {
+            count_sloc: true,
+            count_words: true,
+            ..Config::default()
+        };
+        let chunked = Config {
+            chunk_size: Some(100),
+            ..serial.clone()
+        };
+        for name in ["dump.sql", "script.py", "notes.txt"] {
+            let expected = count_builtin(PathBuf::from(name), sql.as_bytes(), &serial);
+            let actual = count_builtin(PathBuf::from(name), sql.as_bytes(), &chunked);
+            assert_eq!(actual.lines, expected.lines, "{name}");
+            assert_eq!(actual.sloc, expected.sloc, "{name}");
+            assert_eq!(actual.words, expected.words, "{name}");
+            assert_eq!(actual.chars, expected.chars, "{name}");
+            assert_eq!(actual.max_line_length, expected.max_line_length, "{name}");
+        }
+    }
 }
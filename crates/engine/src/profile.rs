@@ -0,0 +1,53 @@
+// crates/engine/src/profile.rs
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+/// Time spent in one step of the counting, summed over all worker threads.
+///
+/// Clones share the same total, so a stopwatch in [`crate::config::Config`]
+/// collects the time of every file counted with that config.
+#[derive(Debug, Clone, Default)]
+pub struct Stopwatch(Arc<AtomicU64>);
+
+impl Stopwatch {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Runs `f` and adds the time it took.
+    pub fn time<T>(&self, f: impl FnOnce() -> T) -> T {
+        let started = Instant::now();
+        let value = f();
+        self.add(started.elapsed());
+        value
+    }
+
+    /// Adds `elapsed` to the total.
+    pub fn add(&self, elapsed: Duration) {
+        let nanos = u64::try_from(elapsed.as_nanos()).unwrap_or(u64::MAX);
+        self.0.fetch_add(nanos, Ordering::Relaxed);
+    }
+
+    /// The total so far.
+    #[must_use]
+    pub fn elapsed(&self) -> Duration {
+        Duration::from_nanos(self.0.load(Ordering::Relaxed))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clones_share_the_total() {
+        let stopwatch = Stopwatch::new();
+        let clone = stopwatch.clone();
+        clone.add(Duration::from_millis(3));
+        assert_eq!(stopwatch.time(|| 7), 7);
+        assert!(stopwatch.elapsed() >= Duration::from_millis(3));
+        assert_eq!(stopwatch.elapsed(), clone.elapsed());
+    }
+}
@@ -0,0 +1,30 @@
+// crates/engine/src/progress.rs
+//! Progress notifications emitted by [`crate::run_with_progress`].
+//!
+//! The walk discovers files ahead of the counting stage, so a reporter can
+//! estimate the remaining work once [`Progress::discovery_finished`] has been
+//! called.
+
+/// Receives progress events from the walk and counting stages.
+///
+/// Methods are called concurrently from worker threads and must be cheap.
+pub trait Progress: Send + Sync {
+    /// A file of `size` bytes was found and queued for counting.
+    fn discovered(&self, size: u64) {
+        let _ = size;
+    }
+
+    /// The walk has finished; no further files will be discovered.
+    fn discovery_finished(&self) {}
+
+    /// A file of `size` bytes was counted (or failed to be read).
+    fn processed(&self, size: u64) {
+        let _ = size;
+    }
+}
+
+/// Reporter that ignores every event.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoProgress;
+
+impl Progress for NoProgress {}
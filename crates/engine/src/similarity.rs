@@ -0,0 +1,136 @@
+// crates/engine/src/similarity.rs
+//! Near-duplicate detection (`--detect-similar`).
+//!
+//! Every file is reduced to the sorted fingerprints of its normalized code
+//! lines (see [`count_lines_core::fingerprint`]); two files are similar when
+//! the share of lines they have in common reaches the threshold.
+
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+use crate::stats::FileStats;
+
+/// Files with fewer fingerprinted lines are ignored, so tiny files don't
+/// match each other on a handful of common lines.
+pub const MIN_LINES: usize = 5;
+
+/// Two files sharing at least the threshold share of their code lines.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SimilarPair {
+    pub a: PathBuf,
+    pub b: PathBuf,
+    /// Shared lines as a percentage of both files' code lines
+    /// (`2 * shared / (lines_a + lines_b)`), rounded to one decimal.
+    pub similarity: f64,
+    /// Number of normalized code lines found in both files.
+    pub shared_lines: usize,
+}
+
+/// Returns every pair of files whose similarity is at least `threshold`
+/// percent, most similar first.
+///
+/// Only `FileStats::line_hashes` is used, so it must have been filled in
+/// while counting.
+#[must_use]
+#[allow(clippy::cast_precision_loss)]
+pub fn find_similar(stats: &[FileStats], threshold: f64) -> Vec<SimilarPair> {
+    let ratio = (threshold / 100.0).clamp(0.01, 1.0);
+    let mut files: Vec<&FileStats> = stats
+        .iter()
+        .filter(|s| s.line_hashes.len() >= MIN_LINES)
+        .collect();
+    files.sort_by_key(|s| s.line_hashes.len());
+
+    let mut pairs: Vec<SimilarPair> = (0..files.len())
+        .into_par_iter()
+        .flat_map_iter(|i| {
+            let a = files[i];
+            let len_a = a.line_hashes.len();
+            // Files are sorted by size and at most `len_a` lines can be
+            // shared, so a larger file past this bound can't reach `ratio`.
+            let max_len = (len_a as f64 * (2.0 - ratio) / ratio).floor() as usize;
+            files[i + 1..]
+                .iter()
+                .take_while(move |b| b.line_hashes.len() <= max_len)
+                .filter_map(move |b| {
+                    let shared = shared_lines(&a.line_hashes, &b.line_hashes);
+                    let similarity = 2.0 * shared as f64 / (len_a + b.line_hashes.len()) as f64;
+                    (similarity >= ratio).then(|| SimilarPair {
+                        a: a.path.clone(),
+                        b: b.path.clone(),
+                        similarity: (similarity * 1000.0).round() / 10.0,
+                        shared_lines: shared,
+                    })
+                })
+        })
+        .map(|mut pair| {
+            if pair.b < pair.a {
+                std::mem::swap(&mut pair.a, &mut pair.b);
+            }
+            pair
+        })
+        .collect();
+
+    pairs.sort_by(|x, y| {
+        y.similarity
+            .total_cmp(&x.similarity)
+            .then_with(|| x.a.cmp(&y.a))
+            .then_with(|| x.b.cmp(&y.b))
+    });
+    pairs
+}
+
+/// Size of the multiset intersection of two sorted slices.
+fn shared_lines(a: &[u64], b: &[u64]) -> usize {
+    let (mut i, mut j, mut shared) = (0, 0, 0);
+    while i < a.len() && j < b.len() {
+        match a[i].cmp(&b[j]) {
+            std::cmp::Ordering::Less => i += 1,
+            std::cmp::Ordering::Greater => j += 1,
+            std::cmp::Ordering::Equal => {
+                shared += 1;
+                i += 1;
+                j += 1;
+            }
+        }
+    }
+    shared
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file(path: &str, hashes: &[u64]) -> FileStats {
+        let mut line_hashes = hashes.to_vec();
+        line_hashes.sort_unstable();
+        FileStats {
+            line_hashes,
+            ..FileStats::new(PathBuf::from(path))
+        }
+    }
+
+    #[test]
+    fn test_shared_lines_counts_duplicates_once_per_side() {
+        assert_eq!(shared_lines(&[1, 1, 2, 3], &[1, 2, 2, 4]), 2);
+    }
+
+    #[test]
+    fn test_find_similar() {
+        let stats = [
+            file("b.rs", &[1, 2, 3, 4, 5, 6, 7, 8, 9, 10]),
+            file("a.rs", &[1, 2, 3, 4, 5, 6, 7, 8, 9, 11]),
+            file("c.rs", &[1, 2, 3, 20, 21, 22, 23, 24, 25, 26]),
+            file("tiny.rs", &[1, 2, 3]),
+        ];
+        let pairs = find_similar(&stats, 80.0);
+        assert_eq!(pairs.len(), 1);
+        assert_eq!(pairs[0].a, PathBuf::from("a.rs"));
+        assert_eq!(pairs[0].b, PathBuf::from("b.rs"));
+        assert_eq!(pairs[0].shared_lines, 9);
+        assert!((pairs[0].similarity - 90.0).abs() < f64::EPSILON);
+
+        assert_eq!(find_similar(&stats, 30.0).len(), 3);
+    }
+}
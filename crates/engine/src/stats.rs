@@ -1,12 +1,19 @@
 // crates/engine/src/stats.rs
 use chrono::{DateTime, Local};
+use count_lines_core::stats::{EmbeddedCount, WhitespaceCounts};
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
+use std::time::Duration;
 
+use crate::attributes::LinguistClass;
+use crate::encoding::Bom;
 use crate::error::EngineError;
+use crate::expr::Value;
+use crate::limits::ScanLimit;
+use crate::similarity::SimilarPair;
 
 /// Statistics for a single processed file.
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
 pub struct FileStats {
     /// The path to the file.
     pub path: PathBuf,
@@ -19,6 +26,38 @@ pub struct FileStats {
     /// SLOC (Source Lines of Code) - 空行を除外した純粋コード行数
     #[serde(default)]
     pub sloc: Option<usize>,
+    /// Lines holding only documentation comments, counted with `--docs`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub doc_lines: Option<usize>,
+    /// Length of the longest line in characters, excluding the line break.
+    #[serde(default)]
+    pub max_line_length: usize,
+    /// Average line length in characters, excluding line breaks.
+    #[serde(default)]
+    pub avg_line_length: f64,
+    /// Deepest indentation level of a line, measured with `--indent`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_depth: Option<usize>,
+    /// Average indentation level of the non-blank lines, measured with
+    /// `--indent`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub avg_depth: Option<f64>,
+    /// Whitespace and line-ending findings, collected with
+    /// `--audit-whitespace`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub whitespace: Option<WhitespaceCounts>,
+    /// The byte order mark the file starts with, recorded with
+    /// `--audit-encoding`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bom: Option<Bom>,
+    /// Whether the file should be UTF-8 by its extension but isn't,
+    /// checked with `--audit-encoding`.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub invalid_utf8: bool,
+    /// Lines over `--long-line-threshold`, counted without the language
+    /// processor.
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub long_lines: usize,
     /// The size of the file in bytes.
     pub size: u64,
     /// The last modification time of the file.
@@ -29,9 +68,91 @@ pub struct FileStats {
     pub name: String,
     /// Whether the file is considered binary.
     pub is_binary: bool,
+    /// Whether the file looks like minified JS/CSS (see
+    /// [`processor::is_minified`](crate::processor::is_minified)).
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub minified: bool,
+    /// Whether `--license-header` is missing from the first lines.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub missing_license_header: bool,
+    /// `.gitattributes` Linguist class, recorded by `--linguist tag`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub linguist: Option<LinguistClass>,
+    /// Source encoding the content was transcoded from, if it wasn't UTF-8.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub encoding: Option<String>,
+    /// Lines of the `<script>` / `<style>` sections of HTML, Vue and Svelte
+    /// files, per embedded language.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub embedded: Vec<EmbeddedCount>,
+    /// The scan root the file was found under, recorded when several roots
+    /// are scanned.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub root: Option<PathBuf>,
+    /// The author a `--by author` slice of the file is attributed to.
+    #[serde(skip)]
+    pub author: Option<String>,
+    /// Sorted fingerprints of the normalized code lines, filled in only for
+    /// `--detect-similar`.
+    #[serde(skip)]
+    pub line_hashes: Vec<u64>,
 }
 
 impl FileStats {
+    /// Field names usable in `--filter` expressions.
+    pub const FIELDS: &'static [&'static str] = &[
+        "lines", "chars", "words", "sloc", "doc", "size", "maxline", "avgline", "maxdepth",
+        "avgdepth",
+    ];
+
+    /// Returns the value of a `--filter` field, or `None` if it wasn't
+    /// counted (`words`, `sloc`, `doc`, `maxdepth`, `avgdepth`) or is
+    /// unknown.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn field(&self, name: &str) -> Option<f64> {
+        match name {
+            "lines" => Some(self.lines as f64),
+            "chars" => Some(self.chars as f64),
+            "words" => self.words.map(|v| v as f64),
+            "sloc" => self.sloc.map(|v| v as f64),
+            "doc" => self.doc_lines.map(|v| v as f64),
+            "size" => Some(self.size as f64),
+            "maxline" => Some(self.max_line_length as f64),
+            "avgline" => Some(self.avg_line_length),
+            "maxdepth" => self.max_depth.map(|v| v as f64),
+            "avgdepth" => self.avg_depth,
+            _ => None,
+        }
+    }
+
+    /// String field names usable in `--filter` expressions.
+    pub const TEXT_FIELDS: &'static [&'static str] = &["path", "name", "ext"];
+
+    /// Returns the value of a numeric or string `--filter` field. The path
+    /// uses `/` separators on every platform.
+    #[must_use]
+    pub fn value(&self, name: &str) -> Option<Value> {
+        match name {
+            "path" => Some(Value::Text(self.path.to_string_lossy().replace('\\', "/"))),
+            "name" => Some(Value::Text(self.name.clone())),
+            "ext" => Some(Value::Text(self.ext.clone())),
+            _ => self.field(name).map(Value::Number),
+        }
+    }
+
+    /// Documentation lines per documentation and code line, in percent.
+    #[must_use]
+    pub fn doc_density(&self) -> Option<f64> {
+        doc_density(self.doc_lines, self.sloc)
+    }
+
+    /// Whether a line was over the long line threshold.
+    #[must_use]
+    pub const fn has_long_lines(&self) -> bool {
+        self.long_lines > 0
+    }
+
     /// Creates a new `FileStats` instance for the given path.
     #[must_use]
     pub fn new(path: PathBuf) -> Self {
@@ -50,13 +171,257 @@ impl FileStats {
             chars: 0,
             words: None,
             sloc: None,
+            doc_lines: None,
+            max_line_length: 0,
+            avg_line_length: 0.0,
+            max_depth: None,
+            avg_depth: None,
+            whitespace: None,
+            bom: None,
+            invalid_utf8: false,
+            long_lines: 0,
             size: 0,
             mtime: None,
             ext,
             name,
             is_binary: false,
+            minified: false,
+            missing_license_header: false,
+            linguist: None,
+            encoding: None,
+            embedded: Vec::new(),
+            root: None,
+            author: None,
+            line_hashes: Vec::new(),
+        }
+    }
+}
+
+/// Aggregated totals over a set of processed files.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub struct Summary {
+    /// The number of files included in the totals.
+    pub files: usize,
+    /// Total number of lines.
+    pub lines: usize,
+    /// Total number of characters.
+    pub chars: usize,
+    /// Total number of words, if any file had words calculated.
+    pub words: Option<usize>,
+    /// Total SLOC, if any file had SLOC calculated.
+    pub sloc: Option<usize>,
+    /// Total documentation lines, if counted (`--docs`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub doc_lines: Option<usize>,
+    /// Total size in bytes.
+    pub size: u64,
+}
+
+impl Summary {
+    /// Aggregates the given file statistics into a `Summary`.
+    #[must_use]
+    pub fn from_stats(stats: &[FileStats]) -> Self {
+        Self {
+            files: stats.len(),
+            lines: stats.iter().map(|s| s.lines).sum(),
+            chars: stats.iter().map(|s| s.chars).sum(),
+            words: stats.iter().filter_map(|s| s.words).reduce(|a, b| a + b),
+            sloc: stats.iter().filter_map(|s| s.sloc).reduce(|a, b| a + b),
+            doc_lines: stats
+                .iter()
+                .filter_map(|s| s.doc_lines)
+                .reduce(|a, b| a + b),
+            size: stats.iter().map(|s| s.size).sum(),
         }
     }
+
+    /// Documentation lines per documentation and code line, in percent.
+    #[must_use]
+    pub fn doc_density(&self) -> Option<f64> {
+        doc_density(self.doc_lines, self.sloc)
+    }
+
+    /// Returns the value of a `--fail-if` summary field (see
+    /// [`Threshold::SUMMARY_FIELDS`](crate::expr::Threshold::SUMMARY_FIELDS)).
+    /// Totals that weren't counted are zero.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn field(&self, name: &str) -> Option<f64> {
+        let value = match name {
+            "total_files" => self.files as f64,
+            "total_lines" => self.lines as f64,
+            "total_chars" => self.chars as f64,
+            "total_words" => self.words.unwrap_or(0) as f64,
+            "total_sloc" => self.sloc.unwrap_or(0) as f64,
+            "total_size" => self.size as f64,
+            _ => return None,
+        };
+        Some(value)
+    }
+
+    /// Adds a single file to the totals.
+    pub fn add(&mut self, stats: &FileStats) {
+        fn add_optional(total: Option<usize>, value: Option<usize>) -> Option<usize> {
+            match (total, value) {
+                (None, None) => None,
+                (total, value) => Some(total.unwrap_or(0) + value.unwrap_or(0)),
+            }
+        }
+
+        self.files += 1;
+        self.lines += stats.lines;
+        self.chars += stats.chars;
+        self.words = add_optional(self.words, stats.words);
+        self.sloc = add_optional(self.sloc, stats.sloc);
+        self.doc_lines = add_optional(self.doc_lines, stats.doc_lines);
+        self.size += stats.size;
+    }
+}
+
+/// `doc / (doc + sloc)` in percent; `None` unless both were counted and
+/// there is any.
+#[allow(clippy::cast_precision_loss)]
+#[allow(clippy::trivially_copy_pass_by_ref)]
+const fn is_zero(n: &usize) -> bool {
+    *n == 0
+}
+
+fn doc_density(doc: Option<usize>, sloc: Option<usize>) -> Option<f64> {
+    let (doc, sloc) = (doc?, sloc?);
+    (doc + sloc > 0).then(|| doc as f64 * 100.0 / (doc + sloc) as f64)
+}
+
+/// Median, 90th and 99th percentile and maximum of one per-file value.
+///
+/// Percentiles use the nearest-rank method, so each is the value of an
+/// actual file.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Percentiles {
+    pub median: u64,
+    pub p90: u64,
+    pub p99: u64,
+    pub max: u64,
+}
+
+impl Percentiles {
+    /// Percentiles of `values`, all zero when there are none.
+    #[must_use]
+    pub fn of(mut values: Vec<u64>) -> Self {
+        values.sort_unstable();
+        let rank = |percent: usize| {
+            let index = (values.len() * percent).div_ceil(100).saturating_sub(1);
+            values.get(index).copied().unwrap_or(0)
+        };
+        Self {
+            median: rank(50),
+            p90: rank(90),
+            p99: rank(99),
+            max: values.last().copied().unwrap_or(0),
+        }
+    }
+}
+
+/// Files whose size falls in `[min, max)`; the last bucket has no `max`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SizeBucket {
+    pub min: u64,
+    pub max: Option<u64>,
+    pub files: usize,
+}
+
+/// How file sizes and line counts are spread (`--distribution`), to spot
+/// the outliers that totals and averages hide.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Distribution {
+    pub lines: Percentiles,
+    pub size: Percentiles,
+    /// File counts per size range, from under 1 KiB to 1 MiB and over in
+    /// steps of four.
+    pub size_histogram: Vec<SizeBucket>,
+}
+
+impl Distribution {
+    /// Upper bounds of all but the last histogram bucket.
+    const SIZE_BOUNDS: [u64; 6] = [1 << 10, 1 << 12, 1 << 14, 1 << 16, 1 << 18, 1 << 20];
+
+    #[must_use]
+    pub fn from_stats(stats: &[FileStats]) -> Self {
+        let mut size_histogram: Vec<SizeBucket> = std::iter::once(0)
+            .chain(Self::SIZE_BOUNDS)
+            .zip(Self::SIZE_BOUNDS.map(Some).into_iter().chain([None]))
+            .map(|(min, max)| SizeBucket { min, max, files: 0 })
+            .collect();
+        for s in stats {
+            let bucket = Self::SIZE_BOUNDS
+                .iter()
+                .take_while(|&&b| s.size >= b)
+                .count();
+            size_histogram[bucket].files += 1;
+        }
+        Self {
+            lines: Percentiles::of(stats.iter().map(|s| s.lines as u64).collect()),
+            size: Percentiles::of(stats.iter().map(|s| s.size).collect()),
+            size_histogram,
+        }
+    }
+}
+
+/// Serializable record of a per-file processing error.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ErrorRecord {
+    /// The path that failed to process.
+    pub path: PathBuf,
+    /// Machine-readable error kind, see [`EngineError::kind`].
+    ///
+    /// [`EngineError::kind`]: crate::error::EngineError::kind
+    #[serde(default)]
+    pub kind: String,
+    /// Human readable error message.
+    pub message: String,
+}
+
+/// A symlink skipped because it points back to one of its ancestors.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SymlinkCycle {
+    /// The link that closes the cycle.
+    pub link: PathBuf,
+    /// The ancestor directory it points to.
+    pub target: PathBuf,
+}
+
+/// Symlinks met during the walk, for `--report-links`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LinkReport {
+    /// Symlinks followed into (`--follow`).
+    pub followed: usize,
+    /// Links skipped because they would loop (`--follow`).
+    pub cycles: Vec<SymlinkCycle>,
+    /// Links whose target doesn't exist.
+    pub broken: Vec<PathBuf>,
+}
+
+impl LinkReport {
+    /// Returns true if no symlink was seen.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.followed == 0 && self.cycles.is_empty() && self.broken.is_empty()
+    }
+}
+
+/// How long each stage of a [`run`](crate::run) took.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct StageTimings {
+    /// Walking the roots and filtering paths.
+    pub walk: Duration,
+    /// Detecting the encoding of the files, summed over the counting
+    /// threads; part of `measure`.
+    pub detect: Duration,
+    /// Reading and counting the files; overlaps the walk.
+    pub measure: Duration,
+    /// Collecting the results, saving the cache and post-processing.
+    pub aggregate: Duration,
+    /// The whole run, from the start of the walk.
+    pub total: Duration,
 }
 
 /// Result of running the file counting engine.
@@ -67,6 +432,38 @@ pub struct RunResult {
     pub stats: Vec<FileStats>,
     /// Errors encountered during processing (path, error)
     pub errors: Vec<(PathBuf, EngineError)>,
+    /// True if the run was cancelled and `stats` covers only part of the files.
+    pub partial: bool,
+    /// Near-duplicate file pairs, when requested with `--detect-similar`.
+    pub similar: Vec<SimilarPair>,
+    /// Minified files, kept out of `stats` unless `--include-minified`.
+    pub minified: Vec<FileStats>,
+    /// Symlinks followed, skipped as cycles, or broken.
+    pub links: LinkReport,
+    /// Time spent in each stage of the run.
+    pub timings: StageTimings,
+    /// When the run started; `None` for results assembled elsewhere.
+    pub started_at: Option<DateTime<Local>>,
+    /// Hits and misses of the incremental cache, when it was used.
+    pub cache: Option<CacheCounts>,
+    /// The scan limit that stopped the walk, with `LimitAction::Truncate`;
+    /// `stats` then covers only part of the files.
+    pub truncated: Option<ScanLimit>,
+    /// True if the run ran past `--timeout`; the files it didn't get to are
+    /// in `errors`.
+    pub timed_out: bool,
+    /// Tracked files left out because a sparse checkout keeps them out of
+    /// the working tree (`git::count_checked_out_revision`).
+    pub sparse_skipped: usize,
+}
+
+/// How many files the incremental cache answered.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CacheCounts {
+    /// Files whose previous result was reused.
+    pub hits: usize,
+    /// Files that had to be counted.
+    pub misses: usize,
 }
 
 impl RunResult {
@@ -93,4 +490,97 @@ impl RunResult {
     pub const fn error_count(&self) -> usize {
         self.errors.len()
     }
+
+    /// Aggregates the successfully processed files into a `Summary`.
+    #[must_use]
+    pub fn summary(&self) -> Summary {
+        Summary::from_stats(&self.stats)
+    }
+
+    /// Returns the processing errors in a serializable form.
+    #[must_use]
+    pub fn error_records(&self) -> Vec<ErrorRecord> {
+        self.errors
+            .iter()
+            .map(|(path, err)| ErrorRecord {
+                path: path.clone(),
+                kind: err.kind().to_string(),
+                message: err.to_string(),
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_percentiles_nearest_rank() {
+        let p = Percentiles::of((1..=100).rev().collect());
+        assert_eq!(
+            p,
+            Percentiles {
+                median: 50,
+                p90: 90,
+                p99: 99,
+                max: 100
+            }
+        );
+        assert_eq!(Percentiles::of(vec![7]).p99, 7);
+        assert_eq!(Percentiles::of(vec![]), Percentiles::default());
+    }
+
+    #[test]
+    fn test_size_histogram() {
+        let stats: Vec<_> = [0, 1023, 1024, 5000, 2 << 20]
+            .into_iter()
+            .map(|size| FileStats {
+                size,
+                ..FileStats::default()
+            })
+            .collect();
+        let distribution = Distribution::from_stats(&stats);
+        let files: Vec<_> = distribution
+            .size_histogram
+            .iter()
+            .map(|b| b.files)
+            .collect();
+        assert_eq!(files, [2, 1, 1, 0, 0, 0, 1]);
+        assert_eq!(distribution.size_histogram[6].max, None);
+        assert_eq!(distribution.size.median, 1024);
+    }
+
+    #[test]
+    fn test_summary_from_stats() {
+        let stats = vec![
+            FileStats {
+                lines: 10,
+                chars: 100,
+                sloc: Some(8),
+                size: 120,
+                ..FileStats::default()
+            },
+            FileStats {
+                lines: 5,
+                chars: 40,
+                sloc: Some(2),
+                size: 50,
+                ..FileStats::default()
+            },
+        ];
+        let summary = Summary::from_stats(&stats);
+        assert_eq!(summary.files, 2);
+        assert_eq!(summary.lines, 15);
+        assert_eq!(summary.chars, 140);
+        assert_eq!(summary.sloc, Some(10));
+        assert_eq!(summary.words, None);
+        assert_eq!(summary.size, 170);
+    }
+
+    #[test]
+    fn test_summary_empty() {
+        let summary = Summary::from_stats(&[]);
+        assert_eq!(summary, Summary::default());
+    }
 }
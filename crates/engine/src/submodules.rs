@@ -0,0 +1,148 @@
+// crates/engine/src/submodules.rs
+//! Git submodules, read from `.gitmodules`: the walk leaves them out unless
+//! `--include-submodules`, and `--by submodule` gives their files a group
+//! of their own.
+
+use std::path::{Path, PathBuf};
+
+/// The submodules of the repositories of the scan roots.
+#[derive(Debug, Default)]
+pub struct Submodules {
+    /// Absolute directory and name of each submodule.
+    entries: Vec<(PathBuf, String)>,
+}
+
+impl Submodules {
+    /// Reads the `.gitmodules` of the repository containing each of `roots`.
+    #[must_use]
+    pub fn find(roots: &[PathBuf]) -> Self {
+        let mut entries = Vec::new();
+        let mut tops: Vec<PathBuf> = Vec::new();
+        for root in roots {
+            let Some(top) = repository_top(root) else {
+                continue;
+            };
+            if tops.contains(&top) {
+                continue;
+            }
+            if let Ok(content) = std::fs::read_to_string(top.join(".gitmodules")) {
+                entries.extend(
+                    parse(&content)
+                        .into_iter()
+                        .map(|(name, path)| (top.join(path), name)),
+                );
+            }
+            tops.push(top);
+        }
+        Self { entries }
+    }
+
+    /// Returns true if no submodule was found.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns true if `dir` is the directory of a submodule.
+    #[must_use]
+    pub fn is_submodule_dir(&self, dir: &Path) -> bool {
+        std::path::absolute(dir).is_ok_and(|dir| self.entries.iter().any(|(path, _)| *path == dir))
+    }
+
+    /// The name of the submodule `path` is in, if any.
+    #[must_use]
+    pub fn submodule_of(&self, path: &Path) -> Option<&str> {
+        let path = std::path::absolute(path).ok()?;
+        self.entries
+            .iter()
+            .filter(|(dir, _)| path.starts_with(dir))
+            .max_by_key(|(dir, _)| dir.components().count())
+            .map(|(_, name)| name.as_str())
+    }
+}
+
+/// The top directory of the repository containing `root`: the nearest
+/// ancestor with a `.git` directory or file.
+fn repository_top(root: &Path) -> Option<PathBuf> {
+    let root = std::path::absolute(root).ok()?;
+    root.ancestors()
+        .find(|dir| dir.join(".git").exists())
+        .map(Path::to_path_buf)
+}
+
+/// The `(name, path)` of each `[submodule "name"]` section of a
+/// `.gitmodules` file that has a `path`.
+#[must_use]
+pub fn parse(content: &str) -> Vec<(String, String)> {
+    let mut submodules = Vec::new();
+    let mut name = None;
+    for line in content.lines() {
+        let line = line.trim();
+        if line.starts_with('[') {
+            name = line
+                .strip_prefix("[submodule")
+                .and_then(|rest| rest.trim().strip_suffix(']'))
+                .map(|quoted| quoted.trim().trim_matches('"').to_string());
+            continue;
+        }
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        if key.trim() == "path"
+            && let Some(name) = &name
+        {
+            let path = value.trim().trim_matches('"');
+            submodules.push((name.clone(), path.to_string()));
+        }
+    }
+    submodules
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GITMODULES: &str = r#"
+[submodule "vendor/json"]
+	path = third_party/json
+	url = https://github.com/nlohmann/json.git
+[core]
+	path = not/a/submodule
+[submodule "docs"]
+	url = https://example.com/docs.git
+	path = "docs/site"
+"#;
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(
+            parse(GITMODULES),
+            [
+                ("vendor/json".to_string(), "third_party/json".to_string()),
+                ("docs".to_string(), "docs/site".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_find() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = dir.path();
+        std::fs::create_dir_all(repo.join(".git")).unwrap();
+        std::fs::create_dir_all(repo.join("src")).unwrap();
+        std::fs::write(repo.join(".gitmodules"), GITMODULES).unwrap();
+
+        // Found from a root below the top of the repository.
+        let submodules = Submodules::find(&[repo.join("src")]);
+        assert!(submodules.is_submodule_dir(&repo.join("third_party/json")));
+        assert!(!submodules.is_submodule_dir(&repo.join("third_party")));
+        assert_eq!(
+            submodules.submodule_of(&repo.join("docs/site/index.md")),
+            Some("docs")
+        );
+        assert_eq!(submodules.submodule_of(&repo.join("src/main.rs")), None);
+
+        let outside = tempfile::tempdir().unwrap();
+        assert!(Submodules::find(&[outside.path().to_path_buf()]).is_empty());
+    }
+}
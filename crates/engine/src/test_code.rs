@@ -0,0 +1,385 @@
+// crates/engine/src/test_code.rs
+//! `--test-split`: tells test code from production code and sums both per
+//! language.
+//!
+//! A file is test code as a whole when its path matches one of the test
+//! patterns (gitignore-like, relative to its scan root: a pattern without
+//! `/` matches at any depth, a trailing `/` covers a directory). In other
+//! Rust files the `#[cfg(test)]` modules are test code and the rest is
+//! production code.
+
+use crate::codeowners::matchers_for;
+use crate::config::Config;
+use crate::error::Result;
+use crate::grouping::{group_key, root_of};
+use crate::options::GroupBy;
+use crate::stats::FileStats;
+use globset::GlobMatcher;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Patterns used when none are given.
+pub const DEFAULT_TEST_PATTERNS: &[&str] = &[
+    "tests/",
+    "test/",
+    "__tests__/",
+    "spec/",
+    "testdata/",
+    "*_test.go",
+    "test_*.py",
+    "*_test.py",
+    "*.test.js",
+    "*.spec.js",
+    "*.test.jsx",
+    "*.spec.jsx",
+    "*.test.ts",
+    "*.spec.ts",
+    "*.test.tsx",
+    "*.spec.tsx",
+    "*Test.java",
+    "*Tests.java",
+    "*Test.kt",
+    "*Tests.cs",
+    "*Tests.swift",
+    "*_spec.rb",
+    "*_test.rb",
+    "*_test.c",
+    "*_test.cc",
+    "*_test.cpp",
+    "*_test.exs",
+];
+
+/// Label of the summed row of a split.
+pub const TOTAL: &str = "Total";
+
+/// How [`split_tests`] classifies files.
+#[derive(Debug, Clone, Default)]
+pub struct TestSplitOptions {
+    /// Test patterns; [`DEFAULT_TEST_PATTERNS`] when empty.
+    pub patterns: Vec<String>,
+}
+
+/// Production and test code of one language.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct LanguageTestSplit {
+    pub language: String,
+    /// Files with production code, including Rust files with inline tests.
+    pub production_files: usize,
+    /// Files that are test code as a whole.
+    pub test_files: usize,
+    pub production_lines: usize,
+    pub test_lines: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub production_sloc: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub test_sloc: Option<usize>,
+    /// Test SLOC per production SLOC (lines when SLOC isn't counted);
+    /// `None` without production code.
+    pub ratio: Option<f64>,
+}
+
+impl LanguageTestSplit {
+    fn add(&mut self, other: &Self) {
+        self.production_files += other.production_files;
+        self.test_files += other.test_files;
+        self.production_lines += other.production_lines;
+        self.test_lines += other.test_lines;
+        self.production_sloc = add_sloc(self.production_sloc, other.production_sloc);
+        self.test_sloc = add_sloc(self.test_sloc, other.test_sloc);
+    }
+
+    fn update_ratio(&mut self) {
+        let (test, production) = match (self.test_sloc, self.production_sloc) {
+            (Some(test), Some(production)) => (test, production),
+            _ => (self.test_lines, self.production_lines),
+        };
+        self.ratio = (production > 0).then(|| test as f64 / production as f64);
+    }
+
+    /// The sum of `splits`, labelled [`TOTAL`].
+    #[must_use]
+    pub fn total(splits: &[Self]) -> Self {
+        let mut total = Self {
+            language: TOTAL.to_string(),
+            ..Self::default()
+        };
+        for split in splits {
+            total.add(split);
+        }
+        total.update_ratio();
+        total
+    }
+}
+
+fn add_sloc(a: Option<usize>, b: Option<usize>) -> Option<usize> {
+    match (a, b) {
+        (None, None) => None,
+        (a, b) => Some(a.unwrap_or(0) + b.unwrap_or(0)),
+    }
+}
+
+/// Matches paths against the test patterns.
+#[derive(Debug)]
+pub struct TestPatterns {
+    matchers: Vec<GlobMatcher>,
+}
+
+impl TestPatterns {
+    /// Compiles `patterns`, or [`DEFAULT_TEST_PATTERNS`] when empty.
+    /// Patterns that aren't valid globs are skipped.
+    #[must_use]
+    pub fn new(patterns: &[String]) -> Self {
+        let patterns: Vec<&str> = if patterns.is_empty() {
+            DEFAULT_TEST_PATTERNS.to_vec()
+        } else {
+            patterns.iter().map(String::as_str).collect()
+        };
+        Self {
+            matchers: patterns
+                .into_iter()
+                .filter_map(matchers_for)
+                .flatten()
+                .collect(),
+        }
+    }
+
+    /// Returns true if `path`, relative to its scan root, is test code.
+    #[must_use]
+    pub fn is_test(&self, path: &Path, roots: &[PathBuf]) -> bool {
+        let relative = root_of(path, roots)
+            .and_then(|root| path.strip_prefix(root).ok())
+            .unwrap_or(path);
+        self.matchers.iter().any(|m| m.is_match(relative))
+    }
+}
+
+/// Counts the files selected by `config` and sums their production and
+/// test code per language, most lines first.
+///
+/// # Errors
+/// Returns an error if the run fails.
+pub fn split_tests(config: &Config, options: &TestSplitOptions) -> Result<Vec<LanguageTestSplit>> {
+    let patterns = TestPatterns::new(&options.patterns);
+    let result = crate::run(config)?;
+    let mut languages: HashMap<String, LanguageTestSplit> = HashMap::new();
+    for s in result.stats.iter().filter(|s| !s.is_binary) {
+        let language = group_key(s, GroupBy::Language, config);
+        let split = languages
+            .entry(language.clone())
+            .or_insert_with(|| LanguageTestSplit {
+                language,
+                ..LanguageTestSplit::default()
+            });
+        split.add(&classify(s, &patterns, config));
+    }
+
+    let mut splits: Vec<LanguageTestSplit> = languages.into_values().collect();
+    for split in &mut splits {
+        split.update_ratio();
+    }
+    splits.sort_by(|a, b| {
+        (b.production_lines + b.test_lines)
+            .cmp(&(a.production_lines + a.test_lines))
+            .then_with(|| a.language.cmp(&b.language))
+    });
+    Ok(splits)
+}
+
+/// The production and test code of one file.
+fn classify(s: &FileStats, patterns: &TestPatterns, config: &Config) -> LanguageTestSplit {
+    if patterns.is_test(&s.path, &config.walk.roots) {
+        return LanguageTestSplit {
+            test_files: 1,
+            test_lines: s.lines,
+            test_sloc: s.sloc,
+            production_sloc: s.sloc.map(|_| 0),
+            ..LanguageTestSplit::default()
+        };
+    }
+    let test_lines = if s.ext.eq_ignore_ascii_case("rs") {
+        std::fs::read(&s.path)
+            .map(|content| rust_test_lines(&String::from_utf8_lossy(&content)))
+            .unwrap_or(0)
+            .min(s.lines)
+    } else {
+        0
+    };
+    // SLOC isn't counted per line, so the test modules get their share.
+    let test_sloc = s.sloc.map(|sloc| sloc * test_lines / s.lines.max(1));
+    LanguageTestSplit {
+        production_files: 1,
+        production_lines: s.lines - test_lines,
+        test_lines,
+        production_sloc: s.sloc.zip(test_sloc).map(|(sloc, test)| sloc - test),
+        test_sloc,
+        ..LanguageTestSplit::default()
+    }
+}
+
+/// The number of lines of the `#[cfg(test)]` modules of Rust source
+/// `content`, attribute included.
+///
+/// The module ends where its braces balance; braces in strings and block
+/// comments are counted too, which is close enough for a ratio.
+#[must_use]
+pub fn rust_test_lines(content: &str) -> usize {
+    let lines: Vec<&str> = content.lines().collect();
+    let mut total = 0;
+    let mut i = 0;
+    while i < lines.len() {
+        if lines[i].trim() != "#[cfg(test)]" {
+            i += 1;
+            continue;
+        }
+        let start = i;
+        // Skip further attributes and doc comments up to the item.
+        let mut item = i + 1;
+        while item < lines.len() && {
+            let line = lines[item].trim();
+            line.is_empty() || line.starts_with("#[") || line.starts_with("//")
+        } {
+            item += 1;
+        }
+        let is_module = lines.get(item).is_some_and(|line| {
+            let line = line.trim_start();
+            let line = line
+                .strip_prefix("pub")
+                .map(|rest| match rest.strip_prefix('(') {
+                    Some(scoped) => scoped.split_once(')').map_or(rest, |(_, rest)| rest),
+                    None => rest,
+                })
+                .unwrap_or(line)
+                .trim_start();
+            line.starts_with("mod ") && !line.trim_end().ends_with(';')
+        });
+        if !is_module {
+            i += 1;
+            continue;
+        }
+
+        let mut depth = 0usize;
+        let mut opened = false;
+        let mut end = item;
+        while end < lines.len() {
+            let code = lines[end].split("//").next().unwrap_or_default();
+            for c in code.chars() {
+                match c {
+                    '{' => {
+                        depth += 1;
+                        opened = true;
+                    }
+                    '}' => depth = depth.saturating_sub(1),
+                    _ => {}
+                }
+            }
+            if opened && depth == 0 {
+                break;
+            }
+            end += 1;
+        }
+        let end = end.min(lines.len() - 1);
+        total += end - start + 1;
+        i = end + 1;
+    }
+    total
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::WalkOptions;
+
+    const SOURCE: &str = r#"pub fn add(a: i32, b: i32) -> i32 {
+    a + b
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_add() {
+        assert_eq!(add(1, 2), 3); // {
+    }
+}
+
+#[cfg(test)]
+fn helper() {}
+
+#[cfg(test)]
+#[path = "more_tests.rs"]
+mod more_tests;
+"#;
+
+    #[test]
+    fn test_rust_test_lines() {
+        assert_eq!(rust_test_lines(SOURCE), 9);
+        assert_eq!(rust_test_lines("fn main() {}\n"), 0);
+        let scoped = "#[cfg(test)]\npub(crate) mod tests {\n    fn a() {}\n}\nfn b() {}\n";
+        assert_eq!(rust_test_lines(scoped), 4);
+    }
+
+    #[test]
+    fn test_patterns() {
+        let roots = [PathBuf::from("/repo/test")];
+        let defaults = TestPatterns::new(&[]);
+        assert!(defaults.is_test(Path::new("/repo/test/tests/cli.rs"), &roots));
+        assert!(defaults.is_test(Path::new("/repo/test/pkg/api_test.go"), &roots));
+        assert!(defaults.is_test(Path::new("/repo/test/web/app.spec.ts"), &roots));
+        // The root itself being called `test` doesn't make everything test code.
+        assert!(!defaults.is_test(Path::new("/repo/test/src/lib.rs"), &roots));
+        assert!(!defaults.is_test(Path::new("/repo/test/src/latest.go"), &roots));
+
+        let custom = TestPatterns::new(&["checks/".to_string(), "*.t".to_string()]);
+        assert!(custom.is_test(Path::new("/repo/test/checks/a.rs"), &roots));
+        assert!(custom.is_test(Path::new("/repo/test/x/y.t"), &roots));
+        assert!(!custom.is_test(Path::new("/repo/test/tests/cli.rs"), &roots));
+    }
+
+    #[test]
+    fn test_split_tests() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        std::fs::create_dir_all(root.join("tests")).unwrap();
+        std::fs::write(root.join("src/lib.rs"), SOURCE).unwrap();
+        std::fs::write(root.join("tests/cli.rs"), "#[test]\nfn cli() {}\n").unwrap();
+        std::fs::write(root.join("src/main.go"), "package main\n\nfunc main() {}\n").unwrap();
+        std::fs::write(root.join("src/main_test.go"), "package main\n").unwrap();
+
+        let config = Config {
+            walk: WalkOptions {
+                roots: vec![root.to_path_buf()],
+                ..WalkOptions::default()
+            },
+            ..Config::default()
+        };
+        let splits = split_tests(&config, &TestSplitOptions::default()).unwrap();
+        let rows: Vec<_> = splits
+            .iter()
+            .map(|s| {
+                (
+                    s.language.as_str(),
+                    s.production_files,
+                    s.test_files,
+                    s.production_lines,
+                    s.test_lines,
+                )
+            })
+            .collect();
+        assert_eq!(rows, [("Rust", 1, 1, 11, 11), ("Go", 1, 1, 3, 1)]);
+        assert!((splits[1].ratio.unwrap() - 1.0 / 3.0).abs() < 1e-9);
+
+        let total = LanguageTestSplit::total(&splits);
+        assert_eq!(
+            (
+                total.language.as_str(),
+                total.production_lines,
+                total.test_lines
+            ),
+            (TOTAL, 14, 12)
+        );
+        assert!((total.ratio.unwrap() - 12.0 / 14.0).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,21 @@
+// crates/engine/src/testing.rs
+//! Helpers for tests that build git repositories, shared with the CLI's
+//! tests through the `testing` feature.
+
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+/// Runs `git` in `dir` as the user `test`, panicking if it fails. Pass
+/// `-c user.name=<name>` first in `args` to commit as someone else.
+pub fn git(dir: &Path, args: &[&str]) {
+    let status = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(["-c", "user.name=test", "-c", "user.email=test@example.com"])
+        .args(args)
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .unwrap();
+    assert!(status.success(), "git {args:?} failed");
+}
@@ -0,0 +1,281 @@
+// crates/engine/src/timeout.rs
+//! `--file-timeout` / `--timeout`: keeps a device file, a FIFO or a file on a
+//! hung network mount from stalling the whole run. A file that takes too
+//! long is reported as an error and the run moves on.
+
+use crate::cache::StatsCache;
+use crate::cancel::CancellationToken;
+use crate::config::Config;
+use crate::error::{EngineError, Result};
+use crate::stats::FileStats;
+use crossbeam_channel::{Receiver, RecvTimeoutError, Sender};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicU8, Ordering};
+use std::time::{Duration, Instant};
+
+/// The time limits of one run.
+#[derive(Debug, Clone)]
+pub(crate) struct Deadline {
+    started: Instant,
+    timeout: Option<Duration>,
+    file_timeout: Option<Duration>,
+    expired: Arc<AtomicBool>,
+}
+
+impl Deadline {
+    pub(crate) fn new(config: &Config) -> Self {
+        Self {
+            started: Instant::now(),
+            timeout: config.timeout,
+            file_timeout: config.file_timeout,
+            expired: Arc::default(),
+        }
+    }
+
+    /// Starts the timer of the run timeout, which cancels `walk` when it
+    /// passes. The timer stops when the returned sender is dropped.
+    pub(crate) fn arm(&self, walk: CancellationToken) -> Option<Sender<()>> {
+        let timeout = self.timeout?;
+        let (done_tx, done_rx) = crossbeam_channel::bounded::<()>(0);
+        let expired = self.expired.clone();
+        std::thread::spawn(move || {
+            if done_rx.recv_timeout(timeout) == Err(RecvTimeoutError::Timeout) {
+                log::debug!("timeout: run exceeded {timeout:?}");
+                expired.store(true, Ordering::Relaxed);
+                walk.cancel();
+            }
+        });
+        Some(done_tx)
+    }
+
+    /// Returns true once the run timeout has passed.
+    pub(crate) fn expired(&self) -> bool {
+        self.expired.load(Ordering::Relaxed)
+    }
+
+    /// The run timeout, if any.
+    pub(crate) const fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
+    /// How long one file may take: the file timeout, capped by what is left
+    /// of the run timeout. `None` if neither is set.
+    pub(crate) fn file_limit(&self) -> Option<Duration> {
+        let left = self
+            .timeout
+            .map(|timeout| timeout.saturating_sub(self.started.elapsed()));
+        match (self.file_timeout, left) {
+            (Some(file), Some(left)) => Some(file.min(left)),
+            (file, left) => file.or(left),
+        }
+    }
+}
+
+/// [`Job::state`] values.
+const QUEUED: u8 = 0;
+const RUNNING: u8 = 1;
+const DONE: u8 = 2;
+const ABANDONED: u8 = 3;
+
+/// A file handed to [`Workers`].
+struct Job {
+    path: PathBuf,
+    meta: std::fs::Metadata,
+    /// When the caller stops waiting for the file.
+    deadline: Instant,
+    results: Sender<Result<FileStats>>,
+    /// `QUEUED`, `RUNNING`, `DONE`, or `ABANDONED` once the caller gave up.
+    state: Arc<AtomicU8>,
+}
+
+/// A set of threads that count files for callers that give up on them after
+/// a time limit.
+///
+/// Files still queued when their caller has given up are skipped. A worker
+/// whose read doesn't return in time is replaced by a new thread, so a hung
+/// file costs one thread until its read returns rather than stalling the
+/// queue; the stuck worker exits when it does. The workers exit once this
+/// is dropped and their current file is done.
+pub(crate) struct Workers {
+    jobs: Sender<Job>,
+    queue: Receiver<Job>,
+    config: Arc<Config>,
+    cache: Option<Arc<StatsCache>>,
+}
+
+impl Workers {
+    pub(crate) fn new(
+        threads: usize,
+        config: &Arc<Config>,
+        cache: Option<&Arc<StatsCache>>,
+    ) -> Self {
+        let (jobs, queue) = crossbeam_channel::unbounded::<Job>();
+        let workers = Self {
+            jobs,
+            queue,
+            config: config.clone(),
+            cache: cache.cloned(),
+        };
+        for _ in 0..threads.max(1) {
+            workers.spawn();
+        }
+        workers
+    }
+
+    /// Starts a worker thread. It runs until the queue closes, or until it
+    /// finishes a file its caller gave up on and was replaced.
+    fn spawn(&self) {
+        let (queue, config, cache) = (self.queue.clone(), self.config.clone(), self.cache.clone());
+        std::thread::spawn(move || {
+            for job in queue {
+                if Instant::now() >= job.deadline
+                    || job
+                        .state
+                        .compare_exchange(QUEUED, RUNNING, Ordering::AcqRel, Ordering::Acquire)
+                        .is_err()
+                {
+                    continue;
+                }
+                crate::process_entry(job.path, job.meta, &config, cache.as_deref(), &mut |res| {
+                    let _ = job.results.send(res);
+                });
+                if job.state.swap(DONE, Ordering::AcqRel) == ABANDONED {
+                    return;
+                }
+            }
+        });
+    }
+
+    /// Like [`crate::process_entry`], but gives up on the file after `limit`
+    /// and reports it as [`EngineError::Timeout`].
+    pub(crate) fn process_entry_within(
+        &self,
+        path: PathBuf,
+        meta: std::fs::Metadata,
+        limit: Duration,
+        emit: &mut dyn FnMut(Result<FileStats>),
+    ) {
+        let deadline = Instant::now() + limit;
+        let (results, rx) = crossbeam_channel::unbounded();
+        let state = Arc::new(AtomicU8::new(QUEUED));
+        let job = Job {
+            path: path.clone(),
+            meta,
+            deadline,
+            results,
+            state: state.clone(),
+        };
+        if self.jobs.send(job).is_err() {
+            return;
+        }
+        loop {
+            match rx.recv_deadline(deadline) {
+                Ok(res) => emit(res),
+                Err(RecvTimeoutError::Disconnected) => return,
+                Err(RecvTimeoutError::Timeout) => {
+                    if state.swap(ABANDONED, Ordering::AcqRel) == RUNNING {
+                        log::debug!("timeout: replacing the worker stuck on {}", path.display());
+                        self.spawn();
+                    }
+                    emit(Err(EngineError::Timeout { path, after: limit }));
+                    return;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_file_limit() {
+        let config = |file_timeout, timeout| Config {
+            file_timeout,
+            timeout,
+            ..Config::default()
+        };
+        let secs = Duration::from_secs;
+        assert_eq!(Deadline::new(&config(None, None)).file_limit(), None);
+        assert_eq!(
+            Deadline::new(&config(Some(secs(10)), None)).file_limit(),
+            Some(secs(10))
+        );
+        let capped = Deadline::new(&config(Some(secs(10)), Some(secs(5))));
+        assert!(capped.file_limit().unwrap() <= secs(5));
+    }
+
+    #[test]
+    fn test_timer_cancels_the_walk() {
+        let deadline = Deadline::new(&Config {
+            timeout: Some(Duration::from_millis(10)),
+            ..Config::default()
+        });
+        let walk = CancellationToken::new();
+        let _timer = deadline.arm(walk.clone());
+        std::thread::sleep(Duration::from_millis(200));
+        assert!(deadline.expired());
+        assert!(walk.is_cancelled());
+
+        // A run that finishes in time stops the timer.
+        let deadline = Deadline::new(&Config {
+            timeout: Some(Duration::from_millis(50)),
+            ..Config::default()
+        });
+        let walk = CancellationToken::new();
+        drop(deadline.arm(walk.clone()));
+        std::thread::sleep(Duration::from_millis(200));
+        assert!(!deadline.expired());
+        assert!(!walk.is_cancelled());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_blocking_read_times_out() {
+        let dir = tempfile::tempdir().unwrap();
+        let fifo = dir.path().join("pipe.rs");
+        let status = std::process::Command::new("mkfifo")
+            .arg(&fifo)
+            .status()
+            .unwrap();
+        assert!(status.success());
+
+        let file = dir.path().join("a.rs");
+        std::fs::write(&file, "fn main() {}\n").unwrap();
+
+        // Opening a FIFO without a writer blocks.
+        let workers = Workers::new(1, &Arc::new(Config::default()), None);
+        let meta = std::fs::metadata(&fifo).unwrap();
+        let mut results = Vec::new();
+        workers.process_entry_within(fifo.clone(), meta, Duration::from_millis(50), &mut |res| {
+            results.push(res)
+        });
+        assert_eq!(results.len(), 1);
+        assert!(matches!(
+            &results[0],
+            Err(EngineError::Timeout { path, .. }) if *path == fifo
+        ));
+
+        // The blocked worker was replaced, so the next file is still counted.
+        results.clear();
+        let meta = std::fs::metadata(&file).unwrap();
+        workers.process_entry_within(
+            file.clone(),
+            meta.clone(),
+            Duration::from_secs(10),
+            &mut |res| results.push(res),
+        );
+        assert!(matches!(&results[..], [Ok(stats)] if stats.lines == 1));
+
+        // Once the read returns, the stuck worker exits and the replacement
+        // keeps serving.
+        drop(std::fs::OpenOptions::new().write(true).open(&fifo));
+        results.clear();
+        workers.process_entry_within(file, meta, Duration::from_secs(10), &mut |res| {
+            results.push(res);
+        });
+        assert!(matches!(&results[..], [Ok(stats)] if stats.lines == 1));
+    }
+}
@@ -1,49 +1,347 @@
 // crates/engine/src/watch.rs
+//! Watch mode: file events from `notify` are gathered over a debounce
+//! window and applied to the previous result, re-measuring only the files
+//! that changed.
+
+use crate::cache::StatsCache;
 use crate::config::Config;
-use crate::error::Result;
-use notify::{RecursiveMode, Watcher};
-use std::sync::mpsc::channel;
+use crate::error::{EngineError, Result};
+use crate::filesystem::IGNORE_FILENAMES;
+use crate::options::LinguistMode;
+use crate::stats::{FileStats, RunResult};
+use notify::event::{AccessKind, AccessMode, CreateKind, ModifyKind};
+use notify::{Event, EventKind, PollWatcher, RecursiveMode, Watcher};
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{RecvTimeoutError, channel};
+use std::time::Instant;
+
+/// More changed paths than this in one window trigger a full rescan.
+const MAX_FILE_UPDATES: usize = 1000;
+
+/// Ignore files besides [`IGNORE_FILENAMES`] that change what is walked.
+const WALK_FILES: [&str; 3] = [".gitignore", ".ignore", ".gitattributes"];
+
+/// Files changed, created or removed during one debounce window.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Changes {
+    /// Changed paths, spelled as under the walk roots.
+    pub paths: BTreeSet<PathBuf>,
+    /// The changes can't be applied file by file: a directory or an ignore
+    /// file changed, or the watcher dropped events.
+    pub rescan: bool,
+}
+
+impl Changes {
+    /// Changes that require measuring everything, as for the first cycle.
+    #[must_use]
+    pub fn rescan() -> Self {
+        Self {
+            paths: BTreeSet::new(),
+            rescan: true,
+        }
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.paths.is_empty() && !self.rescan
+    }
+
+    fn record(&mut self, event: &Event, roots: &[(PathBuf, PathBuf)]) {
+        if event.need_rescan() {
+            self.rescan = true;
+            return;
+        }
+        let new_entry = match event.kind {
+            EventKind::Create(CreateKind::Folder) => {
+                self.rescan = true;
+                return;
+            }
+            EventKind::Create(_) | EventKind::Modify(ModifyKind::Name(_)) => true,
+            EventKind::Modify(_)
+            | EventKind::Remove(_)
+            | EventKind::Access(AccessKind::Close(AccessMode::Write)) => false,
+            _ => return,
+        };
+        for path in &event.paths {
+            let name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
+            if IGNORE_FILENAMES.contains(&name)
+                || WALK_FILES.contains(&name)
+                || (new_entry && path.is_dir())
+            {
+                self.rescan = true;
+            }
+            self.paths.insert(under_root(path, roots));
+        }
+    }
+}
 
-/// Watch files for changes and run the callback.
+/// Watches the walk roots and calls `on_change` once up front with
+/// [`Changes::rescan`], then once per debounce window with the changes.
+///
+/// Events are gathered until `config.watch_debounce` passes without one, or
+/// for at most ten times that while they keep coming. Where native events
+/// are unavailable the roots are polled every `config.watch_interval`.
 ///
 /// This function blocks indefinitely.
-pub fn watch_loop<F>(config: &Config, mut on_event: F) -> Result<()>
+///
+/// # Errors
+/// Returns an error if the watcher can't be created or a root watched.
+pub fn watch_loop<F>(config: &Config, mut on_change: F) -> Result<()>
 where
-    F: FnMut(),
+    F: FnMut(&Changes),
 {
     let (tx, rx) = channel();
+    let poll_tx = tx.clone();
 
-    // Create a watcher object, delivering debounced events.
-    let mut watcher = notify::recommended_watcher(move |res| match res {
-        Ok(event) => {
-            let _ = tx.send(event);
-        }
-        Err(e) => eprintln!("watch error: {e:?}"),
-    })?;
+    let mut watcher: Box<dyn Watcher> = match notify::recommended_watcher(move |res| {
+        let _ = tx.send(res);
+    }) {
+        Ok(watcher) => Box::new(watcher),
+        Err(_) => Box::new(PollWatcher::new(
+            move |res| {
+                let _ = poll_tx.send(res);
+            },
+            notify::Config::default().with_poll_interval(config.watch_interval),
+        )?),
+    };
 
-    // Add paths to be watched
+    let mut roots = Vec::new();
     for root in &config.walk.roots {
         if root.exists() {
             watcher.watch(root, RecursiveMode::Recursive)?;
+            let canonical = root.canonicalize().unwrap_or_else(|_| root.clone());
+            roots.push((root.clone(), canonical));
+        }
+    }
+
+    log::info!("watch: watching {} root(s)", roots.len());
+    on_change(&Changes::rescan());
+
+    let debounce = config.watch_debounce;
+    while let Ok(first) = rx.recv() {
+        let mut changes = Changes::default();
+        let mut pending = Some(first);
+        let deadline = Instant::now() + debounce * 10;
+        while let Some(res) = pending.take() {
+            match res {
+                Ok(event) => changes.record(&event, &roots),
+                Err(e) => {
+                    log::warn!("watch: {e}");
+                    changes.rescan = true;
+                }
+            }
+            let wait = debounce.min(deadline.saturating_duration_since(Instant::now()));
+            match rx.recv_timeout(wait) {
+                Ok(res) => pending = Some(res),
+                Err(RecvTimeoutError::Timeout | RecvTimeoutError::Disconnected) => {}
+            }
+        }
+        if !changes.is_empty() {
+            on_change(&changes);
         }
     }
+    Ok(())
+}
 
-    // Initial run
-    println!("[count_lines] Starting watch mode...");
-    on_event();
+/// Applies `changes` to the `result` of the previous cycle.
+///
+/// Each changed path is dropped from the result and, if it still exists
+/// and the walk would yield it, measured again. A rescan, `--detect-similar`
+/// or too many changes run the whole count instead.
+///
+/// # Errors
+/// Returns an error if a full rescan fails, or with `--strict` if a
+/// changed file can't be measured.
+pub fn apply_changes(result: &mut RunResult, changes: &Changes, config: &Config) -> Result<()> {
+    if changes.rescan || config.detect_similar.is_some() || changes.paths.len() > MAX_FILE_UPDATES {
+        *result = crate::run(config)?;
+        return Ok(());
+    }
 
-    let debounce_interval = config.watch_interval;
+    let cache = config.incremental.then(|| StatsCache::load(config));
+    let linguist = (config.walk.linguist == Some(LinguistMode::Tag))
+        .then(crate::attributes::LinguistAttributes::new);
+    let mut measured = Vec::new();
+    for path in &changes.paths {
+        result.stats.retain(|s| !is_under(&s.path, path));
+        result.minified.retain(|s| !is_under(&s.path, path));
+        result.errors.retain(|(p, _)| !is_under(p, path));
 
-    // Loop forever
-    loop {
-        // Event loop
-        while rx.recv().is_ok() {
-            // Debounce
-            std::thread::sleep(debounce_interval);
-            // Drain
-            while rx.try_recv().is_ok() {}
+        if !path.is_file() || !crate::explain::is_walked(path, config).unwrap_or(false) {
+            continue;
+        }
+        let meta = match std::fs::metadata(path) {
+            Ok(meta) => meta,
+            Err(source) => {
+                measured.push(Err(EngineError::FileRead {
+                    path: path.clone(),
+                    source,
+                }));
+                continue;
+            }
+        };
+        let class = linguist.as_ref().and_then(|l| l.classify(path));
+        crate::process_entry(
+            path.clone(),
+            meta,
+            config,
+            cache.as_ref(),
+            &mut |mut res| {
+                if let Ok(stats) = &mut res {
+                    stats.linguist = class;
+                }
+                measured.push(res);
+            },
+        );
+    }
 
-            on_event();
+    for res in measured {
+        match res {
+            Ok(stats) => add_stats(result, stats, config),
+            Err(e) if config.strict => return Err(e),
+            Err(e) => {
+                let path = match &e {
+                    EngineError::FileRead { path, .. } | EngineError::Plugin { path, .. } => {
+                        path.clone()
+                    }
+                    _ => PathBuf::from("<unknown>"),
+                };
+                result.errors.push((path, e));
+            }
         }
     }
+    if let Some(cache) = cache
+        && let Err(e) = cache.save()
+    {
+        if config.strict {
+            return Err(e);
+        }
+        result.errors.push((PathBuf::from("<cache>"), e));
+    }
+    Ok(())
+}
+
+fn add_stats(result: &mut RunResult, stats: FileStats, config: &Config) {
+    if !crate::matches_result_filter(&stats, &config.filter) {
+        return;
+    }
+    if stats.minified && !config.include_minified {
+        result.minified.push(stats);
+    } else {
+        result.stats.push(stats);
+    }
+}
+
+/// Whether `path` of a result is `changed`, inside it, or a member of it
+/// (`archive.zip!member`).
+fn is_under(path: &Path, changed: &Path) -> bool {
+    path.starts_with(changed)
+        || path
+            .to_string_lossy()
+            .strip_prefix(changed.to_string_lossy().as_ref())
+            .is_some_and(|rest| rest.starts_with('!'))
+}
+
+/// Spells an event path the way the walk of its root does.
+fn under_root(path: &Path, roots: &[(PathBuf, PathBuf)]) -> PathBuf {
+    roots
+        .iter()
+        .find_map(|(root, canonical)| {
+            if path.starts_with(root) {
+                return Some(path.to_path_buf());
+            }
+            Some(root.join(path.strip_prefix(canonical).ok()?))
+        })
+        .unwrap_or_else(|| path.to_path_buf())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::WalkOptions;
+    use notify::event::{ModifyKind, RemoveKind};
+
+    fn config(root: &Path) -> Config {
+        Config {
+            walk: WalkOptions {
+                roots: vec![root.to_path_buf()],
+                ..WalkOptions::default()
+            },
+            ..Config::default()
+        }
+    }
+
+    fn lines(result: &RunResult, path: &Path) -> Option<usize> {
+        result
+            .stats
+            .iter()
+            .find(|s| s.path == path)
+            .map(|s| s.lines)
+    }
+
+    #[test]
+    fn test_apply_changes_remeasures_only_changed_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::write(root.join("a.rs"), "a\n").unwrap();
+        std::fs::write(root.join("b.rs"), "b\n").unwrap();
+        std::fs::write(root.join(".gitignore"), "ignored.rs\n").unwrap();
+        std::fs::create_dir(root.join(".git")).unwrap();
+        let config = config(root);
+
+        let mut result = RunResult::default();
+        apply_changes(&mut result, &Changes::rescan(), &config).unwrap();
+        assert_eq!(result.stats.len(), 2);
+
+        std::fs::write(root.join("a.rs"), "a\na\na\n").unwrap();
+        std::fs::remove_file(root.join("b.rs")).unwrap();
+        std::fs::write(root.join("c.rs"), "c\nc\n").unwrap();
+        std::fs::write(root.join("ignored.rs"), "x\n").unwrap();
+        let changes = Changes {
+            paths: ["a.rs", "b.rs", "c.rs", "ignored.rs"]
+                .iter()
+                .map(|name| root.join(name))
+                .collect(),
+            rescan: false,
+        };
+        apply_changes(&mut result, &changes, &config).unwrap();
+
+        assert_eq!(result.stats.len(), 2);
+        assert_eq!(lines(&result, &root.join("a.rs")), Some(3));
+        assert_eq!(lines(&result, &root.join("b.rs")), None);
+        assert_eq!(lines(&result, &root.join("c.rs")), Some(2));
+    }
+
+    #[test]
+    fn test_record_maps_paths_and_flags_rescans() {
+        let dir = tempfile::tempdir().unwrap();
+        let canonical = dir.path().canonicalize().unwrap();
+        let roots = vec![(PathBuf::from("."), canonical.clone())];
+        let mut changes = Changes::default();
+
+        let modify =
+            Event::new(EventKind::Modify(ModifyKind::Any)).add_path(canonical.join("a.rs"));
+        changes.record(&modify, &roots);
+        let removed =
+            Event::new(EventKind::Remove(RemoveKind::Folder)).add_path(canonical.join("gen"));
+        changes.record(&removed, &roots);
+        assert!(!changes.rescan);
+        assert_eq!(
+            changes.paths,
+            BTreeSet::from([PathBuf::from("./a.rs"), PathBuf::from("./gen")])
+        );
+
+        let ignore =
+            Event::new(EventKind::Modify(ModifyKind::Any)).add_path(canonical.join(".gitignore"));
+        changes.record(&ignore, &roots);
+        assert!(changes.rescan);
+    }
+
+    #[test]
+    fn test_is_under_matches_directories_and_archive_members() {
+        assert!(is_under(Path::new("src/a.rs"), Path::new("src")));
+        assert!(is_under(Path::new("x.zip!a.rs"), Path::new("x.zip")));
+        assert!(!is_under(Path::new("src2/a.rs"), Path::new("src")));
+    }
 }
@@ -0,0 +1,178 @@
+// crates/engine/src/whitespace.rs
+//! `--audit-whitespace`: lists the files with trailing whitespace, mixed
+//! tab/space indentation, mixed line endings or no final newline, and sums
+//! the findings per directory.
+//!
+//! The findings are collected by the core counter in the same pass as the
+//! line counts (see [`WhitespaceCounts`]).
+
+use crate::config::Config;
+use crate::error::Result;
+use crate::stats::FileStats;
+use count_lines_core::stats::WhitespaceCounts;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// The findings of one file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FileWhitespace {
+    pub path: PathBuf,
+    #[serde(flatten)]
+    pub counts: WhitespaceCounts,
+}
+
+/// The findings of the files directly in one directory.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct DirectoryWhitespace {
+    pub directory: PathBuf,
+    /// Text files audited.
+    pub files: usize,
+    /// Files with at least one finding.
+    pub files_with_issues: usize,
+    /// Lines ending in spaces or tabs.
+    pub trailing_whitespace: usize,
+    /// Lines with mixed tab/space indentation.
+    pub mixed_indent: usize,
+    /// Files whose last line has no line break.
+    pub missing_final_newline: usize,
+    /// Files ending lines with `\r\n` only.
+    pub crlf_files: usize,
+    /// Files ending lines with `\n` only.
+    pub lf_files: usize,
+    /// Files using both line endings.
+    pub mixed_line_endings: usize,
+}
+
+impl DirectoryWhitespace {
+    fn add(&mut self, counts: &WhitespaceCounts) {
+        self.files += 1;
+        self.files_with_issues += usize::from(counts.has_issues());
+        self.trailing_whitespace += counts.trailing_whitespace;
+        self.mixed_indent += counts.mixed_indent;
+        self.missing_final_newline += usize::from(counts.missing_final_newline);
+        self.crlf_files += usize::from(counts.crlf > 0 && counts.lf == 0);
+        self.lf_files += usize::from(counts.lf > 0 && counts.crlf == 0);
+        self.mixed_line_endings += usize::from(counts.mixed_line_endings());
+    }
+}
+
+/// The files with findings, by path, and the per-directory sums.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct WhitespaceAudit {
+    pub files: Vec<FileWhitespace>,
+    pub directories: Vec<DirectoryWhitespace>,
+}
+
+/// Counts the files selected by `config` with the whitespace audit enabled
+/// and collects the findings.
+///
+/// # Errors
+/// Returns an error if the run fails.
+pub fn audit_whitespace(config: &Config) -> Result<WhitespaceAudit> {
+    let config = Config {
+        audit_whitespace: true,
+        ..config.clone()
+    };
+    let result = crate::run(&config)?;
+    Ok(collect(&result.stats))
+}
+
+/// Collects the findings recorded in `stats`; files that weren't audited
+/// (binary files) are left out.
+#[must_use]
+pub fn collect(stats: &[FileStats]) -> WhitespaceAudit {
+    let mut files = Vec::new();
+    let mut directories: BTreeMap<PathBuf, DirectoryWhitespace> = BTreeMap::new();
+    for s in stats {
+        let Some(counts) = s.whitespace.filter(|_| !s.is_binary) else {
+            continue;
+        };
+        let directory = s
+            .path
+            .parent()
+            .filter(|d| !d.as_os_str().is_empty())
+            .unwrap_or_else(|| Path::new("."));
+        directories
+            .entry(directory.to_path_buf())
+            .or_insert_with(|| DirectoryWhitespace {
+                directory: directory.to_path_buf(),
+                ..DirectoryWhitespace::default()
+            })
+            .add(&counts);
+        if counts.has_issues() {
+            files.push(FileWhitespace {
+                path: s.path.clone(),
+                counts,
+            });
+        }
+    }
+    files.sort_by(|a, b| a.path.cmp(&b.path));
+    WhitespaceAudit {
+        files,
+        directories: directories.into_values().collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::WalkOptions;
+
+    #[test]
+    fn test_audit_whitespace() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        std::fs::write(root.join("src/clean.rs"), "fn a() {\n    b();\n}\n").unwrap();
+        std::fs::write(
+            root.join("src/messy.rs"),
+            "fn a() { \r\n\tb();\n    c();\n}",
+        )
+        .unwrap();
+        std::fs::write(root.join("README.md"), "# Title\r\n\r\nText\r\n").unwrap();
+
+        let config = Config {
+            walk: WalkOptions {
+                roots: vec![root.to_path_buf()],
+                ..WalkOptions::default()
+            },
+            ..Config::default()
+        };
+        let audit = audit_whitespace(&config).unwrap();
+        assert_eq!(
+            audit.files,
+            [FileWhitespace {
+                path: root.join("src/messy.rs"),
+                counts: WhitespaceCounts {
+                    trailing_whitespace: 1,
+                    mixed_indent: 1,
+                    crlf: 1,
+                    lf: 2,
+                    missing_final_newline: true,
+                },
+            }]
+        );
+        let rows: Vec<_> = audit
+            .directories
+            .iter()
+            .map(|d| {
+                (
+                    d.directory.strip_prefix(root).unwrap().to_path_buf(),
+                    d.files,
+                    d.files_with_issues,
+                    d.crlf_files,
+                    d.lf_files,
+                    d.mixed_line_endings,
+                )
+            })
+            .collect();
+        assert_eq!(
+            rows,
+            [
+                (PathBuf::new(), 1, 0, 1, 0, 0),
+                (PathBuf::from("src"), 2, 1, 0, 1, 1),
+            ]
+        );
+    }
+}
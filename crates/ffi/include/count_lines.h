@@ -0,0 +1,88 @@
+/* count_lines C API. Generated by cbindgen from crates/ffi/src/lib.rs; do not edit. */
+
+#ifndef COUNT_LINES_H
+#define COUNT_LINES_H
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+// Options of `count_buffer_with_options`.
+typedef struct CountLinesOptions {
+  // Count words.
+  bool count_words;
+  // Count source lines of code.
+  bool count_sloc;
+  // Count line breaks as characters.
+  bool count_newlines_in_chars;
+  // Pick the language from a Vim modeline or Emacs mode header.
+  bool detect_modeline;
+} CountLinesOptions;
+
+// Counts of one buffer.
+//
+// `words` and `sloc` are only meaningful when `has_words` / `has_sloc` are set.
+typedef struct CountLinesStats {
+  // Number of lines.
+  size_t lines;
+  // Number of characters.
+  size_t chars;
+  // Number of words.
+  size_t words;
+  // Source lines of code.
+  size_t sloc;
+  // Length of the longest line in characters, excluding the line break.
+  size_t max_line_length;
+  // Average line length in characters, excluding line breaks.
+  double avg_line_length;
+  // Whether `words` was counted.
+  bool has_words;
+  // Whether `sloc` was counted.
+  bool has_sloc;
+  // Whether the buffer was detected as binary (nothing else is counted).
+  bool is_binary;
+} CountLinesStats;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// Options counting words and SLOC, as used by `count_buffer`.
+struct CountLinesOptions count_lines_default_options(void);
+
+// Counts lines, characters, words and SLOC of `len` bytes at `ptr`, as a
+// file with extension `ext` (without the dot).
+//
+// # Safety
+// `ptr` must point to `len` readable bytes, or be null when `len` is 0.
+// `ext` must be null or a NUL-terminated string; null means no extension.
+struct CountLinesStats count_buffer(const uint8_t *ptr, size_t len, const char *ext);
+
+// Like `count_buffer`, with explicit options.
+//
+// # Safety
+// Same as `count_buffer`.
+struct CountLinesStats count_buffer_with_options(const uint8_t *ptr,
+                                                 size_t len,
+                                                 const char *ext,
+                                                 struct CountLinesOptions options);
+
+// Writes the display name of the language of `ext` (e.g. `rs` → `Rust`)
+// to `out` as a NUL-terminated string.
+//
+// Returns the length of the name without the NUL, or 0 for an unknown
+// extension. The name is only written when it fits in `out_len` bytes
+// including the NUL; call with a null `out` to query the length.
+//
+// # Safety
+// `ext` must be null or a NUL-terminated string, and `out` must be null or
+// point to `out_len` writable bytes.
+size_t count_lines_language_name(const char *ext, char *out, size_t out_len);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* COUNT_LINES_H */
@@ -0,0 +1,274 @@
+// crates/ffi/src/lib.rs
+//! C ABI for `count_lines_core`.
+//!
+//! Builds a shared (`cdylib`) and static library exporting [`count_buffer`]
+//! and friends; the matching header is `include/count_lines.h`, generated by
+//! cbindgen from this file. Structs are returned by value, so callers never
+//! free anything.
+
+use core::ffi::{CStr, c_char};
+use core::slice;
+use std::borrow::Cow;
+
+use count_lines_core::config::AnalysisConfig;
+use count_lines_core::counter::count_bytes;
+use count_lines_core::language;
+use count_lines_core::stats::AnalysisResult;
+
+/// Options of `count_buffer_with_options`.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Options {
+    /// Count words.
+    pub count_words: bool,
+    /// Count source lines of code.
+    pub count_sloc: bool,
+    /// Count line breaks as characters.
+    pub count_newlines_in_chars: bool,
+    /// Pick the language from a Vim modeline or Emacs mode header.
+    pub detect_modeline: bool,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        count_lines_default_options()
+    }
+}
+
+/// Counts of one buffer.
+///
+/// `words` and `sloc` are only meaningful when `has_words` / `has_sloc` are set.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Stats {
+    /// Number of lines.
+    pub lines: usize,
+    /// Number of characters.
+    pub chars: usize,
+    /// Number of words.
+    pub words: usize,
+    /// Source lines of code.
+    pub sloc: usize,
+    /// Length of the longest line in characters, excluding the line break.
+    pub max_line_length: usize,
+    /// Average line length in characters, excluding line breaks.
+    pub avg_line_length: f64,
+    /// Whether `words` was counted.
+    pub has_words: bool,
+    /// Whether `sloc` was counted.
+    pub has_sloc: bool,
+    /// Whether the buffer was detected as binary (nothing else is counted).
+    pub is_binary: bool,
+}
+
+impl From<AnalysisResult> for Stats {
+    fn from(result: AnalysisResult) -> Self {
+        Self {
+            lines: result.lines,
+            chars: result.chars,
+            words: result.words.unwrap_or(0),
+            sloc: result.sloc.unwrap_or(0),
+            max_line_length: result.max_line_length,
+            avg_line_length: result.avg_line_length,
+            has_words: result.words.is_some(),
+            has_sloc: result.sloc.is_some(),
+            is_binary: result.is_binary,
+        }
+    }
+}
+
+/// Options counting words and SLOC, as used by `count_buffer`.
+#[unsafe(no_mangle)]
+pub extern "C" fn count_lines_default_options() -> Options {
+    Options {
+        count_words: true,
+        count_sloc: true,
+        count_newlines_in_chars: false,
+        detect_modeline: false,
+    }
+}
+
+/// Counts lines, characters, words and SLOC of `len` bytes at `ptr`, as a
+/// file with extension `ext` (without the dot).
+///
+/// # Safety
+/// `ptr` must point to `len` readable bytes, or be null when `len` is 0.
+/// `ext` must be null or a NUL-terminated string; null means no extension.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn count_buffer(ptr: *const u8, len: usize, ext: *const c_char) -> Stats {
+    // SAFETY: forwarded from the caller.
+    unsafe { count_buffer_with_options(ptr, len, ext, count_lines_default_options()) }
+}
+
+/// Like `count_buffer`, with explicit options.
+///
+/// # Safety
+/// Same as `count_buffer`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn count_buffer_with_options(
+    ptr: *const u8,
+    len: usize,
+    ext: *const c_char,
+    options: Options,
+) -> Stats {
+    let input = if ptr.is_null() || len == 0 {
+        &[]
+    } else {
+        // SAFETY: the caller guarantees `ptr` and `len` describe readable memory.
+        unsafe { slice::from_raw_parts(ptr, len) }
+    };
+    // SAFETY: the caller guarantees `ext` is null or NUL-terminated.
+    let ext = unsafe { c_str(ext) };
+    let config = AnalysisConfig {
+        count_words: options.count_words,
+        count_sloc: options.count_sloc,
+        count_newlines_in_chars: options.count_newlines_in_chars,
+        detect_modeline: options.detect_modeline,
+        ..AnalysisConfig::default()
+    };
+    let mut result = count_bytes(input, &ext, &config);
+    // The core counter always tallies SLOC; only report it when asked for.
+    if !options.count_sloc {
+        result.sloc = None;
+    }
+    result.into()
+}
+
+/// Writes the display name of the language of `ext` (e.g. `rs` → `Rust`)
+/// to `out` as a NUL-terminated string.
+///
+/// Returns the length of the name without the NUL, or 0 for an unknown
+/// extension. The name is only written when it fits in `out_len` bytes
+/// including the NUL; call with a null `out` to query the length.
+///
+/// # Safety
+/// `ext` must be null or a NUL-terminated string, and `out` must be null or
+/// point to `out_len` writable bytes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn count_lines_language_name(
+    ext: *const c_char,
+    out: *mut c_char,
+    out_len: usize,
+) -> usize {
+    // SAFETY: the caller guarantees `ext` is null or NUL-terminated.
+    let ext = unsafe { c_str(ext) };
+    let Some(name) = language::language_name(&ext) else {
+        return 0;
+    };
+    if !out.is_null() && name.len() < out_len {
+        // SAFETY: `out` has room for the name and the NUL.
+        unsafe {
+            core::ptr::copy_nonoverlapping(name.as_ptr(), out.cast::<u8>(), name.len());
+            *out.add(name.len()) = 0;
+        }
+    }
+    name.len()
+}
+
+/// Reads a possibly null C string, replacing invalid UTF-8.
+unsafe fn c_str<'a>(ptr: *const c_char) -> Cow<'a, str> {
+    if ptr.is_null() {
+        return "".into();
+    }
+    // SAFETY: the caller guarantees `ptr` is NUL-terminated.
+    unsafe { CStr::from_ptr(ptr) }.to_string_lossy()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    #[test]
+    fn test_count_buffer_matches_core() {
+        let content = b"fn main() {\n    // comment\n\n    run();\n}\n";
+        let stats = unsafe { count_buffer(content.as_ptr(), content.len(), c"rs".as_ptr()) };
+        let config = AnalysisConfig {
+            count_words: true,
+            count_sloc: true,
+            ..AnalysisConfig::default()
+        };
+        assert_eq!(stats, Stats::from(count_bytes(content, "rs", &config)));
+        assert_eq!((stats.lines, stats.sloc), (5, 3));
+        assert!(stats.has_sloc && stats.has_words);
+    }
+
+    #[test]
+    fn test_count_buffer_null_arguments() {
+        let stats = unsafe { count_buffer(core::ptr::null(), 0, core::ptr::null()) };
+        assert_eq!(stats.lines, 0);
+        assert!(!stats.is_binary);
+    }
+
+    #[test]
+    fn test_count_buffer_with_options() {
+        let content = b"# vim: ft=python\nx = 1\n";
+        let options = Options {
+            count_words: false,
+            detect_modeline: true,
+            ..Options::default()
+        };
+        let stats = unsafe {
+            count_buffer_with_options(content.as_ptr(), content.len(), c"txt".as_ptr(), options)
+        };
+        assert!(!stats.has_words);
+        assert_eq!(stats.sloc, 1);
+    }
+
+    #[test]
+    fn test_count_buffer_without_sloc() {
+        let content = b"fn main() {}\n";
+        let options = Options {
+            count_sloc: false,
+            ..count_lines_default_options()
+        };
+        let stats = unsafe {
+            count_buffer_with_options(content.as_ptr(), content.len(), c"rs".as_ptr(), options)
+        };
+        assert!(!stats.has_sloc);
+        assert_eq!((stats.lines, stats.sloc), (1, 0));
+        assert!(stats.has_words);
+    }
+
+    #[test]
+    fn test_language_name() {
+        let mut buf = [0 as c_char; 16];
+        let len = unsafe { count_lines_language_name(c"py".as_ptr(), buf.as_mut_ptr(), buf.len()) };
+        assert_eq!(len, 6);
+        let name = unsafe { CStr::from_ptr(buf.as_ptr()) };
+        assert_eq!(name.to_str(), Ok("Python"));
+
+        let needed = unsafe { count_lines_language_name(c"rs".as_ptr(), core::ptr::null_mut(), 0) };
+        assert_eq!(needed, 4);
+        let mut small = [1 as c_char; 4];
+        let len = unsafe { count_lines_language_name(c"rs".as_ptr(), small.as_mut_ptr(), 4) };
+        assert_eq!(len, 4);
+        assert_eq!(small, [1; 4]);
+        assert_eq!(
+            unsafe { count_lines_language_name(c"nope".as_ptr(), buf.as_mut_ptr(), buf.len()) },
+            0
+        );
+    }
+
+    /// `include/count_lines.h` must match the API; `UPDATE_HEADER=1` rewrites it.
+    #[test]
+    fn test_header_is_up_to_date() {
+        let crate_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+        let config = cbindgen::Config::from_file(crate_dir.join("cbindgen.toml")).unwrap();
+        let mut generated = Vec::new();
+        cbindgen::generate_with_config(crate_dir, config)
+            .unwrap()
+            .write(&mut generated);
+        let generated = String::from_utf8(generated).unwrap();
+
+        let header = crate_dir.join("include/count_lines.h");
+        if std::env::var_os("UPDATE_HEADER").is_some() {
+            std::fs::write(&header, &generated).unwrap();
+        }
+        let current = std::fs::read_to_string(&header).unwrap_or_default();
+        assert!(
+            current == generated,
+            "include/count_lines.h is stale; rerun with UPDATE_HEADER=1"
+        );
+    }
+}
@@ -0,0 +1,217 @@
+// crates/wasm/src/lib.rs
+//! WebAssembly bindings for `count_lines_core`.
+//!
+//! Build the npm package with `wasm-pack build crates/wasm --target web`.
+//! The exports mirror the core API in JavaScript naming: [`count_bytes`] is
+//! `countBytes`, [`Counter`] holds the [`AnalysisConfig`] options.
+
+use count_lines_core::config::AnalysisConfig;
+use count_lines_core::counter;
+use count_lines_core::language::{self, modeline::detect_modeline};
+use count_lines_core::stats::AnalysisResult;
+use wasm_bindgen::prelude::*;
+
+/// Counts of one file, as returned to JavaScript.
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Stats {
+    /// Number of lines.
+    pub lines: usize,
+    /// Number of characters.
+    pub chars: usize,
+    /// Number of words, `undefined` unless counted.
+    pub words: Option<usize>,
+    /// Source lines of code, `undefined` unless counted.
+    pub sloc: Option<usize>,
+    /// Length of the longest line in characters, excluding the line break.
+    #[wasm_bindgen(js_name = maxLineLength)]
+    pub max_line_length: usize,
+    /// Average line length in characters, excluding line breaks.
+    #[wasm_bindgen(js_name = avgLineLength)]
+    pub avg_line_length: f64,
+    /// Whether the content was detected as binary (nothing else is counted).
+    #[wasm_bindgen(js_name = isBinary)]
+    pub is_binary: bool,
+}
+
+impl From<AnalysisResult> for Stats {
+    fn from(result: AnalysisResult) -> Self {
+        Self {
+            lines: result.lines,
+            chars: result.chars,
+            words: result.words,
+            sloc: result.sloc,
+            max_line_length: result.max_line_length,
+            avg_line_length: result.avg_line_length,
+            is_binary: result.is_binary,
+        }
+    }
+}
+
+/// A counter with options; `new Counter()` counts words and SLOC.
+#[wasm_bindgen]
+#[derive(Debug, Clone)]
+pub struct Counter {
+    config: AnalysisConfig,
+}
+
+impl Default for Counter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[wasm_bindgen]
+impl Counter {
+    /// Creates a counter that counts words and SLOC.
+    #[wasm_bindgen(constructor)]
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            config: AnalysisConfig {
+                count_words: true,
+                count_sloc: true,
+                ..AnalysisConfig::default()
+            },
+        }
+    }
+
+    /// Whether words are counted.
+    #[wasm_bindgen(getter = countWords)]
+    #[must_use]
+    pub fn count_words(&self) -> bool {
+        self.config.count_words
+    }
+
+    /// Sets whether words are counted.
+    #[wasm_bindgen(setter = countWords)]
+    pub fn set_count_words(&mut self, value: bool) {
+        self.config.count_words = value;
+    }
+
+    /// Whether SLOC is counted.
+    #[wasm_bindgen(getter = countSloc)]
+    #[must_use]
+    pub fn count_sloc(&self) -> bool {
+        self.config.count_sloc
+    }
+
+    /// Sets whether SLOC is counted.
+    #[wasm_bindgen(setter = countSloc)]
+    pub fn set_count_sloc(&mut self, value: bool) {
+        self.config.count_sloc = value;
+    }
+
+    /// Whether line breaks count as characters.
+    #[wasm_bindgen(getter = countNewlinesInChars)]
+    #[must_use]
+    pub fn count_newlines_in_chars(&self) -> bool {
+        self.config.count_newlines_in_chars
+    }
+
+    /// Sets whether line breaks count as characters.
+    #[wasm_bindgen(setter = countNewlinesInChars)]
+    pub fn set_count_newlines_in_chars(&mut self, value: bool) {
+        self.config.count_newlines_in_chars = value;
+    }
+
+    /// Whether a Vim modeline or Emacs mode header picks the language.
+    #[wasm_bindgen(getter = detectModeline)]
+    #[must_use]
+    pub fn detect_modeline(&self) -> bool {
+        self.config.detect_modeline
+    }
+
+    /// Sets whether a Vim modeline or Emacs mode header picks the language.
+    #[wasm_bindgen(setter = detectModeline)]
+    pub fn set_detect_modeline(&mut self, value: bool) {
+        self.config.detect_modeline = value;
+    }
+
+    /// Counts files with extension `from` as `to` (like `--map-ext from=to`).
+    #[wasm_bindgen(js_name = mapExtension)]
+    pub fn map_extension(&mut self, from: &str, to: &str) {
+        let from = from.trim_start_matches('.').to_lowercase();
+        let to = to.trim_start_matches('.').to_string();
+        self.config.map_ext.insert(from, to);
+    }
+
+    /// Counts `content` as a file with the given extension (without the dot).
+    #[must_use]
+    pub fn count(&self, content: &[u8], extension: &str) -> Stats {
+        counter::count_bytes(content, extension, &self.config).into()
+    }
+}
+
+/// Counts lines, characters, words and SLOC of `content` as a file with the
+/// given extension (without the dot).
+#[wasm_bindgen(js_name = countBytes)]
+#[must_use]
+pub fn count_bytes(content: &[u8], extension: &str) -> Stats {
+    Counter::new().count(content, extension)
+}
+
+/// Display name of the language of `extension`, e.g. `rs` → `Rust`.
+#[wasm_bindgen(js_name = languageName)]
+#[must_use]
+pub fn language_name(extension: &str) -> Option<String> {
+    language::language_name(extension).map(str::to_string)
+}
+
+/// Language of a file: the one named by a modeline in `content`, otherwise
+/// the one of the file name's extension or of the whole name (`Makefile`).
+#[wasm_bindgen(js_name = detectLanguage)]
+#[must_use]
+pub fn detect_language(file_name: &str, content: &[u8]) -> Option<String> {
+    if let Some(name) = detect_modeline(content).and_then(|ext| language_name(&ext)) {
+        return Some(name);
+    }
+    let base = file_name.rsplit(['/', '\\']).next().unwrap_or(file_name);
+    base.rsplit_once('.')
+        .and_then(|(stem, ext)| (!stem.is_empty()).then(|| language_name(ext)).flatten())
+        .or_else(|| language_name(base))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_count_bytes_matches_core() {
+        let content = b"fn main() {\n    // comment\n\n    run();\n}\n";
+        let stats = count_bytes(content, "rs");
+        assert_eq!(stats.lines, 5);
+        assert_eq!(stats.sloc, Some(3));
+        assert_eq!(stats.words, Some(7));
+        assert!(!stats.is_binary);
+    }
+
+    #[test]
+    fn test_counter_options() {
+        let mut counter = Counter::new();
+        counter.set_count_words(false);
+        counter.map_extension(".INC", "php");
+        let stats = counter.count(b"<?php\n# comment\necho 1;\n", "inc");
+        assert_eq!(stats.words, None);
+        assert_eq!(stats.sloc, Some(2));
+
+        counter.set_detect_modeline(true);
+        let stats = counter.count(b"# vim: ft=python\nx = 1\n", "txt");
+        assert_eq!(stats.sloc, Some(1));
+    }
+
+    #[test]
+    fn test_detect_language() {
+        assert_eq!(detect_language("src/main.rs", b"").as_deref(), Some("Rust"));
+        assert_eq!(
+            detect_language("script", b"#!/bin/sh\n# vim: ft=python\n").as_deref(),
+            Some("Python")
+        );
+        assert_eq!(
+            detect_language("Makefile", b"").as_deref(),
+            language_name("Makefile").as_deref()
+        );
+        assert_eq!(detect_language(".gitignore", b""), None);
+        assert_eq!(language_name("unknown-ext"), None);
+    }
+}